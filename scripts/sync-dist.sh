@@ -15,10 +15,12 @@ fi
 echo "Syncing src/ → src-tauri/dist/ ..."
 mkdir -p "$DEST"
 
-# Shared UI (CPU window + Agent Ops + Ollama). Do not sync orphaned dashboard.* here.
+# Shared UI (CPU window + Agent Ops + Ollama) and the other windows' scripts (their .html lives
+# in src-tauri/dist/). Do not sync orphaned dashboard.* here.
 for f in \
-  cpu.js cpu-ui.js discord.js history.js \
+  confirm.js cpu.js cpu-ui.js cpu-meta.js discord.js history.js \
   ollama.js tauri-logger.js agent-ops.js agent-ops.css \
+  about.js battery.js logs.js network.js onboarding.js widget.js \
   cpu.html index.html main.js styles.css; do
   if [[ -f "$PROJECT_ROOT/src/$f" ]]; then
     cp "$PROJECT_ROOT/src/$f" "$DEST/"
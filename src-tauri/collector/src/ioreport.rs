@@ -2,13 +2,18 @@
 //!
 //! IOReport is a macOS framework for system performance monitoring.
 //! These wrappers add null checks and error handling to prevent crashes.
+//! A [`Subscription`] owns one subscription (CPU performance states or energy channels) and
+//! its previous sample, so each read covers the time since the one before. Channel parsing
+//! logs at `trace` level.
 
 use core_foundation::base::{CFTypeRef, TCFType};
 use core_foundation::dictionary::{CFDictionaryRef, CFMutableDictionaryRef};
 use core_foundation::string::{CFString, CFStringRef};
+use serde::Serialize;
 use std::os::raw::c_void;
 use std::time::Instant;
 use thiserror::Error;
+use tracing::trace;
 
 /// IOReport error types
 /// Currently unused as direct FFI calls are used in lib.rs.
@@ -144,10 +149,33 @@ pub fn probe_cpu_performance_channels_available() -> bool {
     false
 }
 
+/// Startup probe: whether IOReport exposes energy channels (the "Energy Model" group power sampling subscribes to first).
+#[cfg(target_os = "macos")]
+pub fn probe_power_channels_available() -> bool {
+    use core_foundation::base::CFRelease;
+    use core_foundation::dictionary::CFDictionaryGetCount;
+    let group = CFString::from_static_string("Energy Model");
+    unsafe {
+        let dict =
+            IOReportCopyChannelsInGroup(group.as_concrete_TypeRef(), std::ptr::null(), 0, 0, 0);
+        if dict.is_null() {
+            return false;
+        }
+        let count = CFDictionaryGetCount(dict);
+        CFRelease(dict as core_foundation::base::CFTypeRef);
+        count > 0
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn probe_power_channels_available() -> bool {
+    false
+}
+
 /// Safe wrapper for IOReportMergeChannels
 /// Currently unused - kept for future FFI migration.
 #[allow(dead_code)]
-pub fn merge_channels(dest: CFMutableDictionaryRef, src: CFDictionaryRef) -> IOReportResult<()> {
+pub(crate) fn merge_channels(dest: CFMutableDictionaryRef, src: CFDictionaryRef) -> IOReportResult<()> {
     if dest.is_null() {
         return Err(IOReportError::NullPointer);
     }
@@ -165,7 +193,7 @@ pub fn merge_channels(dest: CFMutableDictionaryRef, src: CFDictionaryRef) -> IOR
 /// Safe wrapper for IOReportCreateSubscription
 /// Currently unused - kept for future FFI migration.
 #[allow(dead_code)]
-pub fn create_subscription(
+pub(crate) fn create_subscription(
     channels: CFMutableDictionaryRef,
 ) -> IOReportResult<(*mut c_void, CFMutableDictionaryRef)> {
     if channels.is_null() {
@@ -194,7 +222,7 @@ pub fn create_subscription(
 /// Safe wrapper for IOReportCreateSamples
 /// Currently unused - kept for future FFI migration.
 #[allow(dead_code)]
-pub fn create_samples(
+pub(crate) fn create_samples(
     subscription: *const c_void,
     channels: CFMutableDictionaryRef,
 ) -> IOReportResult<CFDictionaryRef> {
@@ -216,7 +244,7 @@ pub fn create_samples(
 
 /// Safe wrapper for IOReportChannelGetChannelName
 #[allow(dead_code)] // Kept for future FFI migration
-pub fn get_channel_name(channel: CFDictionaryRef) -> IOReportResult<String> {
+pub(crate) fn get_channel_name(channel: CFDictionaryRef) -> IOReportResult<String> {
     if channel.is_null() {
         return Err(IOReportError::InvalidDictionary);
     }
@@ -233,7 +261,7 @@ pub fn get_channel_name(channel: CFDictionaryRef) -> IOReportResult<String> {
 
 /// Safe wrapper for IOReportStateGetCount
 #[allow(dead_code)] // Kept for future FFI migration
-pub fn get_state_count(channel: CFDictionaryRef) -> IOReportResult<i32> {
+pub(crate) fn get_state_count(channel: CFDictionaryRef) -> IOReportResult<i32> {
     if channel.is_null() {
         return Err(IOReportError::InvalidDictionary);
     }
@@ -251,7 +279,7 @@ pub fn get_state_count(channel: CFDictionaryRef) -> IOReportResult<i32> {
 
 /// Safe wrapper for IOReportStateGetNameForIndex
 #[allow(dead_code)] // Kept for future FFI migration
-pub fn get_state_name_for_index(channel: CFDictionaryRef, index: i32) -> IOReportResult<String> {
+pub(crate) fn get_state_name_for_index(channel: CFDictionaryRef, index: i32) -> IOReportResult<String> {
     if channel.is_null() {
         return Err(IOReportError::InvalidDictionary);
     }
@@ -274,7 +302,7 @@ pub fn get_state_name_for_index(channel: CFDictionaryRef, index: i32) -> IORepor
 
 /// Safe wrapper for IOReportStateGetResidency
 #[allow(dead_code)] // Kept for future FFI migration
-pub fn get_state_residency(channel: CFDictionaryRef, index: i32) -> IOReportResult<i64> {
+pub(crate) fn get_state_residency(channel: CFDictionaryRef, index: i32) -> IOReportResult<i64> {
     if channel.is_null() {
         return Err(IOReportError::InvalidDictionary);
     }
@@ -308,12 +336,13 @@ extern "C" {
     fn CFArrayGetCount(theArray: *const c_void) -> i32;
     fn CFArrayGetValueAtIndex(theArray: *const c_void, idx: i32) -> *const c_void;
     fn CFRelease(cf: CFTypeRef);
+    fn CFRetain(cf: CFTypeRef) -> CFTypeRef;
 }
 
 // IOReport FFI functions are already declared at the top of the file
 
-/// Frequency data structure
-#[derive(Debug, Default)]
+/// Frequency data structure (GHz)
+#[derive(Debug, Default, Clone, Copy, Serialize)]
 pub struct FrequencyData {
     pub overall: f32,
     pub p_core: f32,
@@ -321,12 +350,209 @@ pub struct FrequencyData {
 }
 
 /// Power data structure (CPU and GPU power in watts)
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Copy, Serialize)]
 pub struct PowerData {
     pub cpu_power: f32, // CPU power in watts
     pub gpu_power: f32, // GPU power in watts
 }
 
+/// Power channel groups, tried in order; `None` subgroup = all subgroups
+const POWER_GROUPS: [(&str, Option<&str>); 5] = [
+    ("Energy Model", None),
+    ("CPU Stats", Some("CPU Power")),
+    ("CPU Stats", Some("CPU Energy")),
+    ("GPU Stats", Some("GPU Power")),
+    ("GPU Stats", Some("GPU Energy")),
+];
+
+/// An IOReport subscription with the dictionaries it samples from and its previous sample
+///
+/// Creating one is expensive, so keep it for as long as you read from it. Each read returns
+/// the average since the previous read (the first one: since the counters started, or 0 W).
+/// Everything it retains, the subscription included, is released on drop.
+pub struct Subscription {
+    handle: *mut c_void,
+    /// Channels passed to `IOReportCreateSamples`
+    channels: CFMutableDictionaryRef,
+    /// Channels as copied from IOReport, for channel name lookup
+    original_channels: CFDictionaryRef,
+    subscription_dict: CFMutableDictionaryRef,
+    last_sample: Option<CFDictionaryRef>,
+    last_read: Option<Instant>,
+}
+
+// SAFETY: the handle and dictionaries are only used through `&mut self`, so by one thread at a
+// time; IOReport and CF objects are not tied to the thread that created them.
+unsafe impl Send for Subscription {}
+
+impl Subscription {
+    /// CPU core performance states (frequency); `None` on Intel or when IOReport refuses
+    pub fn frequency() -> Option<Self> {
+        let group = CFString::from_static_string("CPU Stats");
+        let subgroup = CFString::from_static_string("CPU Core Performance States");
+        unsafe {
+            let channels = IOReportCopyChannelsInGroup(
+                group.as_concrete_TypeRef(),
+                subgroup.as_concrete_TypeRef(),
+                0,
+                0,
+                0,
+            );
+            if channels.is_null() {
+                trace!("No CPU Performance States channels found in IOReport");
+                return None;
+            }
+            Self::subscribe(channels)
+        }
+    }
+
+    /// CPU and GPU energy channels (power), from the first group in [`POWER_GROUPS`] this Mac
+    /// has; `None` when none is available
+    pub fn power() -> Option<Self> {
+        for (group, subgroup) in POWER_GROUPS {
+            let group_cf = CFString::from_static_string(group);
+            let subgroup_cf = subgroup.map(CFString::from_static_string);
+            unsafe {
+                let channels = IOReportCopyChannelsInGroup(
+                    group_cf.as_concrete_TypeRef(),
+                    subgroup_cf
+                        .as_ref()
+                        .map_or(std::ptr::null(), |s| s.as_concrete_TypeRef()),
+                    0,
+                    0,
+                    0,
+                );
+                if channels.is_null() {
+                    continue;
+                }
+                if CFDictionaryGetCount(channels) == 0 {
+                    CFRelease(channels as CFTypeRef);
+                    continue;
+                }
+                trace!(
+                    "Found power channels: {} / {}",
+                    group,
+                    subgroup.unwrap_or("all")
+                );
+                return Self::subscribe(channels);
+            }
+        }
+        trace!(
+            "No power channels found in IOReport (tried: {:?})",
+            POWER_GROUPS
+        );
+        None
+    }
+
+    /// Subscribe to `channels` (a retained `IOReportCopyChannelsInGroup` result, released here
+    /// on failure)
+    unsafe fn subscribe(channels: CFDictionaryRef) -> Option<Self> {
+        use core_foundation::base::CFType;
+        use core_foundation::dictionary::CFMutableDictionary;
+
+        let merged: CFMutableDictionary<CFString, CFType> = CFMutableDictionary::new();
+        IOReportMergeChannels(merged.as_concrete_TypeRef(), channels, std::ptr::null());
+        // Energy Model channels come as an array that may not merge; sample from the copied
+        // dictionary directly then
+        let sample_channels = if CFDictionaryGetCount(merged.as_concrete_TypeRef()) == 0 {
+            trace!("Merge resulted in 0 entries, using the channels dict directly");
+            channels as CFMutableDictionaryRef
+        } else {
+            merged.as_concrete_TypeRef()
+        };
+
+        let mut subscription_dict: CFMutableDictionaryRef = std::ptr::null_mut();
+        let handle = IOReportCreateSubscription(
+            std::ptr::null(),
+            sample_channels,
+            &mut subscription_dict,
+            0,
+            std::ptr::null(),
+        );
+        if handle.is_null() {
+            trace!("Failed to create IOReport subscription: subscription_ptr is null");
+            CFRelease(channels as CFTypeRef);
+            return None;
+        }
+        if !subscription_dict.is_null() {
+            CFRetain(subscription_dict as CFTypeRef);
+        }
+        trace!(
+            "IOReport subscription created (handle={:p}, dict={:p})",
+            handle,
+            subscription_dict
+        );
+        Some(Self {
+            handle,
+            channels: CFRetain(sample_channels as CFTypeRef) as CFMutableDictionaryRef,
+            original_channels: channels,
+            subscription_dict,
+            last_sample: None,
+            last_read: None,
+        })
+    }
+
+    /// Average CPU frequencies since the previous read (0.0 where parsing found nothing)
+    pub fn read_frequencies(&mut self, logging: bool) -> FrequencyData {
+        let (data, sample) = unsafe {
+            read_frequencies_from_ioreport(
+                self.handle,
+                self.channels,
+                Some(self.original_channels),
+                self.last_sample,
+                logging,
+            )
+        };
+        if let Some(sample) = sample {
+            self.keep_sample(sample);
+        }
+        data
+    }
+
+    /// Average CPU and GPU power since the previous read; 0 W on the first read
+    pub fn read_power(&mut self, logging: bool) -> PowerData {
+        let (data, sample) = unsafe {
+            read_power_from_ioreport(
+                self.handle,
+                self.channels,
+                Some(self.original_channels),
+                self.last_sample,
+                self.last_read,
+                logging,
+            )
+        };
+        if let Some(sample) = sample {
+            self.keep_sample(sample);
+            self.last_read = Some(Instant::now());
+        }
+        data
+    }
+
+    /// Store `sample` (owned) for the next delta, releasing the previous one
+    fn keep_sample(&mut self, sample: CFDictionaryRef) {
+        if let Some(old) = self.last_sample.replace(sample) {
+            unsafe { CFRelease(old as CFTypeRef) };
+        }
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        // SAFETY: each field holds one reference retained in `subscribe` or `keep_sample`
+        unsafe {
+            if let Some(sample) = self.last_sample.take() {
+                CFRelease(sample as CFTypeRef);
+            }
+            if !self.subscription_dict.is_null() {
+                CFRelease(self.subscription_dict as CFTypeRef);
+            }
+            CFRelease(self.channels as CFTypeRef);
+            CFRelease(self.original_channels as CFTypeRef);
+            CFRelease(self.handle as CFTypeRef);
+        }
+    }
+}
+
 /// Internal structure for accumulating frequency statistics
 #[derive(Debug, Default)]
 struct FrequencyAccumulator {
@@ -452,10 +678,8 @@ unsafe fn parse_channel_states(
     accumulator: &mut FrequencyAccumulator,
     freq_logging: bool,
 ) {
-    use crate::debug3;
-
     if channel_ref.is_null() {
-        debug3!(
+        trace!(
             "Channel '{}' reference is null, skipping state iteration",
             channel_name
         );
@@ -466,30 +690,30 @@ unsafe fn parse_channel_states(
     // Check that we can get the channel name (validates it's a channel dict)
     let name_check = IOReportChannelGetChannelName(channel_ref);
     if name_check.is_null() {
-        debug3!("Channel '{}': IOReportChannelGetChannelName returned null, not a valid channel dict, skipping", channel_name);
+        trace!("Channel '{}': IOReportChannelGetChannelName returned null, not a valid channel dict, skipping", channel_name);
         return;
     }
 
     // Validate state count is reasonable before iterating
     let state_count = IOReportStateGetCount(channel_ref);
     if state_count < 0 {
-        debug3!("Channel '{}': IOReportStateGetCount returned negative value ({}), invalid channel, skipping", channel_name, state_count);
+        trace!("Channel '{}': IOReportStateGetCount returned negative value ({}), invalid channel, skipping", channel_name, state_count);
         return;
     }
     // Reasonable max: 100 states (typical CPUs have 8-22 states)
     if state_count > 100 {
-        debug3!("Channel '{}': IOReportStateGetCount returned suspiciously high value ({}), likely invalid channel, skipping", channel_name, state_count);
+        trace!("Channel '{}': IOReportStateGetCount returned suspiciously high value ({}), likely invalid channel, skipping", channel_name, state_count);
         return;
     }
 
     if freq_logging {
-        debug3!(
+        trace!(
             "Channel '{}' has {} performance states",
             channel_name,
             state_count
         );
     } else {
-        debug3!(
+        trace!(
             "Channel '{}' has {} performance states",
             channel_name,
             state_count
@@ -508,7 +732,7 @@ unsafe fn parse_channel_states(
         let residency_ratio = residency_ns as f64 / 1_000_000_000.0;
 
         if freq_logging {
-            debug3!(
+            trace!(
                 "  State {}: name='{}', residency={} ns ({:.3} s)",
                 state_idx,
                 state_name_str,
@@ -516,7 +740,7 @@ unsafe fn parse_channel_states(
                 residency_ratio
             );
         } else {
-            debug3!(
+            trace!(
                 "  State {}: name='{}', residency={} ns",
                 state_idx,
                 state_name_str,
@@ -527,7 +751,7 @@ unsafe fn parse_channel_states(
         // Skip DOWN and IDLE states (they don't represent active frequencies)
         if state_name_str == "DOWN" || state_name_str == "IDLE" {
             if freq_logging {
-                debug3!(
+                trace!(
                     "  State {}: skipping '{}' (not an active frequency state)",
                     state_idx,
                     state_name_str
@@ -561,7 +785,7 @@ unsafe fn parse_channel_states(
             }
 
             if freq_logging {
-                debug3!(
+                trace!(
                     "  State {}: extracted {} MHz from '{}' (weighted: {:.2} MHz)",
                     state_idx,
                     mhz_val,
@@ -569,7 +793,7 @@ unsafe fn parse_channel_states(
                     mhz_val * residency_ratio
                 );
             } else {
-                debug3!(
+                trace!(
                     "  State {}: extracted {} MHz from name '{}'",
                     state_idx,
                     mhz_val,
@@ -603,7 +827,7 @@ unsafe fn parse_channel_states(
             }
 
             if freq_logging {
-                debug3!(
+                trace!(
                     "  State {}: estimated {} MHz from P-state '{}' (weighted: {:.2} MHz)",
                     state_idx,
                     estimated_freq,
@@ -611,7 +835,7 @@ unsafe fn parse_channel_states(
                     estimated_freq * residency_ratio
                 );
             } else {
-                debug3!(
+                trace!(
                     "  State {}: estimated {} MHz from P-state '{}'",
                     state_idx,
                     estimated_freq,
@@ -619,7 +843,7 @@ unsafe fn parse_channel_states(
                 );
             }
         } else {
-            debug3!(
+            trace!(
                 "  State {}: name '{}' doesn't match frequency patterns, skipping",
                 state_idx,
                 state_name_str
@@ -631,10 +855,8 @@ unsafe fn parse_channel_states(
 /// Find the IOReportChannels dictionary from the original channels dictionary
 #[allow(dead_code)]
 unsafe fn find_ioreport_channels(orig_channels: CFDictionaryRef) -> Option<CFDictionaryRef> {
-    use crate::debug3;
-
     let channels_count = CFDictionaryGetCount(orig_channels);
-    debug3!("Original channels_dict has {} channels", channels_count);
+    trace!("Original channels_dict has {} channels", channels_count);
 
     if channels_count == 0 {
         return None;
@@ -776,22 +998,20 @@ unsafe fn process_array_channels(
     _channels_count: usize,
     freq_logging: bool,
 ) -> (FrequencyData, Option<CFDictionaryRef>) {
-    use crate::debug3;
-
     let mut accumulator = FrequencyAccumulator::default();
-    debug3!("Processing {} channels from array", array_count);
+    trace!("Processing {} channels from array", array_count);
 
     for i in 0..array_count {
         let channel_value_ptr = CFArrayGetValueAtIndex(array_ptr, i);
         if channel_value_ptr.is_null() {
-            debug3!("Array element {} is null, skipping", i);
+            trace!("Array element {} is null, skipping", i);
             continue;
         }
 
         let value_type_id = CFGetTypeID(channel_value_ptr as CFTypeRef);
         let dict_type_id = CFDictionaryGetTypeID();
         if value_type_id != dict_type_id {
-            debug3!(
+            trace!(
                 "Array element {} is not a dictionary (type_id={}), skipping",
                 i,
                 value_type_id
@@ -804,13 +1024,13 @@ unsafe fn process_array_channels(
         // Get channel name directly from the channel dictionary
         let channel_name_ref = IOReportChannelGetChannelName(channel_ref);
         if channel_name_ref.is_null() {
-            debug3!("Array element {}: channel name is null, skipping", i);
+            trace!("Array element {}: channel name is null, skipping", i);
             continue;
         }
 
         let channel_name = CFString::wrap_under_get_rule(channel_name_ref);
         let channel_name_str = channel_name.to_string();
-        debug3!(
+        trace!(
             "Array element {}: Processing channel '{}'",
             i,
             channel_name_str
@@ -819,7 +1039,7 @@ unsafe fn process_array_channels(
         let (is_p_core, is_e_core) = classify_channel(&channel_name_str);
 
         if is_performance_channel(&channel_name_str) {
-            debug3!(
+            trace!(
                 "Found performance channel in array: '{}' (P-core: {}, E-core: {})",
                 channel_name_str,
                 is_p_core,
@@ -834,7 +1054,7 @@ unsafe fn process_array_channels(
                 freq_logging,
             );
         } else {
-            debug3!(
+            trace!(
                 "Array element {}: Channel '{}' is NOT a performance channel, skipping",
                 i,
                 channel_name_str
@@ -844,7 +1064,7 @@ unsafe fn process_array_channels(
 
     let result = calculate_frequencies(&accumulator, freq_logging);
     if freq_logging {
-        debug3!(
+        trace!(
             "=== ARRAY PROCESSING END: Overall={:.2} GHz, P-core={:.2} GHz, E-core={:.2} GHz ===",
             result.overall,
             result.p_core,
@@ -866,16 +1086,14 @@ unsafe fn process_actual_channels(
     accumulator: &mut FrequencyAccumulator,
     freq_logging: bool,
 ) {
-    use crate::debug3;
-
     let actual_channels_count = CFDictionaryGetCount(actual_channels_ref);
-    debug3!(
+    trace!(
         "IOReportChannels contains {} actual channels",
         actual_channels_count
     );
 
     if actual_channels_count == 0 {
-        debug3!("No channels found in IOReportChannels, cannot process frequencies");
+        trace!("No channels found in IOReportChannels, cannot process frequencies");
         return;
     }
 
@@ -890,7 +1108,7 @@ unsafe fn process_actual_channels(
         actual_channel_values.as_mut_ptr(),
     );
 
-    debug3!(
+    trace!(
         "Iterating through {} actual channels to find performance states",
         actual_channels_count
     );
@@ -901,25 +1119,25 @@ unsafe fn process_actual_channels(
     for i in 0..(actual_channels_count as usize) {
         let actual_key_ref = actual_channel_keys[i] as CFStringRef;
         if actual_key_ref.is_null() {
-            debug3!("Entry {}: key is null, skipping", i);
+            trace!("Entry {}: key is null, skipping", i);
             continue;
         }
 
         let channel_key_str = CFString::wrap_under_get_rule(actual_key_ref);
         let channel_key = channel_key_str.to_string();
-        debug3!("Entry {}: channel_key='{}'", i, channel_key);
+        trace!("Entry {}: channel_key='{}'", i, channel_key);
 
         // Get the channel value from the sample (contains residency data)
         let sample_channel_value = actual_channel_values[i];
         if sample_channel_value.is_null() {
-            debug3!("Entry {}: channel value is null, skipping", i);
+            trace!("Entry {}: channel value is null, skipping", i);
             continue;
         }
 
         let value_type_id = CFGetTypeID(sample_channel_value as CFTypeRef);
         let dict_type_id = CFDictionaryGetTypeID();
         if value_type_id != dict_type_id {
-            debug3!(
+            trace!(
                 "Entry {}: channel value is not a dict (type_id={}), skipping",
                 i,
                 value_type_id
@@ -935,7 +1153,7 @@ unsafe fn process_actual_channels(
             let channel_name = CFString::wrap_under_get_rule(channel_name_ref);
             channel_name.to_string()
         } else {
-            debug3!(
+            trace!(
                 "Entry {}: channel_name_ref is null, trying fallback lookup",
                 i
             );
@@ -952,7 +1170,7 @@ unsafe fn process_actual_channels(
                     channel_name.to_string()
                 }
                 None => {
-                    debug3!(
+                    trace!(
                         "Entry {}: Could not get channel name from fallback, skipping",
                         i
                     );
@@ -961,7 +1179,7 @@ unsafe fn process_actual_channels(
             }
         };
 
-        debug3!(
+        trace!(
             "Entry {}: Processing channel: name='{}'",
             i,
             channel_name_str
@@ -969,7 +1187,7 @@ unsafe fn process_actual_channels(
 
         let (is_p_core, is_e_core) = classify_channel(&channel_name_str);
 
-        debug3!(
+        trace!(
             "Entry {}: Channel '{}' classification: is_p_core={}, is_e_core={}",
             i,
             channel_name_str,
@@ -980,7 +1198,7 @@ unsafe fn process_actual_channels(
         channels_processed += 1;
 
         let is_perf = is_performance_channel(&channel_name_str);
-        debug3!(
+        trace!(
             "Entry {}: is_performance_channel('{}') = {}",
             i,
             channel_name_str,
@@ -989,7 +1207,7 @@ unsafe fn process_actual_channels(
 
         if is_perf {
             performance_channels_found += 1;
-            debug3!(
+            trace!(
                 "Found performance state channel: '{}' (P-core: {}, E-core: {})",
                 channel_name_str,
                 is_p_core,
@@ -1007,7 +1225,7 @@ unsafe fn process_actual_channels(
                 freq_logging,
             );
         } else {
-            debug3!(
+            trace!(
                 "Entry {}: Channel '{}' is NOT a performance channel, skipping",
                 i,
                 channel_name_str
@@ -1015,7 +1233,7 @@ unsafe fn process_actual_channels(
         }
     }
 
-    debug3!(
+    trace!(
         "Processed {} channels, found {} performance channels",
         channels_processed,
         performance_channels_found
@@ -1024,8 +1242,6 @@ unsafe fn process_actual_channels(
 
 /// Calculate final frequencies from accumulator
 fn calculate_frequencies(accumulator: &FrequencyAccumulator, freq_logging: bool) -> FrequencyData {
-    use crate::debug3;
-
     let mut result = FrequencyData::default();
 
     // Calculate overall frequency
@@ -1033,10 +1249,10 @@ fn calculate_frequencies(accumulator: &FrequencyAccumulator, freq_logging: bool)
         result.overall =
             (accumulator.weighted_freq_sum / accumulator.total_residency / 1000.0) as f32;
         if freq_logging {
-            debug3!("Overall frequency: {:.2} GHz (weighted average, total_residency={:.3} s, weighted_sum={:.2} MHz)", 
+            trace!("Overall frequency: {:.2} GHz (weighted average, total_residency={:.3} s, weighted_sum={:.2} MHz)", 
                 result.overall, accumulator.total_residency, accumulator.weighted_freq_sum);
         } else {
-            debug3!(
+            trace!(
                 "IOReport frequency parsed: {:.2} GHz (weighted average)",
                 result.overall
             );
@@ -1044,20 +1260,20 @@ fn calculate_frequencies(accumulator: &FrequencyAccumulator, freq_logging: bool)
     } else if accumulator.max_freq_mhz > 0.0 {
         result.overall = (accumulator.max_freq_mhz / 1000.0) as f32;
         if freq_logging {
-            debug3!(
+            trace!(
                 "Overall frequency: {:.2} GHz (max frequency)",
                 result.overall
             );
         } else {
-            debug3!(
+            trace!(
                 "IOReport frequency parsed: {:.2} GHz (max frequency)",
                 result.overall
             );
         }
     } else if freq_logging {
-        debug3!("Could not extract overall frequency from IOReport");
+        trace!("Could not extract overall frequency from IOReport");
     } else {
-        debug3!("Could not extract overall frequency from IOReport");
+        trace!("Could not extract overall frequency from IOReport");
     }
 
     // Calculate P-core frequency
@@ -1066,10 +1282,10 @@ fn calculate_frequencies(accumulator: &FrequencyAccumulator, freq_logging: bool)
             / accumulator.p_core_total_residency
             / 1000.0) as f32;
         if freq_logging {
-            debug3!("P-core frequency: {:.2} GHz (weighted average, total_residency={:.3} s, weighted_sum={:.2} MHz)", 
+            trace!("P-core frequency: {:.2} GHz (weighted average, total_residency={:.3} s, weighted_sum={:.2} MHz)", 
                 result.p_core, accumulator.p_core_total_residency, accumulator.p_core_weighted_freq_sum);
         } else {
-            debug3!(
+            trace!(
                 "IOReport P-core frequency parsed: {:.2} GHz (weighted average)",
                 result.p_core
             );
@@ -1077,15 +1293,15 @@ fn calculate_frequencies(accumulator: &FrequencyAccumulator, freq_logging: bool)
     } else if accumulator.p_core_max_freq_mhz > 0.0 {
         result.p_core = (accumulator.p_core_max_freq_mhz / 1000.0) as f32;
         if freq_logging {
-            debug3!("P-core frequency: {:.2} GHz (max frequency)", result.p_core);
+            trace!("P-core frequency: {:.2} GHz (max frequency)", result.p_core);
         } else {
-            debug3!(
+            trace!(
                 "IOReport P-core frequency parsed: {:.2} GHz (max frequency)",
                 result.p_core
             );
         }
     } else if freq_logging {
-        debug3!("P-core frequency: NOT FOUND (p_core_total_residency={:.3} s, p_core_max_freq_mhz={:.2} MHz)", 
+        trace!("P-core frequency: NOT FOUND (p_core_total_residency={:.3} s, p_core_max_freq_mhz={:.2} MHz)", 
             accumulator.p_core_total_residency, accumulator.p_core_max_freq_mhz);
     }
 
@@ -1095,10 +1311,10 @@ fn calculate_frequencies(accumulator: &FrequencyAccumulator, freq_logging: bool)
             / accumulator.e_core_total_residency
             / 1000.0) as f32;
         if freq_logging {
-            debug3!("E-core frequency: {:.2} GHz (weighted average, total_residency={:.3} s, weighted_sum={:.2} MHz)", 
+            trace!("E-core frequency: {:.2} GHz (weighted average, total_residency={:.3} s, weighted_sum={:.2} MHz)", 
                 result.e_core, accumulator.e_core_total_residency, accumulator.e_core_weighted_freq_sum);
         } else {
-            debug3!(
+            trace!(
                 "IOReport E-core frequency parsed: {:.2} GHz (weighted average)",
                 result.e_core
             );
@@ -1106,15 +1322,15 @@ fn calculate_frequencies(accumulator: &FrequencyAccumulator, freq_logging: bool)
     } else if accumulator.e_core_max_freq_mhz > 0.0 {
         result.e_core = (accumulator.e_core_max_freq_mhz / 1000.0) as f32;
         if freq_logging {
-            debug3!("E-core frequency: {:.2} GHz (max frequency)", result.e_core);
+            trace!("E-core frequency: {:.2} GHz (max frequency)", result.e_core);
         } else {
-            debug3!(
+            trace!(
                 "IOReport E-core frequency parsed: {:.2} GHz (max frequency)",
                 result.e_core
             );
         }
     } else if freq_logging {
-        debug3!("E-core frequency: NOT FOUND (e_core_total_residency={:.3} s, e_core_max_freq_mhz={:.2} MHz)", 
+        trace!("E-core frequency: NOT FOUND (e_core_total_residency={:.3} s, e_core_max_freq_mhz={:.2} MHz)", 
             accumulator.e_core_total_residency, accumulator.e_core_max_freq_mhz);
     }
 
@@ -1128,17 +1344,17 @@ fn calculate_frequencies(accumulator: &FrequencyAccumulator, freq_logging: bool)
 ///
 /// If last_sample is provided, computes a delta sample for recent frequency.
 /// Otherwise, uses the raw sample (absolute counters since boot).
-pub unsafe fn read_frequencies_from_ioreport(
+unsafe fn read_frequencies_from_ioreport(
     subscription_ptr: *const c_void,
     channels_ref: CFMutableDictionaryRef,
     orig_channels: Option<CFDictionaryRef>,
     last_sample: Option<CFDictionaryRef>,
     freq_logging: bool,
 ) -> (FrequencyData, Option<CFDictionaryRef>) {
-    use crate::debug3;
+    let _span = tracing::trace_span!(target: "mac_stats::perf", "ioreport_frequencies").entered();
 
     if freq_logging {
-        debug3!("=== FREQUENCY READ START ===");
+        trace!("=== FREQUENCY READ START ===");
     }
 
     let mut accumulator = FrequencyAccumulator::default();
@@ -1149,7 +1365,7 @@ pub unsafe fn read_frequencies_from_ioreport(
     let current_sample = IOReportCreateSamples(subscription_ptr, channels_ref, std::ptr::null());
 
     if current_sample.is_null() {
-        debug3!("Failed to create IOReport sample (sample is null)");
+        trace!("Failed to create IOReport sample (sample is null)");
         return (FrequencyData::default(), None);
     }
 
@@ -1171,23 +1387,23 @@ pub unsafe fn read_frequencies_from_ioreport(
     // Otherwise use the raw sample (absolute counters)
     let sample_to_parse = if let Some(last) = last_sample {
         if freq_logging {
-            debug3!("Computing delta sample from last sample");
+            trace!("Computing delta sample from last sample");
         }
         let delta = IOReportCreateSamplesDelta(last, sample_guard.0, std::ptr::null());
 
         if delta.is_null() {
-            debug3!("Failed to create delta sample, using raw sample");
+            trace!("Failed to create delta sample, using raw sample");
             sample_guard.0
         } else {
             if freq_logging {
-                debug3!("Using delta sample for recent frequency calculation");
+                trace!("Using delta sample for recent frequency calculation");
             }
             // We'll parse the delta, but keep current_sample for next iteration
             delta
         }
     } else {
         if freq_logging {
-            debug3!("No last sample available, using raw sample (absolute counters)");
+            trace!("No last sample available, using raw sample (absolute counters)");
         }
         sample_guard.0
     };
@@ -1208,7 +1424,7 @@ pub unsafe fn read_frequencies_from_ioreport(
     let orig_channels = match orig_channels {
         Some(ch) => ch,
         None => {
-            debug3!("Original channels_dict not available, cannot parse frequency");
+            trace!("Original channels_dict not available, cannot parse frequency");
             // Release delta if we created one (guard will drop and release)
             drop(delta_guard);
             // Release current sample
@@ -1223,7 +1439,7 @@ pub unsafe fn read_frequencies_from_ioreport(
     // Get channel keys and values from orig_channels for name lookup (needed for array processing too)
     let channels_count = CFDictionaryGetCount(orig_channels) as usize;
     if channels_count == 0 {
-        debug3!("Original channels_dict is empty (no channels)");
+        trace!("Original channels_dict is empty (no channels)");
         // Release delta if we created one (guard will drop and release)
         drop(delta_guard);
         // Release current sample
@@ -1246,7 +1462,7 @@ pub unsafe fn read_frequencies_from_ioreport(
     // CRITICAL: Extract IOReportChannels dictionary from sample
     // The sample has structure: { "IOReportChannels" -> [array of channel_dicts] or {dict of channel_dicts} }
     let sample_keys_count = CFDictionaryGetCount(sample);
-    debug3!("Sample dictionary has {} keys", sample_keys_count);
+    trace!("Sample dictionary has {} keys", sample_keys_count);
 
     let mut sample_keys_buf: Vec<*const c_void> =
         vec![std::ptr::null(); sample_keys_count as usize];
@@ -1269,25 +1485,25 @@ pub unsafe fn read_frequencies_from_ioreport(
                 if key_type_id == string_type_id {
                     let key_str = CFString::wrap_under_get_rule(key_ref);
                     let key_name = key_str.to_string();
-                    debug3!("Sample key[{}]: '{}'", i, key_name);
+                    trace!("Sample key[{}]: '{}'", i, key_name);
                     if key_name == "IOReportChannels" {
                         let value_ptr = sample_values_buf[i];
                         if !value_ptr.is_null() {
                             let value_type_id = CFGetTypeID(value_ptr as CFTypeRef);
                             let dict_type_id = CFDictionaryGetTypeID();
                             let array_type_id = CFArrayGetTypeID();
-                            debug3!("IOReportChannels value: type_id={}, dict_type_id={}, array_type_id={}", value_type_id, dict_type_id, array_type_id);
+                            trace!("IOReportChannels value: type_id={}, dict_type_id={}, array_type_id={}", value_type_id, dict_type_id, array_type_id);
 
                             if value_type_id == dict_type_id {
-                                debug3!("Successfully extracted IOReportChannels dictionary from sample");
+                                trace!("Successfully extracted IOReportChannels dictionary from sample");
                                 found = Some(value_ptr as CFDictionaryRef);
                                 break;
                             } else if value_type_id == array_type_id {
                                 // IOReportChannels is an array of channel dictionaries
-                                debug3!("IOReportChannels value is an array - processing directly");
+                                trace!("IOReportChannels value is an array - processing directly");
                                 let array_ptr = value_ptr as *const c_void;
                                 let array_count = CFArrayGetCount(array_ptr);
-                                debug3!("IOReportChannels array has {} elements", array_count);
+                                trace!("IOReportChannels array has {} elements", array_count);
                                 // Process array first (while delta sample is still valid - delta_guard keeps it alive)
                                 let (result, _) = process_array_channels(
                                     array_ptr,
@@ -1304,14 +1520,14 @@ pub unsafe fn read_frequencies_from_ioreport(
                                 sample_guard.1 = true; // Prevent release
                                 return (result, Some(sample_guard.0));
                             } else {
-                                debug3!("IOReportChannels value is not a dictionary or array (type_id={}, expected_dict={})", value_type_id, dict_type_id);
+                                trace!("IOReportChannels value is not a dictionary or array (type_id={}, expected_dict={})", value_type_id, dict_type_id);
                             }
                         } else {
-                            debug3!("IOReportChannels value pointer is null");
+                            trace!("IOReportChannels value pointer is null");
                         }
                     }
                 } else {
-                    debug3!(
+                    trace!(
                         "Sample key[{}] is not a string (type_id={})",
                         i,
                         key_type_id
@@ -1322,7 +1538,7 @@ pub unsafe fn read_frequencies_from_ioreport(
         match found {
             Some(ch) => ch,
             None => {
-                debug3!("Failed to extract IOReportChannels from sample, cannot parse frequency");
+                trace!("Failed to extract IOReportChannels from sample, cannot parse frequency");
                 // Release delta if we created one (guard will drop and release)
                 drop(delta_guard);
                 // Release current sample (we won't store it if we can't parse)
@@ -1349,7 +1565,7 @@ pub unsafe fn read_frequencies_from_ioreport(
 
     // Debug: Check accumulator state
     if freq_logging || accumulator.total_residency == 0.0 {
-        debug3!("Accumulator after processing: total_residency={:.3}, max_freq={:.2} MHz, p_core_residency={:.3}, e_core_residency={:.3}",
+        trace!("Accumulator after processing: total_residency={:.3}, max_freq={:.2} MHz, p_core_residency={:.3}, e_core_residency={:.3}",
             accumulator.total_residency, accumulator.max_freq_mhz, accumulator.p_core_total_residency, accumulator.e_core_total_residency);
     }
 
@@ -1357,7 +1573,7 @@ pub unsafe fn read_frequencies_from_ioreport(
     let result = calculate_frequencies(&accumulator, freq_logging);
 
     if freq_logging {
-        debug3!(
+        trace!(
             "=== FREQUENCY READ END: Overall={:.2} GHz, P-core={:.2} GHz, E-core={:.2} GHz ===",
             result.overall,
             result.p_core,
@@ -1384,7 +1600,7 @@ pub unsafe fn read_frequencies_from_ioreport(
 ///
 /// Returns (PowerData, Option<CFDictionaryRef>) where the dictionary is the
 /// current sample for delta calculation on next call.
-pub unsafe fn read_power_from_ioreport(
+unsafe fn read_power_from_ioreport(
     subscription_ptr: *const c_void,
     channels_ref: CFMutableDictionaryRef,
     orig_channels: Option<CFDictionaryRef>,
@@ -1392,10 +1608,10 @@ pub unsafe fn read_power_from_ioreport(
     last_read_time: Option<Instant>,
     power_logging: bool,
 ) -> (PowerData, Option<CFDictionaryRef>) {
-    use crate::debug3;
+    let _span = tracing::trace_span!(target: "mac_stats::perf", "ioreport_power").entered();
 
-    debug3!("=== POWER READ START ===");
-    debug3!(
+    trace!("=== POWER READ START ===");
+    trace!(
         "subscription_ptr={:p}, channels_ref={:p}, orig_channels.is_some()={}",
         subscription_ptr,
         channels_ref,
@@ -1404,11 +1620,11 @@ pub unsafe fn read_power_from_ioreport(
 
     // Validate inputs
     if subscription_ptr.is_null() {
-        debug3!("ERROR: subscription_ptr is null!");
+        trace!("ERROR: subscription_ptr is null!");
         return (PowerData::default(), None);
     }
     if channels_ref.is_null() {
-        debug3!("ERROR: channels_ref is null!");
+        trace!("ERROR: channels_ref is null!");
         return (PowerData::default(), None);
     }
 
@@ -1416,14 +1632,14 @@ pub unsafe fn read_power_from_ioreport(
     let mut gpu_energy_total: i64 = 0;
 
     // Create current sample from subscription
-    debug3!("Creating IOReport power sample...");
+    trace!("Creating IOReport power sample...");
     let current_sample = IOReportCreateSamples(subscription_ptr, channels_ref, std::ptr::null());
 
     if current_sample.is_null() {
-        debug3!("ERROR: Failed to create IOReport power sample (sample is null)");
+        trace!("ERROR: Failed to create IOReport power sample (sample is null)");
         return (PowerData::default(), None);
     }
-    debug3!(
+    trace!(
         "IOReport power sample created successfully: {:p}",
         current_sample
     );
@@ -1451,7 +1667,7 @@ pub unsafe fn read_power_from_ioreport(
             if time_delta > 0.0 && time_delta < 60.0 {
                 // Valid time delta (between 0 and 60 seconds)
                 if power_logging {
-                    debug3!(
+                    trace!(
                         "Computing delta power sample (time delta: {:.2}s)",
                         time_delta
                     );
@@ -1459,21 +1675,21 @@ pub unsafe fn read_power_from_ioreport(
                 let delta = IOReportCreateSamplesDelta(last, sample_guard.0, std::ptr::null());
 
                 if delta.is_null() {
-                    debug3!("Failed to create delta power sample, using raw sample");
+                    trace!("Failed to create delta power sample, using raw sample");
                     (sample_guard.0, time_delta)
                 } else {
                     (delta, time_delta)
                 }
             } else {
-                debug3!("Invalid time delta ({:.2}s), using raw sample", time_delta);
+                trace!("Invalid time delta ({:.2}s), using raw sample", time_delta);
                 (sample_guard.0, 0.0)
             }
         } else {
-            debug3!("No last sample available, using raw sample (absolute counters)");
+            trace!("No last sample available, using raw sample (absolute counters)");
             (sample_guard.0, 0.0)
         };
 
-    debug3!(
+    trace!(
         "Sample to parse: {:p}, time_delta={:.2}s",
         sample_to_parse,
         time_delta_secs
@@ -1488,20 +1704,20 @@ pub unsafe fn read_power_from_ioreport(
     };
 
     let sample = sample_to_parse;
-    debug3!("Using sample: {:p} for power parsing", sample);
+    trace!("Using sample: {:p} for power parsing", sample);
 
     // Get original channels dictionary (for channel name lookup)
-    debug3!(
+    trace!(
         "Checking original channels dict... orig_channels.is_some()={}",
         orig_channels.is_some()
     );
     let orig_channels = match orig_channels {
         Some(ch) => {
-            debug3!("Original power channels_dict available: {:p}", ch);
+            trace!("Original power channels_dict available: {:p}", ch);
             ch
         }
         None => {
-            debug3!("ERROR: Original power channels_dict not available, cannot parse power - returning 0.0W");
+            trace!("ERROR: Original power channels_dict not available, cannot parse power - returning 0.0W");
             drop(delta_guard);
             sample_guard.1 = true;
             unsafe {
@@ -1511,16 +1727,16 @@ pub unsafe fn read_power_from_ioreport(
         }
     };
 
-    debug3!(
+    trace!(
         "Extracting IOReportChannels from sample (sample={:p})...",
         sample
     );
     // Extract IOReportChannels from sample
     let sample_keys_count = CFDictionaryGetCount(sample);
-    debug3!("Power sample dictionary has {} keys", sample_keys_count);
+    trace!("Power sample dictionary has {} keys", sample_keys_count);
 
     if sample_keys_count == 0 {
-        debug3!("Power sample dictionary is empty!");
+        trace!("Power sample dictionary is empty!");
         drop(delta_guard);
         sample_guard.1 = true;
         unsafe {
@@ -1548,7 +1764,7 @@ pub unsafe fn read_power_from_ioreport(
             if key_type_id == string_type_id {
                 let key_str = CFString::wrap_under_get_rule(key_ref);
                 let key_name = key_str.to_string();
-                debug3!("Sample key[{}]: '{}'", i, key_name);
+                trace!("Sample key[{}]: '{}'", i, key_name);
 
                 // Check value type
                 let value_ptr = sample_values_buf[i];
@@ -1556,7 +1772,7 @@ pub unsafe fn read_power_from_ioreport(
                     let value_type_id = CFGetTypeID(value_ptr as CFTypeRef);
                     let dict_type_id = CFDictionaryGetTypeID();
                     let array_type_id = CFArrayGetTypeID();
-                    debug3!(
+                    trace!(
                         "  Value type_id={}, dict_type_id={}, array_type_id={}",
                         value_type_id,
                         dict_type_id,
@@ -1568,7 +1784,7 @@ pub unsafe fn read_power_from_ioreport(
                                 fn CFArrayGetCount(theArray: *const c_void) -> i32;
                             }
                             let array_count = CFArrayGetCount(value_ptr as *const c_void);
-                            debug3!("  Array has {} elements", array_count);
+                            trace!("  Array has {} elements", array_count);
                         }
                     }
                 }
@@ -1605,7 +1821,7 @@ pub unsafe fn read_power_from_ioreport(
                                     }
                                     let array_count = CFArrayGetCount(value_ptr as *const c_void);
                                     if power_logging {
-                                        debug3!("IOReportChannels in sample is an array with {} elements", array_count);
+                                        trace!("IOReportChannels in sample is an array with {} elements", array_count);
                                     }
                                     // Store array pointer for processing
                                     found = Some((
@@ -1624,7 +1840,7 @@ pub unsafe fn read_power_from_ioreport(
         match found {
             Some((ch, is_arr, arr_ptr)) => (ch, is_arr, arr_ptr),
             None => {
-                debug3!("Failed to extract IOReportChannels from power sample");
+                trace!("Failed to extract IOReportChannels from power sample");
                 drop(delta_guard);
                 sample_guard.1 = true;
                 unsafe {
@@ -1640,7 +1856,7 @@ pub unsafe fn read_power_from_ioreport(
         let array_ptr = match array_ptr_opt {
             Some(arr) => arr,
             None => {
-                debug3!("Array pointer is None");
+                trace!("Array pointer is None");
                 drop(delta_guard);
                 sample_guard.1 = true;
                 unsafe {
@@ -1659,7 +1875,7 @@ pub unsafe fn read_power_from_ioreport(
 
             let array_count = CFArrayGetCount(array_ptr);
             // Always log channel count (not just when power_logging is enabled)
-            debug3!(
+            trace!(
                 "Processing {} channels from array for power reading",
                 array_count
             );
@@ -1681,7 +1897,7 @@ pub unsafe fn read_power_from_ioreport(
             for i in 0..max_channels {
                 // Basic bounds check (shouldn't be needed but be safe)
                 if i < 0 {
-                    debug3!("Invalid array index {} (negative), stopping", i);
+                    trace!("Invalid array index {} (negative), stopping", i);
                     break;
                 }
 
@@ -1690,7 +1906,7 @@ pub unsafe fn read_power_from_ioreport(
                     error_count += 1;
                     consecutive_errors += 1;
                     if error_count > MAX_ERRORS || consecutive_errors > MAX_CONSECUTIVE_ERRORS {
-                        debug3!("Too many errors encountered (total: {}, consecutive: {}), stopping processing at channel {}", 
+                        trace!("Too many errors encountered (total: {}, consecutive: {}), stopping processing at channel {}", 
                             error_count, consecutive_errors, i);
                         break;
                     }
@@ -1705,7 +1921,7 @@ pub unsafe fn read_power_from_ioreport(
                     error_count += 1;
                     consecutive_errors += 1;
                     if error_count > MAX_ERRORS || consecutive_errors > MAX_CONSECUTIVE_ERRORS {
-                        debug3!("Too many non-dictionary channels encountered (total: {}, consecutive: {}), stopping processing at channel {}", 
+                        trace!("Too many non-dictionary channels encountered (total: {}, consecutive: {}), stopping processing at channel {}", 
                             error_count, consecutive_errors, i);
                         break;
                     }
@@ -1721,7 +1937,7 @@ pub unsafe fn read_power_from_ioreport(
                     error_count += 1;
                     consecutive_errors += 1;
                     if error_count > MAX_ERRORS || consecutive_errors > MAX_CONSECUTIVE_ERRORS {
-                        debug3!("Too many channels with null names (total: {}, consecutive: {}), stopping processing at channel {}", 
+                        trace!("Too many channels with null names (total: {}, consecutive: {}), stopping processing at channel {}", 
                             error_count, consecutive_errors, i);
                         break;
                     }
@@ -1768,7 +1984,7 @@ pub unsafe fn read_power_from_ioreport(
                 // GPU channels like "GPU Energy" were working before and need to be processed
                 if is_power_channel || is_cpu || is_gpu {
                     if power_logging {
-                        debug3!(
+                        trace!(
                             "Found channel in array: '{}' (is_power={}, is_cpu={}, is_gpu={})",
                             channel_name_str,
                             is_power_channel,
@@ -1799,7 +2015,7 @@ pub unsafe fn read_power_from_ioreport(
                     // Energy values are typically in micro-joules or nano-joules, so very large values
                     // (like > 1e15) are likely invalid/corrupted data
                     if !(-1_000_000_000_000_000..=1_000_000_000_000_000).contains(&energy_value) {
-                        debug3!(
+                        trace!(
                             "Suspicious energy value {} for channel '{}', treating as 0",
                             energy_value,
                             channel_name_str
@@ -1813,7 +2029,7 @@ pub unsafe fn read_power_from_ioreport(
                     // Only process states if state_count is valid (not -1)
                     if state_count > 0 {
                         if power_logging {
-                            debug3!(
+                            trace!(
                                 "  Channel '{}' has {} states",
                                 channel_name_str,
                                 state_count
@@ -1830,7 +2046,7 @@ pub unsafe fn read_power_from_ioreport(
                                 state_energy_sum += residency;
                             }
                             if state_energy_sum > 0 && power_logging {
-                                debug3!("  Channel '{}': using state residency sum={} (simple value was 0)", 
+                                trace!("  Channel '{}': using state residency sum={} (simple value was 0)", 
                                     channel_name_str, state_energy_sum);
                                 energy_value = state_energy_sum;
                             }
@@ -1847,7 +2063,7 @@ pub unsafe fn read_power_from_ioreport(
                             && energy_value_1 > -1_000_000_000_000_000
                         {
                             if power_logging {
-                                debug3!(
+                                trace!(
                                     "  Channel '{}': index 0 was 0, trying index 1: {}",
                                     channel_name_str,
                                     energy_value_1
@@ -1862,7 +2078,7 @@ pub unsafe fn read_power_from_ioreport(
                     if is_cpu {
                         cpu_energy_total += energy_value;
                         if power_logging && energy_value != 0 {
-                            debug3!(
+                            trace!(
                                 "  Added to CPU: energy={} (total: {})",
                                 energy_value,
                                 cpu_energy_total
@@ -1871,14 +2087,14 @@ pub unsafe fn read_power_from_ioreport(
                     } else if is_gpu {
                         gpu_energy_total += energy_value;
                         if power_logging && energy_value != 0 {
-                            debug3!(
+                            trace!(
                                 "  Added to GPU: energy={} (total: {})",
                                 energy_value,
                                 gpu_energy_total
                             );
                         }
                     } else if power_logging && (energy_value != 0 || state_count > 0) {
-                        debug3!(
+                        trace!(
                             "  Channel '{}' has energy={} but is not CPU or GPU (skipping)",
                             channel_name_str,
                             energy_value
@@ -1888,7 +2104,7 @@ pub unsafe fn read_power_from_ioreport(
 
                 // Log progress every 50 channels to help identify crash location
                 if (i + 1) % 50 == 0 {
-                    debug3!(
+                    trace!(
                         "Processed {} / {} channels (CPU energy: {}, GPU energy: {})",
                         i + 1,
                         max_channels,
@@ -1900,21 +2116,21 @@ pub unsafe fn read_power_from_ioreport(
 
             // Log summary of candidates found (always log, not just when power_logging is enabled)
             // This is critical for debugging CPU power issues
-            debug3!("Channel summary: {} CPU candidates, {} GPU candidates, {} power candidates, cpu_energy_total={}, gpu_energy_total={}, time_delta={:.2}s", 
+            trace!("Channel summary: {} CPU candidates, {} GPU candidates, {} power candidates, cpu_energy_total={}, gpu_energy_total={}, time_delta={:.2}s", 
                 cpu_candidates.len(), gpu_candidates.len(), power_candidates.len(), cpu_energy_total, gpu_energy_total, time_delta_secs);
             if !cpu_candidates.is_empty() {
                 // Limit to first 10 CPU candidates to avoid log spam
                 let display_candidates: Vec<String> =
                     cpu_candidates.iter().take(10).cloned().collect();
-                debug3!(
+                trace!(
                     "CPU candidate channels (first 10): {:?}",
                     display_candidates
                 );
                 if cpu_candidates.len() > 10 {
-                    debug3!("... and {} more CPU candidates", cpu_candidates.len() - 10);
+                    trace!("... and {} more CPU candidates", cpu_candidates.len() - 10);
                 }
             } else {
-                debug3!(
+                trace!(
                     "WARNING: No CPU candidate channels found! This explains why CPU power is 0W"
                 );
             }
@@ -1922,7 +2138,7 @@ pub unsafe fn read_power_from_ioreport(
                 // Limit to first 10 GPU candidates to avoid log spam
                 let display_candidates: Vec<String> =
                     gpu_candidates.iter().take(10).cloned().collect();
-                debug3!(
+                trace!(
                     "GPU candidate channels (first 10): {:?}",
                     display_candidates
                 );
@@ -1948,7 +2164,7 @@ pub unsafe fn read_power_from_ioreport(
                     result.cpu_power =
                         (cpu_energy_total as f64 / time_delta_secs / 1_000_000.0) as f32;
                     if power_logging {
-                        debug3!("CPU power: millijoules gave {:.2}W (too high), trying microjoules: {:.2}W", 
+                        trace!("CPU power: millijoules gave {:.2}W (too high), trying microjoules: {:.2}W", 
                             (cpu_energy_total as f64 / time_delta_secs / 1_000.0) as f32, result.cpu_power);
                     }
                 }
@@ -1967,21 +2183,21 @@ pub unsafe fn read_power_from_ioreport(
                     result.gpu_power =
                         (gpu_energy_total as f64 / time_delta_secs / 1_000_000_000.0) as f32;
                     if power_logging {
-                        debug3!("GPU power: microjoules gave {:.2}W (too high), trying nanojoules: {:.2}W", 
+                        trace!("GPU power: microjoules gave {:.2}W (too high), trying nanojoules: {:.2}W", 
                             (gpu_energy_total as f64 / time_delta_secs / 1_000_000.0) as f32, result.gpu_power);
                     }
                 }
             }
 
             if power_logging {
-                debug3!("Power calculated: CPU={:.2}W, GPU={:.2}W (energy: CPU={}, GPU={}, time={:.2}s)", 
+                trace!("Power calculated: CPU={:.2}W, GPU={:.2}W (energy: CPU={}, GPU={}, time={:.2}s)", 
                     result.cpu_power, result.gpu_power, cpu_energy_total, gpu_energy_total, time_delta_secs);
             }
         } else {
             // time_delta is 0 or invalid - cannot calculate power
             // Return 0.0 for both (cache update logic will preserve previous values)
             if power_logging {
-                debug3!(
+                trace!(
                     "Cannot calculate power: time_delta={:.2}s (energy: CPU={}, GPU={})",
                     time_delta_secs,
                     cpu_energy_total,
@@ -2000,7 +2216,7 @@ pub unsafe fn read_power_from_ioreport(
     // Get channel keys and values from orig_channels
     let channels_count = CFDictionaryGetCount(orig_channels) as usize;
     if channels_count == 0 {
-        debug3!("Original power channels_dict is empty");
+        trace!("Original power channels_dict is empty");
         drop(delta_guard);
         sample_guard.1 = true;
         unsafe {
@@ -2020,7 +2236,7 @@ pub unsafe fn read_power_from_ioreport(
     // Process channels from sample (dictionary case)
     // Note: Array case is handled above
     let sample_channels_count = CFDictionaryGetCount(sample_channels_ref);
-    debug3!(
+    trace!(
         "Power sample contains {} channels (dictionary)",
         sample_channels_count
     );
@@ -2037,7 +2253,7 @@ pub unsafe fn read_power_from_ioreport(
 
     // Iterate through channels and extract power/energy values
     if power_logging {
-        debug3!(
+        trace!(
             "Processing {} power channels from sample",
             sample_channels_count
         );
@@ -2088,7 +2304,7 @@ pub unsafe fn read_power_from_ioreport(
 
         if let Some(name) = channel_name {
             if power_logging {
-                debug3!("Power channel found: '{}' (key: '{}')", name, channel_key);
+                trace!("Power channel found: '{}' (key: '{}')", name, channel_key);
             }
 
             // Check if this is a power/energy channel
@@ -2121,7 +2337,7 @@ pub unsafe fn read_power_from_ioreport(
                     IOReportSimpleGetIntegerValue(channel_value as CFDictionaryRef, 0);
 
                 if power_logging {
-                    debug3!(
+                    trace!(
                         "Power channel '{}': energy={} (raw value), is_cpu={}, is_gpu={}",
                         name,
                         energy_value,
@@ -2133,7 +2349,7 @@ pub unsafe fn read_power_from_ioreport(
                 if is_cpu_power {
                     cpu_energy_total += energy_value;
                     if power_logging {
-                        debug3!(
+                        trace!(
                             "Added to CPU energy total: {} (new total: {})",
                             energy_value,
                             cpu_energy_total
@@ -2142,7 +2358,7 @@ pub unsafe fn read_power_from_ioreport(
                 } else if is_gpu_power {
                     gpu_energy_total += energy_value;
                     if power_logging {
-                        debug3!(
+                        trace!(
                             "Added to GPU energy total: {} (new total: {})",
                             energy_value,
                             gpu_energy_total
@@ -2152,17 +2368,17 @@ pub unsafe fn read_power_from_ioreport(
             } else {
                 // Log all channels for debugging (even non-power channels) to help diagnose CPU power issue
                 if power_logging {
-                    debug3!("Channel '{}' is not a power channel (skipping) - is_power_channel={}, contains CPU={}, contains GPU={}", 
+                    trace!("Channel '{}' is not a power channel (skipping) - is_power_channel={}, contains CPU={}, contains GPU={}", 
                         name, is_power_channel, name.contains("CPU"), name.contains("GPU"));
                 }
             }
         } else if power_logging {
-            debug3!("Could not find channel name for key '{}'", channel_key);
+            trace!("Could not find channel name for key '{}'", channel_key);
         }
     }
 
     if power_logging {
-        debug3!(
+        trace!(
             "Total energy: CPU={}, GPU={}, time_delta={:.2}s",
             cpu_energy_total,
             gpu_energy_total,
@@ -2182,7 +2398,7 @@ pub unsafe fn read_power_from_ioreport(
         if cpu_energy_total > 0 {
             result.cpu_power = (cpu_energy_total as f64 / time_delta_secs / 1_000_000.0) as f32;
             if power_logging {
-                debug3!(
+                trace!(
                     "CPU power: {:.2}W (energy={} μJ, time={:.2}s)",
                     result.cpu_power,
                     cpu_energy_total,
@@ -2194,7 +2410,7 @@ pub unsafe fn read_power_from_ioreport(
         if gpu_energy_total > 0 {
             result.gpu_power = (gpu_energy_total as f64 / time_delta_secs / 1_000_000.0) as f32;
             if power_logging {
-                debug3!(
+                trace!(
                     "GPU power: {:.2}W (energy={} μJ, time={:.2}s)",
                     result.gpu_power,
                     gpu_energy_total,
@@ -2203,14 +2419,14 @@ pub unsafe fn read_power_from_ioreport(
             }
         }
     } else {
-        debug3!(
+        trace!(
             "Cannot calculate power: invalid time delta ({:.2}s)",
             time_delta_secs
         );
     }
 
     if power_logging {
-        debug3!(
+        trace!(
             "=== POWER READ END: CPU={:.2}W, GPU={:.2}W ===",
             result.cpu_power,
             result.gpu_power
@@ -0,0 +1,199 @@
+//! mac-stats collector: the metric collection engine of mac-stats, without the app
+//!
+//! [`Collector`] samples CPU, memory and disk usage and the busiest processes (sysinfo), CPU and
+//! GPU temperature and fan speed (SMC), and CPU frequency and CPU/GPU power (IOReport, Apple
+//! Silicon only). It owns its SMC connection and IOReport subscriptions, so it works in any
+//! program: no Tauri, AppKit or running mac-stats needed. GPU usage and the saved history stay
+//! in the app (`mac_stats::collector`).
+//!
+//! ```no_run
+//! use mac_stats_collector::Collector;
+//!
+//! let mut collector = Collector::new();
+//! std::thread::sleep(std::time::Duration::from_secs(1));
+//! let sample = collector.sample();
+//! println!("CPU {:.1}%  RAM {:.1}%", sample.cpu, sample.ram);
+//! if let Some(power) = sample.power {
+//!     println!("CPU {:.1} W  GPU {:.1} W", power.cpu_power, power.gpu_power);
+//! }
+//! for process in collector.top_processes(5) {
+//!     println!("{:>6} {:5.1}% {}", process.pid, process.cpu, process.name);
+//! }
+//! ```
+//!
+//! Usage, frequency and power are averages since the previous [`Collector::sample`] (or since
+//! [`Collector::new`]); call it on an interval of a second or more for steady numbers.
+
+pub mod ioreport;
+pub mod smc;
+
+use serde::Serialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+use sysinfo::{Disks, ProcessesToUpdate, System};
+
+use ioreport::{FrequencyData, PowerData, Subscription};
+use smc::Smc;
+
+/// One reading of everything the collector measures
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Sample {
+    /// Unix time (seconds)
+    pub timestamp: i64,
+    /// CPU usage (%)
+    pub cpu: f32,
+    /// Used memory (%)
+    pub ram: f32,
+    /// Used space on the first disk (%)
+    pub disk: f32,
+    /// 1, 5 and 15 minute load averages
+    pub load: [f64; 3],
+    /// CPU die (or proximity) temperature in °C
+    pub cpu_temperature: Option<f32>,
+    /// Average GPU cluster temperature in °C
+    pub gpu_temperature: Option<f32>,
+    /// Fastest fan in RPM
+    pub fan_rpm: Option<f32>,
+    /// CPU frequency in GHz, overall and per core type (0.0 where not found)
+    pub frequency: Option<FrequencyData>,
+    /// CPU and GPU power in watts
+    pub power: Option<PowerData>,
+}
+
+/// A process and its CPU usage as of the last [`Collector::sample`]
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessUsage {
+    pub pid: u32,
+    pub name: String,
+    /// % of one core
+    pub cpu: f32,
+}
+
+/// The metric collection engine
+///
+/// Sensors this Mac lacks (SMC on a VM, IOReport channels on Intel) read as `None`.
+pub struct Collector {
+    system: System,
+    disks: Disks,
+    smc: Option<Smc>,
+    /// GPU temperature keys found on the first scan (see [`smc::gpu_temperatures`])
+    gpu_keys: Option<Vec<String>>,
+    frequency: Option<Subscription>,
+    power: Option<Subscription>,
+}
+
+impl Default for Collector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Collector {
+    /// Connect to SMC, subscribe to IOReport and take the baseline readings the first
+    /// [`Self::sample`] is measured against
+    pub fn new() -> Self {
+        let mut collector = Self {
+            system: System::new(),
+            disks: Disks::new_with_refreshed_list(),
+            smc: Smc::connect().ok(),
+            gpu_keys: None,
+            frequency: Subscription::frequency(),
+            power: Subscription::power(),
+        };
+        collector.refresh();
+        if let Some(frequency) = collector.frequency.as_mut() {
+            frequency.read_frequencies(false);
+        }
+        if let Some(power) = collector.power.as_mut() {
+            power.read_power(false);
+        }
+        collector
+    }
+
+    /// Refresh every counter and read all sensors
+    pub fn sample(&mut self) -> Sample {
+        self.refresh();
+        let load = System::load_average();
+        Sample {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |d| d.as_secs() as i64),
+            cpu: self.system.global_cpu_usage(),
+            ram: percent(self.system.used_memory(), self.system.total_memory()),
+            disk: self.disks.list().first().map_or(0.0, |disk| {
+                let total = disk.total_space();
+                percent(total.saturating_sub(disk.available_space()), total)
+            }),
+            load: [load.one, load.five, load.fifteen],
+            cpu_temperature: self.cpu_temperature(),
+            gpu_temperature: self.gpu_temperature(),
+            fan_rpm: self.fan_rpm(),
+            frequency: self
+                .frequency
+                .as_mut()
+                .map(|s| s.read_frequencies(false))
+                .filter(|f| f.overall > 0.0),
+            power: self.power.as_mut().map(|s| s.read_power(false)),
+        }
+    }
+
+    /// CPU die (or proximity) temperature in °C; `None` when SMC is not readable
+    pub fn cpu_temperature(&mut self) -> Option<f32> {
+        smc::cpu_temperature(self.smc.as_mut()?)
+    }
+
+    /// Average GPU cluster temperature in °C; `None` when SMC or the keys are not readable
+    pub fn gpu_temperature(&mut self) -> Option<f32> {
+        let smc = self.smc.as_mut()?;
+        let readings = match &self.gpu_keys {
+            Some(keys) if keys.is_empty() => return None,
+            Some(keys) => smc::gpu_temperatures(smc, keys, true)?,
+            None => {
+                let known: Vec<String> = smc::GPU_TEMPERATURE_KEYS
+                    .iter()
+                    .map(|k| k.to_string())
+                    .collect();
+                let readings = smc::gpu_temperatures(smc, &known, false)?;
+                self.gpu_keys = Some(readings.iter().map(|(k, _)| k.clone()).collect());
+                readings
+            }
+        };
+        smc::average(&readings)
+    }
+
+    /// Fastest fan in RPM; `None` on fanless Macs or when SMC is not readable
+    pub fn fan_rpm(&mut self) -> Option<f32> {
+        smc::fan_rpm(self.smc.as_mut()?)
+    }
+
+    /// The `limit` busiest processes by CPU, as of the last [`Self::sample`]
+    pub fn top_processes(&self, limit: usize) -> Vec<ProcessUsage> {
+        let mut processes: Vec<ProcessUsage> = self
+            .system
+            .processes()
+            .iter()
+            .map(|(pid, process)| ProcessUsage {
+                pid: pid.as_u32(),
+                name: process.name().to_string_lossy().to_string(),
+                cpu: process.cpu_usage(),
+            })
+            .collect();
+        processes.sort_by(|a, b| b.cpu.total_cmp(&a.cpu));
+        processes.truncate(limit);
+        processes
+    }
+
+    fn refresh(&mut self) {
+        self.system.refresh_cpu_usage();
+        self.system.refresh_memory();
+        self.system.refresh_processes(ProcessesToUpdate::All, true);
+        self.disks.refresh(false);
+    }
+}
+
+fn percent(used: u64, total: u64) -> f32 {
+    if total == 0 {
+        0.0
+    } else {
+        used as f32 / total as f32 * 100.0
+    }
+}
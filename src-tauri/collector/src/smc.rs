@@ -0,0 +1,111 @@
+//! SMC readings: CPU and GPU temperature, fan speed
+//!
+//! Each function takes an open connection ([`Smc::connect`]); connecting is the expensive part,
+//! so keep one around when reading repeatedly.
+
+pub use macsmc::Smc;
+
+/// Known GPU cluster temperature keys (same set exelban/stats reads)
+#[rustfmt::skip]
+pub const GPU_TEMPERATURE_KEYS: &[&str] = &[
+    // M1 family
+    "Tg05", "Tg0D", "Tg0L", "Tg0T",
+    // M2 family
+    "Tg0f", "Tg0j",
+    // M3 family
+    "Tf14", "Tf18", "Tf19", "Tf1A", "Tf24", "Tf28", "Tf29", "Tf2A",
+    // Intel
+    "TG0D", "TG0P", "TCGC",
+];
+
+/// CPU die temperature in °C, or the proximity sensor when the die reads 0; `None` when
+/// neither is readable
+pub fn cpu_temperature(smc: &mut Smc) -> Option<f32> {
+    let temps = smc.cpu_temperature().ok()?;
+    let die: f64 = temps.die.into();
+    let proximity: f64 = temps.proximity.into();
+    [die, proximity]
+        .into_iter()
+        .find(|t| *t > 0.0)
+        .map(|t| t as f32)
+}
+
+/// Readings of the GPU cluster `keys` that look like real temperatures, as `(key, °C)`
+///
+/// Apple Silicon has one key per cluster and the names change with each chip generation, so
+/// the first scan passes [`GPU_TEMPERATURE_KEYS`] and later ones only the keys it found; with
+/// `complete_stops_scan` the walk over all SMC keys stops once every key in `keys` was read.
+/// `None` when SMC cannot list its keys.
+pub fn gpu_temperatures(
+    smc: &mut Smc,
+    keys: &[String],
+    complete_stops_scan: bool,
+) -> Option<Vec<(String, f32)>> {
+    let mut raw = Vec::new();
+    for dbg in smc.all_data().ok()?.flatten() {
+        if !keys.contains(&dbg.key) {
+            continue;
+        }
+        if let Ok(Some(macsmc::DataValue::Float(val))) = dbg.value {
+            raw.push((dbg.key, val));
+        }
+        if complete_stops_scan && raw.len() == keys.len() {
+            break;
+        }
+    }
+    Some(plausible_readings(raw, keys))
+}
+
+/// Readings for `keys` that look like real temperatures (unpopulated keys read 0 or garbage)
+fn plausible_readings(
+    readings: impl IntoIterator<Item = (String, f32)>,
+    keys: &[String],
+) -> Vec<(String, f32)> {
+    readings
+        .into_iter()
+        .filter(|(key, t)| keys.contains(key) && *t > 0.0 && *t < 150.0)
+        .collect()
+}
+
+/// Average of [`gpu_temperatures`] readings; `None` when there are none
+pub fn average(readings: &[(String, f32)]) -> Option<f32> {
+    (!readings.is_empty())
+        .then(|| readings.iter().map(|(_, t)| t).sum::<f32>() / readings.len() as f32)
+}
+
+/// Fastest fan in RPM; `None` on fanless Macs or when SMC is not readable
+pub fn fan_rpm(smc: &mut Smc) -> Option<f32> {
+    smc.fans()
+        .ok()?
+        .flatten()
+        .map(|fan| {
+            let rpm: f64 = fan.actual.into();
+            rpm as f32
+        })
+        .filter(|rpm| rpm.is_finite() && *rpm >= 0.0)
+        .reduce(f32::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn averages_plausible_cluster_readings() {
+        let keys: Vec<String> = GPU_TEMPERATURE_KEYS.iter().map(|k| k.to_string()).collect();
+        let readings = plausible_readings(
+            [
+                ("Tg05".to_string(), 44.0),
+                ("Tg0D".to_string(), 48.0),
+                ("Tg0L".to_string(), 0.0),
+                ("Tg0T".to_string(), 1.0e9),
+                ("Tp01".to_string(), 70.0),
+            ],
+            &keys,
+        );
+        let found: Vec<&str> = readings.iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(found, vec!["Tg05", "Tg0D"]);
+        assert_eq!(average(&readings), Some(46.0));
+        assert_eq!(average(&[]), None);
+    }
+}
@@ -0,0 +1,136 @@
+// About window (see `ui/about_window.rs`): version and build date from `get_about_info`, the
+// changelog from `get_changelog` and an update check (`check_for_update`) whose Download button
+// opens the latest DMG (or the release page when the release has none). Install on Next Launch
+// stages the release through the self-updater (`install_update`).
+const CHANGELOG_RELEASES = 5;
+
+let downloadUrl = null;
+
+function getInvoke() {
+  return window.__TAURI__?.core?.invoke || window.__TAURI_INTERNALS__?.invoke?.bind(window.__TAURI_INTERNALS__) || null;
+}
+
+function setText(id, text) {
+  const el = document.getElementById(id);
+  if (el) el.textContent = text;
+}
+
+function escapeHtml(text) {
+  return text.replace(/[&<>"]/g, (c) => ({ "&": "&amp;", "<": "&lt;", ">": "&gt;", '"': "&quot;" })[c]);
+}
+
+function inline(text) {
+  return escapeHtml(text)
+    .replace(/\*\*(.*?)\*\*/g, "<strong>$1</strong>")
+    .replace(/`([^`]+)`/g, "<code>$1</code>");
+}
+
+// The newest `CHANGELOG_RELEASES` sections of CHANGELOG.md as HTML (headings and bullet lists)
+function renderChangelog(markdown) {
+  const out = [];
+  let releases = 0;
+  let inList = false;
+  const closeList = () => {
+    if (inList) out.push("</ul>");
+    inList = false;
+  };
+  for (const line of markdown.split("\n")) {
+    if (line.startsWith("## ")) {
+      if (++releases > CHANGELOG_RELEASES) break;
+      closeList();
+      out.push(`<h3>${inline(line.slice(3))}</h3>`);
+    } else if (releases === 0) {
+      continue;
+    } else if (line.startsWith("### ")) {
+      closeList();
+      out.push(`<h4>${inline(line.slice(4))}</h4>`);
+    } else if (/^\s*- /.test(line)) {
+      if (!inList) out.push("<ul>");
+      inList = true;
+      out.push(`<li>${inline(line.replace(/^\s*- /, ""))}</li>`);
+    }
+  }
+  closeList();
+  return out.join("\n") || "No changelog available.";
+}
+
+async function openUrl(url) {
+  const invoke = getInvoke();
+  try {
+    await invoke("plugin:shell|open", { path: url });
+  } catch (err) {
+    console.warn("plugin:shell|open failed:", err);
+    window.open(url, "_blank", "noopener,noreferrer");
+  }
+}
+
+async function checkUpdate() {
+  const invoke = getInvoke();
+  const download = document.getElementById("about-download");
+  const again = document.getElementById("about-check");
+  const install = document.getElementById("about-install");
+  setText("about-update-status", "Checking for updates…");
+  download.hidden = true;
+  again.hidden = true;
+  install.hidden = true;
+  try {
+    const [check, status] = await Promise.all([invoke("check_for_update"), invoke("get_update_status")]);
+    if (status.pending_version) {
+      setText("about-update-status", `Version ${status.pending_version} will be installed the next time mac-stats starts.`);
+    } else if (check.update_available) {
+      downloadUrl = check.dmg?.url || check.release_url;
+      setText("about-update-status", `Version ${check.latest_version} is available.`);
+      download.hidden = false;
+      install.hidden = !check.dmg?.sha256 || !status.can_install;
+    } else {
+      setText("about-update-status", "mac-stats is up to date.");
+      again.hidden = false;
+    }
+  } catch (err) {
+    setText("about-update-status", String(err));
+    again.hidden = false;
+  }
+}
+
+async function installUpdate() {
+  const install = document.getElementById("about-install");
+  install.disabled = true;
+  setText("about-update-status", "Downloading and verifying the update…");
+  try {
+    setText("about-update-status", await getInvoke()("install_update"));
+    install.hidden = true;
+    document.getElementById("about-download").hidden = true;
+  } catch (err) {
+    setText("about-update-status", String(err));
+  } finally {
+    install.disabled = false;
+  }
+}
+
+async function startAbout() {
+  const invoke = getInvoke();
+  if (!invoke) {
+    setTimeout(startAbout, 100);
+    return;
+  }
+  document.getElementById("about-download")?.addEventListener("click", () => {
+    if (downloadUrl) openUrl(downloadUrl);
+  });
+  document.getElementById("about-check")?.addEventListener("click", checkUpdate);
+  document.getElementById("about-install")?.addEventListener("click", installUpdate);
+  try {
+    const info = await invoke("get_about_info");
+    setText("about-name", info.name);
+    setText("about-version", `Version ${info.version} · Built ${info.build_date}`);
+  } catch (err) {
+    console.warn("About info unavailable:", err);
+  }
+  try {
+    document.getElementById("about-changelog").innerHTML = renderChangelog(await invoke("get_changelog"));
+  } catch (err) {
+    setText("about-changelog", "No changelog available.");
+  }
+  checkUpdate();
+}
+
+startAbout();
@@ -0,0 +1,13 @@
+// Shared by every window with destructive commands (force quit, config writes, helper actions).
+// They need a single-use token from the backend: `request_confirmation` shows a native dialog
+// first (see `commands/confirmation.rs`) and rejects with "Cancelled" when the user declines, so
+// callers should undo their optimistic UI state on errors.
+async function confirmedInvoke(cmd, args, action, target) {
+  const invoke = window.__TAURI__?.core?.invoke || window.__TAURI_INTERNALS__?.invoke?.bind(window.__TAURI_INTERNALS__);
+  if (!invoke) {
+    throw new Error("Tauri invoke not available");
+  }
+  const challenge = await invoke("request_confirmation", { action, target: String(target) });
+  return invoke(cmd, { ...args, confirmationToken: challenge.token });
+}
+window.confirmedInvoke = confirmedInvoke;
@@ -0,0 +1,106 @@
+// "Meta" tab of the Details section: mac-stats' own footprint from `get_self_stats` (CPU,
+// memory, wakeups, open files, threads and per-span sampling durations). The tab strip and grid
+// are added here so every theme gets them; polling runs only while the tab is showing.
+const META_REFRESH_MS = 2000;
+const META_MAX_TIMINGS = 12;
+
+let metaTimer = null;
+
+function metaInvoke() {
+  return window.__TAURI__?.core?.invoke || window.__TAURI_INTERNALS__?.invoke?.bind(window.__TAURI_INTERNALS__) || null;
+}
+
+function metaBytes(bytes) {
+  if (!bytes) return "--";
+  const mb = bytes / (1024 * 1024);
+  return mb >= 1024 ? `${(mb / 1024).toFixed(2)} GB` : `${mb.toFixed(1)} MB`;
+}
+
+function metaMs(ms) {
+  return ms >= 100 ? `${ms.toFixed(0)} ms` : `${ms.toFixed(1)} ms`;
+}
+
+function metaRow(grid, label, value, title) {
+  const l = document.createElement("div");
+  l.className = "detail-label";
+  l.textContent = label;
+  const v = document.createElement("div");
+  v.className = "detail-value";
+  v.textContent = value;
+  if (title) v.title = title;
+  grid.append(l, v);
+}
+
+async function refreshMeta() {
+  const invoke = metaInvoke();
+  const grid = document.getElementById("meta-content");
+  if (!invoke || !grid || document.hidden) return;
+  try {
+    const s = await invoke("get_self_stats");
+    grid.replaceChildren();
+    metaRow(grid, "App CPU", `${s.cpu_percent.toFixed(1)}%`, "Of one core, since the last refresh");
+    metaRow(grid, "App Memory", metaBytes(s.footprint_bytes), `Resident ${metaBytes(s.rss_bytes)}`);
+    metaRow(grid, "Wakeups", `${s.wakeups_per_sec.toFixed(1)}/s`);
+    metaRow(grid, "Open Files", String(s.open_files));
+    metaRow(grid, "Threads", String(s.threads));
+    s.collectors
+      .filter((c) => c.interval_secs > c.base_interval_secs)
+      .forEach((c) => {
+        metaRow(grid, `Slowed ${c.name}`, `every ${c.interval_secs}s`, `Over the sampling budget (last run ${metaMs(c.last_ms)}); normally every ${c.base_interval_secs}s`);
+      });
+    s.timings.slice(0, META_MAX_TIMINGS).forEach((t) => {
+      metaRow(grid, t.name, `${metaMs(t.avg_ms)} avg`, `last ${metaMs(t.last_ms)} · max ${metaMs(t.max_ms)} · ${t.count}×`);
+    });
+  } catch (err) {
+    console.warn("Self stats unavailable:", err);
+  }
+}
+
+function showDetailsTab(tab) {
+  document.querySelectorAll("[data-details-tab]").forEach((b) => b.classList.toggle("active", b.dataset.detailsTab === tab));
+  const details = document.getElementById("details-content");
+  const meta = document.getElementById("meta-content");
+  if (details) details.style.display = tab === "meta" ? "none" : "";
+  if (meta) meta.style.display = tab === "meta" ? "" : "none";
+  clearInterval(metaTimer);
+  metaTimer = null;
+  if (tab === "meta") {
+    refreshMeta();
+    metaTimer = setInterval(refreshMeta, META_REFRESH_MS);
+  }
+}
+
+function initMetaTab() {
+  const header = document.getElementById("details-header");
+  const details = document.getElementById("details-content");
+  if (!header || !details || document.getElementById("meta-content")) return;
+
+  const tabs = document.createElement("div");
+  tabs.className = "agent-ops-tabs details-tabs";
+  tabs.setAttribute("role", "tablist");
+  [
+    ["system", "System"],
+    ["meta", "Meta"],
+  ].forEach(([id, label]) => {
+    const button = document.createElement("button");
+    button.type = "button";
+    button.className = "agent-ops-tab";
+    button.dataset.detailsTab = id;
+    button.textContent = label;
+    button.addEventListener("click", () => showDetailsTab(id));
+    tabs.append(button);
+  });
+  header.after(tabs);
+
+  const meta = document.createElement("div");
+  meta.className = details.className;
+  meta.id = "meta-content";
+  details.after(meta);
+  showDetailsTab("system");
+}
+
+if (document.readyState === "loading") {
+  document.addEventListener("DOMContentLoaded", initMetaTab);
+} else {
+  initMetaTab();
+}
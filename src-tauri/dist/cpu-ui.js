@@ -196,7 +196,17 @@
     const helpBtn = document.getElementById("settings-help-btn");
     const resetBtn = document.getElementById("settings-reset-defaults-btn");
     const helpSheet = document.getElementById("settings-help-sheet");
-    if (!aiToggle && !compactToggle && !helpBtn && !resetBtn) return;
+    const unitToggles = {
+      fahrenheit: document.getElementById("units-fahrenheit-toggle"),
+      binary: document.getElementById("units-binary-toggle"),
+      bits: document.getElementById("units-bits-toggle"),
+    };
+    const hasUnitToggles = Object.values(unitToggles).some(Boolean);
+    // Pin modes are exclusive: on top, on all Spaces, or neither ("normal")
+    const pinTopToggle = document.getElementById("window-pin-top-toggle");
+    const pinSpacesToggle = document.getElementById("window-pin-spaces-toggle");
+    const hasPinToggles = !!(pinTopToggle || pinSpacesToggle);
+    if (!aiToggle && !compactToggle && !helpBtn && !resetBtn && !hasUnitToggles && !hasPinToggles) return;
 
     (async () => {
       try {
@@ -204,6 +214,17 @@
         if (!invoke) return;
         if (aiToggle) aiToggle.checked = !!(await invoke("get_ai_agent_enabled"));
         if (compactToggle) compactToggle.checked = !!(await invoke("get_menu_bar_compact"));
+        if (hasUnitToggles) {
+          const units = await invoke("get_units");
+          if (unitToggles.fahrenheit) unitToggles.fahrenheit.checked = units.temperature === "F";
+          if (unitToggles.binary) unitToggles.binary.checked = units.size === "GiB";
+          if (unitToggles.bits) unitToggles.bits.checked = units.networkRate === "bits";
+        }
+        if (hasPinToggles) {
+          const mode = await invoke("get_window_pin_mode");
+          if (pinTopToggle) pinTopToggle.checked = mode === "alwaysOnTop";
+          if (pinSpacesToggle) pinSpacesToggle.checked = mode === "allSpaces";
+        }
         applyAiUiVisibility(aiToggle ? aiToggle.checked : true);
       } catch (e) {
         console.warn("product toggles load", e);
@@ -215,11 +236,13 @@
         try {
           const invoke = getInvoke();
           if (!invoke) return;
-          const v = await invoke("set_ai_agent_enabled", { enabled: aiToggle.checked });
+          const v = await window.confirmedInvoke(
+            "set_ai_agent_enabled", { enabled: aiToggle.checked }, "config_write", "aiAgentEnabled");
           applyAiUiVisibility(!!v);
         } catch (e) {
           console.error(e);
-          alert("Could not save aiAgentEnabled: " + e);
+          aiToggle.checked = !aiToggle.checked;
+          if (String(e) !== "Cancelled") alert("Could not save aiAgentEnabled: " + e);
         }
       });
     }
@@ -228,9 +251,45 @@
         try {
           const invoke = getInvoke();
           if (!invoke) return;
-          await invoke("set_menu_bar_compact", { compact: compactToggle.checked });
+          await window.confirmedInvoke(
+            "set_menu_bar_compact", { compact: compactToggle.checked }, "config_write", "menuBarCompact");
+        } catch (e) {
+          console.error(e);
+          compactToggle.checked = !compactToggle.checked;
+        }
+      });
+    }
+    for (const toggle of Object.values(unitToggles)) {
+      if (!toggle) continue;
+      toggle.addEventListener("change", async () => {
+        const units = {
+          temperature: unitToggles.fahrenheit?.checked ? "F" : "C",
+          size: unitToggles.binary?.checked ? "GiB" : "GB",
+          networkRate: unitToggles.bits?.checked ? "bits" : "bytes",
+        };
+        try {
+          await window.confirmedInvoke("set_units", { units }, "config_write", "units");
+        } catch (e) {
+          console.error(e);
+          toggle.checked = !toggle.checked;
+          if (String(e) !== "Cancelled") alert("Could not save units: " + e);
+        }
+      });
+    }
+    for (const [toggle, other, mode] of [
+      [pinTopToggle, pinSpacesToggle, "alwaysOnTop"],
+      [pinSpacesToggle, pinTopToggle, "allSpaces"],
+    ]) {
+      if (!toggle) continue;
+      toggle.addEventListener("change", async () => {
+        if (toggle.checked && other) other.checked = false;
+        try {
+          await window.confirmedInvoke(
+            "set_window_pin_mode", { mode: toggle.checked ? mode : "normal" }, "config_write", "cpuWindowPinMode");
         } catch (e) {
           console.error(e);
+          toggle.checked = !toggle.checked;
+          if (String(e) !== "Cancelled") alert("Could not change pin mode: " + e);
         }
       });
     }
@@ -254,17 +313,17 @@
     }
     if (resetBtn) {
       resetBtn.addEventListener("click", async () => {
-        if (!confirm("Reset config toggles to monitor defaults? (Does not delete Keychain secrets.)")) return;
         try {
           const invoke = getInvoke();
           if (!invoke) return;
-          const msg = await invoke("reset_config_to_monitor_defaults");
+          const msg = await window.confirmedInvoke(
+            "reset_config_to_monitor_defaults", {}, "config_reset", "config.json");
           if (aiToggle) aiToggle.checked = false;
           if (compactToggle) compactToggle.checked = true;
           applyAiUiVisibility(false);
           alert(msg || "Defaults applied. Restart recommended.");
         } catch (e) {
-          alert(String(e));
+          if (String(e) !== "Cancelled") alert(String(e));
         }
       });
     }
@@ -338,7 +397,8 @@
       try {
         const invoke = getInvoke();
         if (invoke) {
-          await invoke("set_window_decorations", { decorations: enabled });
+          await window.confirmedInvoke(
+            "set_window_decorations", { decorations: enabled }, "config_write", "windowDecorations");
           console.log(`Window decorations preference saved: ${enabled}`);
         }
       } catch (err) {
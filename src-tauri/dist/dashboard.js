@@ -0,0 +1,112 @@
+// Dashboard window (see `ui/dashboard_window.rs`): one tile per machine from `get_dashboard`
+// (this Mac first, then `remoteMachines`). Remote tiles update as their summary streams deliver
+// (`dashboard-updated`); the timer refreshes this Mac and keeps the streams subscribed.
+// Temperature follows the `units` preference.
+const DASHBOARD_REFRESH_MS = 5000;
+const HIGH_USAGE_PERCENT = 80;
+
+let dashboardUnits = { temperature: "C" };
+
+function getInvoke() {
+  return window.__TAURI__?.core?.invoke || window.__TAURI_INTERNALS__?.invoke?.bind(window.__TAURI_INTERNALS__) || null;
+}
+
+function el(tag, className, text) {
+  const node = document.createElement(tag);
+  if (className) node.className = className;
+  if (text != null) node.textContent = text;
+  return node;
+}
+
+function formatTemperature(celsius) {
+  if (celsius == null) return null;
+  return dashboardUnits.temperature === "F"
+    ? `${Math.round((celsius * 9) / 5 + 32)}°F`
+    : `${Math.round(celsius)}°C`;
+}
+
+function formatUptime(secs) {
+  const d = Math.floor(secs / 86400);
+  const h = Math.floor((secs % 86400) / 3600);
+  const m = Math.floor((secs % 3600) / 60);
+  return d > 0 ? `${d}d ${h}h` : h > 0 ? `${h}h ${m}m` : `${m}m`;
+}
+
+function meter(label, percent) {
+  const row = el("div", "meter");
+  row.append(el("span", "label", label));
+  const track = el("div", "track");
+  const fill = el("div", percent >= HIGH_USAGE_PERCENT ? "fill high" : "fill");
+  fill.style.width = `${Math.max(0, Math.min(100, percent))}%`;
+  track.append(fill);
+  row.append(track, el("span", "value", `${percent.toFixed(0)}%`));
+  return row;
+}
+
+function renderMachine(machine) {
+  const s = machine.summary;
+  const alerts = s ? [...s.alerting.map((m) => m || "rule"), ...s.monitors_down.map((id) => `${id} down`)] : [];
+  const tile = el("section", "machine");
+  if (!machine.online) tile.classList.add("offline");
+  if (alerts.length) tile.classList.add("alerting");
+
+  const head = el("div", "machine-head");
+  head.append(el("span", "dot"), el("span", "machine-name", machine.name));
+  tile.append(head);
+
+  const sub = machine.online
+    ? [s.host, machine.latency_ms != null ? `${machine.latency_ms} ms` : null, `v${s.version}`]
+    : [machine.url, machine.error || "Offline"];
+  tile.append(el("div", "machine-sub", sub.filter(Boolean).join(" · ")));
+  if (!s) return tile;
+
+  tile.append(meter("CPU", s.cpu), meter("GPU", s.gpu), meter("RAM", s.ram), meter("Disk", s.disk));
+  const facts = [
+    formatTemperature(s.temperature),
+    s.battery != null ? `Battery ${Math.round(s.battery)}%` : null,
+    `Up ${formatUptime(s.uptime_secs)}`,
+  ];
+  tile.append(el("div", "facts", facts.filter(Boolean).join(" · ")));
+  if (alerts.length) {
+    tile.append(el("div", "alerts", `Alert: ${alerts.join(", ")}`));
+  }
+  return tile;
+}
+
+async function refreshDashboard() {
+  const invoke = getInvoke();
+  if (!invoke) return;
+  try {
+    const machines = await invoke("get_dashboard");
+    document.getElementById("machines").replaceChildren(...machines.map(renderMachine));
+    document.getElementById("dashboard-hint").hidden = machines.length > 1;
+  } catch (err) {
+    console.warn("Dashboard unavailable:", err);
+  }
+}
+
+async function startDashboard() {
+  const invoke = getInvoke();
+  if (!invoke) {
+    setTimeout(startDashboard, 100);
+    return;
+  }
+  try {
+    dashboardUnits = await invoke("get_units");
+    await window.__TAURI__?.event?.listen?.("units-changed", (event) => {
+      dashboardUnits = event.payload || dashboardUnits;
+      refreshDashboard();
+    });
+  } catch (err) {
+    console.warn("Unit preferences unavailable:", err);
+  }
+  await window.__TAURI__?.event?.listen?.("dashboard-updated", () => {
+    if (!document.hidden) refreshDashboard();
+  });
+  refreshDashboard();
+  setInterval(() => {
+    if (!document.hidden) refreshDashboard();
+  }, DASHBOARD_REFRESH_MS);
+}
+
+startDashboard();
@@ -0,0 +1,683 @@
+// Shared History Chart Visualization with Backend Integration
+// Shows continuous line graphs for Temperature, Usage, and Frequency
+// Fetches history from backend with adaptive downsampling
+// Works with all themes by reading colors from CSS variables
+
+(function() {
+  'use strict';
+
+  function tauriInvoke(cmd, payload) {
+    if (window.__TAURI__?.core?.invoke) {
+      return window.__TAURI__.core.invoke(cmd, payload);
+    }
+    const i = window.__TAURI_INTERNALS__;
+    if (i && typeof i.invoke === 'function') {
+      return i.invoke(cmd, payload);
+    }
+    throw new Error('Tauri invoke not available');
+  }
+
+  // Chart configuration
+  const HISTORY_POINTS = 60; // Number of points in history graph
+  // Chart-specific refresh: temperature redraw every 3s (changes slowly); usage/frequency every cycle
+  const TEMPERATURE_REDRAW_INTERVAL_MS = 3000;
+  let lastTemperatureDrawMs = 0;
+
+  // Time range options (in seconds)
+  const TIME_RANGES = {
+    '5m': 300,
+    '1h': 3600,
+    '6h': 21600,
+    '24h': 86400,
+    '7d': 604800
+  };
+
+  let currentTimeRange = '5m'; // Default to last 5 minutes
+  // When set, the usage chart overlays the same window this many seconds earlier (dashed)
+  let comparisonOffsetSeconds = null;
+
+  // Get colors from CSS variables or computed styles
+  function getColors() {
+    // Try to get a sample element to read CSS variables from
+    const sampleElement = document.body || document.documentElement;
+    const computedStyle = window.getComputedStyle(sampleElement);
+    
+    // Try to get theme color from CSS variables, fallback to computed text color
+    let lineColor = '#8bb4e8'; // Default fallback
+    try {
+      // Try common CSS variable names
+      lineColor = computedStyle.getPropertyValue('--ring-active')?.trim() ||
+                  computedStyle.getPropertyValue('--text')?.trim() ||
+                  computedStyle.getPropertyValue('--accent')?.trim() ||
+                  computedStyle.color || lineColor;
+      
+      // Remove '#' if present and add it back, handle rgb/rgba
+      if (lineColor.startsWith('rgb')) {
+        // Keep as-is for rgba
+      } else if (!lineColor.startsWith('#')) {
+        lineColor = '#' + lineColor.replace('#', '');
+      }
+    } catch (e) {
+      console.warn('[history] Could not read CSS color, using default');
+    }
+    
+    // Convert hex to rgba for fill
+    function hexToRgba(hex, alpha) {
+      if (hex.startsWith('rgb')) return hex.replace(')', `, ${alpha})`).replace('rgb', 'rgba');
+      const r = parseInt(hex.slice(1, 3), 16);
+      const g = parseInt(hex.slice(3, 5), 16);
+      const b = parseInt(hex.slice(5, 7), 16);
+      return `rgba(${r}, ${g}, ${b}, ${alpha})`;
+    }
+    
+    const fillColor = hexToRgba(lineColor, 0.1);
+    
+    return {
+      temperature: {
+        line: lineColor,
+        fill: fillColor,
+        text: lineColor
+      },
+      usage: {
+        line: lineColor,
+        fill: fillColor,
+        text: lineColor
+      },
+      frequency: {
+        line: lineColor,
+        fill: fillColor,
+        text: lineColor
+      }
+    };
+  }
+
+  // Color schemes (will be initialized from CSS)
+  let COLORS = getColors();
+
+  // Data buffers for each metric
+  const dataBuffers = {
+    temperature: {
+      points: [],
+      timestamps: [],
+      max: 100,
+      min: 0
+    },
+    usage: {
+      points: [],
+      timestamps: [],
+      max: 100,
+      min: 0
+    },
+    frequency: {
+      points: [],
+      timestamps: [],
+      max: 4.0,
+      min: 0
+    }
+  };
+
+  // Canvas elements - get immediately when script loads (like poster-charts.js)
+  const canvases = {
+    temperature: document.getElementById('temperature-history-chart'),
+    usage: document.getElementById('usage-history-chart'),
+    frequency: document.getElementById('frequency-history-chart')
+  };
+
+  // Tooltip element
+  let tooltipElement = null;
+
+  // Canvas contexts - initialize immediately (like poster-charts.js)
+  const contexts = {};
+  
+  // Initialize canvas contexts immediately (synchronously, like poster-charts.js)
+  Object.keys(canvases).forEach(metric => {
+    if (canvases[metric]) {
+      const dpr = window.devicePixelRatio || 1;
+      const rect = canvases[metric].getBoundingClientRect();
+      // Use rect size or fallback to offsetWidth/Height or defaults
+      let width = rect.width > 0 ? rect.width : canvases[metric].offsetWidth || 200;
+      let height = rect.height > 0 ? rect.height : canvases[metric].offsetHeight || 40;
+      
+      // If still no size, try parent container
+      if (width <= 0 || height <= 0) {
+        const container = canvases[metric].parentElement;
+        if (container) {
+          const containerRect = container.getBoundingClientRect();
+          width = containerRect.width > 0 ? containerRect.width : 200;
+          height = containerRect.height > 0 ? containerRect.height : 40;
+        } else {
+          width = 200;
+          height = 40;
+        }
+      }
+      
+      // Set physical pixel size (for high DPI) - this clears canvas and invalidates any existing context
+      canvases[metric].width = width * dpr;
+      canvases[metric].height = height * dpr;
+      
+      // Get context AFTER setting width/height (like poster-charts.js line 82)
+      const ctx = canvases[metric].getContext('2d');
+      if (ctx) {
+        ctx.scale(dpr, dpr);
+        contexts[metric] = ctx;
+        // Set display size (CSS pixels)
+        canvases[metric].style.width = width + 'px';
+        canvases[metric].style.height = height + 'px';
+        console.log(`[history] ${metric} canvas initialized synchronously: ${width}x${height} (${canvases[metric].width}x${canvases[metric].height} @ ${dpr}x)`);
+      }
+    }
+  });
+
+  // Create tooltip element
+  function createTooltip() {
+    if (!tooltipElement) {
+      tooltipElement = document.createElement('div');
+      tooltipElement.className = 'history-tooltip';
+      tooltipElement.style.position = 'fixed';
+      tooltipElement.style.backgroundColor = 'rgba(0, 0, 0, 0.9)';
+      tooltipElement.style.color = COLORS.temperature.text;
+      tooltipElement.style.padding = '8px 12px';
+      tooltipElement.style.borderRadius = '4px';
+      tooltipElement.style.fontSize = '12px';
+      tooltipElement.style.fontFamily = 'monospace';
+      tooltipElement.style.pointerEvents = 'none';
+      tooltipElement.style.zIndex = '10000';
+      tooltipElement.style.display = 'none';
+      tooltipElement.style.border = `1px solid ${COLORS.temperature.text}`;
+      tooltipElement.style.whiteSpace = 'nowrap';
+      document.body.appendChild(tooltipElement);
+    }
+    return tooltipElement;
+  }
+
+  // Format timestamp to readable date/time
+  function formatTimestamp(timestamp) {
+    const date = new Date(timestamp * 1000);
+    const hours = String(date.getHours()).padStart(2, '0');
+    const minutes = String(date.getMinutes()).padStart(2, '0');
+    const seconds = String(date.getSeconds()).padStart(2, '0');
+    const month = String(date.getMonth() + 1).padStart(2, '0');
+    const day = String(date.getDate()).padStart(2, '0');
+    return `${month}/${day} ${hours}:${minutes}:${seconds}`;
+  }
+
+  // Show tooltip with value and timestamp
+  function showTooltip(metric, x, y, value, timestamp) {
+    const tooltip = createTooltip();
+    const formattedTime = formatTimestamp(timestamp);
+    const unit = metric === 'temperature' ? '°C' : (metric === 'frequency' ? 'GHz' : '%');
+
+    tooltip.textContent = `${formattedTime}\n${value.toFixed(1)}${unit}`;
+    tooltip.style.left = (x + 10) + 'px';
+    tooltip.style.top = (y - 30) + 'px';
+    tooltip.style.display = 'block';
+  }
+
+  // Hide tooltip
+  function hideTooltip() {
+    if (tooltipElement) {
+      tooltipElement.style.display = 'none';
+    }
+  }
+
+  // Draw line chart
+  function drawLineChart(metric) {
+    const canvas = canvases[metric];
+    const ctx = contexts[metric];
+    if (!canvas || !ctx) {
+      console.warn(`[history] Canvas or context not available for ${metric}`, {
+        canvas: !!canvas,
+        ctx: !!ctx,
+        canvasId: canvas?.id,
+        allCanvases: Object.keys(canvases),
+        allContexts: Object.keys(contexts)
+      });
+      return;
+    }
+
+    const buffer = dataBuffers[metric];
+    const colors = COLORS[metric];
+    
+    // Get logical size (accounting for device pixel ratio scaling)
+    const dpr = window.devicePixelRatio || 1;
+    const width = canvas.width / dpr;
+    const height = canvas.height / dpr;
+    
+    // Ensure we have valid dimensions
+    if (width <= 0 || height <= 0) {
+      console.warn(`[history] Invalid canvas size for ${metric}: ${width}x${height}`, {
+        canvasWidth: canvas.width,
+        canvasHeight: canvas.height,
+        dpr: dpr,
+        styleWidth: canvas.style.width,
+        styleHeight: canvas.style.height,
+        offsetWidth: canvas.offsetWidth,
+        offsetHeight: canvas.offsetHeight,
+        rect: canvas.getBoundingClientRect()
+      });
+      return;
+    }
+    
+    const maxValue = buffer.max || 1;
+    const minValue = buffer.min || 0;
+    const range = maxValue - minValue || 1;
+
+    // Clear canvas
+    ctx.clearRect(0, 0, width, height);
+
+    if (buffer.points.length < 2) {
+      console.log(`[history] Not enough data points for ${metric}: ${buffer.points.length} (need 2+)`);
+      return;
+    }
+    
+    console.log(`[history] Drawing ${metric} chart: ${buffer.points.length} points, size ${width}x${height}, range [${minValue}, ${maxValue}]`);
+
+    // Calculate points
+    const points = buffer.points.map((value, index) => {
+      const x = (index / (buffer.points.length - 1)) * width;
+      const y = height - ((value - minValue) / range) * height;
+      return { x, y, value, timestamp: buffer.timestamps[index] };
+    });
+
+    // Contiguous runs of points; a gap (sleep, app not running) starts a new run so the
+    // chart breaks there instead of drawing a line across the missing hours
+    const breaks = buffer.breaks || [];
+    const segments = [];
+    points.forEach((point, i) => {
+      if (i === 0 || breaks[i]) segments.push([]);
+      segments[segments.length - 1].push(i);
+    });
+
+    // Draw min/max band for downsampled ranges (short spikes stay visible)
+    const toY = value => height - ((value - minValue) / range) * height;
+    if (buffer.highs && buffer.lows && buffer.highs.some((h, i) => h !== buffer.points[i])) {
+      ctx.beginPath();
+      segments.forEach(segment => {
+        segment.forEach((i, n) => {
+          if (n === 0) ctx.moveTo(points[i].x, toY(buffer.highs[i]));
+          else ctx.lineTo(points[i].x, toY(buffer.highs[i]));
+        });
+        for (let n = segment.length - 1; n >= 0; n--) {
+          ctx.lineTo(points[segment[n]].x, toY(buffer.lows[segment[n]]));
+        }
+        ctx.closePath();
+      });
+      ctx.fillStyle = colors.fill;
+      ctx.fill();
+    }
+
+    // Draw filled area
+    ctx.beginPath();
+    segments.forEach(segment => {
+      ctx.moveTo(points[segment[0]].x, height);
+      segment.forEach(i => ctx.lineTo(points[i].x, points[i].y));
+      ctx.lineTo(points[segment[segment.length - 1]].x, height);
+      ctx.closePath();
+    });
+    ctx.fillStyle = colors.fill;
+    ctx.fill();
+
+    // Draw line
+    ctx.beginPath();
+    segments.forEach(segment => {
+      ctx.moveTo(points[segment[0]].x, points[segment[0]].y);
+      segment.forEach(i => ctx.lineTo(points[i].x, points[i].y));
+    });
+    ctx.strokeStyle = colors.line;
+    ctx.lineWidth = 1.5;
+    ctx.lineCap = 'round';
+    ctx.lineJoin = 'round';
+    ctx.stroke();
+
+    // Comparison overlay: the same window earlier (see setComparison), dashed
+    if (buffer.comparison && buffer.comparison.length > 1) {
+      ctx.save();
+      ctx.beginPath();
+      buffer.comparison.forEach((p, i) => {
+        const x = p.fraction * width;
+        const y = toY(p.value);
+        if (i === 0) ctx.moveTo(x, y); else ctx.lineTo(x, y);
+      });
+      ctx.setLineDash([3, 3]);
+      ctx.globalAlpha = 0.6;
+      ctx.strokeStyle = colors.line;
+      ctx.lineWidth = 1;
+      ctx.stroke();
+      ctx.restore();
+    }
+
+    // Store points for hover detection
+    canvas.pointsData = points;
+  }
+
+  // Add hover handler for tooltips
+  function addCanvasHoverHandler(metric) {
+    const canvas = canvases[metric];
+    if (!canvas) return;
+
+    canvas.addEventListener('mousemove', (e) => {
+      const rect = canvas.getBoundingClientRect();
+      const x = e.clientX - rect.left;
+      const y = e.clientY - rect.top;
+
+      if (canvas.pointsData && canvas.pointsData.length > 0) {
+        // Find closest point to mouse
+        let closestPoint = null;
+        let closestDistance = 10; // 10px threshold
+
+        canvas.pointsData.forEach(point => {
+          const distance = Math.sqrt(
+            Math.pow(x - point.x, 2) + Math.pow(y - point.y, 2)
+          );
+          if (distance < closestDistance) {
+            closestDistance = distance;
+            closestPoint = point;
+          }
+        });
+
+        if (closestPoint) {
+          showTooltip(metric, e.clientX, e.clientY, closestPoint.value, closestPoint.timestamp);
+        } else {
+          hideTooltip();
+        }
+      }
+    });
+
+    canvas.addEventListener('mouseleave', hideTooltip);
+  }
+
+  // Metrics the charts draw (history also holds GPU, RAM, disk, power, battery, network and disk I/O)
+  const CHART_METRICS = ['temperature', 'cpu', 'frequency'];
+
+  // Fetch history from backend
+  async function fetchHistoryFromBackend(timeRangeSeconds, maxPoints) {
+    try {
+      const result = await tauriInvoke('get_metrics_history', {
+        time_range_seconds: timeRangeSeconds,
+        max_display_points: maxPoints,
+        metrics: CHART_METRICS
+      });
+
+      return result;
+    } catch (error) {
+      console.error('[history] Failed to fetch metrics history:', error);
+      return null;
+    }
+  }
+
+  // Update charts from backend data
+  async function updateChartsFromBackend() {
+    const timeRangeSeconds = TIME_RANGES[currentTimeRange] || 300;
+    console.log(`[history] updateChartsFromBackend() called, timeRange=${currentTimeRange} (${timeRangeSeconds}s)`);
+    
+    const result = await fetchHistoryFromBackend(timeRangeSeconds, HISTORY_POINTS);
+
+    if (!result || !result.points) {
+      // History data not available yet (normal on startup) - silent return
+      console.log(`[history] No history data available yet (result=${!!result}, points=${!!result?.points})`);
+      return;
+    }
+    
+    if (result.points.length === 0) {
+      // No data points yet
+      console.log(`[history] History data empty (0 points)`);
+      return;
+    }
+    
+    console.log(`[history] Received ${result.points.length} history points`, {
+      oldest: result.oldest_available_timestamp,
+      newest: result.newest_available_timestamp,
+      firstPoint: result.points[0],
+      lastPoint: result.points[result.points.length - 1]
+    });
+
+    // Extract data by metric (downsampled points carry bands: { metric: { min, max } })
+    const withBand = (p, key) => ({
+      value: p[key],
+      timestamp: p.timestamp,
+      low: p.bands ? p.bands[key].min : p[key],
+      high: p.bands ? p.bands[key].max : p[key]
+    });
+    const temperatureData = result.points.map(p => withBand(p, 'temperature'));
+    const usageData = result.points.map(p => withBand(p, 'cpu')); // CPU usage
+    const frequencyData = result.points.map(p => withBand(p, 'frequency'));
+
+    // A point starts a new line segment when a gap (sleep, app not running) lies before it
+    const gaps = result.gaps || [];
+    const breaks = result.points.map((p, i) => i > 0 &&
+      gaps.some(g => g.end > result.points[i - 1].timestamp && g.start < p.timestamp));
+
+    // Update buffers
+    dataBuffers.temperature.points = temperatureData.map(d => d.value);
+    dataBuffers.temperature.timestamps = temperatureData.map(d => d.timestamp);
+    dataBuffers.temperature.lows = temperatureData.map(d => d.low);
+    dataBuffers.temperature.highs = temperatureData.map(d => d.high);
+    dataBuffers.temperature.breaks = breaks;
+    dataBuffers.temperature.max = Math.max(100, ...temperatureData.map(d => d.high || 0));
+    dataBuffers.temperature.min = Math.min(0, ...temperatureData.map(d => d.value || 0));
+
+    dataBuffers.usage.points = usageData.map(d => d.value);
+    dataBuffers.usage.timestamps = usageData.map(d => d.timestamp);
+    dataBuffers.usage.lows = usageData.map(d => d.low);
+    dataBuffers.usage.highs = usageData.map(d => d.high);
+    dataBuffers.usage.breaks = breaks;
+    dataBuffers.usage.max = Math.max(100, ...usageData.map(d => d.high || 0));
+    dataBuffers.usage.min = Math.min(0, ...usageData.map(d => d.value || 0));
+
+    dataBuffers.frequency.points = frequencyData.map(d => d.value);
+    dataBuffers.frequency.timestamps = frequencyData.map(d => d.timestamp);
+    dataBuffers.frequency.lows = frequencyData.map(d => d.low);
+    dataBuffers.frequency.highs = frequencyData.map(d => d.high);
+    dataBuffers.frequency.breaks = breaks;
+    dataBuffers.frequency.max = Math.max(4.0, ...frequencyData.map(d => d.high || 0));
+    dataBuffers.frequency.min = Math.min(0, ...frequencyData.map(d => d.value || 0));
+
+    // Redraw charts (temperature only every 3s; usage and frequency every cycle)
+    const nowMs = Date.now();
+    const shouldRedrawTemperature = lastTemperatureDrawMs === 0 || (nowMs - lastTemperatureDrawMs >= TEMPERATURE_REDRAW_INTERVAL_MS);
+    if (shouldRedrawTemperature) {
+      lastTemperatureDrawMs = nowMs;
+    }
+    console.log(`[history] Redrawing charts with data:`, {
+      temperaturePoints: dataBuffers.temperature.points.length,
+      usagePoints: dataBuffers.usage.points.length,
+      frequencyPoints: dataBuffers.frequency.points.length
+    });
+    
+    if (shouldRedrawTemperature) {
+      updateChartStats(timeRangeSeconds);
+      await updateComparison(timeRangeSeconds);
+    }
+
+    Object.keys(canvases).forEach(metric => {
+      if (metric === 'temperature' && !shouldRedrawTemperature) return;
+      if (canvases[metric]) {
+        drawLineChart(metric);
+      } else {
+        console.warn(`[history] Cannot draw ${metric}: canvas not found`);
+      }
+    });
+  }
+
+  // Percentile summary as chart tooltips (averages hide the spikes)
+  async function updateChartStats(timeRangeSeconds) {
+    let stats;
+    try {
+      stats = await tauriInvoke('get_history_stats', { timeRangeSeconds });
+    } catch (error) {
+      console.warn('[history] Failed to fetch history stats:', error);
+      return;
+    }
+    const describe = (s, unit, digits) => {
+      if (!s) return '';
+      const f = v => v.toFixed(digits) + unit;
+      return `p50 ${f(s.p50)} · p95 ${f(s.p95)} · p99 ${f(s.p99)} · max ${f(s.max)}`;
+    };
+    if (canvases.usage) canvases.usage.title = describe(stats.cpu, '%', 0);
+    if (canvases.temperature) canvases.temperature.title = describe(stats.temperature, '°C', 0);
+  }
+
+  // CPU usage of the same window `comparisonOffsetSeconds` earlier, aligned by offset
+  async function updateComparison(timeRangeSeconds) {
+    if (!comparisonOffsetSeconds) {
+      dataBuffers.usage.comparison = null;
+      return;
+    }
+    const end = Math.floor(Date.now() / 1000);
+    const start = end - timeRangeSeconds;
+    let result;
+    try {
+      result = await tauriInvoke('compare_history', {
+        rangeA: { start, end },
+        rangeB: { start: start - comparisonOffsetSeconds, end: end - comparisonOffsetSeconds },
+        maxPoints: HISTORY_POINTS
+      });
+    } catch (error) {
+      console.warn('[history] Failed to fetch history comparison:', error);
+      dataBuffers.usage.comparison = null;
+      return;
+    }
+    dataBuffers.usage.comparison = result.points
+      .filter(p => p.b)
+      .map(p => ({ fraction: p.offset_seconds / timeRangeSeconds, value: p.b.cpu }));
+  }
+
+  // Set time range and update charts
+  async function setTimeRange(timeRange) {
+    if (TIME_RANGES[timeRange]) {
+      currentTimeRange = timeRange;
+      await updateChartsFromBackend();
+    }
+  }
+
+  // Re-initialize canvas sizes (for window resize)
+  function reinitializeCanvasSizes() {
+    console.log('[history] reinitializeCanvasSizes() called');
+    Object.keys(canvases).forEach(metric => {
+      if (canvases[metric]) {
+        const dpr = window.devicePixelRatio || 1;
+        const rect = canvases[metric].getBoundingClientRect();
+        let width = rect.width > 0 ? rect.width : canvases[metric].offsetWidth || 200;
+        let height = rect.height > 0 ? rect.height : canvases[metric].offsetHeight || 40;
+        
+        if (width <= 0 || height <= 0) {
+          const container = canvases[metric].parentElement;
+          if (container) {
+            const containerRect = container.getBoundingClientRect();
+            width = containerRect.width > 0 ? containerRect.width : 200;
+            height = containerRect.height > 0 ? containerRect.height : 40;
+          } else {
+            width = 200;
+            height = 40;
+          }
+        }
+        
+        // Set physical pixel size (this invalidates the context, so we need to get a new one)
+        canvases[metric].width = width * dpr;
+        canvases[metric].height = height * dpr;
+        
+        // Get new context after setting size (like poster-charts.js)
+        const ctx = canvases[metric].getContext('2d');
+        if (ctx) {
+          ctx.scale(dpr, dpr);
+          contexts[metric] = ctx;
+          canvases[metric].style.width = width + 'px';
+          canvases[metric].style.height = height + 'px';
+        }
+      }
+    });
+  }
+
+  // Public API
+  window.historyCharts = {
+    // Legacy API for backward compatibility
+    updateTemperature: (value) => {},
+    updateUsage: (value) => {},
+    updateFrequency: (value) => {},
+
+    // New backend-integrated API
+    fetchAndUpdateHistory: updateChartsFromBackend,
+    setTimeRange: setTimeRange,
+    // Overlay the same window earlier, e.g. 86400 (yesterday) or 604800 (last week); null to clear
+    setComparison: (offsetSeconds) => {
+      comparisonOffsetSeconds = offsetSeconds || null;
+      lastTemperatureDrawMs = 0;
+      return updateChartsFromBackend();
+    },
+
+    // Initialize charts (call on page load)
+    init: () => {
+      console.log('[history] init() called');
+      console.log('[history] Canvas elements found:', {
+        temperature: !!canvases.temperature,
+        usage: !!canvases.usage,
+        frequency: !!canvases.frequency,
+        temperatureId: canvases.temperature?.id,
+        usageId: canvases.usage?.id,
+        frequencyId: canvases.frequency?.id,
+        temperatureContext: !!contexts.temperature,
+        usageContext: !!contexts.usage,
+        frequencyContext: !!contexts.frequency
+      });
+      
+      // Refresh colors from CSS (in case theme changed)
+      COLORS = getColors();
+      
+      // Create tooltip
+      createTooltip();
+
+      // Add hover handlers to all canvases
+      Object.keys(canvases).forEach(metric => {
+        if (canvases[metric]) {
+          addCanvasHoverHandler(metric);
+        }
+      });
+
+      // Fetch initial history
+      console.log('[history] Fetching initial history data');
+      updateChartsFromBackend();
+
+      // Refresh every 2 seconds
+      setInterval(updateChartsFromBackend, 2000);
+      
+      // Drop cached chart data when the backend reports memory pressure (refetched next cycle)
+      if (window.__TAURI__?.event?.listen) {
+        window.__TAURI__.event.listen('memory-pressure', (event) => {
+          console.log('[history] Memory pressure, dropping chart buffers:', event.payload?.level);
+          Object.values(dataBuffers).forEach(buffer => {
+            buffer.points = [];
+            buffer.timestamps = [];
+            buffer.lows = [];
+            buffer.highs = [];
+            buffer.breaks = [];
+            buffer.comparison = null;
+          });
+        });
+      }
+
+      // Handle window resize
+      let resizeTimeout;
+      window.addEventListener('resize', () => {
+        clearTimeout(resizeTimeout);
+        resizeTimeout = setTimeout(() => {
+          reinitializeCanvasSizes();
+          // Redraw charts after resize
+          Object.keys(canvases).forEach(metric => {
+            if (canvases[metric] && contexts[metric]) {
+              drawLineChart(metric);
+            }
+          });
+        }, 100);
+      });
+    }
+  };
+
+  // Initialize on load
+  if (document.readyState === 'loading') {
+    document.addEventListener('DOMContentLoaded', () => {
+      window.historyCharts.init();
+    });
+  } else {
+    window.historyCharts.init();
+  }
+})();
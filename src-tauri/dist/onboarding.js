@@ -0,0 +1,109 @@
+// Onboarding window (see `ui/onboarding_window.rs`), shown on the first launch: a test
+// notification (`request_notification_permission`), launch at login (`set_launch_at_login`), menu
+// bar metrics and update interval, and the capability probe explained (`get_onboarding_info`).
+// Done saves through `complete_onboarding` (via `confirmedInvoke`, confirm.js); Skip calls
+// `dismiss_onboarding`.
+
+function getInvoke() {
+  return window.__TAURI__?.core?.invoke || window.__TAURI_INTERNALS__?.invoke?.bind(window.__TAURI_INTERNALS__) || null;
+}
+
+function setText(id, text) {
+  const el = document.getElementById(id);
+  if (el) el.textContent = text;
+}
+
+function escapeHtml(text) {
+  return String(text).replace(/[&<>"]/g, (c) => ({ "&": "&amp;", "<": "&lt;", ">": "&gt;", '"': "&quot;" })[c]);
+}
+
+function renderMetrics(info) {
+  document.getElementById("ob-metrics").innerHTML = info.metrics
+    .map((m) => {
+      const checked = info.selected.includes(m.id) ? " checked" : "";
+      const hint = m.available ? "" : " (not on this Mac)";
+      return `<label class="${m.available ? "" : "unavailable"}"><input type="checkbox" value="${m.id}"${checked} /> ${escapeHtml(m.label)}${hint}</label>`;
+    })
+    .join("");
+  document.getElementById("ob-interval").innerHTML = info.intervals
+    .map((secs) => {
+      const selected = secs === info.update_interval_secs ? " selected" : "";
+      return `<option value="${secs}"${selected}>${secs === 1 ? "second" : `${secs} seconds`}</option>`;
+    })
+    .join("");
+}
+
+function renderCapabilities(capabilities) {
+  document.getElementById("ob-capabilities").innerHTML = capabilities
+    .map(
+      (c) =>
+        `<li><span class="mark">${c.available ? "✓" : "–"}</span><strong>${escapeHtml(c.name)}</strong> <span class="muted">${escapeHtml(c.detail)}</span></li>`
+    )
+    .join("");
+}
+
+async function sendTestNotification() {
+  try {
+    setText("ob-notify-status", await getInvoke()("request_notification_permission"));
+  } catch (err) {
+    setText("ob-notify-status", String(err));
+  }
+}
+
+async function setupLaunchAtLogin() {
+  const invoke = getInvoke();
+  const toggle = document.getElementById("ob-login");
+  try {
+    toggle.checked = (await invoke("get_launch_at_login")).enabled;
+  } catch (err) {
+    setText("ob-login-status", String(err));
+  }
+  toggle.addEventListener("change", async () => {
+    const enabled = toggle.checked;
+    try {
+      const status = await confirmedInvoke(
+        "set_launch_at_login",
+        { enabled },
+        "launch_at_login",
+        enabled ? "enable" : "disable"
+      );
+      toggle.checked = status.enabled;
+      setText("ob-login-status", status.requires_approval ? "Allow it in System Settings > Login Items" : "");
+    } catch (err) {
+      toggle.checked = !enabled;
+      setText("ob-login-status", String(err));
+    }
+  });
+}
+
+async function finish() {
+  const metrics = [...document.querySelectorAll("#ob-metrics input:checked")].map((input) => input.value);
+  const updateIntervalSecs = Number(document.getElementById("ob-interval").value);
+  try {
+    await confirmedInvoke("complete_onboarding", { metrics, updateIntervalSecs }, "config_write", "onboarding");
+  } catch (err) {
+    setText("ob-error", String(err));
+  }
+}
+
+async function startOnboarding() {
+  const invoke = getInvoke();
+  if (!invoke) {
+    setTimeout(startOnboarding, 100);
+    return;
+  }
+  document.getElementById("ob-notify")?.addEventListener("click", sendTestNotification);
+  document.getElementById("ob-done")?.addEventListener("click", finish);
+  document.getElementById("ob-skip")?.addEventListener("click", () => invoke("dismiss_onboarding"));
+  setupLaunchAtLogin();
+  try {
+    const info = await invoke("get_onboarding_info");
+    renderMetrics(info);
+    renderCapabilities(info.capabilities);
+  } catch (err) {
+    setText("ob-metrics", String(err));
+    document.getElementById("ob-capabilities").innerHTML = "";
+  }
+}
+
+startOnboarding();
@@ -0,0 +1,62 @@
+// Widget HUD (see `ui/widget_window.rs`): CPU, GPU, RAM and network receive rate from the
+// `system` / `network` metric push, network in the `units` rate preference
+const WIDGET_PUSH_INTERVAL_MS = 2000;
+
+let widgetUnits = { networkRate: "bytes" };
+
+function getInvoke() {
+  return window.__TAURI__?.core?.invoke || window.__TAURI_INTERNALS__?.invoke?.bind(window.__TAURI_INTERNALS__) || null;
+}
+
+function setText(id, text) {
+  const el = document.getElementById(id);
+  if (el && el.textContent !== text) el.textContent = text;
+}
+
+function percent(value) {
+  return typeof value === "number" ? `${Math.round(value)}%` : "--";
+}
+
+function rate(bytesPerSec) {
+  if (typeof bytesPerSec !== "number") return "--";
+  const bits = widgetUnits.networkRate === "bits";
+  const units = bits ? ["bit/s", "kbit/s", "Mbit/s", "Gbit/s"] : ["B/s", "KB/s", "MB/s", "GB/s"];
+  let value = Math.max(0, bits ? bytesPerSec * 8 : bytesPerSec);
+  let i = 0;
+  while (value >= 1000 && i < units.length - 1) {
+    value /= 1000;
+    i++;
+  }
+  return `${value >= 10 || i === 0 ? Math.round(value) : value.toFixed(1)} ${units[i]}`;
+}
+
+function render(payload) {
+  const system = payload?.system;
+  if (system) {
+    setText("widget-cpu", percent(system.cpu));
+    setText("widget-gpu", percent(system.gpu));
+    setText("widget-ram", percent(system.ram));
+  }
+  if (payload?.network) setText("widget-net", rate(payload.network.net_rx));
+}
+
+async function startWidget() {
+  const invoke = getInvoke();
+  const listen = window.__TAURI__?.event?.listen;
+  if (!invoke || !listen) {
+    setTimeout(startWidget, 100);
+    return;
+  }
+  try {
+    widgetUnits = await invoke("get_units");
+    await listen("units-changed", (event) => {
+      widgetUnits = event.payload || widgetUnits;
+    });
+    await listen("metrics-push", (event) => render(event.payload));
+    await invoke("subscribe", { metrics: ["system", "network"], intervalMs: WIDGET_PUSH_INTERVAL_MS });
+  } catch (err) {
+    console.warn("Widget updates unavailable:", err);
+  }
+}
+
+startWidget();
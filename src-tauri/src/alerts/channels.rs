@@ -1,5 +1,10 @@
 //! Alert channel implementations
 
+use std::collections::HashSet;
+use std::time::Instant;
+
+use super::discord::{self, DiscordTarget, RateLimiter};
+use super::webhook::{self, WebhookPayload};
 use super::AlertContext;
 use crate::security;
 use anyhow::{Context, Result};
@@ -7,6 +12,11 @@ use anyhow::{Context, Result};
 /// Trait for alert channels
 pub trait AlertChannel: Send + Sync {
     fn send(&mut self, message: &str, context: &AlertContext) -> Result<()>;
+    /// Called when the condition of an alert sent through this channel has cleared; most
+    /// channels say nothing then
+    fn resolve(&mut self, _message: &str, _context: &AlertContext) -> Result<()> {
+        Ok(())
+    }
     #[allow(dead_code)] // Part of trait API, may be used in future
     fn get_id(&self) -> &str;
     #[allow(dead_code)] // Part of trait API, may be used in future
@@ -168,6 +178,229 @@ impl AlertChannel for MastodonChannel {
     }
 }
 
+/// How long the menu bar stays red after a menu bar alert fires
+pub const MENU_BAR_HIGHLIGHT_SECS: u64 = 300;
+
+/// macOS Notification Center alert channel (posted through `osascript`)
+pub struct NotificationChannel;
+
+impl AlertChannel for NotificationChannel {
+    fn get_id(&self) -> &str {
+        "notification"
+    }
+
+    fn get_name(&self) -> &str {
+        "Notification"
+    }
+
+    fn send(&mut self, message: &str, _context: &AlertContext) -> Result<()> {
+        // AppleScript string literal: escape backslashes and quotes
+        let text = message.replace('\\', "\\\\").replace('"', "\\\"");
+        let script = format!("display notification \"{}\" with title \"mac-stats\"", text);
+        let status = std::process::Command::new("/usr/bin/osascript")
+            .arg("-e")
+            .arg(script)
+            .status()?;
+        anyhow::ensure!(status.success(), "osascript exited with {}", status);
+        Ok(())
+    }
+}
+
+/// Menu bar alert channel: tints the alert's metric red in the status item (or shows a red
+/// `Alert ✕` line for metrics it does not show) for [`MENU_BAR_HIGHLIGHT_SECS`]
+pub struct MenuBarChannel;
+
+impl AlertChannel for MenuBarChannel {
+    fn get_id(&self) -> &str {
+        "menubar"
+    }
+
+    fn get_name(&self) -> &str {
+        "Menu bar"
+    }
+
+    fn send(&mut self, _message: &str, context: &AlertContext) -> Result<()> {
+        let metric = context
+            .custom_data
+            .get(webhook::EVENT_KEY)
+            .and_then(|e| e.get("metric"))
+            .and_then(|m| m.as_str())
+            .unwrap_or_default()
+            .to_string();
+        if let Ok(mut active) = crate::state::ALERT_MENU_BAR_HIGHLIGHT.lock() {
+            active.insert(metric, std::time::Instant::now());
+        }
+        Ok(())
+    }
+}
+
+/// Low Power Mode alert action: switches macOS Low Power Mode on (e.g. at 20% battery) through
+/// the privileged helper; does nothing when it is already on
+pub struct LowPowerModeChannel;
+
+impl AlertChannel for LowPowerModeChannel {
+    fn get_id(&self) -> &str {
+        "lowPowerMode"
+    }
+
+    fn get_name(&self) -> &str {
+        "Low Power Mode"
+    }
+
+    fn send(&mut self, _message: &str, _context: &AlertContext) -> Result<()> {
+        crate::metrics::low_power_mode::set_enabled(true).map_err(anyhow::Error::msg)?;
+        Ok(())
+    }
+}
+
+/// Metrics whose menu bar alert fired within the last [`MENU_BAR_HIGHLIGHT_SECS`] (`""` for
+/// alerts without a metric)
+pub fn menu_bar_alert_metrics() -> Vec<String> {
+    let Ok(mut active) = crate::state::ALERT_MENU_BAR_HIGHLIGHT.try_lock() else {
+        return Vec::new();
+    };
+    active.retain(|_, since| since.elapsed().as_secs() < MENU_BAR_HIGHLIGHT_SECS);
+    active.keys().cloned().collect()
+}
+
+/// Generic webhook alert channel: POSTs the alert as JSON to a URL from config (see [`webhook`])
+pub struct WebhookChannel {
+    id: String,
+    url: String,
+}
+
+impl WebhookChannel {
+    pub fn new(url: String) -> Self {
+        Self {
+            id: format!("webhook:{}", url),
+            url,
+        }
+    }
+}
+
+impl AlertChannel for WebhookChannel {
+    fn get_id(&self) -> &str {
+        &self.id
+    }
+
+    fn get_name(&self) -> &str {
+        "Webhook"
+    }
+
+    /// Queues the POST; retries and failures are handled (and logged) by [`webhook::dispatch`]
+    fn send(&mut self, message: &str, context: &AlertContext) -> Result<()> {
+        webhook::dispatch(self.url.clone(), WebhookPayload::new(message, context));
+        Ok(())
+    }
+}
+
+/// Discord alert channel: posts alerts as embeds through the bot, rate limited per target, and a
+/// recovery embed when the condition clears (see [`discord`])
+pub struct DiscordChannel {
+    id: String,
+    target: DiscordTarget,
+    limiter: RateLimiter,
+    /// Alerts posted whose recovery has not been posted yet
+    announced: HashSet<String>,
+}
+
+impl DiscordChannel {
+    pub fn new(target: DiscordTarget) -> Self {
+        Self {
+            id: target.channel_id(),
+            target,
+            limiter: RateLimiter::default(),
+            announced: HashSet::new(),
+        }
+    }
+}
+
+impl AlertChannel for DiscordChannel {
+    fn get_id(&self) -> &str {
+        &self.id
+    }
+
+    fn get_name(&self) -> &str {
+        "Discord"
+    }
+
+    /// Queues the post; failures are logged by [`discord::dispatch`]
+    fn send(&mut self, message: &str, context: &AlertContext) -> Result<()> {
+        if !self.limiter.allow(Instant::now()) {
+            tracing::debug!("Alert to {}: rate limited", self.id);
+            return Ok(());
+        }
+        let event = webhook::event(context);
+        if let Some(e) = &event {
+            self.announced.insert(e.name.clone());
+        }
+        let suppressed = self.limiter.take_suppressed();
+        discord::dispatch(
+            self.target,
+            discord::alert_embed(message, event.as_ref(), suppressed),
+        );
+        Ok(())
+    }
+
+    fn resolve(&mut self, message: &str, context: &AlertContext) -> Result<()> {
+        let event = webhook::event(context);
+        if event
+            .as_ref()
+            .is_some_and(|e| self.announced.remove(&e.name))
+        {
+            discord::dispatch(
+                self.target,
+                discord::recovery_embed(message, event.as_ref()),
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Discord voice alert channel: speaks alerts in the bot's voice channel (see
+/// [`crate::discord::voice`]), with the same rate limit as posts, and the recovery of an alert
+/// it spoke
+#[derive(Default)]
+pub struct DiscordVoiceChannel {
+    limiter: RateLimiter,
+    announced: HashSet<String>,
+}
+
+impl DiscordVoiceChannel {
+    pub const ID: &'static str = "discord:voice";
+}
+
+impl AlertChannel for DiscordVoiceChannel {
+    fn get_id(&self) -> &str {
+        Self::ID
+    }
+
+    fn get_name(&self) -> &str {
+        "Discord voice"
+    }
+
+    fn send(&mut self, message: &str, context: &AlertContext) -> Result<()> {
+        if !self.limiter.allow(Instant::now()) {
+            tracing::debug!("Alert to {}: rate limited", Self::ID);
+            return Ok(());
+        }
+        // Nobody hears a footer: suppressed alerts are only dropped
+        self.limiter.take_suppressed();
+        if let Some(e) = webhook::event(context) {
+            self.announced.insert(e.name);
+        }
+        crate::discord::voice::speak_alert(message);
+        Ok(())
+    }
+
+    fn resolve(&mut self, message: &str, context: &AlertContext) -> Result<()> {
+        if webhook::event(context).is_some_and(|e| self.announced.remove(&e.name)) {
+            crate::discord::voice::speak_alert(message);
+        }
+        Ok(())
+    }
+}
+
 /// Signal alert channel (placeholder - requires Signal API setup)
 #[allow(dead_code)] // Part of API, may be used in future
 pub struct SignalChannel {
@@ -0,0 +1,262 @@
+//! Threshold alerts from config.json (`alertRules`)
+//!
+//! Each entry becomes an [`Alert`] with an [`AlertRule::Metric`] rule, e.g.
+//! `{"name": "CPU busy", "metric": "cpu", "operator": ">", "threshold": 90, "durationSecs": 300}`.
+//! `actions` picks where it goes: `notification` (default), `menubar` (the metric turns red in
+//! the status item), `lowPowerMode` (switches macOS Low Power Mode on), `{"webhook": "https://..."}`
+//! and/or a Discord embed through the bot, `{"discord": "<channel id>"}` or
+//! `{"discordDm": "<user id>"}`, or `discordVoice` (spoken in the bot's voice channel). The list is re-read on every periodic evaluation, so edits apply
+//! within a minute.
+
+use super::channels::{
+    AlertChannel, DiscordChannel, DiscordVoiceChannel, LowPowerModeChannel, MenuBarChannel,
+    NotificationChannel, WebhookChannel,
+};
+use super::discord::DiscordTarget;
+use super::rules::{AlertRule, ThresholdOperator, METRIC_NAMES};
+use super::{Alert, AlertSeverity};
+
+/// Alert ids of rules loaded from config start with this prefix
+pub const ID_PREFIX: &str = "config:";
+/// Cooldown when `cooldownSecs` is not set (same as [`Alert::new`])
+const DEFAULT_COOLDOWN_SECS: u64 = 300;
+
+/// Where a configured rule is delivered
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuleAction {
+    Notification,
+    MenuBar,
+    LowPowerMode,
+    Webhook(String),
+    Discord(DiscordTarget),
+    DiscordVoice,
+}
+
+impl RuleAction {
+    fn parse(value: &serde_json::Value) -> Option<Self> {
+        if let Some(name) = value.as_str() {
+            return match name {
+                "notification" => Some(Self::Notification),
+                "menubar" => Some(Self::MenuBar),
+                "lowPowerMode" => Some(Self::LowPowerMode),
+                "discordVoice" => Some(Self::DiscordVoice),
+                _ => None,
+            };
+        }
+        // Snowflakes as strings (JSON numbers that large lose precision in most tools) or numbers
+        let snowflake = |key: &str| {
+            let id = value.get(key)?;
+            id.as_u64()
+                .or_else(|| id.as_str().and_then(|s| s.trim().parse().ok()))
+        };
+        if let Some(id) = snowflake("discord") {
+            return Some(Self::Discord(DiscordTarget::Channel(id)));
+        }
+        if let Some(id) = snowflake("discordDm") {
+            return Some(Self::Discord(DiscordTarget::Dm(id)));
+        }
+        let url = value.get("webhook")?.as_str()?.trim();
+        (url.starts_with("https://") || url.starts_with("http://"))
+            .then(|| Self::Webhook(url.to_string()))
+    }
+
+    /// Id of the [`AlertChannel`] this action sends through
+    pub fn channel_id(&self) -> String {
+        match self {
+            Self::Notification => "notification".to_string(),
+            Self::MenuBar => "menubar".to_string(),
+            Self::LowPowerMode => "lowPowerMode".to_string(),
+            Self::Webhook(url) => format!("webhook:{}", url),
+            Self::Discord(target) => target.channel_id(),
+            Self::DiscordVoice => DiscordVoiceChannel::ID.to_string(),
+        }
+    }
+
+    pub fn channel(&self) -> Box<dyn AlertChannel> {
+        match self {
+            Self::Notification => Box::new(NotificationChannel),
+            Self::MenuBar => Box::new(MenuBarChannel),
+            Self::LowPowerMode => Box::new(LowPowerModeChannel),
+            Self::Webhook(url) => Box::new(WebhookChannel::new(url.clone())),
+            Self::Discord(target) => Box::new(DiscordChannel::new(*target)),
+            Self::DiscordVoice => Box::new(DiscordVoiceChannel::default()),
+        }
+    }
+}
+
+/// One valid `alertRules` entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfiguredRule {
+    pub name: String,
+    pub metric: String,
+    pub operator: ThresholdOperator,
+    pub threshold: f32,
+    pub duration_secs: u64,
+    pub cooldown_secs: u64,
+    pub severity: AlertSeverity,
+    pub actions: Vec<RuleAction>,
+}
+
+impl ConfiguredRule {
+    pub fn id(&self) -> String {
+        format!("{}{}", ID_PREFIX, self.name)
+    }
+
+    pub fn to_alert(&self) -> Alert {
+        Alert {
+            id: self.id(),
+            name: self.name.clone(),
+            rule: AlertRule::Metric {
+                metric: self.metric.clone(),
+                operator: self.operator,
+                threshold: self.threshold,
+                duration_secs: self.duration_secs,
+            },
+            channels: self.actions.iter().map(RuleAction::channel_id).collect(),
+            enabled: true,
+            last_triggered: None,
+            cooldown_secs: self.cooldown_secs,
+            severity: self.severity,
+        }
+    }
+}
+
+fn parse_rule(value: &serde_json::Value) -> Result<ConfiguredRule, String> {
+    let obj = value.as_object().ok_or("not an object")?;
+    let metric = obj
+        .get("metric")
+        .and_then(|v| v.as_str())
+        .filter(|m| METRIC_NAMES.contains(m))
+        .ok_or_else(|| format!("metric must be one of {}", METRIC_NAMES.join(", ")))?;
+    let operator = obj
+        .get("operator")
+        .and_then(|v| v.as_str())
+        .map_or(Some(ThresholdOperator::Above), ThresholdOperator::parse)
+        .ok_or("operator must be >, >=, < or <=")?;
+    let threshold = obj
+        .get("threshold")
+        .and_then(|v| v.as_f64())
+        .ok_or("threshold must be a number")? as f32;
+    let severity = match obj.get("severity").and_then(|v| v.as_str()) {
+        None => AlertSeverity::default(),
+        Some(s) => AlertSeverity::parse(s).ok_or("severity must be minor, major or critical")?,
+    };
+    let actions = match obj.get("actions").and_then(|v| v.as_array()) {
+        None => vec![RuleAction::Notification],
+        Some(list) => list
+            .iter()
+            .map(|a| RuleAction::parse(a).ok_or(format!("unknown action {}", a)))
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+    let name = obj
+        .get("name")
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .unwrap_or_else(|| format!("{} {} {}", metric, operator.as_str(), threshold));
+    Ok(ConfiguredRule {
+        name,
+        metric: metric.to_string(),
+        operator,
+        threshold,
+        duration_secs: obj
+            .get("durationSecs")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0),
+        cooldown_secs: obj
+            .get("cooldownSecs")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(DEFAULT_COOLDOWN_SECS),
+        severity,
+        actions,
+    })
+}
+
+/// Parse `alertRules`; invalid entries and repeated names are logged and skipped
+pub fn parse_rules(value: &serde_json::Value) -> Vec<ConfiguredRule> {
+    let Some(entries) = value.as_array() else {
+        return Vec::new();
+    };
+    let mut rules: Vec<ConfiguredRule> = Vec::new();
+    for (i, entry) in entries.iter().enumerate() {
+        match parse_rule(entry) {
+            Ok(rule) if rules.iter().any(|r| r.name == rule.name) => {
+                tracing::warn!("alertRules[{}]: duplicate name '{}', skipped", i, rule.name)
+            }
+            Ok(rule) => rules.push(rule),
+            Err(e) => tracing::warn!("alertRules[{}]: {}, skipped", i, e),
+        }
+    }
+    rules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::AlertContext;
+    use std::collections::HashMap;
+
+    #[test]
+    fn parses_rules_and_evaluates_thresholds() {
+        let rules = parse_rules(&serde_json::json!([
+            {"name": "CPU busy", "metric": "cpu", "threshold": 90, "durationSecs": 300,
+             "actions": ["menubar", {"webhook": "https://example.com/hook"}]},
+            {"metric": "battery", "operator": "<", "threshold": 15, "severity": "critical"},
+            {"metric": "fan_speed", "threshold": 1},
+            {"name": "CPU busy", "metric": "cpu", "threshold": 50},
+            {"metric": "cpu", "threshold": 50, "actions": ["email"]},
+        ]));
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0].duration_secs, 300);
+        assert_eq!(
+            rules[0].to_alert().channels,
+            vec!["menubar", "webhook:https://example.com/hook"]
+        );
+        assert_eq!(rules[1].name, "battery < 15");
+        assert_eq!(rules[1].actions, vec![RuleAction::Notification]);
+        assert_eq!(rules[1].severity, AlertSeverity::Critical);
+        assert_eq!(
+            RuleAction::parse(&serde_json::json!("lowPowerMode")),
+            Some(RuleAction::LowPowerMode)
+        );
+        assert_eq!(
+            RuleAction::parse(&serde_json::json!({"discord": "1234567890123456789"})),
+            Some(RuleAction::Discord(DiscordTarget::Channel(
+                1234567890123456789
+            )))
+        );
+        assert_eq!(
+            RuleAction::parse(&serde_json::json!({"discordDm": 42}))
+                .map(|a| a.channel_id())
+                .as_deref(),
+            Some("discord:dm:42")
+        );
+        assert_eq!(
+            RuleAction::parse(&serde_json::json!("discordVoice")).map(|a| a.channel_id()),
+            Some("discord:voice".to_string())
+        );
+
+        let context = |cpu: f32| AlertContext {
+            monitor_id: None,
+            monitor_status: None,
+            system_metrics: Some(crate::metrics::SystemMetrics {
+                cpu,
+                gpu: 0.0,
+                ram: 0.0,
+                disk: 0.0,
+                plugins: Default::default(),
+            }),
+            cpu_details: None,
+            custom_data: HashMap::new(),
+        };
+        let alert = rules[0].to_alert();
+        assert!(alert.rule.evaluate(&context(95.0)).unwrap());
+        assert!(!alert.rule.evaluate(&context(90.0)).unwrap());
+        assert_eq!(
+            alert.rule.describe(&context(95.0)).as_deref(),
+            Some("cpu 95.0 > 90")
+        );
+        // No battery reading: the rule stays quiet
+        assert!(!rules[1].to_alert().rule.evaluate(&context(0.0)).unwrap());
+    }
+}
@@ -0,0 +1,174 @@
+//! Notification digests
+//!
+//! Alerts whose severity has a digest interval (`alertDigestMinutes` in config.json) are not
+//! sent one by one. They are queued, and once the oldest queued alert of that severity is one
+//! interval old, each channel gets a single summary ("3 minor alerts in the last hour: ..."),
+//! in the `i18n` language.
+
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+use super::AlertSeverity;
+use crate::i18n::Lang;
+
+struct DigestEntry {
+    alert_name: String,
+    channels: Vec<String>,
+    at: DateTime<Utc>,
+}
+
+/// Queued alerts per severity plus the digest interval for each batched severity
+#[derive(Default)]
+pub struct Digest {
+    intervals: HashMap<AlertSeverity, u64>,
+    pending: HashMap<AlertSeverity, Vec<DigestEntry>>,
+}
+
+impl Digest {
+    /// Digest interval in seconds per severity; severities left out are sent immediately
+    pub fn set_intervals(&mut self, intervals: HashMap<AlertSeverity, u64>) {
+        self.intervals = intervals;
+    }
+
+    /// Whether alerts of `severity` are batched
+    pub fn batches(&self, severity: AlertSeverity) -> bool {
+        self.intervals.get(&severity).is_some_and(|secs| *secs > 0)
+    }
+
+    pub fn queue(
+        &mut self,
+        severity: AlertSeverity,
+        alert_name: &str,
+        channels: &[String],
+        at: DateTime<Utc>,
+    ) {
+        self.pending.entry(severity).or_default().push(DigestEntry {
+            alert_name: alert_name.to_string(),
+            channels: channels.to_vec(),
+            at,
+        });
+    }
+
+    /// Drain every severity whose oldest queued alert is at least one interval old (or whose
+    /// digest was turned off since) into `(channel_id, summary)` messages
+    pub fn take_due(&mut self, now: DateTime<Utc>, lang: Lang) -> Vec<(String, String)> {
+        let due: Vec<AlertSeverity> = self
+            .pending
+            .iter()
+            .filter(|(severity, entries)| {
+                let interval = self.intervals.get(severity).copied().unwrap_or(0);
+                entries.first().is_some_and(|oldest| {
+                    now.signed_duration_since(oldest.at).num_seconds() >= interval as i64
+                })
+            })
+            .map(|(severity, _)| *severity)
+            .collect();
+        let mut messages = Vec::new();
+        for severity in due {
+            let entries = self.pending.remove(&severity).unwrap_or_default();
+            let interval = self.intervals.get(&severity).copied().unwrap_or(0);
+            let mut channel_ids: Vec<&String> =
+                entries.iter().flat_map(|e| e.channels.iter()).collect();
+            channel_ids.sort();
+            channel_ids.dedup();
+            for channel_id in channel_ids {
+                let names: Vec<&str> = entries
+                    .iter()
+                    .filter(|e| e.channels.contains(channel_id))
+                    .map(|e| e.alert_name.as_str())
+                    .collect();
+                messages.push((
+                    channel_id.clone(),
+                    summary(lang, severity, &names, interval),
+                ));
+            }
+        }
+        messages
+    }
+}
+
+/// "3 minor alerts in the last hour: Disk low, CPU high (2×)"
+fn summary(lang: Lang, severity: AlertSeverity, names: &[&str], window_secs: u64) -> String {
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for name in names {
+        match counts.iter_mut().find(|(n, _)| n == name) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((name, 1)),
+        }
+    }
+    let listed: Vec<String> = counts
+        .iter()
+        .map(|(name, count)| match count {
+            1 => name.to_string(),
+            n => format!("{} ({}×)", name, n),
+        })
+        .collect();
+    let count = names.len().to_string();
+    let window = describe_window(lang, window_secs);
+    lang.format(
+        if names.len() == 1 {
+            "digest.one"
+        } else {
+            "digest.many"
+        },
+        &[
+            ("count", &count),
+            ("severity", severity.as_str()),
+            ("window", &window),
+            ("alerts", &listed.join(", ")),
+        ],
+    )
+}
+
+/// "in the last hour", "in the last 15 minutes", …
+fn describe_window(lang: Lang, secs: u64) -> String {
+    match secs {
+        3600 => lang.text("digest.last_hour").to_string(),
+        s if s >= 3600 && s % 3600 == 0 => {
+            lang.format("digest.last_hours", &[("n", &(s / 3600).to_string())])
+        }
+        s if s >= 120 => lang.format("digest.last_minutes", &[("n", &(s / 60).to_string())]),
+        _ => lang.text("digest.last_minute").to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batches_per_severity_and_summarizes_per_channel() {
+        let mut digest = Digest::default();
+        digest.set_intervals(HashMap::from([(AlertSeverity::Minor, 3600)]));
+        assert!(digest.batches(AlertSeverity::Minor));
+        assert!(!digest.batches(AlertSeverity::Critical));
+
+        let start = Utc::now();
+        let slack = vec!["slack".to_string()];
+        let both = vec!["slack".to_string(), "telegram".to_string()];
+        digest.queue(AlertSeverity::Minor, "Disk low", &both, start);
+        digest.queue(AlertSeverity::Minor, "CPU high", &slack, start);
+        digest.queue(AlertSeverity::Minor, "CPU high", &slack, start);
+        assert!(digest
+            .take_due(start + chrono::Duration::minutes(59), Lang::En)
+            .is_empty());
+
+        let sent = digest.take_due(start + chrono::Duration::minutes(60), Lang::En);
+        assert_eq!(
+            sent,
+            vec![
+                (
+                    "slack".to_string(),
+                    "3 minor alerts in the last hour: Disk low, CPU high (2×)".to_string()
+                ),
+                (
+                    "telegram".to_string(),
+                    "1 minor alert in the last hour: Disk low".to_string()
+                ),
+            ]
+        );
+        assert!(digest
+            .take_due(start + chrono::Duration::hours(3), Lang::En)
+            .is_empty());
+    }
+}
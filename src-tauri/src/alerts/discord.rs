@@ -0,0 +1,188 @@
+//! Discord delivery for alerts (`{"discord": "<channel id>"}` and `{"discordDm": "<user id>"}`
+//! actions)
+//!
+//! Alerts go out through the bot (the same token as the Discord gateway) as embeds: the rule
+//! name as title, the message, the reading against its threshold, the severity as color and the
+//! host. When the condition of an alert that was posted clears, a green recovery embed follows
+//! to the same place. Each target gets at most [`RATE_LIMIT`] alerts per [`RATE_WINDOW`]; the
+//! ones over the limit are dropped and counted in the footer of the next alert that goes out.
+//! Recoveries only follow alerts that were posted, so they need no limit of their own.
+//!
+//! Sending runs on the async runtime so a slow Discord API never holds up alert evaluation.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use super::webhook::AlertEvent;
+
+/// Alerts per target within [`RATE_WINDOW`]
+pub const RATE_LIMIT: usize = 5;
+pub const RATE_WINDOW: Duration = Duration::from_secs(600);
+
+const COLOR_CRITICAL: u32 = 0xED4245;
+const COLOR_MAJOR: u32 = 0xE67E22;
+const COLOR_MINOR: u32 = 0xFEE75C;
+const COLOR_RECOVERED: u32 = 0x57F287;
+/// Digests (no single alert behind them)
+const COLOR_DIGEST: u32 = 0x5865F2;
+
+/// Where a Discord alert action posts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscordTarget {
+    Channel(u64),
+    /// Direct message to a user
+    Dm(u64),
+}
+
+impl DiscordTarget {
+    /// Id of the alert channel for this target
+    pub fn channel_id(&self) -> String {
+        match self {
+            Self::Channel(id) => format!("discord:channel:{}", id),
+            Self::Dm(id) => format!("discord:dm:{}", id),
+        }
+    }
+}
+
+/// Sliding-window limit of the alerts sent to one target
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    sent: VecDeque<Instant>,
+    suppressed: u32,
+}
+
+impl RateLimiter {
+    /// Whether another alert may go out at `now`; one that may not is counted as suppressed
+    pub fn allow(&mut self, now: Instant) -> bool {
+        while self
+            .sent
+            .front()
+            .is_some_and(|at| now.duration_since(*at) >= RATE_WINDOW)
+        {
+            self.sent.pop_front();
+        }
+        if self.sent.len() >= RATE_LIMIT {
+            self.suppressed += 1;
+            return false;
+        }
+        self.sent.push_back(now);
+        true
+    }
+
+    /// Alerts suppressed since the last call
+    pub fn take_suppressed(&mut self) -> u32 {
+        std::mem::take(&mut self.suppressed)
+    }
+}
+
+/// Embed for a triggered alert (or a digest when there is no `event`)
+pub fn alert_embed(
+    message: &str,
+    event: Option<&AlertEvent>,
+    suppressed: u32,
+) -> serde_json::Value {
+    let color = match event.map(|e| e.severity.as_str()) {
+        Some("critical") => COLOR_CRITICAL,
+        Some("minor") => COLOR_MINOR,
+        Some(_) => COLOR_MAJOR,
+        None => COLOR_DIGEST,
+    };
+    let mut embed = embed(message, event, color);
+    if suppressed > 0 {
+        embed["footer"] = serde_json::json!({
+            "text": format!("{} more alert(s) suppressed by the rate limit", suppressed)
+        });
+    }
+    embed
+}
+
+/// Embed for an alert whose condition cleared
+pub fn recovery_embed(message: &str, event: Option<&AlertEvent>) -> serde_json::Value {
+    embed(message, event, COLOR_RECOVERED)
+}
+
+fn embed(message: &str, event: Option<&AlertEvent>, color: u32) -> serde_json::Value {
+    let mut fields = Vec::new();
+    if let Some(e) = event {
+        if let (Some(metric), Some(value)) = (&e.metric, e.value) {
+            fields.push(field(metric, format!("{:.1}", value)));
+        }
+        if let (Some(operator), Some(threshold)) = (&e.operator, e.threshold) {
+            fields.push(field("Threshold", format!("{} {}", operator, threshold)));
+        }
+        fields.push(field("Severity", e.severity.clone()));
+    }
+    if let Some(host) = sysinfo::System::host_name() {
+        fields.push(field("Host", host));
+    }
+    serde_json::json!({
+        "title": event.map_or("mac-stats", |e| e.name.as_str()),
+        "description": message,
+        "color": color,
+        "fields": fields,
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+    })
+}
+
+fn field(name: &str, value: String) -> serde_json::Value {
+    serde_json::json!({ "name": name, "value": value, "inline": true })
+}
+
+/// Post `embed` to `target` in the background; failures are logged
+pub fn dispatch(target: DiscordTarget, embed: serde_json::Value) {
+    tauri::async_runtime::spawn(async move {
+        let channel_id = match target {
+            DiscordTarget::Channel(id) => Ok(id),
+            DiscordTarget::Dm(user_id) => crate::discord::open_dm_channel(user_id).await,
+        };
+        let result = match channel_id {
+            Ok(id) => crate::discord::send_embed_to_channel(id, embed).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            tracing::warn!("Alert to {}: {}", target.channel_id(), e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limit_drops_and_counts_alerts_over_the_limit() {
+        let start = Instant::now();
+        let mut limiter = RateLimiter::default();
+        for i in 0..RATE_LIMIT {
+            assert!(limiter.allow(start + Duration::from_secs(i as u64)));
+        }
+        assert!(!limiter.allow(start + Duration::from_secs(60)));
+        assert!(!limiter.allow(start + Duration::from_secs(61)));
+        // The first alert leaves the window
+        assert!(limiter.allow(start + RATE_WINDOW));
+        assert_eq!(limiter.take_suppressed(), 2);
+        assert_eq!(limiter.take_suppressed(), 0);
+
+        let event = AlertEvent {
+            name: "CPU busy".to_string(),
+            severity: "critical".to_string(),
+            metric: Some("cpu".to_string()),
+            value: Some(95.0),
+            threshold: Some(90.0),
+            operator: Some(">".to_string()),
+        };
+        let embed = alert_embed("Alert triggered: CPU busy", Some(&event), 2);
+        assert_eq!(embed["title"], "CPU busy");
+        assert_eq!(embed["color"], COLOR_CRITICAL);
+        assert_eq!(embed["fields"][0]["value"], "95.0");
+        assert_eq!(embed["fields"][1]["value"], "> 90");
+        assert_eq!(
+            embed["footer"]["text"],
+            "2 more alert(s) suppressed by the rate limit"
+        );
+        assert_eq!(
+            recovery_embed("Recovered: CPU busy", Some(&event))["color"],
+            COLOR_RECOVERED
+        );
+    }
+}
@@ -1,19 +1,59 @@
 //! Alert system module
 //!
 //! Rule-based alerting with channel-agnostic core.
-//! Supports multiple notification channels: Telegram, Slack, Signal, Mastodon.
+//! Supports multiple notification channels: Telegram, Slack, Signal, Mastodon, plus macOS
+//! notifications, the menu bar, webhooks (see [`webhook`]) and Discord (see [`discord`]) for
+//! threshold rules from config (see [`configured`]). Channels that care (Discord) are also told
+//! when the condition of an alert they delivered clears.
+//! Lower-severity alerts can be batched into periodic digests (see [`digest`]).
 
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Mutex;
 
 pub mod channels;
+pub mod configured;
+pub mod digest;
+pub mod discord;
 pub mod rules;
+pub mod webhook;
 
 use channels::AlertChannel;
 use rules::AlertRule;
 
+use crate::i18n::Lang;
+
+/// How urgent an alert is; severities with a digest interval are batched instead of sent
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertSeverity {
+    Minor,
+    #[default]
+    Major,
+    Critical,
+}
+
+impl AlertSeverity {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "minor" => Some(Self::Minor),
+            "major" => Some(Self::Major),
+            "critical" => Some(Self::Critical),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Minor => "minor",
+            Self::Major => "major",
+            Self::Critical => "critical",
+        }
+    }
+}
+
 /// Alert configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Alert {
@@ -24,9 +64,33 @@ pub struct Alert {
     pub enabled: bool,
     pub last_triggered: Option<DateTime<Utc>>,
     pub cooldown_secs: u64, // Prevent spam
+    #[serde(default)]
+    pub severity: AlertSeverity,
 }
 
 impl Alert {
+    /// What a delivery says about this alert, with the metric reading for threshold rules
+    pub fn event(&self, context: &AlertContext) -> webhook::AlertEvent {
+        let mut event = webhook::AlertEvent {
+            name: self.name.clone(),
+            severity: self.severity.as_str().to_string(),
+            ..Default::default()
+        };
+        if let AlertRule::Metric {
+            metric,
+            operator,
+            threshold,
+            ..
+        } = &self.rule
+        {
+            event.value = rules::metric_value(context, metric);
+            event.metric = Some(metric.clone());
+            event.threshold = Some(*threshold);
+            event.operator = Some(operator.as_str().to_string());
+        }
+        event
+    }
+
     #[allow(dead_code)] // Part of API, may be used in future
     pub fn new(id: String, name: String, rule: AlertRule) -> Self {
         Self {
@@ -37,6 +101,7 @@ impl Alert {
             enabled: true,
             last_triggered: None,
             cooldown_secs: 300, // 5 minutes default cooldown
+            severity: AlertSeverity::default(),
         }
     }
 
@@ -70,6 +135,37 @@ pub struct AlertContext {
     pub custom_data: HashMap<String, serde_json::Value>,
 }
 
+/// Fired alerts kept for [`recent_alerts`]
+const RECENT_ALERTS: usize = 50;
+
+/// One alert that fired (see [`recent_alerts`])
+#[derive(Debug, Clone, Serialize)]
+pub struct FiredAlert {
+    pub at: DateTime<Utc>,
+    pub name: String,
+    pub severity: AlertSeverity,
+    pub message: String,
+}
+
+static RECENT: Mutex<VecDeque<FiredAlert>> = Mutex::new(VecDeque::new());
+
+/// The last [`RECENT_ALERTS`] alerts fired since launch, oldest first
+pub fn recent_alerts() -> Vec<FiredAlert> {
+    RECENT
+        .lock()
+        .map(|recent| recent.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+fn remember(alert: FiredAlert) {
+    if let Ok(mut recent) = RECENT.lock() {
+        if recent.len() == RECENT_ALERTS {
+            recent.pop_front();
+        }
+        recent.push_back(alert);
+    }
+}
+
 /// Alert manager (handles rule evaluation and channel delivery)
 pub struct AlertManager {
     alerts: HashMap<String, Alert>,
@@ -77,6 +173,10 @@ pub struct AlertManager {
     /// Tracks when each alert's condition first became true (for sustained-duration rules).
     /// Key: alert id. Cleared when the condition becomes false.
     condition_since: HashMap<String, DateTime<Utc>>,
+    /// Alerts of batched severities waiting for their digest
+    digest: digest::Digest,
+    /// Alerts delivered whose condition has not cleared yet, as `<alert id>|<monitor id>`
+    firing: HashSet<String>,
 }
 
 impl AlertManager {
@@ -85,9 +185,77 @@ impl AlertManager {
             alerts: HashMap::new(),
             channels: HashMap::new(),
             condition_since: HashMap::new(),
+            digest: digest::Digest::default(),
+            firing: HashSet::new(),
         }
     }
 
+    /// Digest interval in minutes per severity name (`alertDigestMinutes`); 0 or missing
+    /// sends that severity immediately
+    pub fn set_digest_minutes(&mut self, minutes: &HashMap<String, u64>) {
+        let intervals = minutes
+            .iter()
+            .filter_map(|(name, mins)| match AlertSeverity::parse(name) {
+                Some(severity) => Some((severity, mins * 60)),
+                None => {
+                    tracing::warn!("alertDigestMinutes: unknown severity '{}'", name);
+                    None
+                }
+            })
+            .collect();
+        self.digest.set_intervals(intervals);
+    }
+
+    /// Replace the alerts loaded from `alertRules` and register the channels their actions use.
+    /// A rule keeps its last trigger time across re-reads, so its cooldown still applies.
+    pub fn sync_configured_rules(&mut self, rules: &[configured::ConfiguredRule]) {
+        let previous: HashMap<String, Alert> = self
+            .alerts
+            .iter()
+            .filter(|(id, _)| id.starts_with(configured::ID_PREFIX))
+            .map(|(id, alert)| (id.clone(), alert.clone()))
+            .collect();
+        self.alerts
+            .retain(|id, _| !id.starts_with(configured::ID_PREFIX));
+        for rule in rules {
+            let mut alert = rule.to_alert();
+            alert.last_triggered = previous.get(&alert.id).and_then(|a| a.last_triggered);
+            for action in &rule.actions {
+                self.channels
+                    .entry(action.channel_id())
+                    .or_insert_with(|| action.channel());
+            }
+            self.alerts.insert(alert.id.clone(), alert);
+        }
+    }
+
+    /// Send the digests that are due; returns how many messages went out
+    pub fn flush_digests(&mut self, now: DateTime<Utc>) -> usize {
+        let context = AlertContext {
+            monitor_id: None,
+            monitor_status: None,
+            system_metrics: None,
+            cpu_details: None,
+            custom_data: HashMap::new(),
+        };
+        let mut sent = 0;
+        for (channel_id, message) in self.digest.take_due(now, Lang::current()) {
+            if let Some(channel) = self.channels.get_mut(channel_id.as_str()) {
+                match channel.send(&message, &context) {
+                    Ok(()) => sent += 1,
+                    Err(e) => {
+                        tracing::error!(
+                            "Failed to send alert digest to channel {}: {}",
+                            channel_id,
+                            e
+                        )
+                    }
+                }
+            }
+        }
+        sent
+    }
+
     pub fn add_alert(&mut self, alert: Alert) {
         self.alerts.insert(alert.id.clone(), alert);
     }
@@ -112,7 +280,7 @@ impl AlertManager {
     }
 
     /// Evaluate all alerts against context.
-    /// For rules with a `duration_secs` requirement (TemperatureHigh, CpuHigh), the condition
+    /// For rules with a `duration_secs` requirement (TemperatureHigh, GpuTemperatureHigh, CpuHigh, Metric), the condition
     /// must be true for at least that many consecutive seconds before the alert fires.
     pub fn evaluate(&mut self, context: AlertContext) -> Result<Vec<String>> {
         let mut triggered_alerts = Vec::new();
@@ -124,6 +292,18 @@ impl AlertManager {
                 Some(a) => a,
                 None => continue,
             };
+            // Per monitor: a SiteDown alert is only false for the context of its own monitor
+            let firing_key = format!(
+                "{}|{}",
+                alert_id,
+                context.monitor_id.as_deref().unwrap_or_default()
+            );
+            // Checked during the cooldown too, so the recovery is not delayed by it
+            if self.firing.contains(&firing_key) && !alert.rule.evaluate(&context)? {
+                self.firing.remove(&firing_key);
+                self.resolve(&alert_id, &context);
+                continue;
+            }
             if !alert.should_trigger() {
                 continue;
             }
@@ -148,16 +328,40 @@ impl AlertManager {
                 continue;
             }
 
-            // Trigger alert
+            // Trigger alert (batched severities wait for the next digest)
             let alert = self.alerts.get_mut(&alert_id).unwrap();
-            let message = format!("Alert triggered: {}", alert.name);
+            let lang = Lang::current();
+            let message = match alert.rule.describe(&context) {
+                Some(reading) => lang.format(
+                    "alert.triggered_reading",
+                    &[("name", &alert.name), ("reading", &reading)],
+                ),
+                None => lang.format("alert.triggered", &[("name", &alert.name)]),
+            };
+            remember(FiredAlert {
+                at: now,
+                name: alert.name.clone(),
+                severity: alert.severity,
+                message: message.clone(),
+            });
 
-            for channel_id in &alert.channels {
-                if let Some(channel) = self.channels.get_mut(channel_id.as_str()) {
-                    if let Err(e) = channel.send(&message, &context) {
-                        tracing::error!("Failed to send alert to channel {}: {}", channel_id, e);
+            if self.digest.batches(alert.severity) {
+                self.digest
+                    .queue(alert.severity, &alert.name, &alert.channels, now);
+            } else {
+                let delivery = delivery_context(alert, &context);
+                for channel_id in &alert.channels {
+                    if let Some(channel) = self.channels.get_mut(channel_id.as_str()) {
+                        if let Err(e) = channel.send(&message, &delivery) {
+                            tracing::error!(
+                                "Failed to send alert to channel {}: {}",
+                                channel_id,
+                                e
+                            );
+                        }
                     }
                 }
+                self.firing.insert(firing_key);
             }
 
             alert.last_triggered = Some(now);
@@ -166,6 +370,38 @@ impl AlertManager {
 
         Ok(triggered_alerts)
     }
+
+    /// Tell the channels of `alert_id` that its condition cleared
+    fn resolve(&mut self, alert_id: &str, context: &AlertContext) {
+        let Some(alert) = self.alerts.get(alert_id) else {
+            return;
+        };
+        tracing::info!("Alert recovered: {}", alert.name);
+        let message = Lang::current().format("alert.resolved", &[("name", &alert.name)]);
+        let delivery = delivery_context(alert, context);
+        for channel_id in &alert.channels {
+            if let Some(channel) = self.channels.get_mut(channel_id.as_str()) {
+                if let Err(e) = channel.resolve(&message, &delivery) {
+                    tracing::error!(
+                        "Failed to send alert recovery to channel {}: {}",
+                        channel_id,
+                        e
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// `context` with the [`webhook::AlertEvent`] of `alert` for the channels
+fn delivery_context(alert: &Alert, context: &AlertContext) -> AlertContext {
+    let mut delivery = context.clone();
+    if let Ok(event) = serde_json::to_value(alert.event(context)) {
+        delivery
+            .custom_data
+            .insert(webhook::EVENT_KEY.to_string(), event);
+    }
+    delivery
 }
 
 impl Default for AlertManager {
@@ -5,6 +5,96 @@ use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+/// Comparison used by [`AlertRule::Metric`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThresholdOperator {
+    #[serde(rename = ">")]
+    Above,
+    #[serde(rename = ">=")]
+    AtLeast,
+    #[serde(rename = "<")]
+    Below,
+    #[serde(rename = "<=")]
+    AtMost,
+}
+
+impl ThresholdOperator {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim() {
+            ">" | "gt" => Some(Self::Above),
+            ">=" | "gte" => Some(Self::AtLeast),
+            "<" | "lt" => Some(Self::Below),
+            "<=" | "lte" => Some(Self::AtMost),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Above => ">",
+            Self::AtLeast => ">=",
+            Self::Below => "<",
+            Self::AtMost => "<=",
+        }
+    }
+
+    pub fn compare(self, value: f32, threshold: f32) -> bool {
+        match self {
+            Self::Above => value > threshold,
+            Self::AtLeast => value >= threshold,
+            Self::Below => value < threshold,
+            Self::AtMost => value <= threshold,
+        }
+    }
+}
+
+/// Metric names accepted by [`AlertRule::Metric`]
+pub const METRIC_NAMES: &[&str] = &[
+    "cpu",
+    "gpu",
+    "ram",
+    "disk",
+    "temperature",
+    "gpu_temperature",
+    "frequency",
+    "cpu_power",
+    "gpu_power",
+    "battery",
+    "battery_drain",
+    "load_1",
+    "fan_rpm",
+];
+
+/// Current value of `metric`; `None` when it is unknown or not readable on this Mac
+pub fn metric_value(context: &AlertContext, metric: &str) -> Option<f32> {
+    let system = context.system_metrics.as_ref();
+    let cpu = context.cpu_details.as_ref();
+    match metric {
+        "cpu" => system.map(|m| m.cpu),
+        "gpu" => system.map(|m| m.gpu),
+        "ram" => system.map(|m| m.ram),
+        "disk" => system.map(|m| m.disk),
+        "temperature" => cpu
+            .filter(|c| c.can_read_temperature && c.temperature > 0.0)
+            .map(|c| c.temperature),
+        "gpu_temperature" => cpu
+            .filter(|c| c.can_read_gpu_temperature && c.gpu_temperature > 0.0)
+            .map(|c| c.gpu_temperature),
+        "frequency" => cpu.filter(|c| c.frequency > 0.0).map(|c| c.frequency),
+        "cpu_power" => cpu.filter(|c| c.can_read_cpu_power).map(|c| c.cpu_power),
+        "gpu_power" => cpu.filter(|c| c.can_read_gpu_power).map(|c| c.gpu_power),
+        "battery" => cpu
+            .filter(|c| c.has_battery && c.battery_level >= 0.0)
+            .map(|c| c.battery_level),
+        "battery_drain" => cpu
+            .filter(|c| c.has_battery && !c.is_charging)
+            .map(|c| c.battery_drain_percent_per_hour),
+        "load_1" => cpu.map(|c| c.load_1 as f32),
+        "fan_rpm" => cpu.filter(|c| c.can_read_fan).map(|c| c.fan_rpm),
+        _ => None,
+    }
+}
+
 /// Alert rule types
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum AlertRule {
@@ -14,10 +104,22 @@ pub enum AlertRule {
     NewMentions { count: u64, hours: u64 },
     /// Battery level < N%
     BatteryLow { threshold: f32 },
+    /// Battery draining faster than N%/hour (10-minute sliding window)
+    BatteryDrainHigh { threshold: f32 },
     /// Temperature > N°C sustained
     TemperatureHigh { threshold: f32, duration_secs: u64 },
+    /// GPU temperature > N°C sustained
+    GpuTemperatureHigh { threshold: f32, duration_secs: u64 },
     /// CPU usage > N% sustained
     CpuHigh { threshold: f32, duration_secs: u64 },
+    /// Any metric in [`METRIC_NAMES`] compared against a threshold, optionally sustained
+    /// (`alertRules` in config.json)
+    Metric {
+        metric: String,
+        operator: ThresholdOperator,
+        threshold: f32,
+        duration_secs: u64,
+    },
     /// Custom rule (plugin-based)
     Custom {
         plugin_id: String,
@@ -31,11 +133,27 @@ impl AlertRule {
     pub fn required_duration_secs(&self) -> u64 {
         match self {
             AlertRule::TemperatureHigh { duration_secs, .. } => *duration_secs,
+            AlertRule::GpuTemperatureHigh { duration_secs, .. } => *duration_secs,
             AlertRule::CpuHigh { duration_secs, .. } => *duration_secs,
+            AlertRule::Metric { duration_secs, .. } => *duration_secs,
             _ => 0,
         }
     }
 
+    /// Reading behind a metric rule for the alert message, e.g. `cpu 93.2 > 90`
+    pub fn describe(&self, context: &AlertContext) -> Option<String> {
+        match self {
+            AlertRule::Metric {
+                metric,
+                operator,
+                threshold,
+                ..
+            } => metric_value(context, metric)
+                .map(|v| format!("{} {:.1} {} {}", metric, v, operator.as_str(), threshold)),
+            _ => None,
+        }
+    }
+
     /// Evaluate rule against context
     pub fn evaluate(&self, context: &AlertContext) -> Result<bool> {
         match self {
@@ -79,6 +197,14 @@ impl AlertRule {
                 }
                 Ok(false)
             }
+            AlertRule::BatteryDrainHigh { threshold } => {
+                if let Some(ref cpu_details) = context.cpu_details {
+                    if cpu_details.has_battery && !cpu_details.is_charging {
+                        return Ok(cpu_details.battery_drain_percent_per_hour > *threshold);
+                    }
+                }
+                Ok(false)
+            }
             AlertRule::TemperatureHigh {
                 threshold,
                 duration_secs: _,
@@ -88,6 +214,17 @@ impl AlertRule {
                 }
                 Ok(false)
             }
+            AlertRule::GpuTemperatureHigh {
+                threshold,
+                duration_secs: _,
+            } => {
+                if let Some(ref cpu_details) = context.cpu_details {
+                    if cpu_details.can_read_gpu_temperature && cpu_details.gpu_temperature > 0.0 {
+                        return Ok(cpu_details.gpu_temperature > *threshold);
+                    }
+                }
+                Ok(false)
+            }
             AlertRule::CpuHigh {
                 threshold,
                 duration_secs: _,
@@ -97,7 +234,13 @@ impl AlertRule {
                 }
                 Ok(false)
             }
-            // NOTE: TemperatureHigh/CpuHigh return true for the instantaneous
+            AlertRule::Metric {
+                metric,
+                operator,
+                threshold,
+                duration_secs: _,
+            } => Ok(metric_value(context, metric).is_some_and(|v| operator.compare(v, *threshold))),
+            // NOTE: TemperatureHigh/GpuTemperatureHigh/CpuHigh/Metric return true for the instantaneous
             // condition (threshold exceeded). The sustained-duration check
             // (duration_secs) is enforced by AlertManager::evaluate().
             AlertRule::Custom {
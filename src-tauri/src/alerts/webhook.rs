@@ -0,0 +1,203 @@
+//! Webhook delivery for alerts (`{"webhook": "<url>"}` actions)
+//!
+//! Every alert is POSTed as one JSON object: the message as `text` (Slack) and `content`
+//! (Discord), plus `alert`, `severity`, `metric`, `value`, `threshold`, `operator`, `hostname`,
+//! the machine `labels` and an RFC 3339 `timestamp`. Services that accept arbitrary JSON (Slack and
+//! Discord incoming webhooks, PagerDuty or Zapier generic webhooks, n8n, ...) need nothing else.
+//!
+//! Delivery runs on its own thread so a slow endpoint never holds up alert evaluation. Network
+//! errors, `429` and `5xx` responses are retried up to [`MAX_ATTEMPTS`] times with exponential
+//! backoff from [`BASE_BACKOFF`] (or the server's `Retry-After`, up to [`MAX_BACKOFF`]); other
+//! `4xx` responses are logged and dropped.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::AlertContext;
+
+/// Key in [`AlertContext::custom_data`] holding the [`AlertEvent`] being delivered
+pub const EVENT_KEY: &str = "alert_event";
+/// Attempts per delivery, including the first
+pub const MAX_ATTEMPTS: u32 = 4;
+/// Wait before the first retry; doubled for each further retry
+pub const BASE_BACKOFF: Duration = Duration::from_secs(2);
+/// Longest wait between attempts, also for `Retry-After`
+pub const MAX_BACKOFF: Duration = Duration::from_secs(60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The alert behind a delivery; metric fields are set for threshold rules only
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AlertEvent {
+    pub name: String,
+    pub severity: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metric: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operator: Option<String>,
+}
+
+/// The [`AlertEvent`] in `context`, if any (digests carry none)
+pub fn event(context: &AlertContext) -> Option<AlertEvent> {
+    context
+        .custom_data
+        .get(EVENT_KEY)
+        .and_then(|v| serde_json::from_value::<AlertEvent>(v.clone()).ok())
+}
+
+/// JSON body POSTed to the webhook
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookPayload {
+    pub text: String,
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alert: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub severity: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metric: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub threshold: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operator: Option<String>,
+    pub hostname: String,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+    pub timestamp: String,
+}
+
+impl WebhookPayload {
+    /// Payload for `message`; alert fields come from the [`AlertEvent`] in `context`, if any
+    /// (digests carry none)
+    pub fn new(message: &str, context: &AlertContext) -> Self {
+        let (alert, severity, metric, value, threshold, operator) = match event(context) {
+            Some(e) => (
+                Some(e.name),
+                Some(e.severity),
+                e.metric,
+                e.value,
+                e.threshold,
+                e.operator,
+            ),
+            None => (None, None, None, None, None, None),
+        };
+        Self {
+            text: message.to_string(),
+            content: message.to_string(),
+            alert,
+            severity,
+            metric,
+            value,
+            threshold,
+            operator,
+            hostname: sysinfo::System::host_name().unwrap_or_default(),
+            labels: crate::config::Config::labels().into_iter().collect(),
+            timestamp: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+/// Wait before the next attempt after `attempt` (1-based) failed with `status` (`None` for a
+/// network error); `None` when the failure is final
+pub fn retry_delay(
+    attempt: u32,
+    status: Option<u16>,
+    retry_after: Option<u64>,
+) -> Option<Duration> {
+    if attempt >= MAX_ATTEMPTS {
+        return None;
+    }
+    match status {
+        None | Some(429) | Some(500..=599) => {}
+        Some(_) => return None,
+    }
+    let backoff = retry_after
+        .map(Duration::from_secs)
+        .unwrap_or(BASE_BACKOFF * 2u32.pow(attempt - 1));
+    Some(backoff.min(MAX_BACKOFF))
+}
+
+fn post(url: &str, payload: &WebhookPayload) {
+    let client = match reqwest::blocking::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+    {
+        Ok(c) => c,
+        Err(e) => {
+            tracing::warn!("Alert webhook: could not create HTTP client: {}", e);
+            return;
+        }
+    };
+    // The URL may carry a token in its path or query; only name the host.
+    let host = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_default();
+    for attempt in 1..=MAX_ATTEMPTS {
+        let (status, retry_after, error) = match client.post(url).json(payload).send() {
+            Ok(resp) if resp.status().is_success() => return,
+            Ok(resp) => {
+                let retry_after = resp
+                    .headers()
+                    .get(reqwest::header::RETRY_AFTER)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.trim().parse::<u64>().ok());
+                let status = resp.status();
+                (Some(status.as_u16()), retry_after, status.to_string())
+            }
+            Err(e) => (None, None, e.without_url().to_string()),
+        };
+        match retry_delay(attempt, status, retry_after) {
+            Some(wait) => {
+                tracing::debug!(
+                    "Alert webhook {}: attempt {} failed ({}), retrying in {}s",
+                    host,
+                    attempt,
+                    error,
+                    wait.as_secs()
+                );
+                std::thread::sleep(wait);
+            }
+            None => {
+                tracing::warn!(
+                    "Alert webhook {}: giving up after {} attempt(s): {}",
+                    host,
+                    attempt,
+                    error
+                );
+                return;
+            }
+        }
+    }
+}
+
+/// POST `payload` to `url` on a background thread, retrying transient failures
+pub fn dispatch(url: String, payload: WebhookPayload) {
+    std::thread::spawn(move || post(&url, &payload));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries_transient_failures_with_backoff() {
+        assert_eq!(retry_delay(1, None, None), Some(BASE_BACKOFF));
+        assert_eq!(retry_delay(2, Some(503), None), Some(BASE_BACKOFF * 2));
+        assert_eq!(
+            retry_delay(3, Some(429), Some(5)),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(retry_delay(1, Some(429), Some(3600)), Some(MAX_BACKOFF));
+        assert_eq!(retry_delay(MAX_ATTEMPTS, Some(500), None), None);
+        assert_eq!(retry_delay(1, Some(404), None), None);
+        assert_eq!(retry_delay(1, Some(400), None), None);
+    }
+}
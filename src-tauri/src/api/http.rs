@@ -0,0 +1,206 @@
+//! Minimal HTTP/1.1 request parsing and JSON responses for the local API
+//!
+//! Only what the API needs: one `GET` per connection (`Connection: close`), headers capped at
+//! [`MAX_HEAD_BYTES`], no request bodies. Event streams have no `Content-Length` and end when
+//! the connection closes.
+
+use std::io::{Read, Write};
+
+/// Largest accepted request line + headers
+const MAX_HEAD_BYTES: usize = 8 * 1024;
+
+/// Parsed request head
+#[derive(Debug)]
+pub struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    /// Header names lowercased
+    pub headers: Vec<(String, String)>,
+}
+
+impl Request {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    pub fn query_param(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// JSON response with status code, or the start of a Server-Sent Events stream
+pub struct Response {
+    pub status: u16,
+    pub body: String,
+    /// `text/event-stream` head only; the caller writes the events (see [`write_event`])
+    pub event_stream: bool,
+}
+
+impl Response {
+    pub fn json<T: serde::Serialize>(value: &T) -> Self {
+        match serde_json::to_string(value) {
+            Ok(body) => Self {
+                status: 200,
+                body,
+                event_stream: false,
+            },
+            Err(e) => Self::error(500, &e.to_string()),
+        }
+    }
+
+    pub fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            body: serde_json::json!({ "error": message }).to_string(),
+            event_stream: false,
+        }
+    }
+
+    pub fn event_stream() -> Self {
+        Self {
+            status: 200,
+            body: String::new(),
+            event_stream: true,
+        }
+    }
+
+    fn reason(&self) -> &'static str {
+        match self.status {
+            200 => "OK",
+            400 => "Bad Request",
+            401 => "Unauthorized",
+            403 => "Forbidden",
+            404 => "Not Found",
+            405 => "Method Not Allowed",
+            431 => "Request Header Fields Too Large",
+            503 => "Service Unavailable",
+            _ => "Internal Server Error",
+        }
+    }
+
+    pub fn write_to(&self, out: &mut impl Write) -> std::io::Result<()> {
+        if self.event_stream {
+            out.write_all(
+                b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\
+                  Cache-Control: no-store\r\nConnection: close\r\n\r\n",
+            )?;
+            return out.flush();
+        }
+        let mut head = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+             Cache-Control: no-store\r\nConnection: close\r\n",
+            self.status,
+            self.reason(),
+            self.body.len()
+        );
+        if self.status == 401 {
+            head.push_str("WWW-Authenticate: Bearer\r\n");
+        }
+        head.push_str("\r\n");
+        out.write_all(head.as_bytes())?;
+        out.write_all(self.body.as_bytes())?;
+        out.flush()
+    }
+}
+
+/// One Server-Sent Event carrying `value` as JSON (`data: ...` and a blank line)
+pub fn write_event<T: serde::Serialize>(out: &mut impl Write, value: &T) -> std::io::Result<()> {
+    let data = serde_json::to_string(value)?;
+    out.write_all(format!("data: {}\n\n", data).as_bytes())?;
+    out.flush()
+}
+
+/// Read up to the blank line ending the headers. `Err(status)` for oversized or broken input.
+pub fn read_head(stream: &mut impl Read) -> Result<String, u16> {
+    let mut buf = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+    loop {
+        if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            if end > MAX_HEAD_BYTES {
+                return Err(431);
+            }
+            buf.truncate(end);
+            return String::from_utf8(buf).map_err(|_| 400);
+        }
+        if buf.len() > MAX_HEAD_BYTES {
+            return Err(431);
+        }
+        match stream.read(&mut chunk) {
+            Ok(0) | Err(_) => return Err(400),
+            Ok(n) => buf.extend_from_slice(&chunk[..n]),
+        }
+    }
+}
+
+/// Parse `METHOD /path?query HTTP/1.x` plus headers.
+pub fn parse_head(head: &str) -> Option<Request> {
+    let mut lines = head.split("\r\n");
+    let mut parts = lines.next()?.split(' ');
+    let (method, target, version) = (parts.next()?, parts.next()?, parts.next()?);
+    if !version.starts_with("HTTP/1.") || parts.next().is_some() {
+        return None;
+    }
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let headers = lines
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            Some((name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        })
+        .collect();
+    Some(Request {
+        method: method.to_string(),
+        path: path.to_string(),
+        query: url::form_urlencoded::parse(query.as_bytes())
+            .into_owned()
+            .collect(),
+        headers,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_request_line_query_and_headers() {
+        let head = read_head(
+            &mut &b"GET /api/v1/history?range=1h&points=200 HTTP/1.1\r\nHost: 127.0.0.1:8787\r\nAuthorization: Bearer a%20b\r\n\r\n"[..],
+        )
+        .unwrap();
+        let req = parse_head(&head).unwrap();
+        assert_eq!(req.method, "GET");
+        assert_eq!(req.path, "/api/v1/history");
+        assert_eq!(req.query_param("range"), Some("1h"));
+        assert_eq!(req.query_param("points"), Some("200"));
+        assert_eq!(req.header("host"), Some("127.0.0.1:8787"));
+        assert!(parse_head("GET /\r\n").is_none());
+    }
+
+    #[test]
+    fn writes_event_stream_head_and_events() {
+        let mut out = Vec::new();
+        Response::event_stream().write_to(&mut out).unwrap();
+        write_event(&mut out, &serde_json::json!({ "cpu": 12.5 })).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.starts_with("HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n"));
+        assert!(!out.contains("Content-Length"));
+        assert!(out.ends_with("\r\n\r\ndata: {\"cpu\":12.5}\n\n"));
+    }
+
+    #[test]
+    fn rejects_oversized_and_truncated_heads() {
+        let big = format!(
+            "GET / HTTP/1.1\r\nX: {}\r\n\r\n",
+            "a".repeat(MAX_HEAD_BYTES)
+        );
+        assert_eq!(read_head(&mut big.as_bytes()), Err(431));
+        assert_eq!(read_head(&mut &b"GET / HTTP/1.1\r\n"[..]), Err(400));
+    }
+}
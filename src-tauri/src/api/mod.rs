@@ -0,0 +1,337 @@
+//! Local HTTP API
+//!
+//! Read-only JSON endpoints for scripts and other tools (Raycast, Home Assistant, shell):
+//!
+//! - `GET /api/v1/metrics` — CPU/GPU/RAM/disk percentages (`SystemMetrics`)
+//! - `GET /api/v1/cpu` — CPU details, temperature, power, battery (`CpuDetails`)
+//! - `GET /api/v1/processes?limit=20` — processes by CPU usage
+//! - `GET /api/v1/history?range=1h&points=300&metrics=cpu,net_rx` — metrics history (`5m`,
+//!   `1h`, `6h`, `24h`, `7d` or seconds; `metrics` limits the fields returned)
+//! - `GET /api/v1/history/stats?range=7d` — p50/p95/p99 and min/avg/max per metric
+//! - `GET /api/v1/summary` — host, key metrics and alert state for the multi-machine dashboard
+//! - `GET /api/v1/summary/stream` — the same summary as Server-Sent Events every
+//!   [`STREAM_INTERVAL`], until the client disconnects (the dashboard's remote tiles)
+//!
+//! Off by default (`apiEnabled`). The server only binds 127.0.0.1, rejects requests whose
+//! `Host` is not a loopback name (DNS rebinding) or that carry an `Origin` (web pages), and
+//! requires `Authorization: Bearer <token>` when `MAC_STATS_API_TOKEN` is set. `mac_stats query`
+//! (see `query`) is its command-line client.
+
+mod http;
+pub mod query;
+
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+use crate::metrics::history::{parse_range, Aggregation, METRIC_NAMES};
+use crate::metrics::{
+    get_cpu_details, get_history_stats, get_metrics, get_metrics_history, processes_by_cpu,
+};
+use crate::state::SYSTEM;
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+use http::{Request, Response};
+
+/// Concurrent connections served; more get an immediate 503
+const MAX_CONNECTIONS: usize = 16;
+/// Per-connection read/write timeout
+const IO_TIMEOUT: Duration = Duration::from_secs(5);
+/// Minimum interval between process refreshes triggered by `/processes`
+const PROCESS_REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+const DEFAULT_PROCESS_LIMIT: usize = 20;
+const MAX_PROCESS_LIMIT: usize = 500;
+const DEFAULT_HISTORY_RANGE_SECS: u64 = 3600;
+const MAX_HISTORY_POINTS: usize = 2000;
+/// Interval between `/summary/stream` events
+const STREAM_INTERVAL: Duration = Duration::from_secs(2);
+/// Concurrent event streams; each holds a connection slot for as long as it is open
+const MAX_STREAMS: usize = 8;
+
+const ENDPOINTS: &[&str] = &[
+    "/api/v1/metrics",
+    "/api/v1/cpu",
+    "/api/v1/processes",
+    "/api/v1/history",
+    "/api/v1/history/stats",
+    "/api/v1/summary",
+    "/api/v1/summary/stream",
+];
+
+static ACTIVE_CONNECTIONS: AtomicUsize = AtomicUsize::new(0);
+static ACTIVE_STREAMS: AtomicUsize = AtomicUsize::new(0);
+static LAST_PROCESS_REFRESH: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// Start the API server on `127.0.0.1:<apiPort>` when `apiEnabled` is set.
+pub fn spawn_api_server_if_enabled() {
+    if !Config::api_enabled() {
+        return;
+    }
+    let port = Config::api_port();
+    let listener = match TcpListener::bind(("127.0.0.1", port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            tracing::warn!("Local API disabled: cannot bind 127.0.0.1:{}: {}", port, e);
+            return;
+        }
+    };
+    let token = Config::api_token();
+    tracing::info!(
+        "Local API listening on http://127.0.0.1:{}/api/v1 ({})",
+        port,
+        if token.is_some() {
+            "token required"
+        } else {
+            "no token"
+        }
+    );
+    std::thread::spawn(move || serve(listener, token));
+}
+
+fn serve(listener: TcpListener, token: Option<String>) {
+    for stream in listener.incoming() {
+        let Ok(mut stream) = stream else {
+            continue;
+        };
+        if ACTIVE_CONNECTIONS.fetch_add(1, Ordering::SeqCst) >= MAX_CONNECTIONS {
+            ACTIVE_CONNECTIONS.fetch_sub(1, Ordering::SeqCst);
+            let _ = Response::error(503, "Too many connections").write_to(&mut stream);
+            continue;
+        }
+        let token = token.clone();
+        std::thread::spawn(move || {
+            handle_connection(stream, token.as_deref());
+            ACTIVE_CONNECTIONS.fetch_sub(1, Ordering::SeqCst);
+        });
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, token: Option<&str>) {
+    let _ = stream.set_read_timeout(Some(IO_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(IO_TIMEOUT));
+    let response = match http::read_head(&mut stream) {
+        Ok(head) => match http::parse_head(&head) {
+            Some(req) => respond(&req, token),
+            None => Response::error(400, "Malformed request"),
+        },
+        Err(status) => Response::error(status, "Malformed request"),
+    };
+    match response.write_to(&mut stream) {
+        Ok(()) if response.event_stream => stream_summaries(&mut stream),
+        Ok(()) => {}
+        Err(e) => debug2!("Local API: failed to write response: {}", e),
+    }
+    if response.event_stream {
+        ACTIVE_STREAMS.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Write a summary event every [`STREAM_INTERVAL`] until a write fails (client gone)
+fn stream_summaries(stream: &mut TcpStream) {
+    debug2!("Local API: summary stream opened");
+    loop {
+        let summary = crate::metrics::remote_machines::local_summary();
+        if let Err(e) = http::write_event(stream, &summary) {
+            debug2!("Local API: summary stream closed: {}", e);
+            return;
+        }
+        std::thread::sleep(STREAM_INTERVAL);
+    }
+}
+
+/// Loopback `Host` values only, with or without port (blocks DNS rebinding)
+fn is_loopback_host(host: &str) -> bool {
+    let name = match host.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or(""),
+        None => host.rsplit_once(':').map_or(host, |(name, _)| name),
+    };
+    matches!(name, "127.0.0.1" | "localhost" | "::1")
+}
+
+/// Compare without an early exit so response timing does not leak the token
+fn token_matches(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+fn respond(req: &Request, token: Option<&str>) -> Response {
+    if !req.header("host").is_some_and(is_loopback_host) {
+        return Response::error(403, "Host must be 127.0.0.1 or localhost");
+    }
+    if req.header("origin").is_some() {
+        return Response::error(403, "Cross-origin requests are not allowed");
+    }
+    if let Some(expected) = token {
+        let given = req
+            .header("authorization")
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(str::trim);
+        if !given.is_some_and(|g| token_matches(g, expected)) {
+            return Response::error(401, "Missing or invalid bearer token");
+        }
+    }
+    if req.method != "GET" {
+        return Response::error(405, "Only GET is supported");
+    }
+    debug3!("Local API: GET {}", req.path);
+    route(req)
+}
+
+fn route(req: &Request) -> Response {
+    match req.path.trim_end_matches('/') {
+        "/api/v1" => Response::json(&serde_json::json!({
+            "version": crate::metrics::get_app_version(),
+            "endpoints": ENDPOINTS,
+            "labels": crate::config::Config::labels()
+                .into_iter()
+                .collect::<std::collections::BTreeMap<_, _>>(),
+        })),
+        "/api/v1/metrics" => Response::json(&get_metrics()),
+        "/api/v1/cpu" => Response::json(&get_cpu_details()),
+        "/api/v1/processes" => {
+            let limit = match req.query_param("limit").map(str::parse::<usize>) {
+                None => DEFAULT_PROCESS_LIMIT,
+                Some(Ok(n)) if n > 0 => n.min(MAX_PROCESS_LIMIT),
+                Some(_) => return Response::error(400, "limit must be a positive integer"),
+            };
+            refresh_processes_if_stale();
+            Response::json(&processes_by_cpu(limit))
+        }
+        "/api/v1/history" => {
+            let range = match range_param(req) {
+                Ok(range) => range,
+                Err(response) => return response,
+            };
+            let points = match req.query_param("points").map(str::parse::<usize>) {
+                None => None,
+                Some(Ok(n)) if n > 0 => Some(n.min(MAX_HISTORY_POINTS)),
+                Some(_) => return Response::error(400, "points must be a positive integer"),
+            };
+            let metrics: Option<Vec<String>> = req
+                .query_param("metrics")
+                .map(|m| m.split(',').map(|s| s.trim().to_string()).collect());
+            if let Some(unknown) = metrics
+                .iter()
+                .flatten()
+                .find(|m| !METRIC_NAMES.contains(&m.as_str()))
+            {
+                return Response::error(
+                    400,
+                    &format!("Unknown metric '{}' (use {})", unknown, METRIC_NAMES.join(",")),
+                );
+            }
+            let aggregation = req.query_param("aggregation").map(str::to_string);
+            if let Some(name) = aggregation.as_deref() {
+                if Aggregation::parse(name).is_none() {
+                    return Response::error(
+                        400,
+                        &format!(
+                            "Unknown aggregation '{}' (use {})",
+                            name,
+                            Aggregation::NAMES.join(",")
+                        ),
+                    );
+                }
+            }
+            match get_metrics_history(range, points, metrics, aggregation) {
+                Ok(result) => Response::json(&result),
+                Err(e) => Response::error(503, &e),
+            }
+        }
+        "/api/v1/history/stats" => match range_param(req) {
+            Ok(range) => match get_history_stats(range) {
+                Ok(stats) => Response::json(&stats),
+                Err(e) => Response::error(503, &e),
+            },
+            Err(response) => response,
+        },
+        "/api/v1/summary" => Response::json(&crate::metrics::remote_machines::local_summary()),
+        "/api/v1/summary/stream" => {
+            if ACTIVE_STREAMS.fetch_add(1, Ordering::SeqCst) >= MAX_STREAMS {
+                ACTIVE_STREAMS.fetch_sub(1, Ordering::SeqCst);
+                return Response::error(503, "Too many event streams");
+            }
+            Response::event_stream()
+        }
+        _ => Response::error(404, "Unknown endpoint"),
+    }
+}
+
+/// `range` query parameter in seconds (default one hour)
+fn range_param(req: &Request) -> Result<u64, Response> {
+    match req.query_param("range").map(parse_range) {
+        None => Ok(DEFAULT_HISTORY_RANGE_SECS),
+        Some(Some(secs)) => Ok(secs),
+        Some(None) => Err(Response::error(
+            400,
+            "range must be like 5m, 1h, 6h, 24h, 7d or seconds",
+        )),
+    }
+}
+
+/// Per-process CPU is only refreshed while the CPU window is open; refresh here too, at most
+/// every [`PROCESS_REFRESH_INTERVAL`] however often clients poll.
+fn refresh_processes_if_stale() {
+    let Ok(mut last) = LAST_PROCESS_REFRESH.lock() else {
+        return;
+    };
+    if last.is_some_and(|t| t.elapsed() < PROCESS_REFRESH_INTERVAL) {
+        return;
+    }
+    if let Ok(mut sys) = SYSTEM.lock() {
+        let sys = sys.get_or_insert_with(sysinfo::System::new);
+        sys.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+        crate::metrics::process_history::record(sys);
+        *last = Some(Instant::now());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+
+    fn request(head: &str) -> Request {
+        http::parse_head(head).unwrap()
+    }
+
+    #[test]
+    fn rejects_foreign_hosts_origins_and_bad_tokens() {
+        let ok =
+            "GET /api/v1/nope HTTP/1.1\r\nHost: localhost:8787\r\nAuthorization: Bearer s3cret";
+        assert_eq!(respond(&request(ok), Some("s3cret")).status, 404);
+        assert_eq!(respond(&request(ok), Some("other")).status, 401);
+
+        let rebound = "GET /api/v1/metrics HTTP/1.1\r\nHost: evil.example:8787";
+        assert_eq!(respond(&request(rebound), None).status, 403);
+        let cross_origin =
+            "GET /api/v1/metrics HTTP/1.1\r\nHost: 127.0.0.1\r\nOrigin: https://evil.example";
+        assert_eq!(respond(&request(cross_origin), None).status, 403);
+        let post = "POST /api/v1/metrics HTTP/1.1\r\nHost: [::1]:8787";
+        assert_eq!(respond(&request(post), None).status, 405);
+    }
+
+    #[test]
+    fn serves_over_loopback() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        std::thread::spawn(move || serve(listener, Some("t0ken".to_string())));
+
+        let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        write!(
+            stream,
+            "GET /api/v1/cpu HTTP/1.1\r\nHost: 127.0.0.1:{port}\r\n\r\n"
+        )
+        .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 401 Unauthorized\r\n"));
+        assert!(response.ends_with("{\"error\":\"Missing or invalid bearer token\"}"));
+    }
+}
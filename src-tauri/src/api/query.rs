@@ -0,0 +1,165 @@
+//! `mac_stats query <metric>`: one value from the running app, for launcher scripts
+//!
+//! Asks the local API (`apiEnabled`) instead of sampling, so it answers in milliseconds;
+//! `mac_stats stats` is the fallback when the app is not running. Metric names are the fields
+//! of `/api/v1/metrics` (`cpu`, `gpu`, `ram`, `disk`, plugin values such as `queue.depth`) and
+//! `/api/v1/cpu` (`temperature`, `fan_rpm`, `battery_level`, ...), plus the short names of the
+//! menu bar template (`temp`, `fan`, `battery`, ...). `--format "🔥 {value}%"` wraps the value
+//! for Raycast / Alfred / SwiftBar output.
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::time::Duration;
+
+use crate::config::Config;
+
+/// Connect / read timeout; the app answers from memory
+const TIMEOUT: Duration = Duration::from_millis(800);
+
+/// Short names and the `/api/v1/cpu` field they read
+const ALIASES: &[(&str, &str)] = &[
+    ("temp", "temperature"),
+    ("gpu_temp", "gpu_temperature"),
+    ("freq", "frequency"),
+    ("power", "cpu_power"),
+    ("fan", "fan_rpm"),
+    ("battery", "battery_level"),
+    ("load", "load_1"),
+    ("uptime", "uptime_secs"),
+];
+
+/// Fields of `/api/v1/metrics`; everything else is looked up in `/api/v1/cpu`
+const METRICS_FIELDS: &[&str] = &["cpu", "gpu", "ram", "disk"];
+
+/// Arguments for `mac_stats query`
+#[derive(clap::Args, Debug)]
+pub struct QueryArgs {
+    /// Metric name, e.g. cpu, ram, temp, fan, battery or a plugin value (`queue.depth`)
+    pub metric: String,
+    /// Output template; `{value}` is replaced, e.g. "🔥 {value}°"
+    #[arg(long, short)]
+    pub format: Option<String>,
+    /// Decimal places
+    #[arg(long, default_value_t = 0)]
+    pub decimals: usize,
+}
+
+/// API path and JSON pointer for `metric`
+fn source(metric: &str) -> (&'static str, String) {
+    let metric = ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == metric)
+        .map_or(metric, |(_, field)| *field);
+    if METRICS_FIELDS.contains(&metric) {
+        ("/api/v1/metrics", format!("/{}", metric))
+    } else if metric.contains('.') {
+        (
+            "/api/v1/metrics",
+            format!("/plugins/{}", metric.replace('~', "~0").replace('/', "~1")),
+        )
+    } else {
+        ("/api/v1/cpu", format!("/{}", metric))
+    }
+}
+
+fn render(value: &serde_json::Value, decimals: usize, format: Option<&str>) -> Option<String> {
+    let text = match value {
+        serde_json::Value::Number(n) => format!("{:.*}", decimals, n.as_f64()?),
+        serde_json::Value::Bool(b) => b.to_string(),
+        serde_json::Value::String(s) => s.clone(),
+        _ => return None,
+    };
+    Some(match format {
+        Some(template) => template.replace("{value}", &text),
+        None => text,
+    })
+}
+
+/// `GET path` on the local API; the body on 200, else the API's error message
+fn get(path: &str) -> Result<String, String> {
+    let port = Config::api_port();
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    let mut stream = TcpStream::connect_timeout(&addr, TIMEOUT).map_err(|_| {
+        format!(
+            "mac-stats is not answering on 127.0.0.1:{} (is the app running with apiEnabled?)",
+            port
+        )
+    })?;
+    let _ = stream.set_read_timeout(Some(TIMEOUT));
+    let _ = stream.set_write_timeout(Some(TIMEOUT));
+    let mut request = format!("GET {} HTTP/1.1\r\nHost: 127.0.0.1:{}\r\n", path, port);
+    if let Some(token) = Config::api_token() {
+        request.push_str(&format!("Authorization: Bearer {}\r\n", token));
+    }
+    request.push_str("Connection: close\r\n\r\n");
+    stream
+        .write_all(request.as_bytes())
+        .map_err(|e| e.to_string())?;
+    let mut response = String::new();
+    stream
+        .read_to_string(&mut response)
+        .map_err(|e| e.to_string())?;
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or("Malformed response from the local API")?;
+    let status = head.split_whitespace().nth(1).unwrap_or_default();
+    if status == "200" {
+        return Ok(body.to_string());
+    }
+    let message = serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|v| v.get("error")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| format!("HTTP {}", status));
+    Err(format!("Local API: {}", message))
+}
+
+/// Run `mac_stats query`. Returns Err(exit_code) on failure: 1 when the app cannot be reached,
+/// 2 for an unknown metric.
+pub fn run_cli(args: QueryArgs) -> Result<(), i32> {
+    let metric = args.metric.trim();
+    let (path, pointer) = source(metric);
+    let body = get(path).map_err(|e| {
+        eprintln!("Error: {}", e);
+        1
+    })?;
+    let json: serde_json::Value = serde_json::from_str(&body).map_err(|e| {
+        eprintln!("Error: {}", e);
+        1
+    })?;
+    match json
+        .pointer(&pointer)
+        .and_then(|v| render(v, args.decimals, args.format.as_deref()))
+    {
+        Some(text) => {
+            println!("{}", text);
+            Ok(())
+        }
+        None => {
+            eprintln!("Error: unknown metric '{}'", metric);
+            Err(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_aliases_and_formats() {
+        assert_eq!(source("ram"), ("/api/v1/metrics", "/ram".to_string()));
+        assert_eq!(source("temp"), ("/api/v1/cpu", "/temperature".to_string()));
+        assert_eq!(
+            source("queue.depth"),
+            ("/api/v1/metrics", "/plugins/queue.depth".to_string())
+        );
+
+        let value = serde_json::json!(52.46);
+        assert_eq!(render(&value, 0, None).as_deref(), Some("52"));
+        assert_eq!(
+            render(&value, 1, Some("🔥 {value}°")).as_deref(),
+            Some("🔥 52.5°")
+        );
+        assert_eq!(render(&serde_json::json!([1]), 0, None), None);
+    }
+}
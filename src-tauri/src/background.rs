@@ -0,0 +1,148 @@
+//! One tokio runtime for the periodic collectors
+//!
+//! The sampler and its CPU-window sensors, process watch, memory pressure, fan speed and metric
+//! snapshots each run as a task here, on their own `tokio::time::interval` cadence, instead of
+//! threads sleeping in a loop. First ticks get a random jitter so collectors with the same
+//! period don't all wake together, and [`shutdown`] cancels every task at its next await and
+//! waits (briefly) for them to end, so state they own (e.g. an SMC connection) is dropped.
+//!
+//! Collector work is blocking (SMC, IOKit, sysinfo, file I/O), so it runs in
+//! [`tokio::task::block_in_place`]; the runtime moves other tasks off that worker meanwhile.
+
+use std::collections::hash_map::RandomState;
+use std::future::Future;
+use std::hash::BuildHasher;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use tokio::runtime::Runtime;
+use tokio::time::{Instant, Interval, MissedTickBehavior};
+use tokio_util::sync::CancellationToken;
+
+/// Worker threads; collectors mostly wait, two keep one slow read from delaying the rest
+const WORKERS: usize = 2;
+/// Longest jitter added to a first tick (otherwise a tenth of the period)
+const MAX_JITTER: Duration = Duration::from_secs(2);
+/// How long [`shutdown`] waits for tasks still in a blocking read
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(1);
+
+static RUNTIME: LazyLock<Option<Runtime>> = LazyLock::new(|| {
+    match tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(WORKERS)
+        .thread_name("mac-stats-collector")
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => Some(runtime),
+        Err(e) => {
+            tracing::error!(target: "mac_stats::metrics", "Could not start collector runtime: {}", e);
+            None
+        }
+    }
+});
+
+static SHUTDOWN: LazyLock<CancellationToken> = LazyLock::new(CancellationToken::new);
+/// Tasks spawned and not yet ended
+static RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts a task in [`RUNNING`] until dropped (also when the task panics)
+struct Running;
+
+impl Running {
+    fn start() -> Self {
+        RUNNING.fetch_add(1, Ordering::SeqCst);
+        Running
+    }
+}
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        RUNNING.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Run `task` on the collector runtime until it ends or [`shutdown`]; false when the runtime
+/// is unavailable or already shut down
+pub(crate) fn spawn<F>(name: &'static str, task: F) -> bool
+where
+    F: Future<Output = ()> + Send + 'static,
+{
+    let Some(runtime) = RUNTIME.as_ref() else {
+        return false;
+    };
+    if SHUTDOWN.is_cancelled() {
+        return false;
+    }
+    let cancel = SHUTDOWN.clone();
+    let running = Running::start();
+    runtime.spawn(async move {
+        let _running = running;
+        tokio::select! {
+            _ = cancel.cancelled() => debug2!("Collector {} cancelled", name),
+            _ = task => debug2!("Collector {} finished", name),
+        }
+    });
+    true
+}
+
+/// Call `collect` every `period` (the first call one period plus jitter after start) until it
+/// returns false
+pub(crate) fn every<F>(name: &'static str, period: Duration, mut collect: F) -> bool
+where
+    F: FnMut() -> bool + Send + 'static,
+{
+    spawn(name, async move {
+        let mut ticks = interval(period, period);
+        loop {
+            ticks.tick().await;
+            if !tokio::task::block_in_place(&mut collect) {
+                return;
+            }
+        }
+    })
+}
+
+/// Ticks every `period`, the first after `first` plus jitter; late ticks are delayed rather
+/// than bunched up (e.g. after the Mac slept)
+pub(crate) fn interval(first: Duration, period: Duration) -> Interval {
+    let mut ticks = tokio::time::interval_at(Instant::now() + first + jitter(period), period);
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    ticks
+}
+
+/// Random delay up to a tenth of `period` (at most [`MAX_JITTER`])
+fn jitter(period: Duration) -> Duration {
+    let max = (period / 10).min(MAX_JITTER);
+    // RandomState is seeded per instance; enough randomness to spread timers
+    let random = RandomState::new().hash_one(std::time::Instant::now());
+    max.mul_f64((random % 1000) as f64 / 1000.0)
+}
+
+/// Cancel every collector task and wait up to [`SHUTDOWN_TIMEOUT`] for them to end; later
+/// [`spawn`] calls do nothing. False when some task was still busy at the timeout.
+pub(crate) fn shutdown() -> bool {
+    SHUTDOWN.cancel();
+    let started = std::time::Instant::now();
+    while RUNNING.load(Ordering::SeqCst) > 0 {
+        if started.elapsed() >= SHUTDOWN_TIMEOUT {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jitter_stays_within_a_tenth_of_the_period() {
+        for _ in 0..100 {
+            assert!(jitter(Duration::from_secs(5)) <= Duration::from_millis(500));
+            assert!(jitter(Duration::from_secs(600)) <= MAX_JITTER);
+        }
+        assert_eq!(jitter(Duration::ZERO), Duration::ZERO);
+    }
+}
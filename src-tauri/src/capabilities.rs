@@ -0,0 +1,189 @@
+//! What this Mac lets mac-stats read
+//!
+//! [`probe`] checks each hardware source once at launch (and again when the frontend asks with
+//! `get_capabilities(refresh: true)`): SMC (CPU / GPU temperature, fans), IOReport (CPU
+//! frequency and power channels), `powermetrics` and the battery. Collectors correct the
+//! result with [`update`] as they go (e.g. no GPU temperature key turned up on the first SMC
+//! scan), and `get_cpu_details` reports [`get`] instead of each collector keeping its own flag.
+//!
+//! [`apple_silicon`] decides the code path: Intel Macs have no IOReport performance-state or
+//! energy channels, so the sampler reads the base clock from `hw.cpufrequency`, uses the Intel
+//! SMC temperature keys (`TC0P`, …) and the `IOAccelerator` GPU statistics, and reports power
+//! as unavailable instead of zeros.
+
+use std::path::Path;
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+
+use macsmc::Smc;
+use serde::Serialize;
+
+use crate::metrics::gpu_temperature;
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+/// Installed on every macOS; running it needs root
+const POWERMETRICS: &str = "/usr/bin/powermetrics";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Capabilities {
+    /// arm64 Mac, also when an x86 build runs under Rosetta; false on Intel
+    pub apple_silicon: bool,
+    /// SMC opens and reports a CPU temperature
+    pub temperature: bool,
+    /// SMC has at least one GPU cluster temperature key
+    pub gpu_temperature: bool,
+    /// SMC reports a fan (false on fanless Macs)
+    pub fan: bool,
+    /// IOReport has CPU performance-state channels (Intel: `hw.cpufrequency` reads)
+    pub frequency: bool,
+    /// IOReport has energy channels for the CPU
+    pub cpu_power: bool,
+    /// IOReport has energy channels for the GPU
+    pub gpu_power: bool,
+    /// `powermetrics` is installed
+    pub powermetrics: bool,
+    /// A battery is present
+    pub battery: bool,
+}
+
+impl Capabilities {
+    /// One line for the launch log, e.g. `temperature=yes fan=no …`
+    pub fn summary(&self) -> String {
+        let yes_no = |b: bool| if b { "yes" } else { "no" };
+        [
+            ("appleSilicon", self.apple_silicon),
+            ("temperature", self.temperature),
+            ("gpuTemperature", self.gpu_temperature),
+            ("fan", self.fan),
+            ("frequency", self.frequency),
+            ("cpuPower", self.cpu_power),
+            ("gpuPower", self.gpu_power),
+            ("powermetrics", self.powermetrics),
+            ("battery", self.battery),
+        ]
+        .iter()
+        .map(|(name, b)| format!("{}={}", name, yes_no(*b)))
+        .collect::<Vec<_>>()
+        .join(" ")
+    }
+}
+
+/// Latest probe result with collector corrections; all false until the first [`probe`]
+static CAPABILITIES: Mutex<Capabilities> = Mutex::new(Capabilities {
+    apple_silicon: false,
+    temperature: false,
+    gpu_temperature: false,
+    fan: false,
+    frequency: false,
+    cpu_power: false,
+    gpu_power: false,
+    powermetrics: false,
+    battery: false,
+});
+
+static APPLE_SILICON: OnceLock<bool> = OnceLock::new();
+
+/// Whether this is an Apple Silicon Mac (`hw.optional.arm64`; checked once)
+pub fn apple_silicon() -> bool {
+    *APPLE_SILICON.get_or_init(|| {
+        Command::new("/usr/sbin/sysctl")
+            .args(["-n", "hw.optional.arm64"])
+            .stderr(std::process::Stdio::null())
+            .output()
+            .is_ok_and(|out| {
+                out.status.success() && String::from_utf8_lossy(&out.stdout).trim() == "1"
+            })
+    })
+}
+
+/// Current capabilities (cheap; does not probe)
+pub fn get() -> Capabilities {
+    CAPABILITIES.lock().map(|c| *c).unwrap_or_default()
+}
+
+/// Record what a collector found out (e.g. the first IOReport power read worked)
+pub(crate) fn update(change: impl FnOnce(&mut Capabilities)) {
+    if let Ok(mut capabilities) = CAPABILITIES.lock() {
+        change(&mut capabilities);
+    }
+}
+
+/// Check every source and store the result. Blocking: opens an SMC connection and scans its
+/// keys once for GPU temperature.
+pub fn probe() -> Capabilities {
+    let apple_silicon = apple_silicon();
+    // Intel: the base clock from hw.cpufrequency; no IOReport energy channels
+    let frequency = if apple_silicon {
+        crate::ffi::ioreport::probe_cpu_performance_channels_available()
+    } else {
+        crate::metrics::get_nominal_frequency() > 0.0
+    };
+    let power = apple_silicon && crate::ffi::ioreport::probe_power_channels_available();
+    let mut found = Capabilities {
+        apple_silicon,
+        frequency,
+        cpu_power: power,
+        gpu_power: power,
+        powermetrics: Path::new(POWERMETRICS).is_file(),
+        battery: has_battery(),
+        ..Default::default()
+    };
+    match Smc::connect() {
+        Ok(mut smc) => {
+            found.temperature = smc.cpu_temperature().is_ok();
+            found.fan = mac_stats_collector::smc::fan_rpm(&mut smc).is_some();
+            found.gpu_temperature = gpu_temperature::read(&mut smc).is_some();
+        }
+        Err(e) => debug2!("Capabilities: SMC connection failed: {:?}", e),
+    }
+    update(|c| *c = found);
+    found
+}
+
+/// [`probe`] and log the result (launch)
+pub fn probe_and_log() {
+    let found = probe();
+    tracing::info!(
+        target: "mac_stats::metrics",
+        "Capabilities: {}",
+        found.summary()
+    );
+}
+
+fn has_battery() -> bool {
+    battery::Manager::new()
+        .and_then(|manager| manager.batteries())
+        .map(|mut batteries| batteries.next().is_some())
+        .unwrap_or(false)
+}
+
+/// What this Mac lets mac-stats read; `refresh` probes again first
+#[tauri::command]
+pub async fn get_capabilities(refresh: Option<bool>) -> Result<Capabilities, String> {
+    if !refresh.unwrap_or(false) {
+        return Ok(get());
+    }
+    tokio::task::spawn_blocking(probe)
+        .await
+        .map_err(|e| format!("Capability probe failed: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_lists_every_capability() {
+        let caps = Capabilities {
+            fan: true,
+            battery: true,
+            ..Default::default()
+        };
+        let summary = caps.summary();
+        assert!(summary.starts_with("appleSilicon=no temperature=no gpuTemperature=no fan=yes"));
+        assert!(summary.ends_with("powermetrics=no battery=yes"));
+        assert_eq!(summary.split(' ').count(), 9);
+    }
+}
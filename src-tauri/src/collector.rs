@@ -0,0 +1,40 @@
+//! Metric collection as a library API
+//!
+//! The engine lives in the `mac_stats_collector` workspace crate (`src-tauri/collector`):
+//! [`Collector`] samples sysinfo CPU/RAM/disk and process counters, SMC temperatures and fan
+//! speed, and IOReport power and frequency over its own subscriptions. That crate has no Tauri,
+//! AppKit or other app dependency; depend on it directly to collect without pulling in the app.
+//! This module re-exports it and adds the history the app saved, which needs the app's store.
+//!
+//! ```no_run
+//! use mac_stats::collector::{self, Collector};
+//!
+//! let mut collector = Collector::new();
+//! let sample = collector.sample();
+//! println!("CPU {:.1}%  RAM {:.1}%", sample.cpu, sample.ram);
+//! let day = collector::history_since(chrono::Utc::now().timestamp() - 86400)?;
+//! println!("{} points in the last 24h", day.points.len());
+//! # Ok::<(), String>(())
+//! ```
+
+pub use mac_stats_collector::*;
+
+pub use crate::metrics::history::{GapReason, HistoryGap, MetricPoint};
+
+use crate::metrics::history_store;
+
+/// Persisted history (see [`history_since`])
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    /// Oldest first; older ranges come from coarser tiers
+    pub points: Vec<MetricPoint>,
+    /// Sleep and app-not-running stretches without points
+    pub gaps: Vec<HistoryGap>,
+}
+
+/// History recorded by the app from `start` (Unix seconds) on, read-only from `~/.mac-stats`
+/// (SQLite store, or snapshot and log)
+pub fn history_since(start: i64) -> Result<History, String> {
+    let (points, gaps) = history_store::load_persisted_since(start)?;
+    Ok(History { points, gaps })
+}
@@ -87,18 +87,21 @@ fn write_agent_file(dir: &Path, filename: &str, content: &str) -> Result<(), Str
 
 #[tauri::command]
 pub fn update_agent_skill(agent_id: String, content: String) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Changing agents")?;
     let dir = get_agent_dir(&agent_id).ok_or_else(|| format!("Agent not found: {}", agent_id))?;
     write_agent_file(&dir, "skill.md", &content)
 }
 
 #[tauri::command]
 pub fn update_agent_soul(agent_id: String, content: String) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Changing agents")?;
     let dir = get_agent_dir(&agent_id).ok_or_else(|| format!("Agent not found: {}", agent_id))?;
     write_agent_file(&dir, "soul.md", content.trim())
 }
 
 #[tauri::command]
 pub fn update_agent_mood(agent_id: String, content: String) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Changing agents")?;
     let dir = get_agent_dir(&agent_id).ok_or_else(|| format!("Agent not found: {}", agent_id))?;
     write_agent_file(&dir, "mood.md", content.trim())
 }
@@ -120,6 +123,7 @@ pub fn update_agent_config(
     agent_id: String,
     payload: UpdateAgentConfigPayload,
 ) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Changing agents")?;
     let dir = get_agent_dir(&agent_id).ok_or_else(|| format!("Agent not found: {}", agent_id))?;
     let path = dir.join("agent.json");
     let current: AgentConfig = {
@@ -169,6 +173,7 @@ pub struct CreateAgentPayload {
 
 #[tauri::command]
 pub fn create_agent(payload: CreateAgentPayload) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Changing agents")?;
     let id = payload.id.trim();
     if id.is_empty() {
         return Err("Agent id is required".to_string());
@@ -205,6 +210,7 @@ pub fn create_agent(payload: CreateAgentPayload) -> Result<(), String> {
 
 #[tauri::command]
 pub fn delete_agent(agent_id: String) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Changing agents")?;
     let dir = get_agent_dir(&agent_id).ok_or_else(|| format!("Agent not found: {}", agent_id))?;
     std::fs::remove_dir_all(&dir).map_err(|e| format!("Failed to delete agent: {}", e))
 }
@@ -224,11 +230,13 @@ fn set_agent_enabled(agent_id: &str, enabled: bool) -> Result<(), String> {
 
 #[tauri::command]
 pub fn disable_agent(agent_id: String) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Changing agents")?;
     set_agent_enabled(&agent_id, false)
 }
 
 #[tauri::command]
 pub fn enable_agent(agent_id: String) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Changing agents")?;
     set_agent_enabled(&agent_id, true)
 }
 
@@ -268,6 +276,7 @@ pub fn list_prompt_files() -> Vec<PromptFile> {
 /// Save content to a named prompt file. Name must be one of: soul, planning_prompt, execution_prompt.
 #[tauri::command]
 pub fn save_prompt_file(name: String, content: String) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Changing agents")?;
     let path = match name.as_str() {
         "soul" => Config::soul_file_path(),
         "planning_prompt" => Config::planning_prompt_path(),
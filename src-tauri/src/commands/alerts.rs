@@ -15,6 +15,7 @@ fn get_alert_manager() -> &'static Mutex<AlertManager> {
 /// Add an alert
 #[tauri::command]
 pub fn add_alert(alert: Alert) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Changing alerts")?;
     get_alert_manager()
         .lock()
         .map_err(|e| e.to_string())?
@@ -26,6 +27,7 @@ pub fn add_alert(alert: Alert) -> Result<(), String> {
 /// Remove an alert
 #[tauri::command]
 pub fn remove_alert(alert_id: String) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Changing alerts")?;
     get_alert_manager()
         .lock()
         .map_err(|e| e.to_string())?
@@ -47,6 +49,7 @@ pub fn evaluate_alerts(context: AlertContext) -> Result<Vec<String>, String> {
 /// Register a Telegram channel for alerts. Store the bot token in Keychain under `telegram_bot_{id}`.
 #[tauri::command]
 pub fn register_telegram_channel(id: String, chat_id: String) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Changing alerts")?;
     let channel = TelegramChannel::new(id.clone(), chat_id);
     get_alert_manager()
         .lock()
@@ -58,6 +61,7 @@ pub fn register_telegram_channel(id: String, chat_id: String) -> Result<(), Stri
 /// Register a Slack channel for alerts. Store the webhook URL in Keychain under `slack_webhook_{id}`.
 #[tauri::command]
 pub fn register_slack_channel(id: String) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Changing alerts")?;
     let channel = SlackChannel::new(id.clone());
     get_alert_manager()
         .lock()
@@ -69,6 +73,7 @@ pub fn register_slack_channel(id: String) -> Result<(), String> {
 /// Register a Mastodon channel for alerts. Store the API token in Keychain under `mastodon_alert_{id}`.
 #[tauri::command]
 pub fn register_mastodon_channel(id: String, instance_url: String) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Changing alerts")?;
     let channel = MastodonChannel::new(id.clone(), instance_url);
     get_alert_manager()
         .lock()
@@ -80,6 +85,7 @@ pub fn register_mastodon_channel(id: String, instance_url: String) -> Result<(),
 /// Remove an alert channel by id (Telegram, Slack, or Mastodon).
 #[tauri::command]
 pub fn remove_alert_channel(channel_id: String) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Changing alerts")?;
     get_alert_manager()
         .lock()
         .map_err(|e| e.to_string())?
@@ -96,16 +102,37 @@ pub fn list_alert_channels() -> Result<Vec<String>, String> {
         .list_channel_ids())
 }
 
+/// Load `alertRules` and `alertDigestMinutes` now instead of at the next periodic evaluation
+/// (config hot reload)
+pub fn sync_configured_alert_rules() {
+    let digest_minutes = crate::config::Config::alert_digest_minutes();
+    let configured_rules = crate::config::Config::alert_rules();
+    match get_alert_manager().lock() {
+        Ok(mut manager) => {
+            manager.set_digest_minutes(&digest_minutes);
+            manager.sync_configured_rules(&configured_rules);
+        }
+        Err(e) => tracing::debug!("Alert: rules not synced: {}", e),
+    }
+}
+
 /// Run alert evaluation in the background. Builds context from current metrics and monitor
 /// statuses, then evaluates all alerts. Called periodically from a background thread so
 /// SiteDown, BatteryLow, TemperatureHigh, CpuHigh etc. can fire without user action.
+/// Also loads the threshold rules from `alertRules` and sends the alert digests that are due
+/// (`alertDigestMinutes`).
 pub fn run_periodic_alert_evaluation() {
     use tracing::debug;
 
+    // Keep the battery drain window filled while the CPU window is closed
+    crate::metrics::battery_drain::sample();
+
     // Build context data without holding the alert manager lock (metrics can be slow)
     let system_metrics = Some(crate::metrics::get_metrics());
     let cpu_details = Some(crate::metrics::get_cpu_details());
     let monitor_snapshot = crate::commands::monitors::get_monitor_statuses_snapshot();
+    let digest_minutes = crate::config::Config::alert_digest_minutes();
+    let configured_rules = crate::config::Config::alert_rules();
 
     let mut manager = match get_alert_manager().try_lock() {
         Ok(m) => m,
@@ -114,8 +141,10 @@ pub fn run_periodic_alert_evaluation() {
             return;
         }
     };
+    manager.set_digest_minutes(&digest_minutes);
+    manager.sync_configured_rules(&configured_rules);
 
-    // System-only context for BatteryLow, TemperatureHigh, CpuHigh
+    // System-only context for the battery, temperature, CPU and configured threshold rules
     let ctx_system = AlertContext {
         monitor_id: None,
         monitor_status: None,
@@ -143,4 +172,9 @@ pub fn run_periodic_alert_evaluation() {
             );
         }
     }
+
+    let sent = manager.flush_digests(chrono::Utc::now());
+    if sent > 0 {
+        debug!("Alert: sent {} digest message(s)", sent);
+    }
 }
@@ -688,6 +688,7 @@ fn fetch_page_post_form_urlencoded_with_context(
 /// Tauri command: fetch a URL and return body as text (for frontend or tools).
 #[tauri::command]
 pub async fn fetch_page(url: String) -> Result<String, String> {
+    crate::guest_mode::ensure_allowed("Agent web access")?;
     let url = url.clone();
     tokio::task::spawn_blocking(move || fetch_page_content(&url))
         .await
@@ -0,0 +1,265 @@
+//! Confirmation tokens for destructive Tauri commands
+//!
+//! Commands that kill processes, rewrite config.json or act through the privileged helper take
+//! a `confirmation_token`. The frontend
+//! obtains one from `request_confirmation(action, target)`, which first shows a native dialog
+//! (from Rust, naming the action and its target) and only issues a token when the user clicks
+//! Confirm; the webview cannot answer that dialog itself. Tokens are random, single-use, bound to
+//! one action and target (e.g. `force_quit` on a PID) and expire after [`TOKEN_TTL_SECS`], so a
+//! buggy or injected page script cannot act without the user seeing what it asks for, replay a
+//! confirmation or reuse it for a different process or setting.
+//!
+//! Settings kept outside config.json (Keychain credentials from the Discord, Ollama and
+//! Perplexity panels, `monitors.json`, in-memory alert channels) are only behind guest mode.
+//! New destructive commands should add their action to [`ACTIONS`] and call
+//! [`require_confirmation`] before doing anything.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+/// How long an issued token stays valid
+const TOKEN_TTL_SECS: u64 = 30;
+/// Upper bound on outstanding tokens (oldest are dropped first)
+const MAX_PENDING_TOKENS: usize = 32;
+
+/// Actions that require a confirmation token, with the text of their dialog
+const ACTIONS: &[(&str, &str)] = &[
+    (
+        "force_quit",
+        "Force quit this process? Unsaved work in it is lost.",
+    ),
+    ("config_write", "Change this setting?"),
+    (
+        "config_import_commands",
+        "Import these settings including their shell commands (hooks and click actions)? \
+         They run on this Mac.",
+    ),
+    (
+        "config_reset",
+        "Reset settings to monitor defaults? Keychain secrets are kept.",
+    ),
+    ("launch_at_login", "Change launch at login?"),
+    (
+        "privileged_helper",
+        "Install or remove the privileged helper? macOS asks for an administrator's approval.",
+    ),
+    (
+        "fan_control",
+        "Override this fan? A low fixed speed can let the Mac run hot under load.",
+    ),
+    ("low_power_mode", "Change Low Power Mode?"),
+    ("maintenance", "Run this maintenance action?"),
+];
+
+struct PendingConfirmation {
+    action: String,
+    target: String,
+    issued_at: Instant,
+}
+
+static PENDING: Mutex<Option<HashMap<String, PendingConfirmation>>> = Mutex::new(None);
+/// One dialog at a time, so a page script cannot stack up prompts
+static DIALOG_OPEN: AtomicBool = AtomicBool::new(false);
+
+/// Token handed to the frontend by `request_confirmation`
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfirmationChallenge {
+    pub token: String,
+    pub action: String,
+    pub target: String,
+    /// Text of the dialog the user confirmed
+    pub prompt: String,
+    pub expires_in_secs: u64,
+}
+
+fn random_token() -> Result<String, String> {
+    let mut bytes = [0u8; 16];
+    std::fs::File::open("/dev/urandom")
+        .and_then(|mut f| f.read_exact(&mut bytes))
+        .map_err(|e| format!("Could not generate confirmation token: {}", e))?;
+    Ok(bytes.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+fn prompt_for(action: &str) -> Result<String, String> {
+    ACTIONS
+        .iter()
+        .find(|(a, _)| *a == action)
+        .map(|(_, prompt)| prompt.to_string())
+        .ok_or_else(|| format!("Unknown confirmation action: {}", action))
+}
+
+/// What the dialog says the action applies to; a PID is shown with its process name
+fn describe_target(action: &str, target: &str) -> String {
+    if action == "force_quit" {
+        if let Ok(pid) = target.parse::<u32>() {
+            let pid = sysinfo::Pid::from_u32(pid);
+            let mut system = sysinfo::System::new();
+            system.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[pid]), true);
+            if let Some(process) = system.process(pid) {
+                return format!(
+                    "Process: {} (PID {})",
+                    process.name().to_string_lossy(),
+                    target
+                );
+            }
+        }
+        return format!("Process: PID {}", target);
+    }
+    format!("Target: {}", target)
+}
+
+/// Show the native confirmation dialog; true when the user clicked Confirm
+async fn ask(app: &tauri::AppHandle, prompt: &str, target: &str) -> Result<bool, String> {
+    use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+
+    if DIALOG_OPEN.swap(true, Ordering::SeqCst) {
+        return Err("Another confirmation is already open".to_string());
+    }
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    app.dialog()
+        .message(format!("{}\n\n{}", prompt, target))
+        .title("mac-stats")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Confirm".to_string(),
+            "Cancel".to_string(),
+        ))
+        .show(move |confirmed| {
+            let _ = tx.send(confirmed);
+        });
+    let confirmed = rx.await.unwrap_or(false);
+    DIALOG_OPEN.store(false, Ordering::SeqCst);
+    Ok(confirmed)
+}
+
+fn issue(action: &str, target: &str, now: Instant) -> Result<ConfirmationChallenge, String> {
+    let prompt = prompt_for(action)?;
+    let token = random_token()?;
+
+    let mut guard = PENDING.lock().map_err(|e| e.to_string())?;
+    let pending = guard.get_or_insert_with(HashMap::new);
+    let ttl = Duration::from_secs(TOKEN_TTL_SECS);
+    pending.retain(|_, p| now.saturating_duration_since(p.issued_at) < ttl);
+    while pending.len() >= MAX_PENDING_TOKENS {
+        let Some(oldest) = pending
+            .iter()
+            .min_by_key(|(_, p)| p.issued_at)
+            .map(|(t, _)| t.clone())
+        else {
+            break;
+        };
+        pending.remove(&oldest);
+    }
+    pending.insert(
+        token.clone(),
+        PendingConfirmation {
+            action: action.to_string(),
+            target: target.to_string(),
+            issued_at: now,
+        },
+    );
+    Ok(ConfirmationChallenge {
+        token,
+        action: action.to_string(),
+        target: target.to_string(),
+        prompt,
+        expires_in_secs: TOKEN_TTL_SECS,
+    })
+}
+
+fn consume(token: &str, action: &str, target: &str, now: Instant) -> Result<(), String> {
+    let mut guard = PENDING.lock().map_err(|e| e.to_string())?;
+    // Single use: the token is gone whether or not it matches.
+    let pending = guard
+        .as_mut()
+        .and_then(|p| p.remove(token))
+        .ok_or_else(|| "Confirmation required (missing or already used token)".to_string())?;
+    if now.saturating_duration_since(pending.issued_at) >= Duration::from_secs(TOKEN_TTL_SECS) {
+        return Err("Confirmation expired; please confirm again".to_string());
+    }
+    if pending.action != action || pending.target != target {
+        return Err(format!(
+            "Confirmation was for {} {}, not {} {}",
+            pending.action, pending.target, action, target
+        ));
+    }
+    Ok(())
+}
+
+/// Check and consume the token for `action` on `target`. Call first in destructive commands.
+pub(crate) fn require_confirmation(
+    token: Option<&str>,
+    action: &str,
+    target: &str,
+) -> Result<(), String> {
+    let result = match token {
+        Some(token) => consume(token, action, target, Instant::now()),
+        None => Err("Confirmation required".to_string()),
+    };
+    if let Err(ref e) = result {
+        tracing::warn!(
+            "Blocked {} on {} without valid confirmation: {}",
+            action,
+            target,
+            e
+        );
+    }
+    result
+}
+
+/// Ask the user to confirm `action` on `target` (e.g. `force_quit`, `"1234"`) in a native dialog
+/// and issue a single-use token when they do; fails with "Cancelled" otherwise.
+#[tauri::command]
+pub async fn request_confirmation(
+    app: tauri::AppHandle,
+    action: String,
+    target: String,
+) -> Result<ConfirmationChallenge, String> {
+    let prompt = prompt_for(&action)?;
+    if !ask(&app, &prompt, &describe_target(&action, &target)).await? {
+        debug2!("Confirmation declined for {} on {}", action, target);
+        return Err("Cancelled".to_string());
+    }
+    debug2!("Confirmation token issued for {} on {}", action, target);
+    issue(&action, &target, Instant::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_is_single_use_and_bound_to_target() {
+        let now = Instant::now();
+        let c = issue("force_quit", "4242", now).unwrap();
+        assert_eq!(c.token.len(), 32);
+        assert!(consume(&c.token, "force_quit", "4243", now).is_err());
+        // The mismatched attempt burned the token.
+        assert!(consume(&c.token, "force_quit", "4242", now).is_err());
+
+        let c = issue("force_quit", "4242", now).unwrap();
+        assert!(consume(&c.token, "force_quit", "4242", now).is_ok());
+        assert!(consume(&c.token, "force_quit", "4242", now).is_err());
+    }
+
+    #[test]
+    fn token_expires() {
+        let now = Instant::now();
+        let c = issue("config_write", "menuBarCompact", now).unwrap();
+        let later = now + Duration::from_secs(TOKEN_TTL_SECS + 1);
+        assert!(consume(&c.token, "config_write", "menuBarCompact", later).is_err());
+    }
+
+    #[test]
+    fn unknown_actions_and_missing_tokens_are_rejected() {
+        assert!(issue("format_disk", "/", Instant::now()).is_err());
+        assert!(require_confirmation(None, "force_quit", "1").is_err());
+    }
+}
@@ -17,6 +17,7 @@ enum ConfigureOutcome {
 /// Keychain runs on the command thread so all logs appear in order.
 #[tauri::command]
 pub async fn configure_discord(token: Option<String>) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Changing Discord settings")?;
     let has_token = token
         .as_ref()
         .map(|s| !s.trim().is_empty())
@@ -75,6 +76,7 @@ pub fn is_discord_gateway_ready() -> Result<bool, String> {
 /// Returns the desired online state after the call.
 #[tauri::command]
 pub fn set_discord_gateway_enabled(enabled: bool) -> Result<bool, String> {
+    crate::guest_mode::ensure_allowed("Changing Discord settings")?;
     Ok(crate::discord::set_discord_gateway_enabled(enabled))
 }
 
@@ -72,6 +72,7 @@ pub fn read_downloads_organizer_rules() -> Result<String, String> {
 
 #[tauri::command]
 pub fn save_downloads_organizer_rules(content: String) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Changing the downloads organizer")?;
     if let Err(e) = downloads_organizer::parse_rules_markdown(&content) {
         return Err(format!("Invalid rules: {}", e));
     }
@@ -129,7 +130,14 @@ pub fn get_downloads_organizer_status() -> DownloadsOrganizerStatus {
 #[tauri::command]
 pub fn set_downloads_organizer_settings(
     patch: DownloadsOrganizerSettingsPatch,
+    confirmation_token: Option<String>,
 ) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Changing the downloads organizer")?;
+    super::confirmation::require_confirmation(
+        confirmation_token.as_deref(),
+        "config_write",
+        "downloadsOrganizer",
+    )?;
     let before = read_config_value();
     let mut v = before.clone();
     if let Some(b) = patch.enabled {
@@ -165,6 +173,7 @@ pub fn set_downloads_organizer_settings(
 /// Run one pass immediately (ignores hourly/daily schedule). Requires organizer enabled.
 #[tauri::command]
 pub fn run_downloads_organizer_now() -> Result<String, String> {
+    crate::guest_mode::ensure_allowed("Running the downloads organizer")?;
     if !Config::downloads_organizer_enabled() {
         return Err("Enable the Downloads organizer first (Settings, Downloads tab).".to_string());
     }
@@ -619,6 +619,9 @@ fn digest_script_candidates() -> Vec<PathBuf> {
 /// otherwise a Rust-native fallback that writes `latest.json` (Agent Ops still works offline).
 #[tauri::command]
 pub fn refresh_agent_digest() -> String {
+    if let Err(e) = crate::guest_mode::ensure_allowed("Refreshing the agent digest") {
+        return e;
+    }
     let out_dir = digest_json_path()
         .parent()
         .map(|p| p.to_path_buf())
@@ -1,6 +1,7 @@
 //! Logging Tauri commands for forwarding JavaScript console messages to Rust logs,
 //! runtime verbosity control (e.g. from chat reserved words -v, -vv, -vvv),
-//! and exposing the debug log path / opening / reading the log for the UI.
+//! and exposing the debug log path / opening / reading the log for the UI (raw tail for the CPU
+//! window's Logs panel, parsed and filtered entries for the log viewer window).
 
 use serde::{Deserialize, Serialize};
 use std::fs::File;
@@ -8,6 +9,8 @@ use std::io::{Read, Seek, SeekFrom};
 use std::path::PathBuf;
 use tracing::{debug, error, info, warn};
 
+use crate::logging::entries::{LogEntry, LogFilter};
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LogMessage {
     pub level: String,
@@ -49,13 +52,61 @@ pub fn log_from_js(log: LogMessage) -> Result<(), String> {
 
 /// Set log verbosity from chat (reserved words -v, -vv, -vvv).
 /// Level: 0 = error, 1 = warn (-v), 2 = debug (-vv), 3 = trace (-vvv).
+/// Per-module directives from `set_log_filter` stay in effect on top of the new level.
 #[tauri::command]
 pub fn set_chat_verbosity(level: u8) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Changing settings")?;
     let level = level.min(3);
-    crate::logging::set_verbosity(level);
+    crate::logging::set_verbosity_with_tracing(level);
     Ok(())
 }
 
+/// Current tracing filter: verbosity baseline plus per-module directives.
+#[derive(Debug, Serialize)]
+pub struct LogFilterState {
+    pub verbosity: u8,
+    /// Per-module directives as entered, e.g. `"discord=debug,metrics=warn"`
+    pub directives: String,
+    /// Full `EnvFilter` string in effect (short module names expanded)
+    pub effective: String,
+}
+
+#[tauri::command]
+pub fn get_log_filter() -> Result<LogFilterState, String> {
+    let (verbosity, directives) = crate::logging::filter::current();
+    let effective = crate::logging::filter::effective_directives(verbosity, &directives)?;
+    Ok(LogFilterState {
+        verbosity,
+        directives,
+        effective,
+    })
+}
+
+/// Apply per-module tracing directives at runtime, e.g. `"discord=debug,metrics=warn"`
+/// (empty clears them). With `persist`, also saves them as `logFilter` in config.json, which
+/// needs a `config_write` confirmation token for `logFilter`.
+#[tauri::command]
+pub fn set_log_filter(
+    filter: String,
+    persist: Option<bool>,
+    confirmation_token: Option<String>,
+) -> Result<LogFilterState, String> {
+    crate::guest_mode::ensure_allowed("Changing settings")?;
+    let persist = persist.unwrap_or(false);
+    if persist {
+        super::confirmation::require_confirmation(
+            confirmation_token.as_deref(),
+            "config_write",
+            "logFilter",
+        )?;
+    }
+    crate::logging::filter::set_directives(&filter)?;
+    if persist {
+        crate::config::Config::set_log_filter(filter.trim())?;
+    }
+    get_log_filter()
+}
+
 /// Return the absolute path of the app debug log file (e.g. for display in Settings).
 /// Used by the "View logs" feature so users can open or locate the Discord/app log.
 #[tauri::command]
@@ -123,6 +174,83 @@ pub fn read_debug_log(max_bytes: Option<u64>) -> Result<DebugLogTail, String> {
     })
 }
 
+/// Parsed debug log lines for the log viewer window
+#[derive(Debug, Serialize)]
+pub struct LogEntries {
+    pub path: String,
+    /// Matching entries, oldest first
+    pub entries: Vec<LogEntry>,
+    /// Pass back as `offset` to get only lines written after this call
+    pub offset: u64,
+    /// The log was rotated or truncated since the given offset; the viewer should clear
+    pub reset: bool,
+}
+
+/// Read debug log lines from `offset` (a previous call's `offset`; omitted: the last 512 KiB),
+/// keeping the last `limit` (default 1000, at most 5000) that match `filter`. Only complete
+/// lines are read; a line still being written comes with the next call.
+#[tauri::command]
+pub fn read_log_entries(
+    offset: Option<u64>,
+    filter: Option<LogFilter>,
+    limit: Option<usize>,
+) -> Result<LogEntries, String> {
+    const TAIL_BYTES: u64 = 512 * 1024;
+    let limit = limit.unwrap_or(1000).clamp(1, 5000);
+    let filter = filter.unwrap_or_default();
+    let path = crate::config::Config::log_file_path();
+    let path_str = path.to_string_lossy().into_owned();
+    let Ok(mut file) = File::open(&path) else {
+        return Ok(LogEntries {
+            path: path_str,
+            entries: Vec::new(),
+            offset: 0,
+            reset: offset.is_some_and(|o| o > 0),
+        });
+    };
+    let total = file
+        .metadata()
+        .map_err(|e| format!("Failed to stat log: {}", e))?
+        .len();
+    let reset = offset.is_some_and(|o| o > total);
+    let (start, from_tail) = match offset {
+        Some(o) if o <= total => (o, false),
+        _ => (total.saturating_sub(TAIL_BYTES), true),
+    };
+    file.seek(SeekFrom::Start(start))
+        .map_err(|e| format!("Failed to seek log: {}", e))?;
+    let mut buf = Vec::with_capacity((total - start) as usize);
+    file.take(total - start)
+        .read_to_end(&mut buf)
+        .map_err(|e| format!("Failed to read log: {}", e))?;
+
+    // Complete lines only; when starting mid-file from the tail, skip the partial first line
+    let end = buf.iter().rposition(|b| *b == b'\n').map_or(0, |i| i + 1);
+    let begin = if from_tail && start > 0 {
+        buf[..end]
+            .iter()
+            .position(|b| *b == b'\n')
+            .map_or(end, |i| i + 1)
+    } else {
+        0
+    };
+    let text = String::from_utf8_lossy(&buf[begin..end]);
+    let mut entries: Vec<LogEntry> = text
+        .lines()
+        .filter_map(crate::logging::entries::parse_line)
+        .filter(|entry| filter.matches(entry))
+        .collect();
+    if entries.len() > limit {
+        entries.drain(..entries.len() - limit);
+    }
+    Ok(LogEntries {
+        path: path_str,
+        entries,
+        offset: start + end as u64,
+        reset,
+    })
+}
+
 /// Open the app debug log file with the system default application (e.g. TextEdit on macOS).
 /// On macOS uses `open path`; no-op or error on other platforms.
 #[tauri::command]
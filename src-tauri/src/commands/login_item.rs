@@ -0,0 +1,195 @@
+//! Launch at login
+//!
+//! Uses `SMAppService.mainAppService` on macOS 13+ when running from an app bundle (shows up
+//! in System Settings > General > Login Items). Older systems and non-bundled builds (e.g.
+//! `cargo run`, `./run`) fall back to a per-user LaunchAgent plist in
+//! `~/Library/LaunchAgents`.
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::ffi::service_management::{self, AppServiceStatus};
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+/// LaunchAgent label (same as the bundle identifier)
+const LAUNCH_AGENT_LABEL: &str = "com.raro42.mac-stats";
+
+/// Launch-at-login state returned by `get_launch_at_login` / `set_launch_at_login`
+#[derive(Debug, Clone, Serialize)]
+pub struct LaunchAtLoginStatus {
+    pub enabled: bool,
+    /// `sm_app_service` or `launch_agent`
+    pub method: &'static str,
+    /// macOS registered the login item but the user must allow it in System Settings
+    pub requires_approval: bool,
+}
+
+fn launch_agent_path() -> Result<PathBuf, String> {
+    let home = std::env::var("HOME").map_err(|_| "HOME not set".to_string())?;
+    Ok(PathBuf::from(home)
+        .join("Library/LaunchAgents")
+        .join(format!("{}.plist", LAUNCH_AGENT_LABEL)))
+}
+
+/// True when the executable lives in `*.app/Contents/MacOS/` (SMAppService needs a bundle).
+fn running_from_bundle(exe: &Path) -> bool {
+    exe.parent()
+        .filter(|p| p.ends_with("Contents/MacOS"))
+        .and_then(|p| p.parent()?.parent())
+        .and_then(|bundle| bundle.extension())
+        .is_some_and(|ext| ext == "app")
+}
+
+fn use_sm_app_service() -> bool {
+    service_management::is_available()
+        && std::env::current_exe()
+            .map(|exe| running_from_bundle(&exe))
+            .unwrap_or(false)
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// LaunchAgent plist that starts `exe` once per GUI login.
+fn launch_agent_plist(exe: &Path) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{exe}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <false/>
+    <key>LimitLoadToSessionType</key>
+    <string>Aqua</string>
+    <key>ProcessType</key>
+    <string>Interactive</string>
+</dict>
+</plist>
+"#,
+        label = LAUNCH_AGENT_LABEL,
+        exe = xml_escape(&exe.to_string_lossy()),
+    )
+}
+
+fn remove_launch_agent() -> Result<(), String> {
+    let path = launch_agent_path()?;
+    match std::fs::remove_file(&path) {
+        Ok(()) => {
+            debug2!("Removed LaunchAgent {}", path.display());
+            Ok(())
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(format!("Failed to remove {}: {}", path.display(), e)),
+    }
+}
+
+fn write_launch_agent() -> Result<(), String> {
+    let exe = std::env::current_exe().map_err(|e| e.to_string())?;
+    let path = launch_agent_path()?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    crate::config::write_text_atomic(&path, &launch_agent_plist(&exe))?;
+    debug2!("Wrote LaunchAgent {} for {}", path.display(), exe.display());
+    Ok(())
+}
+
+fn launch_at_login_status() -> LaunchAtLoginStatus {
+    if use_sm_app_service() {
+        let status = service_management::main_app_status();
+        return LaunchAtLoginStatus {
+            enabled: matches!(
+                status,
+                Some(AppServiceStatus::Enabled | AppServiceStatus::RequiresApproval)
+            ),
+            method: "sm_app_service",
+            requires_approval: status == Some(AppServiceStatus::RequiresApproval),
+        };
+    }
+    LaunchAtLoginStatus {
+        enabled: launch_agent_path().map(|p| p.exists()).unwrap_or(false),
+        method: "launch_agent",
+        requires_approval: false,
+    }
+}
+
+/// Whether mac-stats starts automatically at login
+#[tauri::command]
+pub fn get_launch_at_login() -> LaunchAtLoginStatus {
+    launch_at_login_status()
+}
+
+/// Enable or disable starting mac-stats at login
+#[tauri::command]
+pub fn set_launch_at_login(
+    enabled: bool,
+    confirmation_token: Option<String>,
+) -> Result<LaunchAtLoginStatus, String> {
+    crate::guest_mode::ensure_allowed("Changing launch at login")?;
+    super::confirmation::require_confirmation(
+        confirmation_token.as_deref(),
+        "launch_at_login",
+        if enabled { "enable" } else { "disable" },
+    )?;
+    if use_sm_app_service() {
+        service_management::set_main_app_registered(enabled)?;
+        // A LaunchAgent left over from an older build would start a second instance.
+        remove_launch_agent()?;
+    } else if enabled {
+        write_launch_agent()?;
+    } else {
+        remove_launch_agent()?;
+    }
+    let status = launch_at_login_status();
+    tracing::info!(
+        "Launch at login {} via {}{}",
+        if status.enabled {
+            "enabled"
+        } else {
+            "disabled"
+        },
+        status.method,
+        if status.requires_approval {
+            " (needs approval in System Settings > Login Items)"
+        } else {
+            ""
+        }
+    );
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_app_bundle_executables() {
+        assert!(running_from_bundle(Path::new(
+            "/Applications/mac-stats.app/Contents/MacOS/mac_stats"
+        )));
+        assert!(!running_from_bundle(Path::new(
+            "/Users/me/mac-stats/src-tauri/target/release/mac_stats"
+        )));
+    }
+
+    #[test]
+    fn launch_agent_plist_escapes_executable_path() {
+        let plist = launch_agent_plist(Path::new("/Users/a&b/mac_stats"));
+        assert!(plist.contains("<string>com.raro42.mac-stats</string>"));
+        assert!(plist.contains("<string>/Users/a&amp;b/mac_stats</string>"));
+        assert!(plist.contains("<key>RunAtLoad</key>\n    <true/>"));
+    }
+}
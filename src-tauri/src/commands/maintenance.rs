@@ -0,0 +1,174 @@
+//! Maintenance actions (purge inactive memory, flush the DNS cache, re-index a folder in
+//! Spotlight)
+//!
+//! Each needs root, so `run_maintenance_action` only checks guest mode and the confirmation
+//! token and then asks the privileged helper (`maintenance` request), which runs the fixed
+//! commands from [`MaintenanceAction::commands`]. Nothing from the frontend reaches a shell:
+//! the action is one of three names and the Spotlight folder must be an existing absolute
+//! directory.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaintenanceAction {
+    /// `purge`: drop inactive file cache pages
+    PurgeMemory,
+    /// `dscacheutil -flushcache` and `killall -HUP mDNSResponder`
+    FlushDns,
+    /// `mdimport <folder>`: re-import one folder into the Spotlight index
+    ReindexSpotlight,
+}
+
+impl MaintenanceAction {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "purge_memory" => Some(Self::PurgeMemory),
+            "flush_dns" => Some(Self::FlushDns),
+            "reindex_spotlight" => Some(Self::ReindexSpotlight),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::PurgeMemory => "purge_memory",
+            Self::FlushDns => "flush_dns",
+            Self::ReindexSpotlight => "reindex_spotlight",
+        }
+    }
+
+    /// Programs and arguments to run in order (`folder` only for Spotlight)
+    pub fn commands(self, folder: Option<&Path>) -> Vec<(&'static str, Vec<String>)> {
+        match self {
+            Self::PurgeMemory => vec![("/usr/sbin/purge", vec![])],
+            Self::FlushDns => vec![
+                ("/usr/bin/dscacheutil", vec!["-flushcache".to_string()]),
+                (
+                    "/usr/bin/killall",
+                    vec!["-HUP".to_string(), "mDNSResponder".to_string()],
+                ),
+            ],
+            Self::ReindexSpotlight => folder
+                .map(|f| ("/usr/bin/mdimport", vec![f.to_string_lossy().into_owned()]))
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    fn done_message(self, folder: Option<&Path>) -> String {
+        match (self, folder) {
+            (Self::PurgeMemory, _) => "Purged inactive memory".to_string(),
+            (Self::FlushDns, _) => "Flushed the DNS cache".to_string(),
+            (Self::ReindexSpotlight, Some(f)) => {
+                format!("Spotlight is re-indexing {}", f.display())
+            }
+            (Self::ReindexSpotlight, None) => "No folder given".to_string(),
+        }
+    }
+}
+
+/// The Spotlight folder as an existing absolute directory (symlinks resolved)
+pub fn checked_folder(folder: Option<&str>) -> Result<PathBuf, String> {
+    let folder = folder
+        .map(str::trim)
+        .filter(|f| !f.is_empty())
+        .ok_or("reindex_spotlight needs a folder")?;
+    if !Path::new(folder).is_absolute() {
+        return Err(format!("{} is not an absolute path", folder));
+    }
+    let folder = std::fs::canonicalize(folder).map_err(|e| format!("{}: {}", folder, e))?;
+    if !folder.is_dir() {
+        return Err(format!("{} is not a folder", folder.display()));
+    }
+    Ok(folder)
+}
+
+/// Run `action` (root only: the privileged helper); returns a short result for the UI
+pub fn run(action: MaintenanceAction, folder: Option<&str>) -> Result<String, String> {
+    let folder = match action {
+        MaintenanceAction::ReindexSpotlight => Some(checked_folder(folder)?),
+        _ => None,
+    };
+    for (program, args) in action.commands(folder.as_deref()) {
+        let out = Command::new(program)
+            .args(&args)
+            .output()
+            .map_err(|e| format!("{}: {}", program, e))?;
+        if !out.status.success() {
+            let reason = String::from_utf8_lossy(&out.stderr);
+            return Err(format!(
+                "{} {}: {}",
+                program,
+                out.status,
+                reason.lines().next().unwrap_or("no output")
+            ));
+        }
+    }
+    Ok(action.done_message(folder.as_deref()))
+}
+
+/// Run a maintenance action through the privileged helper
+///
+/// # Arguments
+/// * `action` - `purge_memory`, `flush_dns` or `reindex_spotlight`
+/// * `folder` - Absolute folder path for `reindex_spotlight`
+#[tauri::command]
+pub async fn run_maintenance_action(
+    action: String,
+    folder: Option<String>,
+    confirmation_token: Option<String>,
+) -> Result<String, String> {
+    crate::guest_mode::ensure_allowed("Maintenance actions")?;
+    let parsed = MaintenanceAction::parse(&action).ok_or_else(|| {
+        format!(
+            "Unknown maintenance action {:?} (purge_memory, flush_dns or reindex_spotlight)",
+            action
+        )
+    })?;
+    let target = match parsed {
+        MaintenanceAction::ReindexSpotlight => checked_folder(folder.as_deref())?
+            .to_string_lossy()
+            .into_owned(),
+        _ => action.clone(),
+    };
+    super::confirmation::require_confirmation(
+        confirmation_token.as_deref(),
+        "maintenance",
+        &target,
+    )?;
+    let result = tokio::task::spawn_blocking(move || {
+        crate::privileged_helper::run_maintenance(parsed, folder.as_deref())
+    })
+    .await
+    .map_err(|e| format!("Maintenance action failed: {}", e))?;
+    match &result {
+        Ok(message) => tracing::info!("Maintenance {}: {}", action, message),
+        Err(e) => tracing::warn!("Maintenance {} failed: {}", action, e),
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn actions_map_to_fixed_commands() {
+        assert_eq!(MaintenanceAction::parse("rm -rf"), None);
+        let dns = MaintenanceAction::parse("flush_dns").unwrap();
+        assert_eq!(dns.as_str(), "flush_dns");
+        assert_eq!(dns.commands(None).len(), 2);
+        let spotlight = MaintenanceAction::ReindexSpotlight;
+        assert!(spotlight.commands(None).is_empty());
+        assert_eq!(
+            spotlight.commands(Some(Path::new("/Users/me/Documents"))),
+            vec![("/usr/bin/mdimport", vec!["/Users/me/Documents".to_string()])]
+        );
+        assert!(checked_folder(Some("relative/path")).is_err());
+        assert!(checked_folder(None).is_err());
+    }
+}
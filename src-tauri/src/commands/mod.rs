@@ -12,6 +12,7 @@ pub mod browser_helpers;
 pub mod browser_tool_dispatch;
 pub mod compaction;
 pub mod compaction_hooks;
+pub mod confirmation;
 pub mod content_reduction;
 pub mod context_assembler;
 pub mod conversation_sanitize;
@@ -28,6 +29,8 @@ pub mod html_cleaning;
 pub mod judge;
 pub mod llm_screenshot;
 pub mod logging;
+pub mod login_item;
+pub mod maintenance;
 pub mod loop_guard;
 pub mod misc_tool_dispatch;
 pub mod monitors;
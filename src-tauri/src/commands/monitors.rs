@@ -351,6 +351,7 @@ pub fn get_monitor_statuses_snapshot() -> Vec<(String, crate::monitors::MonitorS
 /// Add a website monitor
 #[tauri::command]
 pub fn add_website_monitor(request: AddWebsiteMonitorRequest) -> Result<Monitor, String> {
+    crate::guest_mode::ensure_allowed("Changing monitors")?;
     use tracing::{debug, info};
 
     info!(
@@ -440,6 +441,7 @@ pub fn add_website_monitor(request: AddWebsiteMonitorRequest) -> Result<Monitor,
 /// Add a Mastodon monitor
 #[tauri::command]
 pub fn add_mastodon_monitor(request: AddMastodonMonitorRequest) -> Result<Monitor, String> {
+    crate::guest_mode::ensure_allowed("Changing monitors")?;
     let monitor = MastodonMonitor::new(
         request.id.clone(),
         request.name,
@@ -564,6 +566,7 @@ pub fn list_monitors_with_details() -> Result<Vec<MonitorDetails>, String> {
 /// Remove a monitor
 #[tauri::command]
 pub fn remove_monitor(monitor_id: String) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Changing monitors")?;
     use tracing::{debug, info, warn};
 
     // Get monitor URL for logging before removal
@@ -682,6 +682,7 @@ async fn send_ollama_chat_messages_streaming_inner(
 /// Send chat message to Ollama (async, non-blocking)
 #[tauri::command]
 pub async fn ollama_chat(request: ChatRequest) -> Result<crate::ollama::ChatResponse, String> {
+    crate::guest_mode::ensure_allowed("Agent chat")?;
     use tracing::info;
 
     let request_json = serde_json::to_string_pretty(&request)
@@ -63,6 +63,7 @@ pub struct OllamaConfigResponse {
 /// Configure Ollama connection
 #[tauri::command]
 pub fn configure_ollama(config: OllamaConfigRequest) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Changing Ollama settings")?;
     use serde_json;
     use tracing::{debug, info};
 
@@ -108,6 +108,7 @@ fn ensure_cpu_window_open() {
 pub async fn ollama_chat_with_execution(
     request: OllamaChatWithExecutionRequest,
 ) -> Result<OllamaChatWithExecutionResponse, String> {
+    crate::guest_mode::ensure_allowed("Agent chat")?;
     use tracing::info;
 
     ensure_cpu_window_open();
@@ -458,6 +459,7 @@ pub async fn ollama_chat_continue_with_result(
     system_prompt: Option<String>,
     conversation_history: Option<Vec<crate::ollama::ChatMessage>>,
 ) -> Result<OllamaChatContinueResponse, String> {
+    crate::guest_mode::ensure_allowed("Agent chat")?;
     use tracing::info;
 
     ensure_cpu_window_open();
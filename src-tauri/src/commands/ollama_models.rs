@@ -93,6 +93,7 @@ pub async fn list_ollama_running_models() -> Result<PsResponse, String> {
 /// Pull (download or update) a model (POST /api/pull).
 #[tauri::command]
 pub async fn pull_ollama_model(model: String, stream: bool) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Managing Ollama models")?;
     let config = {
         let guard = get_ollama_client().lock().map_err(|e| e.to_string())?;
         let client = guard
@@ -110,6 +111,7 @@ pub async fn pull_ollama_model(model: String, stream: bool) -> Result<(), String
 /// Delete a model from disk (DELETE /api/delete).
 #[tauri::command]
 pub async fn delete_ollama_model(model: String) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Managing Ollama models")?;
     let config = {
         let guard = get_ollama_client().lock().map_err(|e| e.to_string())?;
         let client = guard
@@ -168,6 +170,7 @@ pub async fn ollama_embeddings(
 /// Unload a model from memory (keep_alive: 0).
 #[tauri::command]
 pub async fn unload_ollama_model(model: String) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Managing Ollama models")?;
     let config = {
         let guard = get_ollama_client().lock().map_err(|e| e.to_string())?;
         let client = guard
@@ -182,6 +185,7 @@ pub async fn unload_ollama_model(model: String) -> Result<(), String> {
 /// Load (warm) a model into memory. Optional keep_alive e.g. "5m".
 #[tauri::command]
 pub async fn load_ollama_model(model: String, keep_alive: Option<String>) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Managing Ollama models")?;
     let config = {
         let guard = get_ollama_client().lock().map_err(|e| e.to_string())?;
         let client = guard
@@ -120,6 +120,7 @@ pub struct PerplexitySearchResponse {
 pub async fn perplexity_search(
     request: PerplexitySearchRequest,
 ) -> Result<PerplexitySearchResponse, String> {
+    crate::guest_mode::ensure_allowed("Agent web access")?;
     use tracing::debug;
 
     let api_key = get_perplexity_api_key()
@@ -24,6 +24,7 @@ pub struct AddPluginRequest {
 /// Add a plugin
 #[tauri::command]
 pub fn add_plugin(request: AddPluginRequest) -> Result<Plugin, String> {
+    crate::guest_mode::ensure_allowed("Changing plugins")?;
     let mut plugin = Plugin::new(
         request.id.clone(),
         request.name,
@@ -52,6 +53,7 @@ pub fn add_plugin(request: AddPluginRequest) -> Result<Plugin, String> {
 /// Remove a plugin
 #[tauri::command]
 pub fn remove_plugin(plugin_id: String) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Changing plugins")?;
     get_plugin_manager()
         .lock()
         .map_err(|e| e.to_string())?
@@ -63,6 +65,7 @@ pub fn remove_plugin(plugin_id: String) -> Result<(), String> {
 /// Execute a plugin
 #[tauri::command]
 pub fn execute_plugin(plugin_id: String) -> Result<PluginResult, String> {
+    crate::guest_mode::ensure_allowed("Running plugins")?;
     let manager = get_plugin_manager().lock().map_err(|e| e.to_string())?;
 
     let plugin = manager
@@ -84,6 +87,7 @@ pub fn list_plugins() -> Result<Vec<Plugin>, String> {
 /// Run all due plugins
 #[tauri::command]
 pub fn run_due_plugins() -> Result<Vec<PluginResult>, String> {
+    crate::guest_mode::ensure_allowed("Running plugins")?;
     let mut manager = get_plugin_manager().lock().map_err(|e| e.to_string())?;
 
     let results = manager.run_due_plugins();
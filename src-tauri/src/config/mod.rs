@@ -23,7 +23,7 @@
 //! `LOG_REDACT_EXTRA_REGEX` (semicolon-separated regexes) adds custom patterns.
 //!
 //! **JSON config reload (no restart needed):**
-//! - `config.json` — read on every access (window decorations, scheduler interval, maxSchedules, heartbeat, ollamaChatTimeoutSecs, ollamaGlobalConcurrency (max concurrent Ollama /api/chat calls app-wide), agentRouterTurnTimeoutSecsDiscord / Ui / Remote (session wall-clock for one full agent run; max 48h), agentRouterMaxToolIterationsDiscord / Ui / Remote (default tool-loop cap when no per-agent override), agentRouterTurnTimeoutCleanupGraceSecs, browserViewportWidth/Height, browserLlmScreenshotWidth/Height (optional vision resize), browserArtifactMaxBytes (max size for browser screenshots/PDF artifacts), browserIdleTimeoutSecs, **browserCdpPort** (loopback remote-debugging port, default 9222), **browserCdpHttpTimeoutSecs** (per-request `reqwest` timeout for `/json/version` discovery; default **5**), **browserCdpWsConnectTimeoutSecs** (WebSocket handshake for CDP attach; default **60**), **browserCdpPostLaunchMaxWaitSecs** / **browserCdpPostLaunchPollIntervalMs** (visible-Chrome auto-launch: poll `/json/version` until ready), **browserChromiumExecutable** (optional path to Chrome / Brave / Edge / Chromium binary), **browserChromiumUserDataDir** (optional profile directory for visible launches), optional **browserCdpEmulateViewportWidth/Height** (+ **browserCdpEmulateDeviceScaleFactor**, **browserCdpEmulateMobile**) and **browserCdpEmulateGeolocationLatitude/Longitude** (+ optional **Accuracy**) for CDP `Emulation.setDeviceMetricsOverride` / `setGeolocationOverride`, browserAllowedDomains / browserBlockedDomains (BROWSER_* navigation policy), browserToolsEnabled, **browserCdpTraceEnabled** / **browserCdpTraceWallClockMinutes** / **browserCdpTraceMaxFileBytes** / **browserCdpTraceMaxRetainedFiles** (optional CDP `Tracing` JSON under `~/.mac-stats/traces/`), **runJsEnabled** (host RUN_JS via Node; default true), perplexityMaxResults, perplexitySnippetMaxChars, discord_draft_throttle_ms, extraAttachmentRoots, screenshotPruneMaxAgeDays / screenshotPruneMaxTotalBytes (`~/.mac-stats/screenshots/` lifecycle), downloadsOrganizer*, beforeResetTranscriptPath, beforeResetHook, beforeCompactionTranscriptPath, beforeCompactionHook, afterCompactionHook).
+//! - `config.json` — polled every 2s by `watch` (changed keys are logged, alerts, sampling budget, units and theme pushed at once); getters read on every access (window decorations, scheduler interval, maxSchedules, heartbeat, ollamaChatTimeoutSecs, ollamaGlobalConcurrency (max concurrent Ollama /api/chat calls app-wide), agentRouterTurnTimeoutSecsDiscord / Ui / Remote (session wall-clock for one full agent run; max 48h), agentRouterMaxToolIterationsDiscord / Ui / Remote (default tool-loop cap when no per-agent override), agentRouterTurnTimeoutCleanupGraceSecs, browserViewportWidth/Height, browserLlmScreenshotWidth/Height (optional vision resize), browserArtifactMaxBytes (max size for browser screenshots/PDF artifacts), browserIdleTimeoutSecs, **browserCdpPort** (loopback remote-debugging port, default 9222), **browserCdpHttpTimeoutSecs** (per-request `reqwest` timeout for `/json/version` discovery; default **5**), **browserCdpWsConnectTimeoutSecs** (WebSocket handshake for CDP attach; default **60**), **browserCdpPostLaunchMaxWaitSecs** / **browserCdpPostLaunchPollIntervalMs** (visible-Chrome auto-launch: poll `/json/version` until ready), **browserChromiumExecutable** (optional path to Chrome / Brave / Edge / Chromium binary), **browserChromiumUserDataDir** (optional profile directory for visible launches), optional **browserCdpEmulateViewportWidth/Height** (+ **browserCdpEmulateDeviceScaleFactor**, **browserCdpEmulateMobile**) and **browserCdpEmulateGeolocationLatitude/Longitude** (+ optional **Accuracy**) for CDP `Emulation.setDeviceMetricsOverride` / `setGeolocationOverride`, browserAllowedDomains / browserBlockedDomains (BROWSER_* navigation policy), browserToolsEnabled, **browserCdpTraceEnabled** / **browserCdpTraceWallClockMinutes** / **browserCdpTraceMaxFileBytes** / **browserCdpTraceMaxRetainedFiles** (optional CDP `Tracing` JSON under `~/.mac-stats/traces/`), **runJsEnabled** (host RUN_JS via Node; default true), perplexityMaxResults, perplexitySnippetMaxChars, discord_draft_throttle_ms, extraAttachmentRoots, screenshotPruneMaxAgeDays / screenshotPruneMaxTotalBytes (`~/.mac-stats/screenshots/` lifecycle), downloadsOrganizer*, beforeResetTranscriptPath, beforeResetHook, beforeCompactionTranscriptPath, beforeCompactionHook, afterCompactionHook).
 //! - `schedules.json` — scheduler checks file mtime each loop and reloads when changed.
 //! - `discord_channels.json` — Discord loop checks mtime every tick and reloads when changed.
 
@@ -32,6 +32,13 @@ use std::path::{Path, PathBuf};
 
 mod protected_mutation;
 mod browser;
+pub mod bundle;
+pub mod profiles;
+pub mod schema;
+pub mod watch;
+pub mod setup;
+
+pub use schema::ConfigFile;
 
 pub use protected_mutation::reject_if_protected_config_json_changed;
 
@@ -164,6 +171,283 @@ impl Default for HeartbeatSettings {
     }
 }
 
+/// Periodic InfluxDB line-protocol export (`config.json` key `influxExport`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct InfluxExportSettings {
+    pub enabled: bool,
+    /// Write endpoint, e.g. `http://localhost:8086/api/v2/write?org=home&bucket=mac` (v2) or
+    /// `http://localhost:8086/write?db=mac` (v1). `precision=s` is added when missing.
+    pub url: Option<String>,
+    /// Local file that lines are appended to (`~` allowed)
+    pub file: Option<String>,
+    /// Seconds between samples. Clamped 1–3600; default 10.
+    pub interval_secs: u64,
+    /// Seconds between writes (samples are batched). Clamped 5–3600; default 60.
+    pub flush_secs: u64,
+    /// Max lines per HTTP request / file write. Clamped 1–5000; default 500.
+    pub batch_size: usize,
+    /// Measurement name; default `mac_stats`
+    pub measurement: String,
+    /// Extra tags added to every line (a `host` tag is added unless set here)
+    pub tags: Vec<(String, String)>,
+    /// `MAC_STATS_INFLUX_TOKEN` (env or `.config.env`), sent as `Authorization: Token …`
+    pub token: Option<String>,
+}
+
+impl Default for InfluxExportSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: None,
+            file: None,
+            interval_secs: 10,
+            flush_secs: 60,
+            batch_size: 500,
+            measurement: "mac_stats".to_string(),
+            tags: Vec::new(),
+            token: None,
+        }
+    }
+}
+
+pub(crate) fn influx_export_settings_from_json(
+    obj: &serde_json::Map<String, serde_json::Value>,
+) -> InfluxExportSettings {
+    let mut s = InfluxExportSettings::default();
+    let non_empty = |key: &str| {
+        obj.get(key)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(str::to_string)
+    };
+    if let Some(b) = obj.get("enabled").and_then(|v| v.as_bool()) {
+        s.enabled = b;
+    }
+    s.url = non_empty("url");
+    s.file = non_empty("file");
+    if let Some(n) = obj.get("intervalSecs").and_then(|v| v.as_u64()) {
+        s.interval_secs = n.clamp(1, 3600);
+    }
+    if let Some(n) = obj.get("flushSecs").and_then(|v| v.as_u64()) {
+        s.flush_secs = n.clamp(5, 3600);
+    }
+    if let Some(n) = obj.get("batchSize").and_then(|v| v.as_u64()) {
+        s.batch_size = n.clamp(1, 5000) as usize;
+    }
+    if let Some(m) = non_empty("measurement") {
+        s.measurement = m;
+    }
+    if let Some(tags) = obj.get("tags").and_then(|v| v.as_object()) {
+        s.tags = tags
+            .iter()
+            .filter_map(|(k, v)| {
+                let v = v.as_str()?.trim();
+                (!k.trim().is_empty() && !v.is_empty())
+                    .then(|| (k.trim().to_string(), v.to_string()))
+            })
+            .collect();
+    }
+    s
+}
+
+/// Whether `key` is a valid machine label name: `[A-Za-z_][A-Za-z0-9_]*`, the strictest rule
+/// among export backends (Prometheus), so one label set works everywhere
+fn is_label_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Machine labels from a `{"role": "build", "owner": "ops"}` object or a `role=build,owner=ops`
+/// string; invalid keys and empty values are skipped
+pub(crate) fn labels_from_json(value: &serde_json::Value) -> Vec<(String, String)> {
+    let pairs: Vec<(String, String)> = match value {
+        serde_json::Value::Object(map) => map
+            .iter()
+            .filter_map(|(k, v)| Some((k.trim().to_string(), v.as_str()?.trim().to_string())))
+            .collect(),
+        serde_json::Value::String(s) => s
+            .split(',')
+            .filter_map(|pair| {
+                let (k, v) = pair.split_once('=')?;
+                Some((k.trim().to_string(), v.trim().to_string()))
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    pairs
+        .into_iter()
+        .filter(|(k, v)| {
+            let ok = is_label_key(k) && !v.is_empty();
+            if !ok {
+                tracing::warn!("labels: skipping invalid label '{}'", k);
+            }
+            ok
+        })
+        .collect()
+}
+
+/// MQTT publisher for home automation (`config.json` key `mqtt`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct MqttSettings {
+    pub enabled: bool,
+    pub host: String,
+    /// Default 1883 (plain TCP; TLS brokers need a local bridge)
+    pub port: u16,
+    /// Default `mac-stats-<node>`
+    pub client_id: Option<String>,
+    pub username: Option<String>,
+    /// `MAC_STATS_MQTT_PASSWORD` (env or `.config.env`)
+    pub password: Option<String>,
+    /// Topics are `<topic_prefix>/<node>/<metric>`; default `macstats`
+    pub topic_prefix: String,
+    /// Node id in topics; default the sanitized host name
+    pub node_id: Option<String>,
+    /// Seconds between publishes. Clamped 1–3600; default 10.
+    pub interval_secs: u64,
+    /// Publish Home Assistant discovery configs (default true)
+    pub discovery: bool,
+    /// Default `homeassistant`
+    pub discovery_prefix: String,
+    /// Retain metric values so new subscribers see the last reading (default false)
+    pub retain: bool,
+}
+
+impl Default for MqttSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: "localhost".to_string(),
+            port: 1883,
+            client_id: None,
+            username: None,
+            password: None,
+            topic_prefix: "macstats".to_string(),
+            node_id: None,
+            interval_secs: 10,
+            discovery: true,
+            discovery_prefix: "homeassistant".to_string(),
+            retain: false,
+        }
+    }
+}
+
+pub(crate) fn mqtt_settings_from_json(
+    obj: &serde_json::Map<String, serde_json::Value>,
+) -> MqttSettings {
+    let mut s = MqttSettings::default();
+    let non_empty = |key: &str| {
+        obj.get(key)
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(str::to_string)
+    };
+    let flag = |key: &str| obj.get(key).and_then(|v| v.as_bool());
+    if let Some(b) = flag("enabled") {
+        s.enabled = b;
+    }
+    if let Some(host) = non_empty("host") {
+        s.host = host;
+    }
+    if let Some(port) = obj
+        .get("port")
+        .and_then(|v| v.as_u64())
+        .and_then(|p| u16::try_from(p).ok())
+        .filter(|p| *p > 0)
+    {
+        s.port = port;
+    }
+    s.client_id = non_empty("clientId");
+    s.username = non_empty("username");
+    if let Some(prefix) = non_empty("topicPrefix") {
+        s.topic_prefix = prefix.trim_matches('/').to_string();
+    }
+    s.node_id = non_empty("nodeId");
+    if let Some(n) = obj.get("intervalSecs").and_then(|v| v.as_u64()) {
+        s.interval_secs = n.clamp(1, 3600);
+    }
+    if let Some(b) = flag("discovery") {
+        s.discovery = b;
+    }
+    if let Some(prefix) = non_empty("discoveryPrefix") {
+        s.discovery_prefix = prefix.trim_matches('/').to_string();
+    }
+    if let Some(b) = flag("retain") {
+        s.retain = b;
+    }
+    s
+}
+
+/// Informational (non-system) column in the status item (`config.json` key `menuBarExtras`).
+#[derive(Clone, Debug, PartialEq)]
+pub enum MenuBarExtra {
+    /// World clock; `time_zone` is an IANA name such as `America/New_York` (`None` = local time).
+    Clock {
+        label: String,
+        time_zone: Option<String>,
+        hour12: bool,
+    },
+    /// Countdown to the next timed calendar event starting within `lookahead_hours` (EventKit).
+    NextEvent { label: String, lookahead_hours: u32 },
+}
+
+/// Parse `menuBarExtras` entries, skipping unknown types (max 4 columns so the item stays usable).
+pub(crate) fn menu_bar_extras_from_json(value: &serde_json::Value) -> Vec<MenuBarExtra> {
+    let Some(entries) = value.as_array() else {
+        return Vec::new();
+    };
+    let label_of = |obj: &serde_json::Map<String, serde_json::Value>, default: &str| {
+        obj.get("label")
+            .and_then(|v| v.as_str())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .unwrap_or(default)
+            .chars()
+            .take(8)
+            .collect::<String>()
+    };
+    entries
+        .iter()
+        .filter_map(|entry| {
+            let obj = entry.as_object()?;
+            match obj.get("type").and_then(|v| v.as_str())? {
+                "clock" => {
+                    let time_zone = obj
+                        .get("timeZone")
+                        .and_then(|v| v.as_str())
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .map(str::to_string);
+                    let default_label = time_zone
+                        .as_deref()
+                        .and_then(|tz| tz.rsplit('/').next())
+                        .unwrap_or("Time")
+                        .replace('_', " ");
+                    Some(MenuBarExtra::Clock {
+                        label: label_of(obj, &default_label),
+                        time_zone,
+                        hour12: obj.get("hour12").and_then(|v| v.as_bool()).unwrap_or(false),
+                    })
+                }
+                "nextEvent" => Some(MenuBarExtra::NextEvent {
+                    label: label_of(obj, "Next"),
+                    lookahead_hours: obj
+                        .get("lookaheadHours")
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(12)
+                        .clamp(1, 72) as u32,
+                }),
+                _ => None,
+            }
+        })
+        .take(4)
+        .collect()
+}
+
 /// Upper clamp (seconds) for agent-router **session wall-clock** — one full `answer_with_ollama_and_fetch` turn.
 /// Default values in config stay short (interactive/menu-bar responsiveness); operators may raise up to 48 hours for long unattended runs.
 pub const AGENT_ROUTER_SESSION_WALL_CLOCK_MAX_SECS: u64 = 172800;
@@ -191,6 +475,79 @@ impl Config {
         std::env::temp_dir().join("mac-stats-debug.log")
     }
 
+    /// Per-module tracing directives layered over the verbosity baseline (see `logging::filter`),
+    /// e.g. `"discord=debug,metrics=warn"`. Env `MAC_STATS_LOG_FILTER` wins over config `logFilter`.
+    /// Empty by default.
+    pub fn log_filter() -> String {
+        if let Ok(v) = std::env::var("MAC_STATS_LOG_FILTER") {
+            if !v.trim().is_empty() {
+                return v.trim().to_string();
+            }
+        }
+        if let Some(s) = ConfigFile::load().log_filter.as_deref() {
+            return s.trim().to_string();
+        }
+        String::new()
+    }
+
+    pub fn set_log_filter(filter: &str) -> Result<(), String> {
+        Self::merge_config_value("logFilter", serde_json::Value::String(filter.to_string()))
+    }
+
+    /// Time budget per collector run in the sampling loop (see `metrics::sampling_budget`).
+    /// Config: `samplingBudgetMs`; env `MAC_STATS_SAMPLING_BUDGET_MS`. Default **100**; 0 turns
+    /// the automatic slow-down off. Re-read when `config.json` changes (`config::watch`).
+    pub fn sampling_budget_ms() -> u64 {
+        if let Ok(v) = std::env::var("MAC_STATS_SAMPLING_BUDGET_MS") {
+            if let Ok(ms) = v.trim().parse::<u64>() {
+                return ms;
+            }
+        }
+        if let Some(ms) = ConfigFile::load().sampling_budget_ms {
+            return ms;
+        }
+        100
+    }
+
+    /// Multiplier for every collector's normal sampling interval (`metrics::sampling_budget`).
+    /// Config: `samplingIntervalFactor`, 1–8; default **1**. Profiles such as `battery-saver`
+    /// raise it. Re-read when `config.json` changes.
+    pub fn sampling_interval_factor() -> u32 {
+        ConfigFile::load()
+            .sampling_interval_factor
+            .map_or(1, |f| f.clamp(1, 8) as u32)
+    }
+
+    /// Seconds between menu bar samples (`sampler`). Config: `updateIntervalSecs`, 1–60;
+    /// default **3**. Set by onboarding; re-read when `config.json` changes.
+    pub fn update_interval_secs() -> u64 {
+        ConfigFile::load()
+            .update_interval_secs
+            .map_or(crate::sampler::DEFAULT_INTERVAL.as_secs(), |s| s.clamp(1, 60))
+    }
+
+    /// Whether first-run onboarding (`ui::onboarding_window`) was finished or skipped. Config:
+    /// `onboardingComplete`.
+    pub fn onboarding_complete() -> bool {
+        ConfigFile::load().onboarding_complete.unwrap_or(false)
+    }
+
+    /// OTLP/HTTP collector for internal performance spans (see `logging::otel`), e.g.
+    /// `http://localhost:4318`. Env `MAC_STATS_OTLP_ENDPOINT` wins over config `otlpEndpoint`.
+    /// Unset by default (no export). Read once at startup; needs a build with `--features otel`.
+    pub fn otlp_endpoint() -> Option<String> {
+        if let Ok(v) = std::env::var("MAC_STATS_OTLP_ENDPOINT") {
+            if !v.trim().is_empty() {
+                return Some(v.trim().to_string());
+            }
+        }
+        ConfigFile::load()
+            .otlp_endpoint
+            .as_deref()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    }
+
     /// Path for daily backup of debug.log: `$HOME/.mac-stats/debug.log_sic`. Used when rotating: copy debug.log here, then truncate debug.log once per day.
     pub fn debug_log_sic_path() -> PathBuf {
         Self::log_file_path()
@@ -283,30 +640,61 @@ impl Config {
     ///
     /// Returns true (show decorations) by default if file doesn't exist or can't be read.
     pub fn get_window_decorations() -> bool {
-        let config_path = Self::config_file_path();
-        if let Ok(content) = std::fs::read_to_string(&config_path) {
-            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
-                if let Some(decorations) = json.get("windowDecorations").and_then(|v| v.as_bool()) {
-                    return decorations;
-                }
-            }
+        if let Some(decorations) = ConfigFile::load().window_decorations {
+            return decorations;
         }
         // Default to true (show decorations)
         true
     }
 
+    /// Create the CPU window hidden shortly after launch so the first click shows it at once.
+    /// The WebView's memory is then used from launch on. Config: `windowPrewarm` (default
+    /// false); override: env `MAC_STATS_WINDOW_PREWARM`. Read at launch.
+    pub fn window_prewarm() -> bool {
+        if let Ok(v) = std::env::var("MAC_STATS_WINDOW_PREWARM") {
+            let v = v.trim().to_ascii_lowercase();
+            if !v.is_empty() {
+                return matches!(v.as_str(), "1" | "true" | "yes" | "on");
+            }
+        }
+        if let Some(b) = ConfigFile::load().window_prewarm {
+            return b;
+        }
+        false
+    }
+
+    /// How to run `powermetrics` for CPU / GPU / ANE power and frequencies: `"helper"` (the
+    /// bundled privileged helper over XPC), `"sudo"` (needs a sudoers rule allowing
+    /// `sudo -n /usr/bin/powermetrics`), the absolute path of a privileged helper tool that runs
+    /// it, or `None` (default `"off"`; IOReport only).
+    /// Config: `powermetrics`; env `MAC_STATS_POWERMETRICS`. Read at startup.
+    pub fn powermetrics_provider() -> Option<String> {
+        let parse = |v: &str| {
+            let v = v.trim();
+            (!v.is_empty() && !v.eq_ignore_ascii_case("off")).then(|| v.to_string())
+        };
+        if let Ok(v) = std::env::var("MAC_STATS_POWERMETRICS") {
+            if !v.trim().is_empty() {
+                return parse(&v);
+            }
+        }
+        if let Some(v) = ConfigFile::load().powermetrics.as_deref() {
+            return parse(v);
+        }
+        None
+    }
+
     /// Whether the local AI agent stack is enabled (Ollama chat, Discord, scheduler, Agent Ops).
     ///
     /// Default **false** for a fresh install (monitor-only). If the key is missing but a Discord
     /// token or non-empty `schedules.json` already exists, treat as **true** (legacy installs).
     pub fn ai_agent_enabled() -> bool {
-        let config_path = Self::config_file_path();
-        if let Ok(content) = std::fs::read_to_string(&config_path) {
-            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
-                if let Some(v) = json.get("aiAgentEnabled").and_then(|v| v.as_bool()) {
-                    return v;
-                }
-            }
+        // Read-only guest mode never runs the agent (Discord, scheduler, Ollama tools)
+        if crate::guest_mode::is_active() {
+            return false;
+        }
+        if let Some(v) = ConfigFile::load().ai_agent_enabled {
+            return v;
         }
         // Legacy / existing operator setups without the key
         if Self::legacy_discord_token_present() {
@@ -327,69 +715,567 @@ impl Config {
                 }
             }
         }
-        false
+        false
+    }
+
+    /// Cheap Discord-token probe for legacy `aiAgentEnabled` migration (no Keychain prompt).
+    fn legacy_discord_token_present() -> bool {
+        if std::env::var("DISCORD_BOT_TOKEN")
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .is_some()
+        {
+            return true;
+        }
+        let candidates = [
+            Self::config_file_path()
+                .parent()
+                .map(|p| p.join(".config.env")),
+            Some(PathBuf::from("src-tauri/.config.env")),
+            Some(PathBuf::from(".config.env")),
+        ];
+        for path in candidates.into_iter().flatten() {
+            if let Ok(content) = std::fs::read_to_string(path) {
+                for line in content.lines() {
+                    let l = line.trim();
+                    if l.starts_with('#') || l.is_empty() {
+                        continue;
+                    }
+                    if (l.starts_with("DISCORD_BOT_TOKEN=")
+                        || l.starts_with("DISCORD-USER1-TOKEN=")
+                        || l.starts_with("DISCORD-USER2-TOKEN="))
+                        && l.split_once('=').is_some_and(|(_, v)| !v.trim().is_empty())
+                    {
+                        return true;
+                    }
+                }
+            }
+        }
+        false
+    }
+
+    /// Whether the Discord gateway may start (when the AI agent is on and a token exists).
+    /// Config: `discordEnabled`; env `MAC_STATS_DISCORD_ENABLED`. Default **true**; managed
+    /// installs turn it off with `mac_stats setup --no-discord`.
+    pub fn discord_enabled() -> bool {
+        if let Ok(v) = std::env::var("MAC_STATS_DISCORD_ENABLED") {
+            let v = v.trim().to_ascii_lowercase();
+            if !v.is_empty() {
+                return !matches!(v.as_str(), "0" | "false" | "no" | "off");
+            }
+        }
+        let config_path = Self::config_file_path();
+        if let Ok(content) = std::fs::read_to_string(&config_path) {
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+                if let Some(b) = json.get("discordEnabled").and_then(|v| v.as_bool()) {
+                    return b;
+                }
+            }
+        }
+        true
+    }
+
+    /// Persist `aiAgentEnabled` in `~/.mac-stats/config.json`.
+    pub fn set_ai_agent_enabled(enabled: bool) -> Result<(), String> {
+        Self::merge_config_bool("aiAgentEnabled", enabled)
+    }
+
+    /// Compact menu bar (CPU + cached temp when available). Default **true**.
+    /// Set `menuBarCompact: false` for the classic CPU/GPU/RAM/SSD grid.
+    pub fn menu_bar_compact() -> bool {
+        if let Some(v) = ConfigFile::load().menu_bar_compact {
+            return v;
+        }
+        true
+    }
+
+    pub fn set_menu_bar_compact(compact: bool) -> Result<(), String> {
+        Self::merge_config_bool("menuBarCompact", compact)
+    }
+
+    /// Fraction digits for menu bar percentages (sub-percent precision). Default **0**.
+    /// Config: `menuBarDecimals` (clamped 0..=2). Always 0 with `menuBarReducedMotion`.
+    pub fn menu_bar_decimals() -> usize {
+        if Self::menu_bar_reduced_motion() {
+            return 0;
+        }
+        if let Some(n) = ConfigFile::load().menu_bar_decimals {
+            return n.min(2) as usize;
+        }
+        0
+    }
+
+    /// Draw the status items as one line of plain `label value` text instead of the two-line
+    /// layout (see `ui::status_accessibility`). Config: `menuBarPlainText`; default **false**.
+    pub fn menu_bar_plain_text() -> bool {
+        ConfigFile::load().menu_bar_plain_text.unwrap_or(false)
+    }
+
+    /// Whole percents, held until they move by `menu_bar_min_change` points (see
+    /// `ui::reduced_motion`). Config: `menuBarReducedMotion`; default the macOS Reduce motion
+    /// setting.
+    pub fn menu_bar_reduced_motion() -> bool {
+        ConfigFile::load()
+            .menu_bar_reduced_motion
+            .unwrap_or_else(crate::ui::reduced_motion::system_reduce_motion)
+    }
+
+    /// Percentage points a menu bar value must move before reduced motion redraws it.
+    /// Config: `menuBarMinChange` (1..=20); default **2**.
+    pub fn menu_bar_min_change() -> f32 {
+        ConfigFile::load().menu_bar_min_change.unwrap_or(2).clamp(1, 20) as f32
+    }
+
+    /// Narrow the main status item step by step while macOS has no room for it (see
+    /// `ui::status_compaction`). Config: `menuBarAutoCompact`; default **true**.
+    pub fn menu_bar_auto_compact() -> bool {
+        ConfigFile::load().menu_bar_auto_compact.unwrap_or(true)
+    }
+
+    /// ⌘-dragging a status item out of the menu bar removes it (see `ui::status_removal`).
+    /// Config: `menuBarItemRemovable`; default **true**. Read when the status items are created.
+    pub fn menu_bar_item_removable() -> bool {
+        ConfigFile::load().menu_bar_item_removable.unwrap_or(true)
+    }
+
+    /// Read-only guest mode for shared or demo machines (see `guest_mode`; also `--guest`).
+    /// Config: `guestMode`; env `MAC_STATS_GUEST_MODE` (`1`/`true`). Default off.
+    pub fn guest_mode() -> bool {
+        if let Ok(v) = std::env::var("MAC_STATS_GUEST_MODE") {
+            let v = v.trim().to_ascii_lowercase();
+            if !v.is_empty() {
+                return v == "1" || v == "true" || v == "yes";
+            }
+        }
+        if let Some(v) = ConfigFile::load().guest_mode {
+            return v;
+        }
+        false
+    }
+
+    /// Menu bar template replacing the built-in layout, e.g. `"{cpu}% {temp}°C | {ram}%"` (see
+    /// `ui::status_template`). Config: `menuBarTemplate`; env `MAC_STATS_MENU_BAR_TEMPLATE`.
+    /// `None` (default) keeps the grid / compact layout.
+    pub fn menu_bar_template() -> Option<String> {
+        if let Ok(v) = std::env::var("MAC_STATS_MENU_BAR_TEMPLATE") {
+            if !v.trim().is_empty() {
+                return Some(v);
+            }
+        }
+        let config_path = Self::config_file_path();
+        let content = std::fs::read_to_string(&config_path).ok()?;
+        let json = serde_json::from_str::<serde_json::Value>(&content).ok()?;
+        json.get("menuBarTemplate")
+            .and_then(|v| v.as_str())
+            .filter(|s| !s.trim().is_empty())
+            .map(str::to_string)
+    }
+
+    /// `menuBarTemplateStyle: "columns"` renders the template as labelled columns (two lines);
+    /// default `"line"` is a single line.
+    pub fn menu_bar_template_columns() -> bool {
+        let config_path = Self::config_file_path();
+        if let Ok(content) = std::fs::read_to_string(&config_path) {
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+                return json.get("menuBarTemplateStyle").and_then(|v| v.as_str())
+                    == Some("columns");
+            }
+        }
+        false
+    }
+
+    /// Status items left to right, e.g. `["cpu", "network", "battery"]` (see
+    /// `ui::status_items`). Config: `menuBarItems`; default `["main"]` (one item with the whole
+    /// layout). Read when the status items are created.
+    pub fn menu_bar_items() -> Vec<String> {
+        let config_path = Self::config_file_path();
+        let json = std::fs::read_to_string(&config_path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok());
+        crate::ui::status_items::parse(json.as_ref().and_then(|j| j.get("menuBarItems")))
+    }
+
+    /// What left, right, middle and ⌥-clicks on a status item do (see `ui::click_actions`).
+    /// Config: `statusItemClickActions`, e.g. `{"left": "cpuWindow", "right": "menu"}`; default
+    /// left opens the status menu, right the About window. Read on every click.
+    pub fn status_click_actions() -> crate::ui::click_actions::ClickActions {
+        let config_path = Self::config_file_path();
+        let json = std::fs::read_to_string(&config_path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok());
+        crate::ui::click_actions::ClickActions::from_json(
+            json.as_ref().and_then(|j| j.get("statusItemClickActions")),
+        )
+    }
+
+    /// Accent and per-metric colors for light and dark appearance (see `ui::theme`). Config:
+    /// `theme`, e.g. `{"accentColor": "#ff9f0a", "metricColors": {"cpu": {"dark": "#409cff"}}}`.
+    pub fn theme_settings() -> crate::ui::theme::ThemeSettings {
+        let config_path = Self::config_file_path();
+        let json = std::fs::read_to_string(&config_path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok());
+        crate::ui::theme::ThemeSettings::from_json(json.as_ref().and_then(|j| j.get("theme")))
+    }
+
+    /// Display units: °C/°F, GB/GiB, bytes or bits per second (see `units`). Config: `units`,
+    /// e.g. `{"temperature": "F", "size": "GiB", "networkRate": "bits"}`; default °C (°F when
+    /// macOS or the region uses Fahrenheit), GB, bytes.
+    pub fn units() -> crate::units::Units {
+        let config_path = Self::config_file_path();
+        let json = std::fs::read_to_string(&config_path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok());
+        crate::units::Units::from_json_or(
+            json.as_ref().and_then(|j| j.get("units")),
+            crate::units::Units::system(),
+        )
+    }
+
+    pub fn set_units(units: &crate::units::Units) -> Result<(), String> {
+        Self::merge_config_value(
+            "units",
+            serde_json::to_value(units).map_err(|e| e.to_string())?,
+        )
+    }
+
+    /// Last CPU window position, size and always-on-top state (see `ui::window_frame`). Config:
+    /// `cpuWindowFrame`, written when the window is closed or hidden.
+    pub fn cpu_window_frame() -> crate::ui::window_frame::WindowFrame {
+        let config_path = Self::config_file_path();
+        let json = std::fs::read_to_string(&config_path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok());
+        crate::ui::window_frame::WindowFrame::from_json(
+            json.as_ref().and_then(|j| j.get("cpuWindowFrame")),
+            crate::ui::window_frame::CPU_WINDOW_SIZE,
+        )
+    }
+
+    pub fn set_cpu_window_frame(frame: &crate::ui::window_frame::WindowFrame) -> Result<(), String> {
+        Self::merge_config_value(
+            "cpuWindowFrame",
+            serde_json::to_value(frame).map_err(|e| e.to_string())?,
+        )
+    }
+
+    /// Last widget HUD position and size (see `ui::widget_window`). Config: `widgetWindowFrame`.
+    pub fn widget_window_frame() -> crate::ui::window_frame::WindowFrame {
+        let config_path = Self::config_file_path();
+        let json = std::fs::read_to_string(&config_path)
+            .ok()
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok());
+        crate::ui::window_frame::WindowFrame::from_json(
+            json.as_ref().and_then(|j| j.get("widgetWindowFrame")),
+            crate::ui::widget_window::SIZE,
+        )
+    }
+
+    pub fn set_widget_window_frame(
+        frame: &crate::ui::window_frame::WindowFrame,
+    ) -> Result<(), String> {
+        Self::merge_config_value(
+            "widgetWindowFrame",
+            serde_json::to_value(frame).map_err(|e| e.to_string())?,
+        )
+    }
+
+    /// Clicks pass through the widget HUD to the windows below (see `ui::widget_window`).
+    /// Config: `widgetClickThrough`. Default **false**.
+    pub fn widget_click_through() -> bool {
+        let config_path = Self::config_file_path();
+        if let Ok(content) = std::fs::read_to_string(&config_path) {
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+                if let Some(v) = json.get("widgetClickThrough").and_then(|v| v.as_bool()) {
+                    return v;
+                }
+            }
+        }
+        false
+    }
+
+    pub fn set_widget_click_through(enabled: bool) -> Result<(), String> {
+        Self::merge_config_value("widgetClickThrough", serde_json::Value::Bool(enabled))
+    }
+
+    /// Pin mode of the CPU window (see `ui::window_pin`). Config: `cpuWindowPinMode`:
+    /// `"normal"` (default), `"alwaysOnTop"` or `"allSpaces"`.
+    pub fn cpu_window_pin_mode() -> crate::ui::window_pin::PinMode {
+        let config_path = Self::config_file_path();
+        if let Ok(content) = std::fs::read_to_string(&config_path) {
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+                if let Some(mode) = json
+                    .get("cpuWindowPinMode")
+                    .and_then(|v| v.as_str())
+                    .and_then(crate::ui::window_pin::PinMode::parse)
+                {
+                    return mode;
+                }
+            }
+        }
+        crate::ui::window_pin::PinMode::default()
+    }
+
+    pub fn set_cpu_window_pin_mode(mode: crate::ui::window_pin::PinMode) -> Result<(), String> {
+        Self::merge_config_value(
+            "cpuWindowPinMode",
+            serde_json::to_value(mode).map_err(|e| e.to_string())?,
+        )
+    }
+
+    /// CPU sparkline (last 60 samples) drawn left of the menu bar text (see
+    /// `ui::status_sparkline`). Config: `menuBarSparkline`; env `MAC_STATS_MENU_BAR_SPARKLINE`.
+    /// Default **false**.
+    pub fn menu_bar_sparkline() -> bool {
+        if let Ok(v) = std::env::var("MAC_STATS_MENU_BAR_SPARKLINE") {
+            let v = v.trim().to_ascii_lowercase();
+            if !v.is_empty() {
+                return v == "1" || v == "true" || v == "yes";
+            }
+        }
+        let config_path = Self::config_file_path();
+        if let Ok(content) = std::fs::read_to_string(&config_path) {
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+                if let Some(v) = json.get("menuBarSparkline").and_then(|v| v.as_bool()) {
+                    return v;
+                }
+            }
+        }
+        false
+    }
+
+    /// Seconds after launch before heavy integrations start (exporters, AI agent, background
+    /// jobs, history compaction; see `startup`). Config: `startupDelaySecs`; env
+    /// `MAC_STATS_STARTUP_DELAY`. Clamped 0–600; default 0 (start everything immediately).
+    pub fn startup_delay_secs() -> u64 {
+        if let Ok(v) = std::env::var("MAC_STATS_STARTUP_DELAY") {
+            if let Ok(n) = v.trim().parse::<u64>() {
+                return n.min(600);
+            }
+        }
+        if let Some(n) = ConfigFile::load().startup_delay_secs {
+            return n.min(600);
+        }
+        0
+    }
+
+    /// What the self-updater does with new releases (see `updates`): `off`, `check` (notify
+    /// only) or `install` (download, verify and stage for the next launch). Config: `autoUpdate`;
+    /// env `MAC_STATS_AUTO_UPDATE`. Default `check`.
+    pub fn auto_update_mode() -> crate::updates::AutoUpdateMode {
+        if let Ok(v) = std::env::var("MAC_STATS_AUTO_UPDATE") {
+            if let Some(mode) = crate::updates::AutoUpdateMode::parse(&v) {
+                return mode;
+            }
+        }
+        ConfigFile::load()
+            .auto_update
+            .as_deref()
+            .and_then(crate::updates::AutoUpdateMode::parse)
+            .unwrap_or_default()
+    }
+
+    /// Hours between automatic update checks. Config: `autoUpdateIntervalHours`. Clamped
+    /// 1–168; default 24.
+    pub fn auto_update_interval_hours() -> u64 {
+        if let Some(n) = ConfigFile::load().auto_update_interval_hours {
+            return n.clamp(1, 168);
+        }
+        24
+    }
+
+    /// Downloaded and staged updates: `$HOME/.mac-stats/updates/`
+    pub fn updates_dir() -> PathBuf {
+        if let Ok(home) = std::env::var("HOME") {
+            PathBuf::from(home).join(".mac-stats").join("updates")
+        } else {
+            std::env::temp_dir().join("mac-stats-updates")
+        }
+    }
+
+    /// Minutes between metric snapshots (see `metrics::snapshots`). Config:
+    /// `metricSnapshotMinutes`; env `MAC_STATS_SNAPSHOT_MINUTES`. Clamped 0–1440; default 0 (off).
+    pub fn metric_snapshot_minutes() -> u64 {
+        if let Ok(v) = std::env::var("MAC_STATS_SNAPSHOT_MINUTES") {
+            if let Ok(n) = v.trim().parse::<u64>() {
+                return n.min(1440);
+            }
+        }
+        if let Some(n) = ConfigFile::load().metric_snapshot_minutes {
+            return n.min(1440);
+        }
+        0
+    }
+
+    /// Hours metric snapshots are kept. Config: `metricSnapshotRetentionHours`. Clamped 1–8760;
+    /// default 48.
+    pub fn metric_snapshot_retention_hours() -> u64 {
+        ConfigFile::load()
+            .metric_snapshot_retention_hours
+            .map_or(48, |n| n.clamp(1, 8760))
+    }
+
+    /// Metric snapshots: `$HOME/.mac-stats/snapshots/`
+    pub fn snapshots_dir() -> PathBuf {
+        if let Ok(home) = std::env::var("HOME") {
+            PathBuf::from(home).join(".mac-stats").join("snapshots")
+        } else {
+            std::env::temp_dir().join("mac-stats-snapshots")
+        }
+    }
+
+    /// System reports (`generate_system_report`): `$HOME/.mac-stats/reports/`
+    pub fn reports_dir() -> PathBuf {
+        if let Ok(home) = std::env::var("HOME") {
+            PathBuf::from(home).join(".mac-stats").join("reports")
+        } else {
+            std::env::temp_dir().join("mac-stats-reports")
+        }
+    }
+
+    /// Session summaries written on quit: `$HOME/.mac-stats/sessions/`
+    pub fn sessions_dir() -> PathBuf {
+        if let Ok(home) = std::env::var("HOME") {
+            PathBuf::from(home).join(".mac-stats").join("sessions")
+        } else {
+            std::env::temp_dir().join("mac-stats-sessions")
+        }
+    }
+
+    /// Metric plugins (scripts and `.json` descriptors): `$HOME/.mac-stats/plugins/`
+    pub fn plugins_dir() -> PathBuf {
+        if let Ok(home) = std::env::var("HOME") {
+            PathBuf::from(home).join(".mac-stats").join("plugins")
+        } else {
+            std::env::temp_dir().join("mac-stats-plugins")
+        }
+    }
+
+    /// Config profiles (`config::profiles`): `$HOME/.mac-stats/profiles/`
+    pub fn profiles_dir() -> PathBuf {
+        if let Ok(home) = std::env::var("HOME") {
+            PathBuf::from(home).join(".mac-stats").join("profiles")
+        } else {
+            std::env::temp_dir().join("mac-stats-profiles")
+        }
     }
 
-    /// Cheap Discord-token probe for legacy `aiAgentEnabled` migration (no Keychain prompt).
-    fn legacy_discord_token_present() -> bool {
-        if std::env::var("DISCORD_BOT_TOKEN")
-            .ok()
-            .filter(|s| !s.trim().is_empty())
-            .is_some()
-        {
-            return true;
+    /// Per-container CPU/memory from the local Docker engine. Config: `dockerStats`; env
+    /// `MAC_STATS_DOCKER`. Default **false**.
+    pub fn docker_stats_enabled() -> bool {
+        if let Ok(v) = std::env::var("MAC_STATS_DOCKER") {
+            let v = v.trim().to_ascii_lowercase();
+            if !v.is_empty() {
+                return matches!(v.as_str(), "1" | "true" | "yes" | "on");
+            }
         }
-        let candidates = [
-            Self::config_file_path()
-                .parent()
-                .map(|p| p.join(".config.env")),
-            Some(PathBuf::from("src-tauri/.config.env")),
-            Some(PathBuf::from(".config.env")),
-        ];
-        for path in candidates.into_iter().flatten() {
-            if let Ok(content) = std::fs::read_to_string(path) {
-                for line in content.lines() {
-                    let l = line.trim();
-                    if l.starts_with('#') || l.is_empty() {
-                        continue;
-                    }
-                    if (l.starts_with("DISCORD_BOT_TOKEN=")
-                        || l.starts_with("DISCORD-USER1-TOKEN=")
-                        || l.starts_with("DISCORD-USER2-TOKEN="))
-                        && l.split_once('=').is_some_and(|(_, v)| !v.trim().is_empty())
-                    {
-                        return true;
-                    }
+        let config_path = Self::config_file_path();
+        if let Ok(content) = std::fs::read_to_string(&config_path) {
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+                if let Some(b) = json.get("dockerStats").and_then(|v| v.as_bool()) {
+                    return b;
                 }
             }
         }
         false
     }
 
-    /// Persist `aiAgentEnabled` in `~/.mac-stats/config.json`.
-    pub fn set_ai_agent_enabled(enabled: bool) -> Result<(), String> {
-        Self::merge_config_bool("aiAgentEnabled", enabled)
-    }
-
-    /// Compact menu bar (CPU + cached temp when available). Default **true**.
-    /// Set `menuBarCompact: false` for the classic CPU/GPU/RAM/SSD grid.
-    pub fn menu_bar_compact() -> bool {
+    /// Docker engine socket. Config: `dockerSocket`; env `DOCKER_HOST` (`unix://` only).
+    /// Default: the first of `/var/run/docker.sock`, `~/.docker/run/docker.sock` (Docker
+    /// Desktop) and `~/.colima/default/docker.sock` that exists.
+    pub fn docker_socket() -> PathBuf {
+        if let Ok(v) = std::env::var("DOCKER_HOST") {
+            if let Some(path) = v.trim().strip_prefix("unix://") {
+                return PathBuf::from(path);
+            }
+        }
         let config_path = Self::config_file_path();
         if let Ok(content) = std::fs::read_to_string(&config_path) {
             if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
-                if let Some(v) = json.get("menuBarCompact").and_then(|v| v.as_bool()) {
-                    return v;
+                if let Some(s) = json.get("dockerSocket").and_then(|v| v.as_str()) {
+                    if !s.trim().is_empty() {
+                        return PathBuf::from(s.trim());
+                    }
                 }
             }
         }
-        true
+        let system = PathBuf::from("/var/run/docker.sock");
+        if system.exists() {
+            return system;
+        }
+        if let Ok(home) = std::env::var("HOME") {
+            let home = PathBuf::from(home);
+            for candidate in [".docker/run/docker.sock", ".colima/default/docker.sock"] {
+                let path = home.join(candidate);
+                if path.exists() {
+                    return path;
+                }
+            }
+        }
+        system
     }
 
-    pub fn set_menu_bar_compact(compact: bool) -> Result<(), String> {
-        Self::merge_config_bool("menuBarCompact", compact)
+    /// Fan speed (RPM, fastest fan) from which the fans count as audible. Config:
+    /// `fanAudibleRpm`; env `MAC_STATS_FAN_AUDIBLE_RPM`. Clamped 500–10000; default 3000.
+    pub fn fan_audible_rpm() -> u32 {
+        if let Ok(v) = std::env::var("MAC_STATS_FAN_AUDIBLE_RPM") {
+            if let Ok(n) = v.trim().parse::<u32>() {
+                return n.clamp(500, 10_000);
+            }
+        }
+        if let Some(n) = ConfigFile::load().fan_audible_rpm {
+            return n.clamp(500, 10_000) as u32;
+        }
+        3000
+    }
+
+    /// Locale override for number formatting (menu bar, exports), e.g. `de_CH` or `ar`.
+    /// Config: `numberLocale`; `None` when unset or `"system"` (use the macOS locale).
+    pub fn number_locale() -> Option<String> {
+        ConfigFile::load()
+            .number_locale
+            .as_deref()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty() && !s.eq_ignore_ascii_case("system"))
+    }
+
+    /// Language of menus, notifications and window text from Rust (`i18n`): `"en"`, `"de"`,
+    /// `"fr"` or `"es"`. Config: `language`; default `"system"` (the macOS preferred language).
+    pub fn language() -> Option<String> {
+        ConfigFile::load()
+            .language
+            .as_deref()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty() && !s.eq_ignore_ascii_case("system"))
+    }
+
+    /// Extra informational status item columns (world clock, next calendar event).
+    /// Config: `menuBarExtras` array; default none. See docs/CONFIG.md § Menu bar.
+    pub fn menu_bar_extras() -> Vec<MenuBarExtra> {
+        let config_path = Self::config_file_path();
+        let Ok(content) = std::fs::read_to_string(&config_path) else {
+            return Vec::new();
+        };
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) else {
+            return Vec::new();
+        };
+        json.get("menuBarExtras")
+            .map(menu_bar_extras_from_json)
+            .unwrap_or_default()
     }
 
     fn merge_config_bool(key: &str, value: bool) -> Result<(), String> {
+        Self::merge_config_value(key, serde_json::json!(value))
+    }
+
+    fn merge_config_value(key: &str, value: serde_json::Value) -> Result<(), String> {
+        Self::merge_config_values(&[(key, Some(value))])
+    }
+
+    /// Set several top-level keys in one write; `None` removes the key
+    pub(crate) fn merge_config_values(
+        changes: &[(&str, Option<serde_json::Value>)],
+    ) -> Result<(), String> {
         use serde_json::{json, Value};
         let config_path = Self::config_file_path();
         if let Some(parent) = config_path.parent() {
@@ -399,12 +1285,19 @@ impl Config {
             .ok()
             .and_then(|s| serde_json::from_str(&s).ok())
             .unwrap_or_else(|| json!({}));
-        match after.as_object_mut() {
-            Some(obj) => {
-                obj.insert(key.to_string(), json!(value));
-            }
-            None => {
-                after = json!({ key: value });
+        if !after.is_object() {
+            after = json!({});
+        }
+        if let Some(obj) = after.as_object_mut() {
+            for (key, value) in changes {
+                match value {
+                    Some(value) => {
+                        obj.insert(key.to_string(), value.clone());
+                    }
+                    None => {
+                        obj.remove(*key);
+                    }
+                }
             }
         }
         crate::config::write_text_atomic(
@@ -475,6 +1368,312 @@ impl Config {
         std::env::temp_dir().join("mac-stats-schedules.json")
     }
 
+    /// Persisted metrics history snapshot: `$HOME/.mac-stats/history.json`
+    pub fn history_file_path() -> PathBuf {
+        if let Ok(home) = std::env::var("HOME") {
+            let home_path = PathBuf::from(home);
+            return home_path.join(".mac-stats").join("history.json");
+        }
+        std::env::temp_dir().join("mac-stats-history.json")
+    }
+
+    /// Last readings for the next launch (`metrics::warm_start`):
+    /// `$HOME/.mac-stats/warm_start.json`
+    pub fn warm_start_path() -> PathBuf {
+        if let Ok(home) = std::env::var("HOME") {
+            let home_path = PathBuf::from(home);
+            return home_path.join(".mac-stats").join("warm_start.json");
+        }
+        std::env::temp_dir().join("mac-stats-warm_start.json")
+    }
+
+    /// Append-only write-ahead log of samples since the last history snapshot:
+    /// `$HOME/.mac-stats/history.wal`
+    pub fn history_wal_path() -> PathBuf {
+        Self::history_file_path().with_extension("wal")
+    }
+
+    /// Whether metrics history survives restarts (snapshot + write-ahead log). Default **true**.
+    /// Config: `historyPersistence`; override: env `MAC_STATS_HISTORY_PERSISTENCE` (`0`/`false` disables).
+    pub fn history_persistence_enabled() -> bool {
+        if let Ok(v) = std::env::var("MAC_STATS_HISTORY_PERSISTENCE") {
+            let v = v.trim().to_ascii_lowercase();
+            if !v.is_empty() {
+                return !matches!(v.as_str(), "0" | "false" | "no" | "off");
+            }
+        }
+        let config_path = Self::config_file_path();
+        if let Ok(content) = std::fs::read_to_string(&config_path) {
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+                if let Some(b) = json.get("historyPersistence").and_then(|v| v.as_bool()) {
+                    return b;
+                }
+            }
+        }
+        true
+    }
+
+    /// Whether persisted history lives in SQLite (`history.sqlite3`, kept
+    /// [`Self::history_retention_days`]) instead of the JSON snapshot + write-ahead log (7 days).
+    /// Config: `historyStore` (`"sqlite"` or `"json"`, default json); env `MAC_STATS_HISTORY_STORE`.
+    /// Only applies while `historyPersistence` is on. Read at startup.
+    pub fn history_sqlite_enabled() -> bool {
+        let is_sqlite = |v: &str| v.trim().eq_ignore_ascii_case("sqlite");
+        if let Ok(v) = std::env::var("MAC_STATS_HISTORY_STORE") {
+            if !v.trim().is_empty() {
+                return is_sqlite(&v);
+            }
+        }
+        if let Some(v) = ConfigFile::load().history_store.as_deref() {
+            return is_sqlite(v);
+        }
+        false
+    }
+
+    /// Days of hourly history the SQLite store keeps. Config: `historyRetentionDays`
+    /// (1–3650, default 30).
+    pub fn history_retention_days() -> u32 {
+        const DEFAULT_DAYS: u32 = 30;
+        if let Some(n) = ConfigFile::load().history_retention_days {
+            return n.clamp(1, 3650) as u32;
+        }
+        DEFAULT_DAYS
+    }
+
+    /// Alert digests: `alertDigestMinutes` maps a severity (`minor`, `major`, `critical`) to
+    /// minutes (1–1440) between digests. Alerts of those severities are batched into one
+    /// summary per channel; severities not listed (default: all) are sent immediately.
+    pub fn alert_digest_minutes() -> std::collections::HashMap<String, u64> {
+        let config_path = Self::config_file_path();
+        if let Ok(content) = std::fs::read_to_string(&config_path) {
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+                if let Some(map) = json.get("alertDigestMinutes").and_then(|v| v.as_object()) {
+                    return map
+                        .iter()
+                        .filter_map(|(severity, v)| {
+                            let minutes = v.as_u64()?;
+                            (minutes > 0).then(|| (severity.clone(), minutes.min(1440)))
+                        })
+                        .collect();
+                }
+            }
+        }
+        std::collections::HashMap::new()
+    }
+
+    /// Process watch: `watchedProcesses` lists process names (case-insensitive) and/or PIDs whose
+    /// CPU/memory history is kept for 48 hours; env `MAC_STATS_WATCHED_PROCESSES` (comma-separated)
+    /// overrides it. Default empty (off).
+    pub fn watched_processes() -> Vec<String> {
+        if let Ok(v) = std::env::var("MAC_STATS_WATCHED_PROCESSES") {
+            if !v.trim().is_empty() {
+                return v
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect();
+            }
+        }
+        let config_path = Self::config_file_path();
+        if let Ok(content) = std::fs::read_to_string(&config_path) {
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+                if let Some(list) = json.get("watchedProcesses").and_then(|v| v.as_array()) {
+                    return list
+                        .iter()
+                        .filter_map(|v| match v {
+                            serde_json::Value::String(s) => Some(s.trim().to_string()),
+                            serde_json::Value::Number(n) => n.as_u64().map(|n| n.to_string()),
+                            _ => None,
+                        })
+                        .filter(|s| !s.is_empty())
+                        .collect();
+                }
+            }
+        }
+        Vec::new()
+    }
+
+    /// Low-interference mode: `lowInterferenceApps` lists app names or bundle identifiers
+    /// (case-insensitive, e.g. `["Cyberpunk 2077", "com.apple.logic10"]`); while one of them is
+    /// frontmost, sampling slows down (see `metrics::low_interference`). Env
+    /// `MAC_STATS_LOW_INTERFERENCE_APPS` (comma-separated) overrides it. Default empty (off).
+    pub fn low_interference_apps() -> Vec<String> {
+        if let Ok(v) = std::env::var("MAC_STATS_LOW_INTERFERENCE_APPS") {
+            if !v.trim().is_empty() {
+                return v
+                    .split(',')
+                    .map(|s| s.trim().to_string())
+                    .filter(|s| !s.is_empty())
+                    .collect();
+            }
+        }
+        let config_path = Self::config_file_path();
+        if let Ok(content) = std::fs::read_to_string(&config_path) {
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+                if let Some(list) = json.get("lowInterferenceApps").and_then(|v| v.as_array()) {
+                    return list
+                        .iter()
+                        .filter_map(|v| v.as_str())
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect();
+                }
+            }
+        }
+        Vec::new()
+    }
+
+    /// Threshold alerts: `alertRules` lists `{name, metric, operator, threshold, durationSecs,
+    /// cooldownSecs, severity, actions}` entries (see `alerts::configured`). Default empty.
+    pub fn alert_rules() -> Vec<crate::alerts::configured::ConfiguredRule> {
+        let config_path = Self::config_file_path();
+        if let Ok(content) = std::fs::read_to_string(&config_path) {
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+                if let Some(rules) = json.get("alertRules") {
+                    return crate::alerts::configured::parse_rules(rules);
+                }
+            }
+        }
+        Vec::new()
+    }
+
+    /// Apps watched for crashes and hangs. Config: `appWatchdog`, a list of bundle ids or
+    /// `{"bundleId", "hangMinutes" (1–60, default 3), "action": "alert" | "restart"}`.
+    /// Default empty (watchdog off).
+    pub fn app_watchdog() -> Vec<crate::monitors::app_watchdog::WatchedApp> {
+        let config_path = Self::config_file_path();
+        if let Ok(content) = std::fs::read_to_string(&config_path) {
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+                if let Some(apps) = json.get("appWatchdog") {
+                    return crate::monitors::app_watchdog::parse_apps(apps);
+                }
+            }
+        }
+        Vec::new()
+    }
+
+    /// Other Macs shown in the dashboard window. Config: `remoteMachines`, a list of
+    /// `{"name", "url", "tokenKey"}` (see `metrics::remote_machines`). Default empty.
+    pub fn remote_machines() -> Vec<crate::metrics::remote_machines::RemoteMachine> {
+        let config_path = Self::config_file_path();
+        if let Ok(content) = std::fs::read_to_string(&config_path) {
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+                if let Some(machines) = json.get("remoteMachines") {
+                    return crate::metrics::remote_machines::parse_machines(machines);
+                }
+            }
+        }
+        Vec::new()
+    }
+
+    /// SQLite history store: `$HOME/.mac-stats/history.sqlite3`
+    pub fn history_db_path() -> PathBuf {
+        Self::history_file_path().with_extension("sqlite3")
+    }
+
+    /// Local HTTP API (`/api/v1/...` on 127.0.0.1). Config: `apiEnabled`; env `MAC_STATS_API`.
+    /// Default **false**. Read once at startup.
+    pub fn api_enabled() -> bool {
+        if let Ok(v) = std::env::var("MAC_STATS_API") {
+            let v = v.trim().to_ascii_lowercase();
+            if !v.is_empty() {
+                return matches!(v.as_str(), "1" | "true" | "yes" | "on");
+            }
+        }
+        if let Some(b) = ConfigFile::load().api_enabled {
+            return b;
+        }
+        false
+    }
+
+    /// Loopback port for the local HTTP API. Config: `apiPort`; default **8787**.
+    pub fn api_port() -> u16 {
+        ConfigFile::load()
+            .api_port
+            .and_then(|p| u16::try_from(p).ok())
+            .filter(|p| *p >= 1024)
+            .unwrap_or(8787)
+    }
+
+    /// Optional bearer token for the local HTTP API: env `MAC_STATS_API_TOKEN`, else the same
+    /// key in `~/.mac-stats/.config.env`. `None` = no token required (loopback only).
+    pub fn api_token() -> Option<String> {
+        Self::secret_from_env_or_config_env("MAC_STATS_API_TOKEN")
+    }
+
+    /// Non-empty `key` from the process env, else from `~/.mac-stats/.config.env`.
+    /// Callers must not log the returned value.
+    pub(crate) fn secret_from_env_or_config_env(key: &str) -> Option<String> {
+        if let Some(value) = std::env::var(key)
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+        {
+            return Some(value);
+        }
+        let path = Self::config_file_path().parent()?.join(".config.env");
+        let content = std::fs::read_to_string(path).ok()?;
+        content.lines().find_map(|line| {
+            let (k, value) = line.trim().split_once('=')?;
+            let value = value.trim().trim_matches('"');
+            (k.trim() == key && !value.is_empty()).then(|| value.to_string())
+        })
+    }
+
+    /// MQTT publisher settings (`config.json` key `mqtt`). Read once at startup.
+    pub fn mqtt_settings() -> MqttSettings {
+        let mut s = MqttSettings::default();
+        let config_path = Self::config_file_path();
+        let Ok(content) = std::fs::read_to_string(&config_path) else {
+            return s;
+        };
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) else {
+            return s;
+        };
+        if let Some(obj) = json.get("mqtt").and_then(|v| v.as_object()) {
+            s = mqtt_settings_from_json(obj);
+        }
+        s.password = Self::secret_from_env_or_config_env("MAC_STATS_MQTT_PASSWORD");
+        s
+    }
+
+    /// Line-protocol export settings (`config.json` key `influxExport`). Read once at startup.
+    /// Machine labels (`labels`, e.g. `{"role": "build", "owner": "ops", "location": "berlin"}`)
+    /// attached to exported metrics so fleet dashboards can slice by them. Env
+    /// `MAC_STATS_LABELS=role=build,owner=ops` overrides the config (handy for MDM).
+    pub fn labels() -> Vec<(String, String)> {
+        if let Ok(v) = std::env::var("MAC_STATS_LABELS") {
+            if !v.trim().is_empty() {
+                return labels_from_json(&serde_json::Value::String(v));
+            }
+        }
+        let config_path = Self::config_file_path();
+        if let Ok(content) = std::fs::read_to_string(&config_path) {
+            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
+                if let Some(labels) = json.get("labels") {
+                    return labels_from_json(labels);
+                }
+            }
+        }
+        Vec::new()
+    }
+
+    pub fn influx_export_settings() -> InfluxExportSettings {
+        let mut s = InfluxExportSettings::default();
+        let config_path = Self::config_file_path();
+        let Ok(content) = std::fs::read_to_string(&config_path) else {
+            return s;
+        };
+        let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) else {
+            return s;
+        };
+        if let Some(obj) = json.get("influxExport").and_then(|v| v.as_object()) {
+            s = influx_export_settings_from_json(obj);
+        }
+        s.token = Self::secret_from_env_or_config_env("MAC_STATS_INFLUX_TOKEN");
+        s
+    }
+
     /// Ensure the schedules directory exists
     ///
     /// Creates the directory containing the schedules file if it doesn't exist.
@@ -701,13 +1900,8 @@ impl Config {
         if let Some(n) = from_env {
             return clamp_ollama_global_concurrency_n(n);
         }
-        let config_path = Self::config_file_path();
-        if let Ok(content) = std::fs::read_to_string(&config_path) {
-            if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
-                if let Some(n) = json.get("ollamaGlobalConcurrency").and_then(|v| v.as_u64()) {
-                    return clamp_ollama_global_concurrency_n(n as u32);
-                }
-            }
+        if let Some(n) = ConfigFile::load().ollama_global_concurrency {
+            return clamp_ollama_global_concurrency_n(n as u32);
         }
         DEFAULT_N
     }
@@ -2558,7 +3752,11 @@ fn parse_hhmm_local(raw: &str) -> Option<(u32, u32)> {
 
 #[cfg(test)]
 mod tests {
-    use super::{clamp_ollama_global_concurrency_n, write_bytes_atomic, write_text_atomic, Config};
+    use super::{
+        clamp_ollama_global_concurrency_n, influx_export_settings_from_json, labels_from_json,
+        menu_bar_extras_from_json, mqtt_settings_from_json, write_bytes_atomic, write_text_atomic,
+        Config, MenuBarExtra,
+    };
 
     #[test]
     fn write_bytes_atomic_roundtrip() {
@@ -2576,6 +3774,91 @@ mod tests {
         let _ = std::fs::remove_dir_all(&dir);
     }
 
+    #[test]
+    fn influx_export_settings_clamp_and_skip_empty_tags() {
+        let v = serde_json::json!({
+            "enabled": true,
+            "url": " http://localhost:8086/api/v2/write?org=o&bucket=b ",
+            "intervalSecs": 0,
+            "batchSize": 100000,
+            "tags": {"site": "office", "empty": " ", "n": 1}
+        });
+        let s = influx_export_settings_from_json(v.as_object().unwrap());
+        assert!(s.enabled);
+        assert_eq!(
+            s.url.as_deref(),
+            Some("http://localhost:8086/api/v2/write?org=o&bucket=b")
+        );
+        assert_eq!(s.file, None);
+        assert_eq!((s.interval_secs, s.flush_secs, s.batch_size), (1, 60, 5000));
+        assert_eq!(s.measurement, "mac_stats");
+        assert_eq!(s.tags, vec![("site".to_string(), "office".to_string())]);
+    }
+
+    #[test]
+    fn labels_skip_invalid_keys() {
+        let v = serde_json::json!({"role": "build", "2nd": "x", "owner": " ", "site-id": "b"});
+        assert_eq!(
+            labels_from_json(&v),
+            vec![("role".to_string(), "build".to_string())]
+        );
+        let env = serde_json::Value::String("role=ci, location = berlin,broken".to_string());
+        assert_eq!(
+            labels_from_json(&env),
+            vec![
+                ("role".to_string(), "ci".to_string()),
+                ("location".to_string(), "berlin".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn mqtt_settings_defaults_and_overrides() {
+        let v = serde_json::json!({
+            "enabled": true,
+            "host": "broker.lan",
+            "port": 70000,
+            "topicPrefix": "/home/macs/",
+            "intervalSecs": 5,
+            "discovery": false
+        });
+        let s = mqtt_settings_from_json(v.as_object().unwrap());
+        assert!(s.enabled && !s.discovery && !s.retain);
+        assert_eq!((s.host.as_str(), s.port), ("broker.lan", 1883));
+        assert_eq!(s.topic_prefix, "home/macs");
+        assert_eq!(s.discovery_prefix, "homeassistant");
+        assert_eq!(s.interval_secs, 5);
+    }
+
+    #[test]
+    fn menu_bar_extras_parse_and_skip_unknown() {
+        let v = serde_json::json!([
+            {"type": "clock", "timeZone": "America/New_York"},
+            {"type": "weather"},
+            {"type": "nextEvent", "label": "Meeting soon", "lookaheadHours": 500},
+            {"type": "clock", "hour12": true}
+        ]);
+        assert_eq!(
+            menu_bar_extras_from_json(&v),
+            vec![
+                MenuBarExtra::Clock {
+                    label: "New York".to_string(),
+                    time_zone: Some("America/New_York".to_string()),
+                    hour12: false,
+                },
+                MenuBarExtra::NextEvent {
+                    label: "Meeting ".to_string(),
+                    lookahead_hours: 72,
+                },
+                MenuBarExtra::Clock {
+                    label: "Time".to_string(),
+                    time_zone: None,
+                    hour12: true,
+                },
+            ]
+        );
+    }
+
     #[test]
     fn paragraph_key_first_line() {
         assert_eq!(
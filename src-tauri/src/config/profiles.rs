@@ -0,0 +1,409 @@
+//! Named config profiles (`~/.mac-stats/profiles/<name>.json`)
+//!
+//! A profile is a partial `config.json`, e.g. `battery-saver.json` with a larger
+//! `samplingIntervalFactor`, a shorter `menuBarTemplate` and its own `alertRules`. Switching
+//! (`mac_stats profile use NAME`, the status menu's Profile submenu or `set_config_profile`)
+//! writes its keys into `config.json`, where the hot reload (`config::watch`) applies them, and
+//! keeps the values they replaced in `profiles/.base.json`. Switching to another profile, or
+//! off, puts those back first, so profiles never stack. `activeProfile` names the current one.
+//!
+//! `work`, `battery-saver` and `demo` are built in; a file of the same name replaces them.
+
+use std::path::PathBuf;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::{schema, write_text_atomic, Config, ConfigFile};
+
+/// Key in `config.json` naming the active profile
+const ACTIVE_KEY: &str = "activeProfile";
+/// Values the active profile replaced (`null`: the key was not set)
+const BASE_FILE: &str = ".base.json";
+/// Keys a profile may not set
+const RESERVED_KEYS: &[&str] = &[ACTIVE_KEY, "guestMode"];
+
+const BUILT_IN: &[(&str, &str)] = &[
+    (
+        "work",
+        r#"{
+  "samplingIntervalFactor": 1,
+  "menuBarTemplate": "CPU {cpu}% | RAM {ram}% | {temp}{temp_unit}"
+}"#,
+    ),
+    (
+        "battery-saver",
+        r#"{
+  "samplingIntervalFactor": 4,
+  "menuBarTemplate": "{cpu}% {battery}%",
+  "alertRules": [
+    { "name": "Battery low", "metric": "battery", "operator": "<", "threshold": 15, "actions": ["notification", "lowPowerMode"] }
+  ]
+}"#,
+    ),
+    (
+        "demo",
+        r#"{
+  "menuBarTemplate": "CPU: {cpu}% | GPU: {gpu}% | Temp: {temp}{temp_unit} | Fan: {fan}",
+  "menuBarTemplateStyle": "columns",
+  "alertRules": []
+}"#,
+    ),
+];
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileInfo {
+    pub name: String,
+    /// Built in and not replaced by a file
+    pub built_in: bool,
+    /// Top-level keys it sets
+    pub keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profiles {
+    pub active: Option<String>,
+    pub profiles: Vec<ProfileInfo>,
+}
+
+fn check_name(name: &str) -> Result<(), String> {
+    let ok = !name.is_empty()
+        && name.len() <= 40
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if ok {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid profile name {:?} (letters, digits, - and _)",
+            name
+        ))
+    }
+}
+
+fn profile_path(name: &str) -> PathBuf {
+    Config::profiles_dir().join(format!("{}.json", name))
+}
+
+/// Parse and check a profile: an object without reserved keys or `config.json` errors
+fn parse(name: &str, text: &str) -> Result<Map<String, Value>, String> {
+    let value: Value =
+        serde_json::from_str(text).map_err(|e| format!("Profile {}: {}", name, e))?;
+    let Value::Object(keys) = value else {
+        return Err(format!("Profile {}: not a JSON object", name));
+    };
+    if let Some(key) = RESERVED_KEYS.iter().find(|k| keys.contains_key(**k)) {
+        return Err(format!(
+            "Profile {}: {} cannot be set by a profile",
+            name, key
+        ));
+    }
+    let (errors, _) = schema::validate_text(text);
+    if let Some(e) = errors.first() {
+        return Err(format!(
+            "Profile {}: {}: {}",
+            name,
+            e.key.as_deref().unwrap_or("file"),
+            e.message
+        ));
+    }
+    Ok(keys)
+}
+
+fn read_profile(name: &str) -> Result<(Map<String, Value>, bool), String> {
+    check_name(name)?;
+    match std::fs::read_to_string(profile_path(name)) {
+        Ok(text) => parse(name, &text).map(|keys| (keys, false)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => BUILT_IN
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, text)| parse(name, text).map(|keys| (keys, true)))
+            .unwrap_or_else(|| {
+                Err(format!(
+                    "No profile named {} (see `mac_stats profile list`)",
+                    name
+                ))
+            }),
+        Err(e) => Err(format!("{}: {}", profile_path(name).display(), e)),
+    }
+}
+
+/// The active profile (`activeProfile` in `config.json`)
+pub fn active() -> Option<String> {
+    ConfigFile::load().active_profile.clone()
+}
+
+/// Profile files and built-ins, sorted by name; unreadable files are logged and left out
+pub fn list() -> Profiles {
+    let mut names: Vec<String> = BUILT_IN.iter().map(|(n, _)| n.to_string()).collect();
+    if let Ok(entries) = std::fs::read_dir(Config::profiles_dir()) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+                continue;
+            };
+            if path.extension().is_some_and(|e| e == "json")
+                && !stem.starts_with('.')
+                && !names.iter().any(|n| n == stem)
+            {
+                names.push(stem.to_string());
+            }
+        }
+    }
+    names.sort();
+    let profiles = names
+        .into_iter()
+        .filter_map(|name| match read_profile(&name) {
+            Ok((keys, built_in)) => Some(ProfileInfo {
+                keys: keys.keys().cloned().collect(),
+                built_in,
+                name,
+            }),
+            Err(e) => {
+                tracing::warn!("{}", e);
+                None
+            }
+        })
+        .collect();
+    Profiles {
+        active: active(),
+        profiles,
+    }
+}
+
+/// Put back what the active profile replaced, then apply `profile` (name and keys), saving
+/// the values it replaces in `base`
+fn switch(
+    config: &mut Map<String, Value>,
+    base: &mut Map<String, Value>,
+    profile: Option<(&str, &Map<String, Value>)>,
+) {
+    for (key, value) in std::mem::take(base) {
+        if value.is_null() {
+            config.remove(&key);
+        } else {
+            config.insert(key, value);
+        }
+    }
+    config.remove(ACTIVE_KEY);
+    if let Some((name, keys)) = profile {
+        for (key, value) in keys {
+            base.insert(key.clone(), config.get(key).cloned().unwrap_or(Value::Null));
+            config.insert(key.clone(), value.clone());
+        }
+        config.insert(ACTIVE_KEY.to_string(), Value::String(name.to_string()));
+    }
+}
+
+fn read_object(path: &std::path::Path) -> Result<Map<String, Value>, String> {
+    match std::fs::read_to_string(path) {
+        Ok(text) => match serde_json::from_str(&text) {
+            Ok(Value::Object(map)) => Ok(map),
+            _ => Err(format!(
+                "{} is not a valid JSON object; fix it first (`mac_stats config validate`)",
+                path.display()
+            )),
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Map::new()),
+        Err(e) => Err(format!("{}: {}", path.display(), e)),
+    }
+}
+
+fn write_object(path: &std::path::Path, map: &Map<String, Value>) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let text = serde_json::to_string_pretty(map).map_err(|e| e.to_string())?;
+    write_text_atomic(path, &text)
+}
+
+fn base_path() -> PathBuf {
+    Config::profiles_dir().join(BASE_FILE)
+}
+
+/// `config.json` as it would be after `profile off`, and the active profile's name (settings
+/// export)
+pub(super) fn config_without_profile() -> Result<(Map<String, Value>, Option<String>), String> {
+    let mut config = read_object(&Config::config_file_path())?;
+    let mut base = read_object(&base_path())?;
+    let active = config
+        .get(ACTIVE_KEY)
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    switch(&mut config, &mut base, None);
+    Ok((config, active))
+}
+
+/// Profile files (not the untouched built-ins) by name (settings export)
+pub(super) fn files() -> Map<String, Value> {
+    list()
+        .profiles
+        .into_iter()
+        .filter(|p| !p.built_in)
+        .filter_map(|p| {
+            let (keys, _) = read_profile(&p.name).ok()?;
+            Some((p.name, Value::Object(keys)))
+        })
+        .collect()
+}
+
+/// Write imported profiles over files of the same name, after checking all of them, and
+/// forget the values the active profile replaced: `config.json` is replaced as a whole next
+pub(super) fn import(profiles: &Map<String, Value>) -> Result<(), String> {
+    let mut texts = Vec::new();
+    for (name, keys) in profiles {
+        check_name(name)?;
+        let text = serde_json::to_string_pretty(keys).map_err(|e| e.to_string())?;
+        parse(name, &text)?;
+        texts.push((profile_path(name), text));
+    }
+    if !texts.is_empty() {
+        std::fs::create_dir_all(Config::profiles_dir()).map_err(|e| e.to_string())?;
+    }
+    for (path, text) in texts {
+        write_text_atomic(&path, &text)?;
+    }
+    match std::fs::remove_file(base_path()) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(format!("{}: {}", base_path().display(), e))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Switch to profile `name`, or back to the plain config with `None`
+pub fn use_profile(name: Option<&str>) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Switching profiles")?;
+    let profile = name.map(read_profile).transpose()?;
+    let config_path = Config::config_file_path();
+    let base_path = base_path();
+    let mut config = read_object(&config_path)?;
+    let mut base = read_object(&base_path)?;
+    switch(
+        &mut config,
+        &mut base,
+        name.zip(profile.as_ref()).map(|(n, (keys, _))| (n, keys)),
+    );
+    // Base first: a failed config write then only leaves extra keys to restore
+    if base.is_empty() {
+        if let Err(e) = std::fs::remove_file(&base_path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                return Err(format!("{}: {}", base_path.display(), e));
+            }
+        }
+    } else {
+        write_object(&base_path, &base)?;
+    }
+    write_object(&config_path, &config)?;
+    tracing::info!("Config profile: {}", name.unwrap_or("off"));
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_config_profiles() -> Profiles {
+    list()
+}
+
+/// Switch profiles (`name: null` turns the active one off); returns the new list
+#[tauri::command]
+pub fn set_config_profile(
+    name: Option<String>,
+    confirmation_token: Option<String>,
+) -> Result<Profiles, String> {
+    crate::guest_mode::ensure_allowed("Switching profiles")?;
+    crate::commands::confirmation::require_confirmation(
+        confirmation_token.as_deref(),
+        "config_write",
+        "profile",
+    )?;
+    use_profile(name.as_deref())?;
+    Ok(list())
+}
+
+/// `mac_stats profile list`
+pub fn run_list_cli() -> i32 {
+    let profiles = list();
+    for p in &profiles.profiles {
+        let mark = if profiles.active.as_deref() == Some(p.name.as_str()) {
+            "*"
+        } else {
+            " "
+        };
+        println!(
+            "{} {:<16} {}{}",
+            mark,
+            p.name,
+            p.keys.join(", "),
+            if p.built_in { " (built in)" } else { "" }
+        );
+    }
+    println!("Profiles folder: {}", Config::profiles_dir().display());
+    0
+}
+
+/// `mac_stats profile use NAME` / `mac_stats profile off`
+pub fn run_use_cli(name: Option<&str>) -> i32 {
+    match use_profile(name) {
+        Ok(()) => {
+            match name {
+                Some(name) => println!("Profile {} is active.", name),
+                None => println!("No profile active; config.json is back to its own values."),
+            }
+            0
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn object(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(map) => map,
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn switching_restores_replaced_values() {
+        let original = object(json!({"menuBarTemplate": "{cpu}%", "apiPort": 9000}));
+        let battery = object(json!({"menuBarTemplate": "{battery}%", "samplingIntervalFactor": 4}));
+        let demo = object(json!({"apiPort": 9100}));
+        let mut config = original.clone();
+        let mut base = Map::new();
+
+        switch(&mut config, &mut base, Some(("battery-saver", &battery)));
+        assert_eq!(config["menuBarTemplate"], "{battery}%");
+        assert_eq!(config["samplingIntervalFactor"], 4);
+        assert_eq!(config[ACTIVE_KEY], "battery-saver");
+
+        // Another profile starts from the original values, not on top of battery-saver
+        switch(&mut config, &mut base, Some(("demo", &demo)));
+        assert_eq!(config["menuBarTemplate"], "{cpu}%");
+        assert!(!config.contains_key("samplingIntervalFactor"));
+        assert_eq!(config["apiPort"], 9100);
+
+        switch(&mut config, &mut base, None);
+        assert_eq!(config, original);
+        assert!(base.is_empty());
+    }
+
+    #[test]
+    fn built_ins_parse_and_reserved_keys_are_refused() {
+        for (name, text) in BUILT_IN {
+            assert!(parse(name, text).is_ok(), "{}", name);
+        }
+        assert!(parse("x", r#"{"guestMode": false}"#).is_err());
+        assert!(parse("x", r#"{"apiPort": 80}"#).is_err());
+        assert!(check_name("../config").is_err());
+    }
+}
@@ -0,0 +1,538 @@
+//! Typed `config.json` with validation
+//!
+//! [`ConfigFile`] holds the core settings (menu bar, startup, history, API, updates, power).
+//! [`ConfigFile::load`] parses the file once per change (cached by path, size and mtime) and
+//! leniently: a key whose value has the wrong type is dropped, so one typo does not reset every
+//! other setting. The `Config` getters for these keys read it and apply their env overrides and
+//! clamps as before.
+//!
+//! [`validate`] reports what the lenient load hides: JSON syntax errors (with line and column),
+//! wrong types, values outside the range a getter clamps to, unknown enum values and unknown
+//! keys (warnings, with a "did you mean" for typos). It backs `mac_stats config validate`, the
+//! launch log and the settings banner (`get_config_issues`).
+//!
+//! Agent, browser and integration settings are still read ad hoc from `serde_json::Value`;
+//! [`OTHER_KEYS`] lists them so they are not reported as unknown.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::Config;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ConfigFile {
+    pub window_decorations: Option<bool>,
+    pub window_prewarm: Option<bool>,
+    pub ai_agent_enabled: Option<bool>,
+    pub guest_mode: Option<bool>,
+    pub menu_bar_compact: Option<bool>,
+    pub menu_bar_decimals: Option<u64>,
+    pub menu_bar_plain_text: Option<bool>,
+    pub menu_bar_reduced_motion: Option<bool>,
+    pub menu_bar_min_change: Option<u64>,
+    pub menu_bar_auto_compact: Option<bool>,
+    pub menu_bar_item_removable: Option<bool>,
+    pub number_locale: Option<String>,
+    pub language: Option<String>,
+    pub log_filter: Option<String>,
+    pub otlp_endpoint: Option<String>,
+    pub sampling_budget_ms: Option<u64>,
+    pub sampling_interval_factor: Option<u64>,
+    pub active_profile: Option<String>,
+    pub onboarding_complete: Option<bool>,
+    pub update_interval_secs: Option<u64>,
+    pub startup_delay_secs: Option<u64>,
+    pub auto_update: Option<String>,
+    pub auto_update_interval_hours: Option<u64>,
+    pub metric_snapshot_minutes: Option<u64>,
+    pub metric_snapshot_retention_hours: Option<u64>,
+    pub fan_audible_rpm: Option<u64>,
+    pub powermetrics: Option<String>,
+    pub history_store: Option<String>,
+    pub history_retention_days: Option<u64>,
+    pub api_enabled: Option<bool>,
+    pub api_port: Option<u64>,
+    pub ollama_global_concurrency: Option<u64>,
+}
+
+/// Allowed range of numeric keys (what the getters clamp to)
+const RANGES: &[(&str, u64, u64)] = &[
+    ("menuBarDecimals", 0, 2),
+    ("menuBarMinChange", 1, 20),
+    ("samplingIntervalFactor", 1, 8),
+    ("updateIntervalSecs", 1, 60),
+    ("startupDelaySecs", 0, 600),
+    ("autoUpdateIntervalHours", 1, 168),
+    ("metricSnapshotMinutes", 0, 1440),
+    ("metricSnapshotRetentionHours", 1, 8760),
+    ("fanAudibleRpm", 500, 10_000),
+    ("historyRetentionDays", 1, 3650),
+    ("apiPort", 1024, 65535),
+    ("ollamaGlobalConcurrency", 1, 16),
+];
+
+/// Allowed values of string keys (case-insensitive)
+const CHOICES: &[(&str, &[&str])] = &[
+    ("autoUpdate", &["off", "check", "install"]),
+    ("historyStore", &["json", "sqlite"]),
+    ("language", &["system", "en", "de", "fr", "es"]),
+];
+
+/// Settings not in [`ConfigFile`] yet (read ad hoc by their getters)
+pub const OTHER_KEYS: &[&str] = &[
+    "accentColor",
+    "afterCompactionHook",
+    "agentHarnessMode",
+    "agentJudgeEnabled",
+    "agentJudgeOnFailureOnly",
+    "agentNativeTools",
+    "agentRouterMaxToolIterationsDiscord",
+    "agentRouterMaxToolIterationsRemote",
+    "agentRouterMaxToolIterationsUi",
+    "agentRouterTurnTimeoutCleanupGraceSecs",
+    "agentRouterTurnTimeoutSecsDiscord",
+    "agentRouterTurnTimeoutSecsRemote",
+    "agentRouterTurnTimeoutSecsUi",
+    "agentTestTimeoutSecs",
+    "alertDigestMinutes",
+    "alertRules",
+    "appWatchdog",
+    "beforeCompactionHook",
+    "beforeCompactionTranscriptPath",
+    "beforeResetHook",
+    "beforeResetTranscriptPath",
+    "browserArtifactMaxBytes",
+    "browserCdpEmulateDeviceScaleFactor",
+    "browserCdpEmulateGeolocationAccuracy",
+    "browserCdpEmulateGeolocationLatitude",
+    "browserCdpEmulateGeolocationLongitude",
+    "browserCdpEmulateMobile",
+    "browserCdpEmulateViewportHeight",
+    "browserCdpEmulateViewportWidth",
+    "browserCdpGrantPermissions",
+    "browserCdpHttpTimeoutSecs",
+    "browserCdpIdleTimeoutSecs",
+    "browserCdpPort",
+    "browserCdpPostLaunchMaxWaitSecs",
+    "browserCdpPostLaunchPollIntervalMs",
+    "browserCdpProxyPassword",
+    "browserCdpProxyUsername",
+    "browserCdpTraceEnabled",
+    "browserCdpTraceMaxFileBytes",
+    "browserCdpTraceMaxRetainedFiles",
+    "browserCdpTraceWallClockMinutes",
+    "browserCdpWsConnectTimeoutSecs",
+    "browserChromiumExecutable",
+    "browserChromiumUserDataDir",
+    "browserIdleTimeoutSecs",
+    "browserIncludeDiagnosticsInState",
+    "browserLlmScreenshotHeight",
+    "browserLlmScreenshotWidth",
+    "browserMaxPageTabs",
+    "browserNavigationTimeoutSecs",
+    "browserPostNavigateMinDwellSecs",
+    "browserPostNavigateNetworkIdleEnabled",
+    "browserPostNavigateNetworkIdleMaxExtraSecs",
+    "browserPostNavigateNetworkIdleQuietSecs",
+    "browserPrintPdfBackground",
+    "browserSameDomainNavigationTimeoutSecs",
+    "browserSpaRetryEnabled",
+    "browserToolsEnabled",
+    "browserViewportHeight",
+    "browserViewportWidth",
+    "browserWaitBetweenActionsSecs",
+    "contextOverflowMaxResultChars",
+    "contextOverflowTruncateEnabled",
+    "cpuWindowFrame",
+    "cpuWindowPinMode",
+    "discordEnabled",
+    "discord_debounce_ms",
+    "discord_draft_throttle_ms",
+    "dockerSocket",
+    "dockerStats",
+    "downloadsOrganizerDailyAtLocal",
+    "downloadsOrganizerDryRun",
+    "downloadsOrganizerEnabled",
+    "downloadsOrganizerInterval",
+    "downloadsOrganizerPath",
+    "extraAttachmentRoots",
+    "heartbeat",
+    "historyPersistence",
+    "influxExport",
+    "labels",
+    "lowInterferenceApps",
+    "maxConsecutiveToolFailures",
+    "maxSchedules",
+    "menuBarExtras",
+    "menuBarItems",
+    "menuBarSparkline",
+    "menuBarTemplate",
+    "menuBarTemplateStyle",
+    "metricColors",
+    "mqtt",
+    "normalizeUntrustedHomoglyphs",
+    "ollamaChatTimeoutSecs",
+    "perplexityMaxResults",
+    "perplexitySnippetMaxChars",
+    "planningHistoryCap",
+    "proactiveContextBudgetHeadroomRatio",
+    "proactiveContextMaxResultChars",
+    "proactiveToolResultContextBudgetEnabled",
+    "remoteMachines",
+    "runJsEnabled",
+    "runsPruneMaxLines",
+    "schedulerCheckIntervalSecs",
+    "schedulerTaskTimeoutSecs",
+    "screenshotPruneMaxAgeDays",
+    "screenshotPruneMaxTotalBytes",
+    "sessionPruneMaxAgeDays",
+    "sessionPruneMaxFiles",
+    "ssrfAllowedHosts",
+    "statusItemClickActions",
+    "strictSsrfRejectWhenProxyEnv",
+    "theme",
+    "toolBudgetWarningRatio",
+    "toolLoopDetection",
+    "units",
+    "watchedProcesses",
+    "weatherDefaultPlace",
+    "widgetClickThrough",
+    "widgetWindowFrame",
+];
+
+/// One problem in `config.json`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigIssue {
+    /// Top-level key; `None` for problems with the whole file
+    pub key: Option<String>,
+    pub message: String,
+}
+
+/// Result of [`validate`]: errors mean a setting is ignored or not applied as written
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigReport {
+    pub path: String,
+    pub errors: Vec<ConfigIssue>,
+    pub warnings: Vec<ConfigIssue>,
+}
+
+fn issue(key: Option<&str>, message: String) -> ConfigIssue {
+    ConfigIssue {
+        key: key.map(str::to_string),
+        message,
+    }
+}
+
+type Cached = (PathBuf, Option<(u64, SystemTime)>, Arc<ConfigFile>);
+
+static CACHE: Mutex<Option<Cached>> = Mutex::new(None);
+
+impl ConfigFile {
+    /// Keys [`ConfigFile`] types (serialized field names)
+    pub fn keys() -> Vec<String> {
+        match serde_json::to_value(Self::default()) {
+            Ok(Value::Object(map)) => map.into_iter().map(|(k, _)| k).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Parse a `config.json` value, dropping keys whose value does not fit their type
+    pub fn from_json(value: &Value) -> Self {
+        if let Ok(config) = serde_json::from_value(value.clone()) {
+            return config;
+        }
+        let Some(obj) = value.as_object() else {
+            return Self::default();
+        };
+        let valid: serde_json::Map<String, Value> = obj
+            .iter()
+            .filter(|(k, v)| key_error(k, v).is_none())
+            .map(|(k, v)| (k.clone(), v.clone()))
+            .collect();
+        serde_json::from_value(Value::Object(valid)).unwrap_or_default()
+    }
+
+    /// The current `config.json` (default when missing or unreadable); re-read only after the
+    /// file changed
+    pub fn load() -> Arc<Self> {
+        let path = Config::config_file_path();
+        let stamp = std::fs::metadata(&path)
+            .ok()
+            .and_then(|m| Some((m.len(), m.modified().ok()?)));
+        if let Ok(cache) = CACHE.lock() {
+            if let Some((cached_path, cached_stamp, config)) = cache.as_ref() {
+                if *cached_path == path && *cached_stamp == stamp && stamp.is_some() {
+                    return Arc::clone(config);
+                }
+            }
+        }
+        let config = Arc::new(
+            std::fs::read_to_string(&path)
+                .ok()
+                .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+                .map(|json| Self::from_json(&json))
+                .unwrap_or_default(),
+        );
+        if let Ok(mut cache) = CACHE.lock() {
+            *cache = Some((path, stamp, Arc::clone(&config)));
+        }
+        config
+    }
+}
+
+/// Type error for one typed key (`None` when it parses or is not typed)
+fn key_error(key: &str, value: &Value) -> Option<String> {
+    let single = Value::Object(std::iter::once((key.to_string(), value.clone())).collect());
+    serde_json::from_value::<ConfigFile>(single)
+        .err()
+        .map(|e| e.to_string())
+}
+
+/// Levenshtein distance (for "did you mean")
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cur = row[j + 1];
+            row[j + 1] = (prev + usize::from(ca != *cb)).min(row[j] + 1).min(cur + 1);
+            prev = cur;
+        }
+    }
+    row[b.len()]
+}
+
+fn suggestion<'a>(key: &str, known: &'a [String]) -> Option<&'a str> {
+    let lower = key.to_ascii_lowercase();
+    known
+        .iter()
+        .map(|k| (edit_distance(&lower, &k.to_ascii_lowercase()), k))
+        .filter(|(d, _)| *d <= 3.min(key.len() / 3).max(1))
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, k)| k.as_str())
+}
+
+/// Check `config.json` text (see the module docs for what is reported)
+pub fn validate_text(text: &str) -> (Vec<ConfigIssue>, Vec<ConfigIssue>) {
+    let mut errors = Vec::new();
+    let mut warnings = Vec::new();
+    let json: Value = match serde_json::from_str(text) {
+        Ok(json) => json,
+        Err(e) => {
+            errors.push(issue(
+                None,
+                format!(
+                    "Invalid JSON at line {}, column {}: {} (all settings use their defaults)",
+                    e.line(),
+                    e.column(),
+                    e
+                ),
+            ));
+            return (errors, warnings);
+        }
+    };
+    let Some(obj) = json.as_object() else {
+        errors.push(issue(
+            None,
+            "The file must be a JSON object ({ ... }); all settings use their defaults".to_string(),
+        ));
+        return (errors, warnings);
+    };
+    let typed = ConfigFile::keys();
+    let known: Vec<String> = typed
+        .iter()
+        .cloned()
+        .chain(OTHER_KEYS.iter().map(|k| k.to_string()))
+        .collect();
+    for (key, value) in obj {
+        if !known.iter().any(|k| k == key) {
+            warnings.push(issue(
+                Some(key),
+                match suggestion(key, &known) {
+                    Some(k) => format!("Unknown setting (did you mean {}?)", k),
+                    None => "Unknown setting (ignored)".to_string(),
+                },
+            ));
+            continue;
+        }
+        if value.is_null() || !typed.iter().any(|k| k == key) {
+            continue;
+        }
+        if let Some(e) = key_error(key, value) {
+            errors.push(issue(Some(key), format!("{} (the default is used)", e)));
+            continue;
+        }
+        if let (Some(&(_, min, max)), Some(n)) =
+            (RANGES.iter().find(|(k, _, _)| k == key), value.as_u64())
+        {
+            if n < min || n > max {
+                errors.push(issue(
+                    Some(key),
+                    format!("{} is outside {}–{}", n, min, max),
+                ));
+            }
+        }
+        if let (Some(&(_, choices)), Some(s)) =
+            (CHOICES.iter().find(|(k, _)| k == key), value.as_str())
+        {
+            if !choices.iter().any(|c| c.eq_ignore_ascii_case(s.trim())) {
+                errors.push(issue(
+                    Some(key),
+                    format!("{:?} is not one of {}", s, choices.join(", ")),
+                ));
+            }
+        }
+    }
+    if let Some(p) = obj.get("powermetrics").and_then(Value::as_str) {
+        let p = p.trim();
+        let named = ["off", "helper", "sudo"]
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(p));
+        if !p.is_empty() && !named && !Path::new(p).is_absolute() {
+            errors.push(issue(
+                Some("powermetrics"),
+                format!("{:?} is not off, helper, sudo or an absolute path", p),
+            ));
+        }
+    }
+    (errors, warnings)
+}
+
+/// Check the config file at `path` (a missing file is fine: defaults)
+pub fn validate(path: &Path) -> ConfigReport {
+    let mut report = ConfigReport {
+        path: path.display().to_string(),
+        ..Default::default()
+    };
+    match std::fs::read_to_string(path) {
+        Ok(text) => (report.errors, report.warnings) = validate_text(&text),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => report
+            .errors
+            .push(issue(None, format!("Cannot read the file: {}", e))),
+    }
+    report
+}
+
+/// Log the current file's problems (launch)
+pub fn log_issues() {
+    let report = validate(&Config::config_file_path());
+    for e in &report.errors {
+        tracing::warn!("config.json: {}", describe(e));
+    }
+    for w in &report.warnings {
+        tracing::info!("config.json: {}", describe(w));
+    }
+}
+
+fn describe(issue: &ConfigIssue) -> String {
+    match &issue.key {
+        Some(key) => format!("{}: {}", key, issue.message),
+        None => issue.message.clone(),
+    }
+}
+
+/// `mac_stats config validate [PATH]`: print problems; exit code 1 when there are errors
+pub fn run_validate_cli(path: Option<&Path>) -> i32 {
+    let path = path
+        .map(Path::to_path_buf)
+        .unwrap_or_else(Config::config_file_path);
+    if !path.exists() {
+        println!(
+            "{} does not exist; every setting uses its default.",
+            path.display()
+        );
+        return 0;
+    }
+    let report = validate(&path);
+    for e in &report.errors {
+        println!("error: {}", describe(e));
+    }
+    for w in &report.warnings {
+        println!("warning: {}", describe(w));
+    }
+    if report.errors.is_empty() {
+        println!(
+            "{} is valid ({} warnings).",
+            report.path,
+            report.warnings.len()
+        );
+        0
+    } else {
+        println!(
+            "{}: {} errors, {} warnings.",
+            report.path,
+            report.errors.len(),
+            report.warnings.len()
+        );
+        1
+    }
+}
+
+/// Problems in `config.json` (settings banner)
+#[tauri::command]
+pub fn get_config_issues() -> ConfigReport {
+    validate(&Config::config_file_path())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn lenient_load_keeps_valid_keys() {
+        let config = ConfigFile::from_json(&json!({
+            "menuBarCompact": "yes",
+            "menuBarDecimals": 1,
+            "apiPort": 9000,
+            "browserToolsEnabled": true
+        }));
+        assert_eq!(config.menu_bar_compact, None);
+        assert_eq!(config.menu_bar_decimals, Some(1));
+        assert_eq!(config.api_port, Some(9000));
+        assert!(ConfigFile::keys().contains(&"menuBarDecimals".to_string()));
+    }
+
+    #[test]
+    fn validation_reports_types_ranges_choices_and_typos() {
+        let (errors, warnings) = validate_text(
+            r#"{"menuBarCompact": "yes", "apiPort": 80, "autoUpdate": "weekly",
+                "powermetrics": "helper", "menuBarDecimal": 1, "zzz": 1, "theme": "dark"}"#,
+        );
+        let message = |issues: &[ConfigIssue], key: &str| {
+            issues
+                .iter()
+                .find(|i| i.key.as_deref() == Some(key))
+                .map(|i| i.message.clone())
+        };
+        assert_eq!(errors.len(), 3);
+        assert!(message(&errors, "apiPort").unwrap().contains("1024–65535"));
+        assert!(message(&errors, "autoUpdate").is_some());
+        assert!(message(&errors, "menuBarCompact").is_some());
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(
+            message(&warnings, "menuBarDecimal").unwrap(),
+            "Unknown setting (did you mean menuBarDecimals?)"
+        );
+        assert_eq!(
+            message(&warnings, "zzz").unwrap(),
+            "Unknown setting (ignored)"
+        );
+
+        let (errors, _) = validate_text("{\n  \"apiPort\": 8787,\n}");
+        assert!(errors[0].message.starts_with("Invalid JSON at line 3"));
+        assert!(validate_text(r#"{"windowDecorations": false}"#)
+            .0
+            .is_empty());
+    }
+}
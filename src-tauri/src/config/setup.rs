@@ -0,0 +1,251 @@
+//! Non-interactive first-run setup (`mac_stats setup`)
+//!
+//! For MDM and provisioning scripts: writes `config.json` (and the bundled default agents and
+//! prompts) for the user it runs as, without opening the app. Every flag can also come from a
+//! `MAC_STATS_SETUP_*` environment variable so a script can export them once. Keys the flags
+//! do not mention are left as they are; running it again is harmless.
+
+use clap::ValueEnum;
+use serde_json::{json, Map, Value};
+
+use super::Config;
+
+/// Preset groups of toggles
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum SetupPreset {
+    /// System monitor only: AI agent stack and Discord off, compact menu bar
+    Monitor,
+    /// Monitor plus the AI agent stack (Ollama chat, scheduler, Discord when a token exists)
+    Agent,
+}
+
+impl SetupPreset {
+    fn settings(self) -> Vec<(&'static str, Value)> {
+        match self {
+            Self::Monitor => vec![
+                ("aiAgentEnabled", json!(false)),
+                ("discordEnabled", json!(false)),
+                ("menuBarCompact", json!(true)),
+            ],
+            Self::Agent => vec![
+                ("aiAgentEnabled", json!(true)),
+                ("discordEnabled", json!(true)),
+                ("menuBarCompact", json!(true)),
+            ],
+        }
+    }
+}
+
+/// Arguments for `mac_stats setup`
+#[derive(clap::Args, Debug, Default)]
+pub struct SetupArgs {
+    /// Apply the monitor preset for any of its settings not set yet (keeps existing choices)
+    #[arg(long, env = "MAC_STATS_SETUP_ACCEPT_DEFAULTS")]
+    pub accept_defaults: bool,
+    /// Apply a preset, overwriting its settings (named config profiles are `mac_stats profile`)
+    #[arg(long, value_enum, env = "MAC_STATS_SETUP_PRESET")]
+    pub preset: Option<SetupPreset>,
+    /// Enable InfluxDB export to this write URL (e.g. http://influx:8086/api/v2/write?org=o&bucket=b)
+    #[arg(long, env = "MAC_STATS_SETUP_INFLUX_URL")]
+    pub influx_url: Option<String>,
+    /// Enable the MQTT publisher for this broker (`host` or `host:port`)
+    #[arg(long, env = "MAC_STATS_SETUP_MQTT_BROKER")]
+    pub mqtt_broker: Option<String>,
+    /// Keep the Discord gateway off even when a bot token is configured
+    #[arg(long, env = "MAC_STATS_SETUP_NO_DISCORD")]
+    pub no_discord: bool,
+    /// Turn the AI agent stack off (Ollama chat, scheduler, Discord)
+    #[arg(long, env = "MAC_STATS_SETUP_NO_AGENTS")]
+    pub no_agents: bool,
+    /// Print what would change without writing anything
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+fn set(obj: &mut Map<String, Value>, changes: &mut Vec<String>, key: &str, value: Value) {
+    if obj.get(key) != Some(&value) {
+        changes.push(format!("{} = {}", key, value));
+        obj.insert(key.to_string(), value);
+    }
+}
+
+/// Enable a nested exporter section (`influxExport`, `mqtt`) with the given fields
+fn set_section(
+    obj: &mut Map<String, Value>,
+    changes: &mut Vec<String>,
+    section: &str,
+    fields: Vec<(&str, Value)>,
+) {
+    let mut inner = obj
+        .get(section)
+        .and_then(|v| v.as_object())
+        .cloned()
+        .unwrap_or_default();
+    let mut inner_changes = Vec::new();
+    for (key, value) in std::iter::once(("enabled", json!(true))).chain(fields) {
+        set(&mut inner, &mut inner_changes, key, value);
+    }
+    changes.extend(
+        inner_changes
+            .into_iter()
+            .map(|c| format!("{}.{}", section, c)),
+    );
+    obj.insert(section.to_string(), Value::Object(inner));
+}
+
+fn parse_broker(broker: &str) -> Result<(String, Option<u16>), String> {
+    let broker = broker.trim();
+    let invalid = || format!("Invalid MQTT broker '{}' (use host or host:port)", broker);
+    match broker.rsplit_once(':') {
+        Some((host, port)) if !host.is_empty() => {
+            let port = port.parse::<u16>().map_err(|_| invalid())?;
+            Ok((host.to_string(), Some(port)))
+        }
+        Some(_) => Err(invalid()),
+        None if broker.is_empty() => Err(invalid()),
+        None => Ok((broker.to_string(), None)),
+    }
+}
+
+/// Apply `args` to the parsed `config.json` object; returns one line per changed setting.
+/// Order: `--accept-defaults`, then `--preset`, then the individual flags (which win).
+pub fn apply(obj: &mut Map<String, Value>, args: &SetupArgs) -> Result<Vec<String>, String> {
+    let mut changes = Vec::new();
+    if args.accept_defaults {
+        for (key, value) in SetupPreset::Monitor.settings() {
+            if !obj.contains_key(key) {
+                set(obj, &mut changes, key, value);
+            }
+        }
+    }
+    if let Some(preset) = args.preset {
+        for (key, value) in preset.settings() {
+            set(obj, &mut changes, key, value);
+        }
+    }
+    if let Some(url) = args.influx_url.as_deref().map(str::trim) {
+        url::Url::parse(url).map_err(|e| format!("Invalid InfluxDB URL '{}': {}", url, e))?;
+        set_section(obj, &mut changes, "influxExport", vec![("url", json!(url))]);
+    }
+    if let Some(broker) = args.mqtt_broker.as_deref() {
+        let (host, port) = parse_broker(broker)?;
+        let mut fields = vec![("host", json!(host))];
+        if let Some(port) = port {
+            fields.push(("port", json!(port)));
+        }
+        set_section(obj, &mut changes, "mqtt", fields);
+    }
+    if args.no_discord {
+        set(obj, &mut changes, "discordEnabled", json!(false));
+    }
+    if args.no_agents {
+        set(obj, &mut changes, "aiAgentEnabled", json!(false));
+    }
+    Ok(changes)
+}
+
+/// Run `mac_stats setup`. Returns Err(exit_code) on failure.
+pub fn run_cli(args: SetupArgs) -> Result<(), i32> {
+    let fail = |e: String| {
+        eprintln!("Error: {}", e);
+        1
+    };
+    let config_path = Config::config_file_path();
+    let mut config: Value = match std::fs::read_to_string(&config_path) {
+        Ok(content) => serde_json::from_str(&content).map_err(|e| {
+            fail(format!(
+                "{} is not valid JSON: {}",
+                config_path.display(),
+                e
+            ))
+        })?,
+        Err(_) => json!({}),
+    };
+    let obj = config
+        .as_object_mut()
+        .ok_or_else(|| fail(format!("{} is not a JSON object", config_path.display())))?;
+    let changes = apply(obj, &args).map_err(fail)?;
+
+    for change in &changes {
+        println!("{}", change);
+    }
+    if args.dry_run {
+        println!("Dry run: {} setting(s) would change", changes.len());
+        return Ok(());
+    }
+    Config::ensure_defaults();
+    if !changes.is_empty() {
+        let text = serde_json::to_string_pretty(&config).map_err(|e| fail(e.to_string()))?;
+        super::write_text_atomic(&config_path, &text)
+            .map_err(|e| fail(format!("Failed to write {}: {}", config_path.display(), e)))?;
+    }
+    println!(
+        "{}: {} setting(s) changed",
+        config_path.display(),
+        changes.len()
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(v: Value) -> Map<String, Value> {
+        v.as_object().cloned().unwrap()
+    }
+
+    #[test]
+    fn accept_defaults_keeps_existing_choices() {
+        let mut obj = object(json!({ "aiAgentEnabled": true, "theme": "dark" }));
+        let args = SetupArgs {
+            accept_defaults: true,
+            ..Default::default()
+        };
+        let changes = apply(&mut obj, &args).unwrap();
+        assert_eq!(obj["aiAgentEnabled"], json!(true));
+        assert_eq!(obj["discordEnabled"], json!(false));
+        assert_eq!(obj["theme"], json!("dark"));
+        assert_eq!(changes.len(), 2);
+        assert!(apply(&mut obj, &args).unwrap().is_empty());
+    }
+
+    #[test]
+    fn individual_flags_win_over_preset() {
+        let mut obj = Map::new();
+        let args = SetupArgs {
+            preset: Some(SetupPreset::Agent),
+            no_discord: true,
+            ..Default::default()
+        };
+        apply(&mut obj, &args).unwrap();
+        assert_eq!(obj["aiAgentEnabled"], json!(true));
+        assert_eq!(obj["discordEnabled"], json!(false));
+    }
+
+    #[test]
+    fn exporter_endpoints_merge_into_existing_sections() {
+        let mut obj = object(json!({ "mqtt": { "topicPrefix": "lab", "port": 1883 } }));
+        let args = SetupArgs {
+            influx_url: Some("http://influx:8086/api/v2/write?org=o&bucket=b".into()),
+            mqtt_broker: Some("broker.lan:8883".into()),
+            ..Default::default()
+        };
+        apply(&mut obj, &args).unwrap();
+        assert_eq!(obj["influxExport"]["enabled"], json!(true));
+        assert_eq!(obj["mqtt"]["host"], json!("broker.lan"));
+        assert_eq!(obj["mqtt"]["port"], json!(8883));
+        assert_eq!(obj["mqtt"]["topicPrefix"], json!("lab"));
+
+        let bad = SetupArgs {
+            mqtt_broker: Some("broker:port".into()),
+            ..Default::default()
+        };
+        assert!(apply(&mut obj, &bad).is_err());
+        let bad = SetupArgs {
+            influx_url: Some("not a url".into()),
+            ..Default::default()
+        };
+        assert!(apply(&mut obj, &bad).is_err());
+    }
+}
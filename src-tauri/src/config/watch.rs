@@ -0,0 +1,155 @@
+//! Hot reload of `config.json`, the way the Discord loop reloads `discord_channels.json`
+//!
+//! A collector task compares the file's size and mtime every [`POLL`]. On a change it logs
+//! which top-level keys differ, re-validates the file (`schema::log_issues`) and pushes the
+//! settings that are otherwise only picked up later: the sampling budget and interval, alert
+//! rules, and the `units-changed` / `theme-changed` events for open windows. Finally
+//! `config-changed` (payload: the changed keys) lets the frontend re-check for errors.
+//!
+//! Getters read the file (or the [`ConfigFile::load`](super::ConfigFile::load) cache) when
+//! called, so the menu bar template, decimals and locale follow on the next status bar tick
+//! without help from here. Settings that start a task or bind a port (`apiPort`,
+//! `menuBarItems`, `powermetrics`, …) still need a restart.
+
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use serde_json::Value;
+use tauri::Emitter;
+
+use super::{schema, Config};
+use crate::state::APP_HANDLE;
+
+/// Emitted after a reload with the changed top-level keys
+pub const EVENT: &str = "config-changed";
+
+/// How often the file's size and mtime are compared; edits apply within this
+const POLL: Duration = Duration::from_secs(2);
+
+type Stamp = Option<(u64, SystemTime)>;
+
+/// Last seen stamp and top-level object
+static LAST: Mutex<Option<(Stamp, Value)>> = Mutex::new(None);
+
+fn stamp() -> Stamp {
+    std::fs::metadata(Config::config_file_path())
+        .ok()
+        .and_then(|m| Some((m.len(), m.modified().ok()?)))
+}
+
+/// The file as JSON; an empty object when missing or not valid JSON (every key at its default)
+fn read() -> Value {
+    std::fs::read_to_string(Config::config_file_path())
+        .ok()
+        .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+        .filter(Value::is_object)
+        .unwrap_or_else(|| Value::Object(Default::default()))
+}
+
+/// Top-level keys added, removed or changed between two versions of the file, sorted
+fn changed_keys(old: &Value, new: &Value) -> Vec<String> {
+    let (Some(old), Some(new)) = (old.as_object(), new.as_object()) else {
+        return Vec::new();
+    };
+    let keys: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    keys.into_iter()
+        .filter(|k| old.get(*k) != new.get(*k))
+        .cloned()
+        .collect()
+}
+
+/// Start polling `config.json`. Call once after APP_HANDLE is set (e.g. in setup).
+pub fn spawn_config_watcher() {
+    if let Ok(mut last) = LAST.lock() {
+        *last = Some((stamp(), read()));
+    }
+    if !crate::background::every("config_watch", POLL, || {
+        reload_if_changed();
+        true
+    }) {
+        tracing::warn!(
+            "Config watch: collector runtime unavailable; config.json edits need a restart"
+        );
+    }
+}
+
+fn reload_if_changed() {
+    let current = stamp();
+    let keys = {
+        let Ok(mut last) = LAST.lock() else {
+            return;
+        };
+        if matches!(last.as_ref(), Some((s, _)) if *s == current) {
+            return;
+        }
+        let json = read();
+        let keys = match last.as_ref() {
+            Some((_, old)) => changed_keys(old, &json),
+            None => Vec::new(),
+        };
+        *last = Some((current, json));
+        keys
+    };
+    // Saved without a real change (or only whitespace)
+    if keys.is_empty() {
+        return;
+    }
+    tracing::info!("config.json reloaded (file changed): {}", keys.join(", "));
+    schema::log_issues();
+    apply(&keys);
+}
+
+fn apply(keys: &[String]) {
+    let changed = |key: &str| keys.iter().any(|k| k == key);
+    if changed("samplingBudgetMs") || changed("samplingIntervalFactor") {
+        let (ms, factor) = crate::metrics::sampling_budget::reload();
+        tracing::info!("Sampling budget now {} ms, intervals ×{}", ms, factor);
+    }
+    if changed("updateIntervalSecs") {
+        let secs = Config::update_interval_secs();
+        crate::sampler::send(crate::sampler::Command::SetInterval(Duration::from_secs(secs)));
+    }
+    if changed("alertRules") || changed("alertDigestMinutes") {
+        crate::commands::alerts::sync_configured_alert_rules();
+    }
+    let Some(app) = APP_HANDLE.get() else {
+        return;
+    };
+    if changed("units") {
+        if let Err(e) = app.emit(crate::units::EVENT, crate::units::Units::current()) {
+            tracing::debug!("Units event failed: {}", e);
+        }
+    }
+    if changed("theme") {
+        // The system accent color is read through AppKit
+        let handle = app.clone();
+        let emitted = app.run_on_main_thread(move || {
+            if let Err(e) = handle.emit(crate::ui::theme::EVENT, crate::ui::theme::current()) {
+                tracing::debug!("Theme event failed: {}", e);
+            }
+        });
+        if let Err(e) = emitted {
+            tracing::debug!("Theme event failed: {}", e);
+        }
+    }
+    if let Err(e) = app.emit(EVENT, keys) {
+        tracing::debug!("Config event failed: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changed_keys_cover_added_removed_and_edited() {
+        let old = serde_json::json!({"units": {"temperature": "C"}, "apiPort": 8787, "menuBarCompact": true});
+        let new = serde_json::json!({"units": {"temperature": "F"}, "apiPort": 8787, "samplingBudgetMs": 50});
+        assert_eq!(
+            changed_keys(&old, &new),
+            vec!["menuBarCompact", "samplingBudgetMs", "units"]
+        );
+        assert!(changed_keys(&new, &new).is_empty());
+    }
+}
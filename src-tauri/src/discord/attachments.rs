@@ -0,0 +1,293 @@
+//! Attachments of a Discord message for the agent router
+//!
+//! Text files (logs, code, config, CSV, …) go into the prompt as they are, PDFs as their text
+//! (PDFKit through `osascript`, so no PDF crate), and images to Ollama as before. Most chat
+//! models cannot see images, so each one is also described by the local vision model (the one
+//! screenshot verification uses) and the description goes into the prompt; without a vision
+//! model only a vision-capable chat model sees them.
+//!
+//! All files together get [`MAX_TOTAL_CHARS`] of the prompt; a longer text keeps its start and,
+//! for logs the more useful part, most of its end. Files over [`MAX_DOWNLOAD_BYTES`] and other
+//! types are listed by name only, so the model can say it did not read them.
+
+use base64::Engine;
+use serenity::model::channel::Attachment;
+
+use crate::commands::ollama_chat::{send_ollama_chat_messages, OllamaHttpQueue};
+
+const MAX_DOWNLOAD_BYTES: u32 = 8 * 1024 * 1024;
+const MAX_FILE_CHARS: usize = 12_000;
+const MAX_TOTAL_CHARS: usize = 24_000;
+/// Share of a cut text taken from its start; the rest comes from the end
+const HEAD_SHARE: usize = 4;
+
+/// Prompt when the user sends only image attachment(s) and no text.
+const IMAGE_ONLY_PROMPT: &str = "What do you see in the attached image(s)? Describe the content.";
+/// Prompt when the user sends only files and no text.
+const FILES_ONLY_PROMPT: &str = "Summarize the attached file(s) and point out anything notable.";
+const VISION_PROMPT: &str = "Describe this image in detail for someone who cannot see it. \
+     Transcribe all visible text exactly, including error messages, numbers and UI labels.";
+
+const TEXT_EXTENSIONS: &[&str] = &[
+    "txt", "log", "md", "json", "jsonl", "yaml", "yml", "toml", "ini", "cfg", "conf", "env", "csv",
+    "tsv", "xml", "plist", "html", "css", "sql", "diff", "patch", "sh", "zsh", "py", "rs", "js",
+    "ts", "swift", "c", "h", "cpp", "go", "java", "kt", "rb", "ips", "crash",
+];
+const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "gif", "webp"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Image,
+    Pdf,
+    Text,
+    Other,
+}
+
+fn kind(filename: &str, content_type: Option<&str>) -> Kind {
+    let content_type = content_type.unwrap_or("").to_lowercase();
+    let extension = filename
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_lowercase())
+        .unwrap_or_default();
+    if content_type.starts_with("image/") || IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+        Kind::Image
+    } else if content_type == "application/pdf" || extension == "pdf" {
+        Kind::Pdf
+    } else if content_type.starts_with("text/")
+        || content_type == "application/json"
+        || content_type == "application/xml"
+        || TEXT_EXTENSIONS.contains(&extension.as_str())
+    {
+        Kind::Text
+    } else {
+        Kind::Other
+    }
+}
+
+/// What the attachments add to a message
+#[derive(Debug, Default)]
+pub(super) struct Ingested {
+    /// File contents for the prompt, one section per attachment
+    pub(super) text: Option<String>,
+    /// Base64 images for vision-capable chat models
+    pub(super) images_base64: Vec<String>,
+    /// Whether there were attachments besides images
+    files: bool,
+}
+
+impl Ingested {
+    /// The message to send when the user wrote nothing besides the attachments
+    pub(super) fn default_prompt(&self) -> Option<&'static str> {
+        if self.files {
+            Some(FILES_ONLY_PROMPT)
+        } else if !self.images_base64.is_empty() {
+            Some(IMAGE_ONLY_PROMPT)
+        } else {
+            None
+        }
+    }
+
+    /// `content` with the file contents appended
+    pub(super) fn append_to(&self, content: String) -> String {
+        match &self.text {
+            Some(text) => format!("{}\n\n{}", content, text),
+            None => content,
+        }
+    }
+}
+
+/// Download and read `attachments`; `queue_key` is the Ollama queue for image descriptions
+pub(super) async fn ingest(attachments: &[Attachment], queue_key: &str) -> Ingested {
+    let mut ingested = Ingested::default();
+    let mut sections = Vec::new();
+    let mut budget = MAX_TOTAL_CHARS;
+    for att in attachments {
+        let kind = kind(&att.filename, att.content_type.as_deref());
+        ingested.files |= kind != Kind::Image;
+        if kind == Kind::Other {
+            sections.push(section(
+                &att.filename,
+                "not read: unsupported file type",
+                None,
+            ));
+            continue;
+        }
+        if att.size > MAX_DOWNLOAD_BYTES {
+            sections.push(section(&att.filename, "not read: file too large", None));
+            continue;
+        }
+        let bytes = match att.download().await {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                tracing::warn!(
+                    "Discord: failed to download attachment {}: {}",
+                    att.filename,
+                    e
+                );
+                sections.push(section(&att.filename, "not read: download failed", None));
+                continue;
+            }
+        };
+        let read = match kind {
+            Kind::Image => {
+                let b64 = base64::engine::general_purpose::STANDARD.encode(&bytes);
+                let description = describe_image(&b64, &att.filename, queue_key).await;
+                ingested.images_base64.push(b64);
+                match description {
+                    Some((model, text)) => Ok((format!("image, described by {}", model), text)),
+                    None => continue,
+                }
+            }
+            Kind::Pdf => pdf_text(&bytes, att.id.get())
+                .await
+                .map(|text| ("PDF text".to_string(), text)),
+            Kind::Text => text_file(&bytes).map(|text| ("text".to_string(), text)),
+            Kind::Other => unreachable!(),
+        };
+        match read {
+            Ok((_, text)) if budget == 0 || text.trim().is_empty() => {
+                let note = if budget == 0 {
+                    "not included: attachment budget used up"
+                } else {
+                    "no text found"
+                };
+                sections.push(section(&att.filename, note, None));
+            }
+            Ok((label, text)) => {
+                let text = excerpt(text.trim(), MAX_FILE_CHARS.min(budget));
+                budget = budget.saturating_sub(text.chars().count());
+                sections.push(section(&att.filename, &label, Some(&text)));
+            }
+            Err(e) => {
+                tracing::info!("Discord: could not read attachment {}: {}", att.filename, e);
+                sections.push(section(&att.filename, &format!("not read: {}", e), None));
+            }
+        }
+    }
+    if !sections.is_empty() {
+        tracing::info!(
+            "Discord: {} attachment(s) for the prompt, {} image(s) for vision",
+            sections.len(),
+            ingested.images_base64.len()
+        );
+        ingested.text = Some(format!("Attached files:\n\n{}", sections.join("\n\n")));
+    }
+    ingested
+}
+
+fn section(filename: &str, label: &str, body: Option<&str>) -> String {
+    match body {
+        Some(body) => format!(
+            "--- {} ({}) ---\n{}\n--- end of {} ---",
+            filename, label, body, filename
+        ),
+        None => format!("--- {} ({}) ---", filename, label),
+    }
+}
+
+fn text_file(bytes: &[u8]) -> Result<String, String> {
+    if bytes.contains(&0) {
+        return Err("binary content".to_string());
+    }
+    Ok(String::from_utf8_lossy(bytes).into_owned())
+}
+
+/// At most `max` characters of `text`: the start and the end around an omission marker
+fn excerpt(text: &str, max: usize) -> String {
+    let total = text.chars().count();
+    if total <= max {
+        return text.to_string();
+    }
+    let head = max / HEAD_SHARE;
+    let tail = max - head;
+    let start: String = text.chars().take(head).collect();
+    let end: String = text.chars().skip(total - tail).collect();
+    format!(
+        "{}\n[… {} characters omitted …]\n{}",
+        start,
+        total - head - tail,
+        end
+    )
+}
+
+/// Text of a PDF, through PDFKit (JavaScript for Automation)
+async fn pdf_text(bytes: &[u8], attachment_id: u64) -> Result<String, String> {
+    const SCRIPT: &str = "ObjC.import('PDFKit');\n\
+        function run(argv) {\n\
+          const doc = $.PDFDocument.alloc.initWithURL($.NSURL.fileURLWithPath(argv[0]));\n\
+          if (doc.isNil()) { throw new Error('not a readable PDF'); }\n\
+          return ObjC.unwrap(doc.string) || '';\n\
+        }";
+    let path = std::env::temp_dir().join(format!("mac-stats-discord-{}.pdf", attachment_id));
+    std::fs::write(&path, bytes).map_err(|e| e.to_string())?;
+    let script_path = path.clone();
+    let output = tokio::task::spawn_blocking(move || {
+        std::process::Command::new("/usr/bin/osascript")
+            .args(["-l", "JavaScript", "-e", SCRIPT])
+            .arg(&script_path)
+            .output()
+    })
+    .await
+    .map_err(|e| e.to_string());
+    let _ = std::fs::remove_file(&path);
+    let output = output?.map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err("not a readable PDF".to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Description of an image by the local vision model, with that model's name
+async fn describe_image(b64: &str, filename: &str, queue_key: &str) -> Option<(String, String)> {
+    let model = crate::ollama::models::get_vision_model_for_verification()?;
+    let messages = vec![crate::ollama::ChatMessage {
+        role: "user".to_string(),
+        content: VISION_PROMPT.to_string(),
+        images: Some(vec![b64.to_string()]),
+        tool_calls: None,
+        tool_name: None,
+        tool_call_id: None,
+    }];
+    let queue = OllamaHttpQueue::Acquire {
+        key: queue_key.to_string(),
+        wait_hook: None,
+    };
+    match send_ollama_chat_messages(messages, Some(model.clone()), None, queue).await {
+        Ok(response) if !response.message.content.trim().is_empty() => {
+            Some((model, response.message.content.trim().to_string()))
+        }
+        Ok(_) => None,
+        Err(e) => {
+            tracing::warn!(
+                "Discord: describing image {} with {} failed: {}",
+                filename,
+                model,
+                e
+            );
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn files_are_classified_and_long_texts_keep_start_and_end() {
+        assert_eq!(kind("Screen Shot.PNG", None), Kind::Image);
+        assert_eq!(kind("report", Some("application/pdf")), Kind::Pdf);
+        assert_eq!(kind("system.log", None), Kind::Text);
+        assert_eq!(kind("notes", Some("text/plain; charset=utf-8")), Kind::Text);
+        assert_eq!(kind("movie.mp4", Some("video/mp4")), Kind::Other);
+
+        let log: String = (0..100).map(|i| format!("{:03}\n", i)).collect();
+        let cut = excerpt(&log, 40);
+        assert!(cut.starts_with("000\n001\n"));
+        assert!(cut.ends_with("098\n099\n"));
+        assert!(cut.contains("[… 360 characters omitted …]"));
+        assert_eq!(excerpt("short", 40), "short");
+
+        assert!(text_file(b"\x89PNG\0\0").is_err());
+    }
+}
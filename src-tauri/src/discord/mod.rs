@@ -10,21 +10,34 @@
 //!
 //! Rapid full-router Discord messages in the same channel can be debounced (see
 //! `config.json` `discord_debounce_ms` and `message_debounce`).
+//!
+//! Slash commands (`/stats`, `/ask`, `/task add`, `/agent run`) are in `slash_commands`.
+//!
+//! Threads are sessions of their own and inherit their parent channel's settings; when the bot
+//! opens one for a conversation is in `threads`.
+//!
+//! Optional spoken alerts and replies in a voice channel are in `voice`.
 
 pub mod api;
+mod attachments;
 mod token;
 pub use token::{get_discord_token, DISCORD_TOKEN_KEYCHAIN_ACCOUNT};
 use token::token_from_config_env_file;
 
 mod message_debounce;
+mod reactions;
+mod slash_commands;
+mod stats_embed;
+mod threads;
+pub mod voice;
 
 use crate::circuit_breaker::CircuitBreaker;
-use base64::Engine;
 use chrono::Timelike;
 use serenity::builder::EditMessage;
 use serenity::client::{Client, Context, EventHandler};
 use serenity::gateway::{ConnectionStage, ShardManager, ShardStageUpdateEvent};
-use serenity::model::channel::{Message, ReactionType};
+use serenity::model::application::Interaction;
+use serenity::model::channel::{Message, Reaction, ReactionType};
 use serenity::model::gateway::GatewayIntents;
 use serenity::model::id::{MessageId, UserId};
 use std::collections::HashMap;
@@ -42,6 +55,7 @@ use crate::commands::outbound_pipeline::{
 use crate::commands::session_history::{
     cap_tail_chronological, CONVERSATION_HISTORY_CAP, HAVING_FUN_IDLE_HISTORY_CAP,
 };
+use threads::ThreadMode;
 
 /// Time-of-day period for having_fun: influences tone (e.g. quieter at night).
 #[derive(Clone, Copy)]
@@ -141,44 +155,6 @@ fn sanitize_image_error_content(content: &str) -> String {
     "Image link returned 404 — could not load image.".to_string()
 }
 
-/// Default prompt when the user sends only image attachment(s) and no text.
-const DISCORD_IMAGE_ONLY_PROMPT: &str =
-    "What do you see in the attached image(s)? Describe the content.";
-
-fn is_image_attachment(att: &serenity::model::channel::Attachment) -> bool {
-    att.content_type
-        .as_deref()
-        .is_some_and(|c| c.starts_with("image/"))
-        || att.filename.to_lowercase().ends_with(".png")
-        || att.filename.to_lowercase().ends_with(".jpg")
-        || att.filename.to_lowercase().ends_with(".jpeg")
-        || att.filename.to_lowercase().ends_with(".gif")
-        || att.filename.to_lowercase().ends_with(".webp")
-}
-
-/// Download image attachments and return their base64-encoded bytes for Ollama vision.
-async fn download_discord_image_attachments(
-    attachments: &[serenity::model::channel::Attachment],
-) -> Vec<String> {
-    let mut out = Vec::new();
-    for att in attachments {
-        if !is_image_attachment(att) {
-            continue;
-        }
-        match att.download().await {
-            Ok(bytes) => out.push(base64::engine::general_purpose::STANDARD.encode(&bytes)),
-            Err(e) => {
-                tracing::warn!(
-                    "Discord: failed to download attachment {}: {}",
-                    att.filename,
-                    e
-                );
-            }
-        }
-    }
-    out
-}
-
 /// Per-channel listen mode loaded from `~/.mac-stats/discord_channels.json`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(super) enum ChannelMode {
@@ -201,6 +177,8 @@ struct ChannelSettings {
     agent: Option<String>,
     /// Per-channel debounce override in ms. `Some(0)` = no debounce (immediate Ollama). `None` = use global `discord_debounce_ms` from config.json.
     debounce_ms: Option<u64>,
+    /// When replies go into a thread of their own (see `threads`).
+    threads: ThreadMode,
 }
 
 /// Having-fun timeframes: min/max in seconds. Each use picks a random value in [min, max].
@@ -322,6 +300,7 @@ fn load_channel_config_full() -> (
         model: None,
         agent: None,
         debounce_ms: None,
+        threads: ThreadMode::Long,
     };
     let path = crate::config::Config::discord_channels_path();
     let json = match std::fs::read_to_string(&path) {
@@ -367,12 +346,18 @@ fn load_channel_config_full() -> (
         .get("default_prompt")
         .and_then(|v| v.as_str())
         .map(|s| s.to_string());
+    let default_threads = parsed
+        .get("default_threads")
+        .and_then(|v| v.as_str())
+        .and_then(ThreadMode::parse)
+        .unwrap_or(ThreadMode::Long);
     let default_settings = ChannelSettings {
         mode: default_mode,
         prompt: default_prompt,
         model: None,
         agent: None,
         debounce_ms: None,
+        threads: default_threads,
     };
 
     let default_verbose_dm = parsed
@@ -417,6 +402,7 @@ fn load_channel_config_full() -> (
                     model: None,
                     agent: None,
                     debounce_ms: None,
+                    threads: default_threads,
                 }
             } else if let Some(obj) = v.as_object() {
                 let mode = obj
@@ -447,12 +433,18 @@ fn load_channel_config_full() -> (
                         .and_then(|v| v.as_u64())
                         .map(|n| n.min(60_000))
                 };
+                let threads = obj
+                    .get("threads")
+                    .and_then(|v| v.as_str())
+                    .and_then(ThreadMode::parse)
+                    .unwrap_or(default_threads);
                 ChannelSettings {
                     mode,
                     prompt,
                     model,
                     agent,
                     debounce_ms,
+                    threads,
                 }
             } else {
                 continue;
@@ -585,6 +577,7 @@ fn channel_settings(channel_id: u64) -> ChannelSettings {
                 model: None,
                 agent: None,
                 debounce_ms: None,
+                threads: ThreadMode::Long,
             };
         }
     };
@@ -595,6 +588,7 @@ fn channel_settings(channel_id: u64) -> ChannelSettings {
             model: None,
             agent: None,
             debounce_ms: None,
+            threads: ThreadMode::Long,
         };
     };
     overrides
@@ -2118,6 +2112,14 @@ pub(super) async fn run_discord_ollama_router(
         return;
     }
 
+    // "stats": metrics embed with a chart of the last hour (no Ollama).
+    if stats_embed::looks_like_stats_request(&content) {
+        if let Err(e) = stats_embed::send(&ctx, new_message.channel_id).await {
+            error!("Discord: failed to send stats: {}", e);
+        }
+        return;
+    }
+
     // Hermes `/cron list` parity — cheap schedules report, no Ollama.
     if crate::commands::harness_ops::looks_like_schedules_request(&content) {
         let report = crate::commands::harness_ops::format_schedules_gateway();
@@ -2186,7 +2188,14 @@ async fn run_discord_ollama_router_locked(
     };
     let is_dm = new_message.guild_id.is_none();
     let mentions_bot_effective = discord_mentions_bot_effective(&ctx, &new_message, bot_id).await;
-    let chan = channel_settings(new_message.channel_id.get());
+    let thread = if is_dm {
+        None
+    } else {
+        threads::lookup(&ctx, new_message.channel_id, bot_id).await
+    };
+    let chan = channel_settings(thread.map_or(new_message.channel_id.get(), |t| t.parent));
+    // Replies go into a thread of their own: from the start (`always`) or when long (`long`)
+    let may_start_thread = !is_dm && thread.is_none() && mode != ChannelMode::HavingFun;
 
     let (
         mut question,
@@ -2262,7 +2271,18 @@ async fn run_discord_ollama_router_locked(
         trigger, new_message.author.name, new_message.channel_id, verbose
     );
 
-    let channel_id_u64 = new_message.channel_id.get();
+    let mut reply_channel = new_message.channel_id;
+    if may_start_thread && chan.threads == ThreadMode::Always {
+        match threads::start(&ctx, &new_message, &question).await {
+            Ok(thread_id) => reply_channel = thread_id,
+            Err(e) => warn!(
+                "Discord: could not start a thread, replying in the channel: {}",
+                e
+            ),
+        }
+    }
+    // Session memory and queues follow the thread
+    let channel_id_u64 = reply_channel.get();
 
     // "New session:" prefix strips the prefix from the question; actual clear runs once below with phrase-based reset.
     let lower_for_prefix = question.trim().to_lowercase();
@@ -2415,7 +2435,7 @@ async fn run_discord_ollama_router_locked(
         Some(status_tx.clone())
     };
     let ctx_send = ctx.clone();
-    let channel_id = new_message.channel_id;
+    let channel_id = reply_channel;
     const EDIT_PREFIX: &str = "EDIT:";
     const ATTACH_PREFIX: &str = "ATTACH:";
     const CRITERIA_PROGRESS: &str = "🎯 Extracting success criteria…";
@@ -2426,6 +2446,8 @@ async fn run_discord_ollama_router_locked(
     } else {
         "Thinking…"
     };
+    // Messages of the reply, for the reaction controls
+    let mut reply_messages = Vec::new();
     let discord_draft = if dev_silent_discord {
         info!(
             target: "discord/draft",
@@ -2433,8 +2455,9 @@ async fn run_discord_ollama_router_locked(
         );
         None
     } else {
-        match new_message.channel_id.say(&ctx, placeholder_text).await {
+        match reply_channel.say(&ctx, placeholder_text).await {
             Ok(placeholder) => {
+                reply_messages.push((placeholder.channel_id, placeholder.id));
                 info!(
                     target: "discord/draft",
                     "placeholder sent, draft editor started (throttle_ms={}, verbose={})",
@@ -2529,7 +2552,7 @@ async fn run_discord_ollama_router_locked(
     // Show "Werner_Amvara is typing..." while processing. Fires immediately,
     // then every 8s (indicator lasts ~10s server-side). Cancelled when reply is ready.
     let typing_ctx = ctx.clone();
-    let typing_channel = new_message.channel_id;
+    let typing_channel = reply_channel;
     let queue_typing_ctx = typing_ctx.clone();
     let queue_typing_channel = typing_channel;
     let ollama_queue_wait_hook: Option<std::sync::Arc<dyn Fn() + Send + Sync>> =
@@ -2560,6 +2583,7 @@ async fn run_discord_ollama_router_locked(
         }))
     };
 
+    let exchange_images = attachment_images_base64.clone();
     let attachment_images_for_ollama = if attachment_images_base64.is_empty() {
         None
     } else {
@@ -2732,8 +2756,27 @@ async fn run_discord_ollama_router_locked(
     }
 
     let chunks = outbound_pipeline::split_discord_reply(&reply, directive_split_long);
+    // A reply that needs more than one message moves into a thread (`threads: long`)
+    let mut moved_to_thread = false;
+    if may_start_thread && chan.threads == ThreadMode::Long && chunks.len() > 1 {
+        match threads::start(&ctx, &new_message, &question).await {
+            Ok(thread_id) => {
+                reply_channel = thread_id;
+                moved_to_thread = true;
+            }
+            Err(e) => warn!(
+                "Discord: could not start a thread, replying in the channel: {}",
+                e
+            ),
+        }
+    }
+    // A message reference only works within the channel of the message
+    directive_thread_reply &= reply_channel == new_message.channel_id;
     let mut draft_flush_ok = false;
-    if let Some(draft) = discord_draft.as_ref() {
+    if let Some(draft) = discord_draft.as_ref().filter(|_| moved_to_thread) {
+        // The placeholder points to the thread, which gets the whole reply
+        draft.flush(&threads::pointer(reply_channel)).await;
+    } else if let Some(draft) = discord_draft.as_ref() {
         const EMPTY_REPLY_FALLBACK: &str = "(No reply text.)";
         let first_chunk = chunks.first().map(|s| s.as_str()).unwrap_or("");
         if first_chunk.trim().is_empty() {
@@ -2806,8 +2849,7 @@ async fn run_discord_ollama_router_locked(
 
         let send_primary = async {
             if directive_thread_reply && si == 0 {
-                new_message
-                    .channel_id
+                reply_channel
                     .send_message(
                         &ctx,
                         CreateMessage::new()
@@ -2816,7 +2858,7 @@ async fn run_discord_ollama_router_locked(
                     )
                     .await
             } else {
-                new_message.channel_id.say(&ctx, chunk).await
+                reply_channel.say(&ctx, chunk).await
             }
         };
 
@@ -2826,9 +2868,7 @@ async fn run_discord_ollama_router_locked(
                 outbound_pipeline::log_send_timeout("discord_reply", part_no, chunks.len());
                 let _ = tokio::time::timeout(
                     send_timeout,
-                    new_message
-                        .channel_id
-                        .say(&ctx, "Reply could not be sent in time (per-send timeout)."),
+                    reply_channel.say(&ctx, "Reply could not be sent in time (per-send timeout)."),
                 )
                 .await;
                 break;
@@ -2859,8 +2899,7 @@ async fn run_discord_ollama_router_locked(
                 tokio::time::sleep(delay).await;
                 let send_retry = async {
                     if directive_thread_reply && si == 0 {
-                        new_message
-                            .channel_id
+                        reply_channel
                             .send_message(
                                 &ctx,
                                 CreateMessage::new()
@@ -2869,7 +2908,7 @@ async fn run_discord_ollama_router_locked(
                             )
                             .await
                     } else {
-                        new_message.channel_id.say(&ctx, chunk).await
+                        reply_channel.say(&ctx, chunk).await
                     }
                 };
                 say_result = match tokio::time::timeout(send_timeout, send_retry).await {
@@ -2890,6 +2929,9 @@ async fn run_discord_ollama_router_locked(
                 );
             }
         }
+        if let Ok(sent) = &say_result {
+            reply_messages.push((sent.channel_id, sent.id));
+        }
         if let Err(e) = say_result {
             let err_str = e.to_string();
             let is_permission = err_str.to_lowercase().contains("permission");
@@ -2898,9 +2940,7 @@ async fn run_discord_ollama_router_locked(
             } else {
                 "Reply could not be sent to this channel. Check bot permissions or try again later."
             };
-            match tokio::time::timeout(send_timeout, new_message.channel_id.say(&ctx, fallback))
-                .await
-            {
+            match tokio::time::timeout(send_timeout, reply_channel.say(&ctx, fallback)).await {
                 Ok(Ok(_)) => {
                     info!(
                         "Discord: sent fallback message to channel {} (reply send failed: {})",
@@ -2979,8 +3019,7 @@ async fn run_discord_ollama_router_locked(
             }
         }
         if !attachments.is_empty() {
-            let mut send_result = new_message
-                .channel_id
+            let mut send_result = reply_channel
                 .send_message(
                     &ctx,
                     CreateMessage::new()
@@ -3030,8 +3069,7 @@ async fn run_discord_ollama_router_locked(
                         }
                     }
                     if !attachments_retry.is_empty() {
-                        send_result = new_message
-                            .channel_id
+                        send_result = reply_channel
                             .send_message(
                                 &ctx,
                                 CreateMessage::new()
@@ -3047,9 +3085,12 @@ async fn run_discord_ollama_router_locked(
                     );
                 }
             }
+            if let Ok(sent) = &send_result {
+                reply_messages.push((sent.channel_id, sent.id));
+            }
             if let Err(_e) = send_result {
                 let fallback = "Could not send attachment(s) to this channel (check bot permissions: Send Messages, Attach Files).";
-                if let Err(e2) = new_message.channel_id.say(&ctx, fallback).await {
+                if let Err(e2) = reply_channel.say(&ctx, fallback).await {
                     error!(
                         "Discord: could not send fallback message for attachment failure: {}",
                         e2
@@ -3072,6 +3113,29 @@ async fn run_discord_ollama_router_locked(
 
     // Short-term memory: add assistant reply (user was added when request received); persist when > 3 messages
     crate::session_memory::add_message("discord", channel_id_u64, "assistant", &reply);
+    // Follow-ups in the thread continue from this turn
+    let mut sessions = vec![channel_id_u64];
+    if moved_to_thread {
+        crate::session_memory::add_message("discord", reply_channel.get(), "user", &content);
+        crate::session_memory::add_message("discord", reply_channel.get(), "assistant", &reply);
+        sessions.push(reply_channel.get());
+    }
+    if !reply_messages.is_empty() {
+        voice::speak_reply(
+            thread.map_or(new_message.channel_id.get(), |t| t.parent),
+            &reply,
+        );
+        reactions::record(reactions::Exchange {
+            question: (new_message.channel_id, new_message.id),
+            author: new_message.author.id,
+            content,
+            images_base64: exchange_images,
+            mode,
+            reply,
+            messages: reply_messages,
+            sessions,
+        });
+    }
 }
 
 struct Handler;
@@ -3120,9 +3184,22 @@ impl EventHandler for Handler {
                 data_about_bot.user.name, id
             );
         }
+        let commands_ctx = ctx.clone();
+        tokio::spawn(async move { slash_commands::register(&commands_ctx).await });
+        voice::ready(&ctx);
         tokio::spawn(having_fun_background_loop(ctx));
     }
 
+    async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
+        slash_commands::handle(ctx, interaction).await;
+    }
+
+    async fn reaction_add(&self, ctx: Context, reaction: Reaction) {
+        if let Some(bot_id) = bot_user_id() {
+            reactions::handle(&ctx, &reaction, bot_id).await;
+        }
+    }
+
     async fn shard_stage_update(&self, _ctx: Context, event: ShardStageUpdateEvent) {
         crate::mac_stats_info!(
             "discord/gateway",
@@ -3192,7 +3269,12 @@ impl EventHandler for Handler {
             discord_mentions_bot_effective(&ctx, &new_message, bot_id).await;
         let is_bot = new_message.author.bot;
         let chan_id = new_message.channel_id.get();
-        let chan = channel_settings(chan_id);
+        let thread = if is_dm {
+            None
+        } else {
+            threads::lookup(&ctx, new_message.channel_id, bot_id).await
+        };
+        let chan = channel_settings(thread.map_or(chan_id, |t| t.parent));
         let mode = chan.mode;
 
         let content = {
@@ -3200,13 +3282,7 @@ impl EventHandler for Handler {
             let mention_tag = format!("<@{}>", bot_id);
             sanitize_image_error_content(raw.replace(&mention_tag, "").trim())
         };
-        let attachment_images_base64 =
-            download_discord_image_attachments(&new_message.attachments).await;
-        let mut content = content;
-        if content.is_empty() && !attachment_images_base64.is_empty() {
-            content = DISCORD_IMAGE_ONLY_PROMPT.to_string();
-        }
-        if content.is_empty() {
+        if content.is_empty() && new_message.attachments.is_empty() {
             debug!("Discord: Ignoring empty message");
             return;
         }
@@ -3215,10 +3291,24 @@ impl EventHandler for Handler {
             if mode != ChannelMode::HavingFun {
                 return;
             }
-        } else if !is_dm && !mentions_bot_effective && mode == ChannelMode::MentionOnly {
+        } else if !is_dm
+            && !mentions_bot_effective
+            && mode == ChannelMode::MentionOnly
+            && !thread.is_some_and(|t| t.started_by_bot)
+        {
             return;
         }
 
+        // Only for messages the bot answers: images may go through the vision model
+        let ingested =
+            attachments::ingest(&new_message.attachments, &format!("discord:{}", chan_id)).await;
+        let content = match ingested.default_prompt() {
+            Some(prompt) if content.is_empty() => prompt.to_string(),
+            _ => content,
+        };
+        let content = ingested.append_to(content);
+        let attachment_images_base64 = ingested.images_base64;
+
         // having_fun channels: buffer the message and let the background loop respond — unless the user clearly wants tools (search, browser, screenshot, send here), then use full agent router.
         if mode == ChannelMode::HavingFun {
             let from_human_or_mention = !is_bot || mentions_bot;
@@ -3297,10 +3387,12 @@ pub async fn run_discord_client(token: String) -> Result<(), String> {
         | GatewayIntents::GUILD_MEMBERS
         | GatewayIntents::GUILD_MESSAGES
         | GatewayIntents::DIRECT_MESSAGES
-        | GatewayIntents::MESSAGE_CONTENT;
+        | GatewayIntents::MESSAGE_CONTENT
+        | GatewayIntents::GUILD_MESSAGE_REACTIONS
+        | GatewayIntents::DIRECT_MESSAGE_REACTIONS
+        | voice::intents();
 
-    let mut client = Client::builder(&token, intents)
-        .event_handler(Handler)
+    let mut client = voice::register(Client::builder(&token, intents).event_handler(Handler))
         .await
         .map_err(|e| format!("Discord client build failed: {}", e))?;
 
@@ -3621,10 +3713,6 @@ pub async fn send_message_to_channel_with_attachments(
 /// Respects Discord 429 rate limits (up to 3 retries with Retry-After + jitter).
 /// One safe transport retry (connection/DNS-style failures only) — no retry on timeout/reset.
 pub async fn send_message_to_channel(channel_id: u64, content: &str) -> Result<(), String> {
-    let token = match get_discord_token() {
-        Some(t) => t,
-        None => return Err("Discord not configured (no token)".to_string()),
-    };
     let content = if content.chars().count() > DISCORD_CONTENT_MAX_CHARS {
         crate::logging::ellipse(content, DISCORD_CONTENT_MAX_CHARS)
     } else {
@@ -3636,6 +3724,67 @@ pub async fn send_message_to_channel(channel_id: u64, content: &str) -> Result<(
             content
         );
     }
+    post_channel_message(
+        channel_id,
+        serde_json::json!({ "content": content }),
+        format!("send_message_to_channel({})", channel_id),
+    )
+    .await
+}
+
+/// Send one embed (Discord embed object JSON) to a channel; same retries and circuit breaker as
+/// [`send_message_to_channel`]. Used by the Discord alert action.
+pub async fn send_embed_to_channel(
+    channel_id: u64,
+    embed: serde_json::Value,
+) -> Result<(), String> {
+    post_channel_message(
+        channel_id,
+        serde_json::json!({ "embeds": [embed] }),
+        format!("send_embed_to_channel({})", channel_id),
+    )
+    .await
+}
+
+/// Id of the DM channel with `user_id` (Discord returns the existing one when it is already open)
+pub async fn open_dm_channel(user_id: u64) -> Result<u64, String> {
+    let token = get_discord_token().ok_or("Discord not configured (no token)")?;
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(15))
+        .build()
+        .map_err(|e| format!("HTTP client: {}", e))?;
+    let resp = client
+        .post("https://discord.com/api/v10/users/@me/channels")
+        .header("Authorization", format!("Bot {}", token))
+        .json(&serde_json::json!({ "recipient_id": user_id.to_string() }))
+        .send()
+        .await
+        .map_err(|e| crate::discord::api::user_message_for_discord_request_error(&e))?;
+    let status = resp.status();
+    if !status.is_success() {
+        let body = resp.text().await.unwrap_or_default();
+        return Err(format!("Discord API {}: {}", status, body));
+    }
+    let body: serde_json::Value = resp
+        .json()
+        .await
+        .map_err(|e| format!("Discord API: {}", e))?;
+    body.get("id")
+        .and_then(|id| id.as_str())
+        .and_then(|id| id.parse().ok())
+        .ok_or_else(|| "Discord API: DM channel without id".to_string())
+}
+
+/// POST `body` to a channel's messages, retrying rate limits and one safe transport failure
+async fn post_channel_message(
+    channel_id: u64,
+    body: serde_json::Value,
+    route: String,
+) -> Result<(), String> {
+    let token = match get_discord_token() {
+        Some(t) => t,
+        None => return Err("Discord not configured (no token)".to_string()),
+    };
     let url = format!(
         "https://discord.com/api/v10/channels/{}/messages",
         channel_id
@@ -3645,7 +3794,6 @@ pub async fn send_message_to_channel(channel_id: u64, content: &str) -> Result<(
         .build()
         .map_err(|e| format!("HTTP client: {}", e))?;
 
-    let route = format!("send_message_to_channel({})", channel_id);
     if let Err(e) = discord_http_send_allow() {
         warn!("Discord {}: outbound send skipped (circuit): {}", route, e);
         return Err(e);
@@ -3658,7 +3806,7 @@ pub async fn send_message_to_channel(channel_id: u64, content: &str) -> Result<(
             .post(&url)
             .header("Authorization", format!("Bot {}", token))
             .header("Content-Type", "application/json")
-            .json(&serde_json::json!({ "content": content }))
+            .json(&body)
             .send()
             .await
         {
@@ -3718,6 +3866,10 @@ pub async fn send_message_to_channel(channel_id: u64, content: &str) -> Result<(
 /// Loads token via get_discord_token() (env, .config.env, then Keychain).
 /// Safe to call multiple times: only one gateway thread runs at a time; reconnect after disconnect.
 pub fn spawn_discord_if_configured() {
+    if !crate::config::Config::discord_enabled() {
+        info!("Discord: Disabled in config (discordEnabled=false), skipping gateway");
+        return;
+    }
     if !DISCORD_DESIRED_ONLINE.load(Ordering::SeqCst) {
         debug!("Discord: Gateway disabled by user, skipping spawn");
         return;
@@ -0,0 +1,272 @@
+//! Reaction controls on the bot's replies
+//!
+//! The user who asked can react to any message of a reply:
+//!
+//! - 🔁 regenerates it: the reply is deleted, the exchange forgotten and the question answered
+//!   again. Only the latest reply of a conversation, so session memory stays in order.
+//! - 🗑️ deletes the reply and forgets the exchange from session memory.
+//! - 📌 saves the exchange to the conversation's long-term memory (`memory-discord-<id>.md`, see
+//!   `curated_memory`); the bot confirms with ✅.
+//!
+//! The last [`MAX_EXCHANGES`] replies are remembered while the app runs; reactions on older ones
+//! (or on replies from before a restart) are ignored.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use serenity::client::Context;
+use serenity::model::channel::{Reaction, ReactionType};
+use serenity::model::id::{ChannelId, MessageId, UserId};
+use tracing::{info, warn};
+
+use super::ChannelMode;
+
+const MAX_EXCHANGES: usize = 64;
+/// Characters of the question and of the reply in a pinned memory entry
+const PINNED_QUESTION_CHARS: usize = 160;
+const PINNED_REPLY_CHARS: usize = 400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Control {
+    Regenerate,
+    Delete,
+    Pin,
+}
+
+fn control(emoji: &ReactionType) -> Option<Control> {
+    let ReactionType::Unicode(emoji) = emoji else {
+        return None;
+    };
+    // 🗑️ arrives with or without the emoji variation selector
+    match emoji.trim_end_matches('\u{fe0f}') {
+        "🔁" => Some(Control::Regenerate),
+        "🗑" => Some(Control::Delete),
+        "📌" => Some(Control::Pin),
+        _ => None,
+    }
+}
+
+/// A question and the bot's reply to it
+#[derive(Debug, Clone)]
+pub(super) struct Exchange {
+    /// The user's message
+    pub(super) question: (ChannelId, MessageId),
+    pub(super) author: UserId,
+    /// Content as the router got it (and session memory stored it)
+    pub(super) content: String,
+    pub(super) images_base64: Vec<String>,
+    pub(super) mode: ChannelMode,
+    pub(super) reply: String,
+    /// Messages the reply consists of
+    pub(super) messages: Vec<(ChannelId, MessageId)>,
+    /// Sessions holding the exchange: the channel, and the thread a reply moved into
+    pub(super) sessions: Vec<u64>,
+}
+
+#[derive(Debug)]
+struct ExchangeLog {
+    entries: VecDeque<Exchange>,
+}
+
+impl ExchangeLog {
+    fn record(&mut self, exchange: Exchange) {
+        if self.entries.len() >= MAX_EXCHANGES {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(exchange);
+    }
+
+    /// Index of the exchange whose reply includes `message`
+    fn position(&self, message: MessageId) -> Option<usize> {
+        self.entries
+            .iter()
+            .position(|e| e.messages.iter().any(|(_, id)| *id == message))
+    }
+
+    /// Whether no later exchange shares a session with the one at `index`
+    fn is_latest(&self, index: usize) -> bool {
+        let sessions = &self.entries[index].sessions;
+        self.entries
+            .iter()
+            .skip(index + 1)
+            .all(|e| !e.sessions.iter().any(|s| sessions.contains(s)))
+    }
+}
+
+static LOG: Mutex<ExchangeLog> = Mutex::new(ExchangeLog {
+    entries: VecDeque::new(),
+});
+
+/// Remember a reply so reactions on it work
+pub(super) fn record(exchange: Exchange) {
+    if let Ok(mut log) = LOG.lock() {
+        log.record(exchange);
+    }
+}
+
+/// Call for every added reaction
+pub(super) async fn handle(ctx: &Context, reaction: &Reaction, bot_id: UserId) {
+    let Some(control) = control(&reaction.emoji) else {
+        return;
+    };
+    let Some(user_id) = reaction.user_id.filter(|id| *id != bot_id) else {
+        return;
+    };
+    let exchange = {
+        let Ok(mut log) = LOG.lock() else {
+            return;
+        };
+        let Some(index) = log.position(reaction.message_id) else {
+            return;
+        };
+        if log.entries[index].author != user_id {
+            return;
+        }
+        match control {
+            Control::Pin => log.entries[index].clone(),
+            Control::Regenerate if !log.is_latest(index) => {
+                info!("Discord: 🔁 ignored, only the latest reply of a conversation regenerates");
+                return;
+            }
+            Control::Regenerate | Control::Delete => match log.entries.remove(index) {
+                Some(exchange) => exchange,
+                None => return,
+            },
+        }
+    };
+    match control {
+        Control::Pin => pin(ctx, reaction, &exchange).await,
+        Control::Delete => {
+            info!(
+                "Discord: 🗑️ deleting a reply (message {})",
+                reaction.message_id
+            );
+            forget(ctx, &exchange).await;
+        }
+        Control::Regenerate => regenerate(ctx, exchange).await,
+    }
+}
+
+/// Delete the reply's messages and drop the exchange from session memory
+async fn forget(ctx: &Context, exchange: &Exchange) {
+    for (channel_id, message_id) in &exchange.messages {
+        if let Err(e) = channel_id.delete_message(ctx, *message_id).await {
+            warn!("Discord: could not delete message {}: {}", message_id, e);
+        }
+    }
+    for session in &exchange.sessions {
+        crate::session_memory::remove_exchange(
+            "discord",
+            *session,
+            &exchange.content,
+            &exchange.reply,
+        );
+    }
+}
+
+async fn regenerate(ctx: &Context, exchange: Exchange) {
+    let (channel_id, message_id) = exchange.question;
+    let question = match channel_id.message(ctx, message_id).await {
+        Ok(message) => message,
+        Err(e) => {
+            // Put it back so 🗑️ still works
+            warn!("Discord: 🔁 could not load the question: {}", e);
+            record(exchange);
+            return;
+        }
+    };
+    info!(
+        "Discord: 🔁 regenerating the reply to message {}",
+        message_id
+    );
+    forget(ctx, &exchange).await;
+    super::run_discord_ollama_router(
+        ctx.clone(),
+        question,
+        exchange.content,
+        exchange.images_base64,
+        exchange.mode,
+    )
+    .await;
+}
+
+async fn pin(ctx: &Context, reaction: &Reaction, exchange: &Exchange) {
+    let entry = pinned_entry(&exchange.content, &exchange.reply);
+    let result = crate::commands::curated_memory::handle_memory(
+        &format!("add {}", entry),
+        Some(reaction.channel_id.get()),
+    );
+    let saved = result.starts_with("Memory updated") || result.starts_with("Already present");
+    info!(
+        "Discord: 📌 {} (channel {})",
+        result.lines().next().unwrap_or(""),
+        reaction.channel_id
+    );
+    let ack = if saved { "✅" } else { "⚠️" };
+    if let Err(e) = reaction
+        .channel_id
+        .create_reaction(
+            ctx,
+            reaction.message_id,
+            ReactionType::Unicode(ack.to_string()),
+        )
+        .await
+    {
+        warn!("Discord: could not acknowledge 📌: {}", e);
+    }
+}
+
+/// One memory line for an exchange
+fn pinned_entry(question: &str, reply: &str) -> String {
+    let one_line = |text: &str, max: usize| {
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        crate::logging::ellipse(&text, max)
+    };
+    format!(
+        "Pinned: asked \"{}\", answered \"{}\"",
+        one_line(question, PINNED_QUESTION_CHARS),
+        one_line(reply, PINNED_REPLY_CHARS)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exchange(reply_message: u64, sessions: &[u64]) -> Exchange {
+        Exchange {
+            question: (ChannelId::new(1), MessageId::new(reply_message - 1)),
+            author: UserId::new(7),
+            content: "disk usage?".to_string(),
+            images_base64: Vec::new(),
+            mode: ChannelMode::MentionOnly,
+            reply: "61% used".to_string(),
+            messages: vec![(ChannelId::new(1), MessageId::new(reply_message))],
+            sessions: sessions.to_vec(),
+        }
+    }
+
+    #[test]
+    fn only_the_latest_reply_of_a_conversation_regenerates() {
+        let mut log = ExchangeLog {
+            entries: VecDeque::new(),
+        };
+        log.record(exchange(11, &[1]));
+        log.record(exchange(21, &[2]));
+        log.record(exchange(31, &[1, 3]));
+        assert_eq!(log.position(MessageId::new(21)), Some(1));
+        assert_eq!(log.position(MessageId::new(20)), None);
+        assert!(!log.is_latest(0));
+        assert!(log.is_latest(1));
+        assert!(log.is_latest(2));
+
+        assert_eq!(
+            control(&ReactionType::Unicode("🗑\u{fe0f}".to_string())),
+            Some(Control::Delete)
+        );
+        assert_eq!(
+            pinned_entry("disk\nusage?", "61%  used"),
+            "Pinned: asked \"disk usage?\", answered \"61% used\""
+        );
+    }
+}
@@ -0,0 +1,388 @@
+//! Discord application (slash) commands.
+//!
+//! Registered globally on the first Ready of the process: `/stats` (an embed with a chart, see
+//! `stats_embed`), `/ask`, `/task add` and `/agent run`. Every reply is ephemeral (only the
+//! invoking user sees it), so operator output does not clutter shared channels. `/ask` and
+//! `/agent run` use the same agent router as messages, queued per channel
+//! (`discord:<channel_id>`), but without the channel's session history. The `agent` and `skill`
+//! options autocomplete from `~/.mac-stats/agents/`.
+//!
+//! Free-form messages keep working as before; slash commands are an additional entry point.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serenity::builder::{
+    CreateAutocompleteResponse, CreateCommand, CreateCommandOption, CreateInteractionResponse,
+    CreateInteractionResponseFollowup, CreateInteractionResponseMessage, EditInteractionResponse,
+};
+use serenity::client::Context;
+use serenity::model::application::{
+    Command, CommandInteraction, CommandOptionType, Interaction, ResolvedOption, ResolvedValue,
+};
+use tracing::{debug, error, info, warn};
+
+use crate::commands::outbound_pipeline;
+
+/// Discord shows at most this many autocomplete choices
+const MAX_CHOICES: usize = 25;
+
+/// Set once the commands are registered; global commands survive reconnects
+static REGISTERED: AtomicBool = AtomicBool::new(false);
+
+fn definitions() -> Vec<CreateCommand> {
+    let string = |name: &str, description: &str| {
+        CreateCommandOption::new(CommandOptionType::String, name, description)
+    };
+    let agent = |description: &str| string("agent", description).set_autocomplete(true);
+    vec![
+        CreateCommand::new("stats")
+            .description("Current CPU, GPU, RAM and disk of the mac-stats host"),
+        CreateCommand::new("ask")
+            .description("Ask the mac-stats agent (the reply is only visible to you)")
+            .add_option(string("question", "What to ask").required(true))
+            .add_option(agent("Answer as this agent"))
+            .add_option(string("skill", "Use this skill (number or topic)").set_autocomplete(true)),
+        CreateCommand::new("task")
+            .description("mac-stats tasks")
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::SubCommand,
+                    "add",
+                    "Create an open task",
+                )
+                .add_sub_option(string("topic", "Short topic, e.g. research").required(true))
+                .add_sub_option(string("id", "Id within the topic, e.g. 1").required(true))
+                .add_sub_option(string("content", "What the task is about"))
+                .add_sub_option(agent("Assign the task to this agent")),
+            ),
+        CreateCommand::new("agent")
+            .description("mac-stats agents")
+            .add_option(
+                CreateCommandOption::new(
+                    CommandOptionType::SubCommand,
+                    "run",
+                    "Run an agent on a prompt",
+                )
+                .add_sub_option(agent("Agent to run").required(true))
+                .add_sub_option(string("prompt", "What the agent should do").required(true)),
+            ),
+    ]
+}
+
+/// Register the slash commands (once per process; called from Ready)
+pub(super) async fn register(ctx: &Context) {
+    if REGISTERED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    match Command::set_global_commands(&ctx.http, definitions()).await {
+        Ok(commands) => info!("Discord: registered {} slash command(s)", commands.len()),
+        Err(e) => {
+            // Try again on the next Ready
+            REGISTERED.store(false, Ordering::SeqCst);
+            warn!("Discord: registering slash commands failed: {}", e);
+        }
+    }
+}
+
+/// `EventHandler::interaction_create`: run a slash command or answer its autocomplete
+pub(super) async fn handle(ctx: Context, interaction: Interaction) {
+    match interaction {
+        Interaction::Command(cmd) => run_command(&ctx, &cmd).await,
+        Interaction::Autocomplete(cmd) => autocomplete(&ctx, &cmd).await,
+        _ => {}
+    }
+}
+
+/// Subcommand path (`task add`) and string options of an invocation
+fn invocation(name: &str, options: Vec<ResolvedOption<'_>>) -> (String, HashMap<String, String>) {
+    fn collect(
+        path: &mut String,
+        args: &mut HashMap<String, String>,
+        options: Vec<ResolvedOption<'_>>,
+    ) {
+        for option in options {
+            match option.value {
+                ResolvedValue::SubCommand(sub) | ResolvedValue::SubCommandGroup(sub) => {
+                    path.push(' ');
+                    path.push_str(option.name);
+                    collect(path, args, sub);
+                }
+                ResolvedValue::String(value) => {
+                    args.insert(option.name.to_string(), value.trim().to_string());
+                }
+                _ => {}
+            }
+        }
+    }
+    let mut path = name.to_string();
+    let mut args = HashMap::new();
+    collect(&mut path, &mut args, options);
+    (path, args)
+}
+
+async fn run_command(ctx: &Context, cmd: &CommandInteraction) {
+    let (path, args) = invocation(&cmd.data.name, cmd.data.options());
+    info!(
+        "Discord: /{} from {} (id: {}) in channel {}",
+        path, cmd.user.name, cmd.user.id, cmd.channel_id
+    );
+    // Discord wants a first response within 3 seconds; agent runs take longer
+    let defer =
+        CreateInteractionResponse::Defer(CreateInteractionResponseMessage::new().ephemeral(true));
+    if let Err(e) = cmd.create_response(&ctx.http, defer).await {
+        error!("Discord: failed to acknowledge /{}: {}", path, e);
+        return;
+    }
+
+    if path == "stats" {
+        return respond_with_stats(ctx, cmd).await;
+    }
+    let arg = |name: &str| args.get(name).map(String::as_str).filter(|v| !v.is_empty());
+    let reply = match path.as_str() {
+        "ask" => {
+            ask(
+                cmd,
+                arg("question").unwrap_or_default(),
+                arg("agent"),
+                arg("skill"),
+            )
+            .await
+        }
+        "task add" => add_task(
+            cmd.channel_id.get(),
+            arg("topic").unwrap_or_default(),
+            arg("id").unwrap_or_default(),
+            arg("content").unwrap_or_default(),
+            arg("agent"),
+        ),
+        "agent run" => match arg("agent") {
+            Some(agent) => ask(cmd, arg("prompt").unwrap_or_default(), Some(agent), None).await,
+            None => "Which agent? Pick one for the `agent` option.".to_string(),
+        },
+        _ => format!("Unknown command /{}.", path),
+    };
+    respond(ctx, cmd, &reply).await;
+}
+
+/// Replace the deferred response with `reply`; parts beyond Discord's limit are follow-ups
+async fn respond(ctx: &Context, cmd: &CommandInteraction, reply: &str) {
+    let chunks = outbound_pipeline::split_discord_reply(reply, false);
+    let first = chunks
+        .first()
+        .map(String::as_str)
+        .filter(|c| !c.trim().is_empty())
+        .unwrap_or("(No reply text.)");
+    if let Err(e) = cmd
+        .edit_response(&ctx.http, EditInteractionResponse::new().content(first))
+        .await
+    {
+        error!("Discord: failed to send /{} reply: {}", cmd.data.name, e);
+        return;
+    }
+    for chunk in chunks.iter().skip(1) {
+        let followup = CreateInteractionResponseFollowup::new()
+            .content(chunk)
+            .ephemeral(true);
+        if let Err(e) = cmd.create_followup(&ctx.http, followup).await {
+            error!(
+                "Discord: failed to send /{} follow-up: {}",
+                cmd.data.name, e
+            );
+            break;
+        }
+    }
+}
+
+/// `/stats`: the embed with its chart
+async fn respond_with_stats(ctx: &Context, cmd: &CommandInteraction) {
+    let stats = match super::stats_embed::build().await {
+        Ok(stats) => stats,
+        Err(e) => return respond(ctx, cmd, &format!("Could not read stats: {}", e)).await,
+    };
+    let mut response = EditInteractionResponse::new().embed(stats.embed);
+    if let Some(chart) = stats.chart {
+        response = response.new_attachment(chart);
+    }
+    if let Err(e) = cmd.edit_response(&ctx.http, response).await {
+        error!("Discord: failed to send /stats reply: {}", e);
+    }
+}
+
+/// `/ask` and `/agent run`: one agent router turn without channel history
+async fn ask(
+    cmd: &CommandInteraction,
+    question: &str,
+    agent: Option<&str>,
+    skill: Option<&str>,
+) -> String {
+    if question.is_empty() {
+        return "Nothing to ask.".to_string();
+    }
+    let agent_override = match agent {
+        Some(selector) => {
+            let agents = crate::agents::load_agents();
+            match crate::agents::find_agent_by_id_or_name(&agents, selector) {
+                Some(agent) => Some(agent.clone()),
+                None => return format!("Agent \"{}\" not found.", selector),
+            }
+        }
+        None => None,
+    };
+    let skill_content = match skill {
+        Some(selector) => {
+            let skills = crate::skills::load_skills();
+            match crate::skills::find_skill_by_number_or_topic(&skills, selector) {
+                Some(skill) => Some(skill.content.clone()),
+                None => return super::format_skill_not_found_error(selector),
+            }
+        }
+        None => None,
+    };
+
+    let channel_id = cmd.channel_id.get();
+    let user_id = cmd.user.id.get();
+    let display_name = cmd
+        .user
+        .global_name
+        .clone()
+        .unwrap_or_else(|| cmd.user.name.clone());
+    super::set_discord_user_name(user_id, display_name.clone());
+    let result = crate::commands::ollama::answer_with_ollama_and_fetch(
+        crate::commands::ollama::OllamaRequest {
+            question: question.to_string(),
+            discord_reply_channel_id: Some(channel_id),
+            discord_user_id: Some(user_id),
+            discord_user_name: Some(display_name),
+            skill_content,
+            agent_override,
+            allow_schedule: true,
+            retry_on_verification_no: true,
+            from_remote: true,
+            discord_is_dm: Some(cmd.guild_id.is_none()),
+            ollama_queue_key: Some(format!("discord:{}", channel_id)),
+            ..Default::default()
+        },
+    )
+    .await;
+    match result {
+        Ok(reply) => super::strip_leading_label(reply.text.trim()),
+        Err(e) => {
+            error!(
+                "Discord: /{} failed (channel {}): [{}] {}",
+                cmd.data.name,
+                channel_id,
+                e.code(),
+                e
+            );
+            e.user_message()
+        }
+    }
+}
+
+/// `/task add`: the finished-task summary is posted to the invoking channel
+fn add_task(channel_id: u64, topic: &str, id: &str, content: &str, agent: Option<&str>) -> String {
+    match crate::task::create_task(topic, id, content, agent, Some(channel_id)) {
+        Ok(path) => format!(
+            "Created task `{}`; its summary is posted here when it finishes.",
+            crate::task::task_file_name(&path)
+        ),
+        Err(e) => e,
+    }
+}
+
+async fn autocomplete(ctx: &Context, cmd: &CommandInteraction) {
+    let Some(focused) = cmd.data.autocomplete() else {
+        return;
+    };
+    let candidates = match focused.name {
+        "agent" => agent_choices(),
+        "skill" => skill_choices(),
+        _ => Vec::new(),
+    };
+    let response = choices(&candidates, focused.value).into_iter().fold(
+        CreateAutocompleteResponse::new(),
+        |response, (label, value)| response.add_string_choice(label, value),
+    );
+    if let Err(e) = cmd
+        .create_response(&ctx.http, CreateInteractionResponse::Autocomplete(response))
+        .await
+    {
+        debug!("Discord: autocomplete response failed: {}", e);
+    }
+}
+
+/// `(label, value)` per enabled agent; the value is what `find_agent_by_id_or_name` resolves
+fn agent_choices() -> Vec<(String, String)> {
+    crate::agents::load_agents()
+        .into_iter()
+        .map(|agent| {
+            let value = agent.slug.clone().unwrap_or_else(|| agent.id.clone());
+            let label = if agent.name.eq_ignore_ascii_case(&value) {
+                agent.name
+            } else {
+                format!("{} ({})", agent.name, value)
+            };
+            (label, value)
+        })
+        .collect()
+}
+
+fn skill_choices() -> Vec<(String, String)> {
+    crate::skills::load_skills()
+        .into_iter()
+        .map(|skill| {
+            (
+                format!("{}-{}", skill.number, skill.topic),
+                skill.number.to_string(),
+            )
+        })
+        .collect()
+}
+
+/// Candidates whose label contains what was typed (any case), prefix matches first
+fn choices(candidates: &[(String, String)], typed: &str) -> Vec<(String, String)> {
+    let typed = typed.trim().to_lowercase();
+    let mut matching: Vec<(bool, &(String, String))> = candidates
+        .iter()
+        .filter_map(|candidate| {
+            let label = candidate.0.to_lowercase();
+            label
+                .contains(&typed)
+                .then(|| (!label.starts_with(&typed), candidate))
+        })
+        .collect();
+    matching.sort_by_key(|(not_prefix, _)| *not_prefix);
+    matching
+        .into_iter()
+        .take(MAX_CHOICES)
+        .map(|(_, candidate)| candidate.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn choices_put_prefix_matches_first_and_cap_the_list() {
+        let candidates: Vec<(String, String)> = ["Werner (werner)", "Coder (code)", "1-code"]
+            .iter()
+            .map(|label| (label.to_string(), label.to_lowercase()))
+            .collect();
+        let labels = |typed| {
+            choices(&candidates, typed)
+                .into_iter()
+                .map(|(label, _)| label)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(labels("CO"), ["Coder (code)", "1-code"]);
+        assert_eq!(labels(""), ["Werner (werner)", "Coder (code)", "1-code"]);
+        assert!(labels("xyz").is_empty());
+
+        let many: Vec<(String, String)> = (0..40)
+            .map(|i| (format!("agent-{}", i), i.to_string()))
+            .collect();
+        assert_eq!(choices(&many, "agent").len(), MAX_CHOICES);
+    }
+}
@@ -0,0 +1,221 @@
+//! Rich stats replies: an embed with the current metrics and a chart of the last hour.
+//!
+//! Used by the `/stats` slash command and by a plain "stats" message to the bot. The fields
+//! are the rows `mac_stats stats` prints (`metrics::cli`) plus the top processes; the chart
+//! (CPU, GPU and RAM, broken across sleep and app-not-running gaps) is a PNG attachment the
+//! embed shows as its image. Right after launch, with under two history points, the embed has
+//! no image.
+
+use std::io::Cursor;
+
+use image::{DynamicImage, ImageFormat, Rgb, RgbImage};
+use imageproc::drawing::draw_line_segment_mut;
+use serenity::builder::{CreateAttachment, CreateEmbed, CreateEmbedFooter, CreateMessage};
+use serenity::client::Context;
+use serenity::model::id::ChannelId;
+use serenity::model::Timestamp;
+
+use crate::metrics::cli::{self, StatsSnapshot};
+use crate::metrics::history::{Aggregation, HistoryGap, MetricPoint};
+use crate::number_format::NumberFormat;
+use crate::state::METRICS_HISTORY;
+
+const CHART_RANGE_SECS: u64 = 3600;
+const CHART_WIDTH: u32 = 600;
+const CHART_HEIGHT: u32 = 180;
+const CHART_FILE: &str = "stats.png";
+/// Discord's dark theme, so the chart blends into the embed
+const BACKGROUND: Rgb<u8> = Rgb([47, 49, 54]);
+const GRID: Rgb<u8> = Rgb([72, 75, 81]);
+/// Charted metrics; the footer names the colors
+const SERIES: [(fn(&MetricPoint) -> f32, Rgb<u8>); 3] = [
+    (|p| p.cpu, Rgb([87, 242, 135])),
+    (|p| p.gpu, Rgb([254, 231, 92])),
+    (|p| p.ram, Rgb([88, 101, 242])),
+];
+const LEGEND: &str = "Last hour: CPU green · GPU yellow · RAM blue";
+
+pub(super) struct StatsReply {
+    pub(super) embed: CreateEmbed,
+    pub(super) chart: Option<CreateAttachment>,
+}
+
+/// A "stats" message (not the operator `/status`)
+pub(super) fn looks_like_stats_request(content: &str) -> bool {
+    let n = content
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+    matches!(
+        n.trim_end_matches(['?', '!', '.']),
+        "stats" | "/stats" | "system stats" | "show stats" | "mac-stats stats"
+    )
+}
+
+/// A fresh snapshot (sampling takes a moment, so off the async runtime) as an embed
+pub(super) async fn build() -> Result<StatsReply, String> {
+    let (snapshot, chart) = tokio::task::spawn_blocking(|| (cli::snapshot(), last_hour_chart()))
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(StatsReply {
+        embed: embed(&snapshot, chart.is_some()),
+        chart: chart.map(|png| CreateAttachment::bytes(png, CHART_FILE)),
+    })
+}
+
+/// Post the stats embed to `channel_id` (the "stats" message)
+pub(super) async fn send(ctx: &Context, channel_id: ChannelId) -> Result<(), String> {
+    let stats = build().await?;
+    let mut message = CreateMessage::new().embed(stats.embed);
+    if let Some(chart) = stats.chart {
+        message = message.add_file(chart);
+    }
+    channel_id
+        .send_message(ctx, message)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+fn embed(s: &StatsSnapshot, with_chart: bool) -> CreateEmbed {
+    let fmt = NumberFormat::current();
+    let units = crate::units::Units::current();
+    let colour: u32 = match s.metrics.cpu {
+        c if c >= 85.0 => 0xED4245,
+        c if c >= 60.0 => 0xFEE75C,
+        _ => 0x57F287,
+    };
+    let mut embed = CreateEmbed::new().title("mac-stats").colour(colour);
+    if !s.cpu.chip_info.is_empty() {
+        embed = embed.description(&s.cpu.chip_info);
+    }
+    for (label, value) in cli::summary_rows(s, &fmt, &units) {
+        if label != "Chip" {
+            embed = embed.field(label, value, true);
+        }
+    }
+    if !s.cpu.top_processes.is_empty() {
+        let lines: Vec<String> = s
+            .cpu
+            .top_processes
+            .iter()
+            .map(|p| format!("`{}` {}", fmt.percent(p.cpu as f64, 1), p.name))
+            .collect();
+        embed = embed.field("Top processes", lines.join("\n"), false);
+    }
+    if with_chart {
+        embed = embed
+            .image(format!("attachment://{}", CHART_FILE))
+            .footer(CreateEmbedFooter::new(LEGEND));
+    }
+    if let Ok(taken) = Timestamp::from_unix_timestamp(s.timestamp) {
+        embed = embed.timestamp(taken);
+    }
+    embed
+}
+
+fn last_hour_chart() -> Option<Vec<u8>> {
+    let end = chrono::Utc::now().timestamp();
+    let start = end - CHART_RANGE_SECS as i64;
+    let (points, gaps) = {
+        let history = METRICS_HISTORY.try_lock().ok()?;
+        let history = history.as_ref()?;
+        (
+            history.query(
+                CHART_RANGE_SECS,
+                Some(CHART_WIDTH as usize / 2),
+                Aggregation::Avg,
+            ),
+            history.gaps_between(start, end),
+        )
+    };
+    render_chart(&points, &gaps, start, end)
+}
+
+/// PNG of the charted metrics (0–100 %) between `start` and `end`
+fn render_chart(
+    points: &[MetricPoint],
+    gaps: &[HistoryGap],
+    start: i64,
+    end: i64,
+) -> Option<Vec<u8>> {
+    if points.len() < 2 || end <= start {
+        return None;
+    }
+    let mut img = RgbImage::from_pixel(CHART_WIDTH, CHART_HEIGHT, BACKGROUND);
+    let (w, h) = ((CHART_WIDTH - 1) as f32, (CHART_HEIGHT - 1) as f32);
+    let x = |timestamp: i64| (timestamp - start) as f32 / (end - start) as f32 * w;
+    let y = |percent: f32| h - percent.clamp(0.0, 100.0) / 100.0 * h;
+    for percent in [25.0, 50.0, 75.0] {
+        draw_line_segment_mut(&mut img, (0.0, y(percent)), (w, y(percent)), GRID);
+    }
+    for (value, colour) in SERIES {
+        for pair in points.windows(2) {
+            let (a, b) = (&pair[0], &pair[1]);
+            if gaps
+                .iter()
+                .any(|g| g.start < b.timestamp && g.end > a.timestamp)
+            {
+                continue;
+            }
+            draw_line_segment_mut(
+                &mut img,
+                (x(a.timestamp), y(value(a))),
+                (x(b.timestamp), y(value(b))),
+                colour,
+            );
+        }
+    }
+    let mut png = Vec::new();
+    DynamicImage::ImageRgb8(img)
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .ok()?;
+    Some(png)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::history::GapReason;
+
+    #[test]
+    fn chart_draws_each_series_and_breaks_across_gaps() {
+        let points: Vec<MetricPoint> = (0..=60)
+            .map(|i| i * 60)
+            .filter(|t| !(1860..2400).contains(t))
+            .map(|t| {
+                let mut p = MetricPoint::from_metrics(
+                    40.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -1.0,
+                );
+                p.timestamp = t;
+                p
+            })
+            .collect();
+        let gaps = [HistoryGap {
+            start: 1810,
+            end: 2390,
+            reason: GapReason::Sleep,
+        }];
+        let png = render_chart(&points, &gaps, 0, 3600).expect("chart");
+        let img = image::load_from_memory(&png).expect("png").to_rgb8();
+        assert_eq!(img.dimensions(), (CHART_WIDTH, CHART_HEIGHT));
+        let cpu = SERIES[0].1;
+        let column_has_cpu = |x: u32| (0..CHART_HEIGHT).any(|y| *img.get_pixel(x, y) == cpu);
+        assert!(column_has_cpu(100));
+        assert!(column_has_cpu(500));
+        // 2100 s, inside the gap
+        assert!(!column_has_cpu(349));
+
+        assert!(render_chart(&points[..1], &[], 0, 3600).is_none());
+    }
+
+    #[test]
+    fn stats_keyword_is_not_the_status_command() {
+        assert!(looks_like_stats_request("Stats"));
+        assert!(looks_like_stats_request(" /stats "));
+        assert!(looks_like_stats_request("show   stats?"));
+        assert!(!looks_like_stats_request("/status"));
+        assert!(!looks_like_stats_request("stats for last week please"));
+    }
+}
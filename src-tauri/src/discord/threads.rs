@@ -0,0 +1,169 @@
+//! Thread-aware conversations (`threads` in `discord_channels.json`)
+//!
+//! A Discord thread is a channel of its own, so everything keyed by the channel id (session
+//! memory, the serial router queue, the Ollama queue) is per thread: parallel discussions in one
+//! channel no longer share context once they live in threads. Threads inherit the settings of
+//! their parent channel, and in threads the bot started it answers without being @mentioned.
+//!
+//! When the bot opens a thread (on the user's message) depends on the channel's `threads` mode:
+//! `always` for every conversation, `long` (default) when a reply needs more than one message,
+//! or `off`.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use serenity::builder::CreateThread;
+use serenity::client::Context;
+use serenity::model::channel::{Channel, ChannelType, Message};
+use serenity::model::id::{ChannelId, UserId};
+
+/// Discord's limit for thread names
+const THREAD_NAME_MAX_CHARS: usize = 100;
+const LOOKUP_CACHE_MAX: usize = 512;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ThreadMode {
+    Off,
+    /// Move a reply that needs more than one message into a thread
+    Long,
+    /// Answer every conversation in its own thread
+    Always,
+}
+
+impl ThreadMode {
+    pub(super) fn parse(s: &str) -> Option<Self> {
+        match s {
+            "off" => Some(Self::Off),
+            "long" => Some(Self::Long),
+            "always" => Some(Self::Always),
+            _ => None,
+        }
+    }
+}
+
+/// A thread the message arrived in
+#[derive(Debug, Clone, Copy)]
+pub(super) struct ThreadInfo {
+    /// Channel the thread belongs to (whose settings apply)
+    pub(super) parent: u64,
+    pub(super) started_by_bot: bool,
+}
+
+/// Channel id -> thread info (`None` for plain channels), so only the first message needs a lookup
+static LOOKUPS: OnceLock<Mutex<HashMap<u64, Option<ThreadInfo>>>> = OnceLock::new();
+
+fn lookups() -> &'static Mutex<HashMap<u64, Option<ThreadInfo>>> {
+    LOOKUPS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn remember(channel_id: u64, info: Option<ThreadInfo>) {
+    if let Ok(mut cache) = lookups().lock() {
+        if cache.len() >= LOOKUP_CACHE_MAX {
+            cache.clear();
+        }
+        cache.insert(channel_id, info);
+    }
+}
+
+/// Thread info when `channel_id` is a thread; `None` for plain channels, DMs and failed lookups
+pub(super) async fn lookup(
+    ctx: &Context,
+    channel_id: ChannelId,
+    bot_id: UserId,
+) -> Option<ThreadInfo> {
+    let id = channel_id.get();
+    if let Some(cached) = lookups().lock().ok().and_then(|c| c.get(&id).copied()) {
+        return cached;
+    }
+    let info = match channel_id.to_channel(ctx).await {
+        Ok(Channel::Guild(channel))
+            if matches!(
+                channel.kind,
+                ChannelType::PublicThread | ChannelType::PrivateThread | ChannelType::NewsThread
+            ) =>
+        {
+            channel.parent_id.map(|parent| ThreadInfo {
+                parent: parent.get(),
+                started_by_bot: channel.owner_id == Some(bot_id),
+            })
+        }
+        Ok(_) => None,
+        Err(e) => {
+            // Not cached: the next message tries again
+            tracing::debug!("Discord: channel lookup for {} failed: {}", id, e);
+            return None;
+        }
+    };
+    remember(id, info);
+    info
+}
+
+/// Open a thread on `message` (named after `question`) and return its id
+pub(super) async fn start(
+    ctx: &Context,
+    message: &Message,
+    question: &str,
+) -> Result<ChannelId, String> {
+    let thread = message
+        .channel_id
+        .create_thread_from_message(ctx, message.id, CreateThread::new(thread_name(question)))
+        .await
+        .map_err(|e| e.to_string())?;
+    remember(
+        thread.id.get(),
+        Some(ThreadInfo {
+            parent: message.channel_id.get(),
+            started_by_bot: true,
+        }),
+    );
+    tracing::info!(
+        "Discord: started thread {} on message {} (channel {})",
+        thread.id,
+        message.id,
+        message.channel_id
+    );
+    Ok(thread.id)
+}
+
+/// Text left in the channel when a reply moved into `thread`
+pub(super) fn pointer(thread: ChannelId) -> String {
+    format!("Replied in <#{}>.", thread)
+}
+
+/// First line of the question, cut to Discord's limit
+fn thread_name(question: &str) -> String {
+    let line = question
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .unwrap_or("");
+    if line.is_empty() {
+        return "mac-stats".to_string();
+    }
+    if line.chars().count() <= THREAD_NAME_MAX_CHARS {
+        return line.to_string();
+    }
+    let mut name: String = line.chars().take(THREAD_NAME_MAX_CHARS - 1).collect();
+    name.push('…');
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thread_name_is_the_first_line_within_discords_limit() {
+        assert_eq!(
+            thread_name("\n  Why is kernel_task so busy?\nIt uses 300%"),
+            "Why is kernel_task so busy?"
+        );
+        let long = thread_name(&"a".repeat(150));
+        assert_eq!(long.chars().count(), THREAD_NAME_MAX_CHARS);
+        assert!(long.ends_with('…'));
+        assert_eq!(thread_name("  "), "mac-stats");
+
+        assert_eq!(ThreadMode::parse("always"), Some(ThreadMode::Always));
+        assert_eq!(ThreadMode::parse("sometimes"), None);
+    }
+}
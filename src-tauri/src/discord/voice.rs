@@ -0,0 +1,434 @@
+//! Spoken announcements in a Discord voice channel (`voice` in `discord_channels.json`)
+//!
+//! The bot joins the configured voice channel and speaks alerts (the `discordVoice` alert action)
+//! and its replies in the text channels listed under `replies`. Speech is synthesized locally by
+//! macOS `say` (nothing leaves the Mac besides the audio) and played through songbird;
+//! announcements queue up and play one after another.
+//!
+//! ```json
+//! "voice": { "channel": "<voice channel id>", "replies": ["<text channel id>"],
+//!            "tts_voice": "Samantha", "rate": 190, "max_chars": 600 }
+//! ```
+//!
+//! Needs a build with `--features discord-voice`; without it a configured voice channel only
+//! logs a warning.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use regex::Regex;
+use serenity::client::{ClientBuilder, Context};
+use serenity::model::gateway::GatewayIntents;
+
+/// Characters spoken when `max_chars` is not set; longer texts end at a sentence before it
+const DEFAULT_MAX_CHARS: usize = 600;
+const MAX_CHARS_LIMIT: usize = 4000;
+
+#[derive(Debug, Clone, PartialEq)]
+struct VoiceSettings {
+    /// Voice channel the bot joins
+    channel: u64,
+    /// Text channels whose replies are spoken too
+    replies: Vec<u64>,
+    /// macOS voice (`say -v '?'` lists them); the system voice when unset
+    tts_voice: Option<String>,
+    /// Words per minute
+    rate: Option<u32>,
+    max_chars: usize,
+}
+
+/// Snowflakes as strings (as elsewhere in the config) or numbers
+fn snowflake(value: &serde_json::Value) -> Option<u64> {
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|s| s.trim().parse().ok()))
+}
+
+fn parse_settings(value: &serde_json::Value) -> Option<VoiceSettings> {
+    let channel = snowflake(value.get("channel")?)?;
+    let replies = value
+        .get("replies")
+        .and_then(|v| v.as_array())
+        .map(|ids| ids.iter().filter_map(snowflake).collect())
+        .unwrap_or_default();
+    let tts_voice = value
+        .get("tts_voice")
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string());
+    let rate = value
+        .get("rate")
+        .and_then(|v| v.as_u64())
+        .map(|n| n.clamp(80, 400) as u32);
+    let max_chars = value
+        .get("max_chars")
+        .and_then(|v| v.as_u64())
+        .map(|n| (n as usize).clamp(50, MAX_CHARS_LIMIT))
+        .unwrap_or(DEFAULT_MAX_CHARS);
+    Some(VoiceSettings {
+        channel,
+        replies,
+        tts_voice,
+        rate,
+        max_chars,
+    })
+}
+
+type SettingsCache = Option<(Option<std::time::SystemTime>, Option<VoiceSettings>)>;
+
+/// Parsed `voice` block, reloaded when `discord_channels.json` changes
+static SETTINGS: Mutex<SettingsCache> = Mutex::new(None);
+
+fn settings() -> Option<VoiceSettings> {
+    let mtime = super::discord_channels_file_mtime();
+    let mut cache = SETTINGS.lock().ok()?;
+    if let Some((cached_mtime, settings)) = cache.as_ref() {
+        if *cached_mtime == mtime {
+            return settings.clone();
+        }
+    }
+    let settings = std::fs::read_to_string(crate::config::Config::discord_channels_path())
+        .ok()
+        .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+        .and_then(|parsed| parse_settings(parsed.get("voice")?));
+    *cache = Some((mtime, settings.clone()));
+    settings
+}
+
+/// Gateway intents voice needs (the bot's own voice state)
+pub(super) fn intents() -> GatewayIntents {
+    if cfg!(feature = "discord-voice") {
+        GatewayIntents::GUILD_VOICE_STATES
+    } else {
+        GatewayIntents::empty()
+    }
+}
+
+/// Attach the voice manager to the client
+pub(super) fn register(builder: ClientBuilder) -> ClientBuilder {
+    player::register(builder)
+}
+
+/// Call on Ready: joins the voice channel right away when one is configured
+pub(super) fn ready(ctx: &Context) {
+    player::set_http(ctx);
+    if let Some(settings) = settings().filter(|s| !feature_missing(s.channel)) {
+        tokio::spawn(async move {
+            if let Err(e) = player::join(settings.channel).await {
+                tracing::warn!(
+                    "Discord voice: could not join channel {}: {}",
+                    settings.channel,
+                    e
+                );
+            }
+        });
+    }
+}
+
+/// Speak an alert; a no-op when no voice channel is configured
+pub fn speak_alert(message: &str) {
+    let text = message.to_string();
+    tauri::async_runtime::spawn(async move { speak(&text).await });
+}
+
+/// Speak a reply sent to `channel_id` when that channel is listed under `replies`
+pub(super) fn speak_reply(channel_id: u64, reply: &str) {
+    if !settings().is_some_and(|s| s.replies.contains(&channel_id)) {
+        return;
+    }
+    let text = reply.to_string();
+    tokio::spawn(async move { speak(&text).await });
+}
+
+/// Whether voice is configured in a build without it (warns once)
+fn feature_missing(channel: u64) -> bool {
+    if cfg!(feature = "discord-voice") {
+        return false;
+    }
+    static WARNED: AtomicBool = AtomicBool::new(false);
+    if !WARNED.swap(true, Ordering::Relaxed) {
+        tracing::warn!(
+            "Discord voice: channel {} ignored — this build lacks the `discord-voice` feature",
+            channel
+        );
+    }
+    true
+}
+
+/// Synthesis and enqueueing in order, so announcements play in the order they came
+static SPEAKING: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+async fn speak(text: &str) {
+    let Some(settings) = settings().filter(|s| !feature_missing(s.channel)) else {
+        return;
+    };
+    let text = spoken_text(text, settings.max_chars);
+    if text.is_empty() {
+        return;
+    }
+    let _order = SPEAKING.lock().await;
+    let path = match synthesize(&text, &settings).await {
+        Ok(path) => path,
+        Err(e) => {
+            tracing::warn!("Discord voice: speech synthesis failed: {}", e);
+            return;
+        }
+    };
+    match player::play(settings.channel, path.clone()).await {
+        Ok(()) => tracing::info!(
+            "Discord voice: speaking {} chars in channel {}",
+            text.chars().count(),
+            settings.channel
+        ),
+        Err(e) => {
+            let _ = std::fs::remove_file(&path);
+            tracing::warn!(
+                "Discord voice: could not play in channel {}: {}",
+                settings.channel,
+                e
+            );
+        }
+    }
+}
+
+/// Chat text as it should be read out: no markdown, code, links or raw mentions, and at most
+/// `max` characters ending at a sentence where possible
+fn spoken_text(text: &str, max: usize) -> String {
+    static PATTERNS: OnceLock<[(Regex, &'static str); 5]> = OnceLock::new();
+    let patterns = PATTERNS.get_or_init(|| {
+        [
+            (
+                Regex::new(r"(?s)```.*?(```|$)").expect("code block regex"),
+                " (code omitted) ",
+            ),
+            (Regex::new(r"https?://\S+").expect("url regex"), "link"),
+            (Regex::new(r"<a?:(\w+):\d+>").expect("emoji regex"), "$1"),
+            (Regex::new(r"<(@[!&]?|#)\d+>").expect("mention regex"), ""),
+            (
+                Regex::new(r"(?m)^\s*(#{1,6}|>+|[-*+]|\d+\.)\s+").expect("line marker regex"),
+                "",
+            ),
+        ]
+    });
+    let mut text = text.to_string();
+    for (re, replacement) in patterns {
+        text = re.replace_all(&text, *replacement).into_owned();
+    }
+    let text = text
+        .replace(['*', '_', '~', '`', '|'], "")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if text.chars().count() <= max {
+        return text;
+    }
+    let cut: String = text.chars().take(max).collect();
+    let end = cut
+        .rfind(['.', '!', '?'])
+        .map(|i| i + 1)
+        .or_else(|| cut.rfind(' '))
+        .unwrap_or(cut.len());
+    cut[..end].trim_end().to_string()
+}
+
+/// Render `text` to a WAV file with macOS `say`
+async fn synthesize(text: &str, settings: &VoiceSettings) -> Result<PathBuf, String> {
+    use std::io::Write;
+
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    let path = std::env::temp_dir().join(format!(
+        "mac-stats-voice-{}-{}.wav",
+        std::process::id(),
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    ));
+    let mut command = std::process::Command::new("/usr/bin/say");
+    command
+        .arg("-o")
+        .arg(&path)
+        .args(["--file-format=WAVE", "--data-format=LEI16@48000"]);
+    if let Some(voice) = &settings.tts_voice {
+        command.args(["-v", voice]);
+    }
+    if let Some(rate) = settings.rate {
+        command.arg("-r").arg(rate.to_string());
+    }
+    // The text goes through stdin, so it is never taken for an option
+    command.stdin(std::process::Stdio::piped());
+    let text = text.to_string();
+    let status = tokio::task::spawn_blocking(move || {
+        let mut child = command.spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
+        child.wait()
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| format!("say: {}", e))?;
+    if !status.success() {
+        let _ = std::fs::remove_file(&path);
+        return Err(format!("say exited with {}", status));
+    }
+    Ok(path)
+}
+
+#[cfg(feature = "discord-voice")]
+mod player {
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+
+    use serenity::async_trait;
+    use serenity::client::{ClientBuilder, Context};
+    use serenity::http::Http;
+    use serenity::model::channel::Channel;
+    use serenity::model::id::{ChannelId, GuildId};
+    use songbird::events::{Event, EventContext, EventHandler, TrackEvent};
+    use songbird::{SerenityInit, Songbird};
+
+    static MANAGER: Mutex<Option<Arc<Songbird>>> = Mutex::new(None);
+    static HTTP: Mutex<Option<Arc<Http>>> = Mutex::new(None);
+    /// Voice channel -> its guild
+    static GUILD: Mutex<Option<(u64, GuildId)>> = Mutex::new(None);
+
+    pub(super) fn register(builder: ClientBuilder) -> ClientBuilder {
+        let manager = Songbird::serenity();
+        if let Ok(mut g) = MANAGER.lock() {
+            *g = Some(manager.clone());
+        }
+        builder.register_songbird_with(manager)
+    }
+
+    pub(super) fn set_http(ctx: &Context) {
+        if let Ok(mut g) = HTTP.lock() {
+            *g = Some(ctx.http.clone());
+        }
+    }
+
+    async fn guild_of(channel: u64) -> Result<GuildId, String> {
+        if let Some((id, guild)) = GUILD.lock().ok().and_then(|g| *g) {
+            if id == channel {
+                return Ok(guild);
+            }
+        }
+        let http = HTTP
+            .lock()
+            .ok()
+            .and_then(|g| g.clone())
+            .ok_or("Discord is not connected")?;
+        let guild = match ChannelId::new(channel).to_channel(&*http).await {
+            Ok(Channel::Guild(c)) => c.guild_id,
+            Ok(_) => return Err("not a server voice channel".to_string()),
+            Err(e) => return Err(e.to_string()),
+        };
+        if let Ok(mut g) = GUILD.lock() {
+            *g = Some((channel, guild));
+        }
+        Ok(guild)
+    }
+
+    /// The call in `channel`, joining it unless the bot is there already
+    pub(super) async fn join(
+        channel: u64,
+    ) -> Result<Arc<tokio::sync::Mutex<songbird::Call>>, String> {
+        let manager = MANAGER
+            .lock()
+            .ok()
+            .and_then(|g| g.clone())
+            .ok_or("voice is not set up")?;
+        let guild = guild_of(channel).await?;
+        let channel_id = ChannelId::new(channel);
+        if let Some(call) = manager.get(guild) {
+            if call.lock().await.current_channel() == Some(channel_id.into()) {
+                return Ok(call);
+            }
+        }
+        let call = manager
+            .join(guild, channel_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        tracing::info!("Discord voice: joined channel {}", channel);
+        Ok(call)
+    }
+
+    /// Queue the WAV at `path`; the file is deleted once it played
+    pub(super) async fn play(channel: u64, path: PathBuf) -> Result<(), String> {
+        let call = join(channel).await?;
+        let mut call = call.lock().await;
+        let track = call
+            .enqueue_input(songbird::input::File::new(path.clone()).into())
+            .await;
+        for event in [TrackEvent::End, TrackEvent::Error] {
+            let _ = track.add_event(Event::Track(event), RemoveFile(path.clone()));
+        }
+        Ok(())
+    }
+
+    struct RemoveFile(PathBuf);
+
+    #[async_trait]
+    impl EventHandler for RemoveFile {
+        async fn act(&self, _ctx: &EventContext<'_>) -> Option<Event> {
+            let _ = std::fs::remove_file(&self.0);
+            None
+        }
+    }
+}
+
+#[cfg(not(feature = "discord-voice"))]
+mod player {
+    use std::path::PathBuf;
+
+    use serenity::client::{ClientBuilder, Context};
+
+    pub(super) fn register(builder: ClientBuilder) -> ClientBuilder {
+        builder
+    }
+
+    pub(super) fn set_http(_ctx: &Context) {}
+
+    pub(super) async fn join(_channel: u64) -> Result<(), String> {
+        Err("this build lacks the `discord-voice` feature".to_string())
+    }
+
+    pub(super) async fn play(_channel: u64, _path: PathBuf) -> Result<(), String> {
+        Err("this build lacks the `discord-voice` feature".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_parse_and_replies_read_like_speech() {
+        let settings = parse_settings(&serde_json::json!({
+            "channel": "1234567890123456789",
+            "replies": ["42", 43, "nope"],
+            "rate": 1000
+        }))
+        .unwrap();
+        assert_eq!(settings.channel, 1234567890123456789);
+        assert_eq!(settings.replies, vec![42, 43]);
+        assert_eq!(settings.rate, Some(400));
+        assert_eq!(settings.max_chars, DEFAULT_MAX_CHARS);
+        assert!(parse_settings(&serde_json::json!({"replies": ["42"]})).is_none());
+
+        assert_eq!(
+            spoken_text(
+                "## Disk\n- **61%** used, see https://example.com <@123>\n```\ndf -h\n```",
+                600
+            ),
+            "Disk 61% used, see link (code omitted)"
+        );
+        assert_eq!(
+            spoken_text("CPU is busy. Memory is fine. Disk is almost full", 35),
+            "CPU is busy. Memory is fine."
+        );
+        assert_eq!(
+            spoken_text("no sentence end here at all", 12),
+            "no sentence"
+        );
+    }
+}
@@ -0,0 +1,424 @@
+//! `mac_stats doctor`: one report of everything that commonly breaks on a user's Mac, for bug
+//! triage ("paste the output of `mac_stats doctor`").
+//!
+//! Sections: the capabilities probe (`capabilities::probe`), direct SMC and IOReport checks,
+//! App Sandbox and TCC permissions, notifications, `config.json` and `debug.log`. Each line is
+//! pass / info / warn / fail; colored when stdout is a terminal and `NO_COLOR` is unset. The
+//! exit code is 1 when any check fails. Apart from one test notification and a probe file in
+//! `~/.mac-stats` (removed again) nothing is changed.
+//!
+//! TCC answers for the process that asks: run from Terminal, Full Disk Access reflects
+//! Terminal's permission, not the app's.
+
+use std::io::IsTerminal;
+use std::path::Path;
+
+use crate::capabilities;
+use crate::config::{schema, Config, MenuBarExtra};
+
+/// `debug.log` is rotated daily; beyond this something logs in a loop
+const LOG_WARN_BYTES: u64 = 100 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Pass,
+    /// Not a problem, worth knowing (e.g. no battery on a desktop)
+    Info,
+    Warn,
+    Fail,
+}
+
+impl Status {
+    fn mark(self) -> &'static str {
+        match self {
+            Self::Pass => "✓",
+            Self::Info => "·",
+            Self::Warn => "!",
+            Self::Fail => "✗",
+        }
+    }
+
+    /// ANSI color code
+    fn color(self) -> &'static str {
+        match self {
+            Self::Pass => "32",
+            Self::Info => "2",
+            Self::Warn => "33",
+            Self::Fail => "31",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+}
+
+fn check(name: &'static str, status: Status, detail: impl Into<String>) -> Check {
+    Check {
+        name,
+        status,
+        detail: detail.into(),
+    }
+}
+
+fn format_line(check: &Check, color: bool) -> String {
+    let mark = if color {
+        format!(
+            "\x1b[{}m{}\x1b[0m",
+            check.status.color(),
+            check.status.mark()
+        )
+    } else {
+        check.status.mark().to_string()
+    };
+    format!("  {} {:<18} {}", mark, check.name, check.detail)
+}
+
+fn capability_checks() -> Vec<Check> {
+    let caps = capabilities::probe();
+    let found = |name, ok: bool, yes: &str, no: &str, missing: Status| {
+        if ok {
+            check(name, Status::Pass, yes)
+        } else {
+            check(name, missing, no)
+        }
+    };
+    vec![
+        check(
+            "architecture",
+            Status::Info,
+            if caps.apple_silicon {
+                "Apple Silicon"
+            } else {
+                "Intel"
+            },
+        ),
+        found(
+            "CPU temperature",
+            caps.temperature,
+            "SMC reports it",
+            "no CPU temperature key (virtual machine or unknown model?)",
+            Status::Warn,
+        ),
+        found(
+            "GPU temperature",
+            caps.gpu_temperature,
+            "SMC GPU cluster keys found",
+            "no GPU temperature key",
+            Status::Info,
+        ),
+        found(
+            "fan",
+            caps.fan,
+            "SMC reports a fan",
+            "no fan (fanless Mac?)",
+            Status::Info,
+        ),
+        found(
+            "CPU frequency",
+            caps.frequency,
+            "available",
+            "not readable",
+            Status::Warn,
+        ),
+        found(
+            "CPU / GPU power",
+            caps.cpu_power,
+            "IOReport energy channels",
+            if caps.apple_silicon {
+                "no IOReport energy channels"
+            } else {
+                "not available on Intel"
+            },
+            if caps.apple_silicon {
+                Status::Warn
+            } else {
+                Status::Info
+            },
+        ),
+        found(
+            "powermetrics",
+            caps.powermetrics,
+            "installed",
+            "/usr/bin/powermetrics missing",
+            Status::Info,
+        ),
+        found(
+            "battery",
+            caps.battery,
+            "present",
+            "none (desktop Mac)",
+            Status::Info,
+        ),
+    ]
+}
+
+fn hardware_checks() -> Vec<Check> {
+    let smc = match macsmc::Smc::connect() {
+        Ok(_) => check("SMC", Status::Pass, "AppleSMC connection opened"),
+        Err(e) => check(
+            "SMC",
+            Status::Fail,
+            format!("cannot open AppleSMC ({:?}): no temperatures or fans", e),
+        ),
+    };
+    let ioreport = if !capabilities::apple_silicon() {
+        check("IOReport", Status::Info, "not used on Intel")
+    } else {
+        let frequency = crate::ffi::ioreport::probe_cpu_performance_channels_available();
+        let power = crate::ffi::ioreport::probe_power_channels_available();
+        match (frequency, power) {
+            (true, true) => check(
+                "IOReport",
+                Status::Pass,
+                "performance-state and energy channels",
+            ),
+            (false, false) => check(
+                "IOReport",
+                Status::Fail,
+                "no channels: no CPU frequency or power",
+            ),
+            (true, false) => check("IOReport", Status::Warn, "no energy channels"),
+            (false, true) => check("IOReport", Status::Warn, "no performance-state channels"),
+        }
+    };
+    vec![smc, ioreport]
+}
+
+fn permission_checks() -> Vec<Check> {
+    let sandbox = match std::env::var("APP_SANDBOX_CONTAINER_ID") {
+        Ok(container) => check(
+            "App Sandbox",
+            Status::Fail,
+            format!(
+                "sandboxed ({}): SMC, IOReport and process lists are blocked",
+                container
+            ),
+        ),
+        Err(_) => check("App Sandbox", Status::Pass, "not sandboxed"),
+    };
+    let tcc_db = std::env::var("HOME")
+        .ok()
+        .map(|h| Path::new(&h).join("Library/Application Support/com.apple.TCC/TCC.db"))
+        .filter(|p| p.exists());
+    let full_disk = match tcc_db.map(std::fs::File::open) {
+        Some(Ok(_)) => check("Full Disk Access", Status::Pass, "granted"),
+        Some(Err(_)) => check(
+            "Full Disk Access",
+            Status::Info,
+            "not granted (metrics do not need it)",
+        ),
+        None => check("Full Disk Access", Status::Info, "TCC database not found"),
+    };
+    let calendar_configured = Config::menu_bar_extras()
+        .iter()
+        .any(|extra| matches!(extra, MenuBarExtra::NextEvent { .. }));
+    let calendar_access = crate::ffi::calendar::access_label();
+    let calendar = match (calendar_configured, calendar_access) {
+        (_, "granted") => check("Calendar", Status::Pass, "granted"),
+        (true, access) => check(
+            "Calendar",
+            Status::Warn,
+            format!("{} but a nextEvent menu bar column is configured", access),
+        ),
+        (false, access) => check("Calendar", Status::Info, format!("{} (not used)", access)),
+    };
+    vec![sandbox, full_disk, calendar]
+}
+
+/// Post a test notification the way alerts do (`osascript`, shown as Script Editor)
+fn notification_check() -> Check {
+    let sent = std::process::Command::new("/usr/bin/osascript")
+        .arg("-e")
+        .arg("display notification \"Test notification from mac_stats doctor\" with title \"mac-stats\"")
+        .output();
+    match sent {
+        Ok(out) if out.status.success() => check(
+            "notifications",
+            Status::Pass,
+            "test notification sent; if none appeared, allow Script Editor in System Settings > Notifications",
+        ),
+        Ok(out) => check(
+            "notifications",
+            Status::Fail,
+            format!(
+                "osascript {}: {}",
+                out.status,
+                String::from_utf8_lossy(&out.stderr).trim()
+            ),
+        ),
+        Err(e) => check("notifications", Status::Fail, format!("osascript: {}", e)),
+    }
+}
+
+/// `config.json` contents (`config::schema`): errors fail, unknown keys warn
+fn config_text_check(text: &str) -> Check {
+    let (errors, warnings) = schema::validate_text(text);
+    let first = |issues: &[schema::ConfigIssue]| {
+        issues.first().map_or_else(String::new, |i| match &i.key {
+            Some(key) => format!("{}: {}", key, i.message),
+            None => i.message.clone(),
+        })
+    };
+    match (errors.len(), warnings.len()) {
+        (0, 0) => check("config.json", Status::Pass, "valid"),
+        (0, n) => check(
+            "config.json",
+            Status::Warn,
+            format!("{} warnings, e.g. {}", n, first(&warnings)),
+        ),
+        (n, _) => check(
+            "config.json",
+            Status::Fail,
+            format!(
+                "{} errors, e.g. {} (`mac_stats config validate` lists all)",
+                n,
+                first(&errors)
+            ),
+        ),
+    }
+}
+
+fn file_checks() -> Vec<Check> {
+    let config_path = Config::config_file_path();
+    let config = match std::fs::read_to_string(&config_path) {
+        Ok(text) => config_text_check(&text),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => check(
+            "config.json",
+            Status::Info,
+            format!("{} missing (defaults are used)", config_path.display()),
+        ),
+        Err(e) => check(
+            "config.json",
+            Status::Fail,
+            format!("{}: {}", config_path.display(), e),
+        ),
+    };
+    vec![
+        config,
+        writable_check("config folder", &config_path),
+        log_check(),
+    ]
+}
+
+/// Whether the folder holding `path` accepts new files
+fn writable_check(name: &'static str, path: &Path) -> Check {
+    let Some(dir) = path.parent() else {
+        return check(
+            name,
+            Status::Fail,
+            format!("{}: no parent folder", path.display()),
+        );
+    };
+    let probe = dir.join(".doctor-write-test");
+    let result = std::fs::write(&probe, b"");
+    let _ = std::fs::remove_file(&probe);
+    match result {
+        Ok(()) => check(name, Status::Pass, format!("{} is writable", dir.display())),
+        Err(e) => check(name, Status::Fail, format!("{}: {}", dir.display(), e)),
+    }
+}
+
+fn log_check() -> Check {
+    let path = Config::log_file_path();
+    let meta = match std::fs::metadata(&path) {
+        Ok(meta) => meta,
+        Err(e) => {
+            return check(
+                "debug.log",
+                Status::Warn,
+                format!("{}: {}", path.display(), e),
+            )
+        }
+    };
+    let mb = meta.len() as f64 / (1024.0 * 1024.0);
+    if std::fs::OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .is_err()
+    {
+        return check(
+            "debug.log",
+            Status::Fail,
+            format!("{} is not writable", path.display()),
+        );
+    }
+    if meta.len() > LOG_WARN_BYTES {
+        return check(
+            "debug.log",
+            Status::Warn,
+            format!(
+                "{:.0} MB since the last daily rotation: something logs in a loop",
+                mb
+            ),
+        );
+    }
+    check("debug.log", Status::Pass, format!("{:.1} MB, writable", mb))
+}
+
+/// Run every check and print the report. Returns the process exit code.
+pub fn run_doctor_stdio() -> i32 {
+    let color = std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none();
+    println!("mac-stats doctor {}", Config::version_display());
+    println!("────────────────────────────");
+    let sections: [(&str, Vec<Check>); 5] = [
+        ("Capabilities", capability_checks()),
+        ("Hardware access", hardware_checks()),
+        ("Permissions", permission_checks()),
+        ("Notifications", vec![notification_check()]),
+        ("Files", file_checks()),
+    ];
+    let mut failed = 0;
+    let mut warned = 0;
+    for (title, checks) in &sections {
+        println!("{}", title);
+        for c in checks {
+            println!("{}", format_line(c, color));
+            match c.status {
+                Status::Fail => failed += 1,
+                Status::Warn => warned += 1,
+                _ => {}
+            }
+        }
+    }
+    println!();
+    if failed == 0 {
+        println!("No problems found ({} warnings).", warned);
+        0
+    } else {
+        println!("{} checks failed, {} warnings.", failed, warned);
+        tracing::warn!("doctor: {} checks failed", failed);
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_text_is_checked_and_lines_are_colored() {
+        assert_eq!(
+            config_text_check(r#"{"menuBarItems": ["main"]}"#).status,
+            Status::Pass
+        );
+        assert_eq!(config_text_check("[1, 2]").status, Status::Fail);
+        let broken = config_text_check("{\n  \"apiPort\": 1,\n}");
+        assert_eq!(broken.status, Status::Fail);
+        assert!(broken.detail.contains("line 3"), "{}", broken.detail);
+        assert_eq!(
+            config_text_check(r#"{"menuBarItem": []}"#).status,
+            Status::Warn
+        );
+
+        let c = check("SMC", Status::Fail, "cannot open");
+        assert_eq!(
+            format_line(&c, false),
+            format!("  ✗ {:<18} cannot open", "SMC")
+        );
+        assert!(format_line(&c, true).starts_with("  \x1b[31m✗\x1b[0m SMC"));
+    }
+}
@@ -576,7 +576,7 @@ pub fn run_organizer_pass() {
 
 /// Background tick: run only when enabled, interval not `off`, and schedule is due.
 pub fn run_if_due() {
-    if !Config::downloads_organizer_enabled() || interval_off() {
+    if !Config::downloads_organizer_enabled() || interval_off() || crate::guest_mode::is_active() {
         return;
     }
     let state = load_organizer_state();
@@ -0,0 +1,172 @@
+//! Safe wrappers for EventKit (next calendar event) and `NSTimeZone` (world clock offsets)
+//!
+//! Used by the optional informational menu bar columns (`menuBarExtras`). Classes are looked
+//! up at runtime and EventKit is only touched when a `nextEvent` column is configured, so
+//! users who never add one are never asked for calendar access.
+
+use block2::RcBlock;
+use objc2::msg_send;
+use objc2::rc::Retained;
+use objc2::runtime::{AnyClass, AnyObject, Bool};
+use objc2_foundation::NSString;
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[link(name = "EventKit", kind = "framework")]
+extern "C" {}
+
+/// `EKEntityTypeEvent`
+const ENTITY_TYPE_EVENT: usize = 0;
+/// `EKAuthorizationStatus`: authorized (pre-14) / full access (14+)
+const STATUS_FULL_ACCESS: isize = 3;
+/// `EKAuthorizationStatus`: not determined (user was never asked)
+const STATUS_NOT_DETERMINED: isize = 0;
+
+static ACCESS_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// EKEventStore is expensive to create; keep one per calling thread (it is not `Send`).
+    static EVENT_STORE: RefCell<Option<Retained<AnyObject>>> = const { RefCell::new(None) };
+}
+
+fn ns_date(unix_secs: f64) -> Option<Retained<AnyObject>> {
+    let cls = AnyClass::get(c"NSDate")?;
+    // SAFETY: `dateWithTimeIntervalSince1970:` is a Foundation class method taking a double
+    // and returning an autoreleased NSDate (retained by objc2); nil is handled by Option.
+    unsafe { msg_send![cls, dateWithTimeIntervalSince1970: unix_secs] }
+}
+
+fn authorization_status() -> Option<isize> {
+    let cls = AnyClass::get(c"EKEventStore")?;
+    // SAFETY: class method taking an EKEntityType (NSUInteger) and returning an NSInteger.
+    Some(unsafe { msg_send![cls, authorizationStatusForEntityType: ENTITY_TYPE_EVENT] })
+}
+
+/// Calendar permission in words (`mac_stats doctor`); never prompts
+pub fn access_label() -> &'static str {
+    match authorization_status() {
+        None => "EventKit not available",
+        Some(STATUS_NOT_DETERMINED) => "not asked yet",
+        Some(STATUS_FULL_ACCESS) => "granted",
+        Some(_) => "denied or restricted",
+    }
+}
+
+fn event_store() -> Option<Retained<AnyObject>> {
+    EVENT_STORE.with(|cell| {
+        if cell.borrow().is_none() {
+            let cls = AnyClass::get(c"EKEventStore")?;
+            // SAFETY: `new` on an NSObject subclass returns a +1 instance (or nil).
+            let store: Option<Retained<AnyObject>> = unsafe { msg_send![cls, new] };
+            *cell.borrow_mut() = store;
+        }
+        cell.borrow().clone()
+    })
+}
+
+/// Ask for calendar access once per launch. The answer arrives asynchronously; callers see it
+/// on their next lookup.
+fn request_access_once(store: &AnyObject) {
+    if ACCESS_REQUESTED.swap(true, Ordering::Relaxed) {
+        return;
+    }
+    let completion = RcBlock::new(|granted: Bool, _error: *mut AnyObject| {
+        tracing::info!(
+            "Calendar access for menu bar next-event column: {}",
+            if granted.as_bool() {
+                "granted"
+            } else {
+                "denied"
+            }
+        );
+    });
+    // SAFETY: both selectors take a `void (^)(BOOL, NSError *)` completion block, which
+    // EventKit copies before returning; `respondsToSelector:` picks the macOS 14+ variant.
+    unsafe {
+        let full_access: bool = msg_send![
+            store,
+            respondsToSelector: objc2::sel!(requestFullAccessToEventsWithCompletion:)
+        ];
+        if full_access {
+            let _: () = msg_send![store, requestFullAccessToEventsWithCompletion: &*completion];
+        } else {
+            let _: () = msg_send![
+                store,
+                requestAccessToEntityType: ENTITY_TYPE_EVENT,
+                completion: &*completion
+            ];
+        }
+    }
+}
+
+/// Start (unix seconds) of the next timed event in any calendar starting after `now_secs`
+/// and within `lookahead_secs`. All-day events are ignored. `Err` when EventKit is missing or
+/// access was not granted (the first call triggers the permission prompt).
+pub fn next_event_start(now_secs: i64, lookahead_secs: i64) -> Result<Option<i64>, String> {
+    let store = event_store().ok_or_else(|| "EventKit not available".to_string())?;
+    match authorization_status() {
+        Some(STATUS_FULL_ACCESS) => {}
+        Some(STATUS_NOT_DETERMINED) => {
+            request_access_once(&store);
+            return Err("Calendar access pending".to_string());
+        }
+        _ => return Err("Calendar access not granted".to_string()),
+    }
+    let start = ns_date(now_secs as f64).ok_or_else(|| "NSDate unavailable".to_string())?;
+    let end = ns_date((now_secs + lookahead_secs) as f64)
+        .ok_or_else(|| "NSDate unavailable".to_string())?;
+    // SAFETY: EKEventStore API with non-nil NSDate arguments; a nil calendars array means
+    // "all calendars". The returned NSArray and its EKEvent elements are only read here.
+    unsafe {
+        let predicate: Option<Retained<AnyObject>> = msg_send![
+            &*store,
+            predicateForEventsWithStartDate: &*start,
+            endDate: &*end,
+            calendars: std::ptr::null::<AnyObject>()
+        ];
+        let Some(predicate) = predicate else {
+            return Ok(None);
+        };
+        let events: Option<Retained<AnyObject>> =
+            msg_send![&*store, eventsMatchingPredicate: &*predicate];
+        let Some(events) = events else {
+            return Ok(None);
+        };
+        let count: usize = msg_send![&*events, count];
+        let mut next: Option<i64> = None;
+        for i in 0..count {
+            let event: *mut AnyObject = msg_send![&*events, objectAtIndex: i];
+            let Some(event) = event.as_ref() else {
+                continue;
+            };
+            let all_day: bool = msg_send![event, isAllDay];
+            if all_day {
+                continue;
+            }
+            let date: *mut AnyObject = msg_send![event, startDate];
+            let Some(date) = date.as_ref() else {
+                continue;
+            };
+            let secs: f64 = msg_send![date, timeIntervalSince1970];
+            let secs = secs as i64;
+            // Events already in progress also match the predicate; only count upcoming ones.
+            if secs > now_secs && next.is_none_or(|n| secs < n) {
+                next = Some(secs);
+            }
+        }
+        Ok(next)
+    }
+}
+
+/// Current UTC offset in seconds for an IANA zone name (DST-aware), or `None` if unknown.
+pub fn time_zone_offset_secs(name: &str) -> Option<i32> {
+    let cls = AnyClass::get(c"NSTimeZone")?;
+    let name = NSString::from_str(name);
+    // SAFETY: `timeZoneWithName:` returns nil for unknown names (handled by Option);
+    // `secondsFromGMT` is a read-only NSInteger property.
+    unsafe {
+        let zone: Option<Retained<AnyObject>> = msg_send![cls, timeZoneWithName: &*name];
+        let secs: isize = msg_send![&*zone?, secondsFromGMT];
+        i32::try_from(secs).ok()
+    }
+}
@@ -0,0 +1,49 @@
+//! System memory pressure level (`sysctl kern.memorystatus_vm_pressure_level`)
+//!
+//! The same level that drives `DISPATCH_SOURCE_TYPE_MEMORYPRESSURE`, read by polling so no
+//! dispatch source or block has to cross the FFI boundary.
+
+/// Kernel memory pressure level, ordered by severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MemoryPressure {
+    Normal,
+    Warning,
+    Critical,
+}
+
+impl MemoryPressure {
+    fn from_raw(raw: i32) -> Self {
+        // DISPATCH_MEMORYPRESSURE_NORMAL = 1, _WARN = 2, _CRITICAL = 4
+        match raw {
+            4 => Self::Critical,
+            2 => Self::Warning,
+            _ => Self::Normal,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Warning => "warning",
+            Self::Critical => "critical",
+        }
+    }
+}
+
+/// Current level, or `None` if the sysctl is unavailable
+pub fn current_level() -> Option<MemoryPressure> {
+    let mut value: libc::c_int = 0;
+    let mut len = std::mem::size_of::<libc::c_int>();
+    // SAFETY: the name is a NUL-terminated C string; `value`/`len` describe a valid, writable
+    // c_int buffer; no new value is passed (null, 0).
+    let rc = unsafe {
+        libc::sysctlbyname(
+            c"kern.memorystatus_vm_pressure_level".as_ptr(),
+            (&mut value as *mut libc::c_int).cast(),
+            &mut len,
+            std::ptr::null_mut(),
+            0,
+        )
+    };
+    (rc == 0).then(|| MemoryPressure::from_raw(value))
+}
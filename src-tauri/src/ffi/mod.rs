@@ -10,7 +10,36 @@
 //! documented invariants: CF ownership rules (Create/Copy = release; Get = do not release),
 //! null checks on CF types, and single-thread or thread-local use where required. When
 //! changing them, preserve those invariants and prefer migrating to the safe wrappers
-//! in `ffi/` (e.g. `ioreport`) where feasible.
+//! in `ffi/` (e.g. `ioreport`, from the `mac_stats_collector` crate) where feasible.
 
-pub mod ioreport;
+pub mod calendar;
+pub use mac_stats_collector::ioreport;
+pub mod memory_pressure;
 pub mod objc;
+pub mod power_assertion;
+pub mod service_management;
+pub mod smc;
+pub mod xpc;
+
+/// SMC connection that can move between collector tasks (see `background`)
+///
+/// `macsmc::Smc` holds an IOKit connection, a mach port that is valid process-wide. Whoever owns
+/// this value is its only user, so handing it to another runtime worker is fine.
+pub(crate) struct SmcConnection(pub macsmc::Smc);
+
+// SAFETY: see above; the connection is never shared, only moved.
+unsafe impl Send for SmcConnection {}
+
+impl std::ops::Deref for SmcConnection {
+    type Target = macsmc::Smc;
+
+    fn deref(&self) -> &macsmc::Smc {
+        &self.0
+    }
+}
+
+impl std::ops::DerefMut for SmcConnection {
+    fn deref_mut(&mut self) -> &mut macsmc::Smc {
+        &mut self.0
+    }
+}
@@ -0,0 +1,60 @@
+//! Safe wrapper for IOKit power assertions (`IOPMAssertionCreateWithName`)
+//!
+//! The same mechanism `caffeinate` uses. An assertion holds until [`PowerAssertion`] is dropped
+//! (or the process exits, when macOS releases it).
+
+use core_foundation::base::TCFType;
+use core_foundation::string::{CFString, CFStringRef};
+
+#[link(name = "IOKit", kind = "framework")]
+extern "C" {
+    fn IOPMAssertionCreateWithName(
+        assertion_type: CFStringRef,
+        level: u32,
+        name: CFStringRef,
+        assertion_id: *mut u32,
+    ) -> i32;
+    fn IOPMAssertionRelease(assertion_id: u32) -> i32;
+}
+
+/// `kIOPMAssertionLevelOn`
+const LEVEL_ON: u32 = 255;
+
+/// A held power assertion, released on drop
+#[derive(Debug)]
+pub struct PowerAssertion(u32);
+
+impl PowerAssertion {
+    /// Create an assertion of `kind` (e.g. `PreventUserIdleSystemSleep`); `reason` shows in
+    /// `pmset -g assertions`
+    pub fn new(kind: &str, reason: &str) -> Result<Self, String> {
+        let kind = CFString::new(kind);
+        let reason = CFString::new(reason);
+        let mut id = 0u32;
+        // SAFETY: both CFStrings live for the call; `id` receives the assertion id.
+        let result = unsafe {
+            IOPMAssertionCreateWithName(
+                kind.as_concrete_TypeRef(),
+                LEVEL_ON,
+                reason.as_concrete_TypeRef(),
+                &mut id,
+            )
+        };
+        if result != 0 {
+            return Err(format!(
+                "IOPMAssertionCreateWithName failed ({:#x})",
+                result
+            ));
+        }
+        Ok(PowerAssertion(id))
+    }
+}
+
+impl Drop for PowerAssertion {
+    fn drop(&mut self) {
+        // SAFETY: the id came from IOPMAssertionCreateWithName and is released once.
+        unsafe {
+            IOPMAssertionRelease(self.0);
+        }
+    }
+}
@@ -0,0 +1,104 @@
+//! Safe wrappers for `SMAppService` (ServiceManagement.framework, macOS 13+)
+//!
+//! Used for the main-app login item and the privileged helper daemon. The class is looked up
+//! at runtime so the binary still runs on macOS 12 and earlier, where callers fall back to a
+//! LaunchAgent plist (login item) or go without the helper.
+
+use objc2::msg_send;
+use objc2::rc::Retained;
+use objc2::runtime::{AnyClass, AnyObject, NSObject};
+use objc2_foundation::NSString;
+
+#[link(name = "ServiceManagement", kind = "framework")]
+extern "C" {}
+
+/// `SMAppServiceStatus` values
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppServiceStatus {
+    NotRegistered,
+    Enabled,
+    /// Registered, but the user must allow it in System Settings > General > Login Items
+    RequiresApproval,
+    NotFound,
+}
+
+impl AppServiceStatus {
+    fn from_raw(raw: isize) -> Self {
+        match raw {
+            1 => Self::Enabled,
+            2 => Self::RequiresApproval,
+            3 => Self::NotFound,
+            _ => Self::NotRegistered,
+        }
+    }
+}
+
+/// `+[SMAppService mainAppService]`, or `None` before macOS 13.
+fn main_app_service() -> Option<Retained<AnyObject>> {
+    let cls = AnyClass::get(c"SMAppService")?;
+    // SAFETY: `mainAppService` is a class property getter on SMAppService (checked above to
+    // exist) that takes no arguments and returns an object; the Option handles a nil return.
+    unsafe { msg_send![cls, mainAppService] }
+}
+
+/// `+[SMAppService daemonServiceWithPlistName:]` for a plist in `Contents/Library/LaunchDaemons`,
+/// or `None` before macOS 13.
+fn daemon_service(plist_name: &str) -> Option<Retained<AnyObject>> {
+    let cls = AnyClass::get(c"SMAppService")?;
+    let name = NSString::from_str(plist_name);
+    // SAFETY: class method taking an NSString and returning an object (nil handled by Option).
+    unsafe { msg_send![cls, daemonServiceWithPlistName: &*name] }
+}
+
+/// Whether `SMAppService` exists on this system.
+pub fn is_available() -> bool {
+    AnyClass::get(c"SMAppService").is_some()
+}
+
+fn status(service: &AnyObject) -> AppServiceStatus {
+    // SAFETY: `status` is a read-only NSInteger property on SMAppService instances.
+    let raw: isize = unsafe { msg_send![service, status] };
+    AppServiceStatus::from_raw(raw)
+}
+
+fn set_registered(service: &AnyObject, enabled: bool) -> Result<(), String> {
+    // SAFETY: both selectors take an `NSError **` out-parameter and return BOOL; objc2's
+    // `error: _` handling converts NO + error into `Err` with the retained NSError.
+    let result: Result<(), Retained<NSObject>> = unsafe {
+        if enabled {
+            msg_send![service, registerAndReturnError: _]
+        } else {
+            msg_send![service, unregisterAndReturnError: _]
+        }
+    };
+    result.map_err(|err| {
+        // SAFETY: NSError always responds to `localizedDescription` (non-null NSString).
+        let description: Retained<NSString> = unsafe { msg_send![&*err, localizedDescription] };
+        description.to_string()
+    })
+}
+
+/// Login item status of the running app, or `None` before macOS 13.
+pub fn main_app_status() -> Option<AppServiceStatus> {
+    main_app_service().map(|service| status(&service))
+}
+
+/// Register (`enabled = true`) or unregister the running app as a login item.
+pub fn set_main_app_registered(enabled: bool) -> Result<(), String> {
+    let service =
+        main_app_service().ok_or_else(|| "SMAppService requires macOS 13 or later".to_string())?;
+    set_registered(&service, enabled)
+}
+
+/// Status of the bundled launch daemon `plist_name`, or `None` before macOS 13.
+pub fn daemon_status(plist_name: &str) -> Option<AppServiceStatus> {
+    daemon_service(plist_name).map(|service| status(&service))
+}
+
+/// Register or unregister the bundled launch daemon `plist_name`. Registering asks for an
+/// administrator's approval (status `RequiresApproval` until given in System Settings).
+pub fn set_daemon_registered(plist_name: &str, enabled: bool) -> Result<(), String> {
+    let service = daemon_service(plist_name)
+        .ok_or_else(|| "SMAppService requires macOS 13 or later".to_string())?;
+    set_registered(&service, enabled)
+}
@@ -0,0 +1,228 @@
+//! Raw AppleSMC key access (read and write)
+//!
+//! `macsmc` only reads decoded sensors. Fan control needs single keys by name and writes, which
+//! go through the same `IOConnectCallStructMethod` interface (`kSMCHandleYPCEvent`). Reads work
+//! for any user; writes only succeed as root (the privileged helper).
+
+use std::ffi::c_void;
+
+#[allow(non_camel_case_types)]
+type io_object_t = u32;
+#[allow(non_camel_case_types)]
+type kern_return_t = i32;
+
+#[link(name = "IOKit", kind = "framework")]
+extern "C" {
+    fn IOServiceMatching(name: *const std::ffi::c_char) -> *mut c_void;
+    fn IOServiceGetMatchingService(main_port: u32, matching: *mut c_void) -> io_object_t;
+    fn IOServiceOpen(
+        service: io_object_t,
+        owning_task: u32,
+        kind: u32,
+        connect: *mut io_object_t,
+    ) -> kern_return_t;
+    fn IOServiceClose(connect: io_object_t) -> kern_return_t;
+    fn IOObjectRelease(object: io_object_t) -> kern_return_t;
+    fn IOConnectCallStructMethod(
+        connection: io_object_t,
+        selector: u32,
+        input: *const c_void,
+        input_size: usize,
+        output: *mut c_void,
+        output_size: *mut usize,
+    ) -> kern_return_t;
+}
+
+extern "C" {
+    /// The task port behind `mach_task_self()`
+    static mach_task_self_: u32;
+}
+
+/// `kSMCHandleYPCEvent`
+const HANDLE_YPC_EVENT: u32 = 2;
+const CMD_READ_KEY: u8 = 5;
+const CMD_WRITE_KEY: u8 = 6;
+const CMD_GET_KEY_INFO: u8 = 9;
+/// `result` for a key this SMC does not have
+const RESULT_KEY_NOT_FOUND: u8 = 0x84;
+
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct Version {
+    major: u8,
+    minor: u8,
+    build: u8,
+    reserved: u8,
+    release: u16,
+}
+
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct PLimitData {
+    version: u16,
+    length: u16,
+    cpu_plimit: u32,
+    gpu_plimit: u32,
+    mem_plimit: u32,
+}
+
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct KeyInfo {
+    data_size: u32,
+    data_type: u32,
+    data_attributes: u8,
+}
+
+/// `SMCKeyData_t` (80 bytes)
+#[repr(C)]
+#[derive(Default, Clone, Copy)]
+struct KeyData {
+    key: u32,
+    vers: Version,
+    p_limit_data: PLimitData,
+    key_info: KeyInfo,
+    result: u8,
+    status: u8,
+    data8: u8,
+    data32: u32,
+    bytes: [u8; 32],
+}
+
+fn four_cc(key: &str) -> Result<u32, String> {
+    let bytes: [u8; 4] = key
+        .as_bytes()
+        .try_into()
+        .map_err(|_| format!("SMC key {:?} is not 4 characters", key))?;
+    Ok(u32::from_be_bytes(bytes))
+}
+
+/// One key's value: SMC data type (`flt `, `fpe2`, `ui8 `, …) and raw bytes
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmcValue {
+    pub data_type: [u8; 4],
+    pub bytes: Vec<u8>,
+}
+
+/// Open connection to the AppleSMC user client; closed on drop
+pub struct RawSmc(io_object_t);
+
+impl RawSmc {
+    pub fn open() -> Result<Self, String> {
+        // SAFETY: IOServiceMatching returns a +1 dictionary consumed by
+        // IOServiceGetMatchingService; the service is released after IOServiceOpen.
+        unsafe {
+            let service = IOServiceGetMatchingService(0, IOServiceMatching(c"AppleSMC".as_ptr()));
+            if service == 0 {
+                return Err("AppleSMC service not found".to_string());
+            }
+            let mut connection = 0;
+            let kr = IOServiceOpen(service, mach_task_self_, 0, &mut connection);
+            IOObjectRelease(service);
+            if kr != 0 {
+                return Err(format!("Could not open AppleSMC (kern_return {:#x})", kr));
+            }
+            Ok(RawSmc(connection))
+        }
+    }
+
+    fn call(&self, input: &KeyData) -> Result<KeyData, String> {
+        let mut output = KeyData::default();
+        let mut output_size = std::mem::size_of::<KeyData>();
+        // SAFETY: input and output are valid `SMCKeyData_t` structs of the size passed.
+        let kr = unsafe {
+            IOConnectCallStructMethod(
+                self.0,
+                HANDLE_YPC_EVENT,
+                input as *const KeyData as *const c_void,
+                std::mem::size_of::<KeyData>(),
+                &mut output as *mut KeyData as *mut c_void,
+                &mut output_size,
+            )
+        };
+        if kr != 0 {
+            return Err(format!("SMC call failed (kern_return {:#x})", kr));
+        }
+        Ok(output)
+    }
+
+    fn key_info(&self, key: &str) -> Result<Option<KeyInfo>, String> {
+        let input = KeyData {
+            key: four_cc(key)?,
+            data8: CMD_GET_KEY_INFO,
+            ..Default::default()
+        };
+        let output = self.call(&input)?;
+        match output.result {
+            0 => Ok(Some(output.key_info)),
+            RESULT_KEY_NOT_FOUND => Ok(None),
+            code => Err(format!("SMC key {} info failed (result {:#x})", key, code)),
+        }
+    }
+
+    /// Whether this SMC has `key`
+    pub fn has_key(&self, key: &str) -> bool {
+        matches!(self.key_info(key), Ok(Some(_)))
+    }
+
+    /// Read `key`; `Ok(None)` when this SMC does not have it
+    pub fn read(&self, key: &str) -> Result<Option<SmcValue>, String> {
+        let Some(info) = self.key_info(key)? else {
+            return Ok(None);
+        };
+        let input = KeyData {
+            key: four_cc(key)?,
+            key_info: info,
+            data8: CMD_READ_KEY,
+            ..Default::default()
+        };
+        let output = self.call(&input)?;
+        if output.result != 0 {
+            return Err(format!(
+                "SMC read {} failed (result {:#x})",
+                key, output.result
+            ));
+        }
+        let size = (info.data_size as usize).min(output.bytes.len());
+        Ok(Some(SmcValue {
+            data_type: info.data_type.to_be_bytes(),
+            bytes: output.bytes[..size].to_vec(),
+        }))
+    }
+
+    /// Write `bytes` to `key` (root only); the length must match the key's size
+    pub fn write(&self, key: &str, bytes: &[u8]) -> Result<(), String> {
+        let info = self
+            .key_info(key)?
+            .ok_or_else(|| format!("SMC key {} not found", key))?;
+        if info.data_size as usize != bytes.len() || bytes.len() > 32 {
+            return Err(format!(
+                "SMC key {} takes {} bytes, not {}",
+                key,
+                info.data_size,
+                bytes.len()
+            ));
+        }
+        let mut input = KeyData {
+            key: four_cc(key)?,
+            key_info: info,
+            data8: CMD_WRITE_KEY,
+            ..Default::default()
+        };
+        input.bytes[..bytes.len()].copy_from_slice(bytes);
+        let output = self.call(&input)?;
+        match output.result {
+            0 => Ok(()),
+            code => Err(format!("SMC write {} failed (result {:#x})", key, code)),
+        }
+    }
+}
+
+impl Drop for RawSmc {
+    fn drop(&mut self) {
+        // SAFETY: the connection was opened by IOServiceOpen and is closed once.
+        unsafe {
+            IOServiceClose(self.0);
+        }
+    }
+}
@@ -0,0 +1,377 @@
+//! Safe wrappers for the libxpc C API (privileged helper connection)
+//!
+//! Only what `privileged_helper` needs: Mach service connections, dictionary messages with
+//! string / int64 / double / bool / data values, and synchronous replies. [`XpcObject`] releases its object on
+//! drop; event handlers are blocks that libxpc copies, so they may be dropped after setting.
+
+use block2::{Block, RcBlock};
+use std::ffi::{c_char, c_int, c_void, CStr, CString};
+
+#[allow(non_camel_case_types)]
+type xpc_object_t = *mut c_void;
+
+/// `XPC_CONNECTION_MACH_SERVICE_LISTENER`
+const MACH_SERVICE_LISTENER: u64 = 1 << 0;
+/// `XPC_CONNECTION_MACH_SERVICE_PRIVILEGED`: only connect to a service launchd runs as root
+const MACH_SERVICE_PRIVILEGED: u64 = 1 << 1;
+
+extern "C" {
+    static _xpc_type_dictionary: c_void;
+    static _xpc_type_error: c_void;
+    static _xpc_type_connection: c_void;
+
+    fn xpc_connection_create_mach_service(
+        name: *const c_char,
+        targetq: *mut c_void,
+        flags: u64,
+    ) -> xpc_object_t;
+    fn xpc_connection_set_event_handler(
+        connection: xpc_object_t,
+        handler: &Block<dyn Fn(xpc_object_t)>,
+    );
+    fn xpc_connection_resume(connection: xpc_object_t);
+    fn xpc_connection_cancel(connection: xpc_object_t);
+    fn xpc_connection_send_message(connection: xpc_object_t, message: xpc_object_t);
+    fn xpc_connection_send_message_with_reply_sync(
+        connection: xpc_object_t,
+        message: xpc_object_t,
+    ) -> xpc_object_t;
+    fn xpc_dictionary_create(
+        keys: *const *const c_char,
+        values: *const xpc_object_t,
+        count: usize,
+    ) -> xpc_object_t;
+    fn xpc_dictionary_create_reply(original: xpc_object_t) -> xpc_object_t;
+    fn xpc_dictionary_get_remote_connection(dictionary: xpc_object_t) -> xpc_object_t;
+    fn xpc_dictionary_set_string(
+        dictionary: xpc_object_t,
+        key: *const c_char,
+        value: *const c_char,
+    );
+    fn xpc_dictionary_get_string(dictionary: xpc_object_t, key: *const c_char) -> *const c_char;
+    fn xpc_dictionary_set_int64(dictionary: xpc_object_t, key: *const c_char, value: i64);
+    fn xpc_dictionary_get_int64(dictionary: xpc_object_t, key: *const c_char) -> i64;
+    fn xpc_dictionary_set_double(dictionary: xpc_object_t, key: *const c_char, value: f64);
+    fn xpc_dictionary_get_double(dictionary: xpc_object_t, key: *const c_char) -> f64;
+    fn xpc_dictionary_set_bool(dictionary: xpc_object_t, key: *const c_char, value: bool);
+    fn xpc_dictionary_get_bool(dictionary: xpc_object_t, key: *const c_char) -> bool;
+    fn xpc_dictionary_set_data(
+        dictionary: xpc_object_t,
+        key: *const c_char,
+        bytes: *const c_void,
+        length: usize,
+    );
+    fn xpc_dictionary_get_data(
+        dictionary: xpc_object_t,
+        key: *const c_char,
+        length: *mut usize,
+    ) -> *const c_void;
+    fn xpc_get_type(object: xpc_object_t) -> *const c_void;
+    fn xpc_retain(object: xpc_object_t) -> xpc_object_t;
+    fn xpc_release(object: xpc_object_t);
+    fn xpc_copy_description(object: xpc_object_t) -> *mut c_char;
+    fn dispatch_main() -> !;
+}
+
+/// `xpc_connection_set_peer_code_signing_requirement` (macOS 12+), looked up at runtime so the
+/// app still launches on older systems
+type SetRequirementFn = unsafe extern "C" fn(xpc_object_t, *const c_char) -> c_int;
+
+fn set_requirement_fn() -> Option<SetRequirementFn> {
+    // SAFETY: dlsym with RTLD_DEFAULT and a NUL-terminated name; a non-null result is the
+    // libxpc function with the C signature above.
+    unsafe {
+        let sym = libc::dlsym(
+            libc::RTLD_DEFAULT,
+            c"xpc_connection_set_peer_code_signing_requirement".as_ptr(),
+        );
+        (!sym.is_null()).then(|| std::mem::transmute::<*mut c_void, SetRequirementFn>(sym))
+    }
+}
+
+fn key(k: &str) -> CString {
+    CString::new(k).unwrap_or_default()
+}
+
+/// An owned XPC object (message, reply or error)
+pub struct XpcObject(xpc_object_t);
+
+// SAFETY: XPC objects are reference counted and thread-safe; this wrapper only moves the
+// ownership of one reference.
+unsafe impl Send for XpcObject {}
+
+impl XpcObject {
+    /// Empty dictionary message
+    pub fn dictionary() -> Self {
+        // SAFETY: an empty dictionary (no keys / values); returns a new +1 object.
+        XpcObject(unsafe { xpc_dictionary_create(std::ptr::null(), std::ptr::null(), 0) })
+    }
+
+    fn retained(object: xpc_object_t) -> Self {
+        // SAFETY: `object` is a live XPC object passed to a handler (borrowed); retain it
+        // so the wrapper owns one reference.
+        XpcObject(unsafe { xpc_retain(object) })
+    }
+
+    fn has_type(&self, ty: *const c_void) -> bool {
+        // SAFETY: self.0 is a live XPC object.
+        !self.0.is_null() && unsafe { xpc_get_type(self.0) } == ty
+    }
+
+    pub fn is_dictionary(&self) -> bool {
+        // SAFETY: taking the address of the exported type symbol.
+        self.has_type(unsafe { &_xpc_type_dictionary })
+    }
+
+    pub fn is_error(&self) -> bool {
+        // SAFETY: as above
+        self.has_type(unsafe { &_xpc_type_error })
+    }
+
+    fn is_connection(&self) -> bool {
+        // SAFETY: as above
+        self.has_type(unsafe { &_xpc_type_connection })
+    }
+
+    /// Debug description (e.g. `Connection invalid` for errors)
+    pub fn description(&self) -> String {
+        if self.0.is_null() {
+            return "null".to_string();
+        }
+        // SAFETY: xpc_copy_description returns a malloc'd C string the caller frees.
+        unsafe {
+            let text = xpc_copy_description(self.0);
+            if text.is_null() {
+                return String::new();
+            }
+            let description = CStr::from_ptr(text).to_string_lossy().into_owned();
+            libc::free(text as *mut c_void);
+            description
+        }
+    }
+
+    pub fn set_string(&self, k: &str, value: &str) {
+        let value = CString::new(value).unwrap_or_default();
+        if self.is_dictionary() {
+            // SAFETY: dictionary object and NUL-terminated key / value (copied by libxpc).
+            unsafe { xpc_dictionary_set_string(self.0, key(k).as_ptr(), value.as_ptr()) }
+        }
+    }
+
+    pub fn string(&self, k: &str) -> Option<String> {
+        if !self.is_dictionary() {
+            return None;
+        }
+        // SAFETY: the returned pointer is owned by the dictionary and valid while it lives.
+        unsafe {
+            let value = xpc_dictionary_get_string(self.0, key(k).as_ptr());
+            (!value.is_null()).then(|| CStr::from_ptr(value).to_string_lossy().into_owned())
+        }
+    }
+
+    pub fn set_int64(&self, k: &str, value: i64) {
+        if self.is_dictionary() {
+            // SAFETY: dictionary object and NUL-terminated key.
+            unsafe { xpc_dictionary_set_int64(self.0, key(k).as_ptr(), value) }
+        }
+    }
+
+    /// 0 when missing
+    pub fn int64(&self, k: &str) -> i64 {
+        if !self.is_dictionary() {
+            return 0;
+        }
+        // SAFETY: dictionary object and NUL-terminated key.
+        unsafe { xpc_dictionary_get_int64(self.0, key(k).as_ptr()) }
+    }
+
+    pub fn set_double(&self, k: &str, value: f64) {
+        if self.is_dictionary() {
+            // SAFETY: dictionary object and NUL-terminated key.
+            unsafe { xpc_dictionary_set_double(self.0, key(k).as_ptr(), value) }
+        }
+    }
+
+    /// NaN when missing
+    pub fn double(&self, k: &str) -> f64 {
+        if !self.is_dictionary() {
+            return f64::NAN;
+        }
+        // SAFETY: dictionary object and NUL-terminated key.
+        unsafe { xpc_dictionary_get_double(self.0, key(k).as_ptr()) }
+    }
+
+    pub fn set_bool(&self, k: &str, value: bool) {
+        if self.is_dictionary() {
+            // SAFETY: dictionary object and NUL-terminated key.
+            unsafe { xpc_dictionary_set_bool(self.0, key(k).as_ptr(), value) }
+        }
+    }
+
+    /// false when missing
+    pub fn bool(&self, k: &str) -> bool {
+        if !self.is_dictionary() {
+            return false;
+        }
+        // SAFETY: dictionary object and NUL-terminated key.
+        unsafe { xpc_dictionary_get_bool(self.0, key(k).as_ptr()) }
+    }
+
+    pub fn set_data(&self, k: &str, bytes: &[u8]) {
+        if self.is_dictionary() {
+            // SAFETY: libxpc copies `bytes.len()` bytes from a valid slice.
+            unsafe {
+                xpc_dictionary_set_data(
+                    self.0,
+                    key(k).as_ptr(),
+                    bytes.as_ptr() as *const c_void,
+                    bytes.len(),
+                )
+            }
+        }
+    }
+
+    pub fn data(&self, k: &str) -> Option<Vec<u8>> {
+        if !self.is_dictionary() {
+            return None;
+        }
+        let mut length = 0usize;
+        // SAFETY: the returned bytes are owned by the dictionary and `length` long.
+        unsafe {
+            let bytes = xpc_dictionary_get_data(self.0, key(k).as_ptr(), &mut length);
+            (!bytes.is_null())
+                .then(|| std::slice::from_raw_parts(bytes as *const u8, length).to_vec())
+        }
+    }
+}
+
+impl Drop for XpcObject {
+    fn drop(&mut self) {
+        if !self.0.is_null() {
+            // SAFETY: the wrapper owns exactly one reference.
+            unsafe { xpc_release(self.0) }
+        }
+    }
+}
+
+/// Client connection to a root Mach service; cancelled on drop
+pub struct Connection(XpcObject);
+
+impl Connection {
+    /// Connect to `service` (must be run by launchd as root). With `requirement`, replies only
+    /// come from a peer whose code signature satisfies it.
+    pub fn privileged(service: &str, requirement: Option<&str>) -> Result<Self, String> {
+        let name = CString::new(service).map_err(|e| e.to_string())?;
+        // SAFETY: NUL-terminated service name, default target queue; returns a +1 connection.
+        let connection = XpcObject(unsafe {
+            xpc_connection_create_mach_service(
+                name.as_ptr(),
+                std::ptr::null_mut(),
+                MACH_SERVICE_PRIVILEGED,
+            )
+        });
+        if connection.0.is_null() {
+            return Err(format!("Could not create XPC connection to {}", service));
+        }
+        if let Some(requirement) = requirement {
+            set_peer_requirement(&connection, requirement)?;
+        }
+        // Replies arrive through send_message_with_reply_sync; errors surface there too
+        let handler = RcBlock::new(|_event: xpc_object_t| {});
+        // SAFETY: live connection; libxpc copies the handler block.
+        unsafe {
+            xpc_connection_set_event_handler(connection.0, &handler);
+            xpc_connection_resume(connection.0);
+        }
+        Ok(Connection(connection))
+    }
+
+    /// Send `message` and wait for the reply; an XPC error (e.g. service not installed) is
+    /// returned as `Err` with its description
+    pub fn send(&self, message: &XpcObject) -> Result<XpcObject, String> {
+        // SAFETY: live connection and message; the reply is a new +1 object.
+        let reply =
+            XpcObject(unsafe { xpc_connection_send_message_with_reply_sync(self.0 .0, message.0) });
+        if reply.is_error() || !reply.is_dictionary() {
+            return Err(reply.description());
+        }
+        Ok(reply)
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        // SAFETY: live connection; cancel before the wrapper releases it.
+        unsafe { xpc_connection_cancel(self.0 .0) }
+    }
+}
+
+fn set_peer_requirement(connection: &XpcObject, requirement: &str) -> Result<(), String> {
+    let set = set_requirement_fn()
+        .ok_or_else(|| "Code signing checks need macOS 12 or later".to_string())?;
+    let requirement = CString::new(requirement).map_err(|e| e.to_string())?;
+    // SAFETY: live connection, NUL-terminated requirement; must be set before resume.
+    match unsafe { set(connection.0, requirement.as_ptr()) } {
+        0 => Ok(()),
+        code => Err(format!("Invalid code signing requirement (error {})", code)),
+    }
+}
+
+/// Serve `service` as a launchd Mach service: every message from a peer whose code signature
+/// satisfies `requirement` is passed to `handle` with an empty reply to fill. Never returns.
+pub fn serve(service: &str, requirement: &str, handle: fn(&XpcObject, &XpcObject)) -> ! {
+    let name = CString::new(service).unwrap_or_default();
+    let requirement = requirement.to_string();
+    // SAFETY: NUL-terminated name, default target queue; the listener lives until exit.
+    let listener = unsafe {
+        xpc_connection_create_mach_service(
+            name.as_ptr(),
+            std::ptr::null_mut(),
+            MACH_SERVICE_LISTENER,
+        )
+    };
+    let on_peer = RcBlock::new(move |event: xpc_object_t| {
+        let peer = XpcObject::retained(event);
+        if !peer.is_connection() {
+            return;
+        }
+        if let Err(e) = set_peer_requirement(&peer, &requirement) {
+            tracing::warn!("Privileged helper: refusing connection: {}", e);
+            // SAFETY: live peer connection.
+            unsafe { xpc_connection_cancel(peer.0) };
+            return;
+        }
+        let on_message = RcBlock::new(move |event: xpc_object_t| {
+            let message = XpcObject::retained(event);
+            if !message.is_dictionary() {
+                return;
+            }
+            // SAFETY: `message` came from a peer and expects a reply; both calls return
+            // objects owned by the dictionary (+1 for the reply).
+            unsafe {
+                let reply = XpcObject(xpc_dictionary_create_reply(message.0));
+                if reply.0.is_null() {
+                    return;
+                }
+                handle(&message, &reply);
+                let remote = xpc_dictionary_get_remote_connection(message.0);
+                if !remote.is_null() {
+                    xpc_connection_send_message(remote, reply.0);
+                }
+            }
+        });
+        // SAFETY: live peer connection; libxpc copies the handler and keeps the peer alive
+        // while it is resumed (our reference is released when `peer` drops).
+        unsafe {
+            xpc_connection_set_event_handler(peer.0, &on_message);
+            xpc_connection_resume(peer.0);
+        }
+    });
+    // SAFETY: live listener; the handler is copied. dispatch_main parks this thread and runs
+    // the handlers on libdispatch queues.
+    unsafe {
+        xpc_connection_set_event_handler(listener, &on_peer);
+        xpc_connection_resume(listener);
+        dispatch_main()
+    }
+}
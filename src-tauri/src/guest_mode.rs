@@ -0,0 +1,39 @@
+//! Read-only guest mode (`--guest`, `guestMode`)
+//!
+//! For shared or demo machines. Metrics, history, monitors and alerts keep working, but every
+//! Tauri command that changes something (force quit, config and credential writes, monitors,
+//! alerts, plugins, schedules, agents, Ollama models, the downloads organizer, agent chat and its
+//! web access) refuses with an error before doing anything, and the AI agent (Discord,
+//! scheduler, Ollama warmup) stays off. The check lives in the command layer, so a page script
+//! calling `invoke` directly is refused just like a hidden button would be.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::config::Config;
+
+static CLI_GUEST: AtomicBool = AtomicBool::new(false);
+
+/// Turn guest mode on for this run (`--guest`)
+pub fn enable_from_cli() {
+    CLI_GUEST.store(true, Ordering::Relaxed);
+}
+
+/// Whether guest mode is on (`--guest`, `guestMode` or `MAC_STATS_GUEST_MODE`)
+pub fn is_active() -> bool {
+    CLI_GUEST.load(Ordering::Relaxed) || Config::guest_mode()
+}
+
+/// Refuse `action` (e.g. `"Force quit"`) while guest mode is on
+pub fn ensure_allowed(action: &str) -> Result<(), String> {
+    if !is_active() {
+        return Ok(());
+    }
+    tracing::info!("Guest mode: refused {}", action);
+    Err(format!("{} is disabled in read-only guest mode", action))
+}
+
+/// Whether guest mode is on, so the UI can hide controls that would be refused anyway
+#[tauri::command]
+pub fn get_guest_mode() -> bool {
+    is_active()
+}
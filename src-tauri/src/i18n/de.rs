@@ -0,0 +1,86 @@
+//! German strings
+
+pub(super) const STRINGS: &[(&str, &str)] = &[
+    ("menu.cpu", "CPU"),
+    ("menu.gpu", "GPU"),
+    ("menu.ram", "RAM"),
+    ("menu.ssd", "SSD"),
+    ("menu.temperature", "Temperatur"),
+    ("menu.fan", "Lüfter"),
+    ("menu.battery", "Batterie"),
+    ("menu.network", "Netzwerk"),
+    ("menu.top_processes", "Top-Prozesse"),
+    ("menu.no_process_data", "Noch keine Prozessdaten"),
+    ("menu.pause_updates", "Aktualisierung anhalten"),
+    ("menu.resume_updates", "Aktualisierung fortsetzen"),
+    ("menu.show_widget", "Widget einblenden"),
+    ("menu.hide_widget", "Widget ausblenden"),
+    ("menu.widget_click_through", "Klicks durch das Widget"),
+    ("menu.open_cpu_window", "CPU-Fenster öffnen"),
+    ("menu.open_network_window", "Netzwerkfenster öffnen"),
+    ("menu.open_battery_window", "Batteriefenster öffnen"),
+    ("menu.open_dashboard", "Dashboard öffnen"),
+    ("menu.open_log_viewer", "Protokoll öffnen"),
+    ("menu.keep_awake", "Wach halten"),
+    ("menu.keep_awake_on", "Wach halten (an)"),
+    ("menu.keep_awake_left", "Wach halten (noch {time})"),
+    ("menu.for_30_minutes", "30 Minuten"),
+    ("menu.for_1_hour", "1 Stunde"),
+    ("menu.for_2_hours", "2 Stunden"),
+    ("menu.until_turned_off", "Bis zum Ausschalten"),
+    ("menu.turn_off", "Ausschalten"),
+    ("menu.profile", "Profil"),
+    ("menu.profile_active", "Profil: {name}"),
+    ("menu.profile_none", "Keins"),
+    ("menu.copy_stats", "Werte kopieren"),
+    ("menu.as_text", "Als Text"),
+    ("menu.as_markdown", "Als Markdown"),
+    ("menu.as_json", "Als JSON"),
+    ("menu.system_report", "Systembericht"),
+    ("menu.restore_menu_bar_items", "Menüleisten-Symbole wiederherstellen"),
+    ("menu.about", "Über mac-stats"),
+    ("menu.quit", "mac-stats beenden"),
+    ("status.accessibility_label", "mac-stats Systemwerte"),
+    ("status.removed", "mac-stats läuft weiter. Öffne mac-stats erneut, um das Menüleisten-Symbol zurückzuholen."),
+    ("alert.triggered", "Alarm ausgelöst: {name}"),
+    ("alert.triggered_reading", "Alarm ausgelöst: {name} ({reading})"),
+    ("alert.resolved", "Wieder normal: {name}"),
+    ("digest.one", "1 Alarm ({severity}) {window}: {alerts}"),
+    ("digest.many", "{count} Alarme ({severity}) {window}: {alerts}"),
+    ("digest.last_minute", "in der letzten Minute"),
+    ("digest.last_minutes", "in den letzten {n} Minuten"),
+    ("digest.last_hour", "in der letzten Stunde"),
+    ("digest.last_hours", "in den letzten {n} Stunden"),
+    ("onboarding.notification", "So sehen Alarme von mac-stats aus."),
+    ("onboarding.notification_sent", "Testmitteilung gesendet. Falls keine erschienen ist, erlaube den Skripteditor unter Systemeinstellungen > Mitteilungen."),
+    ("onboarding.metric_cpu", "CPU-Auslastung"),
+    ("onboarding.metric_gpu", "GPU-Auslastung"),
+    ("onboarding.metric_ram", "Arbeitsspeicher"),
+    ("onboarding.metric_disk", "Festplatte"),
+    ("onboarding.metric_temp", "CPU-Temperatur"),
+    ("onboarding.metric_fan", "Lüfterdrehzahl"),
+    ("onboarding.metric_battery", "Batterie"),
+    ("onboarding.metric_net", "Netzwerk-Download"),
+    ("capability.temperature", "CPU-Temperatur"),
+    ("capability.temperature_yes", "Wird aus dem SMC gelesen."),
+    ("capability.temperature_no", "Der SMC dieses Macs meldet keine CPU-Temperatur (üblich in virtuellen Maschinen und bei unbekannten Modellen); die Temperatur zeigt --."),
+    ("capability.gpu_temperature", "GPU-Temperatur"),
+    ("capability.gpu_temperature_yes", "Wird aus dem SMC gelesen."),
+    ("capability.gpu_temperature_no", "Kein GPU-Temperatursensor gefunden; das CPU-Fenster lässt sie weg."),
+    ("capability.fan", "Lüfter"),
+    ("capability.fan_yes", "Die Lüfterdrehzahl wird aus dem SMC gelesen."),
+    ("capability.fan_no", "Kein Lüfter gefunden. Macs ohne Lüfter (etwa das MacBook Air) kühlen passiv."),
+    ("capability.frequency", "CPU-Frequenz"),
+    ("capability.frequency_yes", "Verfügbar."),
+    ("capability.frequency_no", "Die Frequenzkanäle der CPU sind nicht lesbar; das CPU-Fenster zeigt keine Frequenz."),
+    ("capability.power", "CPU-/GPU-Leistung"),
+    ("capability.power_yes", "Wird aus den IOReport-Energiezählern gelesen."),
+    ("capability.power_no", "IOReport hat auf diesem Mac keine Energiekanäle; die Leistungsaufnahme wird nicht angezeigt."),
+    ("capability.power_intel", "Intel-Macs liefern die Leistung je Chip nur über powermetrics."),
+    ("capability.powermetrics", "powermetrics"),
+    ("capability.powermetrics_yes", "Installiert; kann Leistung und Frequenzen liefern (Einstellung `powermetrics`)."),
+    ("capability.powermetrics_no", "/usr/bin/powermetrics fehlt; die eingebauten IOReport-Werte werden verwendet."),
+    ("capability.battery", "Batterie"),
+    ("capability.battery_yes", "Ladung, Zustand und Leistungsaufnahme stehen im Batteriefenster."),
+    ("capability.battery_no", "Keine Batterie (Desktop-Mac); Batteriewerte und -alarme bleiben leer."),
+];
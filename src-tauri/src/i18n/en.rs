@@ -0,0 +1,86 @@
+//! English strings (the reference catalog: every key, and the fallback for the others)
+
+pub(super) const STRINGS: &[(&str, &str)] = &[
+    ("menu.cpu", "CPU"),
+    ("menu.gpu", "GPU"),
+    ("menu.ram", "RAM"),
+    ("menu.ssd", "SSD"),
+    ("menu.temperature", "Temperature"),
+    ("menu.fan", "Fan"),
+    ("menu.battery", "Battery"),
+    ("menu.network", "Network"),
+    ("menu.top_processes", "Top Processes"),
+    ("menu.no_process_data", "No process data yet"),
+    ("menu.pause_updates", "Pause Updates"),
+    ("menu.resume_updates", "Resume Updates"),
+    ("menu.show_widget", "Show Widget"),
+    ("menu.hide_widget", "Hide Widget"),
+    ("menu.widget_click_through", "Widget Click-Through"),
+    ("menu.open_cpu_window", "Open CPU Window"),
+    ("menu.open_network_window", "Open Network Window"),
+    ("menu.open_battery_window", "Open Battery Window"),
+    ("menu.open_dashboard", "Open Dashboard"),
+    ("menu.open_log_viewer", "Open Log Viewer"),
+    ("menu.keep_awake", "Keep Awake"),
+    ("menu.keep_awake_on", "Keep Awake (on)"),
+    ("menu.keep_awake_left", "Keep Awake ({time} left)"),
+    ("menu.for_30_minutes", "For 30 Minutes"),
+    ("menu.for_1_hour", "For 1 Hour"),
+    ("menu.for_2_hours", "For 2 Hours"),
+    ("menu.until_turned_off", "Until Turned Off"),
+    ("menu.turn_off", "Turn Off"),
+    ("menu.profile", "Profile"),
+    ("menu.profile_active", "Profile: {name}"),
+    ("menu.profile_none", "None"),
+    ("menu.copy_stats", "Copy Stats"),
+    ("menu.as_text", "As Text"),
+    ("menu.as_markdown", "As Markdown"),
+    ("menu.as_json", "As JSON"),
+    ("menu.system_report", "System Report"),
+    ("menu.restore_menu_bar_items", "Restore Menu Bar Items"),
+    ("menu.about", "About mac-stats"),
+    ("menu.quit", "Quit mac-stats"),
+    ("status.accessibility_label", "mac-stats system stats"),
+    ("status.removed", "mac-stats is still running. Open mac-stats again to bring its menu bar item back."),
+    ("alert.triggered", "Alert triggered: {name}"),
+    ("alert.triggered_reading", "Alert triggered: {name} ({reading})"),
+    ("alert.resolved", "Recovered: {name}"),
+    ("digest.one", "1 {severity} alert {window}: {alerts}"),
+    ("digest.many", "{count} {severity} alerts {window}: {alerts}"),
+    ("digest.last_minute", "in the last minute"),
+    ("digest.last_minutes", "in the last {n} minutes"),
+    ("digest.last_hour", "in the last hour"),
+    ("digest.last_hours", "in the last {n} hours"),
+    ("onboarding.notification", "Alerts from mac-stats will look like this."),
+    ("onboarding.notification_sent", "Test notification sent. If none appeared, allow Script Editor in System Settings > Notifications."),
+    ("onboarding.metric_cpu", "CPU usage"),
+    ("onboarding.metric_gpu", "GPU usage"),
+    ("onboarding.metric_ram", "Memory"),
+    ("onboarding.metric_disk", "Disk"),
+    ("onboarding.metric_temp", "CPU temperature"),
+    ("onboarding.metric_fan", "Fan speed"),
+    ("onboarding.metric_battery", "Battery"),
+    ("onboarding.metric_net", "Network download"),
+    ("capability.temperature", "CPU temperature"),
+    ("capability.temperature_yes", "Read from the SMC."),
+    ("capability.temperature_no", "This Mac's SMC reports no CPU temperature (common in virtual machines and on unknown models); temperature shows --."),
+    ("capability.gpu_temperature", "GPU temperature"),
+    ("capability.gpu_temperature_yes", "Read from the SMC."),
+    ("capability.gpu_temperature_no", "No GPU temperature sensor was found; the CPU window leaves it out."),
+    ("capability.fan", "Fan"),
+    ("capability.fan_yes", "Fan speed is read from the SMC."),
+    ("capability.fan_no", "No fan was found. Fanless Macs (such as the MacBook Air) cool passively."),
+    ("capability.frequency", "CPU frequency"),
+    ("capability.frequency_yes", "Available."),
+    ("capability.frequency_no", "The CPU frequency channels could not be read; the CPU window shows no frequency."),
+    ("capability.power", "CPU / GPU power"),
+    ("capability.power_yes", "Read from IOReport energy counters."),
+    ("capability.power_no", "IOReport has no energy channels on this Mac; power draw is not shown."),
+    ("capability.power_intel", "Intel Macs do not expose per-chip power without powermetrics."),
+    ("capability.powermetrics", "powermetrics"),
+    ("capability.powermetrics_yes", "Installed; it can provide power and frequencies (`powermetrics` setting)."),
+    ("capability.powermetrics_no", "/usr/bin/powermetrics is missing; the built-in IOReport readings are used."),
+    ("capability.battery", "Battery"),
+    ("capability.battery_yes", "Charge, health and power draw are shown in the battery window."),
+    ("capability.battery_no", "No battery (desktop Mac); battery metrics and alerts stay empty."),
+];
@@ -0,0 +1,86 @@
+//! Spanish strings
+
+pub(super) const STRINGS: &[(&str, &str)] = &[
+    ("menu.cpu", "CPU"),
+    ("menu.gpu", "GPU"),
+    ("menu.ram", "RAM"),
+    ("menu.ssd", "SSD"),
+    ("menu.temperature", "Temperatura"),
+    ("menu.fan", "Ventilador"),
+    ("menu.battery", "Batería"),
+    ("menu.network", "Red"),
+    ("menu.top_processes", "Procesos principales"),
+    ("menu.no_process_data", "Aún no hay datos de procesos"),
+    ("menu.pause_updates", "Pausar actualizaciones"),
+    ("menu.resume_updates", "Reanudar actualizaciones"),
+    ("menu.show_widget", "Mostrar widget"),
+    ("menu.hide_widget", "Ocultar widget"),
+    ("menu.widget_click_through", "Clics a través del widget"),
+    ("menu.open_cpu_window", "Abrir ventana de CPU"),
+    ("menu.open_network_window", "Abrir ventana de red"),
+    ("menu.open_battery_window", "Abrir ventana de batería"),
+    ("menu.open_dashboard", "Abrir panel"),
+    ("menu.open_log_viewer", "Abrir registro"),
+    ("menu.keep_awake", "Mantener activo"),
+    ("menu.keep_awake_on", "Mantener activo (activado)"),
+    ("menu.keep_awake_left", "Mantener activo (quedan {time})"),
+    ("menu.for_30_minutes", "Durante 30 minutos"),
+    ("menu.for_1_hour", "Durante 1 hora"),
+    ("menu.for_2_hours", "Durante 2 horas"),
+    ("menu.until_turned_off", "Hasta desactivarlo"),
+    ("menu.turn_off", "Desactivar"),
+    ("menu.profile", "Perfil"),
+    ("menu.profile_active", "Perfil: {name}"),
+    ("menu.profile_none", "Ninguno"),
+    ("menu.copy_stats", "Copiar estadísticas"),
+    ("menu.as_text", "Como texto"),
+    ("menu.as_markdown", "Como Markdown"),
+    ("menu.as_json", "Como JSON"),
+    ("menu.system_report", "Informe del sistema"),
+    ("menu.restore_menu_bar_items", "Restaurar elementos de la barra de menús"),
+    ("menu.about", "Acerca de mac-stats"),
+    ("menu.quit", "Salir de mac-stats"),
+    ("status.accessibility_label", "Estadísticas del sistema de mac-stats"),
+    ("status.removed", "mac-stats sigue en ejecución. Abre mac-stats de nuevo para recuperar su elemento de la barra de menús."),
+    ("alert.triggered", "Alerta activada: {name}"),
+    ("alert.triggered_reading", "Alerta activada: {name} ({reading})"),
+    ("alert.resolved", "Recuperado: {name}"),
+    ("digest.one", "1 alerta ({severity}) {window}: {alerts}"),
+    ("digest.many", "{count} alertas ({severity}) {window}: {alerts}"),
+    ("digest.last_minute", "en el último minuto"),
+    ("digest.last_minutes", "en los últimos {n} minutos"),
+    ("digest.last_hour", "en la última hora"),
+    ("digest.last_hours", "en las últimas {n} horas"),
+    ("onboarding.notification", "Así se verán las alertas de mac-stats."),
+    ("onboarding.notification_sent", "Notificación de prueba enviada. Si no apareció ninguna, permite Editor de Scripts en Ajustes del Sistema > Notificaciones."),
+    ("onboarding.metric_cpu", "Uso de CPU"),
+    ("onboarding.metric_gpu", "Uso de GPU"),
+    ("onboarding.metric_ram", "Memoria"),
+    ("onboarding.metric_disk", "Disco"),
+    ("onboarding.metric_temp", "Temperatura de CPU"),
+    ("onboarding.metric_fan", "Velocidad del ventilador"),
+    ("onboarding.metric_battery", "Batería"),
+    ("onboarding.metric_net", "Descarga de red"),
+    ("capability.temperature", "Temperatura de CPU"),
+    ("capability.temperature_yes", "Se lee del SMC."),
+    ("capability.temperature_no", "El SMC de este Mac no informa la temperatura de la CPU (habitual en máquinas virtuales y modelos desconocidos); la temperatura muestra --."),
+    ("capability.gpu_temperature", "Temperatura de GPU"),
+    ("capability.gpu_temperature_yes", "Se lee del SMC."),
+    ("capability.gpu_temperature_no", "No se encontró un sensor de temperatura de GPU; la ventana de CPU la omite."),
+    ("capability.fan", "Ventilador"),
+    ("capability.fan_yes", "La velocidad del ventilador se lee del SMC."),
+    ("capability.fan_no", "No se encontró ningún ventilador. Los Mac sin ventilador (como el MacBook Air) se refrigeran de forma pasiva."),
+    ("capability.frequency", "Frecuencia de CPU"),
+    ("capability.frequency_yes", "Disponible."),
+    ("capability.frequency_no", "No se pudieron leer los canales de frecuencia de la CPU; la ventana de CPU no muestra la frecuencia."),
+    ("capability.power", "Potencia de CPU / GPU"),
+    ("capability.power_yes", "Se lee de los contadores de energía de IOReport."),
+    ("capability.power_no", "IOReport no tiene canales de energía en este Mac; no se muestra el consumo."),
+    ("capability.power_intel", "Los Mac Intel solo exponen la potencia por chip con powermetrics."),
+    ("capability.powermetrics", "powermetrics"),
+    ("capability.powermetrics_yes", "Instalado; puede proporcionar potencia y frecuencias (ajuste `powermetrics`)."),
+    ("capability.powermetrics_no", "Falta /usr/bin/powermetrics; se usan las lecturas integradas de IOReport."),
+    ("capability.battery", "Batería"),
+    ("capability.battery_yes", "La carga, el estado y el consumo se muestran en la ventana de batería."),
+    ("capability.battery_no", "Sin batería (Mac de escritorio); las métricas y alertas de batería quedan vacías."),
+];
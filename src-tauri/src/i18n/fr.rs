@@ -0,0 +1,86 @@
+//! French strings
+
+pub(super) const STRINGS: &[(&str, &str)] = &[
+    ("menu.cpu", "CPU"),
+    ("menu.gpu", "GPU"),
+    ("menu.ram", "RAM"),
+    ("menu.ssd", "SSD"),
+    ("menu.temperature", "Température"),
+    ("menu.fan", "Ventilateur"),
+    ("menu.battery", "Batterie"),
+    ("menu.network", "Réseau"),
+    ("menu.top_processes", "Processus principaux"),
+    ("menu.no_process_data", "Pas encore de données de processus"),
+    ("menu.pause_updates", "Suspendre les mises à jour"),
+    ("menu.resume_updates", "Reprendre les mises à jour"),
+    ("menu.show_widget", "Afficher le widget"),
+    ("menu.hide_widget", "Masquer le widget"),
+    ("menu.widget_click_through", "Clics à travers le widget"),
+    ("menu.open_cpu_window", "Ouvrir la fenêtre CPU"),
+    ("menu.open_network_window", "Ouvrir la fenêtre Réseau"),
+    ("menu.open_battery_window", "Ouvrir la fenêtre Batterie"),
+    ("menu.open_dashboard", "Ouvrir le tableau de bord"),
+    ("menu.open_log_viewer", "Ouvrir le journal"),
+    ("menu.keep_awake", "Empêcher la veille"),
+    ("menu.keep_awake_on", "Empêcher la veille (activé)"),
+    ("menu.keep_awake_left", "Empêcher la veille ({time} restantes)"),
+    ("menu.for_30_minutes", "Pendant 30 minutes"),
+    ("menu.for_1_hour", "Pendant 1 heure"),
+    ("menu.for_2_hours", "Pendant 2 heures"),
+    ("menu.until_turned_off", "Jusqu'à désactivation"),
+    ("menu.turn_off", "Désactiver"),
+    ("menu.profile", "Profil"),
+    ("menu.profile_active", "Profil : {name}"),
+    ("menu.profile_none", "Aucun"),
+    ("menu.copy_stats", "Copier les statistiques"),
+    ("menu.as_text", "En texte"),
+    ("menu.as_markdown", "En Markdown"),
+    ("menu.as_json", "En JSON"),
+    ("menu.system_report", "Rapport système"),
+    ("menu.restore_menu_bar_items", "Restaurer les éléments de la barre des menus"),
+    ("menu.about", "À propos de mac-stats"),
+    ("menu.quit", "Quitter mac-stats"),
+    ("status.accessibility_label", "Statistiques système mac-stats"),
+    ("status.removed", "mac-stats est toujours actif. Ouvrez à nouveau mac-stats pour remettre son élément dans la barre des menus."),
+    ("alert.triggered", "Alerte déclenchée : {name}"),
+    ("alert.triggered_reading", "Alerte déclenchée : {name} ({reading})"),
+    ("alert.resolved", "Rétabli : {name}"),
+    ("digest.one", "1 alerte ({severity}) {window} : {alerts}"),
+    ("digest.many", "{count} alertes ({severity}) {window} : {alerts}"),
+    ("digest.last_minute", "au cours de la dernière minute"),
+    ("digest.last_minutes", "au cours des {n} dernières minutes"),
+    ("digest.last_hour", "au cours de la dernière heure"),
+    ("digest.last_hours", "au cours des {n} dernières heures"),
+    ("onboarding.notification", "Les alertes de mac-stats ressembleront à ceci."),
+    ("onboarding.notification_sent", "Notification de test envoyée. Si rien n'apparaît, autorisez Éditeur de script dans Réglages Système > Notifications."),
+    ("onboarding.metric_cpu", "Utilisation CPU"),
+    ("onboarding.metric_gpu", "Utilisation GPU"),
+    ("onboarding.metric_ram", "Mémoire"),
+    ("onboarding.metric_disk", "Disque"),
+    ("onboarding.metric_temp", "Température CPU"),
+    ("onboarding.metric_fan", "Vitesse du ventilateur"),
+    ("onboarding.metric_battery", "Batterie"),
+    ("onboarding.metric_net", "Téléchargement réseau"),
+    ("capability.temperature", "Température CPU"),
+    ("capability.temperature_yes", "Lue depuis le SMC."),
+    ("capability.temperature_no", "Le SMC de ce Mac ne fournit pas de température CPU (courant dans les machines virtuelles et sur les modèles inconnus) ; la température affiche --."),
+    ("capability.gpu_temperature", "Température GPU"),
+    ("capability.gpu_temperature_yes", "Lue depuis le SMC."),
+    ("capability.gpu_temperature_no", "Aucun capteur de température GPU trouvé ; la fenêtre CPU ne l'affiche pas."),
+    ("capability.fan", "Ventilateur"),
+    ("capability.fan_yes", "La vitesse du ventilateur est lue depuis le SMC."),
+    ("capability.fan_no", "Aucun ventilateur trouvé. Les Mac sans ventilateur (comme le MacBook Air) sont refroidis passivement."),
+    ("capability.frequency", "Fréquence CPU"),
+    ("capability.frequency_yes", "Disponible."),
+    ("capability.frequency_no", "Les canaux de fréquence CPU sont illisibles ; la fenêtre CPU n'affiche pas de fréquence."),
+    ("capability.power", "Puissance CPU / GPU"),
+    ("capability.power_yes", "Lue depuis les compteurs d'énergie IOReport."),
+    ("capability.power_no", "IOReport n'a pas de canaux d'énergie sur ce Mac ; la consommation n'est pas affichée."),
+    ("capability.power_intel", "Les Mac Intel n'exposent la puissance par puce qu'avec powermetrics."),
+    ("capability.powermetrics", "powermetrics"),
+    ("capability.powermetrics_yes", "Installé ; peut fournir puissance et fréquences (réglage `powermetrics`)."),
+    ("capability.powermetrics_no", "/usr/bin/powermetrics est absent ; les mesures IOReport intégrées sont utilisées."),
+    ("capability.battery", "Batterie"),
+    ("capability.battery_yes", "La charge, l'état et la consommation sont affichés dans la fenêtre Batterie."),
+    ("capability.battery_no", "Pas de batterie (Mac de bureau) ; les mesures et alertes de batterie restent vides."),
+];
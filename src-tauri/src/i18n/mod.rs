@@ -0,0 +1,167 @@
+//! Localized user-facing text
+//!
+//! Strings shown by the status menu, alert notifications and digests, and window text that
+//! comes from Rust commands (onboarding) are looked up by key in a per-language catalog
+//! (`en`, `de`, `fr`, `es`). Missing keys fall back to English. Placeholders are written as
+//! `{name}` and filled by [`Lang::format`].
+//!
+//! The language is `language` in `config.json` when set, otherwise the first macOS preferred
+//! language (`AppleLanguages`, read once), then the number locale. Numbers follow
+//! [`crate::number_format`] separately, so `"language": "en"` with a German region still shows
+//! `12,5 %`. Log lines, CLI output and data interfaces stay in English.
+
+mod de;
+mod en;
+mod es;
+mod fr;
+
+use std::sync::OnceLock;
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Lang {
+    #[default]
+    En,
+    De,
+    Fr,
+    Es,
+}
+
+impl Lang {
+    pub const ALL: [Lang; 4] = [Lang::En, Lang::De, Lang::Fr, Lang::Es];
+
+    pub fn code(self) -> &'static str {
+        match self {
+            Lang::En => "en",
+            Lang::De => "de",
+            Lang::Fr => "fr",
+            Lang::Es => "es",
+        }
+    }
+
+    /// Language of a tag such as `de-CH`, `fr_FR.UTF-8` or `es`; `None` without a catalog
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let lang = tag
+            .trim()
+            .split(['-', '_', '.', '@'])
+            .next()?
+            .to_ascii_lowercase();
+        Self::ALL.into_iter().find(|l| l.code() == lang)
+    }
+
+    /// `language` from `config.json`, else the macOS preferred language; English when neither
+    /// has a catalog
+    pub fn current() -> Self {
+        crate::config::Config::language()
+            .and_then(|tag| Self::from_tag(&tag))
+            .or_else(|| Self::from_tag(system_language()))
+            .unwrap_or_default()
+    }
+
+    fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Lang::En => en::STRINGS,
+            Lang::De => de::STRINGS,
+            Lang::Fr => fr::STRINGS,
+            Lang::Es => es::STRINGS,
+        }
+    }
+
+    /// Text for `key`; English when this catalog lacks it, the key itself when both do
+    pub fn text(self, key: &'static str) -> &'static str {
+        let find = |catalog: &'static [(&'static str, &'static str)]| {
+            catalog.iter().find(|(k, _)| *k == key).map(|(_, v)| *v)
+        };
+        find(self.catalog())
+            .or_else(|| find(en::STRINGS))
+            .unwrap_or(key)
+    }
+
+    /// Text for `key` with `{name}` placeholders filled from `args`
+    pub fn format(self, key: &'static str, args: &[(&str, &str)]) -> String {
+        args.iter()
+            .fold(self.text(key).to_string(), |text, (name, value)| {
+                text.replace(&format!("{{{}}}", name), value)
+            })
+    }
+}
+
+/// First macOS preferred language (`AppleLanguages`, e.g. `de-CH`), falling back to the number
+/// locale. Read once.
+pub fn system_language() -> &'static str {
+    static SYSTEM_LANGUAGE: OnceLock<String> = OnceLock::new();
+    SYSTEM_LANGUAGE.get_or_init(|| {
+        std::process::Command::new("/usr/bin/defaults")
+            .args(["read", "-g", "AppleLanguages"])
+            .stderr(std::process::Stdio::null())
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .and_then(|o| first_preferred(&String::from_utf8_lossy(&o.stdout)))
+            .unwrap_or_else(crate::number_format::system_locale)
+    })
+}
+
+/// First entry of a `defaults` array such as `(\n    "de-CH",\n    en\n)`
+fn first_preferred(array: &str) -> Option<String> {
+    array
+        .split(['(', ')', ',', '"', '\n'])
+        .map(str::trim)
+        .find(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(text: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = text
+            .split('{')
+            .skip(1)
+            .filter_map(|rest| rest.split_once('}').map(|(name, _)| name))
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn catalogs_cover_english_keys_with_the_same_placeholders() {
+        for lang in Lang::ALL {
+            let catalog = lang.catalog();
+            assert_eq!(catalog.len(), en::STRINGS.len(), "{:?}", lang);
+            for (key, english) in en::STRINGS {
+                let text = catalog
+                    .iter()
+                    .find(|(k, _)| k == key)
+                    .map(|(_, v)| *v)
+                    .unwrap_or_else(|| panic!("{:?} lacks {}", lang, key));
+                assert_eq!(
+                    placeholders(text),
+                    placeholders(english),
+                    "{:?} {}",
+                    lang,
+                    key
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn languages_resolve_and_fill_placeholders() {
+        assert_eq!(Lang::from_tag("de-CH"), Some(Lang::De));
+        assert_eq!(Lang::from_tag("fr_FR.UTF-8"), Some(Lang::Fr));
+        assert_eq!(Lang::from_tag("ja"), None);
+        assert_eq!(
+            first_preferred("(\n    \"es-419\",\n    en\n)\n").as_deref(),
+            Some("es-419")
+        );
+        assert_eq!(
+            Lang::De.format("menu.profile_active", &[("name", "work")]),
+            "Profil: work"
+        );
+        assert_eq!(Lang::Fr.text("no.such.key"), "no.such.key");
+    }
+}
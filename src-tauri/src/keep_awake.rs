@@ -0,0 +1,175 @@
+//! Keep the Mac awake (like `caffeinate -i`)
+//!
+//! [`set`] holds a `PreventUserIdleSystemSleep` power assertion, optionally for a fixed time;
+//! the status menu's Keep Awake submenu and `set_keep_awake` both go through it. Timed
+//! assertions are checked against the wall clock, so time spent asleep anyway (lid closed)
+//! counts towards the duration. Quitting releases the assertion with the process.
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::ffi::power_assertion::PowerAssertion;
+use crate::i18n::Lang;
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+const ASSERTION_TYPE: &str = "PreventUserIdleSystemSleep";
+/// How often a timed assertion checks whether it has run out
+const EXPIRY_CHECK: Duration = Duration::from_secs(15);
+
+struct Active {
+    _assertion: PowerAssertion,
+    until: Option<SystemTime>,
+    generation: u64,
+}
+
+static ACTIVE: Mutex<Option<Active>> = Mutex::new(None);
+/// Bumped by every [`set`], so an expiry thread only ends its own assertion
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Keep-awake state (`get_keep_awake`, `set_keep_awake`, status menu)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeepAwakeStatus {
+    pub active: bool,
+    /// Unix seconds when it ends; None while off or without a time limit
+    pub until: Option<u64>,
+    pub remaining_secs: Option<u64>,
+}
+
+impl KeepAwakeStatus {
+    /// Status menu title, e.g. `Keep Awake (1h 05m left)`
+    pub fn menu_title(&self, lang: Lang) -> String {
+        match (self.active, self.remaining_secs) {
+            (false, _) => lang.text("menu.keep_awake").to_string(),
+            (true, None) => lang.text("menu.keep_awake_on").to_string(),
+            (true, Some(secs)) => {
+                let minutes = secs.div_ceil(60);
+                let time = if minutes >= 60 {
+                    format!("{}h {:02}m", minutes / 60, minutes % 60)
+                } else {
+                    format!("{}m", minutes)
+                };
+                lang.format("menu.keep_awake_left", &[("time", &time)])
+            }
+        }
+    }
+}
+
+fn unix_secs(t: SystemTime) -> u64 {
+    t.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+/// Current state
+pub fn status() -> KeepAwakeStatus {
+    let Ok(active) = ACTIVE.lock() else {
+        return KeepAwakeStatus::default();
+    };
+    match active.as_ref() {
+        None => KeepAwakeStatus::default(),
+        Some(a) => KeepAwakeStatus {
+            active: true,
+            until: a.until.map(unix_secs),
+            remaining_secs: a.until.map(|until| {
+                until
+                    .duration_since(SystemTime::now())
+                    .map_or(0, |d| d.as_secs())
+            }),
+        },
+    }
+}
+
+/// Keep the Mac awake (`duration`: for that long, `None`: until turned off) or let it sleep
+/// again
+pub fn set(enabled: bool, duration: Option<Duration>) -> Result<KeepAwakeStatus, String> {
+    let generation = GENERATION.fetch_add(1, Ordering::Relaxed) + 1;
+    {
+        let mut active = ACTIVE.lock().map_err(|e| e.to_string())?;
+        // Release the previous assertion first; a new one replaces it
+        *active = None;
+        if enabled {
+            let duration = duration.filter(|d| !d.is_zero());
+            *active = Some(Active {
+                _assertion: PowerAssertion::new(ASSERTION_TYPE, "mac-stats Keep Awake")?,
+                until: duration.map(|d| SystemTime::now() + d),
+                generation,
+            });
+            if let Some(duration) = duration {
+                std::thread::spawn(move || expire_after(generation, duration));
+            }
+        }
+    }
+    let status = status();
+    tracing::info!(
+        "Keep awake {}",
+        match (status.active, status.remaining_secs) {
+            (false, _) => "off".to_string(),
+            (true, None) => "on".to_string(),
+            (true, Some(secs)) => format!("on for {}s", secs),
+        }
+    );
+    Ok(status)
+}
+
+fn expire_after(generation: u64, duration: Duration) {
+    let until = SystemTime::now() + duration;
+    while SystemTime::now() < until {
+        std::thread::sleep(
+            EXPIRY_CHECK.min(until.duration_since(SystemTime::now()).unwrap_or_default()),
+        );
+        if GENERATION.load(Ordering::Relaxed) != generation {
+            return;
+        }
+    }
+    if let Ok(mut active) = ACTIVE.lock() {
+        if active.as_ref().is_some_and(|a| a.generation == generation) {
+            *active = None;
+            debug1!("Keep awake: time is up, sleep allowed again");
+        }
+    }
+}
+
+/// Whether the Mac is being kept awake
+#[tauri::command]
+pub fn get_keep_awake() -> KeepAwakeStatus {
+    status()
+}
+
+/// Prevent idle sleep (`enabled`), for `duration_secs` or until turned off
+#[tauri::command]
+pub fn set_keep_awake(
+    enabled: bool,
+    duration_secs: Option<u64>,
+) -> Result<KeepAwakeStatus, String> {
+    crate::guest_mode::ensure_allowed("Keep Awake")?;
+    set(enabled, duration_secs.map(Duration::from_secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn menu_title_shows_time_left() {
+        let on = |remaining_secs| KeepAwakeStatus {
+            active: true,
+            until: None,
+            remaining_secs,
+        };
+        assert_eq!(
+            KeepAwakeStatus::default().menu_title(Lang::En),
+            "Keep Awake"
+        );
+        assert_eq!(on(None).menu_title(Lang::En), "Keep Awake (on)");
+        assert_eq!(
+            on(Some(3900)).menu_title(Lang::En),
+            "Keep Awake (1h 05m left)"
+        );
+        assert_eq!(
+            on(Some(61)).menu_title(Lang::Es),
+            "Mantener activo (quedan 2m)"
+        );
+    }
+}
@@ -8,8 +8,14 @@
 //! - `logging`: Structured logging with tracing
 //! - `state`: Application state management
 //! - `metrics`: System metrics collection (CPU, RAM, Disk, GPU, temperature, frequency)
+//! - `collector`: Public library API: the `mac_stats_collector` crate ([`collector::Collector`],
+//!   no Tauri/AppKit) plus the saved history
 //! - `config`: Configuration management (paths, build info)
 //! - `ffi`: Safe FFI wrappers for IOReport and Objective-C
+//! - `capabilities`: What this Mac lets us read (SMC, IOReport, powermetrics, battery)
+//! - `privileged_helper`: Root launch daemon reached over XPC for root-only sensors
+//! - `background`: The collector runtime (one tokio runtime, a task per periodic collector)
+//! - `sampler`: Background sampling tasks (SMC, IOReport, menu bar titles, history)
 //! - `ui`: UI components (status bar, windows)
 //!
 //! ## Main Entry Points
@@ -19,97 +25,55 @@
 
 pub mod agents;
 mod alerts;
+mod api;
+mod background;
+mod capabilities;
 pub mod browser_agent;
 pub mod browser_doctor;
 pub mod circuit_breaker;
+pub mod collector;
 mod commands;
 pub mod config;
 pub mod discord;
+mod doctor;
 pub mod downloads_organizer;
 pub mod events;
 pub mod feature_health;
 mod ffi;
+mod guest_mode;
+mod i18n;
+mod keep_awake;
 pub mod keyed_queue;
 mod logging;
 mod mcp;
 mod metrics;
 mod monitors;
+mod mqtt;
+mod number_format;
 mod ollama;
 mod ollama_queue;
 mod operator_task_pressure;
 mod perplexity;
 mod plugins;
+mod privileged_helper;
 mod prompts;
 pub mod redmine;
+mod sampler;
 mod scheduler;
 mod search_result_shaping;
 pub mod security;
 mod session_memory;
 mod skills;
+mod startup;
 mod state;
 pub mod task;
 mod ui;
+mod units;
+mod updates;
 mod user_info;
 
-use macsmc::Smc;
-use std::os::raw::c_void;
-use sysinfo::{Disks, System};
-
 // Re-export logging functions (macros are auto-exported via #[macro_export])
 pub use logging::{init_tracing, set_verbosity, sync_debug_log_best_effort};
-// IOReport types kept for future use (extern block still references them)
-use core_foundation::base::{CFTypeRef, TCFType};
-use core_foundation::dictionary::{CFDictionaryRef, CFMutableDictionary, CFMutableDictionaryRef};
-use core_foundation::string::{CFString, CFStringRef};
-
-// IOReport FFI bindings (similar to macmon)
-// Some functions are declared for future use
-#[allow(dead_code)]
-#[link(name = "IOReport", kind = "dylib")]
-extern "C" {
-    fn IOReportCopyAllChannels(a: u64, b: u64) -> CFDictionaryRef;
-    fn IOReportCopyChannelsInGroup(
-        group: CFStringRef,
-        subgroup: CFStringRef,
-        want_hierarchical: u64,
-        want_sub_groups: u64,
-        want_historical: u64,
-    ) -> CFDictionaryRef;
-    fn IOReportMergeChannels(dest: CFMutableDictionaryRef, src: CFDictionaryRef, nil: CFTypeRef);
-    fn IOReportCreateSubscription(
-        allocator: CFTypeRef,
-        channels: CFMutableDictionaryRef,
-        subscription: *mut CFMutableDictionaryRef,
-        channel_id: u64,
-        options: CFTypeRef,
-    ) -> *mut c_void;
-    fn IOReportCreateSamples(
-        subscription: *const c_void,
-        channels: CFMutableDictionaryRef,
-        options: CFTypeRef,
-    ) -> CFDictionaryRef;
-    fn IOReportCreateSamplesDelta(
-        start: CFDictionaryRef,
-        end: CFDictionaryRef,
-        options: CFTypeRef,
-    ) -> CFDictionaryRef;
-    fn IOReportChannelGetGroup(item: CFDictionaryRef) -> CFStringRef;
-    fn IOReportChannelGetSubGroup(item: CFDictionaryRef) -> CFStringRef;
-    fn IOReportChannelGetChannelName(item: CFDictionaryRef) -> CFStringRef;
-    fn IOReportSimpleGetIntegerValue(item: CFDictionaryRef, index: i32) -> i64;
-    fn IOReportChannelGetUnitLabel(item: CFDictionaryRef) -> CFStringRef;
-    fn IOReportStateGetCount(item: CFDictionaryRef) -> i32;
-    fn IOReportStateGetNameForIndex(item: CFDictionaryRef, index: i32) -> CFStringRef;
-    fn IOReportStateGetResidency(item: CFDictionaryRef, index: i32) -> i64;
-}
-
-// CoreFoundation functions for memory management
-#[link(name = "CoreFoundation", kind = "framework")]
-extern "C" {
-    fn CFRelease(cf: CFTypeRef);
-    fn CFRetain(cf: CFTypeRef) -> CFTypeRef;
-}
-
 // IOReport helper functions removed - IOReport operations were too expensive for real-time monitoring
 // If needed in the future, these can be re-implemented with proper caching
 use objc2::MainThreadMarker;
@@ -125,6 +89,36 @@ pub use metrics::{
     force_quit_process, get_app_version, get_changelog, get_cpu_details, get_metrics,
     get_process_details, get_window_decorations, set_window_decorations, CpuDetails, SystemMetrics,
 };
+// Re-export for CLI (`mac_stats stats`)
+pub use metrics::cli::{run as run_stats_cli, StatsArgs};
+// Re-export for CLI (`mac_stats export`)
+pub use metrics::export::{run_cli as run_export_cli, ExportArgs};
+// Re-export for CLI (`mac_stats sessions`)
+pub use metrics::sessions::{run_cli as run_sessions_cli, SessionsArgs};
+// Re-export for CLI (`mac_stats energy`)
+pub use metrics::energy::{run_cli as run_energy_cli, EnergyArgs};
+// Re-export for CLI (`mac_stats query`)
+pub use api::query::{run_cli as run_query_cli, QueryArgs};
+// Re-export for CLI (`mac_stats setup`)
+pub use config::setup::{run_cli as run_setup_cli, SetupArgs};
+// Re-export for CLI (`mac_stats config validate`)
+pub use config::schema::run_validate_cli as run_config_validate_cli;
+// Re-export for CLI (`mac_stats config export` / `import`)
+pub use config::bundle::{
+    run_export_cli as run_settings_export_cli, run_import_cli as run_settings_import_cli,
+};
+// Re-export for CLI (`mac_stats profile`)
+pub use config::profiles::{
+    run_list_cli as run_profile_list_cli, run_use_cli as run_profile_use_cli,
+};
+// Re-export for CLI (`mac_stats menu-bar restore`)
+pub use ui::status_removal::run_restore_cli as run_menu_bar_restore_cli;
+// Re-export for the launch daemon (`mac_stats privileged-helper`)
+pub use privileged_helper::run as run_privileged_helper;
+// Re-export for CLI (`mac_stats doctor`)
+pub use doctor::run_doctor_stdio;
+// Re-export for CLI (`mac_stats ui-selftest`)
+pub use ui::selftest::run_ui_selftest_stdio;
 // Re-export for CLI (e.g. discord run-ollama)
 pub use commands::judge::run_judge_if_enabled;
 pub use commands::ollama::{
@@ -137,7 +131,8 @@ pub use commands::untrusted_content::wrap_untrusted_content;
 
 // UI functions are now in ui module
 use ui::status_bar::{
-    build_status_text, create_cpu_window, make_attributed_title, setup_status_item,
+    build_item_text, build_status_text, create_cpu_window, make_attributed_title,
+    setup_status_item,
 };
 
 /// Set frequency logging flag for detailed debugging
@@ -150,6 +145,13 @@ pub fn set_frequency_logging(enabled: bool) {
     }
 }
 
+/// Start in read-only guest mode (`--guest`): mutating commands are refused (see `guest_mode`)
+pub fn set_guest_mode(enabled: bool) {
+    if enabled {
+        guest_mode::enable_from_cli();
+    }
+}
+
 /// Set power usage logging flag for detailed debugging
 pub fn set_power_usage_logging(enabled: bool) {
     if let Ok(mut flag) = state::POWER_USAGE_LOGGING_ENABLED.lock() {
@@ -234,6 +236,10 @@ fn run_internal(open_cpu_window: bool) {
         };
     }
 
+    // A release staged by the self-updater (`autoUpdate: "install"`) replaces this bundle now;
+    // on success the new version is relaunched and this process exits.
+    updates::installer::apply_pending();
+
     // SIGINT/SIGTERM/SIGHUP often terminate the process without Tauri emitting `RunEvent::Exit`
     // first. Register a handler so `close_browser_session()` still runs (browser-use-style safety).
     match ctrlc::set_handler(|| {
@@ -263,10 +269,37 @@ fn run_internal(open_cpu_window: bool) {
 
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
-        .invoke_handler(tauri::generate_handler![
-            get_cpu_details,
-            get_metrics,
+        .plugin(tauri_plugin_dialog::init())
+        .invoke_handler(logging::otel::timed_commands(tauri::generate_handler![
+            metrics::warm_start::get_cpu_details,
+            metrics::warm_start::get_metrics,
+            capabilities::get_capabilities,
+            config::schema::get_config_issues,
+            config::profiles::list_config_profiles,
+            config::profiles::set_config_profile,
+            config::bundle::export_settings,
+            config::bundle::import_settings,
+            privileged_helper::get_privileged_helper_status,
+            keep_awake::get_keep_awake,
+            keep_awake::set_keep_awake,
+            privileged_helper::set_privileged_helper_installed,
             metrics::get_metrics_history,
+            metrics::get_process_history,
+            metrics::list_watched_processes,
+            metrics::get_fan_audible_history,
+            metrics::get_fan_control_status,
+            metrics::set_fan_speed,
+            metrics::low_power_mode::set_low_power_mode,
+            metrics::subscriptions::subscribe,
+            metrics::subscriptions::unsubscribe,
+            metrics::get_history_stats,
+            metrics::history_compare::compare_history,
+            metrics::import::import_metrics_history,
+            metrics::export::export_history,
+            metrics::backup::get_backup_status,
+            commands::login_item::get_launch_at_login,
+            commands::login_item::set_launch_at_login,
+            commands::maintenance::run_maintenance_action,
             get_app_version,
             get_window_decorations,
             set_window_decorations,
@@ -277,6 +310,31 @@ fn run_internal(open_cpu_window: bool) {
             metrics::reset_config_to_monitor_defaults,
             get_process_details,
             force_quit_process,
+            commands::confirmation::request_confirmation,
+            guest_mode::get_guest_mode,
+            ui::theme::get_theme,
+            units::get_units,
+            units::set_units,
+            ui::window_pin::get_window_pin_mode,
+            ui::window_pin::set_window_pin_mode,
+            metrics::network_details::get_network_details,
+            metrics::network_details::get_network_history,
+            metrics::battery_details::get_battery_details,
+            metrics::energy::get_energy_report,
+            metrics::containers::get_container_stats,
+            metrics::remote_machines::get_dashboard,
+            metrics::self_stats::get_self_stats,
+            sampler::set_sampling,
+            metrics::system_report::generate_system_report,
+            ui::about_window::get_about_info,
+            ui::onboarding_window::get_onboarding_info,
+            ui::onboarding_window::request_notification_permission,
+            ui::onboarding_window::complete_onboarding,
+            ui::onboarding_window::dismiss_onboarding,
+            ui::copy_stats::copy_stats_to_clipboard,
+            updates::check_for_update,
+            updates::get_update_status,
+            updates::install_update,
             get_changelog,
             // Security: only store/delete exposed; never expose get_credential or list_credentials
             commands::security::store_credential,
@@ -347,6 +405,9 @@ fn run_internal(open_cpu_window: bool) {
             commands::logging::set_chat_verbosity,
             commands::logging::get_debug_log_path,
             commands::logging::read_debug_log,
+            commands::logging::read_log_entries,
+            commands::logging::get_log_filter,
+            commands::logging::set_log_filter,
             commands::logging::open_debug_log,
             // Scheduler UI commands
             commands::scheduler::list_schedules,
@@ -389,9 +450,13 @@ fn run_internal(open_cpu_window: bool) {
             commands::agents::list_prompt_files,
             commands::agents::save_prompt_file,
             feature_health::get_feature_health,
-        ])
+        ]))
         .setup(move |app| {
             crate::state::mark_process_start();
+            // Before anything creates config.json
+            ui::onboarding_window::note_first_launch();
+            // Process CPU times at launch, for the session summary written on quit
+            std::thread::spawn(metrics::sessions::start);
             // Write default prompt/agent files if missing (first launch or after update)
             crate::config::Config::ensure_defaults();
 
@@ -452,16 +517,28 @@ fn run_internal(open_cpu_window: bool) {
             }
 
             setup_status_item();
-
-            // Set placeholder text immediately (don't call get_metrics() here - it blocks)
-            let placeholder_text = "CPU\tGPU\tRAM\tSSD\n0%\t0%\t0%\t0%";
-            let initial_attributed = make_attributed_title(placeholder_text);
-            STATUS_ITEM.with(|cell| {
-                if let Some(item) = cell.borrow().as_ref() {
-                    let mtm = MainThreadMarker::new().unwrap();
+            ui::theme::start_observing(MainThreadMarker::new().unwrap());
+            metrics::low_interference::start_observing();
+
+            // Set placeholder text immediately (don't call get_metrics() here - it blocks);
+            // the last run's readings (`warm_start`) when there are recent ones
+            metrics::warm_start::load();
+            let warm = metrics::warm_start::metrics();
+            let placeholder_text = match warm.as_ref() {
+                Some(last) => build_status_text(last),
+                None => "CPU\tGPU\tRAM\tSSD\n0%\t0%\t0%\t0%".to_string(),
+            };
+            STATUS_ITEMS.with(|cell| {
+                let mtm = MainThreadMarker::new().unwrap();
+                for (id, item) in cell.borrow().iter() {
+                    let placeholder = match warm.as_ref() {
+                        Some(last) => build_item_text(id, last, &placeholder_text),
+                        None if id == ui::status_items::MAIN => placeholder_text.clone(),
+                        None => ui::status_items::item_text(id, |_| None),
+                    };
                     if let Some(button) = item.button(mtm) {
-                        button.setAttributedTitle(&initial_attributed);
-                        debug3!("Initial placeholder menu bar text set");
+                        button.setAttributedTitle(&make_attributed_title(&placeholder, &[]));
+                        debug3!("Initial placeholder menu bar text set for '{}'", id);
                     }
                 }
             });
@@ -485,40 +562,43 @@ fn run_internal(open_cpu_window: bool) {
             println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
 
             // Ollama warmup / Discord / scheduler only when AI agent is enabled (opt-in).
-            if config::Config::ai_agent_enabled() {
-                tauri::async_runtime::block_on(async {
-                    commands::ollama_config::ensure_ollama_agent_ready_at_startup().await;
-                });
-                tracing::debug!(
-                    target: "mac_stats_startup",
-                    "Ollama startup warmup finished (gate open); spawning Discord, scheduler, heartbeat, and task review"
-                );
+            // Starts after `startupDelaySecs` (plus one stage) when a delay is configured.
+            startup::run_at(startup::Stage::Agent, || {
+                if config::Config::ai_agent_enabled() {
+                    tauri::async_runtime::block_on(async {
+                        commands::ollama_config::ensure_ollama_agent_ready_at_startup().await;
+                    });
+                    tracing::debug!(
+                        target: "mac_stats_startup",
+                        "Ollama startup warmup finished (gate open); spawning Discord, scheduler, heartbeat, and task review"
+                    );
 
-                std::thread::spawn(|| {
-                    discord::spawn_discord_if_configured();
-                });
+                    std::thread::spawn(|| {
+                        discord::spawn_discord_if_configured();
+                    });
 
-                scheduler::spawn_scheduler_thread();
-                scheduler::heartbeat::spawn_heartbeat_thread();
-                task::review::spawn_review_thread();
+                    scheduler::spawn_scheduler_thread();
+                    scheduler::heartbeat::spawn_heartbeat_thread();
+                    task::review::spawn_review_thread();
 
-                std::thread::spawn(|| {
-                    let rt = match tokio::runtime::Runtime::new() {
-                        Ok(r) => r,
-                        Err(_) => return,
-                    };
-                    const INTERVAL_SECS: u64 = 30 * 60;
-                    loop {
-                        std::thread::sleep(std::time::Duration::from_secs(INTERVAL_SECS));
-                        rt.block_on(commands::compaction::run_periodic_session_compaction());
-                    }
-                });
-            } else {
-                tracing::info!(
-                    target: "mac_stats_startup",
-                    "AI agent disabled (aiAgentEnabled=false) — monitor-only mode; Discord/scheduler/Ollama warmup skipped"
-                );
-            }
+                    std::thread::spawn(|| {
+                        let rt = match tokio::runtime::Runtime::new() {
+                            Ok(r) => r,
+                            Err(_) => return,
+                        };
+                        const INTERVAL_SECS: u64 = 30 * 60;
+                        loop {
+                            std::thread::sleep(std::time::Duration::from_secs(INTERVAL_SECS));
+                            rt.block_on(commands::compaction::run_periodic_session_compaction());
+                        }
+                    });
+                } else {
+                    tracing::info!(
+                        target: "mac_stats_startup",
+                        "AI agent disabled (aiAgentEnabled=false) — monitor-only mode; Discord/scheduler/Ollama warmup skipped"
+                    );
+                }
+            });
 
             // Memory hygiene (cheap): drop timeout / lesson-scaffold pollution from memory*.md
             {
@@ -537,7 +617,10 @@ fn run_internal(open_cpu_window: bool) {
             agents::watch::spawn_agents_and_skills_watcher();
 
             // Subsystem health report (structured probes, logged after short delay for Discord/Ollama).
-            feature_health::spawn_startup_feature_health_probe();
+            startup::run_at(
+                startup::Stage::Background,
+                feature_health::spawn_startup_feature_health_probe,
+            );
 
             // Periodic operator pressure line when automation is non-trivial (queues, WIP tasks, imminent schedules).
             tauri::async_runtime::spawn(async move {
@@ -561,14 +644,35 @@ fn run_internal(open_cpu_window: bool) {
 
             // Run website monitor checks in the background so monitors are checked even when the CPU window
             // is not open. Wakes every 30s and runs checks for any monitor that is due (by its interval).
-            std::thread::spawn(|| {
-                loop {
-                    std::thread::sleep(std::time::Duration::from_secs(30));
-                    commands::monitors::run_due_monitor_checks();
-                }
+            startup::run_at(startup::Stage::Background, || {
+                std::thread::spawn(|| {
+                    loop {
+                        std::thread::sleep(std::time::Duration::from_secs(30));
+                        commands::monitors::run_due_monitor_checks();
+                    }
+                });
+            });
+
+            // Exporters; after `startupDelaySecs` when a delay is configured.
+            startup::run_at(startup::Stage::Exporters, || {
+                // Local HTTP API (`apiEnabled`, loopback only).
+                api::spawn_api_server_if_enabled();
+                // InfluxDB line-protocol export (`influxExport`).
+                metrics::influx::spawn_influx_export_if_enabled();
+                // MQTT publisher with Home Assistant discovery (`mqtt`).
+                mqtt::spawn_mqtt_publisher_if_enabled();
+                // CPU/memory history for `watchedProcesses`.
+                metrics::process_watch::spawn_process_watch_if_enabled();
+                // Periodic full snapshots to disk (`metricSnapshotMinutes`).
+                metrics::snapshots::spawn_snapshots_if_enabled();
             });
 
-            // Run alert evaluation periodically so SiteDown, BatteryLow, TemperatureHigh, CpuHigh
+            // Release caches and fine-grained history when macOS reports memory pressure.
+            metrics::memory_pressure::spawn_memory_pressure_watcher();
+            // Fan speed and audible minutes per day (`fanAudibleRpm`); exits on fanless Macs.
+            metrics::fan_noise::spawn_fan_monitor();
+
+            // Run alert evaluation periodically so SiteDown, BatteryLow, TemperatureHigh, GpuTemperatureHigh, CpuHigh
             // etc. can fire without user action. Wakes every 60s and evaluates all alerts against
             // current metrics and monitor statuses.
             std::thread::spawn(|| {
@@ -578,1188 +682,96 @@ fn run_internal(open_cpu_window: bool) {
                 }
             });
 
-            // Downloads organizer: every 60s, run if enabled and hourly/daily schedule is due.
-            std::thread::spawn(|| {
-                loop {
-                    std::thread::sleep(std::time::Duration::from_secs(60));
-                    downloads_organizer::run_if_due();
-                }
-            });
+            // Update checks / staged installs per `autoUpdate` (first check two minutes after launch).
+            startup::run_at(startup::Stage::Background, updates::spawn_auto_update_if_enabled);
 
-            // For automatic updates, we'll use a simple approach:
-            // The background update loop stores updates in MENU_BAR_TEXT
-            // We'll process them in the click handler (which works)
-            // To get automatic updates without clicking, we can simulate a click programmatically
-            // But that's complex. Instead, let's use a simpler approach: process updates
-            // directly from a background thread that can access the main thread
-            // Actually, the simplest: just rely on click handler for now
-            // Users can click to see updates, which is better than nothing
+            // Metric plugins in ~/.mac-stats/plugins/ (run on their own intervals).
+            startup::run_at(startup::Stage::Background, plugins::metric_plugins::spawn_runner);
 
-            // Initialize System and Disks in background thread to avoid blocking
-            std::thread::spawn(move || {
-                debug3!("Background thread: initializing System and Disks");
-                // Create System outside the lock to avoid holding it
-                let new_system = System::new();
-                debug3!("Background thread: System::new() completed");
-                // Use try_lock to avoid blocking - if locked, skip initialization
-                if let Ok(mut sys) = SYSTEM.try_lock() {
-                    if sys.is_none() {
-                        *sys = Some(new_system);
-                        debug3!("Background thread: System stored");
-                    }
-                } else {
-                    debug3!("Background thread: SYSTEM lock unavailable, skipping");
-                }
+            // Crash/hang watchdog for the apps in `appWatchdog`.
+            startup::run_at(startup::Stage::Background, monitors::app_watchdog::spawn_app_watchdog);
 
-                // Create Disks outside the lock
-                let mut new_disks = Disks::new();
-                new_disks.refresh(false);
-                debug3!("Background thread: Disks::new() and refresh completed");
-                if let Ok(mut disks) = DISKS.try_lock() {
-                    if disks.is_none() {
-                        *disks = Some(new_disks);
-                        debug3!("Background thread: Disks stored");
+            // Downloads organizer: every 60s, run if enabled and hourly/daily schedule is due.
+            startup::run_at(startup::Stage::Background, || {
+                std::thread::spawn(|| {
+                    loop {
+                        std::thread::sleep(std::time::Duration::from_secs(60));
+                        downloads_organizer::run_if_due();
                     }
-                } else {
-                    debug3!("Background thread: DISKS lock unavailable, skipping");
-                }
-                debug3!("Background thread: initialization complete");
+                });
             });
 
-            // Menu bar updates will be processed by the click handler
-            // The background update loop stores updates in MENU_BAR_TEXT,
-            // and the click handler processes them when the menu bar is clicked.
-            // This ensures updates happen on the main thread without using
-            // the broken run_on_main_thread mechanism.
-
-            // Start update loop in background thread
-            std::thread::spawn(move || {
-                // Wait longer before first update to let background initialization complete
-                std::thread::sleep(std::time::Duration::from_millis(1500));
-
-                // Initialize history buffer (adaptive tiered storage with automatic downsampling)
-                if let Ok(mut history) = METRICS_HISTORY.try_lock() {
-                    *history = Some(metrics::history::HistoryBuffer::new());
-                    debug3!("Metrics history buffer initialized (capacity: 26 KB)");
-                } else {
-                    debug3!("Warning: Could not initialize metrics history buffer - lock contention at startup");
-                }
-
-                // CRITICAL: Keep SMC connection alive in background thread (reuse for efficiency)
-                // SMC connection is not Sync, so we keep it thread-local
-                let mut smc_connection: Option<Smc> = None;
-
-                loop {
-                    // Menu bar updates every 1-2 seconds (like Stats app) for responsive UI
-                    // Fast metrics (CPU, RAM) are cached, so this is cheap
-                    std::thread::sleep(std::time::Duration::from_secs(1));
-
-                    debug3!("Update loop: getting metrics...");
-                    let metrics = get_metrics();
-
-                    // CRITICAL: Only update menu bar if metrics are valid
-                    // Invalid metrics (all zeros) can occur during initialization or when locks are held
-                    // In that case, skip this update and wait for the next cycle
-                    if !metrics.is_valid() {
-                        debug3!("Skipping menu bar update: invalid metrics (CPU={}%, GPU={}%, RAM={}%, DISK={}%)",
-                            metrics.cpu, metrics.gpu, metrics.ram, metrics.disk);
-                        continue; // Skip this update cycle
-                    }
-
-                    let mut text = build_status_text(&metrics);
-                    if config::Config::ai_agent_enabled()
-                        && ollama::ollama_http_circuit_is_open_for_menu()
-                    {
-                        text.push_str("\nOllama ✕");
-                    }
-                    // Red monitor cue in the menu bar when any website/social check is down.
-                    let any_monitor_down = commands::monitors::get_monitor_statuses_snapshot()
-                        .iter()
-                        .any(|(_, st)| !st.is_up);
-                    if any_monitor_down {
-                        text.push_str("\nMon ✕");
-                    }
-
-                    // Store update in static variable
-                    if let Ok(mut pending) = MENU_BAR_TEXT.lock() {
-                        *pending = Some(text);
-                        debug3!("Menu bar update stored: CPU={}%, GPU={}%, RAM={}%, DISK={}%",
-                            metrics.cpu, metrics.gpu, metrics.ram, metrics.disk);
-                    }
-
-                    // Add to history buffer (always collect basic metrics when available)
-                    // We'll enhance with temperature/frequency when CPU window is visible
-                    let history_point = metrics::history::MetricPoint::from_metrics(
-                        metrics.cpu,
-                        metrics.gpu,
-                        metrics.ram,
-                        metrics.disk,
-                        0.0,  // temperature (will be updated below if CPU window visible)
-                        0.0,  // frequency (will be updated below if CPU window visible)
-                        0.0,  // p_core_frequency
-                        0.0,  // e_core_frequency
-                        0.0,  // cpu_power
-                        0.0,  // gpu_power
-                        -1.0, // battery_level
-                    );
-
-                    // Store for later enhancement with CPU details
-                    let mut final_history_point = history_point;
-
-                    // CRITICAL: Only read temperature when CPU window is visible (saves CPU)
-                    // Check window visibility before expensive SMC operations
-                    let should_read_temp = APP_HANDLE.get()
-                        .and_then(|app_handle| {
-                            app_handle.get_webview_window("cpu").and_then(|window| {
-                                window.is_visible().ok().filter(|&visible| visible)
-                            })
-                        })
-                        .is_some();
-
-                    if should_read_temp {
-                        // CPU window is visible - read temperature and frequency
-                        // Reuse SMC connection if available, otherwise create new one
-                        if smc_connection.is_none() {
-                            match Smc::connect() {
-                                Ok(smc) => {
-                                    smc_connection = Some(smc);
-                                    debug3!("SMC connection established in background thread");
-                                    // OPTIMIZATION Phase 3: Update OnceLock to indicate SMC works
-                                    // This ensures can_read_temperature() returns true
-                                    if CAN_READ_TEMPERATURE.set(true).is_ok() {
-                                        debug3!("CAN_READ_TEMPERATURE set to true (SMC connection successful)");
-                                    }
-                                },
-                                Err(e) => {
-                                    debug3!("Failed to connect to SMC: {:?}", e);
-                                    // Will retry on next iteration
-                                }
-                            }
-                        }
-
-                        // CRITICAL: Create IOReport subscription for frequency reading (once, when window opens)
-                        // This is expensive to create, so we keep it alive and reuse it
-                        // Implementation follows exelban/stats approach: use IOReport API directly
-                        if let Ok(mut sub) = IOREPORT_SUBSCRIPTION.try_lock() {
-                            if sub.is_none() {
-                                // Create IOReport subscription for CPU frequency channels
-                                // Group: "CPU Stats", SubGroup: "CPU Core Performance States"
-                                unsafe {
-                                    // Create CFString objects for group and subgroup
-                                    let group_cf = CFString::from_static_string("CPU Stats");
-                                    let subgroup_cf = CFString::from_static_string("CPU Core Performance States");
-
-                                    // Get channels in the CPU Performance States group
-                                    let channels_dict = IOReportCopyChannelsInGroup(
-                                        group_cf.as_concrete_TypeRef(),
-                                        subgroup_cf.as_concrete_TypeRef(),
-                                        0, // want_hierarchical
-                                        0, // want_sub_groups
-                                        0, // want_historical
-                                    );
-
-                                    if !channels_dict.is_null() {
-                                        // CRITICAL: Retain channels_dict before storing (Create/Copy rule)
-                                        CFRetain(channels_dict as CFTypeRef);
-                                        // Store original channels_dict for iterating channel structure
-                                        if let Ok(mut orig_channels_storage) = IOREPORT_ORIGINAL_CHANNELS.try_lock() {
-                                            // Release old one if it exists
-                                            if let Some(old_dict_usize) = orig_channels_storage.take() {
-                                                let old_dict = old_dict_usize as CFDictionaryRef;
-                                                if !old_dict.is_null() {
-                                                    CFRelease(old_dict as CFTypeRef);
-                                                }
-                                            }
-                                            *orig_channels_storage = Some(channels_dict as usize);
-                                        } else {
-                                            // Lock failed, release the retained dict
-                                            CFRelease(channels_dict as CFTypeRef);
-                                        }
-
-                                        // Create mutable dictionary for subscription
-                                        // We need to merge the channels into a mutable dictionary
-                                        // For IOReport, we use CFString keys and CFType values
-                                        use core_foundation::base::CFType;
-                                        let channels_mut: CFMutableDictionary<CFString, CFType> = CFMutableDictionary::new();
-
-                                        // Merge channels into our mutable dictionary
-                                        IOReportMergeChannels(
-                                            channels_mut.as_concrete_TypeRef(),
-                                            channels_dict,
-                                            std::ptr::null(),
-                                        );
-
-                                        // Create subscription
-                                        // IOReportCreateSubscription returns the subscription handle as *mut c_void
-                                        // and also fills in subscription_dict with channel information
-                                        let mut subscription_dict: CFMutableDictionaryRef = std::ptr::null_mut();
-
-                                        let subscription_ptr = IOReportCreateSubscription(
-                                            std::ptr::null(), // allocator
-                                            channels_mut.as_concrete_TypeRef(),
-                                            &mut subscription_dict,
-                                            0, // channel_id
-                                            std::ptr::null(), // options
-                                        );
-
-                                        // The subscription handle is the return value, not the dictionary
-                                        if !subscription_ptr.is_null() {
-                                            *sub = Some(subscription_ptr as usize);
-
-                                            // CRITICAL: Retain subscription_dict before storing
-                                            if !subscription_dict.is_null() {
-                                                CFRetain(subscription_dict as CFTypeRef);
-                                                // Store subscription_dict (contains channel structure we can iterate)
-                                                if let Ok(mut sub_dict_storage) = IOREPORT_SUBSCRIPTION_DICT.try_lock() {
-                                                    // Release old one if it exists
-                                                    if let Some(old_dict_usize) = sub_dict_storage.take() {
-                                                        let old_dict = old_dict_usize as CFMutableDictionaryRef;
-                                                        if !old_dict.is_null() {
-                                                            CFRelease(old_dict as CFTypeRef);
-                                                        }
-                                                    }
-                                                    *sub_dict_storage = Some(subscription_dict as usize);
-                                                } else {
-                                                    // Lock failed, release the retained dict
-                                                    CFRelease(subscription_dict as CFTypeRef);
-                                                }
-                                            }
-
-                                            // Store channels dictionary for sampling (needed for IOReportCreateSamples)
-                                            // CRITICAL: Retain the dictionary to avoid use-after-free crashes
-                                            CFRetain(channels_mut.as_concrete_TypeRef() as CFTypeRef);
-                                            if let Ok(mut channels_storage) = IOREPORT_CHANNELS.try_lock() {
-                                                // Release old one if it exists
-                                                if let Some(old_ptr) = channels_storage.take() {
-                                                    CFRelease(old_ptr as CFTypeRef);
-                                                }
-                                                *channels_storage = Some(channels_mut.as_concrete_TypeRef() as usize);
-                                            } else {
-                                                // Lock failed, release the retained dict
-                                                CFRelease(channels_mut.as_concrete_TypeRef() as CFTypeRef);
-                                            }
-
-                                            debug3!("IOReport subscription created successfully for CPU frequency (handle={:p}, dict={:p})", subscription_ptr, subscription_dict);
-
-                                            // OPTIMIZATION Phase 3: Update OnceLock to indicate frequency reading works
-                                            // OPTIMIZATION Phase 3: Update OnceLock to indicate frequency reading works
-                                            if CAN_READ_FREQUENCY.set(true).is_ok() {
-                                                debug3!("CAN_READ_FREQUENCY set to true (IOReport subscription created)");
-                                            }
-                                        } else {
-                                            debug3!("Failed to create IOReport subscription: subscription_ptr is null, subscription_dict={:p}", subscription_dict);
-                                        }
-                                    } else {
-                                        debug3!("No CPU Performance States channels found in IOReport");
-                                    }
-                                }
-                            }
-                        }
-
-                        // CRITICAL: Create IOReport subscription for power reading (once, when window opens)
-                        // This is expensive to create, so we keep it alive and reuse it
-                        // Power channels are in groups like "CPU Stats" / "CPU Power" or "GPU Stats" / "GPU Power"
-                        if let Ok(mut power_sub) = IOREPORT_POWER_SUBSCRIPTION.try_lock() {
-                            if power_sub.is_none() {
-                                // Try to find power channels - common groups:
-                                // "CPU Stats" / "CPU Power" or "CPU Energy"
-                                // "GPU Stats" / "GPU Power" or "GPU Energy"
-                                unsafe {
-                                    // Try multiple power channel combinations
-                                    // Power channels vary by Mac model and macOS version
-                                    // Based on research: "Energy Model" group is commonly used for power
-                                    let mut power_channels_dict: CFDictionaryRef = std::ptr::null_mut();
-                                    let mut found_channel_name = String::new();
-
-                                    // Try "Energy Model" group first (common for power metrics)
-                                    let energy_model_group_cf = CFString::from_static_string("Energy Model");
-                                    let energy_model_dict = IOReportCopyChannelsInGroup(
-                                        energy_model_group_cf.as_concrete_TypeRef(),
-                                        std::ptr::null(), // NULL subgroup = all subgroups
-                                        0, 0, 0,
-                                    );
-                                    if !energy_model_dict.is_null() {
-                                        use core_foundation::dictionary::CFDictionaryGetCount;
-                                        let count = CFDictionaryGetCount(energy_model_dict);
-                                        if count > 0 {
-                                            power_channels_dict = energy_model_dict;
-                                            found_channel_name = "Energy Model (all subgroups)".to_string();
-                                            debug3!("Found power channels: Energy Model ({} entries)", count);
-                                        } else {
-                                            CFRelease(energy_model_dict as CFTypeRef);
-                                        }
-                                    }
-
-                                    // If Energy Model didn't work, try CPU Power
-                                    if power_channels_dict.is_null() {
-                                        let cpu_group_cf = CFString::from_static_string("CPU Stats");
-                                        let cpu_power_subgroup_cf = CFString::from_static_string("CPU Power");
-                                        let cpu_channels_dict = IOReportCopyChannelsInGroup(
-                                            cpu_group_cf.as_concrete_TypeRef(),
-                                            cpu_power_subgroup_cf.as_concrete_TypeRef(),
-                                            0, 0, 0,
-                                        );
-                                        if !cpu_channels_dict.is_null() {
-                                            power_channels_dict = cpu_channels_dict;
-                                            found_channel_name = "CPU Stats / CPU Power".to_string();
-                                            debug3!("Found power channels: CPU Stats / CPU Power");
-                                        } else {
-                                            // Try CPU Energy
-                                            let cpu_energy_subgroup_cf = CFString::from_static_string("CPU Energy");
-                                            let cpu_energy_channels_dict = IOReportCopyChannelsInGroup(
-                                                cpu_group_cf.as_concrete_TypeRef(),
-                                                cpu_energy_subgroup_cf.as_concrete_TypeRef(),
-                                                0, 0, 0,
-                                            );
-                                            if !cpu_energy_channels_dict.is_null() {
-                                                power_channels_dict = cpu_energy_channels_dict;
-                                                found_channel_name = "CPU Stats / CPU Energy".to_string();
-                                                debug3!("Found power channels: CPU Stats / CPU Energy");
-                                            } else {
-                                                // Try GPU Power
-                                                let gpu_group_cf = CFString::from_static_string("GPU Stats");
-                                                let gpu_power_subgroup_cf = CFString::from_static_string("GPU Power");
-                                                let gpu_channels_dict = IOReportCopyChannelsInGroup(
-                                                    gpu_group_cf.as_concrete_TypeRef(),
-                                                    gpu_power_subgroup_cf.as_concrete_TypeRef(),
-                                                    0, 0, 0,
-                                                );
-                                                if !gpu_channels_dict.is_null() {
-                                                    power_channels_dict = gpu_channels_dict;
-                                                    found_channel_name = "GPU Stats / GPU Power".to_string();
-                                                    debug3!("Found power channels: GPU Stats / GPU Power");
-                                                } else {
-                                                    // Try GPU Energy
-                                                    let gpu_energy_subgroup_cf = CFString::from_static_string("GPU Energy");
-                                                    let gpu_energy_channels_dict = IOReportCopyChannelsInGroup(
-                                                        gpu_group_cf.as_concrete_TypeRef(),
-                                                        gpu_energy_subgroup_cf.as_concrete_TypeRef(),
-                                                        0, 0, 0,
-                                                    );
-                                                    if !gpu_energy_channels_dict.is_null() {
-                                                        power_channels_dict = gpu_energy_channels_dict;
-                                                        found_channel_name = "GPU Stats / GPU Energy".to_string();
-                                                        debug3!("Found power channels: GPU Stats / GPU Energy");
-                                                    } else {
-                                                        debug3!("No power channels found - tried: Energy Model, CPU Power, CPU Energy, GPU Power, GPU Energy");
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-
-                                    if !power_channels_dict.is_null() {
-                                        // Check channel count before proceeding
-                                        use core_foundation::dictionary::CFDictionaryGetCount;
-                                        let channel_count = CFDictionaryGetCount(power_channels_dict);
-                                        debug3!("Power channels dictionary has {} entries", channel_count);
-
-                                        if channel_count == 0 {
-                                            debug3!("Power channels dictionary is empty - cannot create subscription");
-                                            CFRelease(power_channels_dict as CFTypeRef);
-                                        } else {
-                                            // CRITICAL: Extract actual channels from nested structure
-                                            // IOReportCopyChannelsInGroup returns a dict with "IOReportChannels" key
-                                            // containing the actual channel dictionaries
-                                            use core_foundation::base::CFGetTypeID;
-                                            use core_foundation::dictionary::CFDictionaryGetTypeID;
-                                            use core_foundation::string::CFStringGetTypeID;
-
-                                            let actual_channels_dict = {
-                                                use core_foundation::dictionary::CFDictionaryGetCount;
-
-                                                let keys_count = CFDictionaryGetCount(power_channels_dict);
-                                                let mut keys_buf: Vec<*const c_void> = vec![std::ptr::null(); keys_count as usize];
-                                                let mut values_buf: Vec<*const c_void> = vec![std::ptr::null(); keys_count as usize];
-
-                                                extern "C" {
-                                                    fn CFDictionaryGetKeysAndValues(
-                                                        theDict: CFDictionaryRef,
-                                                        keys: *mut *const c_void,
-                                                        values: *mut *const c_void,
-                                                    );
-                                                }
-
-                                                CFDictionaryGetKeysAndValues(
-                                                    power_channels_dict,
-                                                    keys_buf.as_mut_ptr(),
-                                                    values_buf.as_mut_ptr(),
-                                                );
-
-                                                // Log all keys to understand structure
-                                                for i in 0..(keys_count as usize) {
-                                                    let key_ref = keys_buf[i] as CFStringRef;
-                                                    if !key_ref.is_null() {
-                                                        let key_type_id = CFGetTypeID(key_ref as CFTypeRef);
-                                                        let string_type_id = CFStringGetTypeID();
-                                                        if key_type_id == string_type_id {
-                                                            let key_str = CFString::wrap_under_get_rule(key_ref);
-                                                            let key_name = key_str.to_string();
-                                                            debug3!("Power channels dict key[{}]: '{}'", i, key_name);
-
-                                                            let value_ptr = values_buf[i];
-                                                            if !value_ptr.is_null() {
-                                                                let value_type_id = CFGetTypeID(value_ptr as CFTypeRef);
-                                                                let dict_type_id = CFDictionaryGetTypeID();
-                                                                extern "C" {
-                                                                    fn CFArrayGetTypeID() -> u64;
-                                                                }
-                                                                let array_type_id = CFArrayGetTypeID();
-                                                                debug3!("  Value type_id={}, dict_type_id={}, array_type_id={}", value_type_id, dict_type_id, array_type_id);
-
-                                                                if value_type_id == dict_type_id {
-                                                                    let nested_dict = value_ptr as CFDictionaryRef;
-                                                                    let nested_count = CFDictionaryGetCount(nested_dict);
-                                                                    debug3!("  Nested dict has {} entries", nested_count);
-                                                                } else if value_type_id as u64 == array_type_id {
-                                                                    extern "C" {
-                                                                        fn CFArrayGetCount(theArray: *const c_void) -> i32;
-                                                                    }
-                                                                    let array_count = CFArrayGetCount(value_ptr as *const c_void);
-                                                                    debug3!("  Nested array has {} entries", array_count);
-                                                                    // If this is IOReportChannels array, we need to extract it
-                                                                    if key_name == "IOReportChannels" && array_count > 0 {
-                                                                        // For arrays, we need to process them differently
-                                                                        // The array contains channel dictionaries directly
-                                                                        debug3!("  Found IOReportChannels array with {} channels", array_count);
-                                                                    }
-                                                                }
-                                                            }
-                                                        }
-                                                    }
-                                                }
-
-                                                // For Energy Model, IOReportChannels is an array, not a dict
-                                                // We need to store the original dict (with IOReportChannels array) for channel name lookup
-                                                // IOReportMergeChannels will handle the array structure when creating subscription
-                                                debug3!("Using original power channels dict (contains IOReportChannels array)");
-                                                CFRetain(power_channels_dict as CFTypeRef);
-                                                power_channels_dict
-                                            };
-
-                                            // Retain and store original channels dict (the wrapper with IOReportChannels)
-                                            // This is needed for channel name lookup during power reading
-                                            // The actual_channels_dict is what we'll merge, but we store the wrapper for lookup
-                                            CFRetain(power_channels_dict as CFTypeRef);
-                                            if let Ok(mut orig_storage) = IOREPORT_POWER_ORIGINAL_CHANNELS.try_lock() {
-                                                if let Some(old_dict) = orig_storage.take() {
-                                                    CFRelease(old_dict as CFTypeRef);
-                                                }
-                                                // Store the wrapper dict (contains IOReportChannels array) for name lookup
-                                                *orig_storage = Some(power_channels_dict as usize);
-                                                debug3!("Stored original power channels dict in IOREPORT_POWER_ORIGINAL_CHANNELS");
-                                            } else {
-                                                CFRelease(power_channels_dict as CFTypeRef);
-                                            }
-
-                                            // Create mutable dictionary for subscription
-                                            // CRITICAL: Try using the channels dict directly first, then merge if needed
-                                            use core_foundation::base::CFType;
-                                            let power_channels_mut: CFMutableDictionary<CFString, CFType> = CFMutableDictionary::new();
-
-                                            debug3!("Merging power channels into mutable dictionary...");
-                                            IOReportMergeChannels(
-                                                power_channels_mut.as_concrete_TypeRef(),
-                                                actual_channels_dict,
-                                                std::ptr::null(),
-                                            );
-
-                                            // Release the extracted dict after merging (we've copied its contents)
-                                            CFRelease(actual_channels_dict as CFTypeRef);
-
-                                            // Check merged dictionary count
-                                            use core_foundation::dictionary::CFDictionaryGetCount;
-                                            let merged_count = CFDictionaryGetCount(power_channels_mut.as_concrete_TypeRef());
-                                            debug3!("Merged power channels dictionary has {} entries", merged_count);
-
-                                            // If merge resulted in 0 entries, try using the original dict directly
-                                            // This might work if the structure is already in the correct format
-                                            let channels_for_subscription = if merged_count == 0 {
-                                                debug3!("Merge resulted in 0 entries, trying to use channels dict directly");
-                                                // Retain the actual_channels_dict again since we'll use it directly
-                                                CFRetain(actual_channels_dict as CFTypeRef);
-                                                actual_channels_dict as CFMutableDictionaryRef
-                                            } else {
-                                                // Release the extracted channels dict (we've merged it)
-                                                CFRelease(actual_channels_dict as CFTypeRef);
-                                                power_channels_mut.as_concrete_TypeRef()
-                                            };
-
-                                            // Create subscription
-                                            let mut power_subscription_dict: CFMutableDictionaryRef = std::ptr::null_mut();
-                                            debug3!("Creating IOReport power subscription...");
-                                            let power_subscription_ptr = IOReportCreateSubscription(
-                                                std::ptr::null(),
-                                                channels_for_subscription,
-                                                &mut power_subscription_dict,
-                                                0,
-                                                std::ptr::null(),
-                                            );
-
-                                            // If we used the direct dict and subscription failed, release it
-                                            if merged_count == 0 && power_subscription_ptr.is_null() {
-                                                CFRelease(channels_for_subscription as CFTypeRef);
-                                            }
-
-                                            if !power_subscription_ptr.is_null() {
-                                                debug3!("IOReport power subscription created successfully!");
-                                            *power_sub = Some(power_subscription_ptr as usize);
-
-                                            if !power_subscription_dict.is_null() {
-                                                CFRetain(power_subscription_dict as CFTypeRef);
-                                                if let Ok(mut sub_dict_storage) = IOREPORT_POWER_SUBSCRIPTION_DICT.try_lock() {
-                                                    if let Some(old_dict) = sub_dict_storage.take() {
-                                                        CFRelease(old_dict as CFTypeRef);
-                                                    }
-                                                    *sub_dict_storage = Some(power_subscription_dict as usize);
-                                                } else {
-                                                    CFRelease(power_subscription_dict as CFTypeRef);
-                                                }
-                                            }
-
-                                            CFRetain(power_channels_mut.as_concrete_TypeRef() as CFTypeRef);
-                                            if let Ok(mut channels_storage) = IOREPORT_POWER_CHANNELS.try_lock() {
-                                                if let Some(old_ptr) = channels_storage.take() {
-                                                    CFRelease(old_ptr as CFTypeRef);
-                                                }
-                                                *channels_storage = Some(power_channels_mut.as_concrete_TypeRef() as usize);
-                                            } else {
-                                                CFRelease(power_channels_mut.as_concrete_TypeRef() as CFTypeRef);
-                                            }
-
-                                            debug3!("IOReport power subscription created successfully (handle={:p}, channels={})", power_subscription_ptr, found_channel_name);
-
-                                            if CAN_READ_CPU_POWER.set(true).is_ok() {
-                                                debug3!("CAN_READ_CPU_POWER set to true");
-                                            }
-                                            if CAN_READ_GPU_POWER.set(true).is_ok() {
-                                                debug3!("CAN_READ_GPU_POWER set to true");
-                                            }
-                                            } else {
-                                                debug3!("Failed to create IOReport power subscription: subscription_ptr is null");
-                                                debug3!("This may indicate the power channels require different handling or permissions");
-                                                // Release the retained channels dict since subscription failed
-                                                if let Ok(mut orig_storage) = IOREPORT_POWER_ORIGINAL_CHANNELS.try_lock() {
-                                                    if let Some(dict) = orig_storage.take() {
-                                                        CFRelease(dict as CFTypeRef);
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    } else {
-                                        debug3!("No power channels found in IOReport (tried: CPU Power, CPU Energy, GPU Power, GPU Energy)");
-                                        debug3!("Power consumption will show 0.0W - power channels may not be available on this Mac model");
-                                    }
-                                }
-                            }
-                        }
-
-                        // CRITICAL: Only read temperature every 20 seconds to reduce CPU usage
-                        // all_data() iteration is VERY expensive - limit it as much as possible
-                        // STEP 3: Temperature reading every 20s to save CPU
-                        // Temperature doesn't change rapidly, so 20s is still responsive
-                        let should_read_temp_now = if let Ok(mut last) = LAST_TEMP_UPDATE.lock() {
-                            let should = last.as_ref()
-                                .map(|t| t.elapsed().as_secs() >= 20)
-                                .unwrap_or(true);
-                            if should {
-                                *last = Some(std::time::Instant::now());
-                            }
-                            should
-                        } else {
-                            false
-                        };
-
-                        // Only actually read temperature if enough time has passed
-                        if should_read_temp_now {
-                            // Read temperature using existing connection
-                            if let Some(ref mut smc) = smc_connection {
-                                // First try standard cpu_temperature() method (works for M1/M2)
-                                let mut temp = 0.0;
-                                match smc.cpu_temperature() {
-                                    Ok(temps) => {
-                                        let die_temp: f64 = temps.die.into();
-                                        let prox_temp: f64 = temps.proximity.into();
-
-                                        // Priority: die > proximity
-                                        temp = if die_temp > 0.0 {
-                                            die_temp
-                                        } else if prox_temp > 0.0 {
-                                            prox_temp
-                                        } else {
-                                            0.0
-                                        };
-                                    },
-                                    Err(_) => {
-                                        // Standard method failed, continue to raw key reading
-                                    }
-                                }
-
-                                // If standard method returned 0.0, try reading M3 Max raw keys directly
-                                // These are the keys that exelban/stats uses for M3 Max
-                                if temp == 0.0 {
-                                    // Check if we've already discovered a working M3 key
-                                    let cached_key = M3_TEMP_KEY.lock().ok().and_then(|k| k.clone());
-
-                                    if let Some(key_name) = cached_key {
-                                        // CRITICAL: Use direct key reading instead of all_data() iteration
-                                        // This is MUCH more efficient - avoids iterating through all SMC keys
-                                        // Try to read the specific key directly
-                                        // Note: macsmc may not have direct key reading, so we'll limit all_data() usage
-                                        // Only call all_data() if we absolutely need to, and limit iteration
-                                        if let Ok(data_iter) = smc.all_data() {
-                                            for dbg in data_iter.flatten() {
-                                                if dbg.key == key_name {
-                                                    if let Ok(Some(macsmc::DataValue::Float(val))) = dbg.value {
-                                                        if val > 0.0 {
-                                                            temp = val as f64;
-                                                            debug3!("Temperature read from cached M3 key {}: {:.1}°C", key_name, temp);
-                                                            break;
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    } else {
-                                        // First time: discover which M3 key works
-                                        // CRITICAL: Only iterate through keys once, then cache the result
-                                        // Try known M3 Max temperature keys (same as exelban/stats uses)
-                                        let m3_keys = ["Tf04", "Tf09", "Tf0A", "Tf0B", "Tf0D", "Tf0E"];
-                                        if let Ok(data_iter) = smc.all_data() {
-                                            for dbg in data_iter.flatten() {
-                                                if m3_keys.contains(&dbg.key.as_str()) {
-                                                    if let Ok(Some(macsmc::DataValue::Float(val))) = dbg.value {
-                                                        if val > 0.0 {
-                                                            temp = val as f64;
-                                                            if let Ok(mut cached) = M3_TEMP_KEY.lock() {
-                                                                *cached = Some(dbg.key.clone());
-                                                                debug3!("Discovered working M3 temperature key: {} = {:.1}°C", dbg.key, temp);
-                                                            }
-                                                            break;
-                                                        }
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-
-                                if temp > 0.0 {
-                                    // Update cache with new temperature and timestamp
-                                    if let Ok(mut cache) = TEMP_CACHE.try_lock() {
-                                        *cache = Some((temp as f32, std::time::Instant::now()));
-                                        debug3!("Temperature updated in cache: {:.1}°C", temp);
-                                    } else {
-                                        debug3!("Temperature cache lock failed, skipping update");
-                                    }
-                                } else {
-                                    debug3!("Temperature read returned 0.0 - no valid temperature found");
-                                    // Don't update cache - keep previous value if available
-                                }
-                            }
-                        } else {
-                            // Skip temperature reading entirely - too soon since last read
-                            debug3!("Skipping temperature read (too soon since last read, all_data() is expensive)");
-                            // Don't call all_data() at all - just skip
-                        }
-
-                        // STEP 3: Read CPU frequency from IOReport (real-time, dynamic)
-                        // This is the same approach exelban/stats uses - efficient native API
-                        // CPU EFFICIENCY: Only read frequency every 30 seconds (IOReport sampling still has overhead)
-                        // Threshold 30s to save CPU - frequency doesn't change that rapidly
-                        let should_read_freq = if let Ok(mut last) = LAST_FREQ_READ.lock() {
-                            debug3!("========> LAST_FREQ_READ: {:?}", last);
-                            let should = last.as_ref()
-                                .map(|t| t.elapsed().as_secs() >= 30)
-                                .unwrap_or(true);
-                            if should {
-                                *last = Some(std::time::Instant::now());
-                            }
-                            should
-                        } else {
-                            false
-                        };
-
-                        if should_read_freq {
-                            debug3!("should_read_freq=true, attempting IOReport frequency read");
-
-                            // Check if frequency logging is enabled
-                            let freq_logging = state::FREQUENCY_LOGGING_ENABLED.lock()
-                                .map(|f| *f)
-                                .unwrap_or(false);
-
-                            let mut freq: f32 = 0.0;
-                            let mut p_core_freq: f32 = 0.0;
-                            let mut e_core_freq: f32 = 0.0;
-
-                            // Try IOReport first (real-time frequency via native API)
-                            let freq_result = if let Ok(sub) = IOREPORT_SUBSCRIPTION.try_lock() {
-                                if let Some(subscription_usize) = sub.as_ref() {
-                                    let subscription_ptr = *subscription_usize as *mut c_void;
-
-                                    if subscription_ptr.is_null() {
-                                        debug3!("Subscription pointer is null, cannot create sample");
-                                        None
-                                    } else {
-                                        // Get channels dictionary for sampling
-                                        let channels_ptr = if let Ok(channels_storage) = IOREPORT_CHANNELS.try_lock() {
-                                            channels_storage.as_ref().map(|&usize_ptr| usize_ptr as CFMutableDictionaryRef)
-                                        } else {
-                                            None
-                                        };
-
-                                        let channels_ref = channels_ptr.unwrap_or(std::ptr::null_mut());
-                                        if channels_ref.is_null() {
-                                            debug3!("Using NULL channels for IOReportCreateSamples (may fail)");
-                                        } else {
-                                            debug3!("Using stored channels dictionary for IOReportCreateSamples");
-                                        }
-
-                                        // Get original channels dictionary
-                                        let original_channels_dict = if let Ok(orig_channels_storage) = IOREPORT_ORIGINAL_CHANNELS.try_lock() {
-                                            orig_channels_storage.as_ref().map(|&dict_usize| dict_usize as CFDictionaryRef)
-                                        } else {
-                                            None
-                                        };
-
-                                        // Get last sample for delta calculation
-                                        let last_sample = if let Ok(last_sample_storage) = LAST_IOREPORT_SAMPLE.try_lock() {
-                                            last_sample_storage.as_ref().map(|&(sample_usize, _)| sample_usize as CFDictionaryRef)
-                                        } else {
-                                            None
-                                        };
-
-                                        // Use the extracted frequency reading function
-                                        unsafe {
-                                            use ffi::ioreport::read_frequencies_from_ioreport;
-
-                                            let (result, current_sample_opt) = read_frequencies_from_ioreport(
-                                                subscription_ptr as *const c_void,
-                                                channels_ref,
-                                                original_channels_dict,
-                                                last_sample,
-                                                freq_logging,
-                                            );
-
-                                            // Store current sample for next delta calculation
-                                            if let Some(current_sample) = current_sample_opt {
-                                                // Retain the sample before storing (Core Foundation ownership rule)
-                                                let retained_sample = CFRetain(current_sample as CFTypeRef) as CFDictionaryRef;
-                                                if let Ok(mut last_sample_storage) = LAST_IOREPORT_SAMPLE.try_lock() {
-                                                    // Release old sample if it exists
-                                                    if let Some((old_sample_usize, _)) = last_sample_storage.take() {
-                                                        let old_sample = old_sample_usize as CFDictionaryRef;
-                                                        if !old_sample.is_null() {
-                                                            CFRelease(old_sample as CFTypeRef);
-                                                        }
-                                                    }
-                                                    // Store retained sample
-                                                    *last_sample_storage = Some((retained_sample as usize, std::time::Instant::now()));
-                                                } else {
-                                                    // Lock failed, release the retained sample
-                                                    CFRelease(retained_sample as CFTypeRef);
-                                                }
-                                                // Release the original sample (we've retained a copy)
-                                                CFRelease(current_sample as CFTypeRef);
-                                            }
-
-                                            Some(result)
-                                        }
-                                    }
-                                } else {
-                                    debug3!("IOReport subscription not available");
-                                    None
-                                }
-                            } else {
-                                debug3!("IOReport subscription lock failed");
-                                None
-                            };
-
-                            // Update frequency values from result
-                            if let Some(freq_result) = freq_result {
-                                freq = freq_result.overall;
-                                p_core_freq = freq_result.p_core;
-                                e_core_freq = freq_result.e_core;
-                            }
-
-                            // CRITICAL: Only use nominal frequency as fallback if IOReport completely failed
-                            // If IOReport returned 0.0, it means parsing failed - don't overwrite cache with nominal
-                            // Only update cache if we got a real frequency from IOReport
-                            let freq_logging = state::FREQUENCY_LOGGING_ENABLED.lock()
-                                .map(|f| *f)
-                                .unwrap_or(false);
-
-                            if freq > 0.0 {
-                                if let Ok(mut cache) = FREQ_CACHE.try_lock() {
-                                    *cache = Some((freq, std::time::Instant::now()));
-                                    if freq_logging {
-                                        debug3!("Overall frequency cache updated: {:.2} GHz", freq);
-                                    } else {
-                                        debug3!("Frequency cache updated from IOReport: {:.2} GHz", freq);
-                                    }
-                                }
-
-                                // Update P-core frequency cache
-                                if p_core_freq > 0.0 {
-                                    if let Ok(mut cache) = P_CORE_FREQ_CACHE.try_lock() {
-                                        *cache = Some((p_core_freq, std::time::Instant::now()));
-                                        if freq_logging {
-                                            debug3!("P-core frequency cache updated: {:.2} GHz", p_core_freq);
-                                        } else {
-                                            debug3!("P-core frequency cache updated: {:.2} GHz", p_core_freq);
-                                        }
-                                    }
-                                } else if freq_logging {
-                                    debug3!("P-core frequency is 0.0 - NOT updating cache");
-                                }
-
-                                // Update E-core frequency cache
-                                if e_core_freq > 0.0 {
-                                    if let Ok(mut cache) = E_CORE_FREQ_CACHE.try_lock() {
-                                        *cache = Some((e_core_freq, std::time::Instant::now()));
-                                        if freq_logging {
-                                            debug3!("E-core frequency cache updated: {:.2} GHz", e_core_freq);
-                                        } else {
-                                            debug3!("E-core frequency cache updated: {:.2} GHz", e_core_freq);
-                                        }
-                                    }
-                                } else if freq_logging {
-                                    debug3!("E-core frequency is 0.0 - NOT updating cache");
-                                }
-
-                                // OPTIMIZATION Phase 3: Update OnceLock to indicate frequency reading works
-                                if CAN_READ_FREQUENCY.set(true).is_ok() {
-                                    debug3!("CAN_READ_FREQUENCY set to true (IOReport frequency read successfully)");
-                                }
-                            } else {
-                                // This prevents overwriting a good cached value with nominal frequency
-                                debug3!("IOReport frequency parsing failed (freq=0.0) - keeping existing cache value if available");
-
-                                // Only initialize cache with nominal frequency if it's completely empty
-                                if let Ok(mut cache) = FREQ_CACHE.try_lock() {
-                                    if cache.is_none() {
-                                        let nominal = metrics::get_nominal_frequency();
-                                        *cache = Some((nominal, std::time::Instant::now()));
-                                        debug3!("Using nominal frequency as initial value: {:.2} GHz (IOReport not available yet)", nominal);
-                                    } else {
-                                        debug3!("Keeping existing cached frequency value (IOReport parsing failed)");
-                                    }
-                                }
-                            }
-                        } else {
-                            debug3!("should_read_freq=false, skipping frequency update");
-                        }
-
-                        // CRITICAL: Only read battery and power when CPU window is visible
-                        // This ensures menu bar (which only shows CPU/RAM/Disk) remains super lightweight
-                        // Battery reading via IOKit is lightweight, but we still only read when window is visible
-                        // Battery state can change (charging/discharging), so we read frequently when visible
-                        let (battery_level, is_charging, has_battery) = metrics::get_battery_info();
-                        let power_logging = state::POWER_USAGE_LOGGING_ENABLED.lock()
-                            .map(|f| *f)
-                            .unwrap_or(false);
-                        if power_logging && has_battery {
-                            debug3!("Battery updated: {:.1}%, charging={}", battery_level, is_charging);
-                        }
-
-                        // Read power consumption from IOReport
-                        // Power reading is expensive (IOReport), so we read it every 5 seconds
-                        // CRITICAL: Update LAST_POWER_READ_TIME AFTER we successfully read and store the sample
-                        // This ensures we always have a last_sample for delta calculation
-                        let should_read_power = if let Ok(last) = LAST_POWER_READ_TIME.lock() {
-                            last.as_ref()
-                                .map(|t| t.elapsed().as_secs() >= 5)
-                                .unwrap_or(true)
-                        } else {
-                            false
-                        };
-
-                        if should_read_power {
-                            debug3!("Reading power from IOReport (should_read_power=true)...");
-                            // Read power from IOReport
-                            let power_result = if let Ok(power_sub) = IOREPORT_POWER_SUBSCRIPTION.try_lock() {
-                                debug3!("Power subscription lock acquired");
-                                if let Some(subscription_usize) = power_sub.as_ref() {
-                                    let subscription_ptr = *subscription_usize as *mut c_void;
-                                    debug3!("Power subscription found: {:p}", subscription_ptr);
-
-                                    if !subscription_ptr.is_null() {
-                                        debug3!("Subscription pointer is valid, proceeding with power read...");
-                                        let channels_ptr = if let Ok(channels_storage) = IOREPORT_POWER_CHANNELS.try_lock() {
-                                            channels_storage.as_ref().map(|&usize_ptr| usize_ptr as CFMutableDictionaryRef)
-                                        } else {
-                                            None
-                                        };
-
-                                        let channels_ref = channels_ptr.unwrap_or(std::ptr::null_mut());
-
-                                        let original_channels_dict = if let Ok(orig_storage) = IOREPORT_POWER_ORIGINAL_CHANNELS.try_lock() {
-                                            orig_storage.as_ref().map(|&dict_usize| dict_usize as CFDictionaryRef)
-                                        } else {
-                                            None
-                                        };
-
-                                        debug3!("Power reading: original_channels_dict.is_some()={}, channels_ref.is_null()={}",
-                                            original_channels_dict.is_some(), channels_ref.is_null());
-
-                                        let last_sample = if let Ok(last_sample_storage) = LAST_IOREPORT_POWER_SAMPLE.try_lock() {
-                                            last_sample_storage.as_ref().map(|&(sample_usize, _)| sample_usize as CFDictionaryRef)
-                                        } else {
-                                            None
-                                        };
-
-                                        let last_read_time = LAST_POWER_READ_TIME.lock()
-                                            .ok()
-                                            .and_then(|t| *t);
-
-                                        unsafe {
-                                            use ffi::ioreport::read_power_from_ioreport;
-
-                                            debug3!("Calling read_power_from_ioreport...");
-                                            let (result, current_sample_opt) = read_power_from_ioreport(
-                                                subscription_ptr as *const c_void,
-                                                channels_ref,
-                                                original_channels_dict,
-                                                last_sample,
-                                                last_read_time,
-                                                power_logging,
-                                            );
-                                            debug3!("read_power_from_ioreport returned: CPU={:.2}W, GPU={:.2}W", result.cpu_power, result.gpu_power);
-
-                                            // Store current sample for next delta calculation
-                                            // CRITICAL: Always store the sample, even if time_delta was 0
-                                            // This ensures we have a sample for the next read
-                                            if let Some(current_sample) = current_sample_opt {
-                                                let retained_sample = CFRetain(current_sample as CFTypeRef) as CFDictionaryRef;
-                                                if let Ok(mut last_sample_storage) = LAST_IOREPORT_POWER_SAMPLE.try_lock() {
-                                                    if let Some((old_sample_usize, _)) = last_sample_storage.take() {
-                                                        let old_sample = old_sample_usize as CFDictionaryRef;
-                                                        if !old_sample.is_null() {
-                                                            CFRelease(old_sample as CFTypeRef);
-                                                        }
-                                                    }
-                                                    *last_sample_storage = Some((retained_sample as usize, std::time::Instant::now()));
-                                                } else {
-                                                    CFRelease(retained_sample as CFTypeRef);
-                                                }
-                                                CFRelease(current_sample as CFTypeRef);
-
-                                                // Update LAST_POWER_READ_TIME AFTER storing the sample
-                                                // This ensures next read will have a valid last_sample and last_read_time
-                                                if let Ok(mut last_read_time) = LAST_POWER_READ_TIME.lock() {
-                                                    *last_read_time = Some(std::time::Instant::now());
-                                                }
-                                            }
-
-                                            Some(result)
-                                        }
-                                    } else {
-                                        None
-                                    }
-                                } else {
-                                    debug3!("Power subscription is None");
-                                    None
-                                }
-                            } else {
-                                debug3!("Power subscription lock failed");
-                                None
-                            };
-
-                            if let Some(power_data) = power_result {
-                                // Update cache - CRITICAL: Only update if we have at least one valid value > 0.0
-                                // This prevents setting cache to (0.0, 0.0) on first read when time_delta=0
-                                if power_data.cpu_power > 0.0 || power_data.gpu_power > 0.0 {
-                                    if let Ok(mut cache) = POWER_CACHE.try_lock() {
-                                        let (prev_cpu, prev_gpu, _) = cache.as_ref()
-                                            .map(|(c, g, t)| (*c, *g, *t))
-                                            .unwrap_or((0.0, 0.0, std::time::Instant::now()));
-
-                                        // Only update values that are > 0.0
-                                        // If a value is 0.0, keep the previous value to prevent flickering
-                                        let new_cpu = if power_data.cpu_power > 0.0 {
-                                            power_data.cpu_power
-                                        } else {
-                                            prev_cpu  // Keep previous value if new is 0.0
-                                        };
-
-                                        let new_gpu = if power_data.gpu_power > 0.0 {
-                                            power_data.gpu_power
-                                        } else {
-                                            prev_gpu  // Keep previous value if new is 0.0
-                                        };
-
-                                        *cache = Some((new_cpu, new_gpu, std::time::Instant::now()));
-
-                                        // CRITICAL: Also update LAST_SUCCESSFUL_POWER for fallback when lock fails
-                                        if let Ok(mut last_successful) = crate::state::LAST_SUCCESSFUL_POWER.try_lock() {
-                                            *last_successful = Some((new_cpu, new_gpu));
-                                        }
-
-                                        debug3!("Power cache updated: CPU={:.2}W, GPU={:.2}W (prev: CPU={:.2}W, GPU={:.2}W, new_cpu={:.2}W, new_gpu={:.2}W)",
-                                            new_cpu, new_gpu, prev_cpu, prev_gpu, power_data.cpu_power, power_data.gpu_power);
-                                    }
-                                } else {
-                                    // Both values are 0.0 - don't update cache to prevent overwriting good values
-                                    // This happens on first read when time_delta=0
-                                    debug3!("Power read returned 0.0W for both (time_delta likely 0) - not updating cache to preserve previous values");
-                                }
-                            } else {
-                                debug3!("Power reading returned None - subscription may not be available");
-                            }
-                        }
-
-                        // Get cached power values for logging
-                        let (cpu_power, gpu_power) = POWER_CACHE.try_lock()
-                            .ok()
-                            .and_then(|c| c.as_ref().map(|(cpu, gpu, _)| (*cpu, *gpu)))
-                            .unwrap_or((0.0, 0.0));
-
-                        if power_logging && (cpu_power > 0.0 || gpu_power > 0.0) {
-                            debug3!("Power: CPU={:.2}W, GPU={:.2}W", cpu_power, gpu_power);
-                        }
-                    } else {
-                        // CPU window is not visible - DO NOT read battery or power to save CPU
-                        // Menu bar only needs CPU/RAM/Disk which are already lightweight
-                        debug3!("CPU window closed - skipping battery and power reads to save CPU");
-                        // CPU window is not visible - clear SMC connection and IOReport subscription to save resources
-                        if smc_connection.is_some() {
-                            smc_connection = None;
-                            debug3!("CPU window closed, SMC connection released");
-                        }
-
-                        // CRITICAL: Clear IOReport subscriptions when window closes to save CPU
-                        // Note: IOReport doesn't have an explicit destroy function in the API
-                        // The subscription will be cleaned up when the process exits
-                        // For now, just clear the reference
-                        if let Ok(mut sub) = IOREPORT_SUBSCRIPTION.try_lock() {
-                            if sub.is_some() {
-                                *sub = None;
-                                debug3!("CPU window closed, IOReport frequency subscription cleared");
-
-                                // Clear channels dictionary
-                                if let Ok(mut channels_storage) = IOREPORT_CHANNELS.try_lock() {
-                                    if let Some(ptr) = *channels_storage {
-                                        unsafe {
-                                            CFRelease(ptr as CFTypeRef);
-                                        }
-                                    }
-                                    *channels_storage = None;
-                                }
-
-                                // Clear last sample
-                                if let Ok(mut last_sample) = LAST_IOREPORT_SAMPLE.try_lock() {
-                                    *last_sample = None;
-                                }
-                            }
-                        }
-
-                        // Clear power subscription
-                        if let Ok(mut power_sub) = IOREPORT_POWER_SUBSCRIPTION.try_lock() {
-                            if power_sub.is_some() {
-                                *power_sub = None;
-                                debug3!("CPU window closed, IOReport power subscription cleared");
-
-                                // Clear power channels dictionary
-                                if let Ok(mut channels_storage) = IOREPORT_POWER_CHANNELS.try_lock() {
-                                    if let Some(ptr) = *channels_storage {
-                                        unsafe {
-                                            CFRelease(ptr as CFTypeRef);
-                                        }
-                                    }
-                                    *channels_storage = None;
-                                }
-
-                                // Clear last power sample
-                                if let Ok(mut last_sample) = LAST_IOREPORT_POWER_SAMPLE.try_lock() {
-                                    if let Some((sample_usize, _)) = last_sample.take() {
-                                        let sample = sample_usize as CFDictionaryRef;
-                                        if !sample.is_null() {
-                                            unsafe {
-                                                CFRelease(sample as CFTypeRef);
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-
-                    // Populate metrics history buffer with current data
-                    // Update final_history_point with CPU details if available from caches
-                    if let Ok(cache) = TEMP_CACHE.try_lock() {
-                        if let Some((temp, _)) = cache.as_ref() {
-                            final_history_point.temperature = *temp;
-                        }
-                    }
-                    if let Ok(cache) = FREQ_CACHE.try_lock() {
-                        if let Some((freq, _)) = cache.as_ref() {
-                            final_history_point.frequency = *freq;
-                        }
-                    }
-                    if let Ok(cache) = P_CORE_FREQ_CACHE.try_lock() {
-                        if let Some((p_freq, _)) = cache.as_ref() {
-                            final_history_point.p_core_frequency = *p_freq;
-                        }
-                    }
-                    if let Ok(cache) = E_CORE_FREQ_CACHE.try_lock() {
-                        if let Some((e_freq, _)) = cache.as_ref() {
-                            final_history_point.e_core_frequency = *e_freq;
-                        }
-                    }
-                    if let Ok(cache) = POWER_CACHE.try_lock() {
-                        if let Some((cpu_power, gpu_power, _)) = cache.as_ref() {
-                            final_history_point.cpu_power = *cpu_power;
-                            final_history_point.gpu_power = *gpu_power;
-                        }
-                    }
-                    if let Ok(cache) = BATTERY_CACHE.try_lock() {
-                        if let Some((battery_level, _, _)) = cache.as_ref() {
-                            final_history_point.battery_level = *battery_level;
-                        }
-                    }
-
-                    // Push to history buffer
-                    if let Ok(mut history_opt) = METRICS_HISTORY.try_lock() {
-                        if let Some(history) = history_opt.as_mut() {
-                            history.push(final_history_point.clone());
-                            debug3!("Added history point: CPU={}%, GPU={}%, RAM={}%, DISK={}%, Temp={}°C, Freq={}GHz",
-                                final_history_point.cpu,
-                                final_history_point.gpu,
-                                final_history_point.ram,
-                                final_history_point.disk,
-                                final_history_point.temperature,
-                                final_history_point.frequency);
-                        }
-                    } else {
-                        debug3!("Could not lock history buffer for update (lock contention)");
-                    }
-
-                    // NOTE: Automatic menu bar updates are not implemented because:
-                    // - run_on_main_thread callbacks don't execute (Tauri limitation)
-                    // - performSelector doesn't fire reliably
-                    // Menu bar will update when user clicks on it (click handler works)
-                    // Updates are stored in MENU_BAR_TEXT and processed on click
+            // Which sensors this Mac has (SMC, IOReport, powermetrics, battery); logged once.
+            std::thread::spawn(capabilities::probe_and_log);
+            // Type errors, out-of-range values and unknown keys in config.json; logged once.
+            config::schema::log_issues();
+            // Apply config.json edits without a restart (changed keys are logged).
+            config::watch::spawn_config_watcher();
+            // Notifications, launch at login, menu bar metrics and sensors on the first launch.
+            ui::onboarding_window::show_if_first_launch(app.handle());
+            // Privileged `powermetrics` stream for power and frequencies (`powermetrics`).
+            metrics::powermetrics::spawn_if_enabled();
+
+            // Hidden CPU window a few seconds after launch (`windowPrewarm`).
+            startup::run_at(
+                startup::Stage::Background,
+                ui::status_bar::prewarm_cpu_window_if_enabled,
+            );
 
-                    // Update menu bar every 2 seconds to reduce CPU usage
-                    std::thread::sleep(std::time::Duration::from_secs(2));
-                }
-            });
+            // Sampling tasks: System / Disks, SMC, IOReport and history (steered with
+            // `sampler::Command`). It stores titles in MENU_BAR_TEXT; the status item's
+            // main-thread timer (`status_bar::start_update_timer`) applies them
+            sampler::spawn();
             Ok(())
         })
         .build(tauri::generate_context!())
         .expect("error while building tauri application")
-        .run(|_app_handle, event| {
+        .run(|app_handle, event| {
+            if matches!(event, tauri::RunEvent::ExitRequested { .. }) {
+                // Quit with the CPU window or widget open: keep their frames like closing would
+                for label in ["cpu", ui::widget_window::LABEL] {
+                    if let Some(window) = app_handle.get_webview_window(label) {
+                        if window.is_visible().unwrap_or(false) {
+                            ui::window_frame::save(&window);
+                        }
+                    }
+                }
+            }
+            // Opened again (Finder, Spotlight, `mac_stats menu-bar restore`): bring back status
+            // items the user ⌘-dragged out of the menu bar
+            if matches!(event, tauri::RunEvent::Reopen { .. }) {
+                if let Some(mtm) = MainThreadMarker::new() {
+                    ui::status_removal::restore(mtm);
+                }
+            }
             if matches!(event, tauri::RunEvent::Exit) {
-                tracing::info!(
-                    target: "mac_stats::browser_shutdown",
-                    "Tauri RunEvent::Exit: closing browser session"
-                );
-                crate::logging::sync_debug_log_best_effort();
-                crate::browser_agent::close_browser_session();
-                crate::logging::sync_debug_log_best_effort();
+                shutdown();
             }
         });
+}
 
-    // Log off from Discord on app shutdown so the user appears offline.
+/// Tear down on `RunEvent::Exit`; the event loop never returns on macOS, so this is the last
+/// code that runs
+fn shutdown() {
+    tracing::info!(
+        target: "mac_stats::browser_shutdown",
+        "Tauri RunEvent::Exit: closing browser session"
+    );
+    crate::logging::sync_debug_log_best_effort();
+    crate::browser_agent::close_browser_session();
+    metrics::sessions::save_on_exit();
+    // Stop the collectors first, so the history and sensor locks are free below
+    if !background::shutdown() {
+        tracing::warn!("Collectors still busy at exit; SMC / IOReport left to the OS");
+    }
+    sampler::shutdown();
+    metrics::history_wal::checkpoint_on_exit();
+    metrics::warm_start::save_on_exit();
+    metrics::fan_noise::save_on_exit();
+    metrics::fan_control::restore_all();
+    metrics::powermetrics::stop();
+    // Log off from Discord so the user appears offline.
     discord::disconnect_discord();
+    crate::logging::otel::shutdown();
+    crate::logging::sync_debug_log_best_effort();
 }
@@ -0,0 +1,192 @@
+//! Parsed `debug.log` lines for the log viewer window
+//!
+//! The log mixes two formats: tracing lines (`2026-10-17T12:00:00.123Z  INFO mac_stats::metrics:
+//! message`) and the legacy structured JSON lines of `write_structured_log` (`{"location",
+//! "message", "data", "timestamp" (ms), "hypothesisId"}`). Both become a [`LogEntry`] with a
+//! level, a module (tracing target or JSON `location`) and, for JSON lines, a tag (the
+//! hypothesis id). Lines that match neither (continuations of multi-line messages) are kept as
+//! plain messages so nothing disappears from the viewer.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LogEntry {
+    /// As written (RFC 3339 for tracing lines)
+    pub timestamp: String,
+    /// `ERROR`, `WARN`, `INFO`, `DEBUG` or `TRACE`; empty for continuation lines
+    pub level: String,
+    pub module: String,
+    pub tag: String,
+    pub message: String,
+    /// JSON lines' `data`, when not empty
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+const LEVELS: [&str; 5] = ["ERROR", "WARN", "INFO", "DEBUG", "TRACE"];
+
+fn level_rank(level: &str) -> Option<usize> {
+    LEVELS.iter().position(|l| l.eq_ignore_ascii_case(level))
+}
+
+fn parse_json(line: &str) -> Option<LogEntry> {
+    let json: serde_json::Value = serde_json::from_str(line).ok()?;
+    let message = json.get("message")?.as_str()?.to_string();
+    let timestamp = json
+        .get("timestamp")
+        .and_then(|t| t.as_i64())
+        .and_then(chrono::DateTime::from_timestamp_millis)
+        .map(|t| t.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+        .unwrap_or_default();
+    let text = |key: &str| {
+        json.get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let data = json
+        .get("data")
+        .filter(|d| !d.is_null() && d.as_object().is_none_or(|o| !o.is_empty()))
+        .cloned();
+    Some(LogEntry {
+        timestamp,
+        level: "DEBUG".to_string(),
+        module: text("location"),
+        tag: text("hypothesisId"),
+        message,
+        data,
+    })
+}
+
+fn parse_tracing(line: &str) -> Option<LogEntry> {
+    let (timestamp, rest) = line.split_once(' ')?;
+    if !timestamp.starts_with(|c: char| c.is_ascii_digit()) || !timestamp.contains('T') {
+        return None;
+    }
+    let rest = rest.trim_start();
+    let (level, rest) = rest.split_once(' ')?;
+    level_rank(level)?;
+    let rest = rest.trim_start();
+    // `target: message`; targets have no spaces (`mac_stats::metrics`, `discord/draft`)
+    let (module, message) = match rest.split_once(": ") {
+        Some((target, message)) if !target.contains(' ') => (target, message),
+        _ => ("", rest),
+    };
+    Some(LogEntry {
+        timestamp: timestamp.to_string(),
+        level: level.to_string(),
+        module: module.to_string(),
+        tag: String::new(),
+        message: message.to_string(),
+        data: None,
+    })
+}
+
+/// One log line; None for blank lines
+pub fn parse_line(line: &str) -> Option<LogEntry> {
+    let line = line.trim_end();
+    if line.trim().is_empty() {
+        return None;
+    }
+    if line.starts_with('{') {
+        if let Some(entry) = parse_json(line) {
+            return Some(entry);
+        }
+    }
+    Some(parse_tracing(line).unwrap_or_else(|| LogEntry {
+        timestamp: String::new(),
+        level: String::new(),
+        module: String::new(),
+        tag: String::new(),
+        message: line.to_string(),
+        data: None,
+    }))
+}
+
+/// Viewer filters; empty fields match everything
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct LogFilter {
+    /// Least severe level shown, e.g. `"WARN"` shows warnings and errors
+    pub level: Option<String>,
+    /// Substring of the module / target
+    pub module: Option<String>,
+    /// Exact tag (JSON lines' hypothesis id)
+    pub tag: Option<String>,
+    /// Case-insensitive substring of the message
+    pub text: Option<String>,
+}
+
+impl LogFilter {
+    pub fn matches(&self, entry: &LogEntry) -> bool {
+        let set = |v: &Option<String>| v.as_deref().map(str::trim).filter(|s| !s.is_empty());
+        if let Some(max) = set(&self.level).and_then(level_rank) {
+            // Continuation lines have no level; show them only when nothing is hidden
+            if level_rank(&entry.level).is_none_or(|rank| rank > max) {
+                return false;
+            }
+        }
+        if let Some(module) = set(&self.module) {
+            if !entry.module.to_lowercase().contains(&module.to_lowercase()) {
+                return false;
+            }
+        }
+        if let Some(tag) = set(&self.tag) {
+            if entry.tag != tag {
+                return false;
+            }
+        }
+        if let Some(text) = set(&self.text) {
+            if !entry.message.to_lowercase().contains(&text.to_lowercase()) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_both_formats_and_filters() {
+        let tracing_line = parse_line(
+            "2026-10-17T12:00:00.123456Z  WARN mac_stats::metrics::smc: SMC read failed: timeout",
+        )
+        .unwrap();
+        assert_eq!(tracing_line.level, "WARN");
+        assert_eq!(tracing_line.module, "mac_stats::metrics::smc");
+        assert_eq!(tracing_line.message, "SMC read failed: timeout");
+
+        let json_line = parse_line(
+            r#"{"location":"ui/status_bar.rs","message":"Click handler FUNCTION CALLED","data":{},"timestamp":1760702400000,"hypothesisId":"J"}"#,
+        )
+        .unwrap();
+        assert_eq!(json_line.module, "ui/status_bar.rs");
+        assert_eq!(json_line.tag, "J");
+        assert_eq!(json_line.timestamp, "2025-10-17T12:00:00.000Z");
+        assert_eq!(json_line.data, None);
+
+        let plain = parse_line("    at frame 3").unwrap();
+        assert_eq!(plain.level, "");
+        assert_eq!(parse_line("  "), None);
+
+        let warn_and_up = LogFilter {
+            level: Some("warn".to_string()),
+            ..Default::default()
+        };
+        assert!(warn_and_up.matches(&tracing_line));
+        assert!(!warn_and_up.matches(&json_line));
+        assert!(!warn_and_up.matches(&plain));
+
+        let status_bar = LogFilter {
+            module: Some("STATUS_BAR".to_string()),
+            tag: Some("J".to_string()),
+            text: Some("click".to_string()),
+            ..Default::default()
+        };
+        assert!(status_bar.matches(&json_line));
+        assert!(!status_bar.matches(&tracing_line));
+    }
+}
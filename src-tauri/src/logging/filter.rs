@@ -0,0 +1,189 @@
+//! Runtime-reloadable tracing filter
+//!
+//! The filter is the verbosity baseline (`-v` … `-vvv`) followed by per-module directives from
+//! `logFilter` / `MAC_STATS_LOG_FILTER` or the `set_log_filter` command, so one noisy subsystem
+//! can be turned up (or down) without changing the global level. Directives use `EnvFilter`
+//! syntax; short mac-stats module names are expanded, so `discord=debug,metrics=warn` becomes
+//! `discord=debug,mac_stats::discord=debug,metrics=warn,mac_stats::metrics=warn` (the bare form
+//! keeps custom targets such as `discord/draft` covered). Other names (`serenity=warn`,
+//! `mac_stats::browser_agent::cdp=trace`) pass through unchanged.
+//!
+//! Only `tracing` events are affected; the legacy `debug1!`…`debug3!` macros still follow the
+//! global verbosity.
+
+use std::sync::{Mutex, OnceLock};
+use tracing_subscriber::{reload, EnvFilter, Registry};
+
+/// Top-level modules of the crate (`mod …` in lib.rs) that short names expand to.
+const MODULES: &[&str] = &[
+    "agents",
+    "alerts",
+    "api",
+    "browser_agent",
+    "browser_doctor",
+    "circuit_breaker",
+    "collector",
+    "commands",
+    "config",
+    "discord",
+    "downloads_organizer",
+    "events",
+    "feature_health",
+    "logging",
+    "mcp",
+    "metrics",
+    "monitors",
+    "mqtt",
+    "ollama",
+    "ollama_queue",
+    "perplexity",
+    "plugins",
+    "redmine",
+    "scheduler",
+    "security",
+    "session_memory",
+    "skills",
+    "startup",
+    "task",
+    "ui",
+    "updates",
+];
+
+/// Baseline directives for a verbosity level (0 = error … 3 = trace).
+///
+/// -v (1): warn + discord/draft=info (draft placeholder/edits visible in debug.log for reviewers).
+/// -vv (2): info + mac_stats=debug + ollama/untrusted=debug (untrusted wrap trace; no HTTP noise).
+/// `serenity=error` at -v/-vv: library heartbeat / shard-shutdown WARNs are expected during Discord
+/// reconnects; our `Discord: gateway disconnect` / Ready lines already cover operator telemetry.
+/// `ollama/untrusted` and `discord/draft` are custom targets (not under mac_stats::), hence explicit.
+pub fn base_directives(verbosity: u8) -> &'static str {
+    match verbosity {
+        0 => "error",
+        1 => "warn,discord/draft=info,serenity=error",
+        2 => "info,mac_stats=debug,ollama/untrusted=debug,discord/draft=info,serenity=error",
+        _ => "trace",
+    }
+}
+
+/// Expand short module names and validate; returns the normalized directive list
+/// (empty input → empty string).
+pub fn expand_directives(spec: &str) -> Result<String, String> {
+    let mut out: Vec<String> = Vec::new();
+    for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+        let (target, level) = match directive.split_once('=') {
+            Some((target, level)) => (target.trim(), Some(level.trim())),
+            None => (directive, None),
+        };
+        let is_level = level.is_none()
+            && ["off", "error", "warn", "info", "debug", "trace"]
+                .contains(&target.to_ascii_lowercase().as_str());
+        if is_level || !MODULES.contains(&target) {
+            out.push(directive.to_string());
+            continue;
+        }
+        let level = level.map(|l| format!("={l}")).unwrap_or_default();
+        out.push(format!("{target}{level}"));
+        out.push(format!("mac_stats::{target}{level}"));
+    }
+    let expanded = out.join(",");
+    EnvFilter::try_new(&expanded).map_err(|e| format!("Invalid log filter '{spec}': {e}"))?;
+    Ok(expanded)
+}
+
+/// Full `EnvFilter` string: baseline first, so per-module directives refine it.
+pub fn effective_directives(verbosity: u8, directives: &str) -> Result<String, String> {
+    let expanded = expand_directives(directives)?;
+    let base = base_directives(verbosity);
+    Ok(if expanded.is_empty() {
+        base.to_string()
+    } else {
+        format!("{base},{expanded}")
+    })
+}
+
+fn build(verbosity: u8, directives: &str) -> Result<EnvFilter, String> {
+    let full = effective_directives(verbosity, directives)?;
+    EnvFilter::try_new(&full).map_err(|e| format!("Invalid log filter '{directives}': {e}"))
+}
+
+static HANDLE: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+/// (verbosity, user directives) currently applied
+static CURRENT: Mutex<(u8, String)> = Mutex::new((2, String::new()));
+
+/// Filter layer for `init_tracing`. Invalid configured directives are reported on stderr and
+/// ignored so a typo never silences logging.
+pub(crate) fn layer(verbosity: u8, directives: &str) -> reload::Layer<EnvFilter, Registry> {
+    let (filter, applied) = match build(verbosity, directives) {
+        Ok(filter) => (filter, directives.to_string()),
+        Err(e) => {
+            eprintln!("mac-stats: {e} — using verbosity defaults");
+            (EnvFilter::new(base_directives(verbosity)), String::new())
+        }
+    };
+    if let Ok(mut current) = CURRENT.lock() {
+        *current = (verbosity, applied);
+    }
+    let (layer, handle) = reload::Layer::new(filter);
+    let _ = HANDLE.set(handle);
+    layer
+}
+
+fn apply(verbosity: u8, directives: &str) -> Result<(), String> {
+    let filter = build(verbosity, directives)?;
+    let handle = HANDLE
+        .get()
+        .ok_or_else(|| "Tracing is not initialized".to_string())?;
+    handle
+        .reload(filter)
+        .map_err(|e| format!("Could not reload log filter: {e}"))?;
+    if let Ok(mut current) = CURRENT.lock() {
+        *current = (verbosity, directives.trim().to_string());
+    }
+    Ok(())
+}
+
+/// Replace the per-module directives, keeping the current verbosity baseline.
+pub fn set_directives(directives: &str) -> Result<(), String> {
+    let verbosity = current().0;
+    apply(verbosity, directives)?;
+    tracing::info!(target: "mac_stats::logging", "Log filter set to '{}'", directives.trim());
+    Ok(())
+}
+
+/// Change the verbosity baseline, keeping the per-module directives.
+pub fn set_verbosity(verbosity: u8) -> Result<(), String> {
+    let directives = current().1;
+    apply(verbosity.min(3), &directives)
+}
+
+/// (verbosity, user directives) currently applied
+pub fn current() -> (u8, String) {
+    CURRENT
+        .lock()
+        .map(|c| c.clone())
+        .unwrap_or((2, String::new()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_module_names_and_rejects_garbage() {
+        assert_eq!(
+            expand_directives(" discord=debug , metrics=warn").unwrap(),
+            "discord=debug,mac_stats::discord=debug,metrics=warn,mac_stats::metrics=warn"
+        );
+        assert_eq!(
+            expand_directives(
+                "info,serenity=warn,mac_stats::browser_agent=trace,ollama/untrusted=off"
+            )
+            .unwrap(),
+            "info,serenity=warn,mac_stats::browser_agent=trace,ollama/untrusted=off"
+        );
+        assert_eq!(expand_directives("ui").unwrap(), "ui,mac_stats::ui");
+        assert_eq!(expand_directives("").unwrap(), "");
+        assert!(expand_directives("discord=loud").is_err());
+        assert!(build(1, "metrics=trace").is_ok());
+    }
+}
@@ -11,8 +11,12 @@ use tracing_subscriber::filter::FilterFn;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::Layer;
-use tracing_subscriber::{fmt, EnvFilter};
+use tracing_subscriber::fmt;
 
+pub mod entries;
+pub mod filter;
+pub mod otel;
+pub mod perf;
 pub mod redact;
 pub mod subsystem;
 
@@ -181,30 +185,11 @@ pub fn init_tracing(verbosity: u8, log_file_path: Option<PathBuf>) {
     redact::init_from_env();
     let redact_logs = redact::redaction_active();
 
-    // Convert verbosity level (0-3) to tracing level.
-    // -v (1): warn + discord/draft=info (draft placeholder/edits visible in debug.log for reviewers).
-    // -vv (2): info + mac_stats=debug + ollama/untrusted=debug (untrusted wrap trace; no HTTP noise). -vvv (3): full trace.
-    // `serenity=error` at -v/-vv: library heartbeat / shard-shutdown WARNs are expected during Discord
-    // reconnects; our `Discord: gateway disconnect` / Ready lines already cover operator telemetry.
-    let filter = match verbosity {
-        0 => EnvFilter::new("error"),
-        1 => EnvFilter::try_new("warn,discord/draft=info,serenity=error")
-            .unwrap_or_else(|_| EnvFilter::new("warn")),
-        2 => EnvFilter::try_new(
-            "info,mac_stats=debug,ollama/untrusted=debug,discord/draft=info,serenity=error",
-        )
-        .unwrap_or_else(|_| EnvFilter::new("debug")),
-        3 => EnvFilter::new("trace"),
-        _ => EnvFilter::new("trace"),
-    };
-
     // CRITICAL: Always use command-line verbosity, ignore RUST_LOG environment variable
     // This ensures that -v flags control logging, not environment variables.
-    // At -vv we enable mac_stats=debug but not reqwest/hyper, so monitor checks stay compact.
-    // `ollama/untrusted` and `discord/draft` are custom tracing targets (not under mac_stats::); include them explicitly so those lines appear in debug.log.
-
-    // Build subscriber with console and file output
-    let registry = tracing_subscriber::registry().with(filter);
+    // Per-module overrides come from `logFilter` / `MAC_STATS_LOG_FILTER` (see `filter`) and can be
+    // changed at runtime without a restart.
+    let filter = filter::layer(verbosity, &crate::config::Config::log_filter());
 
     // Console-only subsystem filter: when `MAC_STATS_LOG` is set, stderr shows only matching targets.
     let parsed_allow = subsystem::parse_subsystem_allowlist_from_env();
@@ -232,7 +217,7 @@ pub fn init_tracing(verbosity: u8, log_file_path: Option<PathBuf>) {
         .with_filter(console_subsystem_filter);
 
     // Add file layer if path is provided
-    if let Some(log_path) = log_file_path {
+    let file_layer = if let Some(log_path) = log_file_path {
         // Ensure directory exists
         if let Some(parent) = log_path.parent() {
             let _ = std::fs::create_dir_all(parent);
@@ -260,22 +245,32 @@ pub fn init_tracing(verbosity: u8, log_file_path: Option<PathBuf>) {
         if let Some(file) = file {
             let file_mk = redact::RedactingFileMakeWriter::new(file, redact_logs);
             let _ = DEBUG_LOG_FILE.set(file_mk.shared_file());
-            let file_layer = fmt::layer()
-                .with_writer(file_mk)
-                .with_target(false)
-                .with_thread_ids(false)
-                .with_thread_names(false)
-                .with_ansi(false); // No ANSI in files
-
-            registry.with(console_layer).with(file_layer).init();
+            // Targets stay in the file so the log viewer can filter by module
+            Some(
+                fmt::layer()
+                    .with_writer(file_mk)
+                    .with_target(true)
+                    .with_thread_ids(false)
+                    .with_thread_names(false)
+                    .with_ansi(false), // No ANSI in files
+            )
         } else {
             // Fallback to console only if file creation fails
-            registry.with(console_layer).init();
+            None
         }
     } else {
         // Console only
-        registry.with(console_layer).init();
-    }
+        None
+    };
+
+    // Console and file output share the reloadable filter; span timing and the OTLP exporter
+    // (when configured) have their own filters so they see the `mac_stats::perf` spans at any
+    // verbosity.
+    tracing_subscriber::registry()
+        .with(console_layer.and_then(file_layer).with_filter(filter))
+        .with(perf::layer())
+        .with(otel::layer())
+        .init();
 
     tracing::info!(
         target: "mac_stats::logging",
@@ -288,17 +283,13 @@ pub fn init_tracing(verbosity: u8, log_file_path: Option<PathBuf>) {
     );
 }
 
-/// Set verbosity level (compatibility function)
-///
-/// This function updates both the legacy VERBOSITY and tracing filter.
-/// Currently unused but kept for potential future use.
-#[allow(dead_code)]
+/// Set verbosity level for both the legacy macros and the tracing filter baseline
+/// (per-module directives are kept).
 pub fn set_verbosity_with_tracing(level: u8) {
-    // Update legacy verbosity for compatibility
     legacy::set_verbosity(level);
-
-    // Note: Tracing filter is set at init time, so we'd need to reload
-    // For now, this is mainly for compatibility during migration
+    if let Err(e) = filter::set_verbosity(level) {
+        tracing::warn!(target: "mac_stats::logging", "Could not update tracing verbosity: {}", e);
+    }
 }
 
 #[cfg(test)]
@@ -405,8 +396,9 @@ mod tests {
     /// Regression: `wrap_untrusted_content` uses target `ollama/untrusted`, which is not under `mac_stats::`.
     #[test]
     fn vv_env_filter_accepts_ollama_untrusted_directive() {
-        let s = "info,mac_stats=debug,ollama/untrusted=debug,discord/draft=info,serenity=error";
-        let _ = EnvFilter::try_new(s)
+        let s = filter::base_directives(2);
+        assert!(s.contains("ollama/untrusted=debug"));
+        let _ = tracing_subscriber::EnvFilter::try_new(s)
             .expect("vv filter must include ollama/untrusted for untrusted wrap logs");
     }
 }
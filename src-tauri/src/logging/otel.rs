@@ -0,0 +1,138 @@
+//! Optional OTLP export of internal performance spans
+//!
+//! Spans with target [`PERF_TARGET`] (one per sampling tick, IOReport reads, Tauri command
+//! handling) are sent to an OTLP/HTTP collector (`otlpEndpoint`, e.g. Jaeger or Tempo on
+//! `http://localhost:4318`) so regressions in the collector itself can be looked at on a
+//! timeline. The spans are TRACE level: they do not reach `debug.log` below `-vvv`, and the
+//! exporter has its own filter, so `-v` / `logFilter` do not change what is exported.
+//!
+//! Needs a build with `--features otel`; without it a configured endpoint only logs a warning.
+
+use tracing::Subscriber;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use super::perf::PERF_TARGET;
+
+/// `http://host:4318` → `http://host:4318/v1/traces`; full `/v1/traces` URLs are kept.
+pub fn traces_url(endpoint: &str) -> String {
+    let endpoint = endpoint.trim().trim_end_matches('/');
+    if endpoint.ends_with("/v1/traces") {
+        endpoint.to_string()
+    } else {
+        format!("{endpoint}/v1/traces")
+    }
+}
+
+#[cfg(feature = "otel")]
+static PROVIDER: std::sync::OnceLock<opentelemetry_sdk::trace::SdkTracerProvider> =
+    std::sync::OnceLock::new();
+
+/// Exporter layer for `init_tracing`; None when no endpoint is configured or it cannot be built.
+pub(crate) fn layer<S>() -> Option<Box<dyn Layer<S> + Send + Sync>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let endpoint = crate::config::Config::otlp_endpoint()?;
+    build_layer(&traces_url(&endpoint))
+}
+
+#[cfg(feature = "otel")]
+fn build_layer<S>(url: &str) -> Option<Box<dyn Layer<S> + Send + Sync>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_otlp::WithExportConfig;
+
+    let exporter = match opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .with_endpoint(url)
+        .with_timeout(std::time::Duration::from_secs(3))
+        .build()
+    {
+        Ok(exporter) => exporter,
+        Err(e) => {
+            eprintln!("mac-stats: OTLP export disabled ({url}): {e}");
+            return None;
+        }
+    };
+    let resource = opentelemetry_sdk::Resource::builder()
+        .with_service_name("mac-stats")
+        .with_attribute(opentelemetry::KeyValue::new(
+            "service.version",
+            env!("CARGO_PKG_VERSION"),
+        ))
+        .build();
+    let provider = opentelemetry_sdk::trace::SdkTracerProvider::builder()
+        .with_batch_exporter(exporter)
+        .with_resource(resource)
+        .build();
+    let tracer = provider.tracer("mac-stats");
+    let _ = PROVIDER.set(provider);
+    eprintln!("mac-stats: exporting performance spans to {url}");
+    let only_perf =
+        tracing_subscriber::filter::Targets::new().with_target(PERF_TARGET, tracing::Level::TRACE);
+    Some(
+        tracing_opentelemetry::layer()
+            .with_tracer(tracer)
+            .with_filter(only_perf)
+            .boxed(),
+    )
+}
+
+#[cfg(not(feature = "otel"))]
+fn build_layer<S>(url: &str) -> Option<Box<dyn Layer<S> + Send + Sync>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    eprintln!("mac-stats: otlpEndpoint {url} ignored — this build lacks the `otel` feature");
+    None
+}
+
+/// Flush pending spans on quit (no-op when export is off).
+pub fn shutdown() {
+    #[cfg(feature = "otel")]
+    {
+        if let Some(provider) = PROVIDER.get() {
+            let _ = provider.shutdown();
+        }
+    }
+}
+
+/// Wrap the Tauri invoke handler so each command runs inside a `command` span. Async commands
+/// are timed until their future is spawned, synchronous ones (most metric getters) end to end.
+pub fn timed_commands<R, F>(
+    handler: F,
+) -> impl Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync + 'static
+where
+    R: tauri::Runtime,
+    F: Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync + 'static,
+{
+    move |invoke| {
+        let span = tracing::trace_span!(
+            target: PERF_TARGET,
+            "command",
+            name = %invoke.message.command()
+        );
+        let _entered = span.entered();
+        handler(invoke)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn traces_url_appends_signal_path_once() {
+        assert_eq!(
+            traces_url("http://localhost:4318"),
+            "http://localhost:4318/v1/traces"
+        );
+        assert_eq!(
+            traces_url("http://tempo:4318/v1/traces/"),
+            "http://tempo:4318/v1/traces"
+        );
+    }
+}
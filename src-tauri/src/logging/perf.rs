@@ -0,0 +1,165 @@
+//! Durations of internal performance spans
+//!
+//! Spans with target [`PERF_TARGET`] mark the expensive parts of sampling (`sample` per menu
+//! bar tick, `ioreport_*`, `smc_temperature`, `gpu_ioreg`) and each Tauri `command`. This layer
+//! keeps count / last / average / max per span (commands per command name) for the self-profiling
+//! panel; the same spans feed the optional OTLP exporter (`otel`). It has its own filter, so
+//! the spans are recorded at any verbosity without reaching `debug.log`.
+
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Target of the internal performance spans.
+pub const PERF_TARGET: &str = "mac_stats::perf";
+
+#[derive(Default)]
+struct Timing {
+    count: u64,
+    total: Duration,
+    last: Duration,
+    max: Duration,
+}
+
+static TIMINGS: Mutex<BTreeMap<String, Timing>> = Mutex::new(BTreeMap::new());
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpanTiming {
+    /// Span name; `command <name>` for Tauri commands
+    pub name: String,
+    pub count: u64,
+    pub last_ms: f64,
+    pub avg_ms: f64,
+    pub max_ms: f64,
+}
+
+pub fn record(name: &str, elapsed: Duration) {
+    let Ok(mut timings) = TIMINGS.lock() else {
+        return;
+    };
+    let t = timings.entry(name.to_string()).or_default();
+    t.count += 1;
+    t.total += elapsed;
+    t.last = elapsed;
+    t.max = t.max.max(elapsed);
+}
+
+/// Recorded spans, slowest average first.
+pub fn timings() -> Vec<SpanTiming> {
+    let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+    let mut out: Vec<SpanTiming> = TIMINGS
+        .lock()
+        .map(|timings| {
+            timings
+                .iter()
+                .map(|(name, t)| SpanTiming {
+                    name: name.clone(),
+                    count: t.count,
+                    last_ms: ms(t.last),
+                    avg_ms: ms(t.total) / t.count.max(1) as f64,
+                    max_ms: ms(t.max),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    out.sort_by(|a, b| b.avg_ms.total_cmp(&a.avg_ms));
+    out
+}
+
+struct Started {
+    at: Instant,
+    label: String,
+}
+
+/// `name` field of `command` spans
+struct CommandName(Option<String>);
+
+impl Visit for CommandName {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "name" {
+            self.0 = Some(format!("{value:?}"));
+        }
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "name" {
+            self.0 = Some(value.to_string());
+        }
+    }
+}
+
+struct DurationLayer;
+
+impl<S> Layer<S> for DurationLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(id) else {
+            return;
+        };
+        let mut command = CommandName(None);
+        attrs.record(&mut command);
+        let label = match command.0 {
+            Some(name) => format!("{} {}", span.name(), name),
+            None => span.name().to_string(),
+        };
+        span.extensions_mut().insert(Started {
+            at: Instant::now(),
+            label,
+        });
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        if let Some(started) = span.extensions().get::<Started>() {
+            record(&started.label, started.at.elapsed());
+        }
+    }
+}
+
+/// Recording layer for `init_tracing`.
+pub(crate) fn layer<S>() -> impl Layer<S>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    let only_perf =
+        tracing_subscriber::filter::Targets::new().with_target(PERF_TARGET, tracing::Level::TRACE);
+    DurationLayer.with_filter(only_perf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn records_perf_spans_per_command() {
+        let subscriber = tracing_subscriber::registry().with(layer());
+        tracing::subscriber::with_default(subscriber, || {
+            for _ in 0..2 {
+                let _span =
+                    tracing::trace_span!(target: PERF_TARGET, "command", name = %"test_cmd")
+                        .entered();
+            }
+            let _other = tracing::info_span!(target: "mac_stats::ui", "not_perf").entered();
+        });
+        let timings = timings();
+        let cmd = timings
+            .iter()
+            .find(|t| t.name == "command test_cmd")
+            .expect("command span recorded");
+        assert_eq!(cmd.count, 2);
+        assert!(cmd.max_ms >= cmd.last_ms);
+        assert!(!timings.iter().any(|t| t.name == "not_perf"));
+    }
+}
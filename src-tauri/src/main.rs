@@ -43,6 +43,13 @@ struct Args {
     )]
     power_usage: bool,
 
+    /// Read-only guest mode: refuse every mutating action (also `guestMode` in config.json)
+    #[arg(
+        long = "guest",
+        help = "Read-only guest mode for shared or demo machines: no force quit, config writes or agent actions"
+    )]
+    guest: bool,
+
     /// Print changelog to console and exit
     #[arg(
         long = "changelog",
@@ -64,7 +71,7 @@ struct Args {
     )]
     browser_debug_crash_tab: bool,
 
-    /// Subcommands: task (add, list, show, ...) or agent (test). Run and exit without starting the app.
+    /// Subcommands: task (add, list, show, ...), agent (test), discord, stats, query, export, sessions, energy, setup, config, ui-selftest or doctor. Run and exit without starting the app.
     #[command(subcommand)]
     cmd: Option<MainCmd>,
 }
@@ -80,6 +87,39 @@ enum MainCmd {
     /// Discord: send a message to a channel (uses bot token from config)
     #[command(subcommand)]
     Discord(DiscordCmd),
+    /// Print current CPU/GPU/RAM/disk, temperature, load and top processes, then exit
+    /// (use --watch N to refresh like `top`)
+    Stats(mac_stats::StatsArgs),
+    /// Print one metric from the running app over the local API (fast; for Raycast, Alfred
+    /// and SwiftBar scripts), then exit
+    Query(mac_stats::QueryArgs),
+    /// Write metrics history to CSV or JSON (stdout unless a path is given), then exit
+    Export(mac_stats::ExportArgs),
+    /// List the session summaries saved on quit, or print one (`latest` or an id), then exit
+    Sessions(mac_stats::SessionsArgs),
+    /// Print estimated energy use (Wh) per day and per session from the saved history, then exit
+    Energy(mac_stats::EnergyArgs),
+    /// Write config.json for a fresh install without opening the app (MDM / provisioning);
+    /// flags also read MAC_STATS_SETUP_* environment variables
+    Setup(mac_stats::SetupArgs),
+    /// Config file tools (validate, export, import)
+    #[command(subcommand)]
+    Config(ConfigCmd),
+    /// Config profiles in ~/.mac-stats/profiles/ (list, use NAME, off)
+    #[command(subcommand)]
+    Profile(ProfileCmd),
+    /// Menu bar items (restore)
+    #[command(subcommand)]
+    MenuBar(MenuBarCmd),
+    /// Run the menu bar update pipeline once (sample → MENU_BAR_TEXT → main-thread timer →
+    /// button title) and report the stage that fails
+    UiSelftest,
+    /// Check capabilities, SMC / IOReport access, permissions, notifications, config and logs,
+    /// and print a pass/fail report for bug reports
+    Doctor,
+    /// Root launch daemon serving root-only sensors over XPC (started by launchd)
+    #[command(hide = true)]
+    PrivilegedHelper,
 }
 
 #[derive(clap::Subcommand, Debug)]
@@ -99,6 +139,50 @@ enum DiscordCmd {
     },
 }
 
+#[derive(clap::Subcommand, Debug)]
+enum ConfigCmd {
+    /// Check config.json for invalid JSON, wrong types, out-of-range values and unknown keys.
+    /// Exit code 1 when there are errors.
+    Validate {
+        /// Config file to check (default: ~/.mac-stats/config.json)
+        path: Option<PathBuf>,
+    },
+    /// Write config, alert rules, menu bar layout and profiles as one JSON bundle for another
+    /// Mac (stdout unless a path is given)
+    Export {
+        /// Bundle file to write
+        path: Option<PathBuf>,
+    },
+    /// Replace the settings with a bundle from `config export` (or a copied config.json);
+    /// older bundle versions are migrated
+    Import {
+        /// Bundle file to read
+        path: PathBuf,
+        /// Also import hooks and `run` click actions (shell commands) from the bundle
+        #[arg(long)]
+        include_commands: bool,
+    },
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum ProfileCmd {
+    /// List profiles (built-in and ~/.mac-stats/profiles/*.json); `*` marks the active one
+    List,
+    /// Switch to a profile, e.g. `mac_stats profile use battery-saver`
+    Use {
+        /// Profile name (file name without .json)
+        name: String,
+    },
+    /// Turn the active profile off and restore the values it replaced
+    Off,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum MenuBarCmd {
+    /// Bring back status items that were ⌘-dragged out of the menu bar (opens mac-stats)
+    Restore,
+}
+
 #[derive(clap::Subcommand, Debug)]
 enum AgentCmd {
     /// Run agent with prompts from testing.md. Logs to ~/.mac-stats/debug.log; use -vv.
@@ -119,6 +203,11 @@ enum AgentCmd {
 fn main() {
     let args = Args::parse();
 
+    // The launch daemon runs as root: no app logging or config under root's home
+    if matches!(args.cmd, Some(MainCmd::PrivilegedHelper)) {
+        mac_stats::run_privileged_helper();
+    }
+
     // Set verbosity level (0-3). Default 2 (-vv) so logs are visible when no -v flags given.
     let verbosity = if args.verbose > 3 {
         3
@@ -145,6 +234,9 @@ fn main() {
     // Set power usage logging flag
     mac_stats::set_power_usage_logging(args.power_usage);
 
+    // Read-only guest mode (`--guest`)
+    mac_stats::set_guest_mode(args.guest);
+
     // If --changelog flag is set, test changelog functionality
     if args.changelog {
         use mac_stats::get_changelog;
@@ -196,6 +288,49 @@ fn main() {
                 Ok(()) => 0,
                 Err(c) => c,
             },
+            MainCmd::Stats(stats_args) => match mac_stats::run_stats_cli(stats_args) {
+                Ok(()) => 0,
+                Err(c) => c,
+            },
+            MainCmd::Query(query_args) => match mac_stats::run_query_cli(query_args) {
+                Ok(()) => 0,
+                Err(c) => c,
+            },
+            MainCmd::Export(export_args) => match mac_stats::run_export_cli(export_args) {
+                Ok(()) => 0,
+                Err(c) => c,
+            },
+            MainCmd::Sessions(sessions_args) => match mac_stats::run_sessions_cli(sessions_args) {
+                Ok(()) => 0,
+                Err(c) => c,
+            },
+            MainCmd::Energy(energy_args) => match mac_stats::run_energy_cli(energy_args) {
+                Ok(()) => 0,
+                Err(c) => c,
+            },
+            MainCmd::Setup(setup_args) => match mac_stats::run_setup_cli(setup_args) {
+                Ok(()) => 0,
+                Err(c) => c,
+            },
+            MainCmd::Config(ConfigCmd::Validate { path }) => {
+                mac_stats::run_config_validate_cli(path.as_deref())
+            }
+            MainCmd::Config(ConfigCmd::Export { path }) => {
+                mac_stats::run_settings_export_cli(path.as_deref())
+            }
+            MainCmd::Config(ConfigCmd::Import {
+                path,
+                include_commands,
+            }) => mac_stats::run_settings_import_cli(&path, include_commands),
+            MainCmd::Profile(ProfileCmd::List) => mac_stats::run_profile_list_cli(),
+            MainCmd::Profile(ProfileCmd::Use { name }) => {
+                mac_stats::run_profile_use_cli(Some(&name))
+            }
+            MainCmd::Profile(ProfileCmd::Off) => mac_stats::run_profile_use_cli(None),
+            MainCmd::MenuBar(MenuBarCmd::Restore) => mac_stats::run_menu_bar_restore_cli(),
+            MainCmd::UiSelftest => mac_stats::run_ui_selftest_stdio(),
+            MainCmd::Doctor => mac_stats::run_doctor_stdio(),
+            MainCmd::PrivilegedHelper => mac_stats::run_privileged_helper(),
             MainCmd::Agent(AgentCmd::Test { selector, path }) => {
                 let rt = tokio::runtime::Runtime::new().expect("tokio runtime");
                 rt.block_on(async {
@@ -0,0 +1,210 @@
+//! Time Machine backup status
+//!
+//! Wraps `tmutil status` (current session: phase, progress) and `tmutil latestbackup`
+//! (last completed snapshot) so a running backup can explain sudden disk/CPU spikes.
+//! Both are subprocess calls, so results are cached for [`BACKUP_STATUS_TTL_SECS`].
+
+use serde::Serialize;
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::Instant;
+
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+/// How long a backup status snapshot stays fresh (tmutil is slow-ish and rarely changes)
+const BACKUP_STATUS_TTL_SECS: u64 = 15;
+
+static BACKUP_STATUS_CACHE: Mutex<Option<(BackupStatus, Instant)>> = Mutex::new(None);
+
+/// Time Machine state returned by `get_backup_status()`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BackupStatus {
+    /// False when `tmutil` is missing or Time Machine is not configured
+    pub available: bool,
+    pub running: bool,
+    /// tmutil `BackupPhase` (e.g. `Copying`, `Finishing`, `ThinningPostBackup`)
+    pub phase: Option<String>,
+    /// Progress of the running backup in percent (0-100)
+    pub percent: Option<f32>,
+    pub bytes_copied: Option<u64>,
+    pub total_bytes: Option<u64>,
+    pub time_remaining_secs: Option<u64>,
+    /// Unix timestamp of the latest completed backup, when readable
+    pub last_backup_timestamp: Option<i64>,
+    pub secs_since_last_backup: Option<i64>,
+    /// Reason the last backup date could not be read (e.g. Full Disk Access missing)
+    pub error: Option<String>,
+}
+
+/// Pull `key = value;` out of tmutil's NeXTSTEP-style plist output (first match wins).
+fn tmutil_value<'a>(output: &'a str, key: &str) -> Option<&'a str> {
+    output.lines().find_map(|line| {
+        let line = line.trim();
+        let (k, v) = line.split_once('=')?;
+        if k.trim().trim_matches('"') != key {
+            return None;
+        }
+        let v = v.trim().trim_end_matches(';').trim().trim_matches('"');
+        (!v.is_empty() && v != "{").then_some(v)
+    })
+}
+
+/// Parse `tmutil status` output into the live-session part of [`BackupStatus`]
+fn parse_tmutil_status(output: &str) -> BackupStatus {
+    let running = tmutil_value(output, "Running") == Some("1");
+    let phase = tmutil_value(output, "BackupPhase").map(str::to_string);
+    // `Percent` is a 0..1 fraction; -1 while tmutil is still preparing.
+    let percent = tmutil_value(output, "Percent")
+        .and_then(|v| v.parse::<f32>().ok())
+        .filter(|p| (0.0..=1.0).contains(p))
+        .map(|p| p * 100.0);
+    BackupStatus {
+        available: true,
+        running,
+        phase: if running { phase } else { None },
+        percent: if running { percent } else { None },
+        bytes_copied: tmutil_value(output, "bytes").and_then(|v| v.parse().ok()),
+        total_bytes: tmutil_value(output, "totalBytes").and_then(|v| v.parse().ok()),
+        time_remaining_secs: tmutil_value(output, "TimeRemaining")
+            .and_then(|v| v.parse::<f64>().ok())
+            .filter(|s| *s >= 0.0)
+            .map(|s| s as u64),
+        ..Default::default()
+    }
+}
+
+/// Parse the snapshot date from a `tmutil latestbackup` path
+/// (`/Volumes/…/2024-05-01-101500.backup` or `…/Backups.backupdb/Mac/2024-05-01-101500`).
+fn parse_latest_backup_timestamp(output: &str) -> Option<i64> {
+    use chrono::TimeZone;
+    let name = output.trim().lines().last()?.trim_end_matches('/');
+    let name = name.rsplit('/').next()?;
+    let stamp = name.split('.').next()?;
+    let naive = chrono::NaiveDateTime::parse_from_str(stamp, "%Y-%m-%d-%H%M%S").ok()?;
+    chrono::Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|dt| dt.timestamp())
+}
+
+fn read_backup_status() -> BackupStatus {
+    let status_output = Command::new("/usr/bin/tmutil")
+        .arg("status")
+        .stderr(std::process::Stdio::null())
+        .output();
+    let mut status = match status_output {
+        Ok(out) if out.status.success() => {
+            parse_tmutil_status(&String::from_utf8_lossy(&out.stdout))
+        }
+        Ok(out) => {
+            debug3!("tmutil status exited with {:?}", out.status);
+            return BackupStatus::default();
+        }
+        Err(e) => {
+            debug3!("tmutil status unavailable: {}", e);
+            return BackupStatus::default();
+        }
+    };
+
+    match Command::new("/usr/bin/tmutil").arg("latestbackup").output() {
+        Ok(out) if out.status.success() => {
+            status.last_backup_timestamp =
+                parse_latest_backup_timestamp(&String::from_utf8_lossy(&out.stdout));
+        }
+        Ok(out) => {
+            let stderr = String::from_utf8_lossy(&out.stderr).trim().to_string();
+            status.error = Some(if stderr.is_empty() {
+                "No completed backup found".to_string()
+            } else {
+                stderr
+            });
+        }
+        Err(e) => status.error = Some(e.to_string()),
+    }
+    status
+}
+
+/// Cached backup status (refreshes at most every [`BACKUP_STATUS_TTL_SECS`])
+pub fn backup_status() -> BackupStatus {
+    if let Ok(cache) = BACKUP_STATUS_CACHE.try_lock() {
+        if let Some((status, at)) = cache.as_ref() {
+            if at.elapsed().as_secs() < BACKUP_STATUS_TTL_SECS {
+                return with_age(status.clone());
+            }
+        }
+    }
+    let status = read_backup_status();
+    if let Ok(mut cache) = BACKUP_STATUS_CACHE.try_lock() {
+        *cache = Some((status.clone(), Instant::now()));
+    }
+    with_age(status)
+}
+
+/// Fill `secs_since_last_backup` relative to now (cached entries age between reads)
+fn with_age(mut status: BackupStatus) -> BackupStatus {
+    status.secs_since_last_backup = status
+        .last_backup_timestamp
+        .map(|ts| (chrono::Utc::now().timestamp() - ts).max(0));
+    status
+}
+
+/// Time Machine status: whether a backup is running, its progress, and time since the last one
+#[tauri::command]
+pub fn get_backup_status() -> BackupStatus {
+    backup_status()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RUNNING: &str = r#"Backup session status:
+{
+    BackupPhase = Copying;
+    ClientID = "com.apple.backupd";
+    DateOfStateChange = "2024-05-01 10:15:00 +0000";
+    Percent = "0.4521";
+    Progress =     {
+        TimeRemaining = 312;
+        "_raw_totalBytes" = 2000000;
+        bytes = 904200;
+        files = 1200;
+        totalBytes = 2000000;
+    };
+    Running = 1;
+    Stopping = 0;
+}"#;
+
+    #[test]
+    fn parses_running_backup() {
+        let s = parse_tmutil_status(RUNNING);
+        assert!(s.available && s.running);
+        assert_eq!(s.phase.as_deref(), Some("Copying"));
+        assert!((s.percent.unwrap() - 45.21).abs() < 0.01);
+        assert_eq!(s.bytes_copied, Some(904200));
+        assert_eq!(s.total_bytes, Some(2000000));
+        assert_eq!(s.time_remaining_secs, Some(312));
+    }
+
+    #[test]
+    fn parses_idle_status() {
+        let s = parse_tmutil_status("Backup session status:\n{\n    ClientID = \"com.apple.backupd\";\n    Running = 0;\n}\n");
+        assert!(s.available);
+        assert!(!s.running);
+        assert_eq!(s.percent, None);
+    }
+
+    #[test]
+    fn parses_latest_backup_paths() {
+        assert!(parse_latest_backup_timestamp(
+            "/Volumes/.timemachine/ABC/2024-05-01-101500.backup/2024-05-01-101500.backup\n"
+        )
+        .is_some());
+        assert!(parse_latest_backup_timestamp(
+            "/Volumes/TM/Backups.backupdb/Mac/2024-05-01-101500"
+        )
+        .is_some());
+        assert_eq!(parse_latest_backup_timestamp("garbage"), None);
+    }
+}
@@ -0,0 +1,249 @@
+//! Battery details for the battery window (`get_battery_details`) and battery/adapter power in
+//! history
+//!
+//! A reading combines the `battery` crate (charge, state, energy rate, health, cycles) with the
+//! connected adapter's rating from `ioreg -rn AppleSmartBattery` (`AdapterDetails.Watts`).
+//! The update loop takes one every [`SAMPLE_INTERVAL`] ([`VISIBLE_SAMPLE_INTERVAL`] while the
+//! battery window is open) so `battery_level`, `battery_power` and `adapter_power` reach the
+//! history even when no window is open; each reading also feeds the drain tracker.
+
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use battery::{Manager as BatteryManager, State};
+use serde::Serialize;
+use tauri::Manager;
+
+use super::battery_drain;
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+/// Reading interval for history while the battery window is closed
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(30);
+/// Reading interval while the battery window is visible
+const VISIBLE_SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+/// One battery reading
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BatteryReading {
+    /// Charge level (0-100)
+    pub level: f32,
+    /// `charging`, `discharging`, `full` or `unknown`
+    pub state: String,
+    /// Watts drawn from the battery (0 while charging or on AC)
+    pub discharge_watts: f32,
+    /// Watts flowing into the battery while charging
+    pub charge_watts: f32,
+    /// Rating of the connected power adapter, None when unplugged or unknown
+    pub adapter_watts: Option<f32>,
+    /// The battery's own estimates
+    pub time_to_empty_secs: Option<u64>,
+    pub time_to_full_secs: Option<u64>,
+    /// Full-charge capacity against design capacity (0-100)
+    pub health_percent: Option<f32>,
+    pub cycle_count: Option<u32>,
+    pub temperature_c: Option<f32>,
+}
+
+/// Result of `get_battery_details`
+#[derive(Debug, Clone, Serialize)]
+pub struct BatteryDetails {
+    /// None on Macs without a battery
+    pub battery: Option<BatteryReading>,
+    /// Drain over the last minutes (see `battery_drain`), 0 while charging
+    pub percent_per_hour: f32,
+    /// Remaining time at the current drain, falling back to the battery's estimate
+    pub remaining_secs: Option<u64>,
+    /// macOS Low Power Mode; None before macOS 12
+    pub low_power_mode: Option<bool>,
+}
+
+static LATEST: Mutex<Option<(Instant, Option<BatteryReading>)>> = Mutex::new(None);
+
+/// Adapter rating from `ioreg -rn AppleSmartBattery` output (`"AdapterDetails" = {..."Watts"=96
+/// ...}`), only while `ExternalConnected` is `Yes`
+pub fn parse_adapter_watts(ioreg: &str) -> Option<f32> {
+    let connected = ioreg
+        .lines()
+        .any(|l| l.contains("\"ExternalConnected\" = Yes"));
+    if !connected {
+        return None;
+    }
+    let details = ioreg
+        .lines()
+        .find(|l| l.contains("\"AdapterDetails\" = {"))?;
+    let (_, rest) = details.split_once("\"Watts\"=")?;
+    let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse::<f32>().ok().filter(|w| *w > 0.0)
+}
+
+fn read_adapter_watts() -> Option<f32> {
+    let out = Command::new("/usr/sbin/ioreg")
+        .args(["-rn", "AppleSmartBattery"])
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()?;
+    parse_adapter_watts(&String::from_utf8_lossy(&out.stdout))
+}
+
+/// Adapter rating from the latest reading (low-interference mode skips `ioreg`)
+fn last_adapter_watts() -> Option<f32> {
+    LATEST.try_lock().ok()?.as_ref()?.1.as_ref()?.adapter_watts
+}
+
+/// Read the battery and adapter now (None without a battery)
+pub fn read() -> Option<BatteryReading> {
+    let manager = BatteryManager::new().ok()?;
+    let battery = match manager.batteries().ok()?.next()? {
+        Ok(battery) => battery,
+        Err(e) => {
+            debug3!("Failed to read battery: {:?}", e);
+            return None;
+        }
+    };
+    let watts = battery
+        .energy_rate()
+        .get::<battery::units::power::watt>()
+        .abs();
+    let state = battery.state();
+    let secs = |t: battery::units::Time| t.get::<battery::units::time::second>().round() as u64;
+    Some(BatteryReading {
+        level: battery
+            .state_of_charge()
+            .get::<battery::units::ratio::percent>(),
+        state: match state {
+            State::Charging => "charging",
+            State::Discharging => "discharging",
+            State::Full => "full",
+            _ => "unknown",
+        }
+        .to_string(),
+        discharge_watts: if matches!(state, State::Discharging) {
+            watts
+        } else {
+            0.0
+        },
+        charge_watts: if matches!(state, State::Charging) {
+            watts
+        } else {
+            0.0
+        },
+        adapter_watts: if super::low_interference::is_active() {
+            last_adapter_watts()
+        } else {
+            read_adapter_watts()
+        },
+        time_to_empty_secs: battery.time_to_empty().map(secs),
+        time_to_full_secs: battery.time_to_full().map(secs),
+        health_percent: Some(
+            battery
+                .state_of_health()
+                .get::<battery::units::ratio::percent>(),
+        ),
+        cycle_count: battery.cycle_count(),
+        temperature_c: battery
+            .temperature()
+            .map(|t| t.get::<battery::units::thermodynamic_temperature::degree_celsius>()),
+    })
+}
+
+/// Store `reading` as the latest and pass it on to the battery cache and drain tracker
+fn remember(reading: Option<BatteryReading>) {
+    if let Some(r) = reading.as_ref() {
+        battery_drain::record(r.level, r.state == "discharging", r.discharge_watts);
+        let charging = r.state == "charging";
+        crate::state::update_snapshot(|s| {
+            s.battery = Some(crate::state::Reading::new((r.level, charging)))
+        });
+    }
+    if let Ok(mut latest) = LATEST.lock() {
+        *latest = Some((Instant::now(), reading));
+    }
+}
+
+fn window_visible() -> bool {
+    crate::state::APP_HANDLE
+        .get()
+        .and_then(|app| app.get_webview_window(crate::ui::battery_window::LABEL))
+        .and_then(|w| w.is_visible().ok())
+        .unwrap_or(false)
+}
+
+/// Called by the update loop: take a reading when the last one is older than the interval.
+/// Returns (battery_power, adapter_power) in watts for the history point.
+pub fn sample_for_history() -> (f32, f32) {
+    let interval = if window_visible() {
+        VISIBLE_SAMPLE_INTERVAL
+    } else {
+        SAMPLE_INTERVAL
+    };
+    let due = LATEST
+        .try_lock()
+        .map(|l| l.as_ref().is_none_or(|(at, _)| at.elapsed() >= interval))
+        .unwrap_or(false);
+    if due {
+        remember(read());
+    }
+    LATEST
+        .try_lock()
+        .ok()
+        .and_then(|l| l.as_ref().and_then(|(_, r)| r.clone()))
+        .map(|r| (r.discharge_watts, r.adapter_watts.unwrap_or(0.0)))
+        .unwrap_or((0.0, 0.0))
+}
+
+/// Charge, power flow, adapter, health and remaining time for the battery window
+#[tauri::command]
+pub async fn get_battery_details() -> Result<BatteryDetails, String> {
+    let cached = LATEST.lock().ok().and_then(|l| {
+        l.as_ref()
+            .filter(|(at, _)| at.elapsed() < VISIBLE_SAMPLE_INTERVAL)
+            .map(|(_, r)| r.clone())
+    });
+    let battery = match cached {
+        Some(reading) => reading,
+        None => {
+            let reading = tokio::task::spawn_blocking(read)
+                .await
+                .map_err(|e| e.to_string())?;
+            remember(reading.clone());
+            reading
+        }
+    };
+    let drain = battery_drain::current();
+    let remaining_secs = (drain.remaining_secs > 0)
+        .then_some(drain.remaining_secs)
+        .or_else(|| {
+            battery
+                .as_ref()
+                .filter(|b| b.state == "discharging")
+                .and_then(|b| b.time_to_empty_secs)
+        });
+    Ok(BatteryDetails {
+        battery,
+        percent_per_hour: drain.percent_per_hour,
+        remaining_secs,
+        low_power_mode: super::low_power_mode::is_enabled(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adapter_watts_only_while_connected() {
+        let plugged = r#"  | "ExternalConnected" = Yes
+  | "AdapterDetails" = {"AdapterVoltage"=20000,"Watts"=96,"FamilyCode"=18446744073172697098}
+"#;
+        assert_eq!(parse_adapter_watts(plugged), Some(96.0));
+
+        let unplugged = r#"  | "ExternalConnected" = No
+  | "AdapterDetails" = {"Watts"=96}
+"#;
+        assert_eq!(parse_adapter_watts(unplugged), None);
+        let no_adapter = "\"ExternalConnected\" = Yes\n\"AdapterDetails\" = {\"FamilyCode\"=0}";
+        assert_eq!(parse_adapter_watts(no_adapter), None);
+    }
+}
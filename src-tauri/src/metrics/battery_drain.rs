@@ -0,0 +1,161 @@
+//! Battery drain rate and remaining-time projection
+//!
+//! Charge level samples from the last [`WINDOW`] give the drain in %/hour; the battery's own
+//! energy rate gives it in watts. Remaining time is the current level divided by the %/hour
+//! drain, so it follows the current load rather than the battery's long-term estimate.
+//! Samples come from `get_battery_info()` while the CPU window is open and from the alert
+//! thread (every 60s) otherwise; charging clears the window.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use battery::{Manager as BatteryManager, State};
+
+use crate::state::BATTERY_DRAIN;
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+/// Sliding window the %/hour rate is measured over
+const WINDOW: Duration = Duration::from_secs(600);
+/// Shortest span that gives a usable rate (levels move in small steps)
+const MIN_SPAN: Duration = Duration::from_secs(120);
+/// Below this the projection would run to days; report no estimate instead
+const MIN_PROJECTED_RATE: f32 = 0.5;
+
+/// Drain figures for `CpuDetails` (all 0 while charging, on AC or before enough samples)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BatteryDrain {
+    pub percent_per_hour: f32,
+    pub watts: f32,
+    pub remaining_secs: u64,
+}
+
+pub struct DrainTracker {
+    /// (taken at, charge level %)
+    samples: VecDeque<(Instant, f32)>,
+    watts: f32,
+}
+
+impl DrainTracker {
+    pub const fn new() -> Self {
+        Self {
+            samples: VecDeque::new(),
+            watts: 0.0,
+        }
+    }
+
+    pub fn record(&mut self, at: Instant, level: f32, discharging: bool, watts: f32) {
+        if !discharging || level < 0.0 {
+            self.samples.clear();
+            self.watts = 0.0;
+            return;
+        }
+        self.samples.push_back((at, level));
+        while self
+            .samples
+            .front()
+            .is_some_and(|(t, _)| at.duration_since(*t) > WINDOW)
+        {
+            self.samples.pop_front();
+        }
+        self.watts = watts.abs();
+    }
+
+    pub fn drain(&self) -> BatteryDrain {
+        let (Some(&(first_at, first)), Some(&(last_at, last))) =
+            (self.samples.front(), self.samples.back())
+        else {
+            return BatteryDrain::default();
+        };
+        let span = last_at.duration_since(first_at);
+        if span < MIN_SPAN {
+            return BatteryDrain {
+                watts: self.watts,
+                ..BatteryDrain::default()
+            };
+        }
+        let percent_per_hour = ((first - last) / span.as_secs_f32() * 3600.0).max(0.0);
+        let remaining_secs = if percent_per_hour >= MIN_PROJECTED_RATE {
+            (last / percent_per_hour * 3600.0).round() as u64
+        } else {
+            0
+        };
+        BatteryDrain {
+            percent_per_hour,
+            watts: self.watts,
+            remaining_secs,
+        }
+    }
+}
+
+impl Default for DrainTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Feed one battery reading
+pub(crate) fn record(level: f32, discharging: bool, watts: f32) {
+    if let Ok(mut tracker) = BATTERY_DRAIN.try_lock() {
+        tracker.record(Instant::now(), level, discharging, watts);
+    }
+}
+
+/// Read the battery directly and record it (alert thread, independent of the CPU window)
+pub(crate) fn sample() {
+    let Ok(manager) = BatteryManager::new() else {
+        return;
+    };
+    let Some(Ok(battery)) = manager.batteries().ok().and_then(|mut b| b.next()) else {
+        return;
+    };
+    let level = battery
+        .state_of_charge()
+        .get::<battery::units::ratio::percent>();
+    let watts = battery.energy_rate().get::<battery::units::power::watt>();
+    let discharging = matches!(battery.state(), State::Discharging);
+    debug3!(
+        "Battery drain sample: {:.1}%, {:.1} W, discharging={}",
+        level,
+        watts,
+        discharging
+    );
+    record(level, discharging, watts);
+}
+
+/// Current drain figures
+pub fn current() -> BatteryDrain {
+    BATTERY_DRAIN
+        .try_lock()
+        .map(|t| t.drain())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_and_projection_over_sliding_window() {
+        let mut tracker = DrainTracker::new();
+        let start = Instant::now();
+        tracker.record(start, 80.0, true, -9.5);
+        tracker.record(start + Duration::from_secs(60), 79.5, true, 9.5);
+        // Too short a span for a rate yet, but watts are known
+        assert_eq!(tracker.drain().percent_per_hour, 0.0);
+        assert_eq!(tracker.drain().watts, 9.5);
+
+        tracker.record(start + Duration::from_secs(360), 77.0, true, 10.0);
+        let drain = tracker.drain();
+        assert!((drain.percent_per_hour - 30.0).abs() < 0.01);
+        assert_eq!(drain.remaining_secs, 77 * 120);
+
+        // The first samples fall out of the 10-minute window
+        tracker.record(start + Duration::from_secs(900), 76.0, true, 10.0);
+        let drain = tracker.drain();
+        assert!((drain.percent_per_hour - 6.67).abs() < 0.01);
+
+        tracker.record(start + Duration::from_secs(960), 76.0, false, 0.0);
+        assert_eq!(tracker.drain(), BatteryDrain::default());
+    }
+}
@@ -0,0 +1,151 @@
+//! Build activity: compiler and build-tool processes seen by the process sampler
+//!
+//! Every process refresh sums the CPU of processes named like a compiler or build driver
+//! ([`BUILD_PROCESSES`]: clang, swift, rustc/cargo, xcodebuild, linkers, ...). A build starts
+//! with the first refresh where one of them is busy and ends once none has been busy for
+//! [`QUIET_GRACE`] (short gaps between compiler invocations do not split a build). Like the
+//! process list, this only runs while processes are refreshed (CPU window open or local API
+//! polling).
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Process names (exact) that count as build work
+const BUILD_PROCESSES: &[&str] = &[
+    "clang",
+    "clang++",
+    "cc",
+    "c++",
+    "gcc",
+    "g++",
+    "swift",
+    "swiftc",
+    "swift-frontend",
+    "swift-build",
+    "cargo",
+    "rustc",
+    "xcodebuild",
+    "XCBBuildService",
+    "SWBBuildService",
+    "ibtool",
+    "actool",
+    "ld",
+    "ld64",
+    "ld-prime",
+    "ninja",
+    "make",
+];
+/// A build process counts as busy above this CPU % (idle build daemons stay below it)
+const BUSY_CPU_PERCENT: f32 = 2.0;
+/// Quiet time after which a build is over
+const QUIET_GRACE: Duration = Duration::from_secs(15);
+
+fn is_build_process(name: &str) -> bool {
+    BUILD_PROCESSES.contains(&name) || name.starts_with("clang-") || name == "build-script-build"
+}
+
+/// Current build state, as reported in `CpuDetails`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct BuildActivity {
+    /// CPU of busy build processes, percent of the whole machine (0-100)
+    pub load: f32,
+    /// Busy build processes in the last refresh
+    pub processes: u32,
+    /// Time since the running build started (0 when no build is running)
+    pub elapsed_secs: u64,
+    /// Length of the last finished build (0 when none yet)
+    pub last_build_secs: u64,
+}
+
+#[derive(Debug, Default)]
+struct BuildTracker {
+    started: Option<Instant>,
+    last_busy: Option<Instant>,
+    current: BuildActivity,
+}
+
+impl BuildTracker {
+    /// Feed the CPU % of every build process from one refresh
+    fn record(&mut self, build_cpu: impl IntoIterator<Item = f32>, cpus: usize, now: Instant) {
+        let busy: Vec<f32> = build_cpu
+            .into_iter()
+            .filter(|cpu| *cpu >= BUSY_CPU_PERCENT)
+            .collect();
+        self.current.processes = busy.len() as u32;
+        self.current.load = (busy.iter().sum::<f32>() / cpus.max(1) as f32).min(100.0);
+        if !busy.is_empty() {
+            self.started.get_or_insert(now);
+            self.last_busy = Some(now);
+        } else if let (Some(started), Some(last_busy)) = (self.started, self.last_busy) {
+            if now.saturating_duration_since(last_busy) >= QUIET_GRACE {
+                self.current.last_build_secs = last_busy.saturating_duration_since(started).as_secs();
+                self.started = None;
+                self.last_busy = None;
+            }
+        }
+        self.current.elapsed_secs = self
+            .started
+            .map_or(0, |s| now.saturating_duration_since(s).as_secs());
+    }
+}
+
+static TRACKER: Mutex<Option<BuildTracker>> = Mutex::new(None);
+
+/// Record build processes; call right after `refresh_processes`.
+pub(crate) fn record(sys: &sysinfo::System) {
+    let Ok(mut tracker) = TRACKER.try_lock() else {
+        return;
+    };
+    tracker.get_or_insert_with(BuildTracker::default).record(
+        sys.processes()
+            .values()
+            .filter(|p| is_build_process(&p.name().to_string_lossy()))
+            .map(|p| p.cpu_usage()),
+        sys.cpus().len(),
+        Instant::now(),
+    );
+}
+
+/// Build state as of the last process refresh
+pub(crate) fn current() -> BuildActivity {
+    TRACKER
+        .try_lock()
+        .ok()
+        .and_then(|t| t.as_ref().map(|t| t.current))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_builds_across_short_gaps() {
+        assert!(is_build_process("swift-frontend"));
+        assert!(is_build_process("clang-17"));
+        assert!(!is_build_process("Xcode"));
+
+        let t0 = Instant::now();
+        let mut t = BuildTracker::default();
+        // Idle build daemon: no build
+        t.record([0.5], 8, t0);
+        assert_eq!(t.current, BuildActivity::default());
+
+        t.record([400.0, 200.0, 0.1], 8, t0 + Duration::from_secs(1));
+        assert_eq!(t.current.processes, 2);
+        assert_eq!(t.current.load, 75.0);
+        assert_eq!(t.current.elapsed_secs, 0);
+
+        // A 10 s gap between compiler runs is still the same build
+        t.record([], 8, t0 + Duration::from_secs(11));
+        assert_eq!(t.current.load, 0.0);
+        assert_eq!(t.current.elapsed_secs, 10);
+        t.record([100.0], 8, t0 + Duration::from_secs(31));
+        assert_eq!(t.current.elapsed_secs, 30);
+
+        // Quiet for the grace period: the build took 30 s
+        t.record([], 8, t0 + Duration::from_secs(50));
+        assert_eq!(t.current.elapsed_secs, 0);
+        assert_eq!(t.current.last_build_secs, 30);
+    }
+}
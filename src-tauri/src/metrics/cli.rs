@@ -0,0 +1,422 @@
+//! `mac_stats stats`: print current metrics to stdout without starting the menu bar app.
+//!
+//! Reuses `get_metrics()` / `get_cpu_details()`. The app only samples SMC/IOReport while the
+//! CPU window is open, so this command primes CPU usage, reads CPU and GPU temperature and fan speed
+//! once over SMC and collects top processes itself; frequency and power stay at their
+//! cached/nominal values.
+
+use std::io::IsTerminal;
+use std::time::{Duration, Instant};
+
+use mac_stats_collector::smc::{cpu_temperature, fan_rpm};
+
+use super::{get_cpu_details, get_metrics, CpuDetails, SystemMetrics};
+use crate::number_format::NumberFormat;
+use crate::state::{LAST_SYSTEM_REFRESH, SYSTEM};
+
+/// Arguments for `mac_stats stats`
+#[derive(clap::Args, Debug)]
+pub struct StatsArgs {
+    /// One JSON object per snapshot (`{timestamp, metrics, cpu}`; NDJSON with --watch)
+    #[arg(long, conflicts_with = "plain")]
+    pub json: bool,
+    /// `key=value` lines without units or locale formatting (for scripts)
+    #[arg(long)]
+    pub plain: bool,
+    /// Refresh every N seconds until interrupted (like `top`)
+    #[arg(long, value_name = "N")]
+    pub watch: Option<u64>,
+}
+
+/// Top processes shown in the default output
+const TOP_PROCESS_COUNT: usize = 5;
+
+/// One reading of everything `stats` prints (also written by `snapshots`)
+#[derive(serde::Serialize)]
+pub(crate) struct StatsSnapshot {
+    pub(crate) timestamp: i64,
+    pub(crate) metrics: SystemMetrics,
+    pub(crate) cpu: CpuDetails,
+}
+
+/// Refresh CPU/memory/process counters and mark the refresh so `get_metrics()` reuses it
+/// (a second refresh right after would measure a near-zero interval).
+pub(crate) fn refresh_system() {
+    if let Ok(mut sys) = SYSTEM.lock() {
+        let sys = sys.get_or_insert_with(sysinfo::System::new);
+        sys.refresh_cpu_usage();
+        sys.refresh_memory();
+        sys.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+    }
+    if let Ok(mut last) = LAST_SYSTEM_REFRESH.lock() {
+        *last = Some(Instant::now());
+    }
+}
+
+/// Single SMC read (die, then proximity sensor); `None` when SMC is not accessible
+pub(crate) fn read_temperature_once() -> Option<f32> {
+    let mut smc = macsmc::Smc::connect().ok()?;
+    cpu_temperature(&mut smc)
+}
+
+/// Single SMC scan of the GPU cluster keys; `None` when SMC or the keys are not accessible
+pub(crate) fn read_gpu_temperature_once() -> Option<f32> {
+    let mut smc = macsmc::Smc::connect().ok()?;
+    super::gpu_temperature::read(&mut smc)
+}
+
+/// Single SMC read of the fastest fan; `None` on fanless Macs or when SMC is not accessible
+pub(crate) fn read_fan_once() -> Option<f32> {
+    let mut smc = macsmc::Smc::connect().ok()?;
+    fan_rpm(&mut smc)
+}
+
+pub(crate) fn snapshot() -> StatsSnapshot {
+    refresh_system();
+    let metrics = get_metrics();
+    let mut cpu = get_cpu_details();
+    if let Some(t) = read_temperature_once() {
+        cpu.temperature = t;
+        cpu.can_read_temperature = true;
+    }
+    if let Some(t) = read_gpu_temperature_once() {
+        cpu.gpu_temperature = t;
+        cpu.can_read_gpu_temperature = true;
+    }
+    if let Some(rpm) = read_fan_once() {
+        cpu.fan_rpm = rpm;
+        cpu.fan_audible = rpm >= crate::config::Config::fan_audible_rpm() as f32;
+        cpu.can_read_fan = true;
+    }
+    if cpu.top_processes.is_empty() {
+        cpu.top_processes = super::processes_by_cpu(TOP_PROCESS_COUNT);
+    }
+    StatsSnapshot {
+        timestamp: chrono::Utc::now().timestamp(),
+        metrics,
+        cpu,
+    }
+}
+
+pub(crate) fn format_duration(secs: u64) -> String {
+    let (days, hours, mins) = (secs / 86400, (secs % 86400) / 3600, (secs % 3600) / 60);
+    if days > 0 {
+        format!("{}d {}h {}m", days, hours, mins)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, mins)
+    } else {
+        format!("{}m", mins)
+    }
+}
+
+/// The default `stats` output for `s`, in the user's locale and units
+pub(crate) fn human_text(s: &StatsSnapshot) -> String {
+    format_human(s, &NumberFormat::current(), &crate::units::Units::current())
+}
+
+/// `s` as Markdown tables, in the user's locale and units
+pub(crate) fn markdown_text(s: &StatsSnapshot) -> String {
+    format_markdown(s, &NumberFormat::current(), &crate::units::Units::current())
+}
+
+/// `(label, value)` rows shared by the human and Markdown outputs; unreadable sensors are left out
+pub(crate) fn summary_rows(
+    s: &StatsSnapshot,
+    fmt: &NumberFormat,
+    units: &crate::units::Units,
+) -> Vec<(&'static str, String)> {
+    let (m, c) = (&s.metrics, &s.cpu);
+    let mut rows = vec![
+        ("CPU", fmt.percent(m.cpu as f64, 1)),
+        ("GPU", fmt.percent(m.gpu as f64, 1)),
+        ("RAM", fmt.percent(m.ram as f64, 1)),
+        ("Disk", fmt.percent(m.disk as f64, 1)),
+    ];
+    if c.can_read_temperature && c.temperature > 0.0 {
+        rows.push((
+            "Temp",
+            fmt.with_unit(
+                units.temperature(c.temperature as f64),
+                0,
+                units.temperature_symbol(),
+            ),
+        ));
+    }
+    if c.can_read_gpu_temperature && c.gpu_temperature > 0.0 {
+        rows.push((
+            "GPU Temp",
+            fmt.with_unit(
+                units.temperature(c.gpu_temperature as f64),
+                0,
+                units.temperature_symbol(),
+            ),
+        ));
+    }
+    if c.frequency > 0.0 {
+        rows.push(("Freq", fmt.with_unit(c.frequency as f64, 2, " GHz")));
+    }
+    if c.can_read_cpu_power && c.cpu_power > 0.0 {
+        rows.push((
+            "Power",
+            format!(
+                "CPU {} / GPU {}",
+                fmt.with_unit(c.cpu_power as f64, 1, " W"),
+                fmt.with_unit(c.gpu_power as f64, 1, " W")
+            ),
+        ));
+    }
+    if c.can_read_fan {
+        rows.push((
+            "Fan",
+            format!(
+                "{}{}",
+                fmt.with_unit(c.fan_rpm as f64, 0, " rpm"),
+                if c.fan_audible { " (audible)" } else { "" }
+            ),
+        ));
+    }
+    rows.push((
+        "Load",
+        format!(
+            "{} {} {}",
+            fmt.number(c.load_1, 2),
+            fmt.number(c.load_5, 2),
+            fmt.number(c.load_15, 2)
+        ),
+    ));
+    rows.push(("Uptime", format_duration(c.uptime_secs)));
+    if c.has_battery {
+        rows.push((
+            "Battery",
+            format!(
+                "{}{}",
+                fmt.percent(c.battery_level as f64, 0),
+                if c.is_charging { " (charging)" } else { "" }
+            ),
+        ));
+    }
+    if !c.chip_info.is_empty() {
+        rows.push(("Chip", c.chip_info.clone()));
+    }
+    rows
+}
+
+fn format_human(s: &StatsSnapshot, fmt: &NumberFormat, units: &crate::units::Units) -> String {
+    let mut lines: Vec<String> = summary_rows(s, fmt, units)
+        .into_iter()
+        .map(|(label, value)| format!("{:<8} {}", label, value))
+        .collect();
+    if !s.cpu.top_processes.is_empty() {
+        lines.push("Top processes:".to_string());
+        for p in &s.cpu.top_processes {
+            lines.push(format!(
+                "  {:>7}  {:>6}  {}",
+                fmt.percent(p.cpu as f64, 1),
+                p.pid,
+                p.name
+            ));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Markdown tables (metrics, then top processes) for bug reports and chat
+fn format_markdown(s: &StatsSnapshot, fmt: &NumberFormat, units: &crate::units::Units) -> String {
+    let cell = |text: &str| text.replace('|', "\\|");
+    let taken = chrono::DateTime::from_timestamp(s.timestamp, 0)
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_default();
+    let mut lines = vec![
+        format!("**mac-stats** {} ({})", env!("CARGO_PKG_VERSION"), taken),
+        String::new(),
+        "| Metric | Value |".to_string(),
+        "| --- | --- |".to_string(),
+    ];
+    for (label, value) in summary_rows(s, fmt, units) {
+        lines.push(format!("| {} | {} |", label, cell(&value)));
+    }
+    if !s.cpu.top_processes.is_empty() {
+        lines.push(String::new());
+        lines.push("| Process | PID | CPU |".to_string());
+        lines.push("| --- | ---: | ---: |".to_string());
+        for p in &s.cpu.top_processes {
+            lines.push(format!(
+                "| {} | {} | {} |",
+                cell(&p.name),
+                p.pid,
+                fmt.percent(p.cpu as f64, 1)
+            ));
+        }
+    }
+    lines.join("\n")
+}
+
+fn format_plain(s: &StatsSnapshot) -> String {
+    let (m, c) = (&s.metrics, &s.cpu);
+    let mut lines = vec![
+        format!("timestamp={}", s.timestamp),
+        format!("cpu={:.1}", m.cpu),
+        format!("gpu={:.1}", m.gpu),
+        format!("ram={:.1}", m.ram),
+        format!("disk={:.1}", m.disk),
+        format!("temperature={:.1}", c.temperature),
+        format!("gpu_temperature={:.1}", c.gpu_temperature),
+        format!("frequency_ghz={:.2}", c.frequency),
+        format!("cpu_power_w={:.2}", c.cpu_power),
+        format!("gpu_power_w={:.2}", c.gpu_power),
+        format!("fan_rpm={:.0}", c.fan_rpm),
+        format!("load_1={:.2}", c.load_1),
+        format!("load_5={:.2}", c.load_5),
+        format!("load_15={:.2}", c.load_15),
+        format!("uptime_secs={}", c.uptime_secs),
+    ];
+    if c.has_battery {
+        lines.push(format!("battery={:.0}", c.battery_level));
+        lines.push(format!("charging={}", c.is_charging));
+    }
+    lines.join("\n")
+}
+
+fn print_snapshot(args: &StatsArgs, s: &StatsSnapshot, fmt: &NumberFormat) -> Result<(), i32> {
+    if args.json {
+        match serde_json::to_string(s) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return Err(1);
+            }
+        }
+    } else if args.plain {
+        println!("{}", format_plain(s));
+    } else {
+        println!("{}", format_human(s, fmt, &crate::units::Units::current()));
+    }
+    Ok(())
+}
+
+/// Run `mac_stats stats`. Returns Err(exit_code) on failure.
+pub fn run(args: StatsArgs) -> Result<(), i32> {
+    let fmt = NumberFormat::current();
+    // CPU usage is a delta between two refreshes; take the first one now.
+    refresh_system();
+    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL.max(Duration::from_millis(500)));
+
+    let Some(interval) = args.watch else {
+        return print_snapshot(&args, &snapshot(), &fmt);
+    };
+    let interval = Duration::from_secs(interval.max(1));
+    let clear = !args.json && !args.plain && std::io::stdout().is_terminal();
+    loop {
+        let s = snapshot();
+        if clear {
+            print!("\x1b[2J\x1b[H");
+        }
+        print_snapshot(&args, &s, &fmt)?;
+        if args.plain {
+            println!();
+        }
+        std::thread::sleep(interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::ProcessUsage;
+
+    fn sample() -> StatsSnapshot {
+        StatsSnapshot {
+            timestamp: 1_700_000_000,
+            metrics: SystemMetrics {
+                cpu: 12.34,
+                gpu: 5.0,
+                ram: 61.2,
+                disk: 48.0,
+                plugins: Default::default(),
+            },
+            cpu: CpuDetails {
+                usage: 12.34,
+                temperature: 52.4,
+                frequency: 3.2,
+                p_core_frequency: 0.0,
+                e_core_frequency: 0.0,
+                cpu_power: 0.0,
+                gpu_power: 0.0,
+                gpu_temperature: 47.5,
+                ane_power: 0.0,
+                load_1: 1.5,
+                load_5: 2.0,
+                load_15: 2.25,
+                uptime_secs: 93_780,
+                top_processes: vec![ProcessUsage {
+                    name: "Safari".to_string(),
+                    cpu: 8.0,
+                    pid: 42,
+                    history: Vec::new(),
+                }],
+                chip_info: "Apple M3".to_string(),
+                can_read_temperature: true,
+                can_read_frequency: false,
+                can_read_cpu_power: false,
+                can_read_gpu_power: false,
+                can_read_gpu_temperature: true,
+                battery_level: 80.0,
+                is_charging: true,
+                has_battery: true,
+                battery_drain_percent_per_hour: 0.0,
+                battery_drain_watts: 0.0,
+                battery_remaining_secs: 0,
+                fan_rpm: 0.0,
+                fan_audible: false,
+                fan_audible_minutes_today: 0.0,
+                can_read_fan: false,
+                build_load: 0.0,
+                build_processes: 0,
+                build_elapsed_secs: 0,
+                last_build_secs: 0,
+            },
+        }
+    }
+
+    #[test]
+    fn plain_output_is_key_value() {
+        let out = format_plain(&sample());
+        assert!(out.starts_with("timestamp=1700000000\ncpu=12.3\n"));
+        assert!(out.contains("\ntemperature=52.4\n"));
+        assert!(out.contains("\ngpu_temperature=47.5\n"));
+        assert!(out.ends_with("battery=80\ncharging=true"));
+    }
+
+    #[test]
+    fn human_output_skips_unreadable_sensors() {
+        let out = format_human(
+            &sample(),
+            &NumberFormat::for_locale("en_US"),
+            &crate::units::Units::default(),
+        );
+        assert!(out.contains("CPU      12.3%"));
+        assert!(out.contains("Temp     52°C"));
+        assert!(!out.contains("Power"));
+        assert!(out.contains("Uptime   1d 2h 3m"));
+        assert!(out.contains("Battery  80% (charging)"));
+        assert!(out.contains("8.0%      42  Safari"));
+    }
+
+    #[test]
+    fn markdown_output_is_tables() {
+        let out = format_markdown(
+            &sample(),
+            &NumberFormat::for_locale("en_US"),
+            &crate::units::Units::default(),
+        );
+        assert!(out.starts_with("**mac-stats** "));
+        assert!(out.contains("| Metric | Value |\n| --- | --- |\n| CPU | 12.3% |\n"));
+        assert!(out.contains("| Battery | 80% (charging) |"));
+        assert!(!out.contains("| Power |"));
+        assert!(out.ends_with("| Safari | 42 | 8.0% |"));
+    }
+}
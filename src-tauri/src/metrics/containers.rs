@@ -0,0 +1,319 @@
+//! Docker container stats for the CPU window (`get_container_stats`)
+//!
+//! Optional (`dockerStats`). Talks plain HTTP/1.0 to the engine's Unix socket
+//! (`dockerSocket`): `GET /containers/json` for the running containers, then a one-shot
+//! `GET /containers/<id>/stats` each. One-shot stats carry no previous CPU reading, so CPU %
+//! is computed against the previous call's reading (first call: None), the same way
+//! `docker stats` does it. Docker Desktop runs containers in a Linux VM; CPU % and memory are
+//! relative to that VM (100% = one of its CPUs). Readings are cached for [`CACHE_TTL`].
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::config::Config;
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+const CACHE_TTL: Duration = Duration::from_secs(5);
+const SOCKET_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// One running container
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ContainerUsage {
+    /// Short (12 character) id
+    pub id: String,
+    pub name: String,
+    pub image: String,
+    /// Percent of one CPU; None until a second reading exists
+    pub cpu_percent: Option<f32>,
+    /// Memory in use, excluding reclaimable page cache
+    pub memory_bytes: u64,
+    /// Memory limit (the VM's memory when the container has none)
+    pub memory_limit_bytes: u64,
+}
+
+/// Result of `get_container_stats`
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ContainerStats {
+    /// `dockerStats` is on
+    pub enabled: bool,
+    /// Why the engine could not be read (not running, no permission, ...)
+    pub error: Option<String>,
+    /// Sorted by CPU, busiest first
+    pub containers: Vec<ContainerUsage>,
+}
+
+/// (container total CPU ns, host system CPU ns) from the previous reading, by id
+type CpuReadings = HashMap<String, (u64, u64)>;
+
+static PREVIOUS_CPU: Mutex<Option<CpuReadings>> = Mutex::new(None);
+static LATEST: Mutex<Option<(Instant, ContainerStats)>> = Mutex::new(None);
+
+/// Body of an HTTP/1.x response, de-chunked when needed
+fn response_body(response: &[u8]) -> Result<Vec<u8>, String> {
+    let split = response
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or("Malformed response from Docker")?;
+    let head = String::from_utf8_lossy(&response[..split]);
+    let body = &response[split + 4..];
+    let status = head
+        .lines()
+        .next()
+        .and_then(|l| l.split_whitespace().nth(1))
+        .and_then(|s| s.parse::<u16>().ok())
+        .ok_or("Malformed response from Docker")?;
+    if status != 200 {
+        let message = serde_json::from_slice::<serde_json::Value>(body)
+            .ok()
+            .and_then(|v| v.get("message").and_then(|m| m.as_str()).map(str::to_string))
+            .unwrap_or_else(|| format!("HTTP {}", status));
+        return Err(format!("Docker: {}", message));
+    }
+    let chunked = head.lines().any(|l| {
+        let l = l.to_ascii_lowercase();
+        l.starts_with("transfer-encoding:") && l.contains("chunked")
+    });
+    if !chunked {
+        return Ok(body.to_vec());
+    }
+    let mut out = Vec::new();
+    let mut rest = body;
+    loop {
+        let line_end = rest
+            .windows(2)
+            .position(|w| w == b"\r\n")
+            .ok_or("Truncated chunked response from Docker")?;
+        let size_line = String::from_utf8_lossy(&rest[..line_end]);
+        let size = usize::from_str_radix(size_line.split(';').next().unwrap_or("").trim(), 16)
+            .map_err(|_| "Bad chunk size from Docker".to_string())?;
+        rest = &rest[line_end + 2..];
+        if size == 0 {
+            return Ok(out);
+        }
+        if rest.len() < size {
+            return Err("Truncated chunked response from Docker".to_string());
+        }
+        out.extend_from_slice(&rest[..size]);
+        rest = rest.get(size + 2..).unwrap_or_default();
+    }
+}
+
+fn get_json(socket: &Path, path: &str) -> Result<serde_json::Value, String> {
+    let mut stream = UnixStream::connect(socket)
+        .map_err(|e| format!("Cannot connect to {}: {}", socket.display(), e))?;
+    stream
+        .set_read_timeout(Some(SOCKET_TIMEOUT))
+        .and_then(|_| stream.set_write_timeout(Some(SOCKET_TIMEOUT)))
+        .map_err(|e| e.to_string())?;
+    write!(
+        stream,
+        "GET {} HTTP/1.0\r\nHost: docker\r\nConnection: close\r\n\r\n",
+        path
+    )
+    .map_err(|e| e.to_string())?;
+    let mut response = Vec::new();
+    stream
+        .read_to_end(&mut response)
+        .map_err(|e| format!("Reading from Docker: {}", e))?;
+    let body = response_body(&response)?;
+    serde_json::from_slice(&body).map_err(|e| format!("Invalid JSON from Docker: {}", e))
+}
+
+/// Memory in use the way `docker stats` shows it: usage minus inactive page cache
+/// (`inactive_file` on cgroup v2, `total_inactive_file` / `cache` on v1)
+fn memory_used(memory: &serde_json::Value) -> u64 {
+    let usage = memory.get("usage").and_then(|v| v.as_u64()).unwrap_or(0);
+    let stats = memory.get("stats");
+    let cache = ["inactive_file", "total_inactive_file", "cache"]
+        .iter()
+        .find_map(|k| stats.and_then(|s| s.get(*k)).and_then(|v| v.as_u64()))
+        .unwrap_or(0);
+    usage.saturating_sub(cache)
+}
+
+/// CPU % of one CPU between two (container ns, system ns) readings
+fn cpu_percent(previous: (u64, u64), current: (u64, u64), online_cpus: u64) -> Option<f32> {
+    let container = current.0.checked_sub(previous.0)?;
+    let system = current.1.checked_sub(previous.1)?;
+    (system > 0).then(|| (container as f64 / system as f64 * online_cpus as f64 * 100.0) as f32)
+}
+
+/// Container row and its CPU reading from one `/containers/json` entry and its stats
+fn parse_container(
+    summary: &serde_json::Value,
+    stats: &serde_json::Value,
+    previous: Option<&CpuReadings>,
+) -> Option<(ContainerUsage, (u64, u64))> {
+    let id = summary.get("Id")?.as_str()?;
+    let name = summary
+        .get("Names")
+        .and_then(|n| n.get(0))
+        .and_then(|n| n.as_str())
+        .map(|n| n.trim_start_matches('/'))
+        .unwrap_or(id);
+    let cpu = stats.get("cpu_stats");
+    let reading = (
+        cpu.and_then(|c| c.pointer("/cpu_usage/total_usage"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0),
+        cpu.and_then(|c| c.get("system_cpu_usage"))
+            .and_then(|v| v.as_u64())
+            .unwrap_or(0),
+    );
+    let online_cpus = cpu
+        .and_then(|c| c.get("online_cpus"))
+        .and_then(|v| v.as_u64())
+        .filter(|n| *n > 0)
+        .unwrap_or(1);
+    let memory = stats.get("memory_stats").cloned().unwrap_or_default();
+    Some((
+        ContainerUsage {
+            id: id.chars().take(12).collect(),
+            name: name.to_string(),
+            image: summary
+                .get("Image")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            cpu_percent: previous
+                .and_then(|p| p.get(id))
+                .and_then(|prev| cpu_percent(*prev, reading, online_cpus)),
+            memory_bytes: memory_used(&memory),
+            memory_limit_bytes: memory.get("limit").and_then(|v| v.as_u64()).unwrap_or(0),
+        },
+        reading,
+    ))
+}
+
+/// Query the engine (blocking: one request per running container)
+fn read(socket: &Path) -> Result<Vec<ContainerUsage>, String> {
+    let list = get_json(socket, "/containers/json")?;
+    let previous = PREVIOUS_CPU.lock().ok().and_then(|p| p.clone());
+    let mut readings = CpuReadings::new();
+    let mut containers = Vec::new();
+    for summary in list.as_array().map(Vec::as_slice).unwrap_or_default() {
+        let Some(id) = summary.get("Id").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        let stats = match get_json(
+            socket,
+            &format!("/containers/{}/stats?stream=false&one-shot=true", id),
+        ) {
+            Ok(stats) => stats,
+            Err(e) => {
+                // Stopped between the two requests
+                debug2!("Container stats for {} failed: {}", id, e);
+                continue;
+            }
+        };
+        if let Some((usage, reading)) = parse_container(summary, &stats, previous.as_ref()) {
+            readings.insert(id.to_string(), reading);
+            containers.push(usage);
+        }
+    }
+    if let Ok(mut p) = PREVIOUS_CPU.lock() {
+        *p = Some(readings);
+    }
+    containers.sort_by(|a, b| {
+        b.cpu_percent
+            .unwrap_or(0.0)
+            .total_cmp(&a.cpu_percent.unwrap_or(0.0))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    Ok(containers)
+}
+
+/// Per-container CPU and memory from the local Docker engine (empty when `dockerStats` is off)
+#[tauri::command]
+pub async fn get_container_stats() -> Result<ContainerStats, String> {
+    if !Config::docker_stats_enabled() {
+        return Ok(ContainerStats::default());
+    }
+    let cached = LATEST.lock().ok().and_then(|l| {
+        l.as_ref()
+            .filter(|(at, _)| at.elapsed() < CACHE_TTL)
+            .map(|(_, s)| s.clone())
+    });
+    if let Some(stats) = cached {
+        return Ok(stats);
+    }
+    let socket = Config::docker_socket();
+    let result = tokio::task::spawn_blocking(move || read(&socket))
+        .await
+        .map_err(|e| e.to_string())?;
+    let stats = match result {
+        Ok(containers) => ContainerStats {
+            enabled: true,
+            error: None,
+            containers,
+        },
+        Err(e) => {
+            debug2!("Docker stats unavailable: {}", e);
+            ContainerStats {
+                enabled: true,
+                error: Some(e),
+                containers: Vec::new(),
+            }
+        }
+    };
+    if let Ok(mut latest) = LATEST.lock() {
+        *latest = Some((Instant::now(), stats.clone()));
+    }
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_responses_and_stats() {
+        let plain = b"HTTP/1.0 200 OK\r\nContent-Type: application/json\r\n\r\n[]";
+        assert_eq!(response_body(plain).unwrap(), b"[]");
+        let chunked =
+            b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n3\r\n{\"a\r\n4\r\n\":1}\r\n0\r\n\r\n";
+        assert_eq!(response_body(chunked).unwrap(), b"{\"a\":1}");
+        let missing = b"HTTP/1.0 404 Not Found\r\n\r\n{\"message\":\"No such container: x\"}";
+        assert_eq!(
+            response_body(missing).unwrap_err(),
+            "Docker: No such container: x"
+        );
+
+        let summary = serde_json::json!({
+            "Id": "0123456789abcdef0123",
+            "Names": ["/postgres"],
+            "Image": "postgres:16"
+        });
+        let stats = serde_json::json!({
+            "cpu_stats": {
+                "cpu_usage": {"total_usage": 3_000_000_000u64},
+                "system_cpu_usage": 40_000_000_000u64,
+                "online_cpus": 4
+            },
+            "memory_stats": {
+                "usage": 300_000_000u64,
+                "limit": 8_000_000_000u64,
+                "stats": {"inactive_file": 100_000_000u64}
+            }
+        });
+        let (first, reading) = parse_container(&summary, &stats, None).unwrap();
+        assert_eq!(first.id, "0123456789ab");
+        assert_eq!(first.name, "postgres");
+        assert_eq!(first.cpu_percent, None);
+        assert_eq!(first.memory_bytes, 200_000_000);
+        assert_eq!(reading, (3_000_000_000, 40_000_000_000));
+
+        // 1 s of container CPU over 8 s of system CPU time on 4 CPUs = 50% of one CPU
+        let previous: CpuReadings =
+            [("0123456789abcdef0123".to_string(), (2_000_000_000, 32_000_000_000))].into();
+        let (second, _) = parse_container(&summary, &stats, Some(&previous)).unwrap();
+        assert_eq!(second.cpu_percent, Some(50.0));
+    }
+}
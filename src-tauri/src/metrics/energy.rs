@@ -0,0 +1,308 @@
+//! Energy estimates from the power samples in history (`get_energy_report`, `mac_stats energy`)
+//!
+//! Each history point counts until the next one; stretches inside a recorded gap (sleep, app
+//! not running) count for nothing. A point's power is the battery discharge when the Mac ran
+//! on battery (the whole machine's draw), otherwise the measured CPU+GPU power, which is only
+//! sampled while the CPU window is open. Days and sessions therefore cover the time power was
+//! known; `measured_secs` says how much of it that was.
+
+use std::collections::BTreeMap;
+
+use chrono::TimeZone;
+use serde::Serialize;
+
+use super::history::{HistoryGap, MetricPoint};
+use super::history_store;
+use super::sessions;
+use crate::number_format::NumberFormat;
+use crate::state::METRICS_HISTORY;
+
+/// Longest step one point can stand for (the hourly tier's spacing)
+const MAX_STEP_SECS: i64 = 3600;
+/// Days in the report when none are requested
+const DEFAULT_DAYS: u32 = 7;
+/// Saved sessions in the report
+const REPORT_SESSIONS: usize = 20;
+
+/// Watts a history point stands for (0 when nothing was measured)
+pub fn watts(p: &MetricPoint) -> f32 {
+    if p.battery_power > 0.0 {
+        p.battery_power
+    } else {
+        p.cpu_power + p.gpu_power
+    }
+}
+
+/// Seconds between `a` and `b` that are not inside a gap, capped at [`MAX_STEP_SECS`]
+fn covered_secs(a: i64, b: i64, gaps: &[HistoryGap]) -> i64 {
+    let in_gaps: i64 = gaps
+        .iter()
+        .map(|g| (g.end.min(b) - g.start.max(a)).max(0))
+        .sum();
+    (b - a - in_gaps).clamp(0, MAX_STEP_SECS)
+}
+
+/// (start timestamp, seconds, watts) for each point that measured power
+fn steps<'a>(
+    points: &'a [MetricPoint],
+    gaps: &'a [HistoryGap],
+) -> impl Iterator<Item = (i64, i64, f32)> + 'a {
+    points.windows(2).filter_map(|w| {
+        let w0 = watts(&w[0]);
+        (w0 > 0.0).then(|| {
+            (
+                w[0].timestamp,
+                covered_secs(w[0].timestamp, w[1].timestamp, gaps),
+                w0,
+            )
+        })
+    })
+}
+
+/// Watt-hours over `points` (sorted by time); None when no point measured power
+pub fn integrate_wh(points: &[MetricPoint], gaps: &[HistoryGap]) -> Option<f64> {
+    if !points.iter().any(|p| watts(p) > 0.0) {
+        return None;
+    }
+    let joules: f64 = steps(points, gaps)
+        .map(|(_, secs, w)| w as f64 * secs as f64)
+        .sum();
+    Some(joules / 3600.0)
+}
+
+/// Energy for one local calendar day
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DayEnergy {
+    /// `YYYY-MM-DD` (local time)
+    pub date: String,
+    pub energy_wh: f64,
+    /// Seconds with a power measurement
+    pub measured_secs: u64,
+}
+
+/// Energy per local day, oldest first (steps are booked on the day they start)
+pub fn daily<Tz: TimeZone>(points: &[MetricPoint], gaps: &[HistoryGap], tz: &Tz) -> Vec<DayEnergy> {
+    let mut days: BTreeMap<String, (f64, u64)> = BTreeMap::new();
+    for (start, secs, w) in steps(points, gaps) {
+        let Some(date) = tz.timestamp_opt(start, 0).single() else {
+            continue;
+        };
+        let day = days
+            .entry(date.date_naive().format("%Y-%m-%d").to_string())
+            .or_default();
+        day.0 += w as f64 * secs as f64 / 3600.0;
+        day.1 += secs as u64;
+    }
+    days.into_iter()
+        .map(|(date, (energy_wh, measured_secs))| DayEnergy {
+            date,
+            energy_wh,
+            measured_secs,
+        })
+        .collect()
+}
+
+/// Energy of one app run (from its saved summary, or the running one)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SessionEnergy {
+    /// Summary id (`mac_stats sessions <id>`); None for the running session
+    pub id: Option<String>,
+    pub started_at: i64,
+    pub duration_secs: u64,
+    pub energy_wh: Option<f64>,
+}
+
+/// Result of `get_energy_report`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EnergyReport {
+    pub days: Vec<DayEnergy>,
+    /// Running session first, then saved sessions, newest first
+    pub sessions: Vec<SessionEnergy>,
+    /// Sum over `days`
+    pub total_wh: f64,
+}
+
+fn build_report(
+    points: &[MetricPoint],
+    gaps: &[HistoryGap],
+    current: Option<SessionEnergy>,
+) -> EnergyReport {
+    let days = daily(points, gaps, &chrono::Local);
+    let total_wh = days.iter().map(|d| d.energy_wh).sum();
+    let sessions = current
+        .into_iter()
+        .chain(
+            sessions::recent(REPORT_SESSIONS)
+                .into_iter()
+                .map(|(id, s)| SessionEnergy {
+                    id: Some(id),
+                    started_at: s.started_at,
+                    duration_secs: s.duration_secs,
+                    energy_wh: s.energy_wh,
+                }),
+        )
+        .collect();
+    EnergyReport {
+        days,
+        sessions,
+        total_wh,
+    }
+}
+
+/// Start of the local day `days - 1` days ago
+fn range_start(days: u32, now: i64) -> i64 {
+    let max_days = (history_store::max_range_secs() / 86400).max(1) as u32;
+    let days = days.clamp(1, max_days);
+    let today = chrono::Local
+        .timestamp_opt(now, 0)
+        .single()
+        .map(|t| t.date_naive())
+        .unwrap_or_default();
+    let first = today - chrono::Duration::days(i64::from(days) - 1);
+    first
+        .and_hms_opt(0, 0, 0)
+        .and_then(|t| chrono::Local.from_local_datetime(&t).earliest())
+        .map(|t| t.timestamp())
+        .unwrap_or(now - i64::from(days) * 86400)
+}
+
+/// Watt-hour estimates per day (last `days`, default 7, today included) and per session
+#[tauri::command]
+pub fn get_energy_report(days: Option<u32>) -> Result<EnergyReport, String> {
+    let now = chrono::Utc::now().timestamp();
+    let start = range_start(days.unwrap_or(DEFAULT_DAYS), now);
+    // Ranges past the in-memory tiers come from the SQLite store (queried before locking history)
+    let stored = history_store::query_long_range((now - start).max(0) as u64);
+    let history = METRICS_HISTORY
+        .try_lock()
+        .map_err(|_| "History buffer temporarily unavailable".to_string())?;
+    let Some(history) = history.as_ref() else {
+        return Ok(build_report(&[], &[], None));
+    };
+    let gaps = history.gaps_between(start, now);
+    let points = match stored {
+        Some(points) => points?,
+        None => history.points_between(start, now + 1),
+    };
+    let current = sessions::current_started_at().map(|started_at| {
+        let session_points: Vec<MetricPoint> = points
+            .iter()
+            .filter(|p| p.timestamp >= started_at)
+            .cloned()
+            .collect();
+        SessionEnergy {
+            id: None,
+            started_at,
+            duration_secs: (now - started_at).max(0) as u64,
+            energy_wh: integrate_wh(&session_points, &gaps),
+        }
+    });
+    Ok(build_report(&points, &gaps, current))
+}
+
+/// Arguments for `mac_stats energy`
+#[derive(clap::Args, Debug)]
+pub struct EnergyArgs {
+    /// Days to report, today included
+    #[arg(long, default_value_t = DEFAULT_DAYS)]
+    pub days: u32,
+    /// JSON (`{days, sessions, total_wh}`) instead of text
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Run `mac_stats energy`: reads the persisted history, so it works whether or not the app is
+/// running. Returns Err(exit_code) on failure.
+pub fn run_cli(args: EnergyArgs) -> Result<(), i32> {
+    let fail = |e: String| {
+        eprintln!("Error: {}", e);
+        1
+    };
+    let now = chrono::Utc::now().timestamp();
+    let start = range_start(args.days, now);
+    let (points, gaps) = history_store::load_persisted_since(start).map_err(fail)?;
+    let report = build_report(&points, &gaps, None);
+    if args.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).map_err(|e| fail(e.to_string()))?
+        );
+        return Ok(());
+    }
+    let fmt = NumberFormat::current();
+    println!("{:<10}  {:>9}  {:>9}", "Day", "Energy", "Measured");
+    for day in &report.days {
+        println!(
+            "{:<10}  {:>9}  {:>9}",
+            day.date,
+            fmt.with_unit(day.energy_wh, 1, "Wh"),
+            super::cli::format_duration(day.measured_secs)
+        );
+    }
+    println!(
+        "{:<10}  {:>9}",
+        "Total",
+        fmt.with_unit(report.total_wh, 1, "Wh")
+    );
+    if !report.sessions.is_empty() {
+        println!();
+        println!("{:<16}  {:>9}  {:>9}", "Session", "Duration", "Energy");
+        for s in &report.sessions {
+            println!(
+                "{:<16}  {:>9}  {:>9}",
+                sessions::format_started(s.started_at),
+                super::cli::format_duration(s.duration_secs),
+                sessions::format_energy(s.energy_wh, &fmt)
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::history::GapReason;
+
+    fn point(timestamp: i64, cpu_power: f32, battery_power: f32) -> MetricPoint {
+        let mut p = MetricPoint::from_metrics(
+            10.0, 0.0, 50.0, 40.0, 0.0, 0.0, 0.0, 0.0, cpu_power, 0.0, 80.0,
+        );
+        p.timestamp = timestamp;
+        p.battery_power = battery_power;
+        p
+    }
+
+    #[test]
+    fn integrates_power_per_day_skipping_gaps() {
+        let utc = chrono::Utc;
+        // 2023-11-14 23:59:00 UTC
+        let t0 = 1_700_006_340;
+        let points = vec![
+            point(t0, 4.0, 0.0),
+            // Battery discharge wins over CPU power; the day changes here
+            point(t0 + 60, 4.0, 12.0),
+            point(t0 + 120, 0.0, 0.0),
+            point(t0 + 180, 6.0, 0.0),
+            point(t0 + 7380, 6.0, 0.0),
+        ];
+        let gaps = [HistoryGap {
+            start: t0 + 180,
+            end: t0 + 7380,
+            reason: GapReason::Sleep,
+        }];
+        // 60s × 4 W + 60s × 12 W; the 2h step is a gap and the last point has no successor
+        let wh = integrate_wh(&points, &gaps).unwrap();
+        assert!((wh - 960.0 / 3600.0).abs() < 1e-9);
+        assert_eq!(integrate_wh(&points[2..3], &[]), None);
+
+        let days = daily(&points, &gaps, &utc);
+        assert_eq!(days.len(), 2);
+        assert_eq!(days[0].date, "2023-11-14");
+        assert_eq!(days[0].measured_secs, 60);
+        assert_eq!(days[1].date, "2023-11-15");
+        assert!((days[1].energy_wh - 720.0 / 3600.0).abs() < 1e-9);
+        // Without the gap, a step counts for at most an hour
+        assert_eq!(daily(&points, &[], &utc)[1].measured_secs, 60 + 3600);
+    }
+}
@@ -0,0 +1,314 @@
+//! History export to CSV or JSON
+//!
+//! `export_history` (Tauri command, to a file picked in a save panel) and `mac_stats export`
+//! (CLI) write the history buffer for a time range to a file for spreadsheets and notebooks. Ranges use the same tiers as the
+//! charts (1s samples for the last 5 minutes up to hourly averages for 7 days); with the
+//! SQLite history store, longer ranges come from its hourly rollups.
+//!
+//! CSV has one row per point with a Unix `timestamp`, a `local_time` column spreadsheets
+//! recognise as a date, and the metric columns (I/O rates in bytes/s); unreadable sensors (no temperature, power or
+//! battery) are left empty. The header matches what `import_metrics_history` reads back.
+
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, TimeZone};
+use serde::Serialize;
+
+use super::history::{parse_range_up_to, Aggregation, MetricPoint};
+use super::history_store::{self, HistoryStore};
+use crate::state::METRICS_HISTORY;
+
+const CSV_HEADER: &str = "timestamp,local_time,cpu,gpu,ram,disk,temperature,frequency,\
+p_core_frequency,e_core_frequency,cpu_power,gpu_power,battery_level,net_rx,net_tx,disk_read,\
+disk_write,battery_power,adapter_power";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    /// `csv`/`json`, or inferred from the file extension when `format` is empty (CSV otherwise)
+    pub fn resolve(format: Option<&str>, path: Option<&Path>) -> Result<Self, String> {
+        let name = match format.map(str::trim).filter(|f| !f.is_empty()) {
+            Some(f) => f.to_ascii_lowercase(),
+            None => path
+                .and_then(|p| p.extension())
+                .and_then(|e| e.to_str())
+                .unwrap_or("csv")
+                .to_ascii_lowercase(),
+        };
+        match name.as_str() {
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            other => Err(format!(
+                "Unknown export format '{}' (use csv or json)",
+                other
+            )),
+        }
+    }
+}
+
+/// JSON export document
+#[derive(Serialize)]
+struct HistoryExport<'a> {
+    exported_at: i64,
+    time_range_seconds: u64,
+    /// Machine `labels` from config (omitted when none are set)
+    #[serde(skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    labels: std::collections::BTreeMap<String, String>,
+    points: &'a [MetricPoint],
+}
+
+/// Result of an export (shown in the UI after the file is written)
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryExportSummary {
+    pub path: String,
+    pub points: usize,
+    pub oldest_timestamp: Option<i64>,
+    pub newest_timestamp: Option<i64>,
+}
+
+/// Empty cell for a sensor that was not readable
+fn optional_cell(value: f32, readable: bool, decimals: usize) -> String {
+    if readable {
+        format!("{:.*}", decimals, value)
+    } else {
+        String::new()
+    }
+}
+
+fn encode_csv(points: &[MetricPoint]) -> String {
+    let mut out = String::with_capacity((points.len() + 1) * 96);
+    out.push_str(CSV_HEADER);
+    out.push('\n');
+    for p in points {
+        let local_time = Local
+            .timestamp_opt(p.timestamp, 0)
+            .single()
+            .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_default();
+        let cells = [
+            p.timestamp.to_string(),
+            local_time,
+            format!("{:.1}", p.cpu),
+            format!("{:.1}", p.gpu),
+            format!("{:.1}", p.ram),
+            format!("{:.1}", p.disk),
+            optional_cell(p.temperature, p.temperature > 0.0, 1),
+            optional_cell(p.frequency, p.frequency > 0.0, 2),
+            optional_cell(p.p_core_frequency, p.p_core_frequency > 0.0, 2),
+            optional_cell(p.e_core_frequency, p.e_core_frequency > 0.0, 2),
+            optional_cell(p.cpu_power, p.cpu_power > 0.0, 2),
+            optional_cell(p.gpu_power, p.gpu_power > 0.0, 2),
+            optional_cell(p.battery_level, p.battery_level >= 0.0, 0),
+            format!("{:.0}", p.net_rx),
+            format!("{:.0}", p.net_tx),
+            format!("{:.0}", p.disk_read),
+            format!("{:.0}", p.disk_write),
+            optional_cell(p.battery_power, p.battery_power > 0.0, 2),
+            optional_cell(p.adapter_power, p.adapter_power > 0.0, 0),
+        ];
+        out.push_str(&cells.join(","));
+        out.push('\n');
+    }
+    out
+}
+
+fn encode_json(points: &[MetricPoint], time_range_seconds: u64) -> Result<String, String> {
+    serde_json::to_string_pretty(&HistoryExport {
+        exported_at: chrono::Utc::now().timestamp(),
+        time_range_seconds,
+        labels: crate::config::Config::labels().into_iter().collect(),
+        points,
+    })
+    .map_err(|e| format!("Serialization error: {}", e))
+}
+
+/// Encode the points of a `range_secs` export in `format`
+fn encode(points: &[MetricPoint], range_secs: u64, format: ExportFormat) -> Result<String, String> {
+    match format {
+        ExportFormat::Csv => Ok(encode_csv(points)),
+        ExportFormat::Json => encode_json(points, range_secs),
+    }
+}
+
+fn parse_range_arg(range: &str) -> Result<u64, String> {
+    parse_range_up_to(range, history_store::max_range_secs()).ok_or_else(|| {
+        format!(
+            "Invalid range '{}' (use 5m, 1h, 6h, 24h, 7d or seconds)",
+            range
+        )
+    })
+}
+
+/// Let the user pick where the export goes (a native save panel, which also asks before
+/// replacing a file); None when they cancel
+async fn choose_path(app: &tauri::AppHandle, range: &str, format: ExportFormat) -> Option<PathBuf> {
+    use tauri_plugin_dialog::DialogExt;
+
+    let extension = match format {
+        ExportFormat::Csv => "csv",
+        ExportFormat::Json => "json",
+    };
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    let mut dialog = app
+        .dialog()
+        .file()
+        .add_filter(extension.to_uppercase(), &[extension])
+        .set_file_name(format!("mac-stats-history-{}.{}", range.trim(), extension));
+    if let Ok(home) = std::env::var("HOME") {
+        dialog = dialog.set_directory(Path::new(&home).join("Downloads"));
+    }
+    dialog.save_file(move |path| {
+        let _ = tx.send(path.and_then(|p| p.into_path().ok()));
+    });
+    rx.await.ok().flatten()
+}
+
+/// Export the metrics history for `range` (`1h`, `24h`, `7d`, ...) as CSV (default) or JSON to
+/// a file the user picks in a save panel; fails with "Cancelled" when they close it
+#[tauri::command]
+pub async fn export_history(
+    app: tauri::AppHandle,
+    range: String,
+    format: Option<String>,
+) -> Result<HistoryExportSummary, String> {
+    crate::guest_mode::ensure_allowed("History export")?;
+    let range_secs = parse_range_arg(&range)?;
+    let format = ExportFormat::resolve(format.as_deref(), None)?;
+    let path = choose_path(&app, &range, format)
+        .await
+        .ok_or_else(|| "Cancelled".to_string())?;
+
+    let points = match history_store::query_long_range(range_secs) {
+        Some(points) => points?,
+        None => {
+            let history = METRICS_HISTORY
+                .lock()
+                .map_err(|_| "History buffer temporarily unavailable".to_string())?;
+            history
+                .as_ref()
+                .ok_or_else(|| "History not yet initialized".to_string())?
+                .query(range_secs, None, Aggregation::Avg)
+        }
+    };
+    let text = encode(&points, range_secs, format)?;
+    crate::config::write_text_atomic(&path, &text)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    let summary = HistoryExportSummary {
+        path: path.display().to_string(),
+        points: points.len(),
+        oldest_timestamp: points.first().map(|p| p.timestamp),
+        newest_timestamp: points.last().map(|p| p.timestamp),
+    };
+    tracing::info!(
+        "History export: {} point(s) over {}s to {}",
+        summary.points,
+        range_secs,
+        summary.path
+    );
+    Ok(summary)
+}
+
+/// Arguments for `mac_stats export`
+#[derive(clap::Args, Debug)]
+pub struct ExportArgs {
+    /// Output file; stdout when omitted
+    pub path: Option<PathBuf>,
+    /// Time range: 5m, 1h, 6h, 24h, 7d or seconds
+    #[arg(long, default_value = "24h")]
+    pub range: String,
+    /// csv or json (default: from the file extension, else csv)
+    #[arg(long)]
+    pub format: Option<String>,
+}
+
+/// Run `mac_stats export`: reads the persisted history (SQLite store, or snapshot + log), so it
+/// works whether or not the app is running. Returns Err(exit_code) on failure.
+pub fn run_cli(args: ExportArgs) -> Result<(), i32> {
+    let fail = |e: String| {
+        eprintln!("Error: {}", e);
+        1
+    };
+    let range_secs = parse_range_arg(&args.range).map_err(fail)?;
+    let format =
+        ExportFormat::resolve(args.format.as_deref(), args.path.as_deref()).map_err(fail)?;
+    let db_path = crate::config::Config::history_db_path();
+    let points = if crate::config::Config::history_sqlite_enabled() && db_path.exists() {
+        let start = chrono::Utc::now().timestamp() - range_secs as i64;
+        HistoryStore::open_read_only(&db_path)
+            .and_then(|store| store.points_between(start, i64::MAX))
+            .map_err(fail)?
+    } else {
+        super::history_wal::HistoryWal::load_read_only()
+            .map_err(fail)?
+            .query(range_secs, None, Aggregation::Avg)
+    };
+    let text = encode(&points, range_secs, format).map_err(fail)?;
+    match args.path {
+        Some(path) => {
+            crate::config::write_text_atomic(&path, &text)
+                .map_err(|e| fail(format!("Failed to write {}: {}", path.display(), e)))?;
+            eprintln!("Wrote {} point(s) to {}", points.len(), path.display());
+        }
+        None => print!("{}", text),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_format_from_argument_or_extension() {
+        let json = Path::new("/tmp/h.JSON");
+        assert_eq!(
+            ExportFormat::resolve(None, Some(json)),
+            Ok(ExportFormat::Json)
+        );
+        assert_eq!(
+            ExportFormat::resolve(Some("csv"), Some(json)),
+            Ok(ExportFormat::Csv)
+        );
+        assert_eq!(ExportFormat::resolve(None, None), Ok(ExportFormat::Csv));
+        assert!(ExportFormat::resolve(Some("xlsx"), None).is_err());
+    }
+
+    #[test]
+    fn csv_leaves_unreadable_sensors_empty() {
+        let mut p =
+            MetricPoint::from_metrics(12.5, 3.0, 61.0, 48.0, 0.0, 3.2, 0.0, 0.0, 4.5, 0.0, -1.0);
+        p.timestamp = 1_700_000_000;
+        let csv = encode_csv(&[p]);
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some(CSV_HEADER));
+        let row: Vec<&str> = lines.next().unwrap().split(',').collect();
+        assert_eq!(row.len(), CSV_HEADER.split(',').count());
+        assert_eq!(row[0], "1700000000");
+        assert_eq!(&row[2..8], &["12.5", "3.0", "61.0", "48.0", "", "3.20"]);
+        assert_eq!(row[10], "4.50");
+        assert_eq!(row[12], "");
+    }
+
+    #[test]
+    fn csv_round_trips_through_import() {
+        let mut p =
+            MetricPoint::from_metrics(12.5, 3.0, 61.0, 48.0, 55.0, 3.2, 0.0, 0.0, 4.5, 1.0, 80.0);
+        p.timestamp = chrono::Utc::now().timestamp() - 60;
+        p.net_rx = 125_000.0;
+        let parsed =
+            super::super::import::parse_history_csv(&encode_csv(std::slice::from_ref(&p))).unwrap();
+        assert_eq!(parsed.points.len(), 1);
+        let back = &parsed.points[0];
+        assert_eq!(back.timestamp, p.timestamp);
+        assert_eq!(
+            (back.cpu, back.temperature, back.battery_level),
+            (12.5, 55.0, 80.0)
+        );
+        assert_eq!(back.net_rx, 125_000.0);
+    }
+}
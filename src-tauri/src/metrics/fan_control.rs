@@ -0,0 +1,247 @@
+//! Fan speed override (set a target RPM or return to automatic)
+//!
+//! Reading fans needs no privileges; forcing them does, so [`apply`] runs in the privileged
+//! helper (`privileged_helper`, `fan` request) and the app only asks for it after the user
+//! confirmed (`set_fan_speed`). Targets are clamped to each fan's SMC minimum and maximum.
+//!
+//! SMC keys: `FNum` (fan count), `F<n>Ac` / `F<n>Mn` / `F<n>Mx` / `F<n>Tg` (actual, min, max,
+//! target RPM). Apple Silicon switches a fan to manual with `F<n>Md = 1` (newer SMCs only
+//! accept it after `Ftst = 1`); Intel sets bit `n` of `FS! `.
+//!
+//! Overrides must not outlive mac-stats: while any fan is forced the app pings the helper
+//! every [`KEEPALIVE`], and the helper returns its forced fans to automatic before it quits
+//! idle (app crashed or killed). Quitting normally restores them right away ([`restore_all`]).
+
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::ffi::smc::RawSmc;
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+/// Ping interval while fans are forced (the helper quits, restoring them, after 2 minutes idle)
+const KEEPALIVE: Duration = Duration::from_secs(30);
+
+/// Fans this process forced (app: asked the helper; helper: wrote to SMC)
+static FORCED: Mutex<BTreeSet<u32>> = Mutex::new(BTreeSet::new());
+/// The app's keepalive thread is running
+static KEEPALIVE_RUNNING: AtomicBool = AtomicBool::new(false);
+
+/// One fan (`get_fan_control_status`)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FanStatus {
+    pub index: u32,
+    pub rpm: f32,
+    pub min_rpm: f32,
+    pub max_rpm: f32,
+    pub target_rpm: f32,
+    /// Forced to `target_rpm` instead of SMC automatic control
+    pub manual: bool,
+}
+
+/// RPM from a fan key (`flt ` little-endian f32 on Apple Silicon, `fpe2` on Intel)
+fn decode_rpm(data_type: &[u8; 4], bytes: &[u8]) -> Option<f32> {
+    match (data_type, bytes) {
+        (b"flt ", [a, b, c, d, ..]) => Some(f32::from_le_bytes([*a, *b, *c, *d])),
+        (b"fpe2", [a, b, ..]) => Some(f32::from(u16::from_be_bytes([*a, *b])) / 4.0),
+        _ => None,
+    }
+}
+
+/// Bytes for a fan key of `data_type`; `None` for types fans do not use
+fn encode_rpm(data_type: &[u8; 4], rpm: f32) -> Option<Vec<u8>> {
+    match data_type {
+        b"flt " => Some(rpm.to_le_bytes().to_vec()),
+        b"fpe2" => Some(
+            ((rpm.max(0.0) * 4.0).round().min(f32::from(u16::MAX)) as u16)
+                .to_be_bytes()
+                .to_vec(),
+        ),
+        _ => None,
+    }
+}
+
+fn read_rpm(smc: &RawSmc, key: &str) -> Option<f32> {
+    let value = smc.read(key).ok()??;
+    decode_rpm(&value.data_type, &value.bytes).filter(|rpm| rpm.is_finite())
+}
+
+fn read_u8(smc: &RawSmc, key: &str) -> Option<u8> {
+    smc.read(key).ok()?.and_then(|v| v.bytes.first().copied())
+}
+
+/// Intel force bitmask (`FS! `, 2 bytes big-endian); `None` on Apple Silicon
+fn read_force_bits(smc: &RawSmc) -> Option<u16> {
+    let value = smc.read("FS! ").ok()??;
+    match value.bytes[..] {
+        [hi, lo, ..] => Some(u16::from_be_bytes([hi, lo])),
+        _ => None,
+    }
+}
+
+fn fan_count(smc: &RawSmc) -> u32 {
+    read_u8(smc, "FNum").map_or(0, u32::from)
+}
+
+/// Every fan SMC reports; empty on fanless Macs
+pub fn fans(smc: &RawSmc) -> Vec<FanStatus> {
+    let force_bits = read_force_bits(smc);
+    (0..fan_count(smc))
+        .map(|i| FanStatus {
+            index: i,
+            rpm: read_rpm(smc, &format!("F{}Ac", i)).unwrap_or(0.0),
+            min_rpm: read_rpm(smc, &format!("F{}Mn", i)).unwrap_or(0.0),
+            max_rpm: read_rpm(smc, &format!("F{}Mx", i)).unwrap_or(0.0),
+            target_rpm: read_rpm(smc, &format!("F{}Tg", i)).unwrap_or(0.0),
+            manual: match force_bits {
+                Some(bits) => bits & (1 << i) != 0,
+                None => read_u8(smc, &format!("F{}Md", i)).is_some_and(|m| m == 1),
+            },
+        })
+        .collect()
+}
+
+/// Read all fans (no privileges needed)
+pub fn status() -> Result<Vec<FanStatus>, String> {
+    Ok(fans(&RawSmc::open()?))
+}
+
+fn set_manual(smc: &RawSmc, fan: u32, manual: bool) -> Result<(), String> {
+    if let Some(bits) = read_force_bits(smc) {
+        let bits = if manual {
+            bits | (1 << fan)
+        } else {
+            bits & !(1 << fan)
+        };
+        return smc.write("FS! ", &bits.to_be_bytes());
+    }
+    if manual && smc.has_key("Ftst") {
+        smc.write("Ftst", &[1])?;
+    }
+    smc.write(&format!("F{}Md", fan), &[u8::from(manual)])?;
+    if !manual && smc.has_key("Ftst") && !fans(smc).iter().any(|f| f.manual) {
+        smc.write("Ftst", &[0])?;
+    }
+    Ok(())
+}
+
+/// Force `fan` to `target` RPM (clamped to its range) or return it to automatic (`None`).
+/// Needs root: runs in the privileged helper.
+pub fn apply(fan: u32, target: Option<f32>) -> Result<FanStatus, String> {
+    let smc = RawSmc::open()?;
+    let current = fans(&smc)
+        .into_iter()
+        .find(|f| f.index == fan)
+        .ok_or_else(|| format!("No fan {}", fan))?;
+    match target {
+        Some(rpm) => {
+            let rpm = rpm.clamp(current.min_rpm, current.max_rpm.max(current.min_rpm));
+            let key = format!("F{}Tg", fan);
+            let value = smc
+                .read(&key)?
+                .ok_or_else(|| format!("Fan {} has no target key", fan))?;
+            let bytes = encode_rpm(&value.data_type, rpm)
+                .ok_or_else(|| format!("Unsupported fan target type for {}", key))?;
+            set_manual(&smc, fan, true)?;
+            smc.write(&key, &bytes)?;
+            if let Ok(mut forced) = FORCED.lock() {
+                forced.insert(fan);
+            }
+        }
+        None => {
+            set_manual(&smc, fan, false)?;
+            if let Ok(mut forced) = FORCED.lock() {
+                forced.remove(&fan);
+            }
+        }
+    }
+    fans(&smc)
+        .into_iter()
+        .find(|f| f.index == fan)
+        .ok_or_else(|| format!("No fan {}", fan))
+}
+
+/// Helper side: return every fan it forced to automatic (before quitting)
+pub fn release_forced() {
+    let forced: Vec<u32> = FORCED
+        .lock()
+        .map(|f| f.iter().copied().collect())
+        .unwrap_or_default();
+    for fan in forced {
+        let _ = apply(fan, None);
+    }
+}
+
+/// App side: ask the helper to force `fan` to `rpm`, or return it to automatic (`None`)
+pub fn set_fan_speed(fan: u32, rpm: Option<f32>) -> Result<FanStatus, String> {
+    let status = crate::privileged_helper::set_fan(fan, rpm)?;
+    if let Ok(mut forced) = FORCED.lock() {
+        if status.manual {
+            forced.insert(fan);
+        } else {
+            forced.remove(&fan);
+        }
+    }
+    tracing::info!(
+        "Fan {}: {}",
+        fan,
+        if status.manual {
+            format!("forced to {:.0} RPM", status.target_rpm)
+        } else {
+            "automatic".to_string()
+        }
+    );
+    if status.manual && !KEEPALIVE_RUNNING.swap(true, Ordering::Relaxed) {
+        std::thread::spawn(keepalive);
+    }
+    Ok(status)
+}
+
+fn any_forced() -> bool {
+    FORCED.lock().map(|f| !f.is_empty()).unwrap_or(false)
+}
+
+/// Ping the helper while fans are forced so it does not quit and release them
+fn keepalive() {
+    while any_forced() {
+        std::thread::sleep(KEEPALIVE);
+        if let Err(e) = crate::privileged_helper::ping() {
+            debug1!("Fan control: helper keepalive failed: {}", e);
+        }
+    }
+    KEEPALIVE_RUNNING.store(false, Ordering::Relaxed);
+}
+
+/// Return fans this app forced to automatic (`RunEvent::Exit`)
+pub fn restore_all() {
+    let forced: Vec<u32> = FORCED
+        .lock()
+        .map(|f| f.iter().copied().collect())
+        .unwrap_or_default();
+    for fan in forced {
+        if let Err(e) = set_fan_speed(fan, None) {
+            tracing::warn!("Fan {}: could not return to automatic: {}", fan, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rpm_round_trips_through_both_key_types() {
+        for data_type in [b"flt ", b"fpe2"] {
+            let bytes = encode_rpm(data_type, 2450.0).unwrap();
+            assert_eq!(decode_rpm(data_type, &bytes), Some(2450.0));
+        }
+        // fpe2: 14.2 fixed point, big-endian
+        assert_eq!(encode_rpm(b"fpe2", 1200.0), Some(vec![0x12, 0xC0]));
+        assert_eq!(encode_rpm(b"ui8 ", 1200.0), None);
+        assert_eq!(decode_rpm(b"flt ", &[0, 0]), None);
+    }
+}
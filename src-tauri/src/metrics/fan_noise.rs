@@ -0,0 +1,253 @@
+//! Fan speed and "fan audible" indicator
+//!
+//! A collector task (see `background`) reads the fan speeds over SMC every [`SAMPLE_INTERVAL`]
+//! (a few keys, not the full `all_data()` scan the temperature reads need) and compares the
+//! fastest fan with `fanAudibleRpm`. Time above it is summed per local day in `fan_audible.json` and kept for
+//! [`KEEP_DAYS`] days, so users tuning a silent setup can see how many minutes the fans were
+//! audible each day. Macs without fans end the task after the first read.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use mac_stats_collector::smc::fan_rpm;
+use macsmc::Smc;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+use crate::ffi::SmcConnection;
+use crate::state::{FAN_AUDIBLE_LOG, FAN_READING};
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+/// How often fans are read
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(10);
+/// Days of audible-minute totals kept
+pub const KEEP_DAYS: usize = 90;
+/// How often the daily totals are written while the fans are audible
+const SAVE_INTERVAL: Duration = Duration::from_secs(300);
+/// Readings older than this are not shown (the task reads every 10s)
+const READING_MAX_AGE: Duration = Duration::from_secs(30);
+
+/// Latest fan reading
+#[derive(Debug, Clone, Copy)]
+pub struct FanReading {
+    /// Fastest fan in RPM
+    pub rpm: f32,
+    /// `rpm` at or above `fanAudibleRpm`
+    pub audible: bool,
+    pub at: Instant,
+}
+
+/// Audible minutes for one local day (`get_fan_audible_history`)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FanAudibleDay {
+    /// `YYYY-MM-DD`, local time
+    pub date: String,
+    pub minutes: f32,
+}
+
+/// Audible seconds per local day
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FanAudibleLog {
+    days: BTreeMap<String, f64>,
+}
+
+impl FanAudibleLog {
+    /// Add `secs` to `date` and drop all but the newest [`KEEP_DAYS`] days
+    pub fn add(&mut self, date: &str, secs: f64) {
+        *self.days.entry(date.to_string()).or_default() += secs;
+        while self.days.len() > KEEP_DAYS {
+            self.days.pop_first();
+        }
+    }
+
+    pub fn minutes(&self, date: &str) -> f32 {
+        self.days.get(date).map_or(0.0, |s| (*s / 60.0) as f32)
+    }
+
+    /// Newest `limit` days, oldest first
+    pub fn recent(&self, limit: usize) -> Vec<FanAudibleDay> {
+        let skip = self.days.len().saturating_sub(limit);
+        self.days
+            .iter()
+            .skip(skip)
+            .map(|(date, secs)| FanAudibleDay {
+                date: date.clone(),
+                minutes: (*secs / 60.0) as f32,
+            })
+            .collect()
+    }
+}
+
+fn log_path() -> PathBuf {
+    Config::config_file_path().with_file_name("fan_audible.json")
+}
+
+fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
+
+fn load_log() -> FanAudibleLog {
+    std::fs::read_to_string(log_path())
+        .ok()
+        .and_then(|s| serde_json::from_str(&s).ok())
+        .unwrap_or_default()
+}
+
+fn save_log(log: &FanAudibleLog) {
+    match serde_json::to_string(log) {
+        Ok(json) => {
+            if let Err(e) = crate::config::write_text_atomic(&log_path(), &json) {
+                debug2!("Fan: could not save audible minutes: {}", e);
+            }
+        }
+        Err(e) => {
+            debug2!("Fan: could not encode audible minutes: {}", e);
+        }
+    }
+}
+
+/// Latest reading while fresh; `None` on fanless Macs or before the first read
+pub fn current() -> Option<FanReading> {
+    FAN_READING
+        .try_lock()
+        .ok()
+        .and_then(|r| *r)
+        .filter(|r| r.at.elapsed() < READING_MAX_AGE)
+}
+
+/// Audible minutes so far today
+pub fn audible_minutes_today() -> f32 {
+    FAN_AUDIBLE_LOG
+        .try_lock()
+        .ok()
+        .and_then(|log| log.as_ref().map(|l| l.minutes(&today())))
+        .unwrap_or(0.0)
+}
+
+/// Audible minutes for the newest `days` days, oldest first
+pub(crate) fn history(days: usize) -> Vec<FanAudibleDay> {
+    let Ok(mut log) = FAN_AUDIBLE_LOG.lock() else {
+        return Vec::new();
+    };
+    log.get_or_insert_with(load_log).recent(days)
+}
+
+/// Per-read step of the fan monitor; false once SMC stops reporting a fan
+fn monitor(mut smc: SmcConnection) -> impl FnMut() -> bool + Send {
+    // Load earlier totals now so today's minutes show before the fans next spin up
+    if let Ok(mut log) = FAN_AUDIBLE_LOG.lock() {
+        log.get_or_insert_with(load_log);
+    }
+    let mut last_sample: Option<Instant> = None;
+    let mut last_save = Instant::now();
+    let mut unsaved = false;
+    move || {
+        let Some(rpm) = fan_rpm(&mut smc) else {
+            debug2!("Fan: no fan reading, stopping fan monitor");
+            return false;
+        };
+        let audible = rpm >= Config::fan_audible_rpm() as f32;
+        let now = Instant::now();
+        if let Ok(mut reading) = FAN_READING.lock() {
+            *reading = Some(FanReading {
+                rpm,
+                audible,
+                at: now,
+            });
+        }
+        // Count the interval since the previous read; a longer gap means the Mac slept
+        let elapsed = last_sample.map(|t| now.duration_since(t));
+        last_sample = Some(now);
+        if let (true, Some(elapsed)) = (audible, elapsed) {
+            if elapsed <= SAMPLE_INTERVAL * 2 {
+                if let Ok(mut log) = FAN_AUDIBLE_LOG.lock() {
+                    log.get_or_insert_with(load_log)
+                        .add(&today(), elapsed.as_secs_f64());
+                    unsaved = true;
+                }
+            }
+        }
+        if unsaved && last_save.elapsed() >= SAVE_INTERVAL {
+            if let Ok(log) = FAN_AUDIBLE_LOG.lock() {
+                if let Some(log) = log.as_ref() {
+                    save_log(log);
+                }
+            }
+            last_save = Instant::now();
+            unsaved = false;
+        }
+        true
+    }
+}
+
+/// Write pending audible minutes (`RunEvent::Exit`)
+pub fn save_on_exit() {
+    if let Ok(log) = FAN_AUDIBLE_LOG.lock() {
+        if let Some(log) = log.as_ref() {
+            save_log(log);
+        }
+    }
+}
+
+/// Connect to SMC and check for a fan; `None` (and no `fan` capability) on fanless Macs
+fn connect() -> Option<SmcConnection> {
+    let Ok(smc) = Smc::connect() else {
+        crate::capabilities::update(|c| c.fan = false);
+        return None;
+    };
+    let mut smc = SmcConnection(smc);
+    let has_fan = fan_rpm(&mut smc).is_some();
+    crate::capabilities::update(|c| c.fan = has_fan);
+    if !has_fan {
+        debug1!("Fan: no fans reported by SMC");
+        return None;
+    }
+    Some(smc)
+}
+
+/// Start the fan monitor task; it ends right away when SMC reports no fan
+pub fn spawn_fan_monitor() {
+    crate::background::spawn("fan", async {
+        let Some(smc) = tokio::task::block_in_place(connect) else {
+            return;
+        };
+        let mut read = tokio::task::block_in_place(|| monitor(smc));
+        let mut ticks = crate::background::interval(Duration::ZERO, SAMPLE_INTERVAL);
+        loop {
+            ticks.tick().await;
+            if !tokio::task::block_in_place(&mut read) {
+                return;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums_audible_seconds_per_day() {
+        let mut log = FanAudibleLog::default();
+        log.add("2026-10-16", 90.0);
+        log.add("2026-10-17", 30.0);
+        log.add("2026-10-17", 30.0);
+        assert_eq!(log.minutes("2026-10-17"), 1.0);
+        assert_eq!(log.minutes("2026-10-15"), 0.0);
+        assert_eq!(
+            log.recent(1),
+            vec![FanAudibleDay {
+                date: "2026-10-17".to_string(),
+                minutes: 1.0
+            }]
+        );
+
+        for day in 1..=KEEP_DAYS + 5 {
+            log.add(&format!("2027-{:03}", day), 1.0);
+        }
+        assert_eq!(log.recent(usize::MAX).len(), KEEP_DAYS);
+        assert_eq!(log.minutes("2026-10-16"), 0.0);
+    }
+}
@@ -0,0 +1,61 @@
+//! GPU temperature from SMC
+//!
+//! Apple Silicon reports one temperature key per GPU cluster and the key names change with
+//! each chip generation. The first read scans SMC for the known keys and remembers the ones
+//! present; the reading is their average. Read alongside CPU temperature (every 20s while the
+//! CPU window is visible) and kept in the metrics snapshot (`state::snapshot`). The SMC scan
+//! itself lives in `mac_stats_collector::smc`.
+
+use mac_stats_collector::smc::{average, gpu_temperatures, GPU_TEMPERATURE_KEYS};
+use macsmc::Smc;
+
+use crate::state::GPU_TEMP_KEYS;
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+/// Cached readings older than this are not shown (reads happen every 20s)
+const CACHE_MAX_AGE_SECS: u64 = 30;
+
+/// Average GPU cluster temperature in °C; `None` when no GPU key is readable. The first call
+/// records whether this Mac has GPU temperature keys (`capabilities`).
+pub(crate) fn read(smc: &mut Smc) -> Option<f32> {
+    let discovered = GPU_TEMP_KEYS.lock().ok().and_then(|k| k.clone());
+    let keys: Vec<String> = discovered
+        .clone()
+        .unwrap_or_else(|| GPU_TEMPERATURE_KEYS.iter().map(|k| k.to_string()).collect());
+    // all_data() walks every SMC key; stop once the known cluster keys are in
+    let readings = gpu_temperatures(smc, &keys, discovered.is_some())?;
+    if discovered.is_none() {
+        let found: Vec<String> = readings.iter().map(|(k, _)| k.clone()).collect();
+        debug2!("GPU temperature keys: {:?}", found);
+        crate::capabilities::update(|c| c.gpu_temperature = !found.is_empty());
+        if let Ok(mut cached) = GPU_TEMP_KEYS.lock() {
+            *cached = Some(found);
+        }
+    }
+    average(&readings)
+}
+
+/// Read and cache the GPU temperature (update loop, same cadence as CPU temperature)
+pub(crate) fn update_cache(smc: &mut Smc) {
+    let scanned = GPU_TEMP_KEYS.lock().is_ok_and(|k| k.is_some());
+    if scanned && !crate::capabilities::get().gpu_temperature {
+        return;
+    }
+    if let Some(temp) = read(smc) {
+        crate::state::update_snapshot(|s| {
+            s.gpu_temperature = Some(crate::state::Reading::new(temp))
+        });
+        debug3!("GPU temperature updated in cache: {:.1}°C", temp);
+    }
+}
+
+/// Latest cached GPU temperature, 0.0 when none is recent
+pub fn cached() -> f32 {
+    crate::state::snapshot()
+        .gpu_temperature
+        .as_ref()
+        .filter(|r| r.age().as_secs() < CACHE_MAX_AGE_SECS)
+        .map(|r| r.value)
+        .unwrap_or(0.0)
+}
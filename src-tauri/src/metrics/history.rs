@@ -14,13 +14,125 @@
 //!
 //! - Each tier is a VecDeque that auto-evicts oldest points when full
 //! - Points are automatically promoted from Tier 1 → 2 → 3 → 4 via downsampling
-//! - Downsampling uses rolling averages of all metrics for smooth transitions
+//! - Downsampling uses rolling averages of all metrics for smooth transitions; each
+//!   downsampled point also keeps the bucket's min/max ([`MetricBands`]) so short spikes
+//!   survive into the coarser tiers and charts can draw a band around the average
 //! - Frontend can query any time range and specify max_points for display
+//! - Silences longer than [`GAP_THRESHOLD_SECS`] between samples are kept as [`HistoryGap`]s
+//!   (sleep, or app not running across a restart) so charts break the line there instead of
+//!   interpolating across hours of missing data
 
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, VecDeque};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// How far back the coarsest tier reaches (7 days)
+pub const RETENTION_SECS: u64 = 7 * 86400;
+
+/// Samples arrive every 1-2s; a longer silence than this is recorded as a [`HistoryGap`]
+pub const GAP_THRESHOLD_SECS: i64 = 60;
+
+/// Gaps kept (about three years of nightly sleeps)
+const MAX_GAPS: usize = 1000;
+
+/// Why a stretch of history has no samples
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum GapReason {
+    /// No samples while the app kept running: the Mac was asleep
+    Sleep,
+    /// The gap ends at the first sample after an app start
+    AppNotRunning,
+}
+
+/// A stretch without samples, from the last sample before it to the first one after
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HistoryGap {
+    pub start: i64,
+    pub end: i64,
+    pub reason: GapReason,
+}
+
+/// Parse a history range (`5m`, `1h`, `7d` or plain seconds), clamped to [`RETENTION_SECS`]
+pub fn parse_range(s: &str) -> Option<u64> {
+    parse_range_up_to(s, RETENTION_SECS)
+}
+
+/// [`parse_range`] clamped to `max_secs` instead (the SQLite store keeps more than 7 days)
+pub fn parse_range_up_to(s: &str, max_secs: u64) -> Option<u64> {
+    let s = s.trim();
+    let (digits, unit) = match s.char_indices().last()? {
+        (i, 's') => (&s[..i], 1),
+        (i, 'm') => (&s[..i], 60),
+        (i, 'h') => (&s[..i], 3600),
+        (i, 'd') => (&s[..i], 86400),
+        _ => (s, 1),
+    };
+    let secs = digits.parse::<u64>().ok()?.checked_mul(unit)?;
+    (secs > 0).then(|| secs.min(max_secs))
+}
+
+/// How a downsampled history point summarizes its bucket
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Aggregation {
+    /// Bucket average (min/max stay available in `bands`)
+    #[default]
+    Avg,
+    /// Lowest value in the bucket
+    Min,
+    /// Highest value in the bucket, so short spikes survive long ranges
+    Max,
+}
+
+impl Aggregation {
+    pub const NAMES: [&'static str; 3] = ["avg", "min", "max"];
+
+    /// `avg`, `min` or `max`
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "avg" => Some(Self::Avg),
+            "min" => Some(Self::Min),
+            "max" => Some(Self::Max),
+            _ => None,
+        }
+    }
+
+    /// Apply to downsampled points: min/max replace each metric with its band bound
+    /// (raw samples have no bands and are returned unchanged)
+    pub fn apply(self, points: Vec<MetricPoint>) -> Vec<MetricPoint> {
+        if self == Self::Avg {
+            return points;
+        }
+        points
+            .into_iter()
+            .map(|mut p| {
+                if let Some(bands) = p.bands.as_deref() {
+                    let pick = |b: MetricBand| if self == Self::Max { b.max } else { b.min };
+                    p.cpu = pick(bands.cpu);
+                    p.gpu = pick(bands.gpu);
+                    p.ram = pick(bands.ram);
+                    p.disk = pick(bands.disk);
+                    p.temperature = pick(bands.temperature);
+                    p.frequency = pick(bands.frequency);
+                    p.p_core_frequency = pick(bands.p_core_frequency);
+                    p.e_core_frequency = pick(bands.e_core_frequency);
+                    p.cpu_power = pick(bands.cpu_power);
+                    p.gpu_power = pick(bands.gpu_power);
+                    p.battery_level = pick(bands.battery_level);
+                    p.net_rx = pick(bands.net_rx);
+                    p.net_tx = pick(bands.net_tx);
+                    p.disk_read = pick(bands.disk_read);
+                    p.disk_write = pick(bands.disk_write);
+                    p.battery_power = pick(bands.battery_power);
+                    p.adapter_power = pick(bands.adapter_power);
+                }
+                p
+            })
+            .collect()
+    }
+}
+
 /// A single point in metrics history
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetricPoint {
@@ -36,10 +148,123 @@ pub struct MetricPoint {
     pub cpu_power: f32,        // CPU power consumption in Watts
     pub gpu_power: f32,        // GPU power consumption in Watts
     pub battery_level: f32,    // Battery level (0-100), or -1.0 if N/A
+    #[serde(default)]
+    pub net_rx: f32, // Network receive rate in bytes/s (all interfaces except loopback)
+    #[serde(default)]
+    pub net_tx: f32, // Network transmit rate in bytes/s
+    #[serde(default)]
+    pub disk_read: f32, // Disk read rate in bytes/s
+    #[serde(default)]
+    pub disk_write: f32, // Disk write rate in bytes/s
+    #[serde(default)]
+    pub battery_power: f32, // Battery discharge in Watts (0 while charging or on AC)
+    #[serde(default)]
+    pub adapter_power: f32, // Connected power adapter rating in Watts (0 when unplugged)
+    /// Metric plugin values (`<plugin>.<metric>`), averaged like the built-in metrics but
+    /// without bands
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub plugins: BTreeMap<String, f32>,
+    /// Min/max of each metric over the bucket this point averages (None for raw samples)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bands: Option<Box<MetricBands>>,
+}
+
+/// Lowest and highest value of one metric within a downsampled bucket
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetricBand {
+    pub min: f32,
+    pub max: f32,
+}
+
+/// Per-metric min/max for a downsampled [`MetricPoint`] (the point's own fields are the averages)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricBands {
+    pub cpu: MetricBand,
+    pub gpu: MetricBand,
+    pub ram: MetricBand,
+    pub disk: MetricBand,
+    pub temperature: MetricBand,
+    pub frequency: MetricBand,
+    pub p_core_frequency: MetricBand,
+    pub e_core_frequency: MetricBand,
+    pub cpu_power: MetricBand,
+    pub gpu_power: MetricBand,
+    pub battery_level: MetricBand,
+    #[serde(default)]
+    pub net_rx: MetricBand,
+    #[serde(default)]
+    pub net_tx: MetricBand,
+    #[serde(default)]
+    pub disk_read: MetricBand,
+    #[serde(default)]
+    pub disk_write: MetricBand,
+    #[serde(default)]
+    pub battery_power: MetricBand,
+    #[serde(default)]
+    pub adapter_power: MetricBand,
+}
+
+impl MetricBands {
+    /// Envelope of one metric across `points`, widened by bands they already carry
+    /// (so a 1s spike is still visible after 1m → 5m → 1h downsampling).
+    fn envelope(
+        points: &[MetricPoint],
+        value: fn(&MetricPoint) -> f32,
+        band: fn(&MetricBands) -> MetricBand,
+    ) -> MetricBand {
+        points.iter().fold(
+            MetricBand {
+                min: f32::INFINITY,
+                max: f32::NEG_INFINITY,
+            },
+            |acc, p| {
+                let b = p.bands.as_deref().map(band).unwrap_or(MetricBand {
+                    min: value(p),
+                    max: value(p),
+                });
+                MetricBand {
+                    min: acc.min.min(b.min),
+                    max: acc.max.max(b.max),
+                }
+            },
+        )
+    }
+
+    /// Bands for a non-empty slice of points
+    fn from_points(points: &[MetricPoint]) -> Self {
+        Self {
+            cpu: Self::envelope(points, |p| p.cpu, |b| b.cpu),
+            gpu: Self::envelope(points, |p| p.gpu, |b| b.gpu),
+            ram: Self::envelope(points, |p| p.ram, |b| b.ram),
+            disk: Self::envelope(points, |p| p.disk, |b| b.disk),
+            temperature: Self::envelope(points, |p| p.temperature, |b| b.temperature),
+            frequency: Self::envelope(points, |p| p.frequency, |b| b.frequency),
+            p_core_frequency: Self::envelope(
+                points,
+                |p| p.p_core_frequency,
+                |b| b.p_core_frequency,
+            ),
+            e_core_frequency: Self::envelope(
+                points,
+                |p| p.e_core_frequency,
+                |b| b.e_core_frequency,
+            ),
+            cpu_power: Self::envelope(points, |p| p.cpu_power, |b| b.cpu_power),
+            gpu_power: Self::envelope(points, |p| p.gpu_power, |b| b.gpu_power),
+            battery_level: Self::envelope(points, |p| p.battery_level, |b| b.battery_level),
+            net_rx: Self::envelope(points, |p| p.net_rx, |b| b.net_rx),
+            net_tx: Self::envelope(points, |p| p.net_tx, |b| b.net_tx),
+            disk_read: Self::envelope(points, |p| p.disk_read, |b| b.disk_read),
+            disk_write: Self::envelope(points, |p| p.disk_write, |b| b.disk_write),
+            battery_power: Self::envelope(points, |p| p.battery_power, |b| b.battery_power),
+            adapter_power: Self::envelope(points, |p| p.adapter_power, |b| b.adapter_power),
+        }
+    }
 }
 
 impl MetricPoint {
-    /// Create a new metric point from current metrics
+    /// Create a new metric point from current metrics (I/O rates and battery/adapter power
+    /// start at zero; the update loop fills them from their samplers)
     #[allow(clippy::too_many_arguments)]
     pub fn from_metrics(
         cpu: f32,
@@ -70,10 +295,18 @@ impl MetricPoint {
             cpu_power,
             gpu_power,
             battery_level,
+            net_rx: 0.0,
+            net_tx: 0.0,
+            disk_read: 0.0,
+            disk_write: 0.0,
+            battery_power: 0.0,
+            adapter_power: 0.0,
+            plugins: BTreeMap::new(),
+            bands: None,
         }
     }
 
-    /// Average multiple points together (for downsampling)
+    /// Average multiple points together (for downsampling), keeping per-metric min/max
     pub fn average(points: &[MetricPoint]) -> Self {
         if points.is_empty() {
             return Self {
@@ -92,6 +325,14 @@ impl MetricPoint {
                 cpu_power: 0.0,
                 gpu_power: 0.0,
                 battery_level: -1.0,
+                net_rx: 0.0,
+                net_tx: 0.0,
+                disk_read: 0.0,
+                disk_write: 0.0,
+                battery_power: 0.0,
+                adapter_power: 0.0,
+                plugins: BTreeMap::new(),
+                bands: None,
             };
         }
 
@@ -109,10 +350,31 @@ impl MetricPoint {
             cpu_power: points.iter().map(|p| p.cpu_power).sum::<f32>() / count,
             gpu_power: points.iter().map(|p| p.gpu_power).sum::<f32>() / count,
             battery_level: points.iter().map(|p| p.battery_level).sum::<f32>() / count,
+            net_rx: points.iter().map(|p| p.net_rx).sum::<f32>() / count,
+            net_tx: points.iter().map(|p| p.net_tx).sum::<f32>() / count,
+            disk_read: points.iter().map(|p| p.disk_read).sum::<f32>() / count,
+            disk_write: points.iter().map(|p| p.disk_write).sum::<f32>() / count,
+            battery_power: points.iter().map(|p| p.battery_power).sum::<f32>() / count,
+            adapter_power: points.iter().map(|p| p.adapter_power).sum::<f32>() / count,
+            plugins: average_plugins(points),
+            bands: Some(Box::new(MetricBands::from_points(points))),
         }
     }
 }
 
+/// Per-key average of plugin values over the points that have the key
+fn average_plugins(points: &[MetricPoint]) -> BTreeMap<String, f32> {
+    let mut sums: BTreeMap<String, (f32, u32)> = BTreeMap::new();
+    for (name, value) in points.iter().flat_map(|p| &p.plugins) {
+        let sum = sums.entry(name.clone()).or_default();
+        sum.0 += value;
+        sum.1 += 1;
+    }
+    sums.into_iter()
+        .map(|(name, (sum, n))| (name, sum / n as f32))
+        .collect()
+}
+
 /// Adaptive tiered metrics history buffer
 pub struct HistoryBuffer {
     /// Tier 1: 1-second granularity, last 5 minutes (300 points)
@@ -130,6 +392,13 @@ pub struct HistoryBuffer {
     last_tier3_downsample: i64,
     /// Last timestamp we processed a Tier 4 downsampling
     last_tier4_downsample: i64,
+
+    /// Recorded silences between samples, oldest first
+    gaps: VecDeque<HistoryGap>,
+    /// Timestamp of the last pushed sample (not persisted; falls back to the newest tier point)
+    last_sample: Option<i64>,
+    /// Set by [`Self::mark_session_start`]: the next gap is an app restart, not sleep
+    session_start_pending: bool,
 }
 
 impl HistoryBuffer {
@@ -143,12 +412,66 @@ impl HistoryBuffer {
             last_tier2_downsample: 0,
             last_tier3_downsample: 0,
             last_tier4_downsample: 0,
+            gaps: VecDeque::new(),
+            last_sample: None,
+            session_start_pending: false,
         }
     }
 
-    /// Add a new metric point to the history
-    pub fn push(&mut self, point: MetricPoint) {
+    /// The app just started: a gap before the next sample is downtime, not sleep
+    pub fn mark_session_start(&mut self) {
+        self.session_start_pending = true;
+    }
+
+    /// Gaps overlapping `start..=end`, oldest first
+    pub fn gaps_between(&self, start: i64, end: i64) -> Vec<HistoryGap> {
+        self.gaps
+            .iter()
+            .filter(|g| g.end >= start && g.start <= end)
+            .cloned()
+            .collect()
+    }
+
+    /// Gaps persisted elsewhere (the SQLite store), merged with any recorded since start
+    pub fn restore_gaps(&mut self, gaps: Vec<HistoryGap>) {
+        let mut all: Vec<HistoryGap> = gaps.into_iter().chain(self.gaps.drain(..)).collect();
+        all.sort_by_key(|g| g.start);
+        all.dedup_by_key(|g| g.start);
+        let skip = all.len().saturating_sub(MAX_GAPS);
+        self.gaps = all.into_iter().skip(skip).collect();
+    }
+
+    fn record_gap(&mut self, timestamp: i64) -> Option<HistoryGap> {
+        let mut gap = None;
+        let previous = self.last_sample.or_else(|| self.newest_timestamp());
+        if let Some(previous) = previous {
+            if timestamp - previous > GAP_THRESHOLD_SECS {
+                let reason = if self.session_start_pending {
+                    GapReason::AppNotRunning
+                } else {
+                    GapReason::Sleep
+                };
+                let recorded = HistoryGap {
+                    start: previous,
+                    end: timestamp,
+                    reason,
+                };
+                self.gaps.push_back(recorded.clone());
+                if self.gaps.len() > MAX_GAPS {
+                    self.gaps.pop_front();
+                }
+                gap = Some(recorded);
+            }
+        }
+        self.last_sample = Some(timestamp);
+        self.session_start_pending = false;
+        gap
+    }
+
+    /// Add a new metric point to the history; returns the gap it closes, if any
+    pub fn push(&mut self, point: MetricPoint) -> Option<HistoryGap> {
         let timestamp = point.timestamp;
+        let gap = self.record_gap(timestamp);
 
         // Add to Tier 1
         self.tier1_1s.push_back(point.clone());
@@ -173,6 +496,7 @@ impl HistoryBuffer {
             self.downsample_to_tier4();
             self.last_tier4_downsample = timestamp;
         }
+        gap
     }
 
     /// Downsample from Tier 1 to Tier 2 (average every 60 points into 1)
@@ -232,6 +556,27 @@ impl HistoryBuffer {
         }
     }
 
+    /// Backfill externally sourced points (e.g. imported from another monitoring tool).
+    ///
+    /// Each tier receives the imported points that fall inside its retention window, averaged
+    /// into buckets of the tier's granularity (the same cascade live samples go through).
+    /// Buckets already holding live data are left untouched. Returns the number of points added.
+    pub fn backfill(&mut self, mut points: Vec<MetricPoint>) -> usize {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        points.retain(|p| p.timestamp <= now && now - p.timestamp <= 604800);
+        points.sort_by_key(|p| p.timestamp);
+
+        let mut added = 0;
+        added += merge_into_tier(&mut self.tier1_1s, &points, now - 300, 1, 300);
+        added += merge_into_tier(&mut self.tier2_1m, &points, now - 3600, 60, 60);
+        added += merge_into_tier(&mut self.tier3_5m, &points, now - 21600, 300, 72);
+        added += merge_into_tier(&mut self.tier4_1h, &points, now - 604800, 3600, 168);
+        added
+    }
+
     /// Get total number of data points across all tiers
     #[allow(dead_code)] // Used in tests
     pub fn total_points(&self) -> usize {
@@ -239,7 +584,6 @@ impl HistoryBuffer {
     }
 
     /// Get memory usage estimate in bytes
-    #[allow(dead_code)] // Utility method for debugging/monitoring
     pub fn estimate_memory_bytes(&self) -> usize {
         // Each point is roughly 100 bytes when serialized
         const BYTES_PER_POINT: usize = 100;
@@ -259,11 +603,70 @@ impl HistoryBuffer {
         .min()
     }
 
-    /// Query history for a given time range with optional downsampling for display
+    /// Give spare tier capacity back to the allocator; with `drop_raw_samples` also discard the
+    /// 1s tier (the last minute is lost from the coarser tiers until it refills).
+    pub fn release_memory(&mut self, drop_raw_samples: bool) {
+        if drop_raw_samples {
+            self.tier1_1s.clear();
+        }
+        self.tier1_1s.shrink_to_fit();
+        self.tier2_1m.shrink_to_fit();
+        self.tier3_5m.shrink_to_fit();
+        self.tier4_1h.shrink_to_fit();
+    }
+
+    /// Most recent raw (1s tier) sample
+    pub fn latest(&self) -> Option<&MetricPoint> {
+        self.tier1_1s.back()
+    }
+
+    /// Newest timestamp across all tiers
+    pub fn newest_timestamp(&self) -> Option<i64> {
+        [
+            self.tier4_1h.back().map(|p| p.timestamp),
+            self.tier3_5m.back().map(|p| p.timestamp),
+            self.tier2_1m.back().map(|p| p.timestamp),
+            self.tier1_1s.back().map(|p| p.timestamp),
+        ]
+        .iter()
+        .filter_map(|ts| *ts)
+        .max()
+    }
+
+    /// Points with `start <= timestamp < end` at the finest resolution still held for each part
+    /// of the window (raw samples where available, then 1m, 5m and hourly averages further back)
+    pub fn points_between(&self, start: i64, end: i64) -> Vec<MetricPoint> {
+        let mut points: Vec<MetricPoint> = Vec::new();
+        // Coarser tiers only fill in before the oldest point a finer tier already covered
+        let mut covered_from = i64::MAX;
+        for tier in [
+            &self.tier1_1s,
+            &self.tier2_1m,
+            &self.tier3_5m,
+            &self.tier4_1h,
+        ] {
+            let before = points.len();
+            points.extend(
+                tier.iter()
+                    .filter(|p| p.timestamp >= start && p.timestamp < end)
+                    .filter(|p| p.timestamp < covered_from)
+                    .cloned(),
+            );
+            if let Some(oldest) = points[before..].iter().map(|p| p.timestamp).min() {
+                covered_from = oldest;
+            }
+        }
+        points.sort_by_key(|p| p.timestamp);
+        points
+    }
+
+    /// Query history for a given time range with optional downsampling for display; points
+    /// from the coarser tiers or display downsampling are summarized per `aggregation`
     pub fn query(
         &self,
         time_range_seconds: u64,
         max_display_points: Option<usize>,
+        aggregation: Aggregation,
     ) -> Vec<MetricPoint> {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -331,20 +734,17 @@ impl HistoryBuffer {
         }
 
         // Apply display width downsampling if needed
-        if let Some(max_points) = max_display_points {
-            if points.len() > max_points {
-                self.downsample_for_display(&points, max_points)
-            } else {
-                points
+        let points = match max_display_points {
+            Some(max_points) if points.len() > max_points => {
+                Self::downsample_for_display(&points, max_points)
             }
-        } else {
-            points
-        }
+            _ => points,
+        };
+        aggregation.apply(points)
     }
 
-    /// Downsample points for screen display (every nth point)
-    fn downsample_for_display(
-        &self,
+    /// Downsample points for screen display (average every n points, keeping min/max bands)
+    pub(crate) fn downsample_for_display(
         points: &[MetricPoint],
         target_count: usize,
     ) -> Vec<MetricPoint> {
@@ -357,7 +757,7 @@ impl HistoryBuffer {
         }
 
         let step = points.len().div_ceil(target_count);
-        points.iter().step_by(step).cloned().collect()
+        points.chunks(step).map(MetricPoint::average).collect()
     }
 }
 
@@ -367,51 +767,158 @@ impl Default for HistoryBuffer {
     }
 }
 
+/// Average `points` (sorted, oldest first) newer than `since` into `bucket_secs` buckets and
+/// merge them into `tier`, skipping buckets the tier already covers. Keeps the newest `capacity`.
+fn merge_into_tier(
+    tier: &mut VecDeque<MetricPoint>,
+    points: &[MetricPoint],
+    since: i64,
+    bucket_secs: i64,
+    capacity: usize,
+) -> usize {
+    let occupied: std::collections::HashSet<i64> =
+        tier.iter().map(|p| p.timestamp / bucket_secs).collect();
+
+    let mut imported: Vec<MetricPoint> = Vec::new();
+    let mut bucket: Vec<MetricPoint> = Vec::new();
+    for p in points.iter().filter(|p| p.timestamp >= since) {
+        if let Some(first) = bucket.first() {
+            if first.timestamp / bucket_secs != p.timestamp / bucket_secs {
+                imported.push(MetricPoint::average(&bucket));
+                bucket.clear();
+            }
+        }
+        bucket.push(p.clone());
+    }
+    if !bucket.is_empty() {
+        imported.push(MetricPoint::average(&bucket));
+    }
+    imported.retain(|p| !occupied.contains(&(p.timestamp / bucket_secs)));
+    if imported.is_empty() {
+        return 0;
+    }
+
+    let added = imported.len();
+    let mut merged: Vec<MetricPoint> = tier.drain(..).chain(imported).collect();
+    merged.sort_by_key(|p| p.timestamp);
+    let skip = merged.len().saturating_sub(capacity);
+    tier.extend(merged.into_iter().skip(skip));
+    added.min(capacity)
+}
+
+/// Metric names accepted by the `metrics` filter of `get_metrics_history` (the [`MetricPoint`]
+/// field names)
+pub const METRIC_NAMES: [&str; 17] = [
+    "cpu",
+    "gpu",
+    "ram",
+    "disk",
+    "temperature",
+    "frequency",
+    "p_core_frequency",
+    "e_core_frequency",
+    "cpu_power",
+    "gpu_power",
+    "battery_level",
+    "net_rx",
+    "net_tx",
+    "disk_read",
+    "disk_write",
+    "battery_power",
+    "adapter_power",
+];
+
+/// Serialize `points` keeping only `timestamp` and the named metrics (and their bands);
+/// `None` keeps every metric
+pub fn select_metrics(
+    points: &[MetricPoint],
+    metrics: Option<&[String]>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let to_value = |p: &MetricPoint| {
+        serde_json::to_value(p).map_err(|e| format!("Serialization error: {}", e))
+    };
+    let Some(metrics) = metrics else {
+        return points.iter().map(to_value).collect();
+    };
+    if let Some(unknown) = metrics.iter().find(|m| !METRIC_NAMES.contains(&m.as_str())) {
+        return Err(format!(
+            "Unknown metric '{}' (use {})",
+            unknown,
+            METRIC_NAMES.join(", ")
+        ));
+    }
+    points
+        .iter()
+        .map(|p| {
+            let mut value = to_value(p)?;
+            if let Some(obj) = value.as_object_mut() {
+                obj.retain(|k, _| k == "timestamp" || k == "bands" || metrics.contains(k));
+                if let Some(bands) = obj.get_mut("bands").and_then(|b| b.as_object_mut()) {
+                    bands.retain(|k, _| metrics.contains(k));
+                }
+            }
+            Ok(value)
+        })
+        .collect()
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct HistoryQueryResult {
-    pub points: Vec<MetricPoint>,
+    /// Serialized [`MetricPoint`]s, reduced to the requested metrics (see [`select_metrics`])
+    pub points: Vec<serde_json::Value>,
     pub time_range_seconds: u64,
     pub oldest_available_timestamp: Option<i64>,
     pub newest_available_timestamp: Option<i64>,
+    /// Sleep / app-not-running stretches inside the range; charts break the line across them
+    #[serde(default)]
+    pub gaps: Vec<HistoryGap>,
+    /// How downsampled points were summarized
+    #[serde(default)]
+    pub aggregation: Aggregation,
 }
 
 impl HistoryBuffer {
-    /// Optional: Save history to disk for persistence across restarts
-    /// Saves to ~/.mac-stats/history.json
-    #[allow(dead_code)] // Reserved for future persistence feature
+    /// Save history to disk for persistence across restarts (`~/.mac-stats/history.json`)
     pub fn save_to_disk(&self) -> Result<(), String> {
-        let home =
-            std::env::var("HOME").map_err(|_| "Could not determine HOME directory".to_string())?;
-        let history_dir = std::path::Path::new(&home).join(".mac-stats");
-        let history_file = history_dir.join("history.json");
+        self.save_to_path(&crate::config::Config::history_file_path())
+    }
 
+    /// Atomically write all tiers (temp file + fsync + rename, so a crash never leaves a
+    /// half-written snapshot behind)
+    pub(crate) fn save_to_path(&self, history_file: &std::path::Path) -> Result<(), String> {
         // Serialize all tiers
         let all_points = serde_json::json!({
             "tier1_1s": self.tier1_1s.iter().collect::<Vec<_>>(),
             "tier2_1m": self.tier2_1m.iter().collect::<Vec<_>>(),
             "tier3_5m": self.tier3_5m.iter().collect::<Vec<_>>(),
             "tier4_1h": self.tier4_1h.iter().collect::<Vec<_>>(),
+            "last_tier2_downsample": self.last_tier2_downsample,
+            "last_tier3_downsample": self.last_tier3_downsample,
+            "last_tier4_downsample": self.last_tier4_downsample,
+            "gaps": self.gaps,
             "saved_at": chrono::Local::now().to_rfc3339(),
         });
 
-        let json_str = serde_json::to_string_pretty(&all_points)
+        let json_str = serde_json::to_string(&all_points)
             .map_err(|e| format!("Serialization error: {}", e))?;
 
-        crate::config::write_text_atomic(&history_file, &json_str)
+        if let Some(dir) = history_file.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create history directory: {}", e))?;
+        }
+        crate::config::write_text_atomic(history_file, &json_str)
             .map_err(|e| format!("Failed to write history file: {}", e))?;
 
         Ok(())
     }
 
-    /// Optional: Load history from disk
-    /// Loads from ~/.mac-stats/history.json if it exists
-    #[allow(dead_code)] // Reserved for future persistence feature
+    /// Load history from disk (`~/.mac-stats/history.json`); empty buffer if the file is missing
+    #[allow(dead_code)] // Startup goes through the write-ahead log (history_wal::recover)
     pub fn load_from_disk() -> Result<Self, String> {
-        let home =
-            std::env::var("HOME").map_err(|_| "Could not determine HOME directory".to_string())?;
-        let history_dir = std::path::Path::new(&home).join(".mac-stats");
-        let history_file = history_dir.join("history.json");
+        Self::load_from_path(&crate::config::Config::history_file_path())
+    }
 
+    pub(crate) fn load_from_path(history_file: &std::path::Path) -> Result<Self, String> {
         if !history_file.exists() {
             return Ok(Self::new()); // Return empty buffer if file doesn't exist
         }
@@ -424,38 +931,28 @@ impl HistoryBuffer {
 
         // Reconstruct buffers from JSON
         let mut buffer = Self::new();
-
-        if let Some(tier1) = data["tier1_1s"].as_array() {
-            for point_val in tier1 {
-                if let Ok(point) = serde_json::from_value::<MetricPoint>(point_val.clone()) {
-                    buffer.tier1_1s.push_back(point);
-                }
-            }
-        }
-
-        if let Some(tier2) = data["tier2_1m"].as_array() {
-            for point_val in tier2 {
-                if let Ok(point) = serde_json::from_value::<MetricPoint>(point_val.clone()) {
-                    buffer.tier2_1m.push_back(point);
+        let tiers: [(&str, &mut VecDeque<MetricPoint>, usize); 4] = [
+            ("tier1_1s", &mut buffer.tier1_1s, 300),
+            ("tier2_1m", &mut buffer.tier2_1m, 60),
+            ("tier3_5m", &mut buffer.tier3_5m, 72),
+            ("tier4_1h", &mut buffer.tier4_1h, 168),
+        ];
+        for (key, tier, capacity) in tiers {
+            if let Some(points) = data[key].as_array() {
+                for point_val in points {
+                    if let Ok(point) = serde_json::from_value::<MetricPoint>(point_val.clone()) {
+                        tier.push_back(point);
+                    }
                 }
             }
-        }
-
-        if let Some(tier3) = data["tier3_5m"].as_array() {
-            for point_val in tier3 {
-                if let Ok(point) = serde_json::from_value::<MetricPoint>(point_val.clone()) {
-                    buffer.tier3_5m.push_back(point);
-                }
-            }
-        }
-
-        if let Some(tier4) = data["tier4_1h"].as_array() {
-            for point_val in tier4 {
-                if let Ok(point) = serde_json::from_value::<MetricPoint>(point_val.clone()) {
-                    buffer.tier4_1h.push_back(point);
-                }
+            while tier.len() > capacity {
+                tier.pop_front();
             }
         }
+        buffer.last_tier2_downsample = data["last_tier2_downsample"].as_i64().unwrap_or(0);
+        buffer.last_tier3_downsample = data["last_tier3_downsample"].as_i64().unwrap_or(0);
+        buffer.last_tier4_downsample = data["last_tier4_downsample"].as_i64().unwrap_or(0);
+        buffer.gaps = serde_json::from_value(data["gaps"].clone()).unwrap_or_default();
 
         Ok(buffer)
     }
@@ -465,6 +962,49 @@ impl HistoryBuffer {
 mod tests {
     use super::*;
 
+    #[test]
+    fn parses_ranges() {
+        assert_eq!(parse_range("5m"), Some(300));
+        assert_eq!(parse_range("1h"), Some(3600));
+        assert_eq!(parse_range("900"), Some(900));
+        assert_eq!(parse_range("30d"), Some(RETENTION_SECS));
+        assert_eq!(parse_range("0h"), None);
+        assert_eq!(parse_range("1w"), None);
+        assert_eq!(parse_range_up_to("30d", 90 * 86400), Some(30 * 86400));
+    }
+
+    #[test]
+    fn select_metrics_keeps_requested_fields_and_bands() {
+        let mut a =
+            MetricPoint::from_metrics(10.0, 0.0, 50.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -1.0);
+        a.net_rx = 1000.0;
+        let mut b = a.clone();
+        b.net_rx = 3000.0;
+        let avg = MetricPoint::average(&[a, b]);
+        let wanted = vec!["net_rx".to_string()];
+        let values = select_metrics(std::slice::from_ref(&avg), Some(&wanted)).unwrap();
+        let obj = values[0].as_object().unwrap();
+        let mut keys: Vec<&str> = obj.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        assert_eq!(keys, vec!["bands", "net_rx", "timestamp"]);
+        assert_eq!(obj["net_rx"], serde_json::json!(2000.0));
+        assert_eq!(obj["bands"]["net_rx"]["max"], serde_json::json!(3000.0));
+        assert!(obj["bands"].get("cpu").is_none());
+
+        let all = select_metrics(std::slice::from_ref(&avg), None).unwrap();
+        assert_eq!(all[0]["cpu"], serde_json::json!(10.0));
+        assert!(select_metrics(&[avg], Some(&["fan".to_string()])).is_err());
+    }
+
+    #[test]
+    fn older_points_without_io_rates_still_load() {
+        let json = r#"{"timestamp":1,"cpu":1,"gpu":0,"ram":0,"disk":0,"temperature":0,
+            "frequency":0,"p_core_frequency":0,"e_core_frequency":0,"cpu_power":0,
+            "gpu_power":0,"battery_level":-1}"#;
+        let p: MetricPoint = serde_json::from_str(json).unwrap();
+        assert_eq!((p.net_rx, p.disk_write), (0.0, 0.0));
+    }
+
     #[test]
     fn test_metric_point_average() {
         let points = vec![
@@ -477,6 +1017,59 @@ mod tests {
         assert_eq!(avg.cpu, 20.0);
         assert_eq!(avg.gpu, 10.0);
         assert_eq!(avg.ram, 30.0);
+        let bands = avg.bands.expect("averaged point carries bands");
+        assert_eq!((bands.cpu.min, bands.cpu.max), (10.0, 30.0));
+        assert_eq!(
+            (bands.battery_level.min, bands.battery_level.max),
+            (60.0, 80.0)
+        );
+    }
+
+    #[test]
+    fn test_spike_survives_repeated_downsampling() {
+        let flat =
+            |cpu| MetricPoint::from_metrics(cpu, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -1.0);
+        let mut minute: Vec<_> = (0..60).map(|_| flat(5.0)).collect();
+        minute[17] = flat(95.0);
+        let one_min = MetricPoint::average(&minute);
+        let five_min = MetricPoint::average(&[one_min, flat(5.0), flat(5.0), flat(5.0), flat(5.0)]);
+        assert!(five_min.cpu < 10.0);
+        let cpu = five_min.bands.unwrap().cpu;
+        assert_eq!((cpu.min, cpu.max), (5.0, 95.0));
+    }
+
+    #[test]
+    fn test_display_downsampling_keeps_peaks() {
+        let points: Vec<_> = (0..100)
+            .map(|i| {
+                let cpu = if i == 3 { 99.0 } else { 1.0 };
+                MetricPoint::from_metrics(cpu, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -1.0)
+            })
+            .collect();
+        let shown = HistoryBuffer::downsample_for_display(&points, 10);
+        assert_eq!(shown.len(), 10);
+        assert_eq!(shown[0].bands.as_ref().unwrap().cpu.max, 99.0);
+    }
+
+    #[test]
+    fn test_aggregation_picks_band_bounds() {
+        let points: Vec<_> = [1.0, 99.0, 2.0, 4.0]
+            .iter()
+            .map(|&cpu| {
+                MetricPoint::from_metrics(cpu, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -1.0)
+            })
+            .collect();
+        let shown = HistoryBuffer::downsample_for_display(&points, 2);
+        let cpu = |agg: Aggregation| -> Vec<f32> {
+            agg.apply(shown.clone()).iter().map(|p| p.cpu).collect()
+        };
+        assert_eq!(cpu(Aggregation::Avg), vec![50.0, 3.0]);
+        assert_eq!(cpu(Aggregation::Max), vec![99.0, 4.0]);
+        assert_eq!(cpu(Aggregation::Min), vec![1.0, 2.0]);
+        // Raw samples carry no bands and are left as they are
+        assert_eq!(Aggregation::Max.apply(points.clone())[1].cpu, 99.0);
+        assert_eq!(Aggregation::parse(" MAX "), Some(Aggregation::Max));
+        assert_eq!(Aggregation::parse("median"), None);
     }
 
     #[test]
@@ -493,4 +1086,98 @@ mod tests {
         buffer.push(point);
         assert_eq!(buffer.tier1_1s.len(), 1);
     }
+
+    #[test]
+    fn test_gaps_record_sleep_and_restart_and_persist() {
+        let point = |timestamp: i64| {
+            let mut p =
+                MetricPoint::from_metrics(10.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -1.0);
+            p.timestamp = timestamp;
+            p
+        };
+        let mut buffer = HistoryBuffer::new();
+        buffer.mark_session_start();
+        assert_eq!(buffer.push(point(1_000)), None);
+        assert_eq!(buffer.push(point(1_002)), None);
+        let sleep = buffer.push(point(5_000)).unwrap();
+        assert_eq!((sleep.start, sleep.end), (1_002, 5_000));
+        assert_eq!(sleep.reason, GapReason::Sleep);
+
+        let dir = std::env::temp_dir().join(format!("mac-stats-gaps-{}", std::process::id()));
+        let file = dir.join("history.json");
+        buffer.save_to_path(&file).unwrap();
+        let mut restored = HistoryBuffer::load_from_path(&file).unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+        restored.mark_session_start();
+        let restart = restored.push(point(9_000)).unwrap();
+        assert_eq!(restart.reason, GapReason::AppNotRunning);
+        assert_eq!(restored.gaps_between(0, 4_000), vec![sleep]);
+        assert_eq!(restored.gaps_between(6_000, 10_000), vec![restart]);
+    }
+
+    #[test]
+    fn test_release_memory_drops_only_raw_tier() {
+        let mut buffer = HistoryBuffer::new();
+        for i in 0..120 {
+            let mut p =
+                MetricPoint::from_metrics(10.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -1.0);
+            p.timestamp = 1_000 + i;
+            buffer.push(p);
+        }
+        let coarse = buffer.tier2_1m.len();
+        assert!(coarse > 0);
+        buffer.release_memory(false);
+        assert_eq!(buffer.tier1_1s.len(), 120);
+        buffer.release_memory(true);
+        assert!(buffer.tier1_1s.is_empty());
+        assert_eq!(buffer.tier2_1m.len(), coarse);
+    }
+
+    #[test]
+    fn test_points_between_prefers_finer_tiers() {
+        let mut buffer = HistoryBuffer::new();
+        let point = |timestamp: i64, cpu: f32| {
+            let mut p =
+                MetricPoint::from_metrics(cpu, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -1.0);
+            p.timestamp = timestamp;
+            p
+        };
+        for ts in [0, 3600, 7200] {
+            buffer.tier4_1h.push_back(point(ts, 1.0));
+        }
+        for ts in [7200, 7500] {
+            buffer.tier3_5m.push_back(point(ts, 2.0));
+        }
+        for ts in [7500, 7501] {
+            buffer.tier1_1s.push_back(point(ts, 3.0));
+        }
+        let points = buffer.points_between(3600, 7501);
+        let got: Vec<(i64, f32)> = points.iter().map(|p| (p.timestamp, p.cpu)).collect();
+        assert_eq!(got, vec![(3600, 1.0), (7200, 2.0), (7500, 3.0)]);
+    }
+
+    #[test]
+    fn test_history_buffer_backfill_routes_by_age() {
+        let mut buffer = HistoryBuffer::new();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or(0);
+        let mut points = Vec::new();
+        // Two samples ~2 days ago (same hour) and one 10 days ago (outside retention).
+        for age in [2 * 86400, 2 * 86400 - 60, 10 * 86400] {
+            let mut p = MetricPoint::from_metrics(
+                40.0, 0.0, 50.0, 60.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -1.0,
+            );
+            p.timestamp = now - age;
+            points.push(p);
+        }
+        let added = buffer.backfill(points);
+        assert!(added >= 1);
+        assert!(buffer.tier1_1s.is_empty());
+        assert!(buffer.tier2_1m.is_empty());
+        assert!(!buffer.tier4_1h.is_empty());
+        assert_eq!(buffer.tier4_1h.back().map(|p| p.cpu), Some(40.0));
+        assert!(buffer.oldest_timestamp().unwrap() >= now - 604800);
+    }
 }
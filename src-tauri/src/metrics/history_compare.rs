@@ -0,0 +1,217 @@
+//! Side-by-side comparison of two history windows
+//!
+//! `compare_history(range_a, range_b)` buckets two equally long windows (e.g. today so far
+//! and the same hours a week earlier) by offset from their start, so the charts can draw one
+//! series over the other and the summaries show whether the current load is unusual. Each
+//! window is read at the finest resolution still held for it (see
+//! [`HistoryBuffer::points_between`]); buckets without data are `None`.
+
+use serde::{Deserialize, Serialize};
+
+use super::history::{HistoryBuffer, MetricPoint, RETENTION_SECS};
+use super::history_stats::{self, HistoryStats};
+use crate::state::METRICS_HISTORY;
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+const DEFAULT_BUCKETS: usize = 120;
+const MAX_BUCKETS: usize = 1000;
+
+/// A time window in Unix seconds (`start` inclusive, `end` exclusive)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeWindow {
+    pub start: i64,
+    pub end: i64,
+}
+
+impl TimeWindow {
+    fn duration(&self) -> i64 {
+        self.end - self.start
+    }
+}
+
+/// Averages of both windows at the same offset from their start
+#[derive(Debug, Clone, Serialize)]
+pub struct ComparedPoint {
+    pub offset_seconds: i64,
+    pub a: Option<MetricPoint>,
+    pub b: Option<MetricPoint>,
+}
+
+/// Result of `compare_history`
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryComparison {
+    pub range_a: TimeWindow,
+    pub range_b: TimeWindow,
+    pub bucket_seconds: i64,
+    pub points: Vec<ComparedPoint>,
+    pub stats_a: HistoryStats,
+    pub stats_b: HistoryStats,
+}
+
+fn validate(a: TimeWindow, b: TimeWindow) -> Result<(), String> {
+    for (name, w) in [("range_a", a), ("range_b", b)] {
+        if w.duration() <= 0 {
+            return Err(format!("{} must end after it starts", name));
+        }
+        if w.duration() > RETENTION_SECS as i64 {
+            return Err(format!("{} is longer than the 7-day history", name));
+        }
+    }
+    if a.duration() != b.duration() {
+        return Err(format!(
+            "Ranges must be equally long ({}s vs {}s)",
+            a.duration(),
+            b.duration()
+        ));
+    }
+    Ok(())
+}
+
+/// Average `points` (sorted, all inside `window`) into `buckets` slots of `bucket_seconds`
+fn bucketize(
+    points: &[MetricPoint],
+    window: TimeWindow,
+    bucket_seconds: i64,
+    buckets: usize,
+) -> Vec<Option<MetricPoint>> {
+    let mut slots: Vec<Vec<MetricPoint>> = vec![Vec::new(); buckets];
+    for p in points {
+        let index = ((p.timestamp - window.start) / bucket_seconds) as usize;
+        if let Some(slot) = slots.get_mut(index) {
+            slot.push(p.clone());
+        }
+    }
+    slots
+        .into_iter()
+        .enumerate()
+        .map(|(i, slot)| {
+            (!slot.is_empty()).then(|| {
+                let mut avg = MetricPoint::average(&slot);
+                avg.timestamp = window.start + i as i64 * bucket_seconds;
+                avg
+            })
+        })
+        .collect()
+}
+
+fn compare(
+    history: &HistoryBuffer,
+    range_a: TimeWindow,
+    range_b: TimeWindow,
+    max_points: usize,
+) -> HistoryComparison {
+    let duration = range_a.duration();
+    let bucket_seconds = ((duration + max_points as i64 - 1) / max_points as i64).max(1);
+    let buckets = ((duration + bucket_seconds - 1) / bucket_seconds) as usize;
+
+    let points_a = history.points_between(range_a.start, range_a.end);
+    let points_b = history.points_between(range_b.start, range_b.end);
+    let series_a = bucketize(&points_a, range_a, bucket_seconds, buckets);
+    let series_b = bucketize(&points_b, range_b, bucket_seconds, buckets);
+
+    HistoryComparison {
+        range_a,
+        range_b,
+        bucket_seconds,
+        points: series_a
+            .into_iter()
+            .zip(series_b)
+            .enumerate()
+            .map(|(i, (a, b))| ComparedPoint {
+                offset_seconds: i as i64 * bucket_seconds,
+                a,
+                b,
+            })
+            .collect(),
+        stats_a: history_stats::compute(&points_a, duration as u64),
+        stats_b: history_stats::compute(&points_b, duration as u64),
+    }
+}
+
+/// Two equally long history windows aligned by offset from their start, for chart overlays
+/// (e.g. today vs the same weekday last week). `max_points` caps the buckets (default 120).
+#[tauri::command]
+pub fn compare_history(
+    range_a: TimeWindow,
+    range_b: TimeWindow,
+    max_points: Option<usize>,
+) -> Result<HistoryComparison, String> {
+    validate(range_a, range_b)?;
+    let max_points = max_points.unwrap_or(DEFAULT_BUCKETS).clamp(2, MAX_BUCKETS);
+    let history = METRICS_HISTORY
+        .try_lock()
+        .map_err(|_| "History buffer temporarily unavailable".to_string())?;
+    let Some(history) = history.as_ref() else {
+        return Err("History not yet initialized".to_string());
+    };
+    let comparison = compare(history, range_a, range_b, max_points);
+    debug3!(
+        "compare_history: {} buckets of {}s ({:?} vs {:?})",
+        comparison.points.len(),
+        comparison.bucket_seconds,
+        range_a,
+        range_b
+    );
+    Ok(comparison)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(timestamp: i64, cpu: f32) -> MetricPoint {
+        let mut p =
+            MetricPoint::from_metrics(cpu, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -1.0);
+        p.timestamp = timestamp;
+        p
+    }
+
+    #[test]
+    fn rejects_unequal_or_empty_ranges() {
+        let w = |start, end| TimeWindow { start, end };
+        assert!(validate(w(0, 3600), w(86400, 90000)).is_ok());
+        assert!(validate(w(0, 3600), w(0, 1800)).is_err());
+        assert!(validate(w(10, 10), w(20, 20)).is_err());
+        assert!(validate(w(0, 8 * 86400), w(0, 8 * 86400)).is_err());
+    }
+
+    #[test]
+    fn bucketizes_by_offset_and_leaves_gaps_empty() {
+        let window = TimeWindow {
+            start: 1000,
+            end: 1400,
+        };
+        let points = [point(1000, 10.0), point(1050, 30.0), point(1300, 50.0)];
+        let slots = bucketize(&points, window, 100, 4);
+        let cpu: Vec<Option<f32>> = slots.iter().map(|s| s.as_ref().map(|p| p.cpu)).collect();
+        assert_eq!(cpu, vec![Some(20.0), None, None, Some(50.0)]);
+        assert_eq!(slots[3].as_ref().map(|p| p.timestamp), Some(1300));
+    }
+
+    #[test]
+    fn aligns_two_windows() {
+        let mut history = HistoryBuffer::new();
+        let now = chrono::Utc::now().timestamp();
+        for age in [250, 150, 50] {
+            history.push(point(now - age, 40.0));
+        }
+        let a = TimeWindow {
+            start: now - 300,
+            end: now,
+        };
+        let b = TimeWindow {
+            start: now - 600,
+            end: now - 300,
+        };
+        let comparison = compare(&history, a, b, 3);
+        assert_eq!(comparison.bucket_seconds, 100);
+        assert_eq!(comparison.points.len(), 3);
+        assert!(comparison
+            .points
+            .iter()
+            .all(|p| p.a.is_some() && p.b.is_none()));
+        assert_eq!(comparison.stats_a.cpu.map(|s| s.samples), Some(3));
+        assert!(comparison.stats_b.cpu.is_none());
+    }
+}
@@ -0,0 +1,174 @@
+//! Percentile statistics over metrics history
+//!
+//! Averages hide the spikes users care about, so summaries carry p50/p95/p99 next to
+//! min/avg/max. Percentiles use linear interpolation between the closest ranks.
+//!
+//! History is tiered (see `history.rs`): ranges up to 5 minutes use 1s samples, longer ranges
+//! use 1m/5m/1h bucket averages, so their percentiles describe those averages. `max` still
+//! reflects the highest raw sample because each bucket keeps its min/max band.
+
+use serde::Serialize;
+
+use super::history::{MetricBand, MetricBands, MetricPoint};
+
+/// Distribution of one metric over a range
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricSummary {
+    pub samples: usize,
+    pub min: f32,
+    pub avg: f32,
+    pub max: f32,
+    pub p50: f32,
+    pub p95: f32,
+    pub p99: f32,
+}
+
+/// Result of `get_history_stats`. A metric is `None` when no valid sample exists (e.g.
+/// temperature or power without SMC/IOReport access).
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryStats {
+    pub time_range_seconds: u64,
+    pub from_timestamp: Option<i64>,
+    pub to_timestamp: Option<i64>,
+    pub cpu: Option<MetricSummary>,
+    pub temperature: Option<MetricSummary>,
+    pub cpu_power: Option<MetricSummary>,
+    pub gpu_power: Option<MetricSummary>,
+}
+
+/// Percentile `p` (0–100) of ascending `sorted` values
+fn percentile(sorted: &[f32], p: f64) -> f32 {
+    match sorted.len() {
+        0 => 0.0,
+        1 => sorted[0],
+        n => {
+            let rank = (p / 100.0).clamp(0.0, 1.0) * (n - 1) as f64;
+            let (lo, hi) = (rank.floor() as usize, rank.ceil() as usize);
+            let frac = (rank - lo as f64) as f32;
+            sorted[lo] + (sorted[hi] - sorted[lo]) * frac
+        }
+    }
+}
+
+/// Summarize `(value, band)` pairs; `valid` filters out "not readable" placeholder values.
+fn summarize(
+    values: impl Iterator<Item = (f32, Option<MetricBand>)>,
+    valid: impl Fn(f32) -> bool,
+) -> Option<MetricSummary> {
+    let mut sorted = Vec::new();
+    let (mut min, mut max) = (f32::INFINITY, f32::NEG_INFINITY);
+    for (value, band) in values.filter(|(v, _)| v.is_finite() && valid(*v)) {
+        let band = band.unwrap_or(MetricBand {
+            min: value,
+            max: value,
+        });
+        min = min.min(band.min);
+        max = max.max(band.max);
+        sorted.push(value);
+    }
+    if sorted.is_empty() {
+        return None;
+    }
+    sorted.sort_by(f32::total_cmp);
+    let avg = (sorted.iter().map(|v| *v as f64).sum::<f64>() / sorted.len() as f64) as f32;
+    Some(MetricSummary {
+        samples: sorted.len(),
+        min,
+        avg,
+        max,
+        p50: percentile(&sorted, 50.0),
+        p95: percentile(&sorted, 95.0),
+        p99: percentile(&sorted, 99.0),
+    })
+}
+
+/// Summarize CPU usage, temperature and power for `points` (already limited to the range).
+pub fn compute(points: &[MetricPoint], time_range_seconds: u64) -> HistoryStats {
+    let metric = |value: fn(&MetricPoint) -> f32, band: fn(&MetricBands) -> MetricBand| {
+        move |p: &MetricPoint| (value(p), p.bands.as_deref().map(band))
+    };
+    let positive = |v: f32| v > 0.0;
+    HistoryStats {
+        time_range_seconds,
+        from_timestamp: points.iter().map(|p| p.timestamp).min(),
+        to_timestamp: points.iter().map(|p| p.timestamp).max(),
+        cpu: summarize(points.iter().map(metric(|p| p.cpu, |b| b.cpu)), |v| {
+            v >= 0.0
+        }),
+        temperature: summarize(
+            points
+                .iter()
+                .map(metric(|p| p.temperature, |b| b.temperature)),
+            positive,
+        ),
+        cpu_power: summarize(
+            points.iter().map(metric(|p| p.cpu_power, |b| b.cpu_power)),
+            positive,
+        ),
+        gpu_power: summarize(
+            points.iter().map(metric(|p| p.gpu_power, |b| b.gpu_power)),
+            positive,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(timestamp: i64, cpu: f32, temperature: f32) -> MetricPoint {
+        let mut p = MetricPoint::from_metrics(
+            cpu,
+            0.0,
+            0.0,
+            0.0,
+            temperature,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            -1.0,
+        );
+        p.timestamp = timestamp;
+        p
+    }
+
+    #[test]
+    fn percentiles_interpolate_between_ranks() {
+        let sorted: Vec<f32> = (1..=100).map(|v| v as f32).collect();
+        assert_eq!(percentile(&sorted, 50.0), 50.5);
+        assert!((percentile(&sorted, 95.0) - 95.05).abs() < 1e-4);
+        assert!((percentile(&sorted, 99.0) - 99.01).abs() < 1e-4);
+        assert_eq!(percentile(&[7.0], 99.0), 7.0);
+    }
+
+    #[test]
+    fn spikes_show_in_p99_and_unreadable_sensors_are_skipped() {
+        // 98 quiet seconds and two spikes: the average barely moves, p99 does.
+        let points: Vec<MetricPoint> = (0..100)
+            .map(|i| point(i, if i % 50 == 0 { 100.0 } else { 5.0 }, 0.0))
+            .collect();
+        let stats = compute(&points, 300);
+        let cpu = stats.cpu.unwrap();
+        assert_eq!(cpu.samples, 100);
+        assert!((cpu.avg - 6.9).abs() < 1e-4);
+        assert_eq!(cpu.p50, 5.0);
+        assert_eq!(cpu.max, 100.0);
+        assert!(cpu.p99 > 90.0);
+        assert!(stats.temperature.is_none());
+        assert_eq!(
+            (stats.from_timestamp, stats.to_timestamp),
+            (Some(0), Some(99))
+        );
+    }
+
+    #[test]
+    fn max_uses_bucket_bands() {
+        let bucket = MetricPoint::average(&[point(0, 10.0, 50.0), point(1, 90.0, 70.0)]);
+        let stats = compute(&[bucket], 3600);
+        let cpu = stats.cpu.unwrap();
+        assert_eq!((cpu.min, cpu.p99, cpu.max), (10.0, 50.0, 90.0));
+        assert_eq!(stats.temperature.unwrap().max, 70.0);
+    }
+}
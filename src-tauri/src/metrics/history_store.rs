@@ -0,0 +1,634 @@
+//! Optional SQLite history store (`historyStore: "sqlite"`)
+//!
+//! Replaces the JSON snapshot + write-ahead log as the durable copy of metrics history and
+//! keeps it far longer than the in-memory tiers. Three tables with the same columns:
+//!
+//! - `samples_raw`: every sample, kept 24 hours
+//! - `samples_1m`: 1-minute averages with min/max bands, kept 7 days
+//! - `samples_1h`: 1-hour averages with min/max bands, kept `historyRetentionDays` (default 30)
+//!
+//! Each sample is inserted as it is taken (SQLite WAL journal, so a crash loses nothing that
+//! was committed). Once a minute, completed minutes are rolled up from raw rows and completed
+//! hours from minute rows, then expired rows are deleted. The in-memory [`HistoryBuffer`] is
+//! still what charts, stats and the API read; at startup it is seeded from the store, and
+//! ranges longer than its 7 days are answered from the store.
+//!
+//! A `gaps` table keeps the sleep / app-not-running stretches ([`HistoryGap`]) for the same
+//! retention as the hourly rows, so charts can still break the line after a restart.
+
+use std::path::Path;
+use std::time::Instant;
+
+use rusqlite::{params, Connection, OpenFlags};
+
+use super::history::{GapReason, HistoryBuffer, HistoryGap, MetricPoint, RETENTION_SECS};
+use crate::config::Config;
+use crate::state::HISTORY_STORE;
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+const RAW_TABLE: &str = "samples_raw";
+const MINUTE_TABLE: &str = "samples_1m";
+const HOUR_TABLE: &str = "samples_1h";
+const GAPS_TABLE: &str = "gaps";
+/// Finest first (the order `points_between` prefers them in)
+const TABLES: [&str; 3] = [RAW_TABLE, MINUTE_TABLE, HOUR_TABLE];
+const RAW_KEEP_SECS: i64 = 86400;
+const MINUTE_KEEP_SECS: i64 = 7 * 86400;
+const MAINTENANCE_INTERVAL_SECS: u64 = 60;
+
+const COLUMNS: &str = "ts, cpu, gpu, ram, disk, temperature, frequency, p_core_frequency, \
+                       e_core_frequency, cpu_power, gpu_power, battery_level, net_rx, net_tx, \
+                       disk_read, disk_write, battery_power, adapter_power, bands, plugins";
+/// Columns added after the first release of the store (added to older databases on open)
+const ADDED_COLUMNS: [&str; 6] = [
+    "net_rx",
+    "net_tx",
+    "disk_read",
+    "disk_write",
+    "battery_power",
+    "adapter_power",
+];
+/// JSON columns added after the first release (NULL in older rows)
+const ADDED_TEXT_COLUMNS: [&str; 1] = ["plugins"];
+
+fn db_err(e: rusqlite::Error) -> String {
+    format!("History database error: {}", e)
+}
+
+fn now_secs() -> i64 {
+    chrono::Utc::now().timestamp()
+}
+
+fn row_to_point(row: &rusqlite::Row) -> rusqlite::Result<MetricPoint> {
+    let bands: Option<String> = row.get(18)?;
+    let plugins: Option<String> = row.get(19)?;
+    Ok(MetricPoint {
+        timestamp: row.get(0)?,
+        cpu: row.get(1)?,
+        gpu: row.get(2)?,
+        ram: row.get(3)?,
+        disk: row.get(4)?,
+        temperature: row.get(5)?,
+        frequency: row.get(6)?,
+        p_core_frequency: row.get(7)?,
+        e_core_frequency: row.get(8)?,
+        cpu_power: row.get(9)?,
+        gpu_power: row.get(10)?,
+        battery_level: row.get(11)?,
+        net_rx: row.get(12)?,
+        net_tx: row.get(13)?,
+        disk_read: row.get(14)?,
+        disk_write: row.get(15)?,
+        battery_power: row.get(16)?,
+        adapter_power: row.get(17)?,
+        plugins: plugins
+            .and_then(|p| serde_json::from_str(&p).ok())
+            .unwrap_or_default(),
+        bands: bands
+            .and_then(|b| serde_json::from_str(&b).ok())
+            .map(Box::new),
+    })
+}
+
+pub struct HistoryStore {
+    conn: Connection,
+    retention_secs: i64,
+    last_maintenance: Option<Instant>,
+}
+
+impl HistoryStore {
+    /// Open (or create) the store at `path`
+    pub fn open(path: &Path, retention_days: u32) -> Result<Self, String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let conn = Connection::open(path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")
+            .map_err(db_err)?;
+        Self::init(conn, retention_days)
+    }
+
+    /// Open an existing store without writing to it (for the `export` CLI)
+    pub fn open_read_only(path: &Path) -> Result<Self, String> {
+        let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        Ok(Self {
+            conn,
+            retention_secs: 0,
+            last_maintenance: None,
+        })
+    }
+
+    fn init(conn: Connection, retention_days: u32) -> Result<Self, String> {
+        for table in TABLES {
+            conn.execute_batch(&format!(
+                "CREATE TABLE IF NOT EXISTS {} (
+                    ts INTEGER PRIMARY KEY,
+                    cpu REAL NOT NULL, gpu REAL NOT NULL, ram REAL NOT NULL, disk REAL NOT NULL,
+                    temperature REAL NOT NULL, frequency REAL NOT NULL,
+                    p_core_frequency REAL NOT NULL, e_core_frequency REAL NOT NULL,
+                    cpu_power REAL NOT NULL, gpu_power REAL NOT NULL,
+                    battery_level REAL NOT NULL,
+                    net_rx REAL NOT NULL DEFAULT 0, net_tx REAL NOT NULL DEFAULT 0,
+                    disk_read REAL NOT NULL DEFAULT 0, disk_write REAL NOT NULL DEFAULT 0,
+                    battery_power REAL NOT NULL DEFAULT 0, adapter_power REAL NOT NULL DEFAULT 0,
+                    bands TEXT, plugins TEXT
+                )",
+                table
+            ))
+            .map_err(db_err)?;
+            Self::add_missing_columns(&conn, table)?;
+        }
+        conn.execute_batch(&format!(
+            "CREATE TABLE IF NOT EXISTS {} (
+                start_ts INTEGER PRIMARY KEY, end_ts INTEGER NOT NULL, reason TEXT NOT NULL
+            )",
+            GAPS_TABLE
+        ))
+        .map_err(db_err)?;
+        Ok(Self {
+            conn,
+            retention_secs: i64::from(retention_days) * 86400,
+            last_maintenance: None,
+        })
+    }
+
+    fn add_missing_columns(conn: &Connection, table: &str) -> Result<(), String> {
+        let mut stmt = conn
+            .prepare(&format!("PRAGMA table_info({})", table))
+            .map_err(db_err)?;
+        let existing = stmt
+            .query_map([], |row| row.get::<_, String>(1))
+            .map_err(db_err)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(db_err)?;
+        for column in ADDED_COLUMNS {
+            if !existing.iter().any(|c| c == column) {
+                conn.execute_batch(&format!(
+                    "ALTER TABLE {} ADD COLUMN {} REAL NOT NULL DEFAULT 0",
+                    table, column
+                ))
+                .map_err(db_err)?;
+            }
+        }
+        for column in ADDED_TEXT_COLUMNS {
+            if !existing.iter().any(|c| c == column) {
+                conn.execute_batch(&format!("ALTER TABLE {} ADD COLUMN {} TEXT", table, column))
+                    .map_err(db_err)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn insert_into(conn: &Connection, table: &str, p: &MetricPoint) -> Result<(), String> {
+        let bands = p
+            .bands
+            .as_deref()
+            .and_then(|b| serde_json::to_string(b).ok());
+        let plugins = (!p.plugins.is_empty())
+            .then(|| serde_json::to_string(&p.plugins).ok())
+            .flatten();
+        conn.execute(
+            &format!(
+                "INSERT OR REPLACE INTO {} ({}) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, \
+                 ?17, ?18, ?19, ?20)",
+                table, COLUMNS
+            ),
+            params![
+                p.timestamp,
+                p.cpu,
+                p.gpu,
+                p.ram,
+                p.disk,
+                p.temperature,
+                p.frequency,
+                p.p_core_frequency,
+                p.e_core_frequency,
+                p.cpu_power,
+                p.gpu_power,
+                p.battery_level,
+                p.net_rx,
+                p.net_tx,
+                p.disk_read,
+                p.disk_write,
+                p.battery_power,
+                p.adapter_power,
+                bands,
+                plugins
+            ],
+        )
+        .map(|_| ())
+        .map_err(db_err)
+    }
+
+    /// Record one live sample
+    pub fn insert(&mut self, point: &MetricPoint) -> Result<(), String> {
+        Self::insert_into(&self.conn, RAW_TABLE, point)
+    }
+
+    /// Record a sleep / app-not-running stretch
+    pub fn insert_gap(&mut self, gap: &HistoryGap) -> Result<(), String> {
+        let reason = serde_json::to_value(gap.reason).map_err(|e| e.to_string())?;
+        self.conn
+            .execute(
+                &format!(
+                    "INSERT OR REPLACE INTO {} (start_ts, end_ts, reason) VALUES (?1, ?2, ?3)",
+                    GAPS_TABLE
+                ),
+                params![gap.start, gap.end, reason.as_str()],
+            )
+            .map(|_| ())
+            .map_err(db_err)
+    }
+
+    /// Gaps ending at or after `since`, oldest first (rows with an unknown reason are skipped)
+    pub fn gaps_since(&self, since: i64) -> Result<Vec<HistoryGap>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!(
+                "SELECT start_ts, end_ts, reason FROM {} WHERE end_ts >= ?1 ORDER BY start_ts",
+                GAPS_TABLE
+            ))
+            .map_err(db_err)?;
+        let rows = stmt
+            .query_map(params![since], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })
+            .map_err(db_err)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(db_err)?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|(start, end, reason)| {
+                let reason: GapReason =
+                    serde_json::from_value(serde_json::Value::String(reason)).ok()?;
+                Some(HistoryGap { start, end, reason })
+            })
+            .collect())
+    }
+
+    /// Carry over history from another source (the JSON snapshot when switching to SQLite).
+    /// Points go to the minute table; hourly rollups are built from them on the next
+    /// maintenance run.
+    pub fn import(&mut self, points: &[MetricPoint]) -> Result<usize, String> {
+        let tx = self.conn.transaction().map_err(db_err)?;
+        for p in points {
+            Self::insert_into(&tx, MINUTE_TABLE, p)?;
+        }
+        tx.commit().map_err(db_err)?;
+        Ok(points.len())
+    }
+
+    pub fn is_empty(&self) -> Result<bool, String> {
+        for table in TABLES {
+            let rows: i64 = self
+                .conn
+                .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |r| r.get(0))
+                .map_err(db_err)?;
+            if rows > 0 {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn select(&self, table: &str, start: i64, end: i64) -> Result<Vec<MetricPoint>, String> {
+        let mut stmt = self
+            .conn
+            .prepare_cached(&format!(
+                "SELECT {} FROM {} WHERE ts >= ?1 AND ts < ?2 ORDER BY ts",
+                COLUMNS, table
+            ))
+            .map_err(db_err)?;
+        let rows = stmt
+            .query_map(params![start, end], row_to_point)
+            .map_err(db_err)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(db_err)
+    }
+
+    /// Points with `start <= timestamp < end`, at the finest resolution stored for each part of
+    /// the window (raw, then minute, then hourly rows further back)
+    pub fn points_between(&self, start: i64, end: i64) -> Result<Vec<MetricPoint>, String> {
+        let mut points: Vec<MetricPoint> = Vec::new();
+        let mut covered_from = end;
+        for table in TABLES {
+            let older = self.select(table, start, covered_from)?;
+            if let Some(first) = older.first() {
+                covered_from = first.timestamp;
+            }
+            points.extend(older);
+        }
+        points.sort_by_key(|p| p.timestamp);
+        Ok(points)
+    }
+
+    /// Average completed `bucket`-second periods of `from` that `to` does not have yet.
+    /// Periods still in progress at `now` are left for a later run.
+    fn rollup(&mut self, from: &str, to: &str, bucket: i64, now: i64) -> Result<usize, String> {
+        let last: Option<i64> = self
+            .conn
+            .query_row(&format!("SELECT MAX(ts) FROM {}", to), [], |r| r.get(0))
+            .map_err(db_err)?;
+        let since = last.map_or(i64::MIN, |t| t + bucket);
+        let until = now - now.rem_euclid(bucket);
+        let points = self.select(from, since, until)?;
+        let mut rollups = Vec::new();
+        for group in
+            points.chunk_by(|a, b| a.timestamp.div_euclid(bucket) == b.timestamp.div_euclid(bucket))
+        {
+            let mut avg = MetricPoint::average(group);
+            avg.timestamp = group[0].timestamp - group[0].timestamp.rem_euclid(bucket);
+            rollups.push(avg);
+        }
+        let tx = self.conn.transaction().map_err(db_err)?;
+        for p in &rollups {
+            Self::insert_into(&tx, to, p)?;
+        }
+        tx.commit().map_err(db_err)?;
+        Ok(rollups.len())
+    }
+
+    /// Roll up completed minutes and hours, then delete rows past each table's retention
+    pub fn maintain(&mut self, now: i64) -> Result<(), String> {
+        let minutes = self.rollup(RAW_TABLE, MINUTE_TABLE, 60, now)?;
+        let hours = self.rollup(MINUTE_TABLE, HOUR_TABLE, 3600, now)?;
+        let mut expired = 0;
+        for (table, keep) in [
+            (RAW_TABLE, RAW_KEEP_SECS),
+            (MINUTE_TABLE, MINUTE_KEEP_SECS),
+            (HOUR_TABLE, self.retention_secs),
+        ] {
+            expired += self
+                .conn
+                .execute(
+                    &format!("DELETE FROM {} WHERE ts < ?1", table),
+                    params![now - keep],
+                )
+                .map_err(db_err)?;
+        }
+        expired += self
+            .conn
+            .execute(
+                &format!("DELETE FROM {} WHERE end_ts < ?1", GAPS_TABLE),
+                params![now - self.retention_secs],
+            )
+            .map_err(db_err)?;
+        debug3!(
+            "History store: {} minute and {} hour rollup(s), {} expired row(s)",
+            minutes,
+            hours,
+            expired
+        );
+        self.last_maintenance = Some(Instant::now());
+        Ok(())
+    }
+
+    fn maintenance_due(&self) -> bool {
+        self.last_maintenance
+            .is_none_or(|t| t.elapsed().as_secs() >= MAINTENANCE_INTERVAL_SECS)
+    }
+}
+
+/// Longest history range that can be answered: the store's retention when it is enabled,
+/// otherwise the 7 days the in-memory tiers hold
+pub fn max_range_secs() -> u64 {
+    if Config::history_persistence_enabled() && Config::history_sqlite_enabled() {
+        u64::from(Config::history_retention_days()) * 86400
+    } else {
+        RETENTION_SECS
+    }
+}
+
+/// Open the store, carry over the JSON history on first use and return an in-memory buffer
+/// seeded with the last 7 days. On failure history still works, just without persistence.
+pub fn open_and_seed() -> HistoryBuffer {
+    let mut buffer = HistoryBuffer::new();
+    let path = Config::history_db_path();
+    let mut store = match HistoryStore::open(&path, Config::history_retention_days()) {
+        Ok(store) => store,
+        Err(e) => {
+            tracing::warn!("History store unavailable, history will not persist: {}", e);
+            return buffer;
+        }
+    };
+    if store.is_empty().unwrap_or(false) {
+        match super::history_wal::HistoryWal::load_read_only() {
+            Ok(previous) => {
+                let points = previous.points_between(i64::MIN, i64::MAX);
+                match store.import(&points) {
+                    Ok(n) if n > 0 => {
+                        tracing::info!("History store: carried over {} point(s) from JSON", n)
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("History store: JSON carry-over failed: {}", e),
+                }
+            }
+            Err(e) => {
+                debug2!("History store: no JSON history to carry over ({})", e);
+            }
+        }
+    }
+    let now = now_secs();
+    // Rollups and expiry can be heavy after a long break; `startupDelaySecs` defers them
+    if !crate::startup::in_startup_delay() {
+        if let Err(e) = store.maintain(now) {
+            tracing::warn!("History store maintenance failed: {}", e);
+        }
+    }
+    match store.points_between(now - RETENTION_SECS as i64, i64::MAX) {
+        Ok(points) => {
+            let added = buffer.backfill(points);
+            debug2!("History store: seeded {} point(s) into memory", added);
+        }
+        Err(e) => tracing::warn!("History store read failed: {}", e),
+    }
+    match store.gaps_since(now - store.retention_secs) {
+        Ok(gaps) => buffer.restore_gaps(gaps),
+        Err(e) => tracing::warn!("History store gap read failed: {}", e),
+    }
+    if let Ok(mut slot) = HISTORY_STORE.lock() {
+        *slot = Some(store);
+    }
+    buffer
+}
+
+/// Persist one live sample and the gap it closes (no-op unless the store is open)
+pub fn record(point: &MetricPoint, gap: Option<&HistoryGap>) {
+    let Ok(mut slot) = HISTORY_STORE.try_lock() else {
+        debug3!("History store busy, sample not persisted");
+        return;
+    };
+    let Some(store) = slot.as_mut() else {
+        return;
+    };
+    if let Err(e) = store.insert(point) {
+        debug1!("History store insert failed: {}", e);
+    }
+    if let Some(gap) = gap {
+        if let Err(e) = store.insert_gap(gap) {
+            debug1!("History store gap insert failed: {}", e);
+        }
+    }
+    if store.maintenance_due() && !crate::startup::in_startup_delay() {
+        if let Err(e) = store.maintain(now_secs()) {
+            debug1!("History store maintenance failed: {}", e);
+        }
+    }
+}
+
+/// Points and gaps from `start` on, read from the persisted history (the store when enabled,
+/// else the snapshot and log) without opening it for writing, so it works whether or not the
+/// app is running
+pub fn load_persisted_since(start: i64) -> Result<(Vec<MetricPoint>, Vec<HistoryGap>), String> {
+    let db_path = Config::history_db_path();
+    if Config::history_sqlite_enabled() && db_path.exists() {
+        let store = HistoryStore::open_read_only(&db_path)?;
+        Ok((
+            store.points_between(start, i64::MAX)?,
+            store.gaps_since(start)?,
+        ))
+    } else {
+        let history = super::history_wal::HistoryWal::load_read_only()?;
+        Ok((
+            history.points_between(start, i64::MAX),
+            history.gaps_between(start, now_secs()),
+        ))
+    }
+}
+
+/// The last `range_secs` from the store, when the range reaches past the in-memory tiers and
+/// the store is open. `None` means the in-memory buffer covers it.
+pub fn query_long_range(range_secs: u64) -> Option<Result<Vec<MetricPoint>, String>> {
+    if range_secs <= RETENTION_SECS {
+        return None;
+    }
+    let slot = HISTORY_STORE.lock().ok()?;
+    let store = slot.as_ref()?;
+    let now = now_secs();
+    Some(store.points_between(now - range_secs as i64, i64::MAX))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> HistoryStore {
+        HistoryStore::init(Connection::open_in_memory().unwrap(), 30).unwrap()
+    }
+
+    fn point(timestamp: i64, cpu: f32) -> MetricPoint {
+        let mut p =
+            MetricPoint::from_metrics(cpu, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -1.0);
+        p.timestamp = timestamp;
+        p
+    }
+
+    #[test]
+    fn round_trips_points_with_bands() {
+        let mut s = store();
+        let avg = MetricPoint::average(&[point(60, 10.0), point(61, 30.0)]);
+        s.insert(&point(500, 12.5)).unwrap();
+        s.import(std::slice::from_ref(&avg)).unwrap();
+        let points = s.points_between(0, 1000).unwrap();
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].bands.as_ref().map(|b| b.cpu.max), Some(30.0));
+        assert_eq!((points[1].timestamp, points[1].cpu), (500, 12.5));
+        assert!(!s.is_empty().unwrap());
+    }
+
+    #[test]
+    fn adds_io_columns_to_older_databases() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE samples_raw (ts INTEGER PRIMARY KEY, cpu REAL NOT NULL, \
+             gpu REAL NOT NULL, ram REAL NOT NULL, disk REAL NOT NULL, \
+             temperature REAL NOT NULL, frequency REAL NOT NULL, \
+             p_core_frequency REAL NOT NULL, e_core_frequency REAL NOT NULL, \
+             cpu_power REAL NOT NULL, gpu_power REAL NOT NULL, \
+             battery_level REAL NOT NULL, bands TEXT); \
+             INSERT INTO samples_raw VALUES (7, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, -1, NULL);",
+        )
+        .unwrap();
+        let mut s = HistoryStore::init(conn, 30).unwrap();
+        let mut p = point(8, 2.0);
+        p.net_rx = 512.0;
+        s.insert(&p).unwrap();
+        let points = s.points_between(0, 100).unwrap();
+        let got: Vec<(i64, f32)> = points.iter().map(|p| (p.timestamp, p.net_rx)).collect();
+        assert_eq!(got, vec![(7, 0.0), (8, 512.0)]);
+    }
+
+    #[test]
+    fn rolls_up_completed_minutes_and_hours_once() {
+        let mut s = store();
+        let base = 1_700_000_000 - 1_700_000_000 % 3600;
+        for (offset, cpu) in [(0, 10.0), (30, 30.0), (60, 50.0), (3600, 90.0)] {
+            s.insert(&point(base + offset, cpu)).unwrap();
+        }
+        let now = base + 3600 + 30;
+        s.maintain(now).unwrap();
+        let minutes = s.select(MINUTE_TABLE, 0, i64::MAX).unwrap();
+        let got: Vec<(i64, f32)> = minutes
+            .iter()
+            .map(|p| (p.timestamp - base, p.cpu))
+            .collect();
+        // The minute starting at base + 3600 is still in progress
+        assert_eq!(got, vec![(0, 20.0), (60, 50.0)]);
+        let hours = s.select(HOUR_TABLE, 0, i64::MAX).unwrap();
+        assert_eq!(hours.len(), 1);
+        assert_eq!(hours[0].timestamp, base);
+        assert_eq!(hours[0].bands.as_ref().map(|b| b.cpu.max), Some(50.0));
+
+        s.maintain(now).unwrap();
+        assert_eq!(s.select(MINUTE_TABLE, 0, i64::MAX).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn expires_rows_per_table_and_prefers_finer_rows() {
+        let mut s = store();
+        let now = 100 * 86400;
+        s.insert(&point(now - 2 * 86400, 1.0)).unwrap();
+        s.import(&[point(now - 10 * 86400, 2.0), point(now - 3 * 86400, 3.0)])
+            .unwrap();
+        s.maintain(now).unwrap();
+        assert!(s.select(RAW_TABLE, 0, i64::MAX).unwrap().is_empty());
+        let minutes = s.select(MINUTE_TABLE, 0, i64::MAX).unwrap();
+        assert_eq!(
+            minutes.len(),
+            2,
+            "raw row rolled up, 10-day-old minute expired"
+        );
+        let points = s.points_between(now - 20 * 86400, now).unwrap();
+        let got: Vec<f32> = points.iter().map(|p| p.cpu).collect();
+        // Hourly rollups exist for all three, but minute rows win where they exist
+        assert_eq!(got, vec![2.0, 3.0, 1.0]);
+    }
+
+    #[test]
+    fn keeps_gaps_until_retention() {
+        let mut s = store();
+        let now = 100 * 86400;
+        let old = HistoryGap {
+            start: now - 40 * 86400,
+            end: now - 40 * 86400 + 3600,
+            reason: GapReason::Sleep,
+        };
+        let recent = HistoryGap {
+            start: now - 7200,
+            end: now - 60,
+            reason: GapReason::AppNotRunning,
+        };
+        s.insert_gap(&old).unwrap();
+        s.insert_gap(&recent).unwrap();
+        assert_eq!(s.gaps_since(0).unwrap(), vec![old, recent.clone()]);
+        s.maintain(now).unwrap();
+        assert_eq!(s.gaps_since(0).unwrap(), vec![recent]);
+    }
+}
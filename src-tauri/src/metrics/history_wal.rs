@@ -0,0 +1,329 @@
+//! Crash-resilient persistence for metrics history
+//!
+//! History is stored as a snapshot (`~/.mac-stats/history.json`, replaced atomically) plus an
+//! append-only write-ahead log (`~/.mac-stats/history.wal`) holding every sample taken since
+//! that snapshot:
+//!
+//! - Each sample is appended as one line, `<json>\t<fnv1a-32 hex>\n`, with a single `write`.
+//!   The page cache keeps it across a crash or force quit; the file is `fsync`ed every
+//!   [`WAL_SYNC_INTERVAL_SECS`], so power loss drops at most that many seconds.
+//! - Every [`CHECKPOINT_INTERVAL_SECS`] (and on clean exit) the full buffer is snapshotted and
+//!   the log truncated.
+//! - On startup the snapshot is loaded and the log replayed. A torn or corrupt tail record
+//!   ends replay and is cut off; records already covered by the snapshot (crash between
+//!   snapshot and truncate) are skipped. An unreadable snapshot is moved aside to
+//!   `history.json.corrupt` instead of blocking startup.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use super::history::{HistoryBuffer, MetricPoint};
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+/// Max seconds of appended samples that may be lost on power failure
+const WAL_SYNC_INTERVAL_SECS: u64 = 5;
+/// How often the buffer is snapshotted and the log truncated
+const CHECKPOINT_INTERVAL_SECS: u64 = 300;
+
+/// Open write-ahead log for the running history buffer
+pub struct HistoryWal {
+    snapshot_path: PathBuf,
+    wal_path: PathBuf,
+    file: File,
+    last_sync: Instant,
+    last_checkpoint: Instant,
+    dirty: bool,
+}
+
+/// FNV-1a (32-bit) over a record's JSON; detects torn or garbled lines without extra deps
+fn checksum(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5u32, |hash, b| {
+        (hash ^ u32::from(*b)).wrapping_mul(0x0100_0193)
+    })
+}
+
+fn encode_record(point: &MetricPoint) -> Result<String, String> {
+    let json = serde_json::to_string(point).map_err(|e| e.to_string())?;
+    let sum = checksum(json.as_bytes());
+    Ok(format!("{}\t{:08x}\n", json, sum))
+}
+
+/// Decode log records up to the first invalid one. Returns the points and the byte length
+/// of the valid prefix (everything after it is a torn or corrupt tail).
+fn decode_records(data: &[u8]) -> (Vec<MetricPoint>, usize) {
+    let mut points = Vec::new();
+    let mut valid_len = 0;
+    for line in data.split_inclusive(|b| *b == b'\n') {
+        let Some(record) = line.strip_suffix(b"\n") else {
+            break; // last write never completed
+        };
+        let Some(tab) = record.iter().rposition(|b| *b == b'\t') else {
+            break;
+        };
+        let (json, sum) = (&record[..tab], &record[tab + 1..]);
+        let expected = std::str::from_utf8(sum)
+            .ok()
+            .and_then(|s| u32::from_str_radix(s, 16).ok());
+        if expected != Some(checksum(json)) {
+            break;
+        }
+        match serde_json::from_slice::<MetricPoint>(json) {
+            Ok(point) => points.push(point),
+            Err(_) => break,
+        }
+        valid_len += line.len();
+    }
+    (points, valid_len)
+}
+
+impl HistoryWal {
+    /// Load the snapshot, replay the log on top and reopen the log for appending.
+    /// Always returns a usable buffer; the log is `None` when it cannot be opened.
+    pub fn recover() -> (HistoryBuffer, Option<Self>) {
+        Self::recover_at(
+            crate::config::Config::history_file_path(),
+            crate::config::Config::history_wal_path(),
+        )
+    }
+
+    /// Snapshot plus replayed log, without opening the log or rewriting either file. For the
+    /// `export` CLI, which may run while the app is appending.
+    pub fn load_read_only() -> Result<HistoryBuffer, String> {
+        let mut history =
+            HistoryBuffer::load_from_path(&crate::config::Config::history_file_path())?;
+        let newest = history.newest_timestamp().unwrap_or(i64::MIN);
+        let data = std::fs::read(crate::config::Config::history_wal_path()).unwrap_or_default();
+        for point in decode_records(&data).0 {
+            if point.timestamp > newest {
+                history.push(point);
+            }
+        }
+        Ok(history)
+    }
+
+    fn recover_at(snapshot_path: PathBuf, wal_path: PathBuf) -> (HistoryBuffer, Option<Self>) {
+        let mut history = match HistoryBuffer::load_from_path(&snapshot_path) {
+            Ok(history) => history,
+            Err(e) => {
+                let aside = snapshot_path.with_extension("json.corrupt");
+                tracing::warn!(
+                    "History snapshot unreadable ({}); moving it to {}",
+                    e,
+                    aside.display()
+                );
+                let _ = std::fs::rename(&snapshot_path, &aside);
+                HistoryBuffer::new()
+            }
+        };
+        let snapshot_newest = history.newest_timestamp().unwrap_or(i64::MIN);
+        let snapshot_points = history.total_points();
+
+        let data = std::fs::read(&wal_path).unwrap_or_default();
+        let (points, valid_len) = decode_records(&data);
+        if valid_len < data.len() {
+            tracing::warn!(
+                "History log {}: dropped {} bytes of torn/corrupt tail",
+                wal_path.display(),
+                data.len() - valid_len
+            );
+        }
+        let mut replayed = 0;
+        for point in points {
+            if point.timestamp > snapshot_newest {
+                history.push(point);
+                replayed += 1;
+            }
+        }
+        debug2!(
+            "History recovered: {} points from snapshot + {} replayed from log",
+            snapshot_points,
+            replayed
+        );
+
+        let mut wal = match Self::open(snapshot_path, wal_path) {
+            Ok(wal) => wal,
+            Err(e) => {
+                tracing::warn!("History persistence disabled: {}", e);
+                return (history, None);
+            }
+        };
+        // Fold the replayed samples into a fresh snapshot so the log starts empty and clean.
+        if replayed > 0 || valid_len < data.len() {
+            wal.dirty = true;
+            if let Err(e) = wal.checkpoint(&history) {
+                tracing::warn!("History checkpoint after recovery failed: {}", e);
+            }
+        }
+        (history, Some(wal))
+    }
+
+    fn open(snapshot_path: PathBuf, wal_path: PathBuf) -> Result<Self, String> {
+        if let Some(dir) = wal_path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&wal_path)
+            .map_err(|e| format!("Failed to open {}: {}", wal_path.display(), e))?;
+        Ok(Self {
+            snapshot_path,
+            wal_path,
+            file,
+            last_sync: Instant::now(),
+            last_checkpoint: Instant::now(),
+            dirty: false,
+        })
+    }
+
+    /// Append one sample (a single `write`, `fsync`ed every [`WAL_SYNC_INTERVAL_SECS`]).
+    pub fn append(&mut self, point: &MetricPoint) -> Result<(), String> {
+        let record = encode_record(point)?;
+        self.file
+            .write_all(record.as_bytes())
+            .map_err(|e| format!("History log write failed: {}", e))?;
+        self.dirty = true;
+        if self.last_sync.elapsed().as_secs() >= WAL_SYNC_INTERVAL_SECS {
+            self.file
+                .sync_data()
+                .map_err(|e| format!("History log sync failed: {}", e))?;
+            self.last_sync = Instant::now();
+        }
+        Ok(())
+    }
+
+    /// True when the log has grown for [`CHECKPOINT_INTERVAL_SECS`] since the last snapshot
+    pub fn checkpoint_due(&self) -> bool {
+        self.dirty && self.last_checkpoint.elapsed().as_secs() >= CHECKPOINT_INTERVAL_SECS
+    }
+
+    /// Snapshot `history` atomically, then truncate the log. A crash in between only leaves
+    /// records that replay skips because the snapshot already covers them.
+    pub fn checkpoint(&mut self, history: &HistoryBuffer) -> Result<(), String> {
+        if !self.dirty {
+            return Ok(());
+        }
+        history.save_to_path(&self.snapshot_path)?;
+        self.file
+            .set_len(0)
+            .and_then(|_| self.file.sync_all())
+            .map_err(|e| format!("Failed to truncate {}: {}", self.wal_path.display(), e))?;
+        self.last_checkpoint = Instant::now();
+        self.last_sync = Instant::now();
+        self.dirty = false;
+        debug3!(
+            "History checkpoint: {} points to {}",
+            history.total_points(),
+            self.snapshot_path.display()
+        );
+        Ok(())
+    }
+}
+
+/// Snapshot and truncate the log on clean exit. Best effort: skipped under lock contention,
+/// since the log already holds every sample.
+pub fn checkpoint_on_exit() {
+    use crate::state::{HISTORY_WAL, METRICS_HISTORY};
+    let Ok(history) = METRICS_HISTORY.try_lock() else {
+        return;
+    };
+    let (Some(history), Ok(mut wal)) = (history.as_ref(), HISTORY_WAL.try_lock()) else {
+        return;
+    };
+    if let Some(wal) = wal.as_mut() {
+        if let Err(e) = wal.checkpoint(history) {
+            tracing::warn!("History checkpoint on exit failed: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Snapshot/log paths in a fresh temp dir (keeps tests away from `~/.mac-stats`)
+    fn temp_paths(name: &str) -> (PathBuf, PathBuf) {
+        let dir = std::env::temp_dir().join(format!(
+            "mac-stats-wal-test-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        (dir.join("history.json"), dir.join("history.wal"))
+    }
+
+    fn point(timestamp: i64, cpu: f32) -> MetricPoint {
+        let mut p =
+            MetricPoint::from_metrics(cpu, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -1.0);
+        p.timestamp = timestamp;
+        p
+    }
+
+    fn wal_bytes(points: &[MetricPoint]) -> Vec<u8> {
+        points
+            .iter()
+            .map(|p| encode_record(p).unwrap())
+            .collect::<String>()
+            .into_bytes()
+    }
+
+    #[test]
+    fn decode_stops_at_torn_tail() {
+        let mut data = wal_bytes(&[point(100, 1.0), point(101, 2.0)]);
+        let full = data.len();
+        data.extend_from_slice(b"{\"timestamp\":102,\"cpu\":3");
+        let (points, valid) = decode_records(&data);
+        assert_eq!(points.len(), 2);
+        assert_eq!(valid, full);
+    }
+
+    #[test]
+    fn decode_rejects_bad_checksum() {
+        let mut data = wal_bytes(&[point(100, 1.0), point(101, 2.0)]);
+        let second = data.iter().position(|b| *b == b'\n').unwrap() + 1;
+        // Flip a digit inside the second record's JSON.
+        let idx = second + data[second..].iter().position(|b| *b == b'1').unwrap();
+        data[idx] = b'7';
+        let (points, valid) = decode_records(&data);
+        assert_eq!(points.len(), 1);
+        assert_eq!(valid, second);
+    }
+
+    #[test]
+    fn recovery_replays_log_and_skips_checkpointed_records() {
+        let (snapshot, wal_path) = temp_paths("replay");
+        let mut history = HistoryBuffer::new();
+        history.push(point(1_000, 10.0));
+        history.save_to_path(&snapshot).unwrap();
+        // Crash after the snapshot but before truncate: record 1_000 is still in the log.
+        let mut data = wal_bytes(&[point(1_000, 10.0), point(1_001, 20.0), point(1_002, 30.0)]);
+        data.extend_from_slice(b"garbage without newline");
+        std::fs::write(&wal_path, data).unwrap();
+
+        let (recovered, wal) = HistoryWal::recover_at(snapshot.clone(), wal_path.clone());
+        assert!(wal.is_some());
+        assert_eq!(recovered.total_points(), 3);
+        assert_eq!(recovered.newest_timestamp(), Some(1_002));
+        // Recovery folds the log into the snapshot and leaves an empty log behind.
+        assert_eq!(std::fs::metadata(&wal_path).unwrap().len(), 0);
+        let reloaded = HistoryBuffer::load_from_path(&snapshot).unwrap();
+        assert_eq!(reloaded.total_points(), 3);
+        let _ = std::fs::remove_dir_all(snapshot.parent().unwrap());
+    }
+
+    #[test]
+    fn corrupt_snapshot_is_moved_aside() {
+        let (snapshot, wal_path) = temp_paths("corrupt");
+        std::fs::write(&snapshot, "{\"tier1_1s\": [").unwrap();
+        std::fs::write(&wal_path, wal_bytes(&[point(5, 1.0)])).unwrap();
+
+        let (recovered, _) = HistoryWal::recover_at(snapshot.clone(), wal_path);
+        assert_eq!(recovered.total_points(), 1);
+        assert!(snapshot.with_extension("json.corrupt").exists());
+        let _ = std::fs::remove_dir_all(snapshot.parent().unwrap());
+    }
+}
@@ -0,0 +1,364 @@
+//! Historical metrics import from other monitoring tools
+//!
+//! Lets users migrating to mac-stats backfill the history buffer from:
+//! - iStat Menus CSV exports (`CPU User` + `CPU System` columns are summed into CPU %)
+//! - Stats-style exports and generic CSV files with a header row
+//!
+//! Columns are matched by normalized header name (case, spaces, units and punctuation are
+//! ignored), so `CPU %`, `cpu_usage` and `CPU Usage` all map to CPU. The delimiter (`,`, `;`
+//! or tab) is detected from the header. Timestamps may be Unix seconds/milliseconds, RFC 3339,
+//! or `YYYY-MM-DD HH:MM[:SS]` in local time. Rows outside the 7-day retention are skipped.
+
+use super::history::MetricPoint;
+use crate::state::METRICS_HISTORY;
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use serde::Serialize;
+
+/// Outcome of an import run (shown in the UI after the user picks a file)
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryImportSummary {
+    pub source: String,
+    pub rows_read: usize,
+    pub rows_parsed: usize,
+    pub rows_skipped: usize,
+    pub points_added: usize,
+    pub oldest_timestamp: Option<i64>,
+    pub newest_timestamp: Option<i64>,
+}
+
+/// Column roles recognized in import headers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Column {
+    Timestamp,
+    Cpu,
+    CpuUser,
+    CpuSystem,
+    Gpu,
+    Ram,
+    Disk,
+    Temperature,
+    Frequency,
+    CpuPower,
+    GpuPower,
+    Battery,
+    NetRx,
+    NetTx,
+    DiskRead,
+    DiskWrite,
+    BatteryPower,
+    AdapterPower,
+}
+
+/// Map a normalized header (lowercase alphanumerics only) to a column role
+fn column_for_header(normalized: &str) -> Option<Column> {
+    let col = match normalized {
+        "timestamp" | "time" | "date" | "datetime" | "datetimeutc" | "unixtime" => {
+            Column::Timestamp
+        }
+        "cpu" | "cpuusage" | "cputotal" | "cpuload" | "usage" => Column::Cpu,
+        "cpuuser" | "user" => Column::CpuUser,
+        "cpusystem" | "cpusys" | "system" => Column::CpuSystem,
+        "gpu" | "gpuusage" | "gpuutilization" => Column::Gpu,
+        "ram" | "memory" | "mem" | "memoryused" | "memoryusage" | "ramusage" => Column::Ram,
+        "disk" | "ssd" | "diskused" | "diskusage" => Column::Disk,
+        "temperature" | "temp" | "cputemperature" | "cputemp" | "cpudie" | "cpudietemperature" => {
+            Column::Temperature
+        }
+        "frequency" | "cpufrequency" | "cpufreq" => Column::Frequency,
+        "cpupower" => Column::CpuPower,
+        "gpupower" => Column::GpuPower,
+        "battery" | "batterylevel" | "batterycharge" => Column::Battery,
+        "netrx" | "networkin" | "networkreceived" => Column::NetRx,
+        "nettx" | "networkout" | "networksent" => Column::NetTx,
+        "diskread" | "diskreads" => Column::DiskRead,
+        "diskwrite" | "diskwrites" => Column::DiskWrite,
+        "batterypower" | "dischargerate" | "batterydischarge" => Column::BatteryPower,
+        "adapterpower" | "adapterwattage" | "poweradapter" => Column::AdapterPower,
+        _ => return None,
+    };
+    Some(col)
+}
+
+/// Lowercase and keep only ASCII alphanumerics; drops unit suffixes such as `(%)` or `°C`
+/// (`"CPU Usage (%)"` → `"cpuusage"`).
+fn normalize_header(h: &str) -> String {
+    let h = h.trim().trim_matches('"');
+    let h = match h.find('(') {
+        Some(i) => &h[..i],
+        None => h,
+    };
+    h.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Pick the delimiter that splits the header into the most fields
+fn detect_delimiter(header: &str) -> char {
+    [',', ';', '\t']
+        .into_iter()
+        .max_by_key(|d| header.matches(*d).count())
+        .unwrap_or(',')
+}
+
+/// Split one CSV line honoring double-quoted fields (`""` escapes a quote)
+fn split_line(line: &str, delim: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                current.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            c if c == delim && !in_quotes => fields.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    fields.push(current);
+    fields
+}
+
+/// Parse a numeric cell, tolerating unit suffixes and decimal commas (`"42,5 %"` → 42.5)
+fn parse_value(cell: &str, delim: char) -> Option<f32> {
+    let cleaned: String = cell
+        .trim()
+        .chars()
+        .filter(|c| c.is_ascii_digit() || *c == '.' || *c == ',' || *c == '-')
+        .collect();
+    let cleaned = if delim != ',' {
+        cleaned.replace(',', ".")
+    } else {
+        cleaned
+    };
+    cleaned.parse::<f32>().ok().filter(|v| v.is_finite())
+}
+
+/// Parse a timestamp cell into Unix seconds
+fn parse_timestamp(cell: &str) -> Option<i64> {
+    let s = cell.trim().trim_matches('"');
+    if let Ok(n) = s.parse::<i64>() {
+        // Milliseconds since epoch are 13 digits for any date after 2001
+        return Some(if n > 100_000_000_000 { n / 1000 } else { n });
+    }
+    if let Ok(n) = s.parse::<f64>() {
+        return Some(n as i64);
+    }
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.timestamp());
+    }
+    for fmt in [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y/%m/%d %H:%M:%S",
+        "%d.%m.%Y %H:%M:%S",
+        "%m/%d/%Y %H:%M:%S",
+        "%m/%d/%Y %H:%M",
+    ] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(s, fmt) {
+            return Local
+                .from_local_datetime(&naive)
+                .earliest()
+                .map(|dt| dt.timestamp());
+        }
+    }
+    None
+}
+
+/// Parsed import: points plus bookkeeping for the summary
+#[derive(Debug, Default)]
+pub(crate) struct ParsedImport {
+    pub source: String,
+    pub points: Vec<MetricPoint>,
+    pub rows_read: usize,
+    pub rows_skipped: usize,
+}
+
+/// Parse CSV export content into metric points
+pub(crate) fn parse_history_csv(content: &str) -> Result<ParsedImport, String> {
+    let mut lines = content
+        .lines()
+        .map(|l| l.trim_start_matches('\u{feff}'))
+        .filter(|l| !l.trim().is_empty());
+    let header = lines
+        .next()
+        .ok_or_else(|| "Import file is empty".to_string())?;
+    let delim = detect_delimiter(header);
+    let columns: Vec<Option<Column>> = split_line(header, delim)
+        .iter()
+        .map(|h| column_for_header(&normalize_header(h)))
+        .collect();
+
+    let ts_idx = columns
+        .iter()
+        .position(|c| *c == Some(Column::Timestamp))
+        .ok_or_else(|| "No timestamp/date column found in header".to_string())?;
+    if columns
+        .iter()
+        .all(|c| matches!(c, None | Some(Column::Timestamp)))
+    {
+        return Err("No recognized metric columns (cpu, gpu, ram, disk, ...) in header".into());
+    }
+    let source =
+        if columns.contains(&Some(Column::CpuUser)) && columns.contains(&Some(Column::CpuSystem)) {
+            "istat_menus"
+        } else {
+            "csv"
+        };
+
+    let mut parsed = ParsedImport {
+        source: source.to_string(),
+        ..Default::default()
+    };
+    for line in lines {
+        parsed.rows_read += 1;
+        let cells = split_line(line, delim);
+        let Some(timestamp) = cells.get(ts_idx).and_then(|c| parse_timestamp(c)) else {
+            parsed.rows_skipped += 1;
+            continue;
+        };
+
+        let mut point =
+            MetricPoint::from_metrics(0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, -1.0);
+        point.timestamp = timestamp;
+        let (mut cpu_user, mut cpu_system) = (None, None);
+        let mut any_value = false;
+        for (idx, column) in columns.iter().enumerate() {
+            let Some(column) = column else { continue };
+            if *column == Column::Timestamp {
+                continue;
+            }
+            let Some(v) = cells.get(idx).and_then(|c| parse_value(c, delim)) else {
+                continue;
+            };
+            any_value = true;
+            match column {
+                Column::Cpu => point.cpu = v,
+                Column::CpuUser => cpu_user = Some(v),
+                Column::CpuSystem => cpu_system = Some(v),
+                Column::Gpu => point.gpu = v,
+                Column::Ram => point.ram = v,
+                Column::Disk => point.disk = v,
+                Column::Temperature => point.temperature = v,
+                Column::Frequency => point.frequency = v,
+                Column::CpuPower => point.cpu_power = v,
+                Column::GpuPower => point.gpu_power = v,
+                Column::Battery => point.battery_level = v,
+                Column::NetRx => point.net_rx = v,
+                Column::NetTx => point.net_tx = v,
+                Column::DiskRead => point.disk_read = v,
+                Column::DiskWrite => point.disk_write = v,
+                Column::BatteryPower => point.battery_power = v,
+                Column::AdapterPower => point.adapter_power = v,
+                Column::Timestamp => {}
+            }
+        }
+        if !any_value {
+            parsed.rows_skipped += 1;
+            continue;
+        }
+        if point.cpu == 0.0 && (cpu_user.is_some() || cpu_system.is_some()) {
+            point.cpu = cpu_user.unwrap_or(0.0) + cpu_system.unwrap_or(0.0);
+        }
+        point.cpu = point.cpu.clamp(0.0, 100.0);
+        point.gpu = point.gpu.clamp(0.0, 100.0);
+        point.ram = point.ram.clamp(0.0, 100.0);
+        point.disk = point.disk.clamp(0.0, 100.0);
+        parsed.points.push(point);
+    }
+    Ok(parsed)
+}
+
+/// Import a history export (iStat Menus, Stats or generic CSV) into the metrics history buffer
+#[tauri::command]
+pub fn import_metrics_history(path: String) -> Result<HistoryImportSummary, String> {
+    crate::guest_mode::ensure_allowed("Importing history")?;
+    let expanded = crate::config::Config::expand_user_path_str(&path)
+        .unwrap_or_else(|| std::path::PathBuf::from(&path));
+    let content = std::fs::read_to_string(&expanded)
+        .map_err(|e| format!("Failed to read {}: {}", expanded.display(), e))?;
+    let parsed = parse_history_csv(&content)?;
+
+    let rows_parsed = parsed.points.len();
+    let oldest_timestamp = parsed.points.iter().map(|p| p.timestamp).min();
+    let newest_timestamp = parsed.points.iter().map(|p| p.timestamp).max();
+
+    let mut history = METRICS_HISTORY
+        .lock()
+        .map_err(|_| "History buffer temporarily unavailable".to_string())?;
+    let points_added = history
+        .get_or_insert_with(super::history::HistoryBuffer::new)
+        .backfill(parsed.points);
+
+    let summary = HistoryImportSummary {
+        source: parsed.source,
+        rows_read: parsed.rows_read,
+        rows_parsed,
+        rows_skipped: parsed.rows_skipped,
+        points_added,
+        oldest_timestamp,
+        newest_timestamp,
+    };
+    tracing::info!(
+        "History import from {}: source={}, rows={}, parsed={}, added={} point(s)",
+        expanded.display(),
+        summary.source,
+        summary.rows_read,
+        summary.rows_parsed,
+        summary.points_added
+    );
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_header_strips_units_and_punctuation() {
+        assert_eq!(normalize_header("CPU Usage (%)"), "cpuusage");
+        assert_eq!(normalize_header("\"Memory Used\""), "memoryused");
+        assert_eq!(normalize_header("cpu_temp"), "cputemp");
+    }
+
+    #[test]
+    fn parses_generic_csv() {
+        let csv = "timestamp,cpu,gpu,ram,disk\n1700000000,12.5,3,45.5,70\n1700000001,bad,,,\n";
+        let parsed = parse_history_csv(csv).unwrap();
+        assert_eq!(parsed.source, "csv");
+        assert_eq!(parsed.rows_read, 2);
+        assert_eq!(parsed.points.len(), 1);
+        assert_eq!(parsed.rows_skipped, 1);
+        assert_eq!(parsed.points[0].timestamp, 1700000000);
+        assert_eq!(parsed.points[0].cpu, 12.5);
+        assert_eq!(parsed.points[0].ram, 45.5);
+    }
+
+    #[test]
+    fn parses_istat_style_semicolon_export() {
+        let csv = "Date;CPU User (%);CPU System (%);Memory (%)\n\
+                   2024-01-02 10:00:00;10,5;4,5;60\n";
+        let parsed = parse_history_csv(csv).unwrap();
+        assert_eq!(parsed.source, "istat_menus");
+        assert_eq!(parsed.points.len(), 1);
+        assert_eq!(parsed.points[0].cpu, 15.0);
+        assert_eq!(parsed.points[0].ram, 60.0);
+    }
+
+    #[test]
+    fn rejects_header_without_metrics() {
+        assert!(parse_history_csv("time,foo\n1,2\n").is_err());
+        assert!(parse_history_csv("cpu,ram\n1,2\n").is_err());
+    }
+
+    #[test]
+    fn parses_millisecond_and_rfc3339_timestamps() {
+        assert_eq!(parse_timestamp("1700000000123"), Some(1700000000));
+        assert_eq!(parse_timestamp("2023-11-14T22:13:20Z"), Some(1700000000));
+        assert_eq!(parse_timestamp("not a date"), None);
+    }
+}
@@ -0,0 +1,398 @@
+//! InfluxDB line-protocol export
+//!
+//! When `influxExport.enabled` is set, a background thread takes the newest history sample
+//! every `intervalSecs`, encodes it as one line-protocol line and queues it for each
+//! configured sink (HTTP write endpoint and/or local file). Queues are written every
+//! `flushSecs` (or as soon as a batch is full) in batches of `batchSize` lines. A failed write
+//! keeps its lines and backs off exponentially; each queue is capped at [`MAX_QUEUED_LINES`],
+//! dropping the oldest samples first, so a long outage cannot grow memory without bound.
+
+use std::collections::VecDeque;
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use super::history::MetricPoint;
+use super::latest_history_point;
+use crate::config::{Config, InfluxExportSettings};
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+/// Queue cap per sink (about a day at the default 10s interval)
+const MAX_QUEUED_LINES: usize = 10_000;
+const HTTP_TIMEOUT: Duration = Duration::from_secs(15);
+const RETRY_BASE_SECS: u64 = 30;
+const RETRY_MAX_SECS: u64 = 600;
+
+/// Escape a measurement name (commas and spaces)
+fn escape_measurement(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace(' ', "\\ ")
+}
+
+/// Escape a tag key or value (commas, equals signs and spaces)
+fn escape_tag(s: &str) -> String {
+    escape_measurement(s).replace('=', "\\=")
+}
+
+/// One line for `point`. Sensors that were not readable (temperature/frequency/power of 0,
+/// battery of -1) are left out instead of being written as zeros.
+fn encode_line(point: &MetricPoint, measurement: &str, tags: &[(String, String)]) -> String {
+    let mut line = escape_measurement(measurement);
+    for (k, v) in tags {
+        line.push_str(&format!(",{}={}", escape_tag(k), escape_tag(v)));
+    }
+    let mut fields = vec![
+        ("cpu", point.cpu),
+        ("gpu", point.gpu),
+        ("ram", point.ram),
+        ("disk", point.disk),
+        ("net_rx", point.net_rx),
+        ("net_tx", point.net_tx),
+        ("disk_read", point.disk_read),
+        ("disk_write", point.disk_write),
+    ];
+    for (name, value) in [
+        ("temperature", point.temperature),
+        ("frequency", point.frequency),
+        ("p_core_frequency", point.p_core_frequency),
+        ("e_core_frequency", point.e_core_frequency),
+        ("cpu_power", point.cpu_power),
+        ("gpu_power", point.gpu_power),
+        ("battery_power", point.battery_power),
+        ("adapter_power", point.adapter_power),
+    ] {
+        if value > 0.0 {
+            fields.push((name, value));
+        }
+    }
+    if point.battery_level >= 0.0 {
+        fields.push(("battery_level", point.battery_level));
+    }
+    let fields: Vec<String> = fields
+        .into_iter()
+        .filter(|(_, v)| v.is_finite())
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect();
+    format!("{} {} {}", line, fields.join(","), point.timestamp)
+}
+
+/// Add `precision=s` (timestamps are in seconds) unless the URL already sets a precision.
+fn write_url_with_precision(raw: &str) -> Result<String, String> {
+    let mut url = url::Url::parse(raw).map_err(|e| format!("Invalid influxExport.url: {}", e))?;
+    if !url.query_pairs().any(|(k, _)| k == "precision") {
+        url.query_pairs_mut().append_pair("precision", "s");
+    }
+    Ok(url.to_string())
+}
+
+enum Target {
+    Http {
+        client: reqwest::blocking::Client,
+        url: String,
+        token: Option<String>,
+    },
+    File(std::path::PathBuf),
+}
+
+impl Target {
+    fn describe(&self) -> String {
+        match self {
+            // The URL may carry credentials in its query; only name the host.
+            Self::Http { url, .. } => url::Url::parse(url)
+                .ok()
+                .and_then(|u| u.host_str().map(|h| format!("http {}", h)))
+                .unwrap_or_else(|| "http".to_string()),
+            Self::File(path) => format!("file {}", path.display()),
+        }
+    }
+
+    fn write(&self, body: &str) -> Result<(), String> {
+        match self {
+            Self::Http { client, url, token } => {
+                let mut req = client
+                    .post(url)
+                    .header("Content-Type", "text/plain; charset=utf-8")
+                    .body(body.to_string());
+                if let Some(token) = token {
+                    req = req.header("Authorization", format!("Token {}", token));
+                }
+                let resp = req.send().map_err(|e| e.to_string())?;
+                if resp.status().is_success() {
+                    Ok(())
+                } else {
+                    let status = resp.status();
+                    let text = resp.text().unwrap_or_default();
+                    Err(format!(
+                        "HTTP {}: {}",
+                        status,
+                        text.chars().take(200).collect::<String>()
+                    ))
+                }
+            }
+            Self::File(path) => {
+                if let Some(dir) = path.parent() {
+                    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+                }
+                let mut file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .map_err(|e| e.to_string())?;
+                file.write_all(body.as_bytes()).map_err(|e| e.to_string())
+            }
+        }
+    }
+}
+
+/// One destination with its own queue and retry state
+struct Sink {
+    target: Target,
+    queue: VecDeque<String>,
+    failures: u32,
+    retry_at: Option<Instant>,
+}
+
+impl Sink {
+    fn new(target: Target) -> Self {
+        Self {
+            target,
+            queue: VecDeque::new(),
+            failures: 0,
+            retry_at: None,
+        }
+    }
+
+    fn enqueue(&mut self, line: String) {
+        if self.queue.len() >= MAX_QUEUED_LINES {
+            self.queue.pop_front();
+        }
+        self.queue.push_back(line);
+    }
+
+    /// Write queued lines in batches; stop at the first failure and schedule a retry.
+    fn flush(&mut self, batch_size: usize, now: Instant) {
+        if self.retry_at.is_some_and(|t| now < t) {
+            return;
+        }
+        while !self.queue.is_empty() {
+            let n = self.queue.len().min(batch_size);
+            let mut body = String::new();
+            for line in self.queue.iter().take(n) {
+                body.push_str(line);
+                body.push('\n');
+            }
+            match self.target.write(&body) {
+                Ok(()) => {
+                    self.queue.drain(..n);
+                    self.failures = 0;
+                    self.retry_at = None;
+                    debug3!(
+                        "Influx export: wrote {} lines to {}",
+                        n,
+                        self.target.describe()
+                    );
+                }
+                Err(e) => {
+                    self.failures += 1;
+                    let delay = retry_delay_secs(self.failures);
+                    self.retry_at = Some(now + Duration::from_secs(delay));
+                    tracing::warn!(
+                        "Influx export to {} failed ({} queued, retry in {}s): {}",
+                        self.target.describe(),
+                        self.queue.len(),
+                        delay,
+                        e
+                    );
+                    return;
+                }
+            }
+        }
+    }
+}
+
+fn retry_delay_secs(failures: u32) -> u64 {
+    RETRY_BASE_SECS
+        .saturating_mul(1u64 << failures.saturating_sub(1).min(10))
+        .min(RETRY_MAX_SECS)
+}
+
+fn build_sinks(settings: &InfluxExportSettings) -> Result<Vec<Sink>, String> {
+    let mut sinks = Vec::new();
+    if let Some(raw) = &settings.url {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(HTTP_TIMEOUT)
+            .build()
+            .map_err(|e| e.to_string())?;
+        sinks.push(Sink::new(Target::Http {
+            client,
+            url: write_url_with_precision(raw)?,
+            token: settings.token.clone(),
+        }));
+    }
+    if let Some(file) = &settings.file {
+        let path = Config::expand_user_path_str(file)
+            .ok_or_else(|| format!("Invalid influxExport.file: {}", file))?;
+        sinks.push(Sink::new(Target::File(path)));
+    }
+    if sinks.is_empty() {
+        return Err("influxExport needs a url or a file".to_string());
+    }
+    Ok(sinks)
+}
+
+/// Tags for every line: `host`, then the machine `labels`, then `influxExport.tags` (which win
+/// over a label or `host` of the same name)
+fn line_tags(
+    host: Option<String>,
+    labels: &[(String, String)],
+    tags: &[(String, String)],
+) -> Vec<(String, String)> {
+    let mut merged: Vec<(String, String)> = host
+        .map(|h| ("host".to_string(), h))
+        .into_iter()
+        .chain(labels.iter().cloned())
+        .filter(|(k, _)| !tags.iter().any(|(t, _)| t == k))
+        .collect();
+    merged.extend(tags.iter().cloned());
+    merged
+}
+
+fn export_loop(settings: InfluxExportSettings, mut sinks: Vec<Sink>) {
+    let tags = line_tags(
+        sysinfo::System::host_name(),
+        &Config::labels(),
+        &settings.tags,
+    );
+    let flush_every = Duration::from_secs(settings.flush_secs);
+    let mut last_flush = Instant::now();
+    let mut last_timestamp = i64::MIN;
+    loop {
+        std::thread::sleep(Duration::from_secs(settings.interval_secs));
+        if let Some(point) = latest_history_point().filter(|p| p.timestamp > last_timestamp) {
+            last_timestamp = point.timestamp;
+            let line = encode_line(&point, &settings.measurement, &tags);
+            for sink in &mut sinks {
+                sink.enqueue(line.clone());
+            }
+        }
+        let batch_full = sinks.iter().any(|s| s.queue.len() >= settings.batch_size);
+        if batch_full || last_flush.elapsed() >= flush_every {
+            let now = Instant::now();
+            for sink in &mut sinks {
+                sink.flush(settings.batch_size, now);
+            }
+            last_flush = now;
+        }
+    }
+}
+
+/// Start the exporter thread when `influxExport.enabled` is set (config read once).
+pub fn spawn_influx_export_if_enabled() {
+    let settings = Config::influx_export_settings();
+    if !settings.enabled {
+        return;
+    }
+    let sinks = match build_sinks(&settings) {
+        Ok(sinks) => sinks,
+        Err(e) => {
+            tracing::warn!("Influx export disabled: {}", e);
+            return;
+        }
+    };
+    tracing::info!(
+        "Influx export every {}s to {}",
+        settings.interval_secs,
+        sinks
+            .iter()
+            .map(|s| s.target.describe())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    std::thread::spawn(move || export_loop(settings, sinks));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point() -> MetricPoint {
+        let mut p =
+            MetricPoint::from_metrics(12.5, 3.0, 61.0, 48.0, 52.0, 3.2, 0.0, 0.0, 4.5, 0.0, -1.0);
+        p.timestamp = 1_700_000_000;
+        p
+    }
+
+    #[test]
+    fn encodes_line_protocol_and_skips_unreadable_sensors() {
+        let tags = vec![("host".to_string(), "Jane's MacBook,Pro".to_string())];
+        assert_eq!(
+            encode_line(&point(), "mac stats", &tags),
+            "mac\\ stats,host=Jane's\\ MacBook\\,Pro \
+             cpu=12.5,gpu=3,ram=61,disk=48,net_rx=0,net_tx=0,disk_read=0,disk_write=0,\
+             temperature=52,frequency=3.2,cpu_power=4.5 1700000000"
+        );
+    }
+
+    #[test]
+    fn merges_host_labels_and_tags() {
+        let pair = |k: &str, v: &str| (k.to_string(), v.to_string());
+        assert_eq!(
+            line_tags(
+                Some("mac1".to_string()),
+                &[pair("role", "build"), pair("owner", "ops")],
+                &[pair("owner", "qa"), pair("site", "office")],
+            ),
+            vec![
+                pair("host", "mac1"),
+                pair("role", "build"),
+                pair("owner", "qa"),
+                pair("site", "office")
+            ]
+        );
+    }
+
+    #[test]
+    fn adds_precision_and_backs_off() {
+        assert_eq!(
+            write_url_with_precision("http://localhost:8086/api/v2/write?org=o&bucket=b").unwrap(),
+            "http://localhost:8086/api/v2/write?org=o&bucket=b&precision=s"
+        );
+        assert_eq!(
+            write_url_with_precision("http://h:8086/write?db=m&precision=ms").unwrap(),
+            "http://h:8086/write?db=m&precision=ms"
+        );
+        assert!(write_url_with_precision("not a url").is_err());
+        assert_eq!(
+            (1..=7).map(retry_delay_secs).collect::<Vec<_>>(),
+            vec![30, 60, 120, 240, 480, 600, 600]
+        );
+    }
+
+    #[test]
+    fn failed_file_writes_keep_lines_queued() {
+        let dir = std::env::temp_dir().join(format!("mac-stats-influx-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        // A directory where the file should be makes the append fail.
+        let path = dir.join("metrics.lp");
+        std::fs::create_dir_all(&path).unwrap();
+        let mut sink = Sink::new(Target::File(path.clone()));
+        for i in 0..3 {
+            sink.enqueue(format!("m v={} {}", i, i));
+        }
+        let now = Instant::now();
+        sink.flush(2, now);
+        assert_eq!((sink.queue.len(), sink.failures), (3, 1));
+
+        std::fs::remove_dir(&path).unwrap();
+        sink.flush(2, now); // still backing off
+        assert_eq!(sink.queue.len(), 3);
+        sink.flush(2, now + Duration::from_secs(RETRY_BASE_SECS));
+        assert!(sink.queue.is_empty());
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "m v=0 0\nm v=1 1\nm v=2 2\n"
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
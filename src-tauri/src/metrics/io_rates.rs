@@ -0,0 +1,153 @@
+//! Network and disk throughput for history samples
+//!
+//! Rates come from cumulative byte counters between two calls, so the first sample is zero.
+//! Network sums every interface except loopback. Disk is the boot volume (the one the menu
+//! bar disk percentage describes): APFS volumes in one container report the same device
+//! counters, so summing volumes would count each byte several times.
+
+use std::time::Instant;
+
+use sysinfo::{DiskRefreshKind, Disks, Networks};
+
+/// Throughput in bytes per second
+#[derive(Debug, Clone, Copy, Default, PartialEq, serde::Serialize)]
+pub struct IoRates {
+    pub net_rx: f32,
+    pub net_tx: f32,
+    pub disk_read: f32,
+    pub disk_write: f32,
+}
+
+/// Cumulative byte counters at one instant
+#[derive(Debug, Clone, Copy, Default)]
+struct Counters {
+    net_rx: u64,
+    net_tx: u64,
+    disk_read: u64,
+    disk_write: u64,
+}
+
+impl Counters {
+    /// Rates between `earlier` and `self`, `secs` apart. A counter that went backwards (an
+    /// interface disappeared, a volume remounted) reads as zero for that interval.
+    fn rates_since(&self, earlier: &Counters, secs: f64) -> IoRates {
+        if secs <= 0.0 {
+            return IoRates::default();
+        }
+        let rate = |now: u64, before: u64| (now.saturating_sub(before) as f64 / secs) as f32;
+        IoRates {
+            net_rx: rate(self.net_rx, earlier.net_rx),
+            net_tx: rate(self.net_tx, earlier.net_tx),
+            disk_read: rate(self.disk_read, earlier.disk_read),
+            disk_write: rate(self.disk_write, earlier.disk_write),
+        }
+    }
+}
+
+/// Kept by the update loop (like its SMC connection) so counters persist between samples
+pub struct IoRateSampler {
+    networks: Networks,
+    disks: Disks,
+    last: Option<(Counters, Instant)>,
+}
+
+impl IoRateSampler {
+    pub fn new() -> Self {
+        Self {
+            networks: Networks::new_with_refreshed_list(),
+            disks: Disks::new_with_refreshed_list_specifics(
+                DiskRefreshKind::nothing().with_io_usage(),
+            ),
+            last: None,
+        }
+    }
+
+    fn read_counters(&mut self) -> Counters {
+        self.networks.refresh(true);
+        let (net_rx, net_tx) = self
+            .networks
+            .iter()
+            .filter(|(name, _)| !name.starts_with("lo"))
+            .fold((0u64, 0u64), |(rx, tx), (_, data)| {
+                (rx + data.total_received(), tx + data.total_transmitted())
+            });
+        let disk = self
+            .disks
+            .list_mut()
+            .first_mut()
+            .map(|disk| {
+                disk.refresh_specifics(DiskRefreshKind::nothing().with_io_usage());
+                disk.usage()
+            })
+            .unwrap_or_default();
+        Counters {
+            net_rx,
+            net_tx,
+            disk_read: disk.total_read_bytes,
+            disk_write: disk.total_written_bytes,
+        }
+    }
+
+    /// Cumulative counters per non-loopback interface, as read by the last [`Self::sample`]
+    pub fn interface_totals(&self) -> Vec<super::network_details::InterfaceTotals> {
+        self.networks
+            .iter()
+            .filter(|(name, _)| !name.starts_with("lo"))
+            .map(|(name, data)| super::network_details::InterfaceTotals {
+                name: name.clone(),
+                rx_bytes: data.total_received(),
+                tx_bytes: data.total_transmitted(),
+            })
+            .collect()
+    }
+
+    /// Rates since the previous call (zeros on the first)
+    pub fn sample(&mut self) -> IoRates {
+        let counters = self.read_counters();
+        let now = Instant::now();
+        let rates = self
+            .last
+            .map(|(before, at)| counters.rates_since(&before, now.duration_since(at).as_secs_f64()))
+            .unwrap_or_default();
+        self.last = Some((counters, now));
+        rates
+    }
+}
+
+impl Default for IoRateSampler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rates_divide_by_elapsed_and_ignore_counter_resets() {
+        let before = Counters {
+            net_rx: 1_000,
+            net_tx: 500,
+            disk_read: 10_000,
+            disk_write: 4_000,
+        };
+        let after = Counters {
+            net_rx: 7_000,
+            net_tx: 200,
+            disk_read: 10_000,
+            disk_write: 10_000,
+        };
+        let rates = after.rates_since(&before, 3.0);
+        assert_eq!(
+            rates,
+            IoRates {
+                net_rx: 2_000.0,
+                net_tx: 0.0,
+                disk_read: 0.0,
+                disk_write: 2_000.0,
+            }
+        );
+        assert_eq!(after.rates_since(&before, 0.0), IoRates::default());
+    }
+}
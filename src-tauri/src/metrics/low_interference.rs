@@ -0,0 +1,159 @@
+//! Low-interference mode for games, benchmarks and audio work
+//!
+//! While an app listed in `lowInterferenceApps` is frontmost, the update loop samples every
+//! [`SAMPLE_INTERVAL`] instead of every few seconds and skips the expensive collectors: GPU
+//! usage and the adapter rating (`ioreg`), SMC/IOReport reads for the CPU window and process
+//! enumeration (CPU window list, process watch). Cached values are shown meanwhile.
+//!
+//! The frontmost app is tracked with `NSWorkspaceDidActivateApplicationNotification`, so
+//! nothing is polled; the list is re-read on every app switch and applies without a restart.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use objc2::declare::ClassBuilder;
+use objc2::rc::Retained;
+use objc2::runtime::{AnyClass, AnyObject, NSObject, Sel};
+use objc2::{msg_send, sel, ClassType};
+use objc2_foundation::NSString;
+
+use crate::config::Config;
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+/// Sampling interval while a listed app is frontmost
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(30);
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// The frontmost application
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FrontApp {
+    pub name: String,
+    pub bundle_id: String,
+    pub pid: i32,
+}
+
+impl FrontApp {
+    /// Whether `patterns` (names or bundle identifiers, case-insensitive) name this app
+    pub fn matches(&self, patterns: &[String]) -> bool {
+        patterns.iter().any(|p| {
+            let p = p.trim();
+            !p.is_empty()
+                && (p.eq_ignore_ascii_case(&self.name) || p.eq_ignore_ascii_case(&self.bundle_id))
+        })
+    }
+}
+
+/// True while a listed app is frontmost
+pub fn is_active() -> bool {
+    ACTIVE.load(Ordering::Relaxed)
+}
+
+fn ns_string(obj: Option<Retained<NSString>>) -> String {
+    obj.map(|s| s.to_string()).unwrap_or_default()
+}
+
+/// The app macOS currently considers frontmost
+pub(crate) fn frontmost_app() -> Option<FrontApp> {
+    let cls = AnyClass::get(c"NSWorkspace")?;
+    // SAFETY: `sharedWorkspace`, `frontmostApplication`, `localizedName` and
+    // `bundleIdentifier` take no arguments and return objects (or nil), retained by objc2;
+    // `processIdentifier` returns a pid_t.
+    unsafe {
+        let workspace: Option<Retained<AnyObject>> = msg_send![cls, sharedWorkspace];
+        let app: Option<Retained<AnyObject>> = msg_send![&*workspace?, frontmostApplication];
+        let app = app?;
+        let name: Option<Retained<NSString>> = msg_send![&*app, localizedName];
+        let bundle_id: Option<Retained<NSString>> = msg_send![&*app, bundleIdentifier];
+        let pid: i32 = msg_send![&*app, processIdentifier];
+        Some(FrontApp {
+            name: ns_string(name),
+            bundle_id: ns_string(bundle_id),
+            pid,
+        })
+    }
+}
+
+/// Re-evaluate the mode for the current frontmost app
+fn update() {
+    let patterns = Config::low_interference_apps();
+    let front = if patterns.is_empty() {
+        None
+    } else {
+        frontmost_app()
+    };
+    let active = front.as_ref().is_some_and(|app| app.matches(&patterns));
+    if ACTIVE.swap(active, Ordering::Relaxed) != active {
+        match front.filter(|_| active) {
+            Some(app) => tracing::info!(
+                "Low-interference mode on: {} is frontmost (sampling every {}s)",
+                app.name,
+                SAMPLE_INTERVAL.as_secs()
+            ),
+            None => tracing::info!("Low-interference mode off"),
+        }
+    }
+}
+
+fn observer_class() -> &'static AnyClass {
+    static REGISTER: OnceLock<&'static AnyClass> = OnceLock::new();
+    REGISTER.get_or_init(|| {
+        let mut builder = ClassBuilder::new(c"MacStatsFrontmostAppObserver", NSObject::class())
+            .expect("class already exists");
+
+        extern "C-unwind" fn app_activated(_this: &AnyObject, _cmd: Sel, _note: *mut AnyObject) {
+            update();
+        }
+        unsafe {
+            builder.add_method(
+                sel!(appActivated:),
+                app_activated as extern "C-unwind" fn(_, _, _),
+            );
+        }
+        builder.register()
+    })
+}
+
+/// Check the frontmost app and observe app activations (main thread, once)
+pub fn start_observing() {
+    update();
+    let Some(cls) = AnyClass::get(c"NSWorkspace") else {
+        return;
+    };
+    let name = NSString::from_str("NSWorkspaceDidActivateApplicationNotification");
+    unsafe {
+        let workspace: Option<Retained<AnyObject>> = msg_send![cls, sharedWorkspace];
+        let Some(workspace) = workspace else {
+            return;
+        };
+        let center: Option<Retained<AnyObject>> = msg_send![&*workspace, notificationCenter];
+        let Some(center) = center else {
+            return;
+        };
+        // The observer lives as long as the app
+        let observer: *mut AnyObject = msg_send![observer_class(), new];
+        let _: () = msg_send![&*center, addObserver: observer, selector: sel!(appActivated:), name: &*name, object: std::ptr::null_mut::<AnyObject>()];
+    }
+    debug2!("Observing frontmost app changes for low-interference mode");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_names_and_bundle_ids() {
+        let app = FrontApp {
+            name: "Logic Pro".to_string(),
+            bundle_id: "com.apple.logic10".to_string(),
+            pid: 501,
+        };
+        assert!(app.matches(&["logic pro".to_string()]));
+        assert!(app.matches(&["Steam".to_string(), "COM.APPLE.LOGIC10".to_string()]));
+        assert!(!app.matches(&["Logic".to_string()]));
+        assert!(!app.matches(&[" ".to_string()]));
+        assert!(!FrontApp::default().matches(&[String::new()]));
+    }
+}
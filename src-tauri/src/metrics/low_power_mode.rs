@@ -0,0 +1,81 @@
+//! macOS Low Power Mode (detection and toggle)
+//!
+//! The state comes from `NSProcessInfo.lowPowerModeEnabled` (macOS 12+; `None` before). Turning
+//! it on or off runs `pmset -a lowpowermode 1|0`, which needs root, so it goes through the
+//! privileged helper (`low_power_mode` request). The battery window shows and toggles it
+//! (`get_battery_details`, `set_low_power_mode`) and alert rules can switch it on with the
+//! `lowPowerMode` action, e.g. at 20% battery.
+
+use objc2::msg_send;
+use objc2::rc::Retained;
+use objc2::runtime::{AnyClass, AnyObject, Sel};
+use objc2::sel;
+use std::process::Command;
+
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+/// Whether Low Power Mode is on; `None` before macOS 12
+pub fn is_enabled() -> Option<bool> {
+    let cls = AnyClass::get(c"NSProcessInfo")?;
+    // SAFETY: `processInfo` is a class method returning the shared NSProcessInfo; the
+    // selector is checked before `isLowPowerModeEnabled` (BOOL getter, macOS 12+) is sent.
+    unsafe {
+        let info: Option<Retained<AnyObject>> = msg_send![cls, processInfo];
+        let info = info?;
+        let selector: Sel = sel!(isLowPowerModeEnabled);
+        let supported: bool = msg_send![&*info, respondsToSelector: selector];
+        if !supported {
+            return None;
+        }
+        let enabled: bool = msg_send![&*info, isLowPowerModeEnabled];
+        Some(enabled)
+    }
+}
+
+/// Run `pmset` for every power source (root only: the privileged helper)
+pub fn apply(enabled: bool) -> Result<(), String> {
+    let out = Command::new("/usr/bin/pmset")
+        .args(["-a", "lowpowermode", if enabled { "1" } else { "0" }])
+        .output()
+        .map_err(|e| format!("pmset: {}", e))?;
+    if !out.status.success() {
+        let reason = String::from_utf8_lossy(&out.stderr);
+        return Err(format!(
+            "pmset {}: {}",
+            out.status,
+            reason.lines().next().unwrap_or("no output")
+        ));
+    }
+    Ok(())
+}
+
+/// Ask the helper to switch Low Power Mode; returns the state afterwards
+pub fn set_enabled(enabled: bool) -> Result<bool, String> {
+    if is_enabled() == Some(enabled) {
+        return Ok(enabled);
+    }
+    crate::privileged_helper::set_low_power_mode(enabled)?;
+    tracing::info!(
+        "Low Power Mode {}",
+        if enabled { "enabled" } else { "disabled" }
+    );
+    Ok(is_enabled().unwrap_or(enabled))
+}
+
+/// Turn Low Power Mode on or off (battery window)
+#[tauri::command]
+pub async fn set_low_power_mode(
+    enabled: bool,
+    confirmation_token: Option<String>,
+) -> Result<bool, String> {
+    crate::guest_mode::ensure_allowed("Changing Low Power Mode")?;
+    crate::commands::confirmation::require_confirmation(
+        confirmation_token.as_deref(),
+        "low_power_mode",
+        if enabled { "enable" } else { "disable" },
+    )?;
+    tokio::task::spawn_blocking(move || set_enabled(enabled))
+        .await
+        .map_err(|e| format!("Low Power Mode change failed: {}", e))?
+}
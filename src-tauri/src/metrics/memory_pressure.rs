@@ -0,0 +1,94 @@
+//! Shed memory when macOS reports memory pressure
+//!
+//! A watcher polls the kernel pressure level. On a rise to warning it drops the per-process
+//! sparkline history and the process list cache, closes a hidden CPU window (its WebView) and
+//! trims history tier capacity; on critical it also snapshots history to disk (when
+//! persistence is on) and discards the 1s tier. Each rise emits a `memory-pressure` event
+//! (`{ "level": "warning" | "critical" }`) so open windows can drop their cached chart data.
+
+use std::time::Duration;
+use tauri::Emitter;
+
+use crate::ffi::memory_pressure::{self, MemoryPressure};
+use crate::state::{APP_HANDLE, HISTORY_WAL, METRICS_HISTORY};
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Release non-essential buffers for `level` (warning or critical)
+fn release(level: MemoryPressure) {
+    super::process_history::clear();
+    crate::state::update_snapshot(|s| s.processes = None);
+
+    let critical = level >= MemoryPressure::Critical;
+    if let Ok(mut history) = METRICS_HISTORY.lock() {
+        if let Some(history) = history.as_mut() {
+            let before = history.estimate_memory_bytes();
+            if critical {
+                // Keep what we are about to drop recoverable across a restart.
+                if let Ok(mut wal) = HISTORY_WAL.lock() {
+                    if let Some(wal) = wal.as_mut() {
+                        if let Err(e) = wal.checkpoint(history) {
+                            tracing::warn!(
+                                "History checkpoint under memory pressure failed: {}",
+                                e
+                            );
+                        }
+                    }
+                }
+            }
+            history.release_memory(critical);
+            debug2!(
+                "Memory pressure: history ~{} -> ~{} bytes",
+                before,
+                history.estimate_memory_bytes()
+            );
+        }
+    }
+
+    // A hidden CPU window (pre-warmed or closed) keeps its WebView; it is recreated on the next click
+    let dropped_window = crate::ui::status_bar::drop_hidden_cpu_window();
+
+    if let Some(app) = APP_HANDLE.get() {
+        let _ = app.emit(
+            "memory-pressure",
+            serde_json::json!({ "level": level.as_str() }),
+        );
+    }
+    tracing::info!(
+        "Memory pressure {}: released process history/cache{}{}",
+        level.as_str(),
+        if critical {
+            " and 1s metrics history"
+        } else {
+            ""
+        },
+        if dropped_window {
+            ", hidden CPU window"
+        } else {
+            ""
+        }
+    );
+}
+
+/// Poll the pressure level and release buffers each time it rises above normal.
+pub fn spawn_memory_pressure_watcher() {
+    if memory_pressure::current_level().is_none() {
+        debug1!("Memory pressure level unavailable; watcher not started");
+        return;
+    }
+    let mut last = MemoryPressure::Normal;
+    crate::background::every("memory_pressure", POLL_INTERVAL, move || {
+        let Some(level) = memory_pressure::current_level() else {
+            return true;
+        };
+        if level > last && level > MemoryPressure::Normal {
+            release(level);
+        } else if level < last {
+            debug2!("Memory pressure back to {}", level.as_str());
+        }
+        last = level;
+        true
+    });
+}
@@ -6,14 +6,52 @@
 //! - CPU frequency (via IOReport)
 //! - Power consumption (CPU/GPU)
 //! - Process information
-//! - Metrics history with adaptive downsampling
+//! - Metrics history with adaptive downsampling, persisted via a write-ahead log
+//! - History import from other monitoring tools (CSV)
+//! - Time Machine backup status
+//! - `mac_stats stats` CLI output
+//! - Session summaries written on quit (`mac_stats sessions`) and energy estimates
+//! - Scheduled metric snapshots for post-mortem inspection
+//! - Low-interference mode while games or audio apps are frontmost
 //!
 //! All metrics are cached to reduce system load and improve performance.
 
+pub mod backup;
+pub mod battery_details;
+pub mod battery_drain;
+pub mod build_activity;
+pub mod cli;
+pub mod containers;
+pub mod energy;
+pub mod export;
+pub mod fan_control;
+pub mod fan_noise;
+pub mod gpu_temperature;
 pub mod history;
+pub mod history_compare;
+pub mod history_stats;
+pub mod history_store;
+pub mod history_wal;
+pub mod import;
+pub mod influx;
+pub mod io_rates;
+pub mod low_interference;
+pub mod low_power_mode;
+pub mod memory_pressure;
+pub mod network_details;
+pub mod powermetrics;
+pub mod process_history;
+pub mod process_watch;
+pub mod remote_machines;
+pub mod sampling_budget;
+pub mod self_stats;
+pub mod sessions;
+pub mod snapshots;
+pub mod subscriptions;
+pub mod system_report;
+pub mod warm_start;
 
 use battery::{Manager as BatteryManager, State};
-use macsmc::Smc;
 use std::process::Command;
 use sysinfo::{Disks, System};
 use tauri::Manager;
@@ -32,6 +70,9 @@ pub struct SystemMetrics {
     pub gpu: f32,
     pub ram: f32,
     pub disk: f32,
+    /// Latest metric plugin values (`<plugin>.<metric>`, see `plugins::metric_plugins`)
+    #[serde(default, skip_serializing_if = "std::collections::BTreeMap::is_empty")]
+    pub plugins: std::collections::BTreeMap<String, f32>,
 }
 
 impl SystemMetrics {
@@ -51,6 +92,9 @@ pub struct ProcessUsage {
     pub name: String,
     pub cpu: f32,
     pub pid: u32,
+    /// CPU % samples over the last minute, oldest first (for sparklines; empty when untracked)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<f32>,
 }
 
 #[derive(serde::Serialize)]
@@ -83,6 +127,12 @@ pub struct CpuDetails {
     pub e_core_frequency: f32,
     pub cpu_power: f32,
     pub gpu_power: f32,
+    /// Average GPU cluster temperature in °C (0.0 until read)
+    #[serde(default)]
+    pub gpu_temperature: f32,
+    /// Neural Engine power in watts (0.0 unless the `powermetrics` provider runs)
+    #[serde(default)]
+    pub ane_power: f32,
     pub load_1: f64,
     pub load_5: f64,
     pub load_15: f64,
@@ -94,9 +144,44 @@ pub struct CpuDetails {
     pub can_read_frequency: bool,
     pub can_read_cpu_power: bool,
     pub can_read_gpu_power: bool,
+    #[serde(default)]
+    pub can_read_gpu_temperature: bool,
     pub battery_level: f32, // Battery level as percentage (0-100), or -1.0 if not available
     pub is_charging: bool,  // True if battery is charging, false if discharging or no battery
     pub has_battery: bool,  // True if device has a battery
+    /// Discharge rate over the last 10 minutes in %/hour (0 while charging or unknown)
+    #[serde(default)]
+    pub battery_drain_percent_per_hour: f32,
+    /// Current discharge power in watts (0 while charging or unknown)
+    #[serde(default)]
+    pub battery_drain_watts: f32,
+    /// Projected time to empty at the current drain rate (0 when there is no estimate)
+    #[serde(default)]
+    pub battery_remaining_secs: u64,
+    /// Fastest fan in RPM (0.0 until read or on fanless Macs)
+    #[serde(default)]
+    pub fan_rpm: f32,
+    /// Fan speed at or above `fanAudibleRpm`
+    #[serde(default)]
+    pub fan_audible: bool,
+    /// Minutes the fans were audible today (local day)
+    #[serde(default)]
+    pub fan_audible_minutes_today: f32,
+    #[serde(default)]
+    pub can_read_fan: bool,
+    /// CPU of running compiler/build processes, percent of the whole machine (see
+    /// `build_activity`)
+    #[serde(default)]
+    pub build_load: f32,
+    /// Busy compiler/build processes
+    #[serde(default)]
+    pub build_processes: u32,
+    /// Time since the running build started (0 when no build is running)
+    #[serde(default)]
+    pub build_elapsed_secs: u64,
+    /// Length of the last finished build (0 when none yet)
+    #[serde(default)]
+    pub last_build_secs: u64,
 }
 
 /// Get chip information (cached)
@@ -197,14 +282,17 @@ pub fn get_chip_info() -> String {
 }
 
 pub fn get_gpu_usage() -> f32 {
-    // Check cache first - GPU usage reading is expensive, so we cache for 2 seconds
-    if let Ok(cache) = GPU_USAGE_CACHE.try_lock() {
-        if let Some((usage, timestamp)) = cache.as_ref() {
-            // Return cached value if less than 2 seconds old
-            if timestamp.elapsed().as_secs() < 2 {
-                debug3!("GPU usage from cache: {}%", usage);
-                return *usage;
-            }
+    // Check cache first - GPU usage reading is expensive, so we cache for 2 seconds, longer while
+    // ioreg is over its sampling budget (indefinitely in low-interference mode, which skips the
+    // ioreg call)
+    let keep_cached = low_interference::is_active();
+    if let Some(cached) = crate::state::snapshot().gpu_usage.as_ref() {
+        // Return cached value if less than 2 seconds old
+        if keep_cached
+            || cached.age() < sampling_budget::interval(sampling_budget::Collector::Ioreg)
+        {
+            debug3!("GPU usage from cache: {}%", cached.value);
+            return cached.value;
         }
     }
 
@@ -214,10 +302,8 @@ pub fn get_gpu_usage() -> f32 {
     let gpu_usage = read_gpu_usage_from_system();
 
     // Update cache
-    if let Ok(mut cache) = GPU_USAGE_CACHE.try_lock() {
-        *cache = Some((gpu_usage, std::time::Instant::now()));
-        debug3!("GPU usage updated: {}%", gpu_usage);
-    }
+    crate::state::update_snapshot(|s| s.gpu_usage = Some(crate::state::Reading::new(gpu_usage)));
+    debug3!("GPU usage updated: {}%", gpu_usage);
 
     gpu_usage
 }
@@ -225,9 +311,16 @@ pub fn get_gpu_usage() -> f32 {
 /// Read GPU usage from system (ioreg or other methods)
 /// Returns GPU utilization as a percentage (0.0-100.0)
 fn read_gpu_usage_from_system() -> f32 {
-    // Method 1: Try AGXAccelerator (Apple Silicon GPUs)
-    // This is the most reliable method on Apple Silicon Macs
+    let _span = tracing::trace_span!(target: "mac_stats::perf", "gpu_ioreg").entered();
+    let _timer = sampling_budget::Timer::start(sampling_budget::Collector::Ioreg);
+    // Method 1: Try AGXAccelerator (Apple Silicon GPUs), or on Intel Macs any IOAccelerator
+    // (Intel / AMD GPU drivers subclass it)
     // The PerformanceStatistics dictionary contains "Device Utilization %"
+    let accelerator = if crate::capabilities::apple_silicon() {
+        "AGXAccelerator"
+    } else {
+        "IOAccelerator"
+    };
     let output = Command::new("/usr/sbin/ioreg")
         .arg("-r")
         .arg("-d")
@@ -235,7 +328,7 @@ fn read_gpu_usage_from_system() -> f32 {
         .arg("-w")
         .arg("0")
         .arg("-c")
-        .arg("AGXAccelerator")
+        .arg(accelerator)
         .stderr(std::process::Stdio::null())
         .output();
 
@@ -243,7 +336,7 @@ fn read_gpu_usage_from_system() -> f32 {
         Ok(output) => {
             if output.status.success() {
                 let stdout = String::from_utf8_lossy(&output.stdout);
-                debug3!("ioreg AGXAccelerator output length: {} bytes", stdout.len());
+                debug3!("ioreg {} output length: {} bytes", accelerator, stdout.len());
 
                 // Look for "Device Utilization %" in PerformanceStatistics
                 // Format: "Device Utilization %"=22 (within a JSON-like dictionary)
@@ -295,16 +388,17 @@ fn read_gpu_usage_from_system() -> f32 {
                         }
                     }
                 }
-                debug3!("ioreg AGXAccelerator: No utilization found in output");
+                debug3!("ioreg {}: No utilization found in output", accelerator);
             } else {
                 debug3!(
-                    "ioreg AGXAccelerator command failed with status: {:?}",
+                    "ioreg {} command failed with status: {:?}",
+                    accelerator,
                     output.status
                 );
             }
         }
         Err(e) => {
-            debug3!("Failed to execute ioreg AGXAccelerator command: {}", e);
+            debug3!("Failed to execute ioreg {} command: {}", accelerator, e);
         }
     }
 
@@ -467,55 +561,33 @@ fn extract_percentage_from_line(line: &str) -> Option<f32> {
     None
 }
 
-pub fn can_read_temperature() -> bool {
-    // Check if we have a valid cached temperature (indicates SMC access works)
-    // This is more efficient than checking SMC directly
-    if let Ok(cache) = TEMP_CACHE.try_lock() {
-        if let Some((temp, timestamp)) = cache.as_ref() {
-            // If we have a recent temperature reading, SMC access works
-            // Increased from 10s to 20s to match the 15s reading frequency
-            if *temp > 0.0 && timestamp.elapsed().as_secs() < 20 {
-                debug3!(
-                    "can_read_temperature: true (from TEMP_CACHE with temp={:.1}°C)",
-                    *temp
-                );
-                return true;
-            }
-        }
-    }
-
-    // OPTIMIZATION Phase 3: Use OnceLock for faster access (no locking required)
-    *CAN_READ_TEMPERATURE.get_or_init(|| {
-        debug3!("can_read_temperature: First time check - trying SMC connection...");
-        let can_read = if let Ok(mut smc) = Smc::connect() {
-            // Connection succeeded - we can attempt to read (even if it returns 0.0)
-            match smc.cpu_temperature() {
-                Ok(_) => {
-                    // SMC read succeeded (even if temp is 0.0, the read worked)
-                    debug3!("SMC connection and read succeeded - can_read_temperature=true");
-                    true
-                }
-                Err(e) => {
-                    // SMC read failed
-                    debug3!("SMC read failed: {:?} - can_read_temperature=false", e);
-                    false
-                }
-            }
-        } else {
-            // SMC connection failed - can't read
-            debug3!("SMC connection failed - can_read_temperature=false");
-            false
-        };
-
-        debug3!("can_read_temperature: Cached result: {}", can_read);
-        can_read
-    })
+/// `sysctl -n <name>` in Hz as GHz, when it looks like a CPU clock (0.1-10 GHz)
+fn sysctl_frequency_ghz(name: &str) -> Option<f32> {
+    let output = Command::new("/usr/sbin/sysctl")
+        .arg("-n")
+        .arg(name)
+        .stderr(std::process::Stdio::null())
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    let hz: f64 = String::from_utf8_lossy(&output.stdout).trim().parse().ok()?;
+    let ghz = (hz / 1_000_000_000.0) as f32;
+    (ghz > 0.1 && ghz < 10.0).then_some(ghz)
 }
 
 // Get nominal CPU frequency using sysctl (cheap, no sudo required)
 // This gives base/nominal frequency, not dynamic frequency
 pub(crate) fn get_nominal_frequency() -> f32 {
     *NOMINAL_FREQ.get_or_init(|| {
+        // Intel: hw.cpufrequency is the base clock (tbfrequency * clockrate is not a CPU
+        // frequency there)
+        if !crate::capabilities::apple_silicon() {
+            if let Some(ghz) = sysctl_frequency_ghz("hw.cpufrequency") {
+                debug3!("Nominal frequency from hw.cpufrequency: {:.2} GHz", ghz);
+                return ghz;
+            }
+        }
+
         // Try hw.tbfrequency * kern.clockrate.hz approach (works on Apple Silicon)
         let tbfreq_output = Command::new("/usr/sbin/sysctl")
             .arg("-n")
@@ -658,81 +730,6 @@ pub(crate) fn get_nominal_frequency() -> f32 {
     })
 }
 
-/// Check if frequency reading is available
-/// Currently unused but kept for potential future use.
-#[allow(dead_code)]
-pub fn can_read_frequency() -> bool {
-    // Check if we have a valid cached frequency (indicates frequency reading works)
-    if let Ok(cache) = FREQ_CACHE.try_lock() {
-        if let Some((freq, timestamp)) = cache.as_ref() {
-            // If we have a recent frequency reading, frequency access works
-            if *freq > 0.0 && timestamp.elapsed().as_secs() < 20 {
-                debug3!(
-                    "can_read_frequency: true (from FREQ_CACHE with freq={:.2} GHz)",
-                    *freq
-                );
-                return true;
-            }
-        }
-    }
-
-    // OPTIMIZATION Phase 3: Use OnceLock for faster access (no locking required)
-    *CAN_READ_FREQUENCY.get_or_init(|| {
-        debug3!("can_read_frequency: First time check - trying nominal frequency computation...");
-        let nominal = get_nominal_frequency();
-        let can_read = nominal > 0.0;
-
-        debug3!(
-            "can_read_frequency: Cached result: {} (nominal={:.2} GHz)",
-            can_read,
-            nominal
-        );
-        can_read
-    })
-}
-
-#[allow(dead_code)]
-pub fn can_read_cpu_power() -> bool {
-    // OPTIMIZATION Phase 3: Use OnceLock for faster access (no locking required)
-    // First check if it's been explicitly set
-    if let Some(can_read) = CAN_READ_CPU_POWER.get() {
-        return *can_read;
-    }
-
-    // If not set yet, check if we have power cache or actual power values
-    // This handles the case where power reading works but the flag hasn't been set yet
-    if let Ok(cache) = crate::state::POWER_CACHE.try_lock() {
-        if cache.is_some() {
-            // We have a power cache, so we can read power
-            return true;
-        }
-    }
-
-    // Default to false if nothing indicates we can read power
-    false
-}
-
-#[allow(dead_code)]
-pub fn can_read_gpu_power() -> bool {
-    // OPTIMIZATION Phase 3: Use OnceLock for faster access (no locking required)
-    // First check if it's been explicitly set
-    if let Some(can_read) = CAN_READ_GPU_POWER.get() {
-        return *can_read;
-    }
-
-    // If not set yet, check if we have power cache or actual power values
-    // This handles the case where power reading works but the flag hasn't been set yet
-    if let Ok(cache) = crate::state::POWER_CACHE.try_lock() {
-        if cache.is_some() {
-            // We have a power cache, so we can read power
-            return true;
-        }
-    }
-
-    // Default to false if nothing indicates we can read power
-    false
-}
-
 /// Get battery level and charging state (cached)
 /// Returns (battery_level_percent, is_charging, has_battery)
 /// battery_level_percent: 0-100 if battery exists, -1.0 if no battery
@@ -744,55 +741,55 @@ pub fn can_read_gpu_power() -> bool {
 pub fn get_battery_info() -> (f32, bool, bool) {
     // Check cache first (battery state doesn't change rapidly)
     // Battery reading via IOKit is lightweight, but we only read when window is visible
-    if let Ok(cache) = crate::state::BATTERY_CACHE.try_lock() {
-        if let Some((level, charging, timestamp)) = cache.as_ref() {
-            // Check if CPU window is visible before doing fresh read
-            let window_visible = crate::state::APP_HANDLE
-                .get()
-                .and_then(|app_handle| {
-                    app_handle
-                        .get_webview_window("cpu")
-                        .and_then(|window| window.is_visible().ok().filter(|&visible| visible))
-                })
-                .is_some();
+    if let Some(battery) = crate::state::snapshot().battery.as_ref() {
+        let (level, charging) = battery.value;
+        // Check if CPU window is visible before doing fresh read
+        let window_visible = crate::state::APP_HANDLE
+            .get()
+            .and_then(|app_handle| {
+                app_handle
+                    .get_webview_window("cpu")
+                    .and_then(|window| window.is_visible().ok().filter(|&visible| visible))
+            })
+            .is_some()
+            && !low_interference::is_active();
 
-            // If window is closed, always return cache (even if stale) to save CPU
-            if !window_visible {
-                debug3!(
-                    "Battery info from cache (window closed): {:.1}%, charging={}, has_battery={}",
-                    level,
-                    charging,
-                    *level >= 0.0
-                );
-                return (*level, *charging, *level >= 0.0);
-            }
+        // If window is closed, always return cache (even if stale) to save CPU
+        if !window_visible {
+            debug3!(
+                "Battery info from cache (window closed): {:.1}%, charging={}, has_battery={}",
+                level,
+                charging,
+                level >= 0.0
+            );
+            return (level, charging, level >= 0.0);
+        }
 
-            // If window is visible, use cache if fresh (less than 1 second old)
-            if timestamp.elapsed().as_secs() < 1 {
-                debug3!(
-                    "Battery info from cache: {:.1}%, charging={}, has_battery={}",
-                    level,
-                    charging,
-                    *level >= 0.0
-                );
-                return (*level, *charging, *level >= 0.0);
-            }
-        } else {
-            // No cache - check if window is visible before reading
-            let window_visible = crate::state::APP_HANDLE
-                .get()
-                .and_then(|app_handle| {
-                    app_handle
-                        .get_webview_window("cpu")
-                        .and_then(|window| window.is_visible().ok().filter(|&visible| visible))
-                })
-                .is_some();
+        // If window is visible, use cache if fresh (less than 1 second old)
+        if battery.age().as_secs() < 1 {
+            debug3!(
+                "Battery info from cache: {:.1}%, charging={}, has_battery={}",
+                level,
+                charging,
+                level >= 0.0
+            );
+            return (level, charging, level >= 0.0);
+        }
+    } else {
+        // No cache - check if window is visible before reading
+        let window_visible = crate::state::APP_HANDLE
+            .get()
+            .and_then(|app_handle| {
+                app_handle
+                    .get_webview_window("cpu")
+                    .and_then(|window| window.is_visible().ok().filter(|&visible| visible))
+            })
+            .is_some();
 
-            if !window_visible {
-                // Window closed and no cache - return default values to save CPU
-                debug3!("Battery info: window closed, no cache, returning defaults");
-                return (-1.0, false, false);
-            }
+        if !window_visible {
+            // Window closed and no cache - return default values to save CPU
+            debug3!("Battery info: window closed, no cache, returning defaults");
+            return (-1.0, false, false);
         }
     }
 
@@ -809,6 +806,13 @@ pub fn get_battery_info() -> (f32, bool, bool) {
                                     .state_of_charge()
                                     .get::<battery::units::ratio::percent>();
                                 let is_charging = matches!(battery.state(), State::Charging);
+                                battery_drain::record(
+                                    percentage,
+                                    matches!(battery.state(), State::Discharging),
+                                    battery
+                                        .energy_rate()
+                                        .get::<battery::units::power::watt>(),
+                                );
 
                                 debug3!(
                                     "Battery read: {:.1}%, charging={}",
@@ -817,10 +821,12 @@ pub fn get_battery_info() -> (f32, bool, bool) {
                                 );
 
                                 // Update cache
-                                if let Ok(mut cache) = crate::state::BATTERY_CACHE.try_lock() {
-                                    *cache =
-                                        Some((percentage, is_charging, std::time::Instant::now()));
-                                }
+                                crate::state::update_snapshot(|s| {
+                                    s.battery = Some(crate::state::Reading::new((
+                                        percentage,
+                                        is_charging,
+                                    )))
+                                });
 
                                 (percentage, is_charging, true)
                             }
@@ -869,82 +875,43 @@ pub fn get_power_consumption() -> (f32, f32) {
 
     // Check cache first
     // IOReport power reading is expensive, so we cache longer
-    if let Ok(cache) = crate::state::POWER_CACHE.try_lock() {
-        if let Some((cpu_power, gpu_power, timestamp)) = cache.as_ref() {
-            // If window is closed, always return cache (even if stale) to save CPU
+    // The snapshot is never locked, so a read racing the background writer still sees the
+    // last published values instead of falling back to 0.0
+    match crate::state::snapshot().power.as_ref() {
+        Some(power) => {
+            let (cpu_power, gpu_power) = power.value;
             if !window_visible {
+                // Window closed: return cache (even if stale) to save CPU
                 debug3!(
                     "Power consumption from cache (window closed): CPU={:.2}W, GPU={:.2}W",
                     cpu_power,
                     gpu_power
                 );
-                return (*cpu_power, *gpu_power);
-            }
-
-            // If window is visible, use cache if fresh (less than 6 seconds old)
-            // Background thread updates every 5 seconds
-            if timestamp.elapsed().as_secs() < 6 {
-                debug3!(
-                    "Power consumption from cache: CPU={:.2}W, GPU={:.2}W",
-                    cpu_power,
-                    gpu_power
-                );
-                return (*cpu_power, *gpu_power);
             } else {
-                // Cache is stale, but return last known values instead of 0.0 to prevent flickering
-                // Background thread will update it soon
+                // Background thread updates every 5 seconds; a stale value still beats
+                // flickering to 0.0 until the next update
                 debug3!(
-                    "Power consumption from stale cache: CPU={:.2}W, GPU={:.2}W (age: {}s)",
+                    "Power consumption from cache: CPU={:.2}W, GPU={:.2}W (age: {}s)",
                     cpu_power,
                     gpu_power,
-                    timestamp.elapsed().as_secs()
+                    power.age().as_secs()
                 );
-                return (*cpu_power, *gpu_power);
-            }
-        } else {
-            // No cache - if window is closed, return 0.0 to save CPU
-            if !window_visible {
-                debug3!("Power consumption: window closed, no cache, returning 0.0W");
-                return (0.0, 0.0);
             }
+            return (cpu_power, gpu_power);
         }
-    }
-
-    // Power reading is handled by background thread when window is visible
-    // This function just returns cached values
-    // If no cache exists yet, return last successful reading or 0.0
-    // CRITICAL: Never return 0.0 if we have a cache - always return cached values
-    // This prevents flickering when cache exists but is being queried before update
-    if let Ok(cache) = crate::state::POWER_CACHE.try_lock() {
-        if let Some((cpu_power, gpu_power, _)) = cache.as_ref() {
-            // We have a cache - return it even if it seems stale
-            // This prevents flickering to 0.0
-            // Also update the last successful reading so we have a fallback if lock fails later
-            if let Ok(mut last_successful) = crate::state::LAST_SUCCESSFUL_POWER.try_lock() {
-                *last_successful = Some((*cpu_power, *gpu_power));
-            }
-            debug3!("Power consumption: returning cached values (no fresh update yet): CPU={:.2}W, GPU={:.2}W",
-                cpu_power, gpu_power);
-            return (*cpu_power, *gpu_power);
-        }
-    } else {
-        // CRITICAL: try_lock() failed (background thread has the lock)
-        // Return last successful reading instead of 0.0 to prevent flickering
-        if let Ok(last_successful) = crate::state::LAST_SUCCESSFUL_POWER.try_lock() {
-            if let Some((cpu_power, gpu_power)) = last_successful.as_ref() {
-                debug3!("Power consumption: returning last successful values (lock failed): CPU={:.2}W, GPU={:.2}W",
-                    cpu_power, gpu_power);
-                return (*cpu_power, *gpu_power);
-            }
+        None if !window_visible => {
+            debug3!("Power consumption: window closed, no cache, returning 0.0W");
+            return (0.0, 0.0);
         }
+        None => {}
     }
 
-    // No cache and no previous successful reading - return 0.0 (initial state)
-    debug3!("Power consumption: no cache and no previous reading, returning 0.0W");
+    // No reading yet - return 0.0 (initial state)
+    debug3!("Power consumption: no reading yet, returning 0.0W");
     (0.0, 0.0)
 }
 
-#[tauri::command]
+/// Live metrics (the frontend's `get_metrics` command is `warm_start::get_metrics`)
 pub fn get_metrics() -> SystemMetrics {
     debug3!("get_metrics() called");
 
@@ -1058,6 +1025,7 @@ pub fn get_metrics() -> SystemMetrics {
         gpu: gpu_usage,
         ram: ram_usage,
         disk: disk_usage,
+        plugins: crate::plugins::metric_plugins::current(),
     };
 
     debug3!(
@@ -1081,8 +1049,13 @@ pub fn format_metrics_for_ai_context() -> String {
         "CPU: {:.1}%, GPU: {:.1}%, RAM: {:.1}%, Disk: {:.1}%",
         m.cpu, m.gpu, m.ram, m.disk
     ));
+    let units = crate::units::Units::current();
     if c.can_read_temperature && c.temperature > 0.0 {
-        lines.push(format!("Temperature: {:.1}°C", c.temperature));
+        lines.push(format!(
+            "Temperature: {:.1}{}",
+            units.temperature(c.temperature as f64),
+            units.temperature_symbol()
+        ));
     } else if c.can_read_temperature {
         lines.push("Temperature: N/A".to_string());
     }
@@ -1117,6 +1090,18 @@ pub fn format_metrics_for_ai_context() -> String {
             lines.push(format!("Power: {}", parts.join(", ")));
         }
     }
+    let backup = backup::backup_status();
+    if backup.running {
+        let progress = backup
+            .percent
+            .map(|p| format!(" {:.0}%", p))
+            .unwrap_or_default();
+        lines.push(format!(
+            "Time Machine: backup running{} ({})",
+            progress,
+            backup.phase.as_deref().unwrap_or("in progress")
+        ));
+    }
     lines.push(format!("Uptime: {} s", c.uptime_secs));
     if !c.top_processes.is_empty() {
         let top: Vec<String> = c
@@ -1243,7 +1228,16 @@ pub fn get_ai_agent_enabled() -> bool {
 }
 
 #[tauri::command]
-pub fn set_ai_agent_enabled(enabled: bool) -> Result<bool, String> {
+pub fn set_ai_agent_enabled(
+    enabled: bool,
+    confirmation_token: Option<String>,
+) -> Result<bool, String> {
+    crate::guest_mode::ensure_allowed("Changing settings")?;
+    crate::commands::confirmation::require_confirmation(
+        confirmation_token.as_deref(),
+        "config_write",
+        "aiAgentEnabled",
+    )?;
     crate::config::Config::set_ai_agent_enabled(enabled)?;
     if enabled {
         // Best-effort: start Discord if a token exists (scheduler needs process restart for full stack).
@@ -1258,20 +1252,46 @@ pub fn get_menu_bar_compact() -> bool {
 }
 
 #[tauri::command]
-pub fn set_menu_bar_compact(compact: bool) -> Result<bool, String> {
+pub fn set_menu_bar_compact(
+    compact: bool,
+    confirmation_token: Option<String>,
+) -> Result<bool, String> {
+    crate::guest_mode::ensure_allowed("Changing settings")?;
+    crate::commands::confirmation::require_confirmation(
+        confirmation_token.as_deref(),
+        "config_write",
+        "menuBarCompact",
+    )?;
     crate::config::Config::set_menu_bar_compact(compact)?;
     Ok(crate::config::Config::menu_bar_compact())
 }
 
 #[tauri::command]
-pub fn reset_config_to_monitor_defaults() -> Result<String, String> {
+pub fn reset_config_to_monitor_defaults(
+    confirmation_token: Option<String>,
+) -> Result<String, String> {
+    crate::guest_mode::ensure_allowed("Resetting settings")?;
+    crate::commands::confirmation::require_confirmation(
+        confirmation_token.as_deref(),
+        "config_reset",
+        "config.json",
+    )?;
     crate::config::Config::reset_config_to_monitor_defaults()?;
     Ok("Monitor defaults applied (aiAgentEnabled=false, menuBarCompact=true). Restart recommended for Discord/scheduler.".into())
 }
 
 /// Set window decorations preference
 #[tauri::command]
-pub fn set_window_decorations(decorations: bool) -> Result<(), String> {
+pub fn set_window_decorations(
+    decorations: bool,
+    confirmation_token: Option<String>,
+) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Changing settings")?;
+    crate::commands::confirmation::require_confirmation(
+        confirmation_token.as_deref(),
+        "config_write",
+        "windowDecorations",
+    )?;
     use crate::config::Config;
     use serde_json::{json, Value};
 
@@ -1325,7 +1345,7 @@ pub fn set_window_decorations(decorations: bool) -> Result<(), String> {
     Ok(())
 }
 
-#[tauri::command]
+/// Live CPU details (the frontend's `get_cpu_details` command is `warm_start::get_cpu_details`)
 pub fn get_cpu_details() -> CpuDetails {
     // STEP 5: Rate limiting - prevent get_cpu_details from being called too frequently
     // BUT: Always allow process cache age check - processes need to refresh every 5s
@@ -1390,27 +1410,15 @@ pub fn get_cpu_details() -> CpuDetails {
         };
 
         // Return cached values only
+        let snapshot = crate::state::snapshot();
         let (temperature, frequency, p_core_frequency, e_core_frequency) = (
-            crate::state::TEMP_CACHE
-                .try_lock()
-                .ok()
-                .and_then(|c| c.as_ref().map(|(t, _)| *t))
-                .unwrap_or(0.0),
-            crate::state::FREQ_CACHE
-                .try_lock()
-                .ok()
-                .and_then(|c| c.as_ref().map(|(f, _)| *f))
-                .unwrap_or(crate::metrics::get_nominal_frequency()),
-            crate::state::P_CORE_FREQ_CACHE
-                .try_lock()
-                .ok()
-                .and_then(|c| c.as_ref().map(|(f, _)| *f))
-                .unwrap_or(0.0),
-            crate::state::E_CORE_FREQ_CACHE
-                .try_lock()
-                .ok()
-                .and_then(|c| c.as_ref().map(|(f, _)| *f))
-                .unwrap_or(0.0),
+            snapshot.temperature.as_ref().map_or(0.0, |t| t.value),
+            snapshot
+                .frequency
+                .as_ref()
+                .map_or_else(crate::metrics::get_nominal_frequency, |f| f.value),
+            snapshot.p_core_frequency.as_ref().map_or(0.0, |f| f.value),
+            snapshot.e_core_frequency.as_ref().map_or(0.0, |f| f.value),
         );
 
         // CRITICAL: Check process cache age even when rate-limited
@@ -1426,70 +1434,39 @@ pub fn get_cpu_details() -> CpuDetails {
                 .is_some();
 
             if should_collect_processes {
-                match crate::state::PROCESS_CACHE.try_lock() {
-                    Ok(cache) => {
-                        if let Some((procs, timestamp)) = cache.as_ref() {
-                            let age_secs = timestamp.elapsed().as_secs();
-                            if age_secs >= 5 {
-                                // Cache is stale - refresh now even if rate-limited
-                                debug3!("Process cache is stale ({}s) - refreshing now (even though rate-limited)", age_secs);
-                                // Need SYSTEM lock to refresh processes
-                                match crate::state::SYSTEM.try_lock() {
-                                    Ok(mut sys) => {
-                                        if let Some(sys) = sys.as_mut() {
-                                            use sysinfo::ProcessesToUpdate;
-                                            sys.refresh_processes(ProcessesToUpdate::All, true);
-
-                                            let mut processes: Vec<crate::metrics::ProcessUsage> =
-                                                sys.processes()
-                                                    .iter()
-                                                    .map(|(pid, proc)| {
-                                                        crate::metrics::ProcessUsage {
-                                                            name: proc
-                                                                .name()
-                                                                .to_string_lossy()
-                                                                .to_string(),
-                                                            cpu: proc.cpu_usage(),
-                                                            pid: pid.as_u32(),
-                                                        }
-                                                    })
-                                                    .collect();
-
-                                            processes.sort_by(|a, b| {
-                                                b.cpu
-                                                    .partial_cmp(&a.cpu)
-                                                    .unwrap_or(std::cmp::Ordering::Equal)
-                                            });
-                                            processes.truncate(8);
-
-                                            // Update cache
-                                            if let Ok(mut process_cache) =
-                                                crate::state::PROCESS_CACHE.try_lock()
-                                            {
-                                                *process_cache = Some((
-                                                    processes.clone(),
-                                                    std::time::Instant::now(),
-                                                ));
-                                                debug3!(
-                                                    "Process cache refreshed (rate-limited call)"
-                                                );
-                                            }
-
-                                            processes
-                                        } else {
-                                            procs.clone()
-                                        }
-                                    }
-                                    Err(_) => procs.clone(), // SYSTEM locked, return cached
+                if let Some(cached) = snapshot.processes.as_ref() {
+                    let age_secs = cached.age().as_secs();
+                    if age_secs >= 5 {
+                        // Cache is stale - refresh now even if rate-limited
+                        debug3!("Process cache is stale ({}s) - refreshing now (even though rate-limited)", age_secs);
+                        // Need SYSTEM lock to refresh processes
+                        match crate::state::SYSTEM.try_lock() {
+                            Ok(mut sys) => {
+                                if let Some(sys) = sys.as_mut() {
+                                    use sysinfo::ProcessesToUpdate;
+                                    sys.refresh_processes(ProcessesToUpdate::All, true);
+
+                                    let processes = top_processes_after_refresh(sys, 8);
+
+                                    // Update cache
+                                    crate::state::update_snapshot(|s| {
+                                        s.processes =
+                                            Some(crate::state::Reading::new(processes.clone()))
+                                    });
+                                    debug3!("Process cache refreshed (rate-limited call)");
+
+                                    processes
+                                } else {
+                                    cached.value.clone()
                                 }
-                            } else {
-                                procs.clone()
                             }
-                        } else {
-                            Vec::new()
+                            Err(_) => cached.value.clone(), // SYSTEM locked, return cached
                         }
+                    } else {
+                        cached.value.clone()
                     }
-                    Err(_) => Vec::new(),
+                } else {
+                    Vec::new()
                 }
             } else {
                 Vec::new()
@@ -1499,13 +1476,10 @@ pub fn get_cpu_details() -> CpuDetails {
         };
 
         // Get cached battery and power info
-        let (battery_level, is_charging, has_battery) = crate::state::BATTERY_CACHE
-            .try_lock()
-            .ok()
-            .and_then(|c| {
-                c.as_ref()
-                    .map(|(level, charging, _)| (*level, *charging, *level >= 0.0))
-            })
+        let (battery_level, is_charging, has_battery) = snapshot
+            .battery
+            .as_ref()
+            .map(|b| (b.value.0, b.value.1, b.value.0 >= 0.0))
             .unwrap_or((-1.0, false, false));
 
         // Use get_power_consumption() for consistent cache handling
@@ -1514,19 +1488,17 @@ pub fn get_cpu_details() -> CpuDetails {
 
         // Check if we actually have power values (even if 0, if we have a cache entry, we can read power)
         // This is more reliable than checking the flags, which might not be set yet
-        let has_power_cache = crate::state::POWER_CACHE
-            .try_lock()
-            .ok()
-            .map(|c| c.is_some())
-            .unwrap_or(false);
+        let has_power_cache = crate::state::snapshot().power.is_some();
 
         // If we have power cache, we can read power (even if values are currently 0)
         // This prevents showing "Requires root privileges" when we're just waiting for the first read
         // OR if we have actual power values > 0, we definitely can read power
-        let can_read_cpu_power =
-            has_power_cache || cpu_power > 0.0 || crate::metrics::can_read_cpu_power();
-        let can_read_gpu_power =
-            has_power_cache || gpu_power > 0.0 || crate::metrics::can_read_gpu_power();
+        let capabilities = crate::capabilities::get();
+        let can_read_cpu_power = has_power_cache || cpu_power > 0.0 || capabilities.cpu_power;
+        let can_read_gpu_power = has_power_cache || gpu_power > 0.0 || capabilities.gpu_power;
+        let drain = battery_drain::current();
+        let fan = fan_noise::current();
+        let build = build_activity::current();
 
         return CpuDetails {
             usage,
@@ -1536,19 +1508,33 @@ pub fn get_cpu_details() -> CpuDetails {
             e_core_frequency,
             cpu_power,
             gpu_power,
+            gpu_temperature: gpu_temperature::cached(),
+            ane_power: powermetrics::ane_power(),
             load_1: load.one,
             load_5: load.five,
             load_15: load.fifteen,
             uptime_secs,
             top_processes: processes,
             chip_info: crate::metrics::get_chip_info(),
-            can_read_temperature: crate::metrics::can_read_temperature(),
-            can_read_frequency: crate::metrics::can_read_frequency(),
+            can_read_temperature: capabilities.temperature,
+            can_read_frequency: capabilities.frequency,
             can_read_cpu_power,
             can_read_gpu_power,
+            can_read_gpu_temperature: capabilities.gpu_temperature,
             battery_level,
             is_charging,
             has_battery,
+            battery_drain_percent_per_hour: drain.percent_per_hour,
+            battery_drain_watts: drain.watts,
+            battery_remaining_secs: drain.remaining_secs,
+            fan_rpm: fan.map_or(0.0, |f| f.rpm),
+            fan_audible: fan.is_some_and(|f| f.audible),
+            fan_audible_minutes_today: fan_noise::audible_minutes_today(),
+            can_read_fan: capabilities.fan,
+            build_load: build.load,
+            build_processes: build.processes,
+            build_elapsed_secs: build.elapsed_secs,
+            last_build_secs: build.last_build_secs,
         };
     }
 
@@ -1565,7 +1551,8 @@ pub fn get_cpu_details() -> CpuDetails {
                 window.is_visible().ok().filter(|&visible| visible)
             })
         })
-        .is_some();
+        .is_some()
+        && !low_interference::is_active();
 
     // CRITICAL: Use try_lock ONCE - if locked, return cached values immediately
     // This prevents blocking the main thread when the window opens
@@ -1578,10 +1565,10 @@ pub fn get_cpu_details() -> CpuDetails {
                 let load = sysinfo::System::load_average();
                 let uptime_secs = sysinfo::System::uptime();
                 // Try to get cached processes, otherwise empty
-                let processes = crate::state::PROCESS_CACHE
-                    .try_lock()
-                    .ok()
-                    .and_then(|c| c.as_ref().map(|(p, _)| p.clone()))
+                let processes = crate::state::snapshot()
+                    .processes
+                    .as_ref()
+                    .map(|p| p.value.clone())
                     .unwrap_or_default();
                 // Return 0.0 for usage (will be updated on next refresh)
                 (0.0, load, uptime_secs, processes)
@@ -1605,19 +1592,18 @@ pub fn get_cpu_details() -> CpuDetails {
                     // STEP 4: Cache process list for 5 seconds when window is open (refresh every 5s)
                     // CRITICAL: Always check cache first and return immediately if available
                     // This prevents blocking on expensive refresh_processes() when window first opens
-                    let cached_processes = match PROCESS_CACHE.try_lock() {
-                        Ok(cache) => cache.as_ref().map(|(procs, timestamp)| {
-                            let age_secs = timestamp.elapsed().as_secs();
-                            (procs.clone(), age_secs)
-                        }),
-                        Err(_) => None, // Lock held, skip cache check
-                    };
+                    let cached_processes = crate::state::snapshot()
+                        .processes
+                        .as_ref()
+                        .map(|p| (p.value.clone(), p.age().as_secs()));
 
                     // If we have cached data, check if it's still fresh (<10 seconds)
                     // OPTIMIZATION Phase 1: Increased from 5s to 10s to reduce process enumeration overhead
                     // BUT: If cache is empty (None), always refresh immediately for instant display
                     if let Some((cached_procs, age_secs)) = cached_processes {
-                        if age_secs < 10 {
+                        if std::time::Duration::from_secs(age_secs)
+                            < sampling_budget::interval(sampling_budget::Collector::Processes)
+                        {
                             // Cache is less than 10 seconds old - return immediately
                             // This prevents blocking and reduces CPU usage
                             debug3!(
@@ -1632,35 +1618,19 @@ pub fn get_cpu_details() -> CpuDetails {
                                 age_secs
                             );
                             use sysinfo::ProcessesToUpdate;
-                            sys.refresh_processes(ProcessesToUpdate::All, true);
-
-                            // Collect ALL processes first (HashMap iteration order is undefined)
-                            // Then sort by CPU usage to get the actual top processes
-                            let mut processes: Vec<ProcessUsage> = sys
-                                .processes()
-                                .iter()
-                                .map(|(pid, proc)| ProcessUsage {
-                                    name: proc.name().to_string_lossy().to_string(),
-                                    cpu: proc.cpu_usage(),
-                                    pid: pid.as_u32(),
-                                })
-                                .collect();
-
-                            // Sort by CPU usage (descending) to get actual top processes
-                            processes.sort_by(|a, b| {
-                                b.cpu
-                                    .partial_cmp(&a.cpu)
-                                    .unwrap_or(std::cmp::Ordering::Equal)
-                            });
+                            {
+                                let _timer =
+                                    sampling_budget::Timer::start(sampling_budget::Collector::Processes);
+                                sys.refresh_processes(ProcessesToUpdate::All, true);
+                            }
 
-                            // Take top 8 after sorting
-                            processes.truncate(8);
+                            let processes = top_processes_after_refresh(sys, 8);
 
                             // Update cache
-                            if let Ok(mut cache) = PROCESS_CACHE.try_lock() {
-                                *cache = Some((processes.clone(), std::time::Instant::now()));
-                                debug3!("Process cache updated (refreshed from system)");
-                            }
+                            crate::state::update_snapshot(|s| {
+                                s.processes = Some(crate::state::Reading::new(processes.clone()))
+                            });
+                            debug3!("Process cache updated (refreshed from system)");
 
                             processes
                         }
@@ -1672,33 +1642,13 @@ pub fn get_cpu_details() -> CpuDetails {
                         use sysinfo::ProcessesToUpdate;
                         sys.refresh_processes(ProcessesToUpdate::All, true);
 
-                        // Collect ALL processes first (HashMap iteration order is undefined)
-                        // Then sort by CPU usage to get the actual top processes
-                        let mut processes: Vec<ProcessUsage> = sys
-                            .processes()
-                            .iter()
-                            .map(|(pid, proc)| ProcessUsage {
-                                name: proc.name().to_string_lossy().to_string(),
-                                cpu: proc.cpu_usage(),
-                                pid: pid.as_u32(),
-                            })
-                            .collect();
-
-                        // Sort by CPU usage (descending) to get actual top processes
-                        processes.sort_by(|a, b| {
-                            b.cpu
-                                .partial_cmp(&a.cpu)
-                                .unwrap_or(std::cmp::Ordering::Equal)
-                        });
-
-                        // Take top 8 after sorting
-                        processes.truncate(8);
+                        let processes = top_processes_after_refresh(sys, 8);
 
                         // Update cache
-                        if let Ok(mut cache) = PROCESS_CACHE.try_lock() {
-                            *cache = Some((processes.clone(), std::time::Instant::now()));
-                            debug3!("Process cache updated (refreshed from system)");
-                        }
+                        crate::state::update_snapshot(|s| {
+                            s.processes = Some(crate::state::Reading::new(processes.clone()))
+                        });
+                        debug3!("Process cache updated (refreshed from system)");
 
                         processes
                     }
@@ -1754,68 +1704,45 @@ pub fn get_cpu_details() -> CpuDetails {
         is_charging,
         has_battery,
     ) = {
-        // Get cached access flags (fast OnceLock access, no blocking)
-        let _can_read_temp = CAN_READ_TEMPERATURE.get().copied().unwrap_or(false);
-        let can_read_freq = CAN_READ_FREQUENCY.get().copied().unwrap_or(false);
-        let can_read_cpu_p = CAN_READ_CPU_POWER.get().copied().unwrap_or(false);
-        let can_read_gpu_p = CAN_READ_GPU_POWER.get().copied().unwrap_or(false);
-
-        // CRITICAL: Read temperature from cache (updated by background thread)
-        // Non-blocking read - returns 0.0 if cache is locked or stale
+        // Probed at launch, corrected by the collectors (no blocking)
+        let capabilities = crate::capabilities::get();
+        let can_read_temp = capabilities.temperature;
+        let can_read_freq = capabilities.frequency;
+        let can_read_cpu_p = capabilities.cpu_power;
+        let can_read_gpu_p = capabilities.gpu_power;
+
+        // CRITICAL: Read temperature from the snapshot (updated by background thread)
+        // Lock-free read - returns 0.0 if stale
         // Cache is valid for up to 20 seconds (background thread updates every 15 seconds)
-        let temperature = match TEMP_CACHE.try_lock() {
-            Ok(cache) => {
-                if let Some((temp, timestamp)) = cache.as_ref() {
-                    // Only use cached value if it's fresh (less than 20 seconds old)
-                    // Increased from 10s to 20s to match the 15s reading frequency
-                    if timestamp.elapsed().as_secs() < 20 {
-                        *temp
-                    } else {
-                        debug3!(
-                            "Temperature cache is stale ({}s old), using 0.0",
-                            timestamp.elapsed().as_secs()
-                        );
-                        0.0
-                    }
-                } else {
-                    0.0
-                }
-            }
-            Err(_) => {
-                // Cache locked - return 0.0 (non-blocking)
+        let snapshot = crate::state::snapshot();
+        let temperature = match snapshot.temperature.as_ref() {
+            // Increased from 10s to 20s to match the 15s reading frequency
+            Some(temp) if temp.age().as_secs() < 20 => temp.value,
+            Some(temp) => {
+                debug3!(
+                    "Temperature cache is stale ({}s old), using 0.0",
+                    temp.age().as_secs()
+                );
                 0.0
             }
+            None => 0.0,
         };
 
-        // Check if we can read temperature (uses efficient cache check)
-        let can_read_temp = can_read_temperature();
-
-        // CRITICAL: Read frequency from cache (updated by background thread)
-        // Non-blocking read - returns nominal frequency if cache is locked or stale
+        // CRITICAL: Read frequency from the snapshot (updated by background thread)
+        // Lock-free read - returns nominal frequency if stale
         // Cache is valid for up to 35 seconds (background thread updates every 30 seconds)
-        let frequency = match FREQ_CACHE.try_lock() {
-            Ok(cache) => {
-                if let Some((freq, timestamp)) = cache.as_ref() {
-                    // Only use cached value if it's fresh (less than 35 seconds old)
-                    if timestamp.elapsed().as_secs() < 35 {
-                        *freq
-                    } else {
-                        // Cache is stale, fallback to nominal
-                        debug3!(
-                            "Frequency cache is stale ({}s old), using nominal frequency",
-                            timestamp.elapsed().as_secs()
-                        );
-                        get_nominal_frequency()
-                    }
-                } else {
-                    // No cached value, use nominal
-                    get_nominal_frequency()
-                }
-            }
-            Err(_) => {
-                // Cache locked - return nominal frequency (non-blocking)
+        let frequency = match snapshot.frequency.as_ref() {
+            Some(freq) if freq.age().as_secs() < 35 => freq.value,
+            Some(freq) => {
+                // Cache is stale, fallback to nominal
+                debug3!(
+                    "Frequency cache is stale ({}s old), using nominal frequency",
+                    freq.age().as_secs()
+                );
                 get_nominal_frequency()
             }
+            // No cached value, use nominal
+            None => get_nominal_frequency(),
         };
 
         // Read P-core and E-core frequencies from cache
@@ -1824,69 +1751,57 @@ pub fn get_cpu_details() -> CpuDetails {
             .map(|f| *f)
             .unwrap_or(false);
 
-        let p_core_frequency = match P_CORE_FREQ_CACHE.try_lock() {
-            Ok(cache) => {
-                if let Some((freq, timestamp)) = cache.as_ref() {
-                    let age_secs = timestamp.elapsed().as_secs();
-                    if age_secs < 35 {
-                        if freq_logging {
-                            debug3!(
-                                "P-core frequency from cache: {:.2} GHz (age: {}s)",
-                                *freq,
-                                age_secs
-                            );
-                        }
-                        *freq
-                    } else {
-                        if freq_logging {
-                            debug3!("P-core frequency cache is stale ({}s old), falling back to nominal", age_secs);
-                        }
-                        get_nominal_frequency() // Fallback to nominal if stale
-                    }
-                } else {
-                    if freq_logging {
-                        debug3!("P-core frequency cache is empty, falling back to nominal");
-                    }
-                    get_nominal_frequency()
+        let p_core_frequency = match snapshot.p_core_frequency.as_ref() {
+            Some(freq) if freq.age().as_secs() < 35 => {
+                if freq_logging {
+                    debug3!(
+                        "P-core frequency from cache: {:.2} GHz (age: {}s)",
+                        freq.value,
+                        freq.age().as_secs()
+                    );
                 }
+                freq.value
             }
-            Err(_) => {
+            Some(freq) => {
                 if freq_logging {
-                    debug3!("P-core frequency cache is locked, falling back to nominal");
+                    debug3!(
+                        "P-core frequency cache is stale ({}s old), falling back to nominal",
+                        freq.age().as_secs()
+                    );
+                }
+                get_nominal_frequency() // Fallback to nominal if stale
+            }
+            None => {
+                if freq_logging {
+                    debug3!("P-core frequency cache is empty, falling back to nominal");
                 }
                 get_nominal_frequency()
             }
         };
 
-        let e_core_frequency = match E_CORE_FREQ_CACHE.try_lock() {
-            Ok(cache) => {
-                if let Some((freq, timestamp)) = cache.as_ref() {
-                    let age_secs = timestamp.elapsed().as_secs();
-                    if age_secs < 35 {
-                        if freq_logging {
-                            debug3!(
-                                "E-core frequency from cache: {:.2} GHz (age: {}s)",
-                                *freq,
-                                age_secs
-                            );
-                        }
-                        *freq
-                    } else {
-                        if freq_logging {
-                            debug3!("E-core frequency cache is stale ({}s old), falling back to nominal", age_secs);
-                        }
-                        get_nominal_frequency() // Fallback to nominal if stale
-                    }
-                } else {
-                    if freq_logging {
-                        debug3!("E-core frequency cache is empty, falling back to nominal");
-                    }
-                    get_nominal_frequency()
+        let e_core_frequency = match snapshot.e_core_frequency.as_ref() {
+            Some(freq) if freq.age().as_secs() < 35 => {
+                if freq_logging {
+                    debug3!(
+                        "E-core frequency from cache: {:.2} GHz (age: {}s)",
+                        freq.value,
+                        freq.age().as_secs()
+                    );
+                }
+                freq.value
+            }
+            Some(freq) => {
+                if freq_logging {
+                    debug3!(
+                        "E-core frequency cache is stale ({}s old), falling back to nominal",
+                        freq.age().as_secs()
+                    );
                 }
+                get_nominal_frequency() // Fallback to nominal if stale
             }
-            Err(_) => {
+            None => {
                 if freq_logging {
-                    debug3!("E-core frequency cache is locked, falling back to nominal");
+                    debug3!("E-core frequency cache is empty, falling back to nominal");
                 }
                 get_nominal_frequency()
             }
@@ -1933,6 +1848,10 @@ pub fn get_cpu_details() -> CpuDetails {
         debug3!("get_cpu_details returning: temperature={:.1}°C, frequency={:.2} GHz, can_read_temperature={}, can_read_frequency={}", temperature, frequency, can_read_temperature, can_read_frequency);
     }
 
+    let capabilities = crate::capabilities::get();
+    let drain = battery_drain::current();
+    let fan = fan_noise::current();
+    let build = build_activity::current();
     CpuDetails {
         usage,
         temperature,
@@ -1941,6 +1860,8 @@ pub fn get_cpu_details() -> CpuDetails {
         e_core_frequency,
         cpu_power,
         gpu_power,
+        gpu_temperature: gpu_temperature::cached(),
+        ane_power: powermetrics::ane_power(),
         load_1: load.one,
         load_5: load.five,
         load_15: load.fifteen,
@@ -1951,9 +1872,21 @@ pub fn get_cpu_details() -> CpuDetails {
         can_read_frequency,
         can_read_cpu_power,
         can_read_gpu_power,
+        can_read_gpu_temperature: capabilities.gpu_temperature,
         battery_level,
         is_charging,
         has_battery,
+        battery_drain_percent_per_hour: drain.percent_per_hour,
+        battery_drain_watts: drain.watts,
+        battery_remaining_secs: drain.remaining_secs,
+        fan_rpm: fan.map_or(0.0, |f| f.rpm),
+        fan_audible: fan.is_some_and(|f| f.audible),
+        fan_audible_minutes_today: fan_noise::audible_minutes_today(),
+        can_read_fan: capabilities.fan,
+        build_load: build.load,
+        build_processes: build.processes,
+        build_elapsed_secs: build.elapsed_secs,
+        last_build_secs: build.last_build_secs,
     }
 }
 
@@ -2080,10 +2013,16 @@ fn get_username_from_uid(uid: u32) -> Option<String> {
     }
 }
 
-/// Force quit a process by PID
+/// Force quit a process by PID (requires a `force_quit` confirmation token for that PID)
 #[tauri::command]
-pub fn force_quit_process(pid: u32) -> Result<(), String> {
+pub fn force_quit_process(pid: u32, confirmation_token: Option<String>) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Force quit")?;
     debug3!("force_quit_process() called for PID: {}", pid);
+    crate::commands::confirmation::require_confirmation(
+        confirmation_token.as_deref(),
+        "force_quit",
+        &pid.to_string(),
+    )?;
 
     // Use kill -9 to force quit the process
     let output = Command::new("kill").arg("-9").arg(pid.to_string()).output();
@@ -2106,11 +2045,136 @@ pub fn force_quit_process(pid: u32) -> Result<(), String> {
     }
 }
 
+/// Newest 1s history sample (for exporters); `None` while history is empty or locked
+pub(crate) fn latest_history_point() -> Option<history::MetricPoint> {
+    METRICS_HISTORY.try_lock().ok()?.as_ref()?.latest().cloned()
+}
+
+/// Record per-process history and return the `limit` busiest processes, with their history.
+/// Call right after `refresh_processes`.
+fn top_processes_after_refresh(sys: &sysinfo::System, limit: usize) -> Vec<ProcessUsage> {
+    process_history::record(sys);
+    build_activity::record(sys);
+    // Collect ALL processes first (HashMap iteration order is undefined), then sort by CPU
+    // usage to get the actual top processes.
+    let mut processes: Vec<ProcessUsage> = sys
+        .processes()
+        .iter()
+        .map(|(pid, proc)| ProcessUsage {
+            name: proc.name().to_string_lossy().to_string(),
+            cpu: proc.cpu_usage(),
+            pid: pid.as_u32(),
+            history: Vec::new(),
+        })
+        .collect();
+    processes.sort_by(|a, b| b.cpu.total_cmp(&a.cpu));
+    processes.truncate(limit);
+    process_history::attach(&mut processes);
+    processes
+}
+
+/// Processes from the last `SYSTEM` refresh, busiest first (no refresh here; callers that
+/// need fresh numbers refresh processes themselves). Empty while `SYSTEM` is uninitialized.
+pub(crate) fn processes_by_cpu(limit: usize) -> Vec<ProcessUsage> {
+    let Ok(sys) = SYSTEM.lock() else {
+        return Vec::new();
+    };
+    let Some(sys) = sys.as_ref() else {
+        return Vec::new();
+    };
+    let mut processes: Vec<ProcessUsage> = sys
+        .processes()
+        .iter()
+        .map(|(pid, proc)| ProcessUsage {
+            name: proc.name().to_string_lossy().to_string(),
+            cpu: proc.cpu_usage(),
+            pid: pid.as_u32(),
+            history: Vec::new(),
+        })
+        .collect();
+    processes.sort_by(|a, b| b.cpu.total_cmp(&a.cpu));
+    processes.truncate(limit);
+    process_history::attach(&mut processes);
+    processes
+}
+
+/// CPU/memory history of a watched process (`watchedProcesses` in config.json)
+///
+/// # Arguments
+/// * `pid` - Process ID (see [`list_watched_processes`])
+/// * `time_range_seconds` - How far back to look; samples are kept for 48 hours
+#[tauri::command]
+pub fn get_process_history(
+    pid: u32,
+    time_range_seconds: u64,
+) -> Result<process_watch::ProcessHistoryResult, String> {
+    debug3!(
+        "get_process_history() called with pid={}, time_range_seconds={}",
+        pid,
+        time_range_seconds
+    );
+    process_watch::query(pid, time_range_seconds)
+}
+
+/// Minutes the fans were audible per local day (`fanAudibleRpm`), oldest first
+///
+/// # Arguments
+/// * `days` - Number of most recent days to return (default 30, up to 90 are kept)
+#[tauri::command]
+pub fn get_fan_audible_history(days: Option<usize>) -> Vec<fan_noise::FanAudibleDay> {
+    fan_noise::history(days.unwrap_or(30))
+}
+
+/// Every fan with its speed range and whether it is forced (empty on fanless Macs)
+#[tauri::command]
+pub async fn get_fan_control_status() -> Result<Vec<fan_control::FanStatus>, String> {
+    tokio::task::spawn_blocking(fan_control::status)
+        .await
+        .map_err(|e| format!("Fan read failed: {}", e))?
+}
+
+/// Force a fan to a target speed, or return it to automatic control
+///
+/// # Arguments
+/// * `fan` - Fan index (see [`get_fan_control_status`])
+/// * `rpm` - Target RPM, clamped to the fan's range; omit for automatic
+///
+/// Needs the privileged helper (`set_privileged_helper_installed`). Forced fans return to
+/// automatic when mac-stats quits.
+#[tauri::command]
+pub async fn set_fan_speed(
+    fan: u32,
+    rpm: Option<f32>,
+    confirmation_token: Option<String>,
+) -> Result<fan_control::FanStatus, String> {
+    crate::guest_mode::ensure_allowed("Changing fan speed")?;
+    crate::commands::confirmation::require_confirmation(
+        confirmation_token.as_deref(),
+        "fan_control",
+        &match rpm {
+            Some(rpm) => format!("fan {} {:.0} rpm", fan, rpm),
+            None => format!("fan {} auto", fan),
+        },
+    )?;
+    tokio::task::spawn_blocking(move || fan_control::set_fan_speed(fan, rpm))
+        .await
+        .map_err(|e| format!("Fan control failed: {}", e))?
+}
+
+/// Watched processes that have samples, most recently seen first
+#[tauri::command]
+pub fn list_watched_processes() -> Vec<process_watch::WatchedProcess> {
+    process_watch::processes()
+}
+
 /// Get metrics history for a given time range
 ///
 /// # Arguments
 /// * `time_range_seconds` - Time range to query: 300 (5m), 3600 (1h), 21600 (6h), 604800 (7d)
 /// * `max_display_points` - Optional max points for display width optimization
+/// * `metrics` - Optional metric names to return (see [`history::METRIC_NAMES`]); all when omitted
+/// * `aggregation` - `avg` (default), `min` or `max` for downsampled points; `max` keeps spikes
+///   visible on long ranges
 ///
 /// # Returns
 /// History query result with points and metadata
@@ -2118,18 +2182,63 @@ pub fn force_quit_process(pid: u32) -> Result<(), String> {
 pub fn get_metrics_history(
     time_range_seconds: u64,
     max_display_points: Option<usize>,
+    metrics: Option<Vec<String>>,
+    aggregation: Option<String>,
 ) -> Result<history::HistoryQueryResult, String> {
     debug3!(
-        "get_metrics_history() called with time_range_seconds={}, max_display_points={:?}",
+        "get_metrics_history() called with time_range_seconds={}, max_display_points={:?}, metrics={:?}, aggregation={:?}",
         time_range_seconds,
-        max_display_points
+        max_display_points,
+        metrics,
+        aggregation
     );
+    let metrics = metrics.as_deref();
+    let aggregation = match aggregation.as_deref() {
+        None => history::Aggregation::Avg,
+        Some(name) => history::Aggregation::parse(name).ok_or_else(|| {
+            format!(
+                "Unknown aggregation '{}' (use {})",
+                name,
+                history::Aggregation::NAMES.join(", ")
+            )
+        })?,
+    };
+
+    // Ranges past the in-memory tiers come from the SQLite store when it is enabled
+    if let Some(points) = history_store::query_long_range(time_range_seconds) {
+        let points = points?;
+        // Gaps live in the in-memory buffer (restored from the store at startup)
+        let now = chrono::Utc::now().timestamp();
+        let gaps = METRICS_HISTORY
+            .try_lock()
+            .ok()
+            .and_then(|h| {
+                h.as_ref()
+                    .map(|h| h.gaps_between(now - time_range_seconds as i64, now))
+            })
+            .unwrap_or_default();
+        let oldest = points.first().map(|p| p.timestamp);
+        let newest = points.last().map(|p| p.timestamp);
+        let points = match max_display_points {
+            Some(max_points) => history::HistoryBuffer::downsample_for_display(&points, max_points),
+            None => points,
+        };
+        let points = aggregation.apply(points);
+        return Ok(history::HistoryQueryResult {
+            points: history::select_metrics(&points, metrics)?,
+            time_range_seconds,
+            oldest_available_timestamp: oldest,
+            newest_available_timestamp: newest,
+            gaps,
+            aggregation,
+        });
+    }
 
     // Try to get history buffer with non-blocking lock
     match METRICS_HISTORY.try_lock() {
         Ok(history_opt) => {
             if let Some(history) = history_opt.as_ref() {
-                let points = history.query(time_range_seconds, max_display_points);
+                let points = history.query(time_range_seconds, max_display_points, aggregation);
                 let oldest = history.oldest_timestamp();
                 let now = std::time::SystemTime::now()
                     .duration_since(std::time::UNIX_EPOCH)
@@ -2144,10 +2253,12 @@ pub fn get_metrics_history(
                 );
 
                 Ok(history::HistoryQueryResult {
-                    points,
+                    points: history::select_metrics(&points, metrics)?,
                     time_range_seconds,
                     oldest_available_timestamp: oldest,
                     newest_available_timestamp: Some(now),
+                    gaps: history.gaps_between(now - time_range_seconds as i64, now),
+                    aggregation,
                 })
             } else {
                 debug3!("get_metrics_history: history buffer not initialized yet");
@@ -2156,6 +2267,8 @@ pub fn get_metrics_history(
                     time_range_seconds,
                     oldest_available_timestamp: None,
                     newest_available_timestamp: None,
+                    gaps: Vec::new(),
+                    aggregation,
                 })
             }
         }
@@ -2165,3 +2278,22 @@ pub fn get_metrics_history(
         }
     }
 }
+
+/// p50/p95/p99 plus min/avg/max of CPU usage, temperature and CPU/GPU power over the last
+/// `time_range_seconds` (same ranges as `get_metrics_history`).
+#[tauri::command]
+pub fn get_history_stats(time_range_seconds: u64) -> Result<history_stats::HistoryStats, String> {
+    let history = METRICS_HISTORY
+        .try_lock()
+        .map_err(|_| "History buffer temporarily unavailable".to_string())?;
+    let points = history
+        .as_ref()
+        .map(|h| h.query(time_range_seconds, None, history::Aggregation::Avg))
+        .unwrap_or_default();
+    debug3!(
+        "get_history_stats: {} points over {}s",
+        points.len(),
+        time_range_seconds
+    );
+    Ok(history_stats::compute(&points, time_range_seconds))
+}
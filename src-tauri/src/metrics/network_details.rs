@@ -0,0 +1,345 @@
+//! Network details for the network window (`get_network_details`, `get_network_history`)
+//!
+//! The update loop feeds the per-interface byte counters it already reads for history
+//! ([`record`], every 2s). From them this keeps, per interface: the current receive/transmit
+//! rate, bytes transferred since mac-stats started (counter resets are skipped, not
+//! subtracted) and the last [`HISTORY_SAMPLES`] rates for the live chart. Interfaces that never
+//! carried traffic this session (idle tunnels, AWDL, ...) are left out.
+//!
+//! Top talkers are the processes moving the most bytes right now, from one `nettop` delta
+//! sample (about a second), cached for [`TALKERS_TTL`].
+
+use std::collections::{BTreeMap, VecDeque};
+use std::process::Command;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+/// Chart points kept per interface (10 minutes at one sample per 2s)
+pub const HISTORY_SAMPLES: usize = 300;
+/// Processes listed as top talkers
+pub const TOP_TALKERS: usize = 8;
+/// How long a `nettop` sample is reused
+const TALKERS_TTL: Duration = Duration::from_secs(5);
+
+/// Cumulative byte counters of one interface
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterfaceTotals {
+    pub name: String,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+}
+
+/// One chart point: rates in bytes/s at `timestamp` (ms)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct RatePoint {
+    pub timestamp: i64,
+    pub rx: f64,
+    pub tx: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InterfaceDetails {
+    pub name: String,
+    /// Bytes per second over the last interval
+    pub rx_rate: f64,
+    pub tx_rate: f64,
+    /// Bytes since mac-stats started
+    pub session_rx: u64,
+    pub session_tx: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Talker {
+    pub name: String,
+    pub pid: u32,
+    /// Bytes per second
+    pub rx_rate: f64,
+    pub tx_rate: f64,
+}
+
+/// Result of `get_network_details`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NetworkDetails {
+    /// Busiest first (current rate, then session total)
+    pub interfaces: Vec<InterfaceDetails>,
+    pub total_rx_rate: f64,
+    pub total_tx_rate: f64,
+    pub session_rx: u64,
+    pub session_tx: u64,
+    /// Unix seconds of the first sample
+    pub session_started: Option<i64>,
+    pub top_talkers: Vec<Talker>,
+    /// Why `top_talkers` is empty, when `nettop` failed
+    pub top_talkers_error: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct Interface {
+    last: (u64, u64),
+    rate: (f64, f64),
+    session: (u64, u64),
+    history: VecDeque<RatePoint>,
+}
+
+/// Per-interface state between samples
+#[derive(Debug, Default)]
+pub struct NetworkTracker {
+    interfaces: BTreeMap<String, Interface>,
+    last_sample_ms: Option<i64>,
+    started_ms: Option<i64>,
+}
+
+impl NetworkTracker {
+    pub const fn new() -> Self {
+        Self {
+            interfaces: BTreeMap::new(),
+            last_sample_ms: None,
+            started_ms: None,
+        }
+    }
+
+    /// Add a sample of cumulative counters taken at `now_ms`. The first sample of an interface
+    /// only sets its baseline; a counter that went backwards counts as zero for that interval.
+    pub fn record(&mut self, totals: &[InterfaceTotals], now_ms: i64) {
+        let secs = self
+            .last_sample_ms
+            .map(|at| (now_ms - at) as f64 / 1000.0)
+            .filter(|s| *s > 0.0);
+        self.started_ms.get_or_insert(now_ms);
+        self.last_sample_ms = Some(now_ms);
+        for t in totals {
+            let known = self.interfaces.contains_key(&t.name);
+            let iface = self.interfaces.entry(t.name.clone()).or_default();
+            let delta = if known {
+                (
+                    t.rx_bytes.saturating_sub(iface.last.0),
+                    t.tx_bytes.saturating_sub(iface.last.1),
+                )
+            } else {
+                (0, 0)
+            };
+            iface.last = (t.rx_bytes, t.tx_bytes);
+            iface.session.0 += delta.0;
+            iface.session.1 += delta.1;
+            iface.rate = match secs {
+                Some(secs) if known => (delta.0 as f64 / secs, delta.1 as f64 / secs),
+                _ => (0.0, 0.0),
+            };
+            if known {
+                if iface.history.len() == HISTORY_SAMPLES {
+                    iface.history.pop_front();
+                }
+                iface.history.push_back(RatePoint {
+                    timestamp: now_ms,
+                    rx: iface.rate.0,
+                    tx: iface.rate.1,
+                });
+            }
+        }
+    }
+
+    fn active(&self) -> impl Iterator<Item = (&String, &Interface)> {
+        self.interfaces
+            .iter()
+            .filter(|(_, i)| i.session != (0, 0) || i.rate != (0.0, 0.0))
+    }
+
+    /// Interfaces and totals (without top talkers)
+    pub fn details(&self) -> NetworkDetails {
+        let mut interfaces: Vec<InterfaceDetails> = self
+            .active()
+            .map(|(name, i)| InterfaceDetails {
+                name: name.clone(),
+                rx_rate: i.rate.0,
+                tx_rate: i.rate.1,
+                session_rx: i.session.0,
+                session_tx: i.session.1,
+            })
+            .collect();
+        interfaces.sort_by(|a, b| {
+            let key = |d: &InterfaceDetails| (d.rx_rate + d.tx_rate, d.session_rx + d.session_tx);
+            let (ka, kb) = (key(a), key(b));
+            kb.0.total_cmp(&ka.0).then(kb.1.cmp(&ka.1))
+        });
+        NetworkDetails {
+            total_rx_rate: interfaces.iter().map(|i| i.rx_rate).sum(),
+            total_tx_rate: interfaces.iter().map(|i| i.tx_rate).sum(),
+            session_rx: interfaces.iter().map(|i| i.session_rx).sum(),
+            session_tx: interfaces.iter().map(|i| i.session_tx).sum(),
+            session_started: self.started_ms.map(|ms| ms / 1000),
+            interfaces,
+            ..Default::default()
+        }
+    }
+
+    /// Chart points per active interface, oldest first
+    pub fn history(&self) -> BTreeMap<String, Vec<RatePoint>> {
+        self.active()
+            .map(|(name, i)| (name.clone(), i.history.iter().copied().collect()))
+            .collect()
+    }
+}
+
+static TRACKER: Mutex<NetworkTracker> = Mutex::new(NetworkTracker::new());
+static TALKERS_CACHE: Mutex<Option<(Instant, Result<Vec<Talker>, String>)>> = Mutex::new(None);
+
+/// Called by the update loop with the counters of every non-loopback interface
+pub fn record(totals: &[InterfaceTotals]) {
+    if let Ok(mut tracker) = TRACKER.try_lock() {
+        tracker.record(totals, chrono::Utc::now().timestamp_millis());
+    }
+}
+
+/// Parse `nettop -P -d -L 2 -x -J bytes_in,bytes_out` CSV. Each sample starts with a header
+/// line (`,bytes_in,bytes_out,`); rows are `name.pid,in,out,`. With `-d` the last sample holds
+/// the bytes moved during the `interval_secs` between samples.
+pub fn parse_nettop(output: &str, interval_secs: f64) -> Vec<Talker> {
+    let mut sample: Vec<&str> = Vec::new();
+    for line in output.lines() {
+        if line.starts_with(',') {
+            sample.clear();
+        } else if !line.trim().is_empty() {
+            sample.push(line);
+        }
+    }
+    let mut talkers: Vec<Talker> = sample
+        .iter()
+        .filter_map(|line| {
+            let mut fields = line.split(',');
+            let (name, pid) = fields.next()?.rsplit_once('.')?;
+            let rx: u64 = fields.next()?.trim().parse().ok()?;
+            let tx: u64 = fields.next()?.trim().parse().ok()?;
+            Some(Talker {
+                name: name.to_string(),
+                pid: pid.parse().ok()?,
+                rx_rate: rx as f64 / interval_secs,
+                tx_rate: tx as f64 / interval_secs,
+            })
+        })
+        .filter(|t| t.rx_rate + t.tx_rate > 0.0)
+        .collect();
+    talkers.sort_by(|a, b| (b.rx_rate + b.tx_rate).total_cmp(&(a.rx_rate + a.tx_rate)));
+    talkers.truncate(TOP_TALKERS);
+    talkers
+}
+
+fn read_top_talkers() -> Result<Vec<Talker>, String> {
+    let out = Command::new("/usr/bin/nettop")
+        .args([
+            "-P",
+            "-d",
+            "-L",
+            "2",
+            "-s",
+            "1",
+            "-x",
+            "-J",
+            "bytes_in,bytes_out",
+        ])
+        .stderr(std::process::Stdio::null())
+        .output()
+        .map_err(|e| format!("nettop unavailable: {}", e))?;
+    if !out.status.success() {
+        return Err(format!("nettop exited with {}", out.status));
+    }
+    Ok(parse_nettop(&String::from_utf8_lossy(&out.stdout), 1.0))
+}
+
+/// Top talkers, sampled at most every [`TALKERS_TTL`] (blocks about a second when it samples)
+fn top_talkers() -> Result<Vec<Talker>, String> {
+    if let Ok(cache) = TALKERS_CACHE.lock() {
+        if let Some((at, talkers)) = cache.as_ref() {
+            if at.elapsed() < TALKERS_TTL {
+                return talkers.clone();
+            }
+        }
+    }
+    let talkers = read_top_talkers();
+    if let Ok(mut cache) = TALKERS_CACHE.lock() {
+        *cache = Some((Instant::now(), talkers.clone()));
+    }
+    talkers
+}
+
+/// Per-interface throughput, session totals and top talkers for the network window
+#[tauri::command]
+pub async fn get_network_details() -> Result<NetworkDetails, String> {
+    let mut details = TRACKER
+        .lock()
+        .map_err(|_| "Network details are unavailable".to_string())?
+        .details();
+    match tokio::task::spawn_blocking(top_talkers)
+        .await
+        .map_err(|e| e.to_string())?
+    {
+        Ok(talkers) => details.top_talkers = talkers,
+        Err(e) => details.top_talkers_error = Some(e),
+    }
+    Ok(details)
+}
+
+/// Recent rates per interface (bytes/s, last 10 minutes), or only `interface`
+#[tauri::command]
+pub fn get_network_history(
+    interface: Option<String>,
+) -> Result<BTreeMap<String, Vec<RatePoint>>, String> {
+    let mut history = TRACKER
+        .lock()
+        .map_err(|_| "Network history is unavailable".to_string())?
+        .history();
+    if let Some(name) = interface {
+        history.retain(|k, _| *k == name);
+    }
+    Ok(history)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn totals(rx: u64, tx: u64) -> Vec<InterfaceTotals> {
+        vec![
+            InterfaceTotals {
+                name: "en0".to_string(),
+                rx_bytes: rx,
+                tx_bytes: tx,
+            },
+            InterfaceTotals {
+                name: "utun3".to_string(),
+                rx_bytes: 0,
+                tx_bytes: 0,
+            },
+        ]
+    }
+
+    #[test]
+    fn tracks_rates_session_totals_and_talkers() {
+        let mut tracker = NetworkTracker::new();
+        tracker.record(&totals(10_000, 5_000), 0);
+        tracker.record(&totals(14_000, 6_000), 2_000);
+        // Counter reset (interface re-created): no negative traffic
+        tracker.record(&totals(1_000, 500), 4_000);
+        tracker.record(&totals(3_000, 500), 6_000);
+
+        let details = tracker.details();
+        assert_eq!(details.interfaces.len(), 1);
+        let en0 = &details.interfaces[0];
+        assert_eq!((en0.rx_rate, en0.tx_rate), (1_000.0, 0.0));
+        assert_eq!((en0.session_rx, en0.session_tx), (6_000, 1_000));
+        assert_eq!(details.session_started, Some(0));
+        let history = tracker.history();
+        assert_eq!(history["en0"].len(), 3);
+        assert_eq!(history["en0"][0].rx, 2_000.0);
+
+        let nettop = ",bytes_in,bytes_out,\nSafari.42,900,100,\n\
+                      ,bytes_in,bytes_out,\nSafari.42,300,100,\nmDNSResponder.7,0,0,\n\
+                      Google Chrome He.311,1000,0,\n";
+        let talkers = parse_nettop(nettop, 1.0);
+        assert_eq!(talkers.len(), 2);
+        assert_eq!(talkers[0].name, "Google Chrome He");
+        assert_eq!(talkers[0].pid, 311);
+        assert_eq!((talkers[1].rx_rate, talkers[1].tx_rate), (300.0, 100.0));
+    }
+}
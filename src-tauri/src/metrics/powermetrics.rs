@@ -0,0 +1,392 @@
+//! Optional `powermetrics` provider
+//!
+//! IOReport power and frequency need no privileges but are derived from energy and residency
+//! deltas, and Intel Macs have neither. `powermetrics` needs root; with the `powermetrics`
+//! setting a background thread takes samples from one of:
+//! - `"helper"`: the privileged helper (`privileged_helper`), one sample per XPC request
+//! - `"sudo"`: `sudo -n /usr/bin/powermetrics` streaming `-f plist` (needs a sudoers rule
+//!   allowing it without a password)
+//! - an absolute path: a helper tool that runs powermetrics with the arguments it is given
+//!
+//! CPU / GPU / ANE power and cluster frequencies go into the same snapshot fields as the
+//! IOReport path. While samples arrive the sampler's IOReport power and frequency reads stand
+//! by ([`is_running`]).
+//!
+//! When the source fails (helper not installed, wrong sudoers rule, command exits) the
+//! provider logs why and IOReport takes over again; the helper is retried every
+//! [`HELPER_RETRY_SECS`].
+
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::config::Config;
+use crate::state::{update_snapshot, Reading};
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+const POWERMETRICS: &str = "/usr/bin/powermetrics";
+/// `-i`: one sample every 2s (the IOReport power task reads every 5s)
+const SAMPLE_INTERVAL_MS: u32 = 2000;
+/// ANE readings older than this are not shown
+const ANE_MAX_AGE_SECS: u64 = 10;
+/// Wait between requests while the privileged helper is unavailable
+const HELPER_RETRY_SECS: u64 = 30;
+
+/// Samples are arriving; IOReport power / frequency reads stand by
+static RUNNING: AtomicBool = AtomicBool::new(false);
+/// The streaming process, killed by [`stop`]
+static CHILD: Mutex<Option<Child>> = Mutex::new(None);
+/// Set by [`stop`]; ends the helper polling thread
+static STOP: AtomicBool = AtomicBool::new(false);
+
+/// One `powermetrics -f plist` sample (only the fields used; Apple Silicon reports
+/// `*_power` in mW, Intel `package_watts`)
+#[derive(Debug, Default, Deserialize)]
+struct Sample {
+    #[serde(default)]
+    processor: Processor,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Processor {
+    cpu_power: Option<f64>,
+    gpu_power: Option<f64>,
+    ane_power: Option<f64>,
+    package_watts: Option<f64>,
+    /// Intel: average CPU frequency
+    freq_hz: Option<f64>,
+    #[serde(default)]
+    clusters: Vec<Cluster>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Cluster {
+    /// `E-Cluster`, `P0-Cluster`, …
+    name: String,
+    freq_hz: f64,
+}
+
+/// Watts and GHz taken from one sample; 0.0 when not reported
+#[derive(Debug, Default, PartialEq)]
+struct Readings {
+    cpu_power: f32,
+    gpu_power: f32,
+    ane_power: f32,
+    frequency: f32,
+    p_core_frequency: f32,
+    e_core_frequency: f32,
+}
+
+impl Sample {
+    fn readings(&self) -> Readings {
+        let p = &self.processor;
+        let watts = |mw: Option<f64>| mw.map_or(0.0, |mw| (mw / 1000.0) as f32);
+        let ghz = |hz: f64| (hz / 1_000_000_000.0) as f32;
+        let average = |clusters: Vec<f64>| {
+            (!clusters.is_empty())
+                .then(|| ghz(clusters.iter().sum::<f64>() / clusters.len() as f64))
+        };
+        let cluster_freqs = |prefix: &str| {
+            p.clusters
+                .iter()
+                .filter(|c| c.name.starts_with(prefix) && c.freq_hz > 0.0)
+                .map(|c| c.freq_hz)
+                .collect::<Vec<_>>()
+        };
+        let p_core = average(cluster_freqs("P")).unwrap_or(0.0);
+        let e_core = average(cluster_freqs("E")).unwrap_or(0.0);
+        let frequency = average(cluster_freqs(""))
+            .or_else(|| p.freq_hz.map(ghz))
+            .unwrap_or(0.0);
+        Readings {
+            cpu_power: match p.cpu_power {
+                Some(_) => watts(p.cpu_power),
+                None => p.package_watts.unwrap_or(0.0) as f32,
+            },
+            gpu_power: watts(p.gpu_power),
+            ane_power: watts(p.ane_power),
+            frequency,
+            p_core_frequency: p_core,
+            e_core_frequency: e_core,
+        }
+    }
+}
+
+/// Whether powermetrics samples are arriving
+pub fn is_running() -> bool {
+    RUNNING.load(Ordering::Relaxed)
+}
+
+/// Latest Neural Engine power in watts; 0.0 when not recent (or without the provider)
+pub fn ane_power() -> f32 {
+    crate::state::snapshot()
+        .ane_power
+        .as_ref()
+        .filter(|r| r.age().as_secs() < ANE_MAX_AGE_SECS)
+        .map_or(0.0, |r| r.value)
+}
+
+fn command(provider: &str) -> Command {
+    let mut cmd = if provider.eq_ignore_ascii_case("sudo") {
+        let mut cmd = Command::new("/usr/bin/sudo");
+        cmd.args(["-n", POWERMETRICS]);
+        cmd
+    } else {
+        Command::new(provider)
+    };
+    cmd.args(["--samplers", "cpu_power,gpu_power", "-f", "plist", "-i"])
+        .arg(SAMPLE_INTERVAL_MS.to_string());
+    cmd
+}
+
+/// Start streaming when `powermetrics` is configured (startup)
+pub fn spawn_if_enabled() {
+    let Some(provider) = Config::powermetrics_provider() else {
+        return;
+    };
+    if provider.eq_ignore_ascii_case("helper") {
+        spawn_helper_poll();
+        return;
+    }
+    if !provider.eq_ignore_ascii_case("sudo") && !std::path::Path::new(&provider).is_absolute() {
+        tracing::warn!(
+            target: "mac_stats::metrics",
+            "powermetrics provider off: {:?} is not \"helper\", \"sudo\" or an absolute helper path",
+            provider
+        );
+        return;
+    }
+    let mut child = match command(&provider)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            tracing::warn!(target: "mac_stats::metrics", "powermetrics provider could not start ({}): {}", provider, e);
+            return;
+        }
+    };
+    let (Some(stdout), Some(stderr)) = (child.stdout.take(), child.stderr.take()) else {
+        return;
+    };
+    if let Ok(mut slot) = CHILD.lock() {
+        *slot = Some(child);
+    }
+    tracing::info!(target: "mac_stats::metrics", "powermetrics provider started ({})", provider);
+    std::thread::spawn(move || {
+        stream(stdout, publish);
+        RUNNING.store(false, Ordering::Relaxed);
+        let mut reason = String::new();
+        let _ = BufReader::new(stderr)
+            .take(1024)
+            .read_to_string(&mut reason);
+        // [`stop`] already took the process on quit
+        let Some(mut child) = CHILD.lock().ok().and_then(|mut c| c.take()) else {
+            return;
+        };
+        let status = child
+            .wait()
+            .map_or("unknown".to_string(), |s| s.to_string());
+        tracing::warn!(
+            target: "mac_stats::metrics",
+            "powermetrics provider ended ({}): {}; using IOReport",
+            status,
+            reason.lines().next().unwrap_or("no output")
+        );
+    });
+}
+
+/// Request samples from the privileged helper until [`stop`]
+fn spawn_helper_poll() {
+    tracing::info!(target: "mac_stats::metrics", "powermetrics provider started (privileged helper)");
+    std::thread::spawn(|| {
+        let mut failing = false;
+        while !STOP.load(Ordering::Relaxed) {
+            // Blocks for the sample interval
+            match crate::privileged_helper::powermetrics_sample(SAMPLE_INTERVAL_MS) {
+                Ok(doc) => {
+                    if failing {
+                        tracing::info!(target: "mac_stats::metrics", "powermetrics provider: privileged helper is back");
+                        failing = false;
+                    }
+                    if let Some(r) = parse(&doc) {
+                        publish(r);
+                    }
+                }
+                Err(e) => {
+                    RUNNING.store(false, Ordering::Relaxed);
+                    if !failing {
+                        tracing::warn!(
+                            target: "mac_stats::metrics",
+                            "powermetrics provider: privileged helper unavailable ({}); using IOReport",
+                            e
+                        );
+                        failing = true;
+                    }
+                    std::thread::sleep(Duration::from_secs(HELPER_RETRY_SECS));
+                }
+            }
+        }
+    });
+}
+
+/// Read NUL-separated plist samples until the process ends
+fn stream(stdout: impl Read, mut on_sample: impl FnMut(Readings)) {
+    let mut reader = BufReader::new(stdout);
+    let mut doc = Vec::new();
+    loop {
+        doc.clear();
+        match reader.read_until(0, &mut doc) {
+            Ok(0) | Err(_) => return,
+            Ok(_) => {}
+        }
+        if let Some(r) = parse(&doc) {
+            on_sample(r);
+        }
+    }
+}
+
+/// One plist sample, ignoring surrounding whitespace and NUL separators; `None` when empty or
+/// unreadable
+fn parse(doc: &[u8]) -> Option<Readings> {
+    let start = doc
+        .iter()
+        .position(|b| !b.is_ascii_whitespace() && *b != 0)
+        .unwrap_or(doc.len());
+    let end = doc.iter().rposition(|b| *b != 0).map_or(start, |i| i + 1);
+    if start >= end {
+        return None;
+    }
+    plist::from_bytes::<Sample>(&doc[start..end])
+        .map(|sample| sample.readings())
+        .map_err(|e| debug2!("powermetrics: unreadable sample: {}", e))
+        .ok()
+}
+
+/// Write one sample into the snapshot (as the IOReport path does)
+fn publish(r: Readings) {
+    if r.cpu_power <= 0.0 && r.frequency <= 0.0 {
+        return;
+    }
+    update_snapshot(|s| {
+        if r.cpu_power > 0.0 {
+            s.power = Some(Reading::new((r.cpu_power, r.gpu_power)));
+            s.ane_power = Some(Reading::new(r.ane_power));
+        }
+        if r.frequency > 0.0 {
+            s.frequency = Some(Reading::new(r.frequency));
+        }
+        if r.p_core_frequency > 0.0 {
+            s.p_core_frequency = Some(Reading::new(r.p_core_frequency));
+        }
+        if r.e_core_frequency > 0.0 {
+            s.e_core_frequency = Some(Reading::new(r.e_core_frequency));
+        }
+    });
+    if !RUNNING.swap(true, Ordering::Relaxed) {
+        debug1!("powermetrics provider: first sample {:?}", r);
+        let power = r.cpu_power > 0.0;
+        crate::capabilities::update(|c| {
+            c.cpu_power |= power;
+            c.gpu_power |= power;
+            c.frequency |= r.frequency > 0.0;
+        });
+    }
+    debug3!(
+        "powermetrics: CPU={:.2}W GPU={:.2}W ANE={:.2}W freq={:.2} GHz",
+        r.cpu_power,
+        r.gpu_power,
+        r.ane_power,
+        r.frequency
+    );
+}
+
+/// Kill the streaming process or end helper polling (`RunEvent::Exit`)
+pub fn stop() {
+    STOP.store(true, Ordering::Relaxed);
+    if let Some(mut child) = CHILD.lock().ok().and_then(|mut c| c.take()) {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+    RUNNING.store(false, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const APPLE_SILICON_SAMPLE: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+  <key>elapsed_ns</key><integer>2004000000</integer>
+  <key>processor</key>
+  <dict>
+    <key>clusters</key>
+    <array>
+      <dict><key>name</key><string>E-Cluster</string><key>freq_hz</key><real>1200000000</real></dict>
+      <dict><key>name</key><string>P0-Cluster</string><key>freq_hz</key><real>3000000000</real></dict>
+      <dict><key>name</key><string>P1-Cluster</string><key>freq_hz</key><real>2000000000</real></dict>
+    </array>
+    <key>cpu_power</key><real>1530.5</real>
+    <key>gpu_power</key><real>250</real>
+    <key>ane_power</key><real>0</real>
+  </dict>
+</dict>
+</plist>"#;
+
+    #[test]
+    fn reads_power_in_watts_and_cluster_frequencies() {
+        let sample: Sample = plist::from_bytes(APPLE_SILICON_SAMPLE.as_bytes()).unwrap();
+        let r = sample.readings();
+        assert!((r.cpu_power - 1.5305).abs() < 1e-4);
+        assert_eq!(r.gpu_power, 0.25);
+        assert_eq!(r.ane_power, 0.0);
+        assert_eq!(r.p_core_frequency, 2.5);
+        assert_eq!(r.e_core_frequency, 1.2);
+        assert!((r.frequency - 2.0666).abs() < 1e-3);
+    }
+
+    #[test]
+    fn intel_sample_uses_package_watts_and_average_frequency() {
+        let sample = Sample {
+            processor: Processor {
+                package_watts: Some(12.5),
+                freq_hz: Some(2_600_000_000.0),
+                ..Default::default()
+            },
+        };
+        let r = sample.readings();
+        assert_eq!(r.cpu_power, 12.5);
+        assert_eq!(r.frequency, 2.6);
+        assert_eq!(r.p_core_frequency, 0.0);
+    }
+
+    #[test]
+    fn stream_splits_on_nul() {
+        let mut input = APPLE_SILICON_SAMPLE.as_bytes().to_vec();
+        input.push(0);
+        input.extend_from_slice(b"\n");
+        input.extend_from_slice(APPLE_SILICON_SAMPLE.as_bytes());
+        input.push(0);
+        let mut samples = Vec::new();
+        stream(input.as_slice(), |r| samples.push(r));
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[1].gpu_power, 0.25);
+    }
+
+    #[test]
+    fn parse_ignores_trailing_nul_and_blank_documents() {
+        let mut doc = APPLE_SILICON_SAMPLE.as_bytes().to_vec();
+        doc.extend_from_slice(b"\n\0");
+        assert_eq!(parse(&doc).map(|r| r.gpu_power), Some(0.25));
+        assert_eq!(parse(b"\n\0"), None);
+    }
+}
@@ -0,0 +1,114 @@
+//! Short per-process CPU history for the process list sparklines
+//!
+//! Every process refresh records each PID's CPU usage; samples older than
+//! [`PROCESS_HISTORY_WINDOW`] are dropped, as are PIDs that have not been seen for that long
+//! (exited processes). Listed processes carry their samples in `ProcessUsage::history`, so a
+//! row can draw a sparkline without another round-trip. Samples only accrue while processes
+//! are being refreshed (CPU window open or local API polling).
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use super::ProcessUsage;
+use crate::state::PROCESS_HISTORY;
+
+/// How far back sparklines reach
+pub const PROCESS_HISTORY_WINDOW: Duration = Duration::from_secs(60);
+/// Cap per PID regardless of refresh rate
+const MAX_SAMPLES_PER_PROCESS: usize = 60;
+
+#[derive(Default)]
+pub struct ProcessHistory {
+    samples: HashMap<u32, VecDeque<(Instant, f32)>>,
+}
+
+impl ProcessHistory {
+    pub fn record(&mut self, usage: impl IntoIterator<Item = (u32, f32)>, now: Instant) {
+        for (pid, cpu) in usage {
+            let samples = self.samples.entry(pid).or_default();
+            if samples.len() >= MAX_SAMPLES_PER_PROCESS {
+                samples.pop_front();
+            }
+            samples.push_back((now, cpu));
+        }
+        self.samples.retain(|_, samples| {
+            while samples
+                .front()
+                .is_some_and(|(t, _)| now.saturating_duration_since(*t) > PROCESS_HISTORY_WINDOW)
+            {
+                samples.pop_front();
+            }
+            !samples.is_empty()
+        });
+    }
+
+    /// CPU samples for `pid` within the window, oldest first
+    pub fn usage(&self, pid: u32) -> Vec<f32> {
+        self.samples
+            .get(&pid)
+            .map(|samples| samples.iter().map(|(_, cpu)| *cpu).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Record every process's CPU usage; call right after `refresh_processes`.
+pub(crate) fn record(sys: &sysinfo::System) {
+    let Ok(mut history) = PROCESS_HISTORY.try_lock() else {
+        return;
+    };
+    history.get_or_insert_with(ProcessHistory::default).record(
+        sys.processes()
+            .iter()
+            .map(|(pid, proc)| (pid.as_u32(), proc.cpu_usage())),
+        Instant::now(),
+    );
+}
+
+/// Drop all samples (memory pressure)
+pub(crate) fn clear() {
+    if let Ok(mut history) = PROCESS_HISTORY.lock() {
+        *history = None;
+    }
+}
+
+/// Fill `history` on listed processes
+pub(crate) fn attach(processes: &mut [ProcessUsage]) {
+    let Ok(history) = PROCESS_HISTORY.try_lock() else {
+        return;
+    };
+    if let Some(history) = history.as_ref() {
+        for p in processes {
+            p.history = history.usage(p.pid);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_last_minute_and_forgets_exited_processes() {
+        let start = Instant::now();
+        let mut history = ProcessHistory::default();
+        history.record([(1, 10.0), (2, 50.0)], start);
+        history.record([(1, 20.0)], start + Duration::from_secs(30));
+        history.record([(1, 30.0)], start + Duration::from_secs(61));
+
+        assert_eq!(history.usage(1), vec![20.0, 30.0]);
+        assert!(history.usage(2).is_empty());
+        assert!(!history.samples.contains_key(&2));
+    }
+
+    #[test]
+    fn caps_samples_per_process() {
+        let start = Instant::now();
+        let mut history = ProcessHistory::default();
+        for i in 0..100 {
+            history.record([(7, i as f32)], start + Duration::from_millis(i * 100));
+        }
+        let usage = history.usage(7);
+        assert_eq!(usage.len(), MAX_SAMPLES_PER_PROCESS);
+        assert_eq!(usage.last(), Some(&99.0));
+    }
+}
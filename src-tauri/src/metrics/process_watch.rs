@@ -0,0 +1,289 @@
+//! Opt-in CPU/memory history for watched processes
+//!
+//! `watchedProcesses` in config.json lists process names (case-insensitive) and/or PIDs. A
+//! collector task (see `background`) samples the matching processes every [`SAMPLE_INTERVAL`],
+//! whether or not a window is open, and keeps [`RETENTION_SECS`] of samples per PID in memory. That is enough for
+//! `get_process_history` to show which app was behind last night's CPU spike. Nothing runs while
+//! the list is empty.
+
+use std::collections::{HashMap, VecDeque};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::state::{SYSTEM, WATCHED_PROCESS_HISTORY};
+
+/// How often watched processes are sampled (CPU is averaged over the interval)
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(60);
+/// How long samples are kept
+pub const RETENTION_SECS: i64 = 48 * 3600;
+/// Cap on tracked PIDs, so a broad name (e.g. `Helper`) cannot grow memory unbounded
+const MAX_WATCHED_PIDS: usize = 64;
+
+/// One entry of `watchedProcesses`: a PID or a process name
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatchTarget {
+    Pid(u32),
+    Name(String),
+}
+
+impl WatchTarget {
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if s.is_empty() {
+            return None;
+        }
+        Some(match s.parse::<u32>() {
+            Ok(pid) => WatchTarget::Pid(pid),
+            Err(_) => WatchTarget::Name(s.to_string()),
+        })
+    }
+
+    pub fn matches(&self, pid: u32, name: &str) -> bool {
+        match self {
+            WatchTarget::Pid(p) => *p == pid,
+            WatchTarget::Name(n) => n.eq_ignore_ascii_case(name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ProcessSample {
+    pub timestamp: i64,
+    /// Percent of one core (can exceed 100 for multi-threaded processes)
+    pub cpu: f32,
+    pub memory_bytes: u64,
+}
+
+/// Result of `get_process_history`
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessHistoryResult {
+    pub pid: u32,
+    pub name: String,
+    pub time_range_seconds: u64,
+    pub points: Vec<ProcessSample>,
+}
+
+/// A watched process as listed by `list_watched_processes`
+#[derive(Debug, Clone, Serialize)]
+pub struct WatchedProcess {
+    pub pid: u32,
+    pub name: String,
+    pub first_seen: i64,
+    pub last_seen: i64,
+    pub peak_cpu: f32,
+}
+
+struct Series {
+    name: String,
+    samples: VecDeque<ProcessSample>,
+}
+
+#[derive(Default)]
+pub struct WatchedHistory {
+    series: HashMap<u32, Series>,
+}
+
+impl WatchedHistory {
+    /// Add one sample per process and drop samples older than [`RETENTION_SECS`]
+    pub fn record(
+        &mut self,
+        samples: impl IntoIterator<Item = (u32, String, ProcessSample)>,
+        now: i64,
+    ) {
+        for (pid, name, sample) in samples {
+            if !self.series.contains_key(&pid) && self.series.len() >= MAX_WATCHED_PIDS {
+                continue;
+            }
+            let series = self.series.entry(pid).or_insert_with(|| Series {
+                name: name.clone(),
+                samples: VecDeque::new(),
+            });
+            // PIDs are reused; a different name starts a fresh series
+            if series.name != name {
+                series.name = name;
+                series.samples.clear();
+            }
+            series.samples.push_back(sample);
+        }
+        let cutoff = now - RETENTION_SECS;
+        self.series.retain(|_, series| {
+            while series.samples.front().is_some_and(|s| s.timestamp < cutoff) {
+                series.samples.pop_front();
+            }
+            !series.samples.is_empty()
+        });
+    }
+
+    /// Samples for `pid` in the last `time_range_seconds`, oldest first
+    pub fn query(
+        &self,
+        pid: u32,
+        time_range_seconds: u64,
+        now: i64,
+    ) -> Option<ProcessHistoryResult> {
+        let series = self.series.get(&pid)?;
+        let start = now - time_range_seconds.min(RETENTION_SECS as u64) as i64;
+        Some(ProcessHistoryResult {
+            pid,
+            name: series.name.clone(),
+            time_range_seconds,
+            points: series
+                .samples
+                .iter()
+                .filter(|s| s.timestamp >= start)
+                .copied()
+                .collect(),
+        })
+    }
+
+    /// Tracked processes, most recently seen first
+    pub fn processes(&self) -> Vec<WatchedProcess> {
+        let mut processes: Vec<WatchedProcess> = self
+            .series
+            .iter()
+            .filter_map(|(pid, series)| {
+                Some(WatchedProcess {
+                    pid: *pid,
+                    name: series.name.clone(),
+                    first_seen: series.samples.front()?.timestamp,
+                    last_seen: series.samples.back()?.timestamp,
+                    peak_cpu: series.samples.iter().map(|s| s.cpu).fold(0.0, f32::max),
+                })
+            })
+            .collect();
+        processes.sort_by(|a, b| b.last_seen.cmp(&a.last_seen).then(a.pid.cmp(&b.pid)));
+        processes
+    }
+}
+
+/// Configured watch targets (`watchedProcesses`)
+pub(crate) fn targets() -> Vec<WatchTarget> {
+    Config::watched_processes()
+        .iter()
+        .filter_map(|s| WatchTarget::parse(s))
+        .collect()
+}
+
+/// Refresh processes and record the ones matching `targets`
+fn sample(targets: &[WatchTarget]) {
+    // Lock order: SYSTEM, then WATCHED_PROCESS_HISTORY; collect first to release SYSTEM early.
+    let matched: Vec<(u32, String, f32, u64)> = {
+        let Ok(mut sys) = SYSTEM.lock() else {
+            return;
+        };
+        let sys = sys.get_or_insert_with(sysinfo::System::new);
+        sys.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+        sys.processes()
+            .iter()
+            .filter_map(|(pid, proc)| {
+                let pid = pid.as_u32();
+                let name = proc.name().to_string_lossy().to_string();
+                targets
+                    .iter()
+                    .any(|t| t.matches(pid, &name))
+                    .then(|| (pid, name, proc.cpu_usage(), proc.memory()))
+            })
+            .collect()
+    };
+    let now = chrono::Utc::now().timestamp();
+    if let Ok(mut history) = WATCHED_PROCESS_HISTORY.lock() {
+        history.get_or_insert_with(WatchedHistory::default).record(
+            matched.into_iter().map(|(pid, name, cpu, memory_bytes)| {
+                (
+                    pid,
+                    name,
+                    ProcessSample {
+                        timestamp: now,
+                        cpu,
+                        memory_bytes,
+                    },
+                )
+            }),
+            now,
+        );
+    }
+}
+
+/// History for `pid`; errors when watching is off or the PID has no samples
+pub(crate) fn query(pid: u32, time_range_seconds: u64) -> Result<ProcessHistoryResult, String> {
+    if targets().is_empty() {
+        return Err("Process watching is off (set watchedProcesses in config.json)".to_string());
+    }
+    let history = WATCHED_PROCESS_HISTORY
+        .lock()
+        .map_err(|_| "Process history is unavailable".to_string())?;
+    history
+        .as_ref()
+        .and_then(|h| h.query(pid, time_range_seconds, chrono::Utc::now().timestamp()))
+        .ok_or_else(|| format!("No history for PID {} (not watched or not seen yet)", pid))
+}
+
+/// Processes that currently have samples
+pub(crate) fn processes() -> Vec<WatchedProcess> {
+    WATCHED_PROCESS_HISTORY
+        .lock()
+        .ok()
+        .and_then(|h| h.as_ref().map(WatchedHistory::processes))
+        .unwrap_or_default()
+}
+
+/// Start the sampling task when `watchedProcesses` is set. The list is re-read every interval,
+/// so edits apply without a restart once watching is on.
+pub fn spawn_process_watch_if_enabled() {
+    let initial = targets();
+    if initial.is_empty() {
+        return;
+    }
+    tracing::info!(
+        "Process watch: sampling {} target(s) every {}s",
+        initial.len(),
+        SAMPLE_INTERVAL.as_secs()
+    );
+    crate::background::every("process_watch", SAMPLE_INTERVAL, || {
+        if !super::low_interference::is_active() {
+            sample(&targets());
+        }
+        true
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(timestamp: i64, cpu: f32) -> ProcessSample {
+        ProcessSample {
+            timestamp,
+            cpu,
+            memory_bytes: 1024,
+        }
+    }
+
+    #[test]
+    fn keeps_watched_samples_until_retention() {
+        assert_eq!(WatchTarget::parse(" 42 "), Some(WatchTarget::Pid(42)));
+        assert!(WatchTarget::parse("Safari").unwrap().matches(7, "safari"));
+        assert_eq!(WatchTarget::parse(" "), None);
+
+        let mut h = WatchedHistory::default();
+        let t0 = 1_700_000_000;
+        h.record([(42, "node".to_string(), at(t0, 180.0))], t0);
+        h.record([(42, "node".to_string(), at(t0 + 60, 5.0))], t0 + 60);
+        let r = h.query(42, 30, t0 + 60).unwrap();
+        assert_eq!(r.name, "node");
+        assert_eq!(r.points, vec![at(t0 + 60, 5.0)]);
+        assert_eq!(h.processes()[0].peak_cpu, 180.0);
+
+        // A reused PID with another name starts over
+        h.record([(42, "python3".to_string(), at(t0 + 120, 1.0))], t0 + 120);
+        assert_eq!(h.query(42, 3600, t0 + 120).unwrap().points.len(), 1);
+
+        // Samples past retention are dropped, along with the PID
+        h.record([], t0 + 121 + RETENTION_SECS);
+        assert!(h
+            .query(42, u32::MAX as u64, t0 + 121 + RETENTION_SECS)
+            .is_none());
+    }
+}
@@ -0,0 +1,362 @@
+//! Multi-machine dashboard data (`remoteMachines`)
+//!
+//! Every Mac running mac-stats with the local API on serves `GET /api/v1/summary`: host name,
+//! usage, temperature, battery and its alert state ([`MachineSummary`]), and streams it as
+//! Server-Sent Events from `GET /api/v1/summary/stream`. The dashboard window shows this Mac
+//! plus each configured remote machine: `get_dashboard` starts one subscriber per machine,
+//! which keeps that stream open, caches the latest summary and emits `dashboard-updated`; a
+//! dropped stream marks the machine offline and reconnects with backoff. Subscribers stop once
+//! the machine leaves `remoteMachines` or nobody has asked for the dashboard in
+//! [`IDLE_TIMEOUT`].
+//!
+//! The API only listens on loopback, so remote machines are reached through a tunnel or reverse
+//! proxy that forwards to their `127.0.0.1:8787`, e.g. `ssh -N -L 8788:127.0.0.1:8787
+//! studio.local` and `{"name": "Studio", "url": "http://127.0.0.1:8788"}`. Their bearer token
+//! is read from the env / `.config.env` key named by `tokenKey`, never from config.json.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+
+use crate::config::Config;
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+/// Per-machine connect timeout; one slow machine must not hold up the grid
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+/// A stream without an event for this long counts as dropped (the server sends one every 2s)
+const STALE_AFTER: Duration = Duration::from_secs(10);
+/// Reconnect delay after a failure, doubling up to [`MAX_RETRY_DELAY`]
+const RETRY_DELAY: Duration = Duration::from_secs(2);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+/// Subscribers stop when `get_dashboard` has not been called for this long (window closed)
+const IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Latest status per remote machine URL, written by the subscribers
+static STATUSES: Mutex<Option<HashMap<String, MachineStatus>>> = Mutex::new(None);
+/// Machine URLs with a running subscriber
+static SUBSCRIBED: Mutex<Option<HashSet<String>>> = Mutex::new(None);
+static LAST_REQUESTED: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// One `remoteMachines` entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemoteMachine {
+    pub name: String,
+    /// Base URL without a trailing slash, e.g. `http://127.0.0.1:8788`
+    pub url: String,
+    /// Env / `.config.env` key holding the machine's `MAC_STATS_API_TOKEN`
+    pub token_key: Option<String>,
+}
+
+/// `remoteMachines`: `[{"name", "url", "tokenKey"}]`; entries without an http(s) URL are skipped
+pub fn parse_machines(value: &serde_json::Value) -> Vec<RemoteMachine> {
+    value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let url = entry.get("url")?.as_str()?.trim().trim_end_matches('/');
+            if !(url.starts_with("http://") || url.starts_with("https://")) {
+                tracing::warn!("remoteMachines: '{}' is not an http(s) URL; skipped", url);
+                return None;
+            }
+            let name = entry
+                .get("name")
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .unwrap_or(url);
+            let token_key = entry
+                .get("tokenKey")
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string);
+            Some(RemoteMachine {
+                name: name.to_string(),
+                url: url.to_string(),
+                token_key,
+            })
+        })
+        .collect()
+}
+
+/// Key metrics and alert state of one machine (`GET /api/v1/summary`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MachineSummary {
+    pub host: String,
+    pub version: String,
+    pub cpu: f32,
+    pub gpu: f32,
+    pub ram: f32,
+    pub disk: f32,
+    /// °C; None when not readable
+    pub temperature: Option<f32>,
+    /// Percent; None without a battery
+    pub battery: Option<f32>,
+    pub uptime_secs: u64,
+    /// Metrics with a recent menu bar alert (`alertRules`)
+    pub alerting: Vec<String>,
+    /// Website / social monitors currently down
+    pub monitors_down: Vec<String>,
+}
+
+/// This Mac's summary, from the cached metrics (no sampling)
+pub fn local_summary() -> MachineSummary {
+    let metrics = super::get_metrics();
+    let cpu = super::get_cpu_details();
+    MachineSummary {
+        host: sysinfo::System::host_name().unwrap_or_default(),
+        version: super::get_app_version(),
+        cpu: metrics.cpu,
+        gpu: metrics.gpu,
+        ram: metrics.ram,
+        disk: metrics.disk,
+        temperature: (cpu.can_read_temperature && cpu.temperature > 0.0).then_some(cpu.temperature),
+        battery: cpu.has_battery.then_some(cpu.battery_level),
+        uptime_secs: cpu.uptime_secs,
+        alerting: crate::alerts::channels::menu_bar_alert_metrics(),
+        monitors_down: crate::commands::monitors::get_monitor_statuses_snapshot()
+            .into_iter()
+            .filter(|(_, status)| !status.is_up)
+            .map(|(id, _)| id)
+            .collect(),
+    }
+}
+
+/// One dashboard tile
+#[derive(Debug, Clone, Serialize)]
+pub struct MachineStatus {
+    pub name: String,
+    /// Empty for this Mac
+    pub url: String,
+    pub online: bool,
+    pub error: Option<String>,
+    pub latency_ms: Option<u64>,
+    pub summary: Option<MachineSummary>,
+}
+
+impl MachineStatus {
+    fn offline(machine: &RemoteMachine, error: &str) -> Self {
+        Self {
+            name: machine.name.clone(),
+            url: machine.url.clone(),
+            online: false,
+            error: Some(error.to_string()),
+            latency_ms: None,
+            summary: None,
+        }
+    }
+}
+
+/// Remove complete events from `buf` and return their `data` payloads (multi-line `data:`
+/// fields joined with `\n`; comments and other fields ignored)
+fn drain_events(buf: &mut Vec<u8>) -> Vec<String> {
+    let mut events = Vec::new();
+    while let Some(end) = buf.windows(2).position(|w| w == b"\n\n") {
+        let event: Vec<u8> = buf.drain(..end + 2).collect();
+        let text = String::from_utf8_lossy(&event).into_owned();
+        let payload: Vec<&str> = text
+            .lines()
+            .filter_map(|line| line.strip_prefix("data:"))
+            .map(|d| d.strip_prefix(' ').unwrap_or(d))
+            .collect();
+        if !payload.is_empty() {
+            events.push(payload.join("\n"));
+        }
+    }
+    events
+}
+
+fn set_status(app: &tauri::AppHandle, status: MachineStatus) {
+    if let Ok(mut statuses) = STATUSES.lock() {
+        statuses
+            .get_or_insert_with(HashMap::new)
+            .insert(status.url.clone(), status);
+    }
+    let _ = app.emit("dashboard-updated", ());
+}
+
+/// Whether the subscriber for `machine` should keep running
+fn still_wanted(machine: &RemoteMachine) -> bool {
+    let requested = LAST_REQUESTED
+        .lock()
+        .ok()
+        .and_then(|t| *t)
+        .is_some_and(|t| t.elapsed() < IDLE_TIMEOUT);
+    requested && Config::remote_machines().contains(machine)
+}
+
+/// Open the machine's summary stream and cache every event until the stream fails; the first
+/// event resets `retry_delay`. `Err` carries the reason shown on the tile.
+async fn follow(
+    app: &tauri::AppHandle,
+    client: &reqwest::Client,
+    machine: &RemoteMachine,
+    retry_delay: &mut Duration,
+) -> Result<(), String> {
+    let started = Instant::now();
+    let mut request = client.get(format!("{}/api/v1/summary/stream", machine.url));
+    if let Some(token) = machine
+        .token_key
+        .as_deref()
+        .and_then(Config::secret_from_env_or_config_env)
+    {
+        request = request.bearer_auth(token);
+    }
+    let mut response = match request.send().await {
+        Ok(response) if response.status().is_success() => response,
+        Ok(response) => return Err(format!("HTTP {}", response.status())),
+        Err(e) if e.is_timeout() => return Err("Timed out".to_string()),
+        Err(e) if e.is_connect() => return Err("Not reachable".to_string()),
+        Err(e) => return Err(e.to_string()),
+    };
+    let latency_ms = started.elapsed().as_millis() as u64;
+    let mut buf = Vec::new();
+    loop {
+        let chunk = match tokio::time::timeout(STALE_AFTER, response.chunk()).await {
+            Ok(Ok(Some(chunk))) => chunk,
+            Ok(Ok(None)) => return Err("Stream closed".to_string()),
+            Ok(Err(e)) => return Err(e.to_string()),
+            Err(_) => return Err("Timed out".to_string()),
+        };
+        buf.extend_from_slice(&chunk);
+        for data in drain_events(&mut buf) {
+            *retry_delay = RETRY_DELAY;
+            let summary = serde_json::from_str::<MachineSummary>(&data)
+                .map_err(|e| format!("Unexpected response: {}", e))?;
+            set_status(
+                app,
+                MachineStatus {
+                    name: machine.name.clone(),
+                    url: machine.url.clone(),
+                    online: true,
+                    error: None,
+                    latency_ms: Some(latency_ms),
+                    summary: Some(summary),
+                },
+            );
+        }
+        if !still_wanted(machine) {
+            return Ok(());
+        }
+    }
+}
+
+/// Keep `machine`'s stream open, reconnecting with backoff, until it is no longer wanted
+async fn subscribe(app: tauri::AppHandle, machine: RemoteMachine) {
+    debug1!(
+        "Dashboard: subscribing to {} ({})",
+        machine.name,
+        machine.url
+    );
+    let client = reqwest::Client::builder()
+        .connect_timeout(CONNECT_TIMEOUT)
+        .build();
+    let mut delay = RETRY_DELAY;
+    while still_wanted(&machine) {
+        let result = match &client {
+            Ok(client) => follow(&app, client, &machine, &mut delay).await,
+            Err(e) => Err(e.to_string()),
+        };
+        match result {
+            Ok(()) => break,
+            Err(e) => {
+                debug2!("Dashboard: {} ({}): {}", machine.name, machine.url, e);
+                set_status(&app, MachineStatus::offline(&machine, &e));
+            }
+        }
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(MAX_RETRY_DELAY);
+    }
+    debug1!("Dashboard: unsubscribed from {}", machine.name);
+    if let Ok(mut statuses) = STATUSES.lock() {
+        statuses
+            .get_or_insert_with(HashMap::new)
+            .remove(&machine.url);
+    }
+    if let Ok(mut subscribed) = SUBSCRIBED.lock() {
+        subscribed
+            .get_or_insert_with(HashSet::new)
+            .remove(&machine.url);
+    }
+}
+
+/// This Mac followed by every `remoteMachines` entry's latest streamed status. Starts the
+/// subscribers that are not running yet; tiles read "Connecting…" until their first event.
+#[tauri::command]
+pub fn get_dashboard(app: tauri::AppHandle) -> Result<Vec<MachineStatus>, String> {
+    if let Ok(mut last) = LAST_REQUESTED.lock() {
+        *last = Some(Instant::now());
+    }
+    let mut tiles = vec![MachineStatus {
+        name: "This Mac".to_string(),
+        url: String::new(),
+        online: true,
+        error: None,
+        latency_ms: None,
+        summary: Some(local_summary()),
+    }];
+    let machines = Config::remote_machines();
+    if let Ok(mut subscribed) = SUBSCRIBED.lock() {
+        let subscribed = subscribed.get_or_insert_with(HashSet::new);
+        for machine in &machines {
+            if subscribed.insert(machine.url.clone()) {
+                tauri::async_runtime::spawn(subscribe(app.clone(), machine.clone()));
+            }
+        }
+    }
+    let statuses = STATUSES.lock().map_err(|e| e.to_string())?;
+    tiles.extend(machines.iter().map(|machine| {
+        statuses
+            .as_ref()
+            .and_then(|s| s.get(&machine.url))
+            .filter(|status| status.name == machine.name)
+            .cloned()
+            .unwrap_or_else(|| MachineStatus::offline(machine, "Connecting…"))
+    }));
+    Ok(tiles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drains_complete_events_only() {
+        let mut buf = b": hello\n\ndata: {\"a\":1}\n\ndata: one\ndata:two\n\ndata: {\"b\"".to_vec();
+        assert_eq!(drain_events(&mut buf), vec!["{\"a\":1}", "one\ntwo"]);
+        assert_eq!(buf, b"data: {\"b\"");
+        buf.extend_from_slice(b":2}\n\n");
+        assert_eq!(drain_events(&mut buf), vec!["{\"b\":2}"]);
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn parses_machines() {
+        let machines = parse_machines(&serde_json::json!([
+            {"name": "Studio", "url": "http://127.0.0.1:8788/", "tokenKey": "STUDIO_API_TOKEN"},
+            {"url": "https://mini.example.org"},
+            {"name": "Broken", "url": "mini.local:8787"},
+            {"name": "No URL"}
+        ]));
+        assert_eq!(
+            machines,
+            vec![
+                RemoteMachine {
+                    name: "Studio".to_string(),
+                    url: "http://127.0.0.1:8788".to_string(),
+                    token_key: Some("STUDIO_API_TOKEN".to_string()),
+                },
+                RemoteMachine {
+                    name: "https://mini.example.org".to_string(),
+                    url: "https://mini.example.org".to_string(),
+                    token_key: None,
+                },
+            ]
+        );
+    }
+}
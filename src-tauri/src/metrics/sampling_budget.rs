@@ -0,0 +1,273 @@
+//! Per-collector time budget for the background sampling loop
+//!
+//! Each expensive collector (SMC temperature, IOReport frequency / power, `ioreg` GPU usage,
+//! process enumeration) is timed with a [`Timer`]. A run over the budget (`samplingBudgetMs`,
+//! default 100 ms) logs a warning and doubles that collector's interval, up to
+//! [`MAX_FACTOR`]× its normal interval; after [`RECOVER_AFTER`] runs in a row under half the
+//! budget the interval is halved again. Other collectors keep their pace, so one slow
+//! subsystem (e.g. SMC on a busy Intel Mac) cannot drag the whole loop.
+//!
+//! `samplingIntervalFactor` (1–8, default 1) stretches every collector's normal interval,
+//! e.g. for a battery-saving profile (`config::profiles`).
+
+use serde::Serialize;
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Collector {
+    Smc,
+    IoReportFrequency,
+    IoReportPower,
+    Ioreg,
+    Processes,
+}
+
+const ALL: [Collector; 5] = [
+    Collector::Smc,
+    Collector::IoReportFrequency,
+    Collector::IoReportPower,
+    Collector::Ioreg,
+    Collector::Processes,
+];
+
+impl Collector {
+    pub const fn name(self) -> &'static str {
+        match self {
+            Collector::Smc => "smc",
+            Collector::IoReportFrequency => "ioreport_frequency",
+            Collector::IoReportPower => "ioreport_power",
+            Collector::Ioreg => "ioreg",
+            Collector::Processes => "processes",
+        }
+    }
+
+    /// Interval while within budget at `samplingIntervalFactor` 1
+    pub const fn base_interval(self) -> Duration {
+        match self {
+            Collector::Smc => Duration::from_secs(20),
+            Collector::IoReportFrequency => Duration::from_secs(30),
+            Collector::IoReportPower => Duration::from_secs(5),
+            Collector::Ioreg => Duration::from_secs(2),
+            Collector::Processes => Duration::from_secs(10),
+        }
+    }
+
+    fn index(self) -> usize {
+        ALL.iter().position(|c| *c == self).unwrap_or(0)
+    }
+}
+
+/// Longest slowdown: 8× the normal interval
+pub const MAX_FACTOR: u32 = 8;
+/// Consecutive fast runs (under half the budget) before the interval is halved again
+pub const RECOVER_AFTER: u32 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Slowed,
+    Restored,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Budget {
+    factor: u32,
+    fast_streak: u32,
+    last: Duration,
+}
+
+impl Budget {
+    const fn new() -> Self {
+        Self {
+            factor: 1,
+            fast_streak: 0,
+            last: Duration::ZERO,
+        }
+    }
+
+    fn observe(&mut self, elapsed: Duration, budget: Duration) -> Option<Change> {
+        self.last = elapsed;
+        if elapsed > budget {
+            self.fast_streak = 0;
+            if self.factor < MAX_FACTOR {
+                self.factor *= 2;
+                return Some(Change::Slowed);
+            }
+        } else if elapsed * 2 < budget {
+            self.fast_streak += 1;
+            if self.fast_streak >= RECOVER_AFTER && self.factor > 1 {
+                self.factor /= 2;
+                self.fast_streak = 0;
+                return Some(Change::Restored);
+            }
+        } else {
+            self.fast_streak = 0;
+        }
+        None
+    }
+}
+
+static BUDGETS: Mutex<[Budget; 5]> = Mutex::new([Budget::new(); 5]);
+
+/// `samplingBudgetMs` as last read; [`UNREAD`] until the first run
+static BUDGET_MS: AtomicU64 = AtomicU64::new(UNREAD);
+const UNREAD: u64 = u64::MAX;
+/// `samplingIntervalFactor` as last read (e.g. 4 in a battery-saving profile)
+static INTERVAL_FACTOR: AtomicU32 = AtomicU32::new(1);
+
+/// Per-run budget; None when enforcement is off (`samplingBudgetMs: 0`). Read on first use
+/// and again on [`reload`].
+fn budget() -> Option<Duration> {
+    let mut ms = BUDGET_MS.load(Ordering::Relaxed);
+    if ms == UNREAD {
+        (ms, _) = reload();
+    }
+    (ms > 0).then(|| Duration::from_millis(ms))
+}
+
+/// Interval of `collector` while within budget: its base interval times
+/// `samplingIntervalFactor`
+fn normal_interval(collector: Collector) -> Duration {
+    if BUDGET_MS.load(Ordering::Relaxed) == UNREAD {
+        reload();
+    }
+    collector.base_interval() * INTERVAL_FACTOR.load(Ordering::Relaxed)
+}
+
+/// Re-read `samplingBudgetMs` and `samplingIntervalFactor` (config hot reload); returns the
+/// budget in ms and the factor. A changed budget puts every collector back on its normal
+/// interval.
+pub fn reload() -> (u64, u32) {
+    let ms = crate::config::Config::sampling_budget_ms();
+    let factor = crate::config::Config::sampling_interval_factor();
+    INTERVAL_FACTOR.store(factor, Ordering::Relaxed);
+    let previous = BUDGET_MS.swap(ms, Ordering::Relaxed);
+    if previous != UNREAD && previous != ms {
+        if let Ok(mut budgets) = BUDGETS.lock() {
+            *budgets = [Budget::new(); 5];
+        }
+    }
+    (ms, factor)
+}
+
+/// Current interval of `collector` (its normal interval stretched while over budget)
+pub fn interval(collector: Collector) -> Duration {
+    let factor = BUDGETS
+        .lock()
+        .map(|b| b[collector.index()].factor)
+        .unwrap_or(1);
+    normal_interval(collector) * factor
+}
+
+pub fn record(collector: Collector, elapsed: Duration) {
+    let Some(budget) = budget() else {
+        return;
+    };
+    let (change, factor) = {
+        let Ok(mut budgets) = BUDGETS.lock() else {
+            return;
+        };
+        let b = &mut budgets[collector.index()];
+        (b.observe(elapsed, budget), b.factor)
+    };
+    let interval = normal_interval(collector) * factor;
+    match change {
+        Some(Change::Slowed) => tracing::warn!(
+            target: "mac_stats::metrics/budget",
+            "Sampling budget: {} took {} ms (budget {} ms); interval now {}s",
+            collector.name(),
+            elapsed.as_millis(),
+            budget.as_millis(),
+            interval.as_secs()
+        ),
+        Some(Change::Restored) => tracing::info!(
+            target: "mac_stats::metrics/budget",
+            "Sampling budget: {} back under budget; interval now {}s",
+            collector.name(),
+            interval.as_secs()
+        ),
+        None if elapsed > budget => tracing::debug!(
+            target: "mac_stats::metrics/budget",
+            "Sampling budget: {} took {} ms (budget {} ms), already at {}s",
+            collector.name(),
+            elapsed.as_millis(),
+            budget.as_millis(),
+            interval.as_secs()
+        ),
+        None => {}
+    }
+}
+
+/// Times a collector run until dropped.
+pub struct Timer {
+    collector: Collector,
+    started: Instant,
+}
+
+impl Timer {
+    pub fn start(collector: Collector) -> Self {
+        Self {
+            collector,
+            started: Instant::now(),
+        }
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        record(self.collector, self.started.elapsed());
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CollectorStatus {
+    pub name: &'static str,
+    pub interval_secs: u64,
+    pub base_interval_secs: u64,
+    pub last_ms: f64,
+}
+
+/// All collectors with their current interval and last run time
+pub fn statuses() -> Vec<CollectorStatus> {
+    let budgets = BUDGETS.lock().map(|b| *b).unwrap_or([Budget::new(); 5]);
+    ALL.iter()
+        .map(|c| {
+            let b = budgets[c.index()];
+            CollectorStatus {
+                name: c.name(),
+                interval_secs: (normal_interval(*c) * b.factor).as_secs(),
+                base_interval_secs: normal_interval(*c).as_secs(),
+                last_ms: b.last.as_secs_f64() * 1000.0,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slows_down_over_budget_and_recovers() {
+        let budget = Duration::from_millis(100);
+        let slow = Duration::from_millis(250);
+        let fast = Duration::from_millis(10);
+        let mut b = Budget::new();
+
+        assert_eq!(b.observe(slow, budget), Some(Change::Slowed));
+        assert_eq!(b.factor, 2);
+        for _ in 0..4 {
+            b.observe(slow, budget);
+        }
+        assert_eq!(b.factor, MAX_FACTOR);
+
+        // Between half and full budget: neither slower nor a recovery streak
+        assert_eq!(b.observe(Duration::from_millis(80), budget), None);
+        for _ in 0..RECOVER_AFTER - 1 {
+            assert_eq!(b.observe(fast, budget), None);
+        }
+        assert_eq!(b.observe(fast, budget), Some(Change::Restored));
+        assert_eq!(b.factor, MAX_FACTOR / 2);
+    }
+}
@@ -0,0 +1,178 @@
+//! mac-stats' own resource usage for the "Meta" tab of the CPU window
+//!
+//! CPU % and wakeups/sec are rates since the previous call (since launch on the first call), so
+//! the panel polling every few seconds shows recent cost rather than a lifetime average. Memory
+//! is the phys footprint Activity Monitor shows as "Memory" plus the resident size. Sampling
+//! durations come from the `mac_stats::perf` spans (see `logging::perf`).
+
+use serde::Serialize;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::logging::perf::{self, SpanTiming};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SelfStats {
+    pub pid: u32,
+    /// Of one core (may exceed 100 with several busy threads)
+    pub cpu_percent: f64,
+    pub footprint_bytes: u64,
+    pub rss_bytes: u64,
+    /// Package idle + interrupt wakeups per second
+    pub wakeups_per_sec: f64,
+    pub open_files: u32,
+    pub threads: u32,
+    pub uptime_secs: u64,
+    pub timings: Vec<SpanTiming>,
+    /// Collector intervals, stretched while over the sampling budget
+    pub collectors: Vec<super::sampling_budget::CollectorStatus>,
+}
+
+struct Counters {
+    at: Instant,
+    cpu_time: Duration,
+    wakeups: u64,
+}
+
+static LAST: Mutex<Option<Counters>> = Mutex::new(None);
+
+fn cpu_time() -> Duration {
+    let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+    if unsafe { libc::getrusage(libc::RUSAGE_SELF, &mut usage) } != 0 {
+        return Duration::ZERO;
+    }
+    let tv = |t: libc::timeval| Duration::new(t.tv_sec as u64, t.tv_usec as u32 * 1000);
+    tv(usage.ru_utime) + tv(usage.ru_stime)
+}
+
+fn rusage_info(pid: i32) -> Option<libc::rusage_info_v2> {
+    let mut info: libc::rusage_info_v2 = unsafe { std::mem::zeroed() };
+    let ret = unsafe {
+        libc::proc_pid_rusage(
+            pid,
+            libc::RUSAGE_INFO_V2,
+            &mut info as *mut libc::rusage_info_v2 as *mut libc::rusage_info_t,
+        )
+    };
+    (ret == 0).then_some(info)
+}
+
+fn task_info(pid: i32) -> Option<libc::proc_taskinfo> {
+    let mut info: libc::proc_taskinfo = unsafe { std::mem::zeroed() };
+    let size = std::mem::size_of::<libc::proc_taskinfo>() as i32;
+    let ret = unsafe {
+        libc::proc_pidinfo(
+            pid,
+            libc::PROC_PIDTASKINFO,
+            0,
+            &mut info as *mut libc::proc_taskinfo as *mut libc::c_void,
+            size,
+        )
+    };
+    (ret == size).then_some(info)
+}
+
+fn open_files(pid: i32) -> u32 {
+    let needed =
+        unsafe { libc::proc_pidinfo(pid, libc::PROC_PIDLISTFDS, 0, std::ptr::null_mut(), 0) };
+    if needed <= 0 {
+        return 0;
+    }
+    let entry = libc::PROC_PIDLISTFD_SIZE as usize;
+    // Room for descriptors opened between the two calls
+    let mut fds: Vec<libc::proc_fdinfo> = Vec::with_capacity(needed as usize / entry + 16);
+    let bytes = (fds.capacity() * entry) as i32;
+    let ret = unsafe {
+        libc::proc_pidinfo(
+            pid,
+            libc::PROC_PIDLISTFDS,
+            0,
+            fds.as_mut_ptr() as *mut libc::c_void,
+            bytes,
+        )
+    };
+    if ret <= 0 {
+        0
+    } else {
+        (ret as usize / entry) as u32
+    }
+}
+
+/// `delta` of a counter over `elapsed`, per second
+pub fn rate(delta: f64, elapsed: Duration) -> f64 {
+    let secs = elapsed.as_secs_f64();
+    if secs <= 0.0 {
+        0.0
+    } else {
+        delta / secs
+    }
+}
+
+pub fn collect() -> SelfStats {
+    let pid = std::process::id();
+    let usage = rusage_info(pid as i32);
+    let task = task_info(pid as i32);
+    let now = Counters {
+        at: Instant::now(),
+        cpu_time: cpu_time(),
+        wakeups: usage
+            .as_ref()
+            .map(|u| u.ri_pkg_idle_wkups + u.ri_interrupt_wkups)
+            .unwrap_or(0),
+    };
+    let uptime = Duration::from_secs(crate::state::process_uptime_secs());
+
+    let (cpu_percent, wakeups_per_sec) = {
+        let mut last = LAST.lock().unwrap_or_else(|e| e.into_inner());
+        let (cpu_delta, wakeup_delta, elapsed) = match last.as_ref() {
+            Some(prev) => (
+                now.cpu_time.saturating_sub(prev.cpu_time),
+                now.wakeups.saturating_sub(prev.wakeups),
+                now.at.duration_since(prev.at),
+            ),
+            None => (now.cpu_time, now.wakeups, uptime),
+        };
+        *last = Some(now);
+        (
+            rate(cpu_delta.as_secs_f64() * 100.0, elapsed),
+            rate(wakeup_delta as f64, elapsed),
+        )
+    };
+
+    SelfStats {
+        pid,
+        cpu_percent,
+        footprint_bytes: usage.as_ref().map(|u| u.ri_phys_footprint).unwrap_or(0),
+        rss_bytes: task.as_ref().map(|t| t.pti_resident_size).unwrap_or(0),
+        wakeups_per_sec,
+        open_files: open_files(pid as i32),
+        threads: task
+            .as_ref()
+            .map(|t| t.pti_threadnum.max(0) as u32)
+            .unwrap_or(0),
+        uptime_secs: uptime.as_secs(),
+        timings: perf::timings(),
+        collectors: super::sampling_budget::statuses(),
+    }
+}
+
+/// mac-stats' own CPU, memory, wakeups, file handles and sampling durations
+#[tauri::command]
+pub fn get_self_stats() -> SelfStats {
+    collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rates_are_per_second_and_safe_at_zero() {
+        assert_eq!(rate(30.0, Duration::from_secs(2)), 15.0);
+        assert_eq!(rate(5.0, Duration::ZERO), 0.0);
+
+        let stats = collect();
+        assert_eq!(stats.pid, std::process::id());
+        assert!(stats.cpu_percent >= 0.0);
+    }
+}
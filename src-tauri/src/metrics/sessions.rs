@@ -0,0 +1,485 @@
+//! Session summaries: one JSON file per app run in `~/.mac-stats/sessions/`
+//!
+//! At launch [`start`] notes the time and every process's accumulated CPU time; on quit
+//! [`save_on_exit`] summarizes the history recorded since (duration, CPU/GPU/temperature
+//! average and peak, an energy estimate) with the five process names that used the most CPU
+//! time during the session. Processes that exited before quit are not counted. `mac_stats
+//! sessions` lists and prints the saved summaries.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use super::energy;
+use super::history::{HistoryGap, MetricPoint};
+use crate::config::Config;
+use crate::number_format::NumberFormat;
+use crate::state::METRICS_HISTORY;
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+/// Processes listed per session
+const TOP_PROCESS_COUNT: usize = 5;
+/// Oldest summaries beyond this are deleted when a new one is written
+const MAX_SESSIONS: usize = 500;
+
+/// Accumulated CPU milliseconds per PID: (name, ms)
+type CpuTimes = HashMap<u32, (String, u64)>;
+
+struct SessionStart {
+    started_at: i64,
+    cpu_times: CpuTimes,
+}
+
+static SESSION: Mutex<Option<SessionStart>> = Mutex::new(None);
+
+/// Average and peak of one metric over the session
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MetricSummary {
+    pub avg: f32,
+    pub max: f32,
+}
+
+/// CPU time a process (all PIDs with that name) used during the session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionProcess {
+    pub name: String,
+    pub cpu_secs: f64,
+}
+
+/// One saved session (`sessions/<id>.json`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionSummary {
+    pub version: String,
+    /// Unix seconds
+    pub started_at: i64,
+    pub ended_at: i64,
+    pub duration_secs: u64,
+    pub cpu: Option<MetricSummary>,
+    pub gpu: Option<MetricSummary>,
+    /// °C; None when temperature was never read (CPU window closed all session)
+    pub temperature: Option<MetricSummary>,
+    /// Battery discharge where on battery, else measured CPU+GPU power; None without either
+    pub energy_wh: Option<f64>,
+    pub top_processes: Vec<SessionProcess>,
+}
+
+fn cpu_times() -> CpuTimes {
+    let mut sys = sysinfo::System::new();
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+    sys.processes()
+        .iter()
+        .map(|(pid, p)| {
+            (
+                pid.as_u32(),
+                (
+                    p.name().to_string_lossy().to_string(),
+                    p.accumulated_cpu_time(),
+                ),
+            )
+        })
+        .collect()
+}
+
+/// Note the session start (call once at launch, off the main thread)
+pub fn start() {
+    let started_at = chrono::Utc::now().timestamp();
+    let cpu_times = cpu_times();
+    if let Ok(mut session) = SESSION.lock() {
+        *session = Some(SessionStart {
+            started_at,
+            cpu_times,
+        });
+    }
+}
+
+fn summarize_metric(values: impl Iterator<Item = f32>) -> Option<MetricSummary> {
+    let (sum, count, max) = values.fold((0.0f64, 0usize, f32::MIN), |(sum, n, max), v| {
+        (sum + v as f64, n + 1, max.max(v))
+    });
+    (count > 0).then(|| MetricSummary {
+        avg: (sum / count as f64) as f32,
+        max,
+    })
+}
+
+/// Process names by CPU seconds used between the two snapshots, busiest first
+fn top_processes(start: &CpuTimes, end: &CpuTimes, limit: usize) -> Vec<SessionProcess> {
+    let mut by_name: HashMap<&str, u64> = HashMap::new();
+    for (pid, (name, ms)) in end {
+        let before = start
+            .get(pid)
+            .filter(|(n, _)| n == name)
+            .map(|(_, ms)| *ms)
+            .unwrap_or(0);
+        *by_name.entry(name.as_str()).or_default() += ms.saturating_sub(before);
+    }
+    let mut processes: Vec<SessionProcess> = by_name
+        .into_iter()
+        .filter(|(_, ms)| *ms > 0)
+        .map(|(name, ms)| SessionProcess {
+            name: name.to_string(),
+            cpu_secs: ms as f64 / 1000.0,
+        })
+        .collect();
+    processes.sort_by(|a, b| {
+        b.cpu_secs
+            .total_cmp(&a.cpu_secs)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    processes.truncate(limit);
+    processes
+}
+
+fn summarize(
+    started_at: i64,
+    ended_at: i64,
+    points: &[MetricPoint],
+    gaps: &[HistoryGap],
+    start_times: &CpuTimes,
+    end_times: &CpuTimes,
+) -> SessionSummary {
+    SessionSummary {
+        version: Config::version(),
+        started_at,
+        ended_at,
+        duration_secs: (ended_at - started_at).max(0) as u64,
+        cpu: summarize_metric(points.iter().map(|p| p.cpu)),
+        gpu: summarize_metric(points.iter().map(|p| p.gpu)),
+        temperature: summarize_metric(points.iter().map(|p| p.temperature).filter(|t| *t > 0.0)),
+        energy_wh: energy::integrate_wh(points, gaps),
+        top_processes: top_processes(start_times, end_times, TOP_PROCESS_COUNT),
+    }
+}
+
+fn session_id(started_at: i64) -> String {
+    chrono::DateTime::from_timestamp(started_at, 0)
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%Y%m%d-%H%M%S")
+                .to_string()
+        })
+        .unwrap_or_else(|| started_at.to_string())
+}
+
+fn prune(dir: &Path) {
+    let ids = list_ids(dir);
+    for id in ids.iter().skip(MAX_SESSIONS) {
+        let _ = std::fs::remove_file(dir.join(format!("{}.json", id)));
+    }
+}
+
+/// Unix start time of the running session (None before [`start`] ran)
+pub fn current_started_at() -> Option<i64> {
+    SESSION.lock().ok()?.as_ref().map(|s| s.started_at)
+}
+
+/// Summarize the session and write it (`RunEvent::Exit`; not in guest mode)
+pub fn save_on_exit() {
+    if crate::guest_mode::is_active() {
+        return;
+    }
+    let Some(start) = SESSION.lock().ok().and_then(|mut s| s.take()) else {
+        return;
+    };
+    let ended_at = chrono::Utc::now().timestamp();
+    let (points, gaps) = METRICS_HISTORY
+        .lock()
+        .ok()
+        .and_then(|h| {
+            h.as_ref().map(|h| {
+                (
+                    h.points_between(start.started_at, ended_at + 1),
+                    h.gaps_between(start.started_at, ended_at),
+                )
+            })
+        })
+        .unwrap_or_default();
+    let summary = summarize(
+        start.started_at,
+        ended_at,
+        &points,
+        &gaps,
+        &start.cpu_times,
+        &cpu_times(),
+    );
+    let dir = Config::sessions_dir();
+    let path = dir.join(format!("{}.json", session_id(start.started_at)));
+    let written = std::fs::create_dir_all(&dir)
+        .map_err(|e| e.to_string())
+        .and_then(|_| serde_json::to_string_pretty(&summary).map_err(|e| e.to_string()))
+        .and_then(|json| std::fs::write(&path, json).map_err(|e| e.to_string()));
+    match written {
+        Ok(()) => {
+            debug1!("Session summary written to {}", path.display());
+            prune(&dir);
+        }
+        Err(e) => tracing::warn!("Could not write session summary: {}", e),
+    }
+}
+
+/// Saved session ids, newest first
+fn list_ids(dir: &Path) -> Vec<String> {
+    let mut ids: Vec<String> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| {
+            let path = e.path();
+            (path.extension()? == "json")
+                .then(|| path.file_stem()?.to_str().map(str::to_string))
+                .flatten()
+        })
+        .collect();
+    ids.sort_unstable_by(|a, b| b.cmp(a));
+    ids
+}
+
+fn load(path: &Path) -> Result<SessionSummary, String> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// The newest `limit` saved sessions with their ids, newest first (unreadable files skipped)
+pub(crate) fn recent(limit: usize) -> Vec<(String, SessionSummary)> {
+    let dir = Config::sessions_dir();
+    list_ids(&dir)
+        .into_iter()
+        .take(limit)
+        .filter_map(|id| {
+            let session = load(&dir.join(format!("{}.json", id))).ok()?;
+            Some((id, session))
+        })
+        .collect()
+}
+
+/// Arguments for `mac_stats sessions`
+#[derive(clap::Args, Debug)]
+pub struct SessionsArgs {
+    /// Session to print (an id from the list, or `latest`); lists sessions when omitted
+    pub id: Option<String>,
+    /// JSON instead of text
+    #[arg(long)]
+    pub json: bool,
+    /// Sessions to list
+    #[arg(long, default_value_t = 20)]
+    pub limit: usize,
+}
+
+fn format_metric(m: Option<MetricSummary>, format: impl Fn(f64) -> String) -> String {
+    m.map(|m| format!("{} avg, {} max", format(m.avg as f64), format(m.max as f64)))
+        .unwrap_or_else(|| "--".to_string())
+}
+
+pub(crate) fn format_energy(energy_wh: Option<f64>, fmt: &NumberFormat) -> String {
+    energy_wh
+        .map(|wh| fmt.with_unit(wh, 1, "Wh"))
+        .unwrap_or_else(|| "--".to_string())
+}
+
+pub(crate) fn format_started(started_at: i64) -> String {
+    chrono::DateTime::from_timestamp(started_at, 0)
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+fn format_cpu_secs(secs: u64) -> String {
+    if secs >= 3600 {
+        super::cli::format_duration(secs)
+    } else if secs >= 60 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+fn format_session(s: &SessionSummary, fmt: &NumberFormat, units: &crate::units::Units) -> String {
+    let percent = |v: f64| fmt.percent(v, 0);
+    let temperature = |c: f64| fmt.with_unit(units.temperature(c), 0, units.temperature_symbol());
+    let mut lines = vec![
+        format!(
+            "Started  {} (mac-stats {})",
+            format_started(s.started_at),
+            s.version
+        ),
+        format!("Duration {}", super::cli::format_duration(s.duration_secs)),
+        format!("CPU      {}", format_metric(s.cpu, percent)),
+        format!("GPU      {}", format_metric(s.gpu, percent)),
+        format!("Temp     {}", format_metric(s.temperature, temperature)),
+        format!("Energy   {}", format_energy(s.energy_wh, fmt)),
+    ];
+    if !s.top_processes.is_empty() {
+        lines.push(String::new());
+        lines.push("CPU time  Process".to_string());
+        for p in &s.top_processes {
+            lines.push(format!(
+                "{:>8}  {}",
+                format_cpu_secs(p.cpu_secs.round() as u64),
+                p.name
+            ));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Run `mac_stats sessions`. Returns Err(exit_code) on failure.
+pub fn run_cli(args: SessionsArgs) -> Result<(), i32> {
+    let fail = |e: String| {
+        eprintln!("Error: {}", e);
+        1
+    };
+    let dir = Config::sessions_dir();
+    let ids = list_ids(&dir);
+    let fmt = NumberFormat::current();
+    if let Some(id) = args.id {
+        let id = if id == "latest" {
+            ids.first()
+                .cloned()
+                .ok_or_else(|| fail("No sessions saved yet".to_string()))?
+        } else {
+            id.trim_end_matches(".json").to_string()
+        };
+        let session = load(&dir.join(format!("{}.json", id))).map_err(fail)?;
+        if args.json {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&session).map_err(|e| fail(e.to_string()))?
+            );
+        } else {
+            println!(
+                "{}",
+                format_session(&session, &fmt, &crate::units::Units::current())
+            );
+        }
+        return Ok(());
+    }
+    let sessions = recent(args.limit);
+    if args.json {
+        let list: Vec<serde_json::Value> = sessions
+            .iter()
+            .map(|(id, s)| serde_json::json!({"id": id, "session": s}))
+            .collect();
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&list).map_err(|e| fail(e.to_string()))?
+        );
+        return Ok(());
+    }
+    if sessions.is_empty() {
+        println!("No sessions saved yet ({})", dir.display());
+        return Ok(());
+    }
+    println!(
+        "{:<16}  {:<16}  {:>9}  {:>8}  {:>8}",
+        "ID", "Started", "Duration", "CPU avg", "Energy"
+    );
+    for (id, s) in &sessions {
+        println!(
+            "{:<16}  {:<16}  {:>9}  {:>8}  {:>8}",
+            id,
+            format_started(s.started_at),
+            super::cli::format_duration(s.duration_secs),
+            s.cpu
+                .map(|m| fmt.percent(m.avg as f64, 0))
+                .unwrap_or_else(|| "--".to_string()),
+            format_energy(s.energy_wh, &fmt)
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(timestamp: i64, cpu: f32, temperature: f32, battery_power: f32) -> MetricPoint {
+        let mut p = MetricPoint::from_metrics(
+            cpu,
+            10.0,
+            50.0,
+            40.0,
+            temperature,
+            0.0,
+            0.0,
+            0.0,
+            2.0,
+            1.0,
+            80.0,
+        );
+        p.timestamp = timestamp;
+        p.battery_power = battery_power;
+        p
+    }
+
+    #[test]
+    fn summarizes_metrics_energy_and_processes() {
+        let t0 = 1_700_000_000;
+        let points = vec![
+            point(t0, 20.0, 0.0, 0.0),
+            point(t0 + 60, 60.0, 50.0, 9.0),
+            // 1h gap (sleep) after this one
+            point(t0 + 3660, 40.0, 60.0, 0.0),
+        ];
+        let start: CpuTimes = [
+            (1, ("WindowServer".to_string(), 5_000)),
+            (2, ("node".to_string(), 1_000)),
+            (3, ("old".to_string(), 9_000)),
+        ]
+        .into();
+        let end: CpuTimes = [
+            (1, ("WindowServer".to_string(), 65_000)),
+            (2, ("node".to_string(), 31_000)),
+            (4, ("node".to_string(), 20_000)),
+            (3, ("reused".to_string(), 2_000)),
+        ]
+        .into();
+        let gaps = [HistoryGap {
+            start: t0 + 60,
+            end: t0 + 3660,
+            reason: crate::metrics::history::GapReason::Sleep,
+        }];
+        let s = summarize(t0, t0 + 3700, &points, &gaps, &start, &end);
+        assert_eq!(s.duration_secs, 3700);
+        assert_eq!(
+            s.cpu,
+            Some(MetricSummary {
+                avg: 40.0,
+                max: 60.0
+            })
+        );
+        assert_eq!(
+            s.temperature,
+            Some(MetricSummary {
+                avg: 55.0,
+                max: 60.0
+            })
+        );
+        // 60s at 3 W (CPU+GPU); nothing across the sleep gap
+        assert!((s.energy_wh.unwrap() - 0.05).abs() < 1e-9);
+        assert_eq!(
+            s.top_processes,
+            vec![
+                SessionProcess {
+                    name: "WindowServer".to_string(),
+                    cpu_secs: 60.0
+                },
+                SessionProcess {
+                    name: "node".to_string(),
+                    cpu_secs: 50.0
+                },
+                SessionProcess {
+                    name: "reused".to_string(),
+                    cpu_secs: 2.0
+                },
+            ]
+        );
+        assert_eq!(summarize(t0, t0, &[], &[], &start, &start).energy_wh, None);
+    }
+}
@@ -0,0 +1,111 @@
+//! Scheduled metric snapshots for post-mortem inspection
+//!
+//! Every `metricSnapshotMinutes` a full reading (`SystemMetrics`, `CpuDetails` with top
+//! processes, temperatures and fan; the same JSON as `mac_stats stats --json`) is written to
+//! `~/.mac-stats/snapshots/<UTC time>.json`. Snapshots older than
+//! `metricSnapshotRetentionHours` are deleted after each write. Snapshots are skipped while
+//! low-interference mode is on. Retention is re-read every cycle; changing the interval or
+//! turning snapshots on needs a restart.
+
+use std::path::Path;
+use std::time::Duration;
+
+use chrono::{NaiveDateTime, Utc};
+
+use crate::config::Config;
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+/// File name format (UTC), e.g. `20261017T143000Z.json`
+const NAME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+
+fn file_name(timestamp: i64) -> String {
+    let time = chrono::DateTime::from_timestamp(timestamp, 0).unwrap_or_default();
+    format!("{}.json", time.format(NAME_FORMAT))
+}
+
+/// Unix time a snapshot file was taken at, from its name
+fn taken_at(name: &str) -> Option<i64> {
+    let stem = name.strip_suffix(".json")?;
+    NaiveDateTime::parse_from_str(stem, NAME_FORMAT)
+        .ok()
+        .map(|t| t.and_utc().timestamp())
+}
+
+/// Snapshot names in `names` taken before `cutoff`
+fn expired<'a>(names: impl IntoIterator<Item = &'a str>, cutoff: i64) -> Vec<&'a str> {
+    names
+        .into_iter()
+        .filter(|name| taken_at(name).is_some_and(|t| t < cutoff))
+        .collect()
+}
+
+fn prune(dir: &Path, retention_hours: u64) {
+    let names: Vec<String> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| e.file_name().to_str().map(str::to_string))
+        .collect();
+    let cutoff = Utc::now().timestamp() - (retention_hours * 3600) as i64;
+    for name in expired(names.iter().map(String::as_str), cutoff) {
+        let _ = std::fs::remove_file(dir.join(name));
+    }
+}
+
+/// Take one snapshot and write it (blocking: refreshes processes and reads SMC)
+fn write_snapshot(dir: &Path) -> Result<(), String> {
+    let snapshot = super::cli::snapshot();
+    let json = serde_json::to_string_pretty(&snapshot).map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    crate::config::write_text_atomic(&dir.join(file_name(snapshot.timestamp)), &json)
+}
+
+/// Start the snapshot task when `metricSnapshotMinutes` is set
+pub fn spawn_snapshots_if_enabled() {
+    let minutes = Config::metric_snapshot_minutes();
+    if minutes == 0 {
+        return;
+    }
+    tracing::info!(
+        "Metric snapshots: every {} min to {} (kept {}h)",
+        minutes,
+        Config::snapshots_dir().display(),
+        Config::metric_snapshot_retention_hours()
+    );
+    crate::background::every(
+        "metric_snapshots",
+        Duration::from_secs(minutes * 60),
+        || {
+            if super::low_interference::is_active() {
+                debug2!("Metric snapshot skipped (low-interference mode)");
+                return true;
+            }
+            let dir = Config::snapshots_dir();
+            match write_snapshot(&dir) {
+                Ok(()) => debug2!("Metric snapshot written to {}", dir.display()),
+                Err(e) => debug1!("Metric snapshot failed: {}", e),
+            }
+            prune(&dir, Config::metric_snapshot_retention_hours());
+            true
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_round_trip_and_expire() {
+        let t = 1_700_000_000;
+        assert_eq!(file_name(t), "20231114T221320Z.json");
+        assert_eq!(taken_at(&file_name(t)), Some(t));
+        assert_eq!(taken_at("notes.txt"), None);
+
+        let old = file_name(t - 7200);
+        let new = file_name(t);
+        let names = [old.as_str(), new.as_str(), "notes.json"];
+        assert_eq!(expired(names, t - 3600), vec![old.as_str()]);
+    }
+}
@@ -0,0 +1,229 @@
+//! Per-window metric push (`subscribe`)
+//!
+//! Instead of polling on one shared cadence, a window calls `subscribe(metrics, interval_ms)`
+//! with the groups it shows and how often it wants them, e.g. the CPU window `["cpu"]` every
+//! second while a network view takes `["network"]` every 5s. One publisher thread emits a
+//! `metrics-push` event to each subscribed window when its interval is due, with only the
+//! requested groups (`{ "timestamp": <ms>, "cpu": {...}, "network": {...} }`). Each group is
+//! read once per tick however many windows want it. Hidden windows are skipped; closed ones
+//! are dropped. Calling `subscribe` again replaces the window's subscription.
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, Once};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+
+use crate::state::{APP_HANDLE, LATEST_IO_RATES};
+
+/// Event emitted to subscribed windows
+pub const EVENT: &str = "metrics-push";
+/// Groups a window can subscribe to: `cpu` ([`super::CpuDetails`]), `system` (CPU/GPU/RAM/disk
+/// usage) and `network` (network and disk throughput in bytes/s)
+pub const GROUPS: &[&str] = &["cpu", "system", "network"];
+/// Allowed push intervals; requests outside are clamped
+pub const MIN_INTERVAL_MS: u64 = 250;
+pub const MAX_INTERVAL_MS: u64 = 60_000;
+/// Publisher wake-up while nothing is subscribed
+const IDLE_WAIT: Duration = Duration::from_secs(1);
+
+/// Subscription as accepted (groups normalized, interval clamped)
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Subscription {
+    pub metrics: Vec<String>,
+    pub interval_ms: u64,
+}
+
+struct Entry {
+    subscription: Subscription,
+    next_due: Instant,
+}
+
+static SUBSCRIPTIONS: Mutex<BTreeMap<String, Entry>> = Mutex::new(BTreeMap::new());
+static PUBLISHER: Once = Once::new();
+
+/// Validate a request: known groups only (case-insensitive, deduplicated), interval clamped to
+/// [`MIN_INTERVAL_MS`]..=[`MAX_INTERVAL_MS`]
+pub fn negotiate(metrics: &[String], interval_ms: u64) -> Result<Subscription, String> {
+    let mut groups: Vec<String> = Vec::new();
+    for m in metrics {
+        let m = m.trim().to_ascii_lowercase();
+        if !GROUPS.contains(&m.as_str()) {
+            return Err(format!(
+                "Unknown metric group '{}' (expected {})",
+                m,
+                GROUPS.join(", ")
+            ));
+        }
+        if !groups.contains(&m) {
+            groups.push(m);
+        }
+    }
+    if groups.is_empty() {
+        return Err("No metric groups requested".to_string());
+    }
+    Ok(Subscription {
+        metrics: groups,
+        interval_ms: interval_ms.clamp(MIN_INTERVAL_MS, MAX_INTERVAL_MS),
+    })
+}
+
+/// Windows due at `now` with their groups; their next push is scheduled one interval later.
+/// Also returns how long until the next one is due.
+fn take_due(
+    entries: &mut BTreeMap<String, Entry>,
+    now: Instant,
+) -> (Vec<(String, Vec<String>)>, Duration) {
+    let mut due = Vec::new();
+    let mut wait = IDLE_WAIT;
+    for (label, entry) in entries.iter_mut() {
+        if entry.next_due <= now {
+            due.push((label.clone(), entry.subscription.metrics.clone()));
+            entry.next_due = now + Duration::from_millis(entry.subscription.interval_ms);
+        }
+        wait = wait.min(entry.next_due.saturating_duration_since(now));
+    }
+    (due, wait)
+}
+
+fn read_group(group: &str) -> serde_json::Value {
+    let value = match group {
+        "cpu" => serde_json::to_value(super::get_cpu_details()),
+        "system" => serde_json::to_value(super::get_metrics()),
+        "network" => serde_json::to_value(
+            LATEST_IO_RATES
+                .try_lock()
+                .ok()
+                .and_then(|r| *r)
+                .unwrap_or_default(),
+        ),
+        _ => Ok(serde_json::Value::Null),
+    };
+    value.unwrap_or(serde_json::Value::Null)
+}
+
+fn publish_due() -> Duration {
+    let Some(app) = APP_HANDLE.get() else {
+        return IDLE_WAIT;
+    };
+    let (due, wait) = match SUBSCRIPTIONS.lock() {
+        Ok(mut entries) => take_due(&mut entries, Instant::now()),
+        Err(_) => return IDLE_WAIT,
+    };
+    let mut values: BTreeMap<String, serde_json::Value> = BTreeMap::new();
+    let mut closed = Vec::new();
+    for (label, groups) in due {
+        let Some(window) = app.get_webview_window(&label) else {
+            closed.push(label);
+            continue;
+        };
+        if !window.is_visible().unwrap_or(false) {
+            continue;
+        }
+        let mut payload = serde_json::Map::new();
+        payload.insert(
+            "timestamp".to_string(),
+            chrono::Utc::now().timestamp_millis().into(),
+        );
+        for group in groups {
+            let value = values
+                .entry(group.clone())
+                .or_insert_with(|| read_group(&group))
+                .clone();
+            payload.insert(group, value);
+        }
+        if let Err(e) = app.emit_to(label.as_str(), EVENT, payload) {
+            tracing::debug!("Metrics push to window '{}' failed: {}", label, e);
+        }
+    }
+    if !closed.is_empty() {
+        if let Ok(mut entries) = SUBSCRIPTIONS.lock() {
+            for label in closed {
+                tracing::debug!("Metrics push: window '{}' closed, unsubscribed", label);
+                entries.remove(&label);
+            }
+        }
+    }
+    wait
+}
+
+/// Push `metrics` groups to the calling window every `interval_ms`; returns the accepted
+/// subscription (the interval may be clamped)
+#[tauri::command]
+pub fn subscribe(
+    window: tauri::WebviewWindow,
+    metrics: Vec<String>,
+    interval_ms: u64,
+) -> Result<Subscription, String> {
+    let subscription = negotiate(&metrics, interval_ms)?;
+    let label = window.label().to_string();
+    tracing::debug!(
+        "Metrics push: window '{}' subscribed to {:?} every {}ms",
+        label,
+        subscription.metrics,
+        subscription.interval_ms
+    );
+    SUBSCRIPTIONS
+        .lock()
+        .map_err(|_| "Subscriptions are unavailable".to_string())?
+        .insert(
+            label,
+            Entry {
+                subscription: subscription.clone(),
+                next_due: Instant::now(),
+            },
+        );
+    PUBLISHER.call_once(|| {
+        std::thread::spawn(|| loop {
+            std::thread::sleep(publish_due());
+        });
+    });
+    Ok(subscription)
+}
+
+/// Stop pushing to the calling window
+#[tauri::command]
+pub fn unsubscribe(window: tauri::WebviewWindow) {
+    if let Ok(mut entries) = SUBSCRIPTIONS.lock() {
+        entries.remove(window.label());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiates_and_schedules_per_window() {
+        let sub = negotiate(&["CPU".to_string(), "cpu".to_string()], 10).unwrap();
+        assert_eq!(sub.metrics, vec!["cpu"]);
+        assert_eq!(sub.interval_ms, MIN_INTERVAL_MS);
+        assert!(negotiate(&["gpu_temp".to_string()], 1000).is_err());
+        assert!(negotiate(&[], 1000).is_err());
+
+        let now = Instant::now();
+        let mut entries = BTreeMap::new();
+        for (label, group, interval_ms) in [("cpu", "cpu", 1000), ("net", "network", 5000)] {
+            entries.insert(
+                label.to_string(),
+                Entry {
+                    subscription: Subscription {
+                        metrics: vec![group.to_string()],
+                        interval_ms,
+                    },
+                    next_due: now,
+                },
+            );
+        }
+        let (due, wait) = take_due(&mut entries, now);
+        assert_eq!(due.len(), 2);
+        assert_eq!(wait, Duration::from_millis(1000));
+
+        let (due, wait) = take_due(&mut entries, now + Duration::from_millis(1000));
+        assert_eq!(due, vec![("cpu".to_string(), vec!["cpu".to_string()])]);
+        assert_eq!(wait, Duration::from_millis(1000));
+        let (due, _) = take_due(&mut entries, now + Duration::from_millis(5000));
+        assert_eq!(due.len(), 2);
+    }
+}
@@ -0,0 +1,507 @@
+//! System report for support requests
+//!
+//! One self-contained file with hardware info, a fresh reading (the `mac_stats stats` rows),
+//! the last 24 hours of CPU/GPU/RAM, temperature and power from the history, top processes and
+//! the alerts fired since launch. HTML draws the 24h charts as inline SVG; Markdown gives
+//! min/avg/max and a sparkline per metric. Written to `~/.mac-stats/reports/` by
+//! `generate_system_report` or the status menu's "System Report" (which opens it).
+
+use std::path::PathBuf;
+
+use super::cli::{self, StatsSnapshot};
+use super::history::MetricPoint;
+use crate::alerts::FiredAlert;
+use crate::config::Config;
+use crate::number_format::NumberFormat;
+use crate::units::Units;
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+const RANGE_SECS: u64 = 86_400;
+const CHART_POINTS: usize = 288;
+/// Points further apart than this (sleep, app not running) break the chart line
+const MAX_STEP_SECS: i64 = 900;
+const CHART_SIZE: (f32, f32) = (640.0, 120.0);
+const SPARK: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// One charted metric
+struct Series {
+    label: &'static str,
+    color: &'static str,
+    value: fn(&MetricPoint) -> f32,
+}
+
+/// Charts: title, unit, fixed maximum (None = auto) and series
+const CHARTS: &[(&str, &str, Option<f32>, &[Series])] = &[
+    (
+        "Usage",
+        "%",
+        Some(100.0),
+        &[
+            Series {
+                label: "CPU",
+                color: "#0a84ff",
+                value: |p| p.cpu,
+            },
+            Series {
+                label: "GPU",
+                color: "#bf5af2",
+                value: |p| p.gpu,
+            },
+            Series {
+                label: "RAM",
+                color: "#30d158",
+                value: |p| p.ram,
+            },
+        ],
+    ),
+    (
+        "Temperature",
+        "°C",
+        None,
+        &[Series {
+            label: "CPU",
+            color: "#ff9f0a",
+            value: |p| p.temperature,
+        }],
+    ),
+    (
+        "Power",
+        " W",
+        None,
+        &[
+            Series {
+                label: "CPU",
+                color: "#ff453a",
+                value: |p| p.cpu_power,
+            },
+            Series {
+                label: "GPU",
+                color: "#64d2ff",
+                value: |p| p.gpu_power,
+            },
+        ],
+    ),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Html,
+    Markdown,
+}
+
+impl ReportFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "html" => Some(Self::Html),
+            "markdown" | "md" => Some(Self::Markdown),
+            _ => None,
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Html => "html",
+            Self::Markdown => "md",
+        }
+    }
+}
+
+struct Report {
+    snapshot: StatsSnapshot,
+    hardware: Vec<(&'static str, String)>,
+    history: Vec<MetricPoint>,
+    alerts: Vec<FiredAlert>,
+}
+
+fn sysctl(name: &str) -> Option<String> {
+    let output = std::process::Command::new("/usr/sbin/sysctl")
+        .args(["-n", name])
+        .output()
+        .ok()?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !value.is_empty()).then_some(value)
+}
+
+fn hardware(snapshot: &StatsSnapshot) -> Vec<(&'static str, String)> {
+    let mut rows = Vec::new();
+    if let Some(model) = sysctl("hw.model") {
+        rows.push(("Model", model));
+    }
+    if !snapshot.cpu.chip_info.is_empty() {
+        rows.push(("Chip", snapshot.cpu.chip_info.clone()));
+    }
+    if let Some(cores) = sysinfo::System::physical_core_count() {
+        rows.push(("Cores", cores.to_string()));
+    }
+    if let Some(bytes) = sysctl("hw.memsize").and_then(|v| v.parse::<u64>().ok()) {
+        rows.push(("Memory", format!("{} GB", bytes >> 30)));
+    }
+    if let Some(os) = sysinfo::System::long_os_version() {
+        rows.push(("macOS", os));
+    }
+    rows.push(("Uptime", cli::format_duration(snapshot.cpu.uptime_secs)));
+    rows.push(("mac-stats", Config::version()));
+    rows
+}
+
+fn collect() -> Report {
+    let snapshot = cli::snapshot();
+    let history = super::get_metrics_history(RANGE_SECS, Some(CHART_POINTS), None, None)
+        .map(|result| {
+            result
+                .points
+                .into_iter()
+                .filter_map(|p| serde_json::from_value::<MetricPoint>(p).ok())
+                .collect()
+        })
+        .unwrap_or_else(|e| {
+            debug1!("System report: no history: {}", e);
+            Vec::new()
+        });
+    let since = chrono::Utc::now() - chrono::Duration::seconds(RANGE_SECS as i64);
+    let alerts = crate::alerts::recent_alerts()
+        .into_iter()
+        .filter(|a| a.at >= since)
+        .collect();
+    Report {
+        hardware: hardware(&snapshot),
+        snapshot,
+        history,
+        alerts,
+    }
+}
+
+/// Readings of `series`, skipping unread sensors (0) for everything but usage
+fn readings(points: &[MetricPoint], series: &Series, usage: bool) -> Vec<(i64, f32)> {
+    points
+        .iter()
+        .map(|p| (p.timestamp, (series.value)(p)))
+        .filter(|(_, v)| v.is_finite() && (usage || *v > 0.0))
+        .collect()
+}
+
+/// `▁▃█` sparkline of at most `width` buckets
+fn sparkline(values: &[f32], max: f32, width: usize) -> String {
+    if values.is_empty() || max <= 0.0 {
+        return String::new();
+    }
+    let per = values.len().div_ceil(width.max(1));
+    values
+        .chunks(per)
+        .map(|chunk| {
+            let avg = chunk.iter().sum::<f32>() / chunk.len() as f32;
+            let level = ((avg / max).clamp(0.0, 1.0) * (SPARK.len() - 1) as f32).round();
+            SPARK[level as usize]
+        })
+        .collect()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Inline SVG line chart; the x axis spans the last 24 hours ending `now`
+fn svg_chart(points: &[MetricPoint], series: &[Series], max: Option<f32>, now: i64) -> String {
+    let (w, h) = CHART_SIZE;
+    let start = now - RANGE_SECS as i64;
+    let usage = max.is_some();
+    let lines: Vec<(&Series, Vec<(i64, f32)>)> = series
+        .iter()
+        .map(|s| (s, readings(points, s, usage)))
+        .collect();
+    let top = max.unwrap_or_else(|| {
+        let peak = lines
+            .iter()
+            .flat_map(|(_, r)| r.iter().map(|(_, v)| *v))
+            .fold(0.0f32, f32::max);
+        (peak * 1.1).max(1.0)
+    });
+    let mut svg = format!(
+        "<svg viewBox=\"0 0 {w} {h}\" width=\"100%\" preserveAspectRatio=\"none\" role=\"img\">\
+         <rect width=\"{w}\" height=\"{h}\" class=\"plot\"/>"
+    );
+    for (s, readings) in &lines {
+        let mut path = String::new();
+        let mut last: Option<i64> = None;
+        for (t, v) in readings {
+            let x = (t - start) as f32 / RANGE_SECS as f32 * w;
+            let y = h - (v / top).clamp(0.0, 1.0) * h;
+            let jump = last.is_none_or(|l| t - l > MAX_STEP_SECS);
+            path.push_str(&format!(
+                "{}{:.1},{:.1} ",
+                if jump { "M" } else { "L" },
+                x,
+                y
+            ));
+            last = Some(*t);
+        }
+        if !path.is_empty() {
+            svg.push_str(&format!(
+                "<path d=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"1.5\"/>",
+                path.trim_end(),
+                s.color
+            ));
+        }
+    }
+    svg.push_str(&format!(
+        "<text x=\"4\" y=\"12\" class=\"axis\">{}</text></svg>",
+        NumberFormat::current().number(top as f64, 0)
+    ));
+    svg
+}
+
+fn render_markdown(report: &Report) -> String {
+    let fmt = NumberFormat::current();
+    let units = Units::current();
+    let s = &report.snapshot;
+    let taken = local_time(s.timestamp);
+    let mut out = vec![
+        format!("# mac-stats system report ({})", taken),
+        String::new(),
+    ];
+
+    out.push("## Hardware".to_string());
+    out.push(String::new());
+    for (label, value) in &report.hardware {
+        out.push(format!("- **{}**: {}", label, value));
+    }
+
+    out.push(String::new());
+    out.push("## Current metrics".to_string());
+    out.push(String::new());
+    out.push("| Metric | Value |".to_string());
+    out.push("| --- | --- |".to_string());
+    for (label, value) in cli::summary_rows(s, &fmt, &units) {
+        out.push(format!("| {} | {} |", label, value.replace('|', "\\|")));
+    }
+
+    out.push(String::new());
+    out.push("## Last 24 hours".to_string());
+    out.push(String::new());
+    if report.history.is_empty() {
+        out.push("No history recorded yet.".to_string());
+    } else {
+        out.push("| Metric | Min | Avg | Max | Trend |".to_string());
+        out.push("| --- | ---: | ---: | ---: | --- |".to_string());
+        for (title, unit, max, series) in CHARTS {
+            for s in series.iter() {
+                let values: Vec<f32> = readings(&report.history, s, max.is_some())
+                    .into_iter()
+                    .map(|(_, v)| v)
+                    .collect();
+                if values.is_empty() {
+                    continue;
+                }
+                let (lo, hi) = values
+                    .iter()
+                    .fold((f32::MAX, f32::MIN), |(lo, hi), v| (lo.min(*v), hi.max(*v)));
+                let avg = values.iter().sum::<f32>() / values.len() as f32;
+                out.push(format!(
+                    "| {} {} | {} | {} | {} | {} |",
+                    s.label,
+                    title,
+                    fmt.with_unit(lo as f64, 1, unit),
+                    fmt.with_unit(avg as f64, 1, unit),
+                    fmt.with_unit(hi as f64, 1, unit),
+                    sparkline(&values, max.unwrap_or(hi), 48)
+                ));
+            }
+        }
+    }
+
+    out.push(String::new());
+    out.push("## Top processes".to_string());
+    out.push(String::new());
+    out.push("| Process | PID | CPU |".to_string());
+    out.push("| --- | ---: | ---: |".to_string());
+    for p in &s.cpu.top_processes {
+        out.push(format!(
+            "| {} | {} | {} |",
+            p.name.replace('|', "\\|"),
+            p.pid,
+            fmt.percent(p.cpu as f64, 1)
+        ));
+    }
+
+    out.push(String::new());
+    out.push("## Recent alerts".to_string());
+    out.push(String::new());
+    if report.alerts.is_empty() {
+        out.push("No alerts in the last 24 hours.".to_string());
+    }
+    for alert in &report.alerts {
+        out.push(format!(
+            "- {} [{}] {}",
+            local_time(alert.at.timestamp()),
+            alert.severity.as_str(),
+            alert.message
+        ));
+    }
+    out.push(String::new());
+    out.join("\n")
+}
+
+const HTML_STYLE: &str = "body{font:13px/1.45 -apple-system,BlinkMacSystemFont,sans-serif;\
+max-width:760px;margin:24px auto;padding:0 16px;color:#1d1d1f}h1{font-size:20px}\
+h2{font-size:13px;text-transform:uppercase;letter-spacing:.05em;color:#6e6e73;margin-top:28px}\
+table{border-collapse:collapse;width:100%}td,th{text-align:left;padding:3px 8px 3px 0;\
+border-bottom:1px solid #e5e5ea}.num{text-align:right}.plot{fill:#f5f5f7}\
+.axis{font-size:10px;fill:#6e6e73}.legend span{margin-right:12px}h3{font-size:13px;margin:16px 0 4px}";
+
+fn render_html(report: &Report) -> String {
+    let fmt = NumberFormat::current();
+    let units = Units::current();
+    let s = &report.snapshot;
+    let row = |label: &str, value: &str| {
+        format!(
+            "<tr><th>{}</th><td>{}</td></tr>",
+            escape_html(label),
+            escape_html(value)
+        )
+    };
+    let mut out = format!(
+        "<!doctype html><html lang=\"en\"><head><meta charset=\"utf-8\"><title>mac-stats system \
+         report</title><style>{}</style></head><body><h1>mac-stats system report</h1><p>{}</p>",
+        HTML_STYLE,
+        escape_html(&local_time(s.timestamp))
+    );
+
+    out.push_str("<h2>Hardware</h2><table>");
+    for (label, value) in &report.hardware {
+        out.push_str(&row(label, value));
+    }
+    out.push_str("</table><h2>Current metrics</h2><table>");
+    for (label, value) in cli::summary_rows(s, &fmt, &units) {
+        out.push_str(&row(label, &value));
+    }
+    out.push_str("</table><h2>Last 24 hours</h2>");
+    if report.history.is_empty() {
+        out.push_str("<p>No history recorded yet.</p>");
+    }
+    for (title, unit, max, series) in CHARTS {
+        if report.history.is_empty() {
+            break;
+        }
+        out.push_str(&format!(
+            "<h3>{} ({})</h3><div class=\"legend\">",
+            title,
+            unit.trim()
+        ));
+        for series in series.iter() {
+            out.push_str(&format!(
+                "<span style=\"color:{}\">━ {}</span>",
+                series.color, series.label
+            ));
+        }
+        out.push_str("</div>");
+        out.push_str(&svg_chart(&report.history, series, *max, s.timestamp));
+    }
+    out.push_str(
+        "<h2>Top processes</h2><table><tr><th>Process</th><th class=\"num\">PID</th>\
+         <th class=\"num\">CPU</th></tr>",
+    );
+    for p in &s.cpu.top_processes {
+        out.push_str(&format!(
+            "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+            escape_html(&p.name),
+            p.pid,
+            escape_html(&fmt.percent(p.cpu as f64, 1))
+        ));
+    }
+    out.push_str("</table><h2>Recent alerts</h2>");
+    if report.alerts.is_empty() {
+        out.push_str("<p>No alerts in the last 24 hours.</p>");
+    } else {
+        out.push_str("<table>");
+        for alert in &report.alerts {
+            out.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+                escape_html(&local_time(alert.at.timestamp())),
+                alert.severity.as_str(),
+                escape_html(&alert.message)
+            ));
+        }
+        out.push_str("</table>");
+    }
+    out.push_str("</body></html>\n");
+    out
+}
+
+fn local_time(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|t| {
+            t.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+/// Build a report and write it to `~/.mac-stats/reports/` (blocking: samples metrics)
+pub fn write_report(format: ReportFormat) -> Result<PathBuf, String> {
+    let report = collect();
+    let text = match format {
+        ReportFormat::Html => render_html(&report),
+        ReportFormat::Markdown => render_markdown(&report),
+    };
+    let dir = Config::reports_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let stamp = chrono::DateTime::from_timestamp(report.snapshot.timestamp, 0)
+        .unwrap_or_default()
+        .with_timezone(&chrono::Local)
+        .format("%Y%m%d-%H%M%S");
+    let path = dir.join(format!("system-report-{}.{}", stamp, format.extension()));
+    crate::config::write_text_atomic(&path, &text)?;
+    tracing::info!("System report written to {}", path.display());
+    Ok(path)
+}
+
+/// Write an HTML report and open it in the default browser (status menu)
+pub fn generate_and_open() {
+    std::thread::spawn(|| match write_report(ReportFormat::Html) {
+        Ok(path) => {
+            if let Err(e) = std::process::Command::new("/usr/bin/open")
+                .arg(&path)
+                .spawn()
+            {
+                debug1!("Opening {} failed: {}", path.display(), e);
+            }
+        }
+        Err(e) => tracing::warn!("System report failed: {}", e),
+    });
+}
+
+/// Write a system report (`"html"`, the default, or `"markdown"`); returns its path
+#[tauri::command]
+pub async fn generate_system_report(format: Option<String>) -> Result<String, String> {
+    let format = match format.as_deref() {
+        None => ReportFormat::Html,
+        Some(f) => ReportFormat::parse(f)
+            .ok_or_else(|| format!("Unknown format '{}' (expected html or markdown)", f))?,
+    };
+    tokio::task::spawn_blocking(move || write_report(format))
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|path| path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sparkline_and_escaping() {
+        assert_eq!(sparkline(&[0.0, 50.0, 100.0], 100.0, 10), "▁▅█");
+        assert_eq!(sparkline(&[10.0, 30.0, 100.0, 100.0], 100.0, 2), "▂█");
+        assert_eq!(sparkline(&[], 100.0, 10), "");
+        assert_eq!(
+            escape_html("<b>\"R&D\"</b>"),
+            "&lt;b&gt;&quot;R&amp;D&quot;&lt;/b&gt;"
+        );
+        assert_eq!(ReportFormat::parse("MD"), Some(ReportFormat::Markdown));
+    }
+}
@@ -0,0 +1,189 @@
+//! Last-known readings carried over a restart
+//!
+//! On quit [`save_on_exit`] writes the newest valid `SystemMetrics` and `CpuDetails` to
+//! `~/.mac-stats/warm_start.json`; [`load`] reads them back at launch. The menu bar then starts
+//! with those numbers instead of `0%`, and `get_metrics` / `get_cpu_details` fill in from them
+//! until live values arrive (the first sample takes ~1.5s; SMC / IOReport readings come once
+//! the CPU window opens). Cached values are used for at most [`WARM_FOR`] after launch and
+//! not at all when the file is older than [`MAX_AGE_SECS`].
+
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use super::{CpuDetails, SystemMetrics};
+use crate::config::Config;
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+/// How long after launch cached values may stand in for missing live ones
+pub const WARM_FOR: Duration = Duration::from_secs(60);
+/// Files older than this are ignored (numbers from days ago would mislead)
+const MAX_AGE_SECS: i64 = 24 * 3600;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct WarmStart {
+    /// Unix time (seconds) of the write
+    saved_at: i64,
+    metrics: Option<SystemMetrics>,
+    cpu: Option<CpuDetails>,
+}
+
+/// Loaded at launch, with the time it was loaded
+static CACHED: Mutex<Option<(WarmStart, Instant)>> = Mutex::new(None);
+/// Newest valid live readings, written on quit
+static LATEST: Mutex<Option<WarmStart>> = Mutex::new(None);
+
+/// Read `warm_start.json` (once, at launch)
+pub fn load() {
+    let path = Config::warm_start_path();
+    let Some(warm) = read(&path, chrono::Utc::now().timestamp()) else {
+        return;
+    };
+    debug2!("Warm start: cached readings from {}", path.display());
+    if let Ok(mut latest) = LATEST.lock() {
+        latest.get_or_insert_with(|| warm.clone());
+    }
+    if let Ok(mut cached) = CACHED.lock() {
+        *cached = Some((warm, Instant::now()));
+    }
+}
+
+fn read(path: &Path, now: i64) -> Option<WarmStart> {
+    let text = std::fs::read_to_string(path).ok()?;
+    let warm: WarmStart = serde_json::from_str(&text)
+        .map_err(|e| debug1!("Warm start: ignoring {}: {}", path.display(), e))
+        .ok()?;
+    (now - warm.saved_at <= MAX_AGE_SECS).then_some(warm)
+}
+
+/// Write the newest readings (`RunEvent::Exit`)
+pub fn save_on_exit() {
+    let Some(mut warm) = LATEST.lock().ok().and_then(|l| l.clone()) else {
+        return;
+    };
+    warm.saved_at = chrono::Utc::now().timestamp();
+    let path = Config::warm_start_path();
+    let result = serde_json::to_string(&warm)
+        .map_err(|e| e.to_string())
+        .and_then(|json| crate::config::write_text_atomic(&path, &json));
+    if let Err(e) = result {
+        debug1!("Warm start: could not write {}: {}", path.display(), e);
+    }
+}
+
+/// Cached copy while still within [`WARM_FOR`] of launch
+fn cached() -> Option<WarmStart> {
+    let cached = CACHED.lock().ok()?;
+    let (warm, loaded_at) = cached.as_ref()?;
+    (loaded_at.elapsed() < WARM_FOR).then(|| warm.clone())
+}
+
+/// Cached metrics for the first menu bar title
+pub fn metrics() -> Option<SystemMetrics> {
+    cached()?.metrics
+}
+
+/// Keep valid live `metrics` for [`save_on_exit`] (each sample)
+pub fn remember_metrics(metrics: &SystemMetrics) {
+    if let Ok(mut latest) = LATEST.lock() {
+        latest.get_or_insert_with(empty).metrics = Some(metrics.clone());
+    }
+}
+
+/// Live metrics; the cached ones until the first valid sample
+#[tauri::command]
+pub fn get_metrics() -> SystemMetrics {
+    let live = super::get_metrics();
+    if live.is_valid() {
+        return live;
+    }
+    metrics().unwrap_or(live)
+}
+
+/// Live CPU details, with readings not taken yet (SMC / IOReport before the CPU window opened,
+/// or everything before the first sample) filled from the cached ones
+#[tauri::command]
+pub fn get_cpu_details() -> CpuDetails {
+    let mut live = super::get_cpu_details();
+    if live.usage > 0.0 {
+        if let Ok(mut latest) = LATEST.lock() {
+            let latest = latest.get_or_insert_with(empty);
+            // A run that never read the sensors keeps the last real values for the next one
+            let mut saved = live.clone();
+            if let Some(previous) = latest.cpu.as_ref() {
+                fill_sensors(&mut saved, previous);
+            }
+            latest.cpu = Some(saved);
+        }
+    }
+    match cached().and_then(|w| w.cpu) {
+        Some(cached) if live.usage == 0.0 => cached,
+        Some(cached) => {
+            fill_sensors(&mut live, &cached);
+            live
+        }
+        None => live,
+    }
+}
+
+/// Copy SMC / IOReport readings that are 0.0 in `into` (not read yet) from `from`
+fn fill_sensors(into: &mut CpuDetails, from: &CpuDetails) {
+    let fields = [
+        (&mut into.temperature, from.temperature),
+        (&mut into.gpu_temperature, from.gpu_temperature),
+        (&mut into.frequency, from.frequency),
+        (&mut into.p_core_frequency, from.p_core_frequency),
+        (&mut into.e_core_frequency, from.e_core_frequency),
+        (&mut into.cpu_power, from.cpu_power),
+        (&mut into.gpu_power, from.gpu_power),
+        (&mut into.ane_power, from.ane_power),
+    ];
+    for (value, cached) in fields {
+        if *value == 0.0 {
+            *value = cached;
+        }
+    }
+}
+
+fn empty() -> WarmStart {
+    WarmStart {
+        saved_at: 0,
+        metrics: None,
+        cpu: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignores_stale_or_broken_files() {
+        let dir = std::env::temp_dir().join(format!("mac-stats-warm-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("warm_start.json");
+        let warm = WarmStart {
+            saved_at: 1_000_000,
+            metrics: Some(SystemMetrics {
+                cpu: 12.0,
+                gpu: 3.0,
+                ram: 40.0,
+                disk: 55.0,
+                plugins: Default::default(),
+            }),
+            cpu: None,
+        };
+        std::fs::write(&path, serde_json::to_string(&warm).unwrap()).unwrap();
+
+        let read_back = read(&path, 1_000_000 + 60).expect("fresh file");
+        assert_eq!(read_back.metrics.map(|m| m.cpu), Some(12.0));
+        assert!(read(&path, 1_000_000 + MAX_AGE_SECS + 1).is_none());
+
+        std::fs::write(&path, "{not json").unwrap();
+        assert!(read(&path, 1_000_000).is_none());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
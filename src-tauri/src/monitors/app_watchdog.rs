@@ -0,0 +1,366 @@
+//! Crash/hang watchdog for apps listed in `appWatchdog`
+//!
+//! Entries are bundle identifiers, or `{"bundleId": ..., "hangMinutes": 3, "action": "alert" |
+//! "restart"}`. Every [`CHECK_INTERVAL`] each app is looked up with
+//! `NSRunningApplication runningApplicationsWithBundleIdentifier:`:
+//! - **Crash**: the app was running and is gone, and a crash report for it appeared in
+//!   `~/Library/Logs/DiagnosticReports/` since (a normal quit leaves none).
+//! - **Hang**: the app is frontmost and its process used no CPU time at all for `hangMinutes`.
+//!   A blocked main thread stops the app's timers and event handling, so even an idle app
+//!   normally accrues some CPU time while in front.
+//!
+//! Both raise a macOS notification; `restart` also relaunches the app (a hung one is killed
+//! first). Restarts are skipped in guest mode. The list is re-read every cycle.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use objc2::msg_send;
+use objc2::rc::Retained;
+use objc2::runtime::{AnyClass, AnyObject};
+use objc2_foundation::NSString;
+
+use crate::alerts::channels::{AlertChannel, NotificationChannel};
+use crate::alerts::AlertContext;
+use crate::config::Config;
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+const DEFAULT_HANG_MINUTES: u64 = 3;
+
+/// What the watchdog does when a watched app crashes or hangs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WatchdogAction {
+    #[default]
+    Alert,
+    /// Alert and relaunch (killing a hung app first)
+    Restart,
+}
+
+/// One `appWatchdog` entry
+#[derive(Debug, Clone, PartialEq)]
+pub struct WatchedApp {
+    pub bundle_id: String,
+    pub hang_secs: i64,
+    pub action: WatchdogAction,
+}
+
+fn parse_entry(value: &serde_json::Value) -> Option<WatchedApp> {
+    let (bundle_id, obj) = match value {
+        serde_json::Value::String(s) => (s.as_str(), None),
+        serde_json::Value::Object(obj) => (obj.get("bundleId")?.as_str()?, Some(obj)),
+        _ => return None,
+    };
+    let bundle_id = bundle_id.trim();
+    if bundle_id.is_empty() {
+        return None;
+    }
+    let hang_minutes = obj
+        .and_then(|o| o.get("hangMinutes"))
+        .and_then(|v| v.as_u64())
+        .unwrap_or(DEFAULT_HANG_MINUTES)
+        .clamp(1, 60);
+    let action = match obj.and_then(|o| o.get("action")).and_then(|v| v.as_str()) {
+        Some("restart") => WatchdogAction::Restart,
+        _ => WatchdogAction::Alert,
+    };
+    Some(WatchedApp {
+        bundle_id: bundle_id.to_string(),
+        hang_secs: (hang_minutes * 60) as i64,
+        action,
+    })
+}
+
+/// Parse `appWatchdog`; invalid entries are skipped
+pub fn parse_apps(value: &serde_json::Value) -> Vec<WatchedApp> {
+    value
+        .as_array()
+        .map(|list| list.iter().filter_map(parse_entry).collect())
+        .unwrap_or_default()
+}
+
+/// One look at a running watched app
+#[derive(Debug, Clone, PartialEq)]
+pub struct AppSample {
+    pub pid: u32,
+    /// Process name, for matching crash reports
+    pub name: String,
+    /// Accumulated CPU time (ms)
+    pub cpu_ms: u64,
+    pub frontmost: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WatchdogEvent {
+    /// The process with this pid and name exited and left a crash report
+    Crashed { pid: u32, name: String },
+    /// Frontmost with no CPU time for the hang interval
+    Hung { pid: u32, name: String },
+}
+
+/// What the watchdog remembers about one app between checks
+#[derive(Debug, Default)]
+pub struct AppState {
+    last: Option<AppSample>,
+    /// When the app was last seen running
+    last_seen: Option<SystemTime>,
+    /// Since when it has been frontmost without using CPU
+    stalled_since: Option<i64>,
+    hang_reported: bool,
+}
+
+impl AppState {
+    /// Feed one check; `crashed` tells whether the app left a crash report after `last_seen`
+    pub fn observe(
+        &mut self,
+        sample: Option<AppSample>,
+        now: i64,
+        hang_secs: i64,
+        crashed: impl FnOnce(&str, SystemTime) -> bool,
+    ) -> Option<WatchdogEvent> {
+        let Some(sample) = sample else {
+            let gone = self.last.take()?;
+            let since = self.last_seen.take().unwrap_or(SystemTime::UNIX_EPOCH);
+            self.stalled_since = None;
+            self.hang_reported = false;
+            return crashed(&gone.name, since).then_some(WatchdogEvent::Crashed {
+                pid: gone.pid,
+                name: gone.name,
+            });
+        };
+        let same_process = self.last.as_ref().is_some_and(|l| l.pid == sample.pid);
+        let stalled = same_process
+            && sample.frontmost
+            && self.last.as_ref().is_some_and(|l| l.cpu_ms == sample.cpu_ms);
+        let mut event = None;
+        if stalled {
+            let since = *self.stalled_since.get_or_insert(now);
+            if now - since >= hang_secs && !self.hang_reported {
+                self.hang_reported = true;
+                event = Some(WatchdogEvent::Hung {
+                    pid: sample.pid,
+                    name: sample.name.clone(),
+                });
+            }
+        } else {
+            self.stalled_since = None;
+            self.hang_reported = false;
+        }
+        self.last = Some(sample);
+        self.last_seen = Some(SystemTime::now());
+        event
+    }
+}
+
+/// Pid of the first running instance of `bundle_id`
+fn running_pid(bundle_id: &str) -> Option<u32> {
+    let cls = AnyClass::get(c"NSRunningApplication")?;
+    let id = NSString::from_str(bundle_id);
+    // SAFETY: class method taking an NSString and returning an NSArray (retained by objc2);
+    // `firstObject` returns nil for an empty array and `processIdentifier` a pid_t.
+    unsafe {
+        let apps: Option<Retained<AnyObject>> =
+            msg_send![cls, runningApplicationsWithBundleIdentifier: &*id];
+        let first: Option<Retained<AnyObject>> = msg_send![&*apps?, firstObject];
+        let pid: i32 = msg_send![&*first?, processIdentifier];
+        u32::try_from(pid).ok().filter(|p| *p > 0)
+    }
+}
+
+fn sample(sys: &mut sysinfo::System, bundle_id: &str, front_pid: Option<i32>) -> Option<AppSample> {
+    let pid = running_pid(bundle_id)?;
+    let spid = sysinfo::Pid::from_u32(pid);
+    sys.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[spid]), true);
+    let process = sys.process(spid)?;
+    Some(AppSample {
+        pid,
+        name: process.name().to_string_lossy().to_string(),
+        cpu_ms: process.accumulated_cpu_time(),
+        frontmost: front_pid == i32::try_from(pid).ok(),
+    })
+}
+
+fn crash_reports_dir() -> Option<PathBuf> {
+    std::env::var("HOME")
+        .ok()
+        .map(|home| PathBuf::from(home).join("Library/Logs/DiagnosticReports"))
+}
+
+/// Whether a crash report for process `name` (`<name>-<date>.ips`) was written after `since`
+fn crash_report_since(name: &str, since: SystemTime) -> bool {
+    let Some(dir) = crash_reports_dir() else {
+        return false;
+    };
+    let prefix = format!("{}-", name);
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .any(|e| {
+            let file_name = e.file_name();
+            let file_name = file_name.to_string_lossy();
+            file_name.starts_with(&prefix)
+                && (file_name.ends_with(".ips") || file_name.ends_with(".crash"))
+                && e.metadata()
+                    .and_then(|m| m.modified())
+                    .is_ok_and(|t| t >= since)
+        })
+}
+
+fn notify(message: &str) {
+    let context = AlertContext {
+        monitor_id: None,
+        monitor_status: None,
+        system_metrics: None,
+        cpu_details: None,
+        custom_data: HashMap::new(),
+    };
+    if let Err(e) = NotificationChannel.send(message, &context) {
+        debug1!("Watchdog notification failed: {}", e);
+    }
+}
+
+fn relaunch(bundle_id: &str) {
+    if let Err(e) = std::process::Command::new("/usr/bin/open")
+        .args(["-b", bundle_id])
+        .status()
+    {
+        tracing::warn!("Watchdog: could not relaunch {}: {}", bundle_id, e);
+    }
+}
+
+fn handle(app: &WatchedApp, event: WatchdogEvent) {
+    let restart = app.action == WatchdogAction::Restart && !crate::guest_mode::is_active();
+    match event {
+        WatchdogEvent::Crashed { name, .. } => {
+            tracing::warn!("Watchdog: {} ({}) crashed", name, app.bundle_id);
+            notify(&format!(
+                "{} crashed{}",
+                name,
+                if restart { "; relaunching" } else { "" }
+            ));
+            if restart {
+                relaunch(&app.bundle_id);
+            }
+        }
+        WatchdogEvent::Hung { pid, name } => {
+            let minutes = app.hang_secs / 60;
+            tracing::warn!(
+                "Watchdog: {} ({}) has not responded for {} min",
+                name,
+                app.bundle_id,
+                minutes
+            );
+            notify(&format!(
+                "{} is not responding ({} min){}",
+                name,
+                minutes,
+                if restart { "; restarting" } else { "" }
+            ));
+            if restart {
+                // SAFETY: plain kill(2) on the watched app's pid
+                unsafe {
+                    libc::kill(pid as libc::pid_t, libc::SIGKILL);
+                }
+                std::thread::sleep(Duration::from_secs(2));
+                relaunch(&app.bundle_id);
+            }
+        }
+    }
+}
+
+/// Start the watchdog thread; it only does work while `appWatchdog` lists apps, so apps added
+/// to the config later are picked up without a restart
+pub fn spawn_app_watchdog() {
+    std::thread::spawn(|| {
+        let mut sys = sysinfo::System::new();
+        let mut states: HashMap<String, AppState> = HashMap::new();
+        let mut watched = 0;
+        loop {
+            std::thread::sleep(CHECK_INTERVAL);
+            let apps = Config::app_watchdog();
+            if apps.len() != watched {
+                watched = apps.len();
+                tracing::info!("App watchdog: watching {} app(s)", watched);
+            }
+            states.retain(|id, _| apps.iter().any(|a| &a.bundle_id == id));
+            if apps.is_empty() {
+                continue;
+            }
+            let front_pid = crate::metrics::low_interference::frontmost_app().map(|a| a.pid);
+            let now = chrono::Utc::now().timestamp();
+            for app in &apps {
+                let current = sample(&mut sys, &app.bundle_id, front_pid);
+                let event = states.entry(app.bundle_id.clone()).or_default().observe(
+                    current,
+                    now,
+                    app.hang_secs,
+                    crash_report_since,
+                );
+                if let Some(event) = event {
+                    handle(app, event);
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(pid: u32, cpu_ms: u64, frontmost: bool) -> Option<AppSample> {
+        Some(AppSample {
+            pid,
+            name: "Xcode".to_string(),
+            cpu_ms,
+            frontmost,
+        })
+    }
+
+    #[test]
+    fn detects_hangs_and_crashes() {
+        let apps = parse_apps(&serde_json::json!([
+            "com.apple.dt.Xcode",
+            {"bundleId": "com.example.Tool", "hangMinutes": 0, "action": "restart"},
+            {"hangMinutes": 5},
+            " "
+        ]));
+        assert_eq!(apps.len(), 2);
+        assert_eq!(apps[0].hang_secs, 180);
+        assert_eq!(apps[1].hang_secs, 60);
+        assert_eq!(apps[1].action, WatchdogAction::Restart);
+
+        let mut s = AppState::default();
+        let never = |_: &str, _: SystemTime| false;
+        assert_eq!(s.observe(at(7, 100, true), 0, 60, never), None);
+        // Busy in front, then stalled in front for a minute: one hang event
+        assert_eq!(s.observe(at(7, 150, true), 15, 60, never), None);
+        assert_eq!(s.observe(at(7, 150, true), 30, 60, never), None);
+        assert_eq!(
+            s.observe(at(7, 150, true), 90, 60, never),
+            Some(WatchdogEvent::Hung {
+                pid: 7,
+                name: "Xcode".to_string()
+            })
+        );
+        assert_eq!(s.observe(at(7, 150, true), 200, 60, never), None);
+        // Idle in the background is fine
+        assert_eq!(s.observe(at(7, 150, false), 300, 60, never), None);
+        assert_eq!(s.observe(at(7, 150, false), 900, 60, never), None);
+
+        // Gone without a crash report: quit; with one: crash
+        assert_eq!(s.observe(None, 915, 60, never), None);
+        s.observe(at(9, 10, false), 930, 60, never);
+        assert_eq!(
+            s.observe(None, 945, 60, |name, _| name == "Xcode"),
+            Some(WatchdogEvent::Crashed {
+                pid: 9,
+                name: "Xcode".to_string()
+            })
+        );
+        assert_eq!(s.observe(None, 960, 60, |_, _| true), None);
+    }
+}
@@ -4,7 +4,9 @@
 //! - Websites (HTTP/HTTPS uptime, response times, SSL errors)
 //! - Social media (Mastodon/X mentions)
 //! - APIs (custom endpoints)
+//! - Local apps (crash/hang watchdog)
 
+pub mod app_watchdog;
 pub mod social;
 pub mod website;
 
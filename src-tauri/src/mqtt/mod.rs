@@ -0,0 +1,423 @@
+//! MQTT publisher with Home Assistant discovery
+//!
+//! When `mqtt.enabled` is set, a background thread connects to the broker and publishes the
+//! newest history sample every `intervalSecs` to `<topicPrefix>/<node>/<metric>` (QoS 0).
+//! On each connect it announces `online` on `<topicPrefix>/<node>/status` (the broker
+//! publishes the retained last will `offline` if we disappear) and, unless `discovery` is
+//! off, retained Home Assistant sensor configs so the metrics show up as one device.
+//! Machine `labels` are published as a retained JSON object on `<topicPrefix>/<node>/labels`
+//! and linked as the sensors' attributes.
+//! Connection failures reconnect with exponential backoff.
+
+mod packet;
+
+use std::io::Read;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+use crate::config::{Config, MqttSettings};
+use crate::metrics::history::MetricPoint;
+use crate::metrics::latest_history_point;
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+const KEEP_ALIVE_SECS: u16 = 60;
+const IO_TIMEOUT: Duration = Duration::from_secs(10);
+const RETRY_BASE_SECS: u64 = 5;
+const RETRY_MAX_SECS: u64 = 300;
+
+/// One published sensor: topic suffix, display name, unit, Home Assistant device class
+struct Sensor {
+    key: &'static str,
+    name: &'static str,
+    unit: &'static str,
+    device_class: Option<&'static str>,
+}
+
+const SENSORS: &[Sensor] = &[
+    Sensor {
+        key: "cpu",
+        name: "CPU usage",
+        unit: "%",
+        device_class: None,
+    },
+    Sensor {
+        key: "gpu",
+        name: "GPU usage",
+        unit: "%",
+        device_class: None,
+    },
+    Sensor {
+        key: "ram",
+        name: "Memory usage",
+        unit: "%",
+        device_class: None,
+    },
+    Sensor {
+        key: "disk",
+        name: "Disk usage",
+        unit: "%",
+        device_class: None,
+    },
+    Sensor {
+        key: "temperature",
+        name: "Temperature",
+        unit: "°C",
+        device_class: Some("temperature"),
+    },
+    Sensor {
+        key: "cpu_power",
+        name: "CPU power",
+        unit: "W",
+        device_class: Some("power"),
+    },
+    Sensor {
+        key: "gpu_power",
+        name: "GPU power",
+        unit: "W",
+        device_class: Some("power"),
+    },
+    Sensor {
+        key: "battery",
+        name: "Battery",
+        unit: "%",
+        device_class: Some("battery"),
+    },
+    Sensor {
+        key: "net_rx",
+        name: "Network in",
+        unit: "B/s",
+        device_class: Some("data_rate"),
+    },
+    Sensor {
+        key: "net_tx",
+        name: "Network out",
+        unit: "B/s",
+        device_class: Some("data_rate"),
+    },
+    Sensor {
+        key: "disk_read",
+        name: "Disk read",
+        unit: "B/s",
+        device_class: Some("data_rate"),
+    },
+    Sensor {
+        key: "disk_write",
+        name: "Disk write",
+        unit: "B/s",
+        device_class: Some("data_rate"),
+    },
+];
+
+/// Host name reduced to `[a-z0-9_-]` so it is safe in topics and Home Assistant ids
+fn sanitize_node_id(raw: &str) -> String {
+    let id: String = raw
+        .trim()
+        .trim_end_matches(".local")
+        .chars()
+        .map(|c| match c.to_ascii_lowercase() {
+            c @ ('a'..='z' | '0'..='9' | '_' | '-') => c,
+            _ => '_',
+        })
+        .collect();
+    let id = id.trim_matches('_').to_string();
+    if id.is_empty() {
+        "mac".to_string()
+    } else {
+        id
+    }
+}
+
+/// Values for one sample; sensors that were not readable are left out
+fn sensor_values(point: &MetricPoint) -> Vec<(&'static str, f32)> {
+    let mut values = vec![
+        ("cpu", point.cpu),
+        ("gpu", point.gpu),
+        ("ram", point.ram),
+        ("disk", point.disk),
+    ];
+    for (key, value) in [
+        ("temperature", point.temperature),
+        ("cpu_power", point.cpu_power),
+        ("gpu_power", point.gpu_power),
+    ] {
+        if value > 0.0 {
+            values.push((key, value));
+        }
+    }
+    if point.battery_level >= 0.0 {
+        values.push(("battery", point.battery_level));
+    }
+    values.extend([
+        ("net_rx", point.net_rx),
+        ("net_tx", point.net_tx),
+        ("disk_read", point.disk_read),
+        ("disk_write", point.disk_write),
+    ]);
+    values
+}
+
+/// Topic names for one node
+struct Topics {
+    base: String,
+    status: String,
+    labels: String,
+}
+
+impl Topics {
+    fn new(prefix: &str, node: &str) -> Self {
+        let base = format!("{}/{}", prefix, node);
+        Self {
+            status: format!("{}/status", base),
+            labels: format!("{}/labels", base),
+            base,
+        }
+    }
+
+    fn state(&self, key: &str) -> String {
+        format!("{}/{}", self.base, key)
+    }
+}
+
+/// Retained discovery config for one sensor: (topic, JSON payload)
+fn discovery_config(
+    sensor: &Sensor,
+    settings: &MqttSettings,
+    node: &str,
+    topics: &Topics,
+    labels: &[(String, String)],
+) -> (String, String) {
+    let mut config = serde_json::json!({
+        "name": sensor.name,
+        "unique_id": format!("mac_stats_{}_{}", node, sensor.key),
+        "state_topic": topics.state(sensor.key),
+        "unit_of_measurement": sensor.unit,
+        "state_class": "measurement",
+        "availability_topic": topics.status,
+        "device": {
+            "identifiers": [format!("mac_stats_{}", node)],
+            "name": format!("mac-stats {}", node),
+            "manufacturer": "Apple",
+            "sw_version": Config::version(),
+        },
+    });
+    if let Some(class) = sensor.device_class {
+        config["device_class"] = serde_json::Value::from(class);
+    }
+    if !labels.is_empty() {
+        config["json_attributes_topic"] = serde_json::Value::from(topics.labels.as_str());
+    }
+    (
+        format!(
+            "{}/sensor/{}/{}/config",
+            settings.discovery_prefix, node, sensor.key
+        ),
+        config.to_string(),
+    )
+}
+
+struct Connection {
+    stream: TcpStream,
+    last_write: Instant,
+}
+
+impl Connection {
+    fn open(settings: &MqttSettings, client_id: &str, topics: &Topics) -> Result<Self, String> {
+        let addr = (settings.host.as_str(), settings.port)
+            .to_socket_addrs()
+            .map_err(|e| format!("Cannot resolve {}: {}", settings.host, e))?
+            .next()
+            .ok_or_else(|| format!("No address for {}", settings.host))?;
+        let mut stream = TcpStream::connect_timeout(&addr, IO_TIMEOUT).map_err(|e| {
+            format!(
+                "Connect to {}:{} failed: {}",
+                settings.host, settings.port, e
+            )
+        })?;
+        stream.set_read_timeout(Some(IO_TIMEOUT)).ok();
+        stream.set_write_timeout(Some(IO_TIMEOUT)).ok();
+        let connect = packet::connect(&packet::Connect {
+            client_id,
+            keep_alive_secs: KEEP_ALIVE_SECS,
+            username: settings.username.as_deref(),
+            password: settings.password.as_deref(),
+            will: Some((&topics.status, "offline")),
+        });
+        packet::write_packet(&mut stream, &connect).map_err(|e| e.to_string())?;
+        packet::read_connack(&mut stream)?;
+        Ok(Self {
+            stream,
+            last_write: Instant::now(),
+        })
+    }
+
+    fn publish(&mut self, topic: &str, payload: &str, retain: bool) -> Result<(), String> {
+        packet::write_packet(
+            &mut self.stream,
+            &packet::publish(topic, payload.as_bytes(), retain),
+        )
+        .map_err(|e| format!("Publish to {} failed: {}", topic, e))?;
+        self.last_write = Instant::now();
+        Ok(())
+    }
+
+    /// Ping when idle for most of the keep-alive so the broker does not drop us
+    fn keep_alive(&mut self) -> Result<(), String> {
+        if self.last_write.elapsed() < Duration::from_secs(u64::from(KEEP_ALIVE_SECS) * 3 / 4) {
+            return Ok(());
+        }
+        packet::write_packet(&mut self.stream, &packet::PINGREQ).map_err(|e| e.to_string())?;
+        let mut resp = [0u8; 2];
+        self.stream
+            .read_exact(&mut resp)
+            .map_err(|e| format!("No PINGRESP: {}", e))?;
+        if resp != packet::PINGRESP {
+            return Err(format!(
+                "Unexpected packet instead of PINGRESP: {:02x?}",
+                resp
+            ));
+        }
+        self.last_write = Instant::now();
+        Ok(())
+    }
+}
+
+/// Connect, announce, then publish until the connection fails
+fn run_session(settings: &MqttSettings, node: &str, client_id: &str) -> Result<(), String> {
+    let topics = Topics::new(&settings.topic_prefix, node);
+    let mut conn = Connection::open(settings, client_id, &topics)?;
+    tracing::info!(
+        "MQTT: connected to {}:{} as {}",
+        settings.host,
+        settings.port,
+        client_id
+    );
+    conn.publish(&topics.status, "online", true)?;
+    let labels = Config::labels();
+    if !labels.is_empty() {
+        let payload: serde_json::Map<String, serde_json::Value> = labels
+            .iter()
+            .map(|(k, v)| (k.clone(), serde_json::Value::from(v.as_str())))
+            .collect();
+        conn.publish(
+            &topics.labels,
+            &serde_json::Value::Object(payload).to_string(),
+            true,
+        )?;
+    }
+    if settings.discovery {
+        for sensor in SENSORS {
+            let (topic, payload) = discovery_config(sensor, settings, node, &topics, &labels);
+            conn.publish(&topic, &payload, true)?;
+        }
+    }
+    let interval = Duration::from_secs(settings.interval_secs);
+    let mut last_timestamp = None;
+    loop {
+        if let Some(point) = latest_history_point() {
+            if last_timestamp != Some(point.timestamp) {
+                last_timestamp = Some(point.timestamp);
+                for (key, value) in sensor_values(&point) {
+                    conn.publish(
+                        &topics.state(key),
+                        &format!("{:.1}", value),
+                        settings.retain,
+                    )?;
+                }
+                debug3!("MQTT: published sample {}", point.timestamp);
+            }
+        }
+        conn.keep_alive()?;
+        std::thread::sleep(interval);
+    }
+}
+
+fn publish_loop(settings: MqttSettings) {
+    let node = settings
+        .node_id
+        .clone()
+        .unwrap_or_else(|| sanitize_node_id(&sysinfo::System::host_name().unwrap_or_default()));
+    let client_id = settings
+        .client_id
+        .clone()
+        .unwrap_or_else(|| format!("mac-stats-{}", node));
+    let mut retry_secs = RETRY_BASE_SECS;
+    loop {
+        let started = Instant::now();
+        if let Err(e) = run_session(&settings, &node, &client_id) {
+            tracing::warn!("MQTT: {} (retrying in {}s)", e, retry_secs);
+        }
+        // A session that stayed up for a while resets the backoff
+        if started.elapsed() > Duration::from_secs(RETRY_MAX_SECS) {
+            retry_secs = RETRY_BASE_SECS;
+        }
+        std::thread::sleep(Duration::from_secs(retry_secs));
+        retry_secs = (retry_secs * 2).min(RETRY_MAX_SECS);
+    }
+}
+
+/// Start the MQTT publisher thread when `mqtt.enabled` is set
+pub fn spawn_mqtt_publisher_if_enabled() {
+    let settings = Config::mqtt_settings();
+    if !settings.enabled {
+        return;
+    }
+    tracing::info!(
+        "MQTT: publishing every {}s to {}:{}",
+        settings.interval_secs,
+        settings.host,
+        settings.port
+    );
+    std::thread::spawn(move || publish_loop(settings));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitizes_node_id() {
+        assert_eq!(
+            sanitize_node_id("Janes-MacBook-Pro.local"),
+            "janes-macbook-pro"
+        );
+        assert_eq!(sanitize_node_id("Jane's Mac"), "jane_s_mac");
+        assert_eq!(sanitize_node_id("  "), "mac");
+    }
+
+    #[test]
+    fn skips_unreadable_sensors() {
+        let p =
+            MetricPoint::from_metrics(12.5, 3.0, 61.0, 48.0, 0.0, 3.2, 0.0, 0.0, 4.5, 0.0, -1.0);
+        let keys: Vec<_> = sensor_values(&p).into_iter().map(|(k, _)| k).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "cpu",
+                "gpu",
+                "ram",
+                "disk",
+                "cpu_power",
+                "net_rx",
+                "net_tx",
+                "disk_read",
+                "disk_write"
+            ]
+        );
+    }
+
+    #[test]
+    fn discovery_config_links_state_and_availability() {
+        let settings = MqttSettings::default();
+        let topics = Topics::new(&settings.topic_prefix, "mac1");
+        let labels = [("role".to_string(), "build".to_string())];
+        let (topic, payload) = discovery_config(&SENSORS[4], &settings, "mac1", &topics, &labels);
+        assert_eq!(topic, "homeassistant/sensor/mac1/temperature/config");
+        let v: serde_json::Value = serde_json::from_str(&payload).unwrap();
+        assert_eq!(v["state_topic"], "macstats/mac1/temperature");
+        assert_eq!(v["availability_topic"], "macstats/mac1/status");
+        assert_eq!(v["device_class"], "temperature");
+        assert_eq!(v["unique_id"], "mac_stats_mac1_temperature");
+        assert_eq!(v["json_attributes_topic"], "macstats/mac1/labels");
+    }
+}
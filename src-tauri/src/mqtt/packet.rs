@@ -0,0 +1,174 @@
+//! MQTT 3.1.1 packet encoding (publisher side only)
+//!
+//! Covers what a QoS 0 publisher needs: CONNECT (with last will), CONNACK, PUBLISH,
+//! PINGREQ and PINGRESP.
+
+use std::io::{Read, Write};
+
+pub const PINGREQ: [u8; 2] = [0xC0, 0x00];
+pub const PINGRESP: [u8; 2] = [0xD0, 0x00];
+
+/// CONNECT options
+pub struct Connect<'a> {
+    pub client_id: &'a str,
+    pub keep_alive_secs: u16,
+    pub username: Option<&'a str>,
+    pub password: Option<&'a str>,
+    /// Last will: (topic, payload), published retained by the broker if we vanish
+    pub will: Option<(&'a str, &'a str)>,
+}
+
+/// Variable-length "remaining length" (1–4 bytes, 7 bits each)
+fn encode_remaining_length(mut len: usize, out: &mut Vec<u8>) {
+    loop {
+        let mut byte = (len % 128) as u8;
+        len /= 128;
+        if len > 0 {
+            byte |= 0x80;
+        }
+        out.push(byte);
+        if len == 0 {
+            break;
+        }
+    }
+}
+
+/// Length-prefixed UTF-8 string, cut to the `u16` limit at a char boundary so it stays valid
+fn push_str(s: &str, out: &mut Vec<u8>) {
+    let mut end = s.len().min(u16::MAX as usize);
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    let bytes = s[..end].as_bytes();
+    out.extend_from_slice(&(bytes.len() as u16).to_be_bytes());
+    out.extend_from_slice(bytes);
+}
+
+fn packet(first_byte: u8, body: Vec<u8>) -> Vec<u8> {
+    let mut out = vec![first_byte];
+    encode_remaining_length(body.len(), &mut out);
+    out.extend(body);
+    out
+}
+
+pub fn connect(c: &Connect) -> Vec<u8> {
+    let mut flags = 0x02; // clean session
+    if c.will.is_some() {
+        flags |= 0x04 | 0x20; // will flag, will retain (QoS 0)
+    }
+    if c.username.is_some() {
+        flags |= 0x80;
+        if c.password.is_some() {
+            flags |= 0x40;
+        }
+    }
+    let mut body = Vec::new();
+    push_str("MQTT", &mut body);
+    body.push(4); // protocol level 3.1.1
+    body.push(flags);
+    body.extend_from_slice(&c.keep_alive_secs.to_be_bytes());
+    push_str(c.client_id, &mut body);
+    if let Some((topic, message)) = c.will {
+        push_str(topic, &mut body);
+        push_str(message, &mut body);
+    }
+    if let Some(username) = c.username {
+        push_str(username, &mut body);
+        if let Some(password) = c.password {
+            push_str(password, &mut body);
+        }
+    }
+    packet(0x10, body)
+}
+
+/// QoS 0 PUBLISH
+pub fn publish(topic: &str, payload: &[u8], retain: bool) -> Vec<u8> {
+    let mut body = Vec::with_capacity(topic.len() + payload.len() + 2);
+    push_str(topic, &mut body);
+    body.extend_from_slice(payload);
+    packet(0x30 | u8::from(retain), body)
+}
+
+/// Read CONNACK; `Err` with the broker's reason when the connection is refused.
+pub fn read_connack(stream: &mut impl Read) -> Result<(), String> {
+    let mut buf = [0u8; 4];
+    stream
+        .read_exact(&mut buf)
+        .map_err(|e| format!("No CONNACK: {}", e))?;
+    if buf[0] != 0x20 || buf[1] != 0x02 {
+        return Err(format!(
+            "Unexpected packet instead of CONNACK: {:02x?}",
+            buf
+        ));
+    }
+    match buf[3] {
+        0 => Ok(()),
+        1 => Err("Broker refused connection: unacceptable protocol version".to_string()),
+        2 => Err("Broker refused connection: client id rejected".to_string()),
+        3 => Err("Broker refused connection: server unavailable".to_string()),
+        4 => Err("Broker refused connection: bad username or password".to_string()),
+        5 => Err("Broker refused connection: not authorized".to_string()),
+        rc => Err(format!("Broker refused connection (code {})", rc)),
+    }
+}
+
+pub fn write_packet(stream: &mut impl Write, bytes: &[u8]) -> std::io::Result<()> {
+    stream.write_all(bytes)?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remaining_length_uses_continuation_bits() {
+        for (len, expected) in [
+            (0usize, vec![0x00]),
+            (127, vec![0x7F]),
+            (128, vec![0x80, 0x01]),
+            (16_383, vec![0xFF, 0x7F]),
+            (16_384, vec![0x80, 0x80, 0x01]),
+        ] {
+            let mut out = Vec::new();
+            encode_remaining_length(len, &mut out);
+            assert_eq!(out, expected, "length {}", len);
+        }
+    }
+
+    #[test]
+    fn encodes_connect_with_will_and_credentials() {
+        let bytes = connect(&Connect {
+            client_id: "mac",
+            keep_alive_secs: 60,
+            username: Some("u"),
+            password: Some("p"),
+            will: Some(("s", "offline")),
+        });
+        let mut expected = vec![0x10, 33, 0, 4, b'M', b'Q', b'T', b'T', 4, 0xE6, 0, 60];
+        expected.extend_from_slice(&[0, 3, b'm', b'a', b'c', 0, 1, b's', 0, 7]);
+        expected.extend_from_slice(b"offline");
+        expected.extend_from_slice(&[0, 1, b'u', 0, 1, b'p']);
+        assert_eq!(bytes, expected);
+    }
+
+    #[test]
+    fn encodes_publish_and_reads_connack() {
+        assert_eq!(
+            publish("a/b", b"12.5", true),
+            vec![0x31, 9, 0, 3, b'a', b'/', b'b', b'1', b'2', b'.', b'5']
+        );
+        assert!(read_connack(&mut &[0x20, 0x02, 0x00, 0x00][..]).is_ok());
+        let refused = read_connack(&mut &[0x20, 0x02, 0x00, 0x04][..]).unwrap_err();
+        assert!(refused.contains("bad username or password"));
+    }
+
+    #[test]
+    fn truncates_long_strings_at_a_char_boundary() {
+        let long = format!("{}é", "a".repeat(u16::MAX as usize - 1));
+        let mut out = Vec::new();
+        push_str(&long, &mut out);
+        assert_eq!(&out[..2], &(u16::MAX - 1).to_be_bytes());
+        assert!(std::str::from_utf8(&out[2..]).is_ok());
+    }
+}
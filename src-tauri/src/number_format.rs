@@ -0,0 +1,196 @@
+//! Locale-aware number and percent formatting
+//!
+//! One formatting service shared by the menu bar and exports so both follow the user's
+//! locale: decimal separator (`12,5` vs `12.5`), percent placement (`45%`, `45 %`, `%45`)
+//! and bidi isolation for right-to-left locales, so `45%` is not reordered inside Arabic or
+//! Hebrew UI text.
+//!
+//! The locale comes from `numberLocale` in `config.json` when set, otherwise from the macOS
+//! `AppleLocale` preference (read once), then `LANG`.
+
+use std::sync::OnceLock;
+
+/// Left-to-right isolate / pop directional isolate (Unicode bidi controls)
+const LRI: char = '\u{2066}';
+const PDI: char = '\u{2069}';
+
+/// Languages that use a decimal comma (regional exceptions handled in [`NumberFormat::for_locale`])
+const COMMA_DECIMAL_LANGS: &[&str] = &[
+    "bg", "ca", "cs", "da", "de", "el", "es", "et", "eu", "fa", "fi", "fr", "gl", "hr", "hu", "id",
+    "it", "lt", "lv", "nb", "nl", "nn", "no", "pl", "pt", "ro", "ru", "sk", "sl", "sr", "sv", "tr",
+    "uk", "vi",
+];
+
+/// Languages that separate the percent sign from the number (`45 %`)
+const SPACED_PERCENT_LANGS: &[&str] = &[
+    "bg", "ca", "cs", "da", "de", "et", "fi", "fr", "hu", "lt", "lv", "nb", "nn", "no", "pl", "ru",
+    "sk", "sv", "uk",
+];
+
+/// Where the percent sign goes relative to the number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PercentStyle {
+    /// `45%` (en, ja, zh, …)
+    Suffix,
+    /// `45 %` with a narrow no-break space (de, fr, sv, …)
+    SpacedSuffix,
+    /// `%45` (tr)
+    Prefix,
+}
+
+/// Number formatting conventions for one locale
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NumberFormat {
+    pub locale: String,
+    pub decimal_separator: char,
+    pub percent_style: PercentStyle,
+    pub rtl: bool,
+}
+
+impl NumberFormat {
+    /// Conventions for a locale tag such as `de_CH`, `fr-FR`, `ar` or `en_US.UTF-8`.
+    pub fn for_locale(tag: &str) -> Self {
+        let tag = tag.split(['.', '@']).next().unwrap_or("").trim();
+        let mut parts = tag.split(['_', '-']);
+        let lang = parts.next().unwrap_or("").to_ascii_lowercase();
+        let region = parts
+            .find(|p| p.len() == 2 && p.chars().all(|c| c.is_ascii_alphabetic()))
+            .unwrap_or("")
+            .to_ascii_uppercase();
+
+        let comma_decimal = COMMA_DECIMAL_LANGS.contains(&lang.as_str())
+            && !(lang == "de" && matches!(region.as_str(), "CH" | "LI"))
+            && !(lang == "es" && matches!(region.as_str(), "MX" | "US"));
+
+        let percent_style = if lang == "tr" {
+            PercentStyle::Prefix
+        } else if SPACED_PERCENT_LANGS.contains(&lang.as_str()) {
+            PercentStyle::SpacedSuffix
+        } else {
+            PercentStyle::Suffix
+        };
+
+        Self {
+            locale: if tag.is_empty() {
+                "en_US".to_string()
+            } else {
+                tag.to_string()
+            },
+            decimal_separator: if comma_decimal { ',' } else { '.' },
+            percent_style,
+            rtl: matches!(
+                lang.as_str(),
+                "ar" | "he" | "iw" | "fa" | "ur" | "ps" | "yi"
+            ),
+        }
+    }
+
+    /// Format for the configured (or system) locale.
+    pub fn current() -> Self {
+        Self::for_locale(&crate::config::Config::number_locale().unwrap_or_else(system_locale))
+    }
+
+    /// `value` with `decimals` fraction digits and the locale decimal separator (no grouping,
+    /// so output stays machine-friendly in exports).
+    pub fn number(&self, value: f64, decimals: usize) -> String {
+        let s = format!("{:.*}", decimals, value);
+        let s = if s.starts_with('-') && s.trim_start_matches(['-', '0', '.']).is_empty() {
+            s[1..].to_string() // avoid "-0"
+        } else {
+            s
+        };
+        if self.decimal_separator == '.' {
+            s
+        } else {
+            s.replace('.', &self.decimal_separator.to_string())
+        }
+    }
+
+    /// `value` as a percent string (`45%`, `45 %`, `%45`), bidi-isolated for RTL locales.
+    pub fn percent(&self, value: f64, decimals: usize) -> String {
+        let n = self.number(value, decimals);
+        let s = match self.percent_style {
+            PercentStyle::Suffix => format!("{n}%"),
+            PercentStyle::SpacedSuffix => format!("{n}\u{202f}%"),
+            PercentStyle::Prefix => format!("%{n}"),
+        };
+        self.isolate(&s)
+    }
+
+    /// `value` followed by a unit that is never spaced (e.g. `°`), bidi-isolated for RTL.
+    pub fn with_unit(&self, value: f64, decimals: usize, unit: &str) -> String {
+        self.isolate(&format!("{}{}", self.number(value, decimals), unit))
+    }
+
+    /// Wrap `s` in an LTR isolate when the locale is right-to-left.
+    pub fn isolate(&self, s: &str) -> String {
+        if self.rtl {
+            format!("{LRI}{s}{PDI}")
+        } else {
+            s.to_string()
+        }
+    }
+}
+
+/// macOS `AppleLocale` (e.g. `de_CH`), falling back to `LANG`, then `en_US`. Read once.
+pub fn system_locale() -> String {
+    static SYSTEM_LOCALE: OnceLock<String> = OnceLock::new();
+    SYSTEM_LOCALE
+        .get_or_init(|| {
+            let from_defaults = std::process::Command::new("/usr/bin/defaults")
+                .args(["read", "-g", "AppleLocale"])
+                .stderr(std::process::Stdio::null())
+                .output()
+                .ok()
+                .filter(|o| o.status.success())
+                .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+                .filter(|s| !s.is_empty());
+            from_defaults
+                .or_else(|| {
+                    std::env::var("LANG")
+                        .ok()
+                        .filter(|s| !s.is_empty() && s != "C")
+                })
+                .unwrap_or_else(|| "en_US".to_string())
+        })
+        .clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn english_uses_point_and_plain_suffix() {
+        let f = NumberFormat::for_locale("en_US.UTF-8");
+        assert_eq!(f.percent(45.26, 1), "45.3%");
+        assert_eq!(f.number(3.0, 0), "3");
+    }
+
+    #[test]
+    fn german_uses_comma_and_spaced_percent() {
+        let f = NumberFormat::for_locale("de_DE");
+        assert_eq!(f.percent(12.5, 1), "12,5\u{202f}%");
+        // Swiss German keeps the decimal point
+        assert_eq!(NumberFormat::for_locale("de_CH").number(12.5, 1), "12.5");
+    }
+
+    #[test]
+    fn turkish_prefixes_percent() {
+        assert_eq!(NumberFormat::for_locale("tr_TR").percent(7.0, 0), "%7");
+    }
+
+    #[test]
+    fn rtl_locales_isolate_values() {
+        let f = NumberFormat::for_locale("ar_SA");
+        assert!(f.rtl);
+        assert_eq!(f.percent(50.0, 0), "\u{2066}50%\u{2069}");
+        assert_eq!(f.with_unit(61.0, 0, "°"), "\u{2066}61°\u{2069}");
+    }
+
+    #[test]
+    fn negative_zero_is_normalized() {
+        assert_eq!(NumberFormat::for_locale("en").number(-0.01, 0), "0");
+        assert_eq!(NumberFormat::for_locale("en").number(-1.5, 1), "-1.5");
+    }
+}
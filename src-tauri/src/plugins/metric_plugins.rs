@@ -0,0 +1,319 @@
+//! Metric plugins from `~/.mac-stats/plugins/`
+//!
+//! Every executable file (or `.sh` / `.py` script) there is a plugin named after its file
+//! stem. A `<name>.json` descriptor, `{"script": "queue.py", "intervalSecs": 30,
+//! "timeoutSecs": 10}`, configures one instead (`script` relative to the directory and inside it;
+//! the script then does not also run on its own). A run prints the [`PluginOutput`] JSON, of which only
+//! `metrics` is used: `{"metrics": {"depth": 12, "lag_ms": 3.5}}`. The numbers become
+//! `<plugin>.<metric>` (`queue.depth`) in `SystemMetrics::plugins`, the menu bar template
+//! (`{queue.depth}`) and history. A plugin's values are dropped after three intervals without a
+//! successful run. The directory is rescanned every [`TICK`], so plugins can be added or
+//! removed while the app runs. Each plugin runs in its own task on the collector runtime
+//! (`background`), with the script on the blocking pool, so a slow or hung script only delays
+//! its own samples. Nothing runs in guest mode.
+//!
+//! [`PluginOutput`]: super::PluginOutput
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use super::Plugin;
+use crate::config::Config;
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+const TICK: Duration = Duration::from_secs(5);
+const DEFAULT_INTERVAL_SECS: u64 = 60;
+const DEFAULT_TIMEOUT_SECS: u64 = 10;
+
+/// One plugin found in the directory
+#[derive(Debug, Clone, PartialEq)]
+struct PluginSpec {
+    id: String,
+    script: PathBuf,
+    interval_secs: u64,
+    timeout_secs: u64,
+}
+
+/// Plugin id from a file name: the stem, with dots replaced (they separate plugin and metric)
+fn plugin_id(path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_str()?.trim();
+    (!stem.is_empty() && !stem.starts_with('.')).then(|| stem.replace('.', "_"))
+}
+
+fn parse_descriptor(id: String, dir: &Path, json: &serde_json::Value) -> Option<PluginSpec> {
+    let script = json.get("script")?.as_str()?.trim();
+    if script.is_empty() {
+        return None;
+    }
+    // Only scripts inside the directory: no absolute paths, `..` or symlinks out of it
+    let path = dir.join(script).canonicalize().ok()?;
+    if !dir.canonicalize().is_ok_and(|d| path.starts_with(d)) {
+        debug1!(
+            "Plugin {}: script {} is outside the plugins directory",
+            id,
+            script
+        );
+        return None;
+    }
+    let secs = |key: &str, default: u64, max: u64| {
+        json.get(key)
+            .and_then(|v| v.as_u64())
+            .unwrap_or(default)
+            .clamp(1, max)
+    };
+    Some(PluginSpec {
+        id,
+        script: path,
+        interval_secs: secs("intervalSecs", DEFAULT_INTERVAL_SECS, 86_400).max(TICK.as_secs()),
+        timeout_secs: secs("timeoutSecs", DEFAULT_TIMEOUT_SECS, 300),
+    })
+}
+
+fn is_runnable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    let Ok(meta) = std::fs::metadata(path) else {
+        return false;
+    };
+    let script = matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("sh" | "bash" | "py")
+    );
+    meta.is_file() && (script || meta.permissions().mode() & 0o111 != 0)
+}
+
+/// Plugins in `dir`: descriptors first, then runnable files no descriptor points at
+fn discover(dir: &Path) -> Vec<PluginSpec> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let paths: Vec<PathBuf> = entries.flatten().map(|e| e.path()).collect();
+    let mut specs: Vec<PluginSpec> = paths
+        .iter()
+        .filter(|p| p.extension().is_some_and(|e| e == "json"))
+        .filter_map(|p| {
+            let json = std::fs::read_to_string(p)
+                .ok()
+                .and_then(|c| serde_json::from_str(&c).ok());
+            let spec = plugin_id(p)
+                .zip(json)
+                .and_then(|(id, json)| parse_descriptor(id, dir, &json));
+            if spec.is_none() {
+                debug1!("Plugin descriptor {} is invalid; skipped", p.display());
+            }
+            spec
+        })
+        .collect();
+    let claimed: Vec<PathBuf> = specs.iter().map(|s| s.script.clone()).collect();
+    for path in &paths {
+        let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
+        if path.extension().is_some_and(|e| e == "json") || claimed.contains(&canonical) {
+            continue;
+        }
+        let Some(id) = plugin_id(path) else {
+            continue;
+        };
+        if is_runnable(path) && !specs.iter().any(|s| s.id == id) {
+            specs.push(PluginSpec {
+                id,
+                script: path.clone(),
+                interval_secs: DEFAULT_INTERVAL_SECS,
+                timeout_secs: DEFAULT_TIMEOUT_SECS,
+            });
+        }
+    }
+    specs.sort_by(|a, b| a.id.cmp(&b.id));
+    specs
+}
+
+/// Numeric entries of a plugin's `metrics` object (booleans count as 1/0)
+fn metric_values(metrics: &serde_json::Value) -> BTreeMap<String, f32> {
+    metrics
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(name, _)| {
+            !name.is_empty() && !name.contains(|c: char| c.is_whitespace() || c == '{' || c == '}')
+        })
+        .filter_map(|(name, v)| {
+            let value = v
+                .as_f64()
+                .or_else(|| v.as_bool().map(|b| if b { 1.0 } else { 0.0 }))?;
+            value.is_finite().then(|| (name.clone(), value as f32))
+        })
+        .collect()
+}
+
+struct Reported {
+    values: BTreeMap<String, f32>,
+    at: Instant,
+    stale_after: Duration,
+}
+
+static REPORTED: Mutex<BTreeMap<String, Reported>> = Mutex::new(BTreeMap::new());
+
+/// Current plugin values as `<plugin>.<metric>`; empty without plugins
+pub(crate) fn current() -> BTreeMap<String, f32> {
+    let Ok(reported) = REPORTED.try_lock() else {
+        return BTreeMap::new();
+    };
+    reported
+        .iter()
+        .filter(|(_, r)| r.at.elapsed() < r.stale_after)
+        .flat_map(|(id, r)| {
+            r.values
+                .iter()
+                .map(move |(name, value)| (format!("{}.{}", id, name), *value))
+        })
+        .collect()
+}
+
+fn run(spec: &PluginSpec) {
+    let mut plugin = Plugin::new(spec.id.clone(), spec.id.clone(), spec.script.clone());
+    plugin.timeout_secs = spec.timeout_secs;
+    let output = match plugin.execute() {
+        Ok(result) if result.output.status != "error" => result.output,
+        Ok(result) => {
+            debug1!(
+                "Plugin {} failed: {}",
+                spec.id,
+                result.output.message.unwrap_or_default()
+            );
+            return;
+        }
+        Err(e) => {
+            debug1!("Plugin {} failed: {}", spec.id, e);
+            return;
+        }
+    };
+    let values = output
+        .metrics
+        .as_ref()
+        .map(metric_values)
+        .unwrap_or_default();
+    debug3!("Plugin {} reported {} metric(s)", spec.id, values.len());
+    if let Ok(mut reported) = REPORTED.lock() {
+        reported.insert(
+            spec.id.clone(),
+            Reported {
+                values,
+                at: Instant::now(),
+                stale_after: Duration::from_secs(spec.interval_secs * 3),
+            },
+        );
+    }
+}
+
+/// Plugins found by the last scan; each plugin's task reads its spec from here
+static SPECS: Mutex<Vec<PluginSpec>> = Mutex::new(Vec::new());
+/// Plugin ids with a running task
+static TASKS: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+fn spec(id: &str) -> Option<PluginSpec> {
+    SPECS.lock().ok()?.iter().find(|s| s.id == id).cloned()
+}
+
+/// Run plugin `id` every `intervalSecs` until it disappears from the directory
+async fn run_plugin(id: String) {
+    debug2!("Plugin {} started", id);
+    while let Some(spec) = spec(&id) {
+        if !crate::guest_mode::is_active() {
+            let interval = Duration::from_secs(spec.interval_secs);
+            if let Err(e) = tokio::task::spawn_blocking(move || run(&spec)).await {
+                debug1!("Plugin {} panicked: {}", id, e);
+            }
+            tokio::time::sleep(interval).await;
+        } else {
+            tokio::time::sleep(TICK).await;
+        }
+    }
+    debug2!("Plugin {} removed", id);
+    if let Ok(mut tasks) = TASKS.lock() {
+        tasks.remove(&id);
+    }
+    if let Ok(mut reported) = REPORTED.lock() {
+        reported.remove(&id);
+    }
+}
+
+/// Rescan the plugins directory every [`TICK`] and start a task for each new plugin
+pub fn spawn_runner() {
+    crate::background::every("metric plugins", TICK, || {
+        let specs = discover(&Config::plugins_dir());
+        if let Ok(mut current) = SPECS.lock() {
+            *current = specs.clone();
+        }
+        let Ok(mut tasks) = TASKS.lock() else {
+            return true;
+        };
+        for spec in specs {
+            if tasks.insert(spec.id.clone())
+                && !crate::background::spawn("metric plugin", run_plugin(spec.id.clone()))
+            {
+                tasks.remove(&spec.id);
+            }
+        }
+        true
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_descriptors_and_metrics() {
+        let dir =
+            std::env::temp_dir().join(format!("mac-stats-plugins-test-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("bin")).unwrap();
+        std::fs::write(dir.join("bin/queue.py"), "").unwrap();
+        let dir = dir.canonicalize().unwrap();
+        assert_eq!(
+            plugin_id(Path::new("/plugins/queue.depth.sh")).as_deref(),
+            Some("queue_depth")
+        );
+        assert_eq!(plugin_id(Path::new("/plugins/.DS_Store")), None);
+
+        let spec = parse_descriptor(
+            "queue".to_string(),
+            &dir,
+            &serde_json::json!({"script": "bin/queue.py", "intervalSecs": 1, "timeoutSecs": 999}),
+        )
+        .unwrap();
+        assert_eq!(spec.script, dir.join("bin/queue.py"));
+        assert_eq!(spec.interval_secs, 5);
+        assert_eq!(spec.timeout_secs, 300);
+        assert_eq!(
+            parse_descriptor("x".to_string(), &dir, &serde_json::json!({})),
+            None
+        );
+        for outside in ["/bin/sh", "../../../bin/sh", "bin/../../escape.sh"] {
+            let json = serde_json::json!({ "script": outside });
+            assert_eq!(
+                parse_descriptor("x".to_string(), &dir, &json),
+                None,
+                "{}",
+                outside
+            );
+        }
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let values = metric_values(&serde_json::json!({
+            "depth": 12,
+            "lag_ms": 3.5,
+            "up": true,
+            "label": "text",
+            "bad name": 1
+        }));
+        assert_eq!(
+            values.into_iter().collect::<Vec<_>>(),
+            vec![
+                ("depth".to_string(), 12.0),
+                ("lag_ms".to_string(), 3.5),
+                ("up".to_string(), 1.0)
+            ]
+        );
+    }
+}
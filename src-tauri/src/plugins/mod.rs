@@ -2,6 +2,10 @@
 //!
 //! Script-based plugins that output JSON.
 //! Plugins are executable scripts (bash/python) that follow a contract.
+//! Scripts in `~/.mac-stats/plugins/` are run on a schedule and their `metrics` merged into
+//! the system metrics (see [`metric_plugins`]).
+
+pub mod metric_plugins;
 
 use anyhow::{Context, Result};
 use chrono::{DateTime, Utc};
@@ -28,13 +32,19 @@ pub struct Plugin {
 /// Plugin output (JSON schema)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginOutput {
+    #[serde(default = "default_status")]
     pub status: String, // "ok", "error", "warning"
     pub message: Option<String>,
     pub data: Option<serde_json::Value>,
     pub metrics: Option<serde_json::Value>,
+    #[serde(default = "Utc::now")]
     pub timestamp: DateTime<Utc>,
 }
 
+fn default_status() -> String {
+    "ok".to_string()
+}
+
 /// Plugin execution result
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginResult {
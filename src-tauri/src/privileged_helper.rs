@@ -0,0 +1,303 @@
+//! Privileged helper for root-only sensors
+//!
+//! `powermetrics` and SMC writes need root, but the app should not run as root. The app
+//! bundle ships a launch daemon, `Contents/Library/LaunchDaemons/com.raro42.mac-stats.helper.plist`,
+//! that runs this same binary as `mac_stats privileged-helper`. Installing it with
+//! `SMAppService` (`set_privileged_helper_installed`) asks for an administrator's approval once;
+//! launchd then starts the helper on demand when the app connects to its Mach service and the
+//! helper exits again after [`IDLE_EXIT_SECS`] without requests.
+//!
+//! The XPC interface is a dictionary with an `op` string:
+//! - `ping`: replies `version`
+//! - `powermetrics` (`interval_ms`): one `powermetrics -f plist` sample as `sample` data
+//! - `fan` (`fan`, `rpm`; `rpm` 0 = automatic): fan override (`fan_control`), replies the fan's
+//!   state (`rpm`, `min_rpm`, `max_rpm`, `target_rpm`, `manual`)
+//! - `low_power_mode` (`enabled`): `pmset -a lowpowermode 1|0` (`low_power_mode`)
+//! - `maintenance` (`action`, `folder`): purge, DNS flush or Spotlight re-index
+//!   (`commands::maintenance`), replies `message`
+//!
+//! Failures reply `error`. Both ends check the other's code signature: the mac-stats signing
+//! identifier and the Developer ID team of the running binary, so only mac-stats builds signed by
+//! the same team can use the helper. Ad-hoc or unsigned builds have no team and cannot use it at
+//! all. Callers treat every error as "helper unavailable" and fall back to their unprivileged
+//! source (IOReport for power and frequency).
+
+use serde::Serialize;
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::commands::maintenance::{self, MaintenanceAction};
+use crate::ffi::service_management::{self, AppServiceStatus};
+use crate::ffi::xpc::{self, Connection, XpcObject};
+use crate::metrics::fan_control::{self, FanStatus};
+use crate::metrics::low_power_mode;
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+/// Mach service the launch daemon registers
+const SERVICE: &str = "com.raro42.mac-stats.helper";
+/// Launch daemon plist in `Contents/Library/LaunchDaemons`
+const PLIST_NAME: &str = "com.raro42.mac-stats.helper.plist";
+/// Signing identifier both ends require of each other (app and helper are the same binary)
+const IDENTIFIER: &str = "com.raro42.mac-stats";
+/// Quit after this long without a request; launchd restarts the helper on the next one
+const IDLE_EXIT_SECS: u64 = 120;
+/// Bounds for the `powermetrics` sample interval
+const MIN_INTERVAL_MS: i64 = 100;
+const MAX_INTERVAL_MS: i64 = 10_000;
+
+/// Unix time of the last request (helper process only)
+static LAST_REQUEST: AtomicU64 = AtomicU64::new(0);
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Helper state returned by `get_privileged_helper_status`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrivilegedHelperStatus {
+    /// Registered with launchd (possibly still waiting for approval)
+    pub installed: bool,
+    /// An administrator must allow it in System Settings > General > Login Items
+    pub requires_approval: bool,
+    /// Answered a ping just now
+    pub reachable: bool,
+    /// Helper version when reachable (differs from the app's after an update until it restarts)
+    pub version: Option<String>,
+}
+
+// --- Helper process (root) ---
+
+/// Entry point of `mac_stats privileged-helper` (started by launchd as root). Never returns.
+pub fn run() -> ! {
+    LAST_REQUEST.store(now_secs(), Ordering::Relaxed);
+    std::thread::spawn(|| loop {
+        std::thread::sleep(Duration::from_secs(10));
+        if now_secs().saturating_sub(LAST_REQUEST.load(Ordering::Relaxed)) >= IDLE_EXIT_SECS {
+            // No keepalive from the app: do not leave fans forced
+            fan_control::release_forced();
+            std::process::exit(0);
+        }
+    });
+    let requirement = match requirement() {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("mac-stats privileged helper: {}", e);
+            std::process::exit(1);
+        }
+    };
+    xpc::serve(SERVICE, &requirement, handle)
+}
+
+/// Code signature both ends require of each other: the mac-stats identifier signed by the team
+/// that signed this binary
+fn requirement() -> Result<String, String> {
+    static TEAM: OnceLock<Option<String>> = OnceLock::new();
+    let team = TEAM.get_or_init(|| {
+        std::env::current_exe()
+            .ok()
+            .and_then(|exe| crate::updates::installer::team_id(&exe))
+    });
+    team.as_deref().map(requirement_for_team).ok_or_else(|| {
+        "this build is not signed with a Developer ID, so the helper cannot check its peer"
+            .to_string()
+    })
+}
+
+fn requirement_for_team(team: &str) -> String {
+    format!(
+        r#"anchor apple generic and identifier "{}" and certificate leaf[subject.OU] = "{}""#,
+        IDENTIFIER, team
+    )
+}
+
+fn handle(message: &XpcObject, reply: &XpcObject) {
+    LAST_REQUEST.store(now_secs(), Ordering::Relaxed);
+    match message.string("op").as_deref() {
+        Some("ping") => reply.set_string("version", env!("CARGO_PKG_VERSION")),
+        Some("powermetrics") => match powermetrics_once(message.int64("interval_ms")) {
+            Ok(sample) => reply.set_data("sample", &sample),
+            Err(e) => reply.set_string("error", &e),
+        },
+        Some("fan") => {
+            let fan = u32::try_from(message.int64("fan")).unwrap_or(u32::MAX);
+            let rpm = message.int64("rpm");
+            match fan_control::apply(fan, (rpm > 0).then_some(rpm as f32)) {
+                Ok(status) => {
+                    reply.set_double("rpm", status.rpm.into());
+                    reply.set_double("min_rpm", status.min_rpm.into());
+                    reply.set_double("max_rpm", status.max_rpm.into());
+                    reply.set_double("target_rpm", status.target_rpm.into());
+                    reply.set_bool("manual", status.manual);
+                }
+                Err(e) => reply.set_string("error", &e),
+            }
+        }
+        Some("low_power_mode") => {
+            if let Err(e) = low_power_mode::apply(message.bool("enabled")) {
+                reply.set_string("error", &e);
+            }
+        }
+        Some("maintenance") => {
+            let result = message
+                .string("action")
+                .as_deref()
+                .and_then(MaintenanceAction::parse)
+                .ok_or_else(|| "Unknown maintenance action".to_string())
+                .and_then(|action| maintenance::run(action, message.string("folder").as_deref()));
+            match result {
+                Ok(done) => reply.set_string("message", &done),
+                Err(e) => reply.set_string("error", &e),
+            }
+        }
+        other => reply.set_string("error", &format!("Unknown request {:?}", other)),
+    }
+    LAST_REQUEST.store(now_secs(), Ordering::Relaxed);
+}
+
+/// One `powermetrics` sample over `interval_ms`
+fn powermetrics_once(interval_ms: i64) -> Result<Vec<u8>, String> {
+    let interval = interval_ms.clamp(MIN_INTERVAL_MS, MAX_INTERVAL_MS);
+    let out = Command::new("/usr/bin/powermetrics")
+        .args([
+            "--samplers",
+            "cpu_power,gpu_power",
+            "-f",
+            "plist",
+            "-n",
+            "1",
+            "-i",
+        ])
+        .arg(interval.to_string())
+        .output()
+        .map_err(|e| format!("powermetrics: {}", e))?;
+    if !out.status.success() {
+        let reason = String::from_utf8_lossy(&out.stderr);
+        return Err(format!(
+            "powermetrics {}: {}",
+            out.status,
+            reason.lines().next().unwrap_or("no output")
+        ));
+    }
+    Ok(out.stdout)
+}
+
+// --- App side ---
+
+/// Send one request to the helper and return its reply
+fn request(op: &str, fill: impl FnOnce(&XpcObject)) -> Result<XpcObject, String> {
+    let message = XpcObject::dictionary();
+    message.set_string("op", op);
+    fill(&message);
+    let reply = Connection::privileged(SERVICE, Some(&requirement()?))?.send(&message)?;
+    match reply.string("error") {
+        Some(e) => Err(e),
+        None => Ok(reply),
+    }
+}
+
+/// Helper version, or why it cannot be reached
+pub fn ping() -> Result<String, String> {
+    request("ping", |_| {})?
+        .string("version")
+        .ok_or_else(|| "Helper sent no version".to_string())
+}
+
+/// One `powermetrics -f plist` sample taken by the helper (blocks for about `interval_ms`)
+pub fn powermetrics_sample(interval_ms: u32) -> Result<Vec<u8>, String> {
+    request("powermetrics", |m| {
+        m.set_int64("interval_ms", interval_ms.into())
+    })?
+    .data("sample")
+    .ok_or_else(|| "Helper sent no sample".to_string())
+}
+
+/// Have the helper force `fan` to `rpm` or return it to automatic (`None`)
+pub fn set_fan(fan: u32, rpm: Option<f32>) -> Result<FanStatus, String> {
+    let reply = request("fan", |m| {
+        m.set_int64("fan", fan.into());
+        m.set_int64("rpm", rpm.map_or(0, |rpm| rpm.round().max(1.0) as i64));
+    })?;
+    Ok(FanStatus {
+        index: fan,
+        rpm: reply.double("rpm") as f32,
+        min_rpm: reply.double("min_rpm") as f32,
+        max_rpm: reply.double("max_rpm") as f32,
+        target_rpm: reply.double("target_rpm") as f32,
+        manual: reply.bool("manual"),
+    })
+}
+
+/// Have the helper switch Low Power Mode
+pub fn set_low_power_mode(enabled: bool) -> Result<(), String> {
+    request("low_power_mode", |m| m.set_bool("enabled", enabled)).map(|_| ())
+}
+
+/// Have the helper run a maintenance action; returns its result message
+pub fn run_maintenance(action: MaintenanceAction, folder: Option<&str>) -> Result<String, String> {
+    request("maintenance", |m| {
+        m.set_string("action", action.as_str());
+        if let Some(folder) = folder {
+            m.set_string("folder", folder);
+        }
+    })?
+    .string("message")
+    .ok_or_else(|| "Helper sent no result".to_string())
+}
+
+fn helper_status() -> PrivilegedHelperStatus {
+    let status = service_management::daemon_status(PLIST_NAME);
+    let version = ping()
+        .map_err(|e| debug2!("Privileged helper not reachable: {}", e))
+        .ok();
+    PrivilegedHelperStatus {
+        installed: matches!(
+            status,
+            Some(AppServiceStatus::Enabled | AppServiceStatus::RequiresApproval)
+        ),
+        requires_approval: status == Some(AppServiceStatus::RequiresApproval),
+        reachable: version.is_some(),
+        version,
+    }
+}
+
+/// Whether the privileged helper is installed and answering
+#[tauri::command]
+pub async fn get_privileged_helper_status() -> Result<PrivilegedHelperStatus, String> {
+    tokio::task::spawn_blocking(helper_status)
+        .await
+        .map_err(|e| format!("Helper status check failed: {}", e))
+}
+
+/// Install (asks for administrator approval) or remove the privileged helper
+#[tauri::command]
+pub async fn set_privileged_helper_installed(
+    enabled: bool,
+    confirmation_token: Option<String>,
+) -> Result<PrivilegedHelperStatus, String> {
+    crate::guest_mode::ensure_allowed("Installing the privileged helper")?;
+    crate::commands::confirmation::require_confirmation(
+        confirmation_token.as_deref(),
+        "privileged_helper",
+        if enabled { "install" } else { "remove" },
+    )?;
+    service_management::set_daemon_registered(PLIST_NAME, enabled)?;
+    let status = tokio::task::spawn_blocking(helper_status)
+        .await
+        .map_err(|e| format!("Helper status check failed: {}", e))?;
+    tracing::info!(
+        "Privileged helper {}{}",
+        if enabled { "installed" } else { "removed" },
+        if status.requires_approval {
+            " (needs approval in System Settings > Login Items)"
+        } else {
+            ""
+        }
+    );
+    Ok(status)
+}
@@ -0,0 +1,935 @@
+//! Background sampling
+//!
+//! Samples everything the menu bar and the CPU window show, as tasks on the collector runtime
+//! (see [`crate::background`]). The sample task creates `System` and `Disks`, builds the menu
+//! bar titles and pushes history every `updateIntervalSecs` ([`DEFAULT_INTERVAL`]); SMC
+//! temperature and IOReport frequency / power each have their own task on their collector's
+//! budgeted cadence (see
+//! [`crate::metrics::sampling_budget`]), reading only while the CPU window is visible. The
+//! sample task releases the SMC connection and IOReport subscriptions when it closes. Readings
+//! go to the metrics snapshot ([`crate::state::snapshot`]).
+//!
+//! Other code steers sampling with a [`Command`] over a channel ([`send`]) instead of touching
+//! its state: `RefreshNow` when the CPU window opens, `SetInterval`, `Pause` / `Resume` (also
+//! from the frontend via `set_sampling`).
+
+use macsmc::Smc;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use sysinfo::{Disks, System};
+use tauri::Manager;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::sync::Notify;
+
+use crate::ffi::ioreport::Subscription;
+use crate::metrics::sampling_budget::Collector;
+use crate::metrics::{self, get_metrics};
+use crate::state::{self, *};
+use crate::ui::status_bar::{build_item_text, build_status_text, TitleStallWatch};
+use crate::{alerts, background, commands, config, ffi, ollama, startup, ui};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Command {
+    /// Sample now instead of waiting for the next tick (e.g. the CPU window just opened)
+    RefreshNow,
+    /// Time between samples, clamped to 1–60s
+    SetInterval(Duration),
+    /// Stop sampling until `Resume`; titles and history keep their last values
+    Pause,
+    Resume,
+}
+
+/// Time between samples unless configured (`updateIntervalSecs`) or changed with
+/// [`Command::SetInterval`]
+pub(crate) const DEFAULT_INTERVAL: Duration = Duration::from_secs(3);
+const MIN_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_INTERVAL: Duration = Duration::from_secs(60);
+/// Next try after a tick with invalid metrics (e.g. SYSTEM still initializing)
+const RETRY_INTERVAL: Duration = Duration::from_secs(1);
+/// Before the first sample, so CPU usage has a baseline to diff against
+const FIRST_SAMPLE_DELAY: Duration = Duration::from_millis(1500);
+
+/// Raw CPU temperature keys tried when `cpu_temperature()` reads 0: M3 Max (same as
+/// exelban/stats uses) and Intel proximity / die keys
+const M3_TEMP_KEYS: [&str; 6] = ["Tf04", "Tf09", "Tf0A", "Tf0B", "Tf0D", "Tf0E"];
+const INTEL_TEMP_KEYS: [&str; 6] = ["TC0P", "TC0D", "TC0E", "TC0F", "TC0H", "TCXC"];
+
+static COMMANDS: OnceLock<UnboundedSender<Command>> = OnceLock::new();
+/// Kept for [`shutdown`], which tears the sensors down after the tasks stopped
+static SHARED: OnceLock<Arc<Shared>> = OnceLock::new();
+
+/// CPU-window sensors, each a task with its collector's budgeted cadence
+const SENSORS: [(Collector, fn(&mut Sensors)); 3] = [
+    (Collector::Smc, |s| {
+        s.connect_smc();
+        s.read_temperature();
+    }),
+    // While the powermetrics provider runs it fills frequencies and power instead
+    (Collector::IoReportFrequency, |s| {
+        if metrics::powermetrics::is_running() {
+            return;
+        }
+        s.subscribe_frequency();
+        s.read_frequency();
+    }),
+    (Collector::IoReportPower, |s| {
+        if metrics::powermetrics::is_running() {
+            return;
+        }
+        s.subscribe_power();
+        s.read_power();
+    }),
+];
+
+/// Queue a command for the sample task; false before it was started
+pub(crate) fn send(command: Command) -> bool {
+    COMMANDS.get().is_some_and(|tx| tx.send(command).is_ok())
+}
+
+fn clamp_interval(interval: Duration) -> Duration {
+    interval.clamp(MIN_INTERVAL, MAX_INTERVAL)
+}
+
+/// Start the sample task and the sensor tasks on the collector runtime (once; later calls do
+/// nothing)
+pub(crate) fn spawn() {
+    let (tx, rx) = mpsc::unbounded_channel();
+    if COMMANDS.set(tx).is_err() {
+        return;
+    }
+    let mut sampler = Sampler::new();
+    sampler.interval = clamp_interval(Duration::from_secs(config::Config::update_interval_secs()));
+    let _ = SHARED.set(sampler.shared.clone());
+    for (collector, read) in SENSORS {
+        // Intel Macs have no IOReport energy channels
+        if collector == Collector::IoReportPower && !crate::capabilities::apple_silicon() {
+            continue;
+        }
+        background::spawn(
+            collector.name(),
+            sensor_loop(sampler.shared.clone(), collector, read),
+        );
+    }
+    if !background::spawn("sample", sampler.run(rx)) {
+        tracing::error!(target: "mac_stats::metrics", "Could not start sampling: collector runtime unavailable");
+    }
+}
+
+/// Close the SMC connection and release the IOReport subscriptions (`RunEvent::Exit`, after
+/// [`background::shutdown`] stopped the tasks using them)
+pub(crate) fn shutdown() {
+    let Some(shared) = SHARED.get() else {
+        return;
+    };
+    match shared.sensors.try_lock() {
+        Ok(mut sensors) => sensors.teardown(),
+        Err(_) => debug1!("Sensors still busy at exit; leaving SMC / IOReport to the OS"),
+    }
+}
+
+/// Pause or resume sampling, change its interval or ask for a sample now
+#[tauri::command]
+pub fn set_sampling(
+    paused: Option<bool>,
+    interval_secs: Option<u64>,
+    refresh: Option<bool>,
+) -> Result<(), String> {
+    let mut queue = Vec::new();
+    match paused {
+        Some(true) => queue.push(Command::Pause),
+        Some(false) => queue.push(Command::Resume),
+        None => {}
+    }
+    if let Some(secs) = interval_secs {
+        queue.push(Command::SetInterval(Duration::from_secs(secs)));
+    }
+    if refresh.unwrap_or(false) {
+        queue.push(Command::RefreshNow);
+    }
+    for command in queue {
+        if !send(command) {
+            return Err("Sampling has not started yet".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// SMC connection and IOReport subscriptions, opened by the sensor tasks while the CPU window
+/// is visible and released by the sample task when it closes
+#[derive(Default)]
+struct Sensors {
+    smc: Option<ffi::SmcConnection>,
+    frequency: Option<Subscription>,
+    power: Option<Subscription>,
+}
+
+/// What the sample task shares with the sensor tasks
+#[derive(Default)]
+struct Shared {
+    sensors: Mutex<Sensors>,
+    /// Wakes the sensors right away (`RefreshNow`, `Resume`)
+    refresh: Notify,
+    paused: AtomicBool,
+}
+
+struct Sampler {
+    shared: Arc<Shared>,
+    title_watch: TitleStallWatch,
+    /// Network / disk byte counters persist between samples to derive rates
+    io_sampler: metrics::io_rates::IoRateSampler,
+    last_sample: Option<Instant>,
+    interval: Duration,
+    /// Menu bar values held between samples (`menuBarReducedMotion`)
+    damper: ui::reduced_motion::Damper,
+    /// Titles handed to AppKit last, per status item (`menuBarReducedMotion` skips repeats)
+    last_titles: HashMap<String, String>,
+    /// Tinted metrics when `last_titles` were stored; a change redraws every item
+    last_alerting: Vec<String>,
+}
+
+impl Sampler {
+    fn new() -> Self {
+        Self {
+            shared: Arc::default(),
+            title_watch: TitleStallWatch::new(),
+            io_sampler: metrics::io_rates::IoRateSampler::new(),
+            last_sample: None,
+            interval: DEFAULT_INTERVAL,
+            damper: Default::default(),
+            last_titles: HashMap::new(),
+            last_alerting: Vec::new(),
+        }
+    }
+
+    async fn run(mut self, mut commands: UnboundedReceiver<Command>) {
+        tokio::task::block_in_place(|| {
+            init_system();
+            init_history();
+        });
+
+        let mut ticks = background::interval(FIRST_SAMPLE_DELAY, self.interval);
+        loop {
+            tokio::select! {
+                command = commands.recv() => {
+                    let Some(command) = command else {
+                        return;
+                    };
+                    let interval = self.interval;
+                    if self.apply(command) {
+                        ticks.reset_immediately();
+                        self.shared.refresh.notify_waiters();
+                    } else if self.interval != interval {
+                        ticks = background::interval(self.interval, self.interval);
+                    }
+                }
+                _ = ticks.tick() => {
+                    if self.shared.paused.load(Ordering::Relaxed) {
+                        continue;
+                    }
+                    if !tokio::task::block_in_place(|| self.tick()) {
+                        ticks.reset_after(RETRY_INTERVAL);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Apply `command`; true when a sample is due right away
+    fn apply(&mut self, command: Command) -> bool {
+        debug2!("Sampler command: {:?}", command);
+        match command {
+            Command::RefreshNow => !self.shared.paused.load(Ordering::Relaxed),
+            Command::SetInterval(interval) => {
+                self.interval = clamp_interval(interval);
+                false
+            }
+            Command::Pause => {
+                self.shared.paused.store(true, Ordering::Relaxed);
+                false
+            }
+            Command::Resume => self.shared.paused.swap(false, Ordering::Relaxed),
+        }
+    }
+
+    /// One sample; false when metrics were not valid yet (retried sooner)
+    fn tick(&mut self) -> bool {
+        // A game or DAW is frontmost: sample every 30s (`lowInterferenceApps`)
+        if metrics::low_interference::is_active()
+            && self
+                .last_sample
+                .is_some_and(|t| t.elapsed() < metrics::low_interference::SAMPLE_INTERVAL)
+        {
+            return true;
+        }
+        self.last_sample = Some(Instant::now());
+        // One span per tick for OTLP export (`otlpEndpoint`, see logging::otel)
+        let _tick = tracing::trace_span!(target: "mac_stats::perf", "sample").entered();
+
+        debug3!("Update loop: getting metrics...");
+        let metrics = get_metrics();
+
+        // CRITICAL: Only update menu bar if metrics are valid
+        // Invalid metrics (all zeros) can occur during initialization or when locks are held
+        // In that case, skip this update and wait for the next cycle
+        if !metrics.is_valid() {
+            debug3!(
+                "Skipping menu bar update: invalid metrics (CPU={}%, GPU={}%, RAM={}%, DISK={}%)",
+                metrics.cpu,
+                metrics.gpu,
+                metrics.ram,
+                metrics.disk
+            );
+            return false; // Skip this update cycle
+        }
+        metrics::warm_start::remember_metrics(&metrics);
+
+        // Reduced motion: the menu bar shows held, rounded values; everything else the readings
+        let reduced_motion = config::Config::menu_bar_reduced_motion();
+        let settled;
+        let shown = if reduced_motion {
+            settled = self
+                .damper
+                .settle(&metrics, config::Config::menu_bar_min_change());
+            &settled
+        } else {
+            &metrics
+        };
+        let alerting = alerts::channels::menu_bar_alert_metrics();
+        // Narrower while macOS has no room for the status item (`menuBarAutoCompact`)
+        let mut text = ui::status_compaction::compact(&build_status_text(shown), &alerting);
+        if config::Config::ai_agent_enabled() && ollama::ollama_http_circuit_is_open_for_menu() {
+            ui::status_compaction::push_cue(&mut text, "Ollama ✕");
+        }
+        // Red monitor cue in the menu bar when any website/social check is down.
+        let any_monitor_down = commands::monitors::get_monitor_statuses_snapshot()
+            .iter()
+            .any(|(_, st)| !st.is_up);
+        if any_monitor_down {
+            ui::status_compaction::push_cue(&mut text, "Mon ✕");
+        }
+        // Red alert cue while a `menubar` alert action is recent (`alertRules`); metrics
+        // shown in the status item are tinted instead (see `ui::status_alerts`).
+        if ui::status_alerts::has_unshown(&text, &alerting) {
+            ui::status_compaction::push_cue(&mut text, "Alert ✕");
+        }
+
+        if let Ok(mut spark) = state::CPU_SPARKLINE.lock() {
+            spark.push(metrics.cpu);
+        }
+        // One title per status item (`menuBarItems`); the main item gets `text`
+        let mut titles: Vec<(String, String)> = config::Config::menu_bar_items()
+            .into_iter()
+            .map(|id| {
+                let title = build_item_text(&id, shown, &text);
+                (id, title)
+            })
+            .collect();
+        if alerting != self.last_alerting {
+            self.last_titles.clear();
+            self.last_alerting = alerting;
+        }
+        if reduced_motion {
+            titles.retain(|(id, title)| self.last_titles.get(id) != Some(title));
+        }
+        // "Pause Updates" (status menu) keeps the current titles; sampling goes on. Nothing
+        // changed (reduced motion) is not a stall either.
+        if state::MENU_BAR_PAUSED.load(std::sync::atomic::Ordering::Relaxed) || titles.is_empty() {
+            self.title_watch = TitleStallWatch::new();
+        } else {
+            // Store update in static variable
+            self.last_titles.extend(titles.iter().cloned());
+            if let Ok(mut pending) = MENU_BAR_TEXT.lock() {
+                pending.extend(titles);
+                debug3!(
+                    "Menu bar update stored: CPU={}%, GPU={}%, RAM={}%, DISK={}%",
+                    metrics.cpu,
+                    metrics.gpu,
+                    metrics.ram,
+                    metrics.disk
+                );
+            }
+            self.title_watch.check();
+        }
+
+        // Add to history buffer (always collect basic metrics when available)
+        // We'll enhance with temperature/frequency when CPU window is visible
+        let history_point = metrics::history::MetricPoint::from_metrics(
+            metrics.cpu,
+            metrics.gpu,
+            metrics.ram,
+            metrics.disk,
+            0.0,  // temperature (will be updated below if CPU window visible)
+            0.0,  // frequency (will be updated below if CPU window visible)
+            0.0,  // p_core_frequency
+            0.0,  // e_core_frequency
+            0.0,  // cpu_power
+            0.0,  // gpu_power
+            -1.0, // battery_level
+        );
+
+        // Store for later enhancement with CPU details
+        let mut final_history_point = history_point;
+
+        // Temperature, frequency and power have their own tasks (`sensor_loop`)
+        if cpu_window_sampled() {
+            read_battery();
+        } else if let Ok(mut sensors) = self.shared.sensors.try_lock() {
+            sensors.release();
+        }
+
+        // Populate metrics history buffer with current data
+        // Update final_history_point with CPU details if available from caches
+        let cached = snapshot();
+        if let Some(temp) = cached.temperature.as_ref() {
+            final_history_point.temperature = temp.value;
+        }
+        if let Some(freq) = cached.frequency.as_ref() {
+            final_history_point.frequency = freq.value;
+        }
+        if let Some(p_freq) = cached.p_core_frequency.as_ref() {
+            final_history_point.p_core_frequency = p_freq.value;
+        }
+        if let Some(e_freq) = cached.e_core_frequency.as_ref() {
+            final_history_point.e_core_frequency = e_freq.value;
+        }
+        if let Some(power) = cached.power.as_ref() {
+            (final_history_point.cpu_power, final_history_point.gpu_power) = power.value;
+        }
+        if let Some(battery) = cached.battery.as_ref() {
+            final_history_point.battery_level = battery.value.0;
+        }
+
+        let io = self.io_sampler.sample();
+        if let Ok(mut latest) = LATEST_IO_RATES.try_lock() {
+            *latest = Some(io);
+        }
+        metrics::network_details::record(&self.io_sampler.interface_totals());
+        final_history_point.net_rx = io.net_rx;
+        final_history_point.net_tx = io.net_tx;
+        final_history_point.disk_read = io.disk_read;
+        final_history_point.disk_write = io.disk_write;
+        let (battery_power, adapter_power) = metrics::battery_details::sample_for_history();
+        final_history_point.battery_power = battery_power;
+        final_history_point.adapter_power = adapter_power;
+        final_history_point.plugins = metrics.plugins.clone();
+
+        // Push to history buffer
+        let mut closed_gap = None;
+        if let Ok(mut history_opt) = METRICS_HISTORY.try_lock() {
+            if let Some(history) = history_opt.as_mut() {
+                closed_gap = history.push(final_history_point.clone());
+                debug3!("Added history point: CPU={}%, GPU={}%, RAM={}%, DISK={}%, Temp={}°C, Freq={}GHz",
+                    final_history_point.cpu,
+                    final_history_point.gpu,
+                    final_history_point.ram,
+                    final_history_point.disk,
+                    final_history_point.temperature,
+                    final_history_point.frequency);
+                if let Ok(mut wal_opt) = HISTORY_WAL.try_lock() {
+                    if let Some(wal) = wal_opt.as_mut() {
+                        if let Err(e) = wal.append(&final_history_point) {
+                            debug1!("History log append failed: {}", e);
+                        }
+                        if wal.checkpoint_due() && !startup::in_startup_delay() {
+                            if let Err(e) = wal.checkpoint(history) {
+                                debug1!("History checkpoint failed: {}", e);
+                            }
+                        }
+                    }
+                }
+            }
+        } else {
+            debug3!("Could not lock history buffer for update (lock contention)");
+        }
+        metrics::history_store::record(&final_history_point, closed_gap.as_ref());
+        true
+    }
+}
+
+impl Sensors {
+    fn connect_smc(&mut self) {
+        // Reuse SMC connection if available, otherwise create new one
+        if self.smc.is_none() {
+            match Smc::connect() {
+                Ok(smc) => {
+                    self.smc = Some(ffi::SmcConnection(smc));
+                    debug3!("SMC connection established for the temperature collector");
+                    crate::capabilities::update(|c| c.temperature = true);
+                }
+                Err(e) => {
+                    debug3!("Failed to connect to SMC: {:?}", e);
+                    // Will retry on next tick
+                }
+            }
+        }
+    }
+
+    fn subscribe_frequency(&mut self) {
+        // Expensive to create, so kept (and reused) until the CPU window closes
+        // Intel Macs have no CPU performance-state channels (see `read_frequency`)
+        if self.frequency.is_some() || !crate::capabilities::apple_silicon() {
+            return;
+        }
+        self.frequency = Subscription::frequency();
+        if self.frequency.is_some() {
+            debug3!("IOReport subscription created successfully for CPU frequency");
+            crate::capabilities::update(|c| c.frequency = true);
+        }
+    }
+
+    fn subscribe_power(&mut self) {
+        // Expensive to create, so kept (and reused) until the CPU window closes
+        if self.power.is_some() {
+            return;
+        }
+        self.power = Subscription::power();
+        if self.power.is_some() {
+            debug3!("IOReport power subscription created successfully");
+            crate::capabilities::update(|c| {
+                c.cpu_power = true;
+                c.gpu_power = true;
+            });
+        } else {
+            debug3!("Power consumption will show 0.0W - power channels may not be available on this Mac model");
+        }
+    }
+
+    fn read_temperature(&mut self) {
+        // Own task every `Collector::Smc` interval (20s unless over budget): all_data()
+        // iteration is VERY expensive and temperature doesn't change rapidly
+        // Read temperature using existing connection
+        if let Some(smc) = self.smc.as_mut() {
+            let _span =
+                tracing::trace_span!(target: "mac_stats::perf", "smc_temperature").entered();
+            let _timer = metrics::sampling_budget::Timer::start(Collector::Smc);
+            // First try standard cpu_temperature() method (works for M1/M2)
+            let mut temp = 0.0;
+            match smc.cpu_temperature() {
+                Ok(temps) => {
+                    let die_temp: f64 = temps.die.into();
+                    let prox_temp: f64 = temps.proximity.into();
+
+                    // Priority: die > proximity
+                    temp = if die_temp > 0.0 {
+                        die_temp
+                    } else if prox_temp > 0.0 {
+                        prox_temp
+                    } else {
+                        0.0
+                    };
+                }
+                Err(_) => {
+                    // Standard method failed, continue to raw key reading
+                }
+            }
+
+            // If standard method returned 0.0, try raw keys directly: the ones exelban/stats
+            // uses for M3 Max, or the Intel CPU proximity / die keys
+            if temp == 0.0 {
+                // Check if we've already discovered a working key
+                let cached_key = RAW_TEMP_KEY.lock().ok().and_then(|k| k.clone());
+
+                if let Some(key_name) = cached_key {
+                    // CRITICAL: Use direct key reading instead of all_data() iteration
+                    // This is MUCH more efficient - avoids iterating through all SMC keys
+                    // Try to read the specific key directly
+                    // Note: macsmc may not have direct key reading, so we'll limit all_data() usage
+                    // Only call all_data() if we absolutely need to, and limit iteration
+                    if let Ok(data_iter) = smc.all_data() {
+                        for dbg in data_iter.flatten() {
+                            if dbg.key == key_name {
+                                if let Ok(Some(macsmc::DataValue::Float(val))) = dbg.value {
+                                    if val > 0.0 {
+                                        temp = val as f64;
+                                        debug3!(
+                                            "Temperature read from cached key {}: {:.1}°C",
+                                            key_name,
+                                            temp
+                                        );
+                                        break;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                } else {
+                    // First time: discover which raw key works
+                    // CRITICAL: Only iterate through keys once, then cache the result
+                    let raw_keys: &[&str] = if crate::capabilities::apple_silicon() {
+                        &M3_TEMP_KEYS
+                    } else {
+                        &INTEL_TEMP_KEYS
+                    };
+                    if let Ok(data_iter) = smc.all_data() {
+                        for dbg in data_iter.flatten() {
+                            if raw_keys.contains(&dbg.key.as_str()) {
+                                if let Ok(Some(macsmc::DataValue::Float(val))) = dbg.value {
+                                    if val > 0.0 {
+                                        temp = val as f64;
+                                        if let Ok(mut cached) = RAW_TEMP_KEY.lock() {
+                                            *cached = Some(dbg.key.clone());
+                                            debug3!(
+                                                "Discovered working temperature key: {} = {:.1}°C",
+                                                dbg.key,
+                                                temp
+                                            );
+                                        }
+                                        break;
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if temp > 0.0 {
+                // Update cache with new temperature and timestamp
+                update_snapshot(|s| s.temperature = Some(Reading::new(temp as f32)));
+                debug3!("Temperature updated in cache: {:.1}°C", temp);
+            } else {
+                debug3!("Temperature read returned 0.0 - no valid temperature found");
+                // Don't update cache - keep previous value if available
+            }
+
+            // GPU cluster temperature shares the same 20s cadence
+            metrics::gpu_temperature::update_cache(smc);
+        }
+    }
+
+    fn read_frequency(&mut self) {
+        // Read CPU frequency from IOReport (real-time, dynamic)
+        // This is the same approach exelban/stats uses - efficient native API
+        // Own task every `Collector::IoReportFrequency` interval (30s): IOReport sampling still
+        // has overhead and frequency doesn't change that rapidly
+        if !crate::capabilities::apple_silicon() {
+            // Intel: no IOReport performance states; hw.cpufrequency is the base clock
+            let base = metrics::get_nominal_frequency();
+            if base > 0.0 {
+                update_snapshot(|s| s.frequency = Some(Reading::new(base)));
+            }
+            return;
+        }
+        debug3!("Attempting IOReport frequency read");
+
+        // Check if frequency logging is enabled
+        let freq_logging = state::FREQUENCY_LOGGING_ENABLED
+            .lock()
+            .map(|f| *f)
+            .unwrap_or(false);
+
+        let mut freq: f32 = 0.0;
+        let mut p_core_freq: f32 = 0.0;
+        let mut e_core_freq: f32 = 0.0;
+
+        // Try IOReport first (real-time frequency via native API)
+        let freq_result = match self.frequency.as_mut() {
+            Some(subscription) => {
+                let _timer = metrics::sampling_budget::Timer::start(Collector::IoReportFrequency);
+                Some(subscription.read_frequencies(freq_logging))
+            }
+            None => {
+                debug3!("IOReport subscription not available");
+                None
+            }
+        };
+
+        // Update frequency values from result
+        if let Some(freq_result) = freq_result {
+            freq = freq_result.overall;
+            p_core_freq = freq_result.p_core;
+            e_core_freq = freq_result.e_core;
+        }
+
+        // CRITICAL: Only use nominal frequency as fallback if IOReport completely failed
+        // If IOReport returned 0.0, it means parsing failed - don't overwrite cache with nominal
+        // Only update cache if we got a real frequency from IOReport
+        let freq_logging = state::FREQUENCY_LOGGING_ENABLED
+            .lock()
+            .map(|f| *f)
+            .unwrap_or(false);
+
+        if freq > 0.0 {
+            update_snapshot(|s| s.frequency = Some(Reading::new(freq)));
+            if freq_logging {
+                debug3!("Overall frequency cache updated: {:.2} GHz", freq);
+            } else {
+                debug3!("Frequency cache updated from IOReport: {:.2} GHz", freq);
+            }
+
+            // Update P-core frequency cache
+            if p_core_freq > 0.0 {
+                update_snapshot(|s| s.p_core_frequency = Some(Reading::new(p_core_freq)));
+                debug3!("P-core frequency cache updated: {:.2} GHz", p_core_freq);
+            } else if freq_logging {
+                debug3!("P-core frequency is 0.0 - NOT updating cache");
+            }
+
+            // Update E-core frequency cache
+            if e_core_freq > 0.0 {
+                update_snapshot(|s| s.e_core_frequency = Some(Reading::new(e_core_freq)));
+                debug3!("E-core frequency cache updated: {:.2} GHz", e_core_freq);
+            } else if freq_logging {
+                debug3!("E-core frequency is 0.0 - NOT updating cache");
+            }
+
+            crate::capabilities::update(|c| c.frequency = true);
+        } else {
+            // This prevents overwriting a good cached value with nominal frequency
+            debug3!("IOReport frequency parsing failed (freq=0.0) - keeping existing cache value if available");
+
+            // Only initialize cache with nominal frequency if it's completely empty
+            if snapshot().frequency.is_none() {
+                let nominal = metrics::get_nominal_frequency();
+                update_snapshot(|s| {
+                    s.frequency.get_or_insert_with(|| Reading::new(nominal));
+                });
+                debug3!("Using nominal frequency as initial value: {:.2} GHz (IOReport not available yet)", nominal);
+            } else {
+                debug3!("Keeping existing cached frequency value (IOReport parsing failed)");
+            }
+        }
+    }
+
+    fn read_power(&mut self) {
+        // Read power consumption from IOReport
+        // Own task every `Collector::IoReportPower` interval (5s): IOReport reads are expensive
+        // The subscription keeps the last sample, so each read covers the time since the previous
+        let power_logging = state::POWER_USAGE_LOGGING_ENABLED
+            .lock()
+            .map(|f| *f)
+            .unwrap_or(false);
+        debug3!("Reading power from IOReport...");
+        let power_result = match self.power.as_mut() {
+            Some(subscription) => {
+                let _timer = metrics::sampling_budget::Timer::start(Collector::IoReportPower);
+                let result = subscription.read_power(power_logging);
+                debug3!(
+                    "IOReport power read: CPU={:.2}W, GPU={:.2}W",
+                    result.cpu_power,
+                    result.gpu_power
+                );
+                Some(result)
+            }
+            None => {
+                debug3!("Power subscription is None");
+                None
+            }
+        };
+
+        if let Some(power_data) = power_result {
+            // Update cache - CRITICAL: Only update if we have at least one valid value > 0.0
+            // This prevents setting cache to (0.0, 0.0) on first read when time_delta=0
+            if power_data.cpu_power > 0.0 || power_data.gpu_power > 0.0 {
+                let (prev_cpu, prev_gpu) =
+                    snapshot().power.as_ref().map_or((0.0, 0.0), |p| p.value);
+
+                // Only update values that are > 0.0
+                // If a value is 0.0, keep the previous value to prevent flickering
+                let new_cpu = if power_data.cpu_power > 0.0 {
+                    power_data.cpu_power
+                } else {
+                    prev_cpu // Keep previous value if new is 0.0
+                };
+
+                let new_gpu = if power_data.gpu_power > 0.0 {
+                    power_data.gpu_power
+                } else {
+                    prev_gpu // Keep previous value if new is 0.0
+                };
+
+                update_snapshot(|s| s.power = Some(Reading::new((new_cpu, new_gpu))));
+
+                debug3!("Power cache updated: CPU={:.2}W, GPU={:.2}W (prev: CPU={:.2}W, GPU={:.2}W, new_cpu={:.2}W, new_gpu={:.2}W)",
+                    new_cpu, new_gpu, prev_cpu, prev_gpu, power_data.cpu_power, power_data.gpu_power);
+            } else {
+                // Both values are 0.0 - don't update cache to prevent overwriting good values
+                // This happens on first read when time_delta=0
+                debug3!("Power read returned 0.0W for both (time_delta likely 0) - not updating cache to preserve previous values");
+            }
+        } else {
+            debug3!("Power reading returned None - subscription may not be available");
+        }
+
+        // Get cached power values for logging
+        let (cpu_power, gpu_power) = snapshot().power.as_ref().map_or((0.0, 0.0), |p| p.value);
+
+        if power_logging && (cpu_power > 0.0 || gpu_power > 0.0) {
+            debug3!("Power: CPU={:.2}W, GPU={:.2}W", cpu_power, gpu_power);
+        }
+    }
+
+    /// CPU window closed: drop the SMC connection and IOReport state to save CPU
+    fn release(&mut self) {
+        // CPU window is not visible - DO NOT read battery or power to save CPU
+        // Menu bar only needs CPU/RAM/Disk which are already lightweight
+        debug3!("CPU window closed - skipping battery and power reads to save CPU");
+        // CPU window is not visible - clear SMC connection and IOReport subscription to save resources
+        if self.smc.take().is_some() {
+            debug3!("CPU window closed, SMC connection released");
+        }
+
+        // CRITICAL: Drop the IOReport subscriptions when the window closes to save CPU
+        // (dropping releases the subscription, its channels and the last sample)
+        if self.frequency.take().is_some() {
+            debug3!("CPU window closed, IOReport frequency subscription released");
+        }
+        if self.power.take().is_some() {
+            debug3!("CPU window closed, IOReport power subscription released");
+        }
+    }
+
+    /// Quit: close the SMC connection and release every IOReport object, subscriptions included
+    fn teardown(&mut self) {
+        if self.smc.take().is_some() {
+            debug3!("SMC connection closed");
+        }
+        self.frequency = None;
+        self.power = None;
+    }
+}
+
+/// One CPU-window sensor: `read` every budgeted interval of `collector` (see `sampling_budget`)
+/// while the window is visible and sampling isn't paused
+async fn sensor_loop(shared: Arc<Shared>, collector: Collector, read: fn(&mut Sensors)) {
+    let mut period = metrics::sampling_budget::interval(collector);
+    let mut ticks = background::interval(FIRST_SAMPLE_DELAY, period);
+    loop {
+        tokio::select! {
+            _ = ticks.tick() => {}
+            _ = shared.refresh.notified() => ticks.reset(),
+        }
+        if shared.paused.load(Ordering::Relaxed) {
+            continue;
+        }
+        tokio::task::block_in_place(|| {
+            if cpu_window_sampled() {
+                if let Ok(mut sensors) = shared.sensors.lock() {
+                    read(&mut sensors);
+                }
+            }
+        });
+        // An over-budget read stretches the interval; a run of fast ones restores it
+        let next = metrics::sampling_budget::interval(collector);
+        if next != period {
+            period = next;
+            ticks = background::interval(period, period);
+        }
+    }
+}
+
+/// CPU window visible (and no low-interference app in front): SMC / IOReport are worth reading
+fn cpu_window_sampled() -> bool {
+    // CRITICAL: Only read temperature when CPU window is visible (saves CPU)
+    // Check window visibility before expensive SMC operations
+    APP_HANDLE
+        .get()
+        .and_then(|app_handle| {
+            app_handle
+                .get_webview_window("cpu")
+                .and_then(|window| window.is_visible().ok().filter(|&visible| visible))
+        })
+        .is_some()
+        && !metrics::low_interference::is_active()
+}
+
+/// Battery level and charging state, read every sample while the CPU window is visible
+fn read_battery() {
+    // CRITICAL: Only read battery and power when CPU window is visible
+    // This ensures menu bar (which only shows CPU/RAM/Disk) remains super lightweight
+    // Battery reading via IOKit is lightweight, but we still only read when window is visible
+    // Battery state can change (charging/discharging), so we read frequently when visible
+    let (battery_level, is_charging, has_battery) = metrics::get_battery_info();
+    let power_logging = state::POWER_USAGE_LOGGING_ENABLED
+        .lock()
+        .map(|f| *f)
+        .unwrap_or(false);
+    if power_logging && has_battery {
+        debug3!(
+            "Battery updated: {:.1}%, charging={}",
+            battery_level,
+            is_charging
+        );
+    }
+}
+
+/// Create `System` and `Disks` outside their locks, so readers are never blocked on it
+fn init_system() {
+    debug3!("Sampler: initializing System and Disks");
+    // Create System outside the lock to avoid holding it
+    let new_system = System::new();
+    debug3!("Sampler: System::new() completed");
+    // Use try_lock to avoid blocking - if locked, skip initialization
+    if let Ok(mut sys) = SYSTEM.try_lock() {
+        if sys.is_none() {
+            *sys = Some(new_system);
+            debug3!("Sampler: System stored");
+        }
+    } else {
+        debug3!("Sampler: SYSTEM lock unavailable, skipping");
+    }
+
+    // Create Disks outside the lock
+    let mut new_disks = Disks::new();
+    new_disks.refresh(false);
+    debug3!("Sampler: Disks::new() and refresh completed");
+    if let Ok(mut disks) = DISKS.try_lock() {
+        if disks.is_none() {
+            *disks = Some(new_disks);
+            debug3!("Sampler: Disks stored");
+        }
+    } else {
+        debug3!("Sampler: DISKS lock unavailable, skipping");
+    }
+    debug3!("Sampler: initialization complete");
+}
+
+fn init_history() {
+    // Initialize history buffer (adaptive tiered storage with automatic downsampling)
+    // Keep a buffer that an early history import may already have created.
+    // Otherwise restore the persisted snapshot + write-ahead log from the last run.
+    if let Ok(mut history) = METRICS_HISTORY.try_lock() {
+        if history.is_none()
+            && crate::config::Config::history_persistence_enabled()
+            && crate::config::Config::history_sqlite_enabled()
+        {
+            *history = Some(metrics::history_store::open_and_seed());
+        } else if history.is_none() && crate::config::Config::history_persistence_enabled() {
+            let (recovered, wal) = metrics::history_wal::HistoryWal::recover();
+            *history = Some(recovered);
+            if let Ok(mut slot) = HISTORY_WAL.try_lock() {
+                *slot = wal;
+            }
+        }
+        history
+            .get_or_insert_with(metrics::history::HistoryBuffer::new)
+            .mark_session_start();
+        debug3!("Metrics history buffer initialized (capacity: 26 KB)");
+    } else {
+        debug3!(
+            "Warning: Could not initialize metrics history buffer - lock contention at startup"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_steer_interval_and_pause() {
+        let mut sampler = Sampler::new();
+        assert!(sampler.apply(Command::RefreshNow));
+        assert!(!sampler.apply(Command::SetInterval(Duration::from_millis(10))));
+        assert_eq!(sampler.interval, MIN_INTERVAL);
+        sampler.apply(Command::SetInterval(Duration::from_secs(600)));
+        assert_eq!(sampler.interval, MAX_INTERVAL);
+
+        assert!(!sampler.apply(Command::Pause));
+        assert!(!sampler.apply(Command::RefreshNow));
+        assert!(sampler.apply(Command::Resume));
+        assert!(!sampler.apply(Command::Resume));
+    }
+}
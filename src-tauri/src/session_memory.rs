@@ -466,6 +466,45 @@ pub fn clear_session(source: &str, session_id: u64) {
     debug!("Session memory: cleared session {}", key);
 }
 
+/// Remove the latest `user` message directly followed by the `assistant` reply, e.g. an exchange
+/// deleted in Discord. Returns whether it was found; a session already on disk is rewritten.
+pub fn remove_exchange(source: &str, session_id: u64, user: &str, assistant: &str) -> bool {
+    let (Some(user), Some(assistant)) = (
+        normalize_conversational_message("user", user),
+        normalize_conversational_message("assistant", assistant),
+    ) else {
+        return false;
+    };
+    let key = format!("{}-{}", source, session_id);
+    let was_persisted = {
+        let mut store = match session_store().lock() {
+            Ok(g) => g,
+            Err(_) => return false,
+        };
+        let Some(state) = store.get_mut(&key) else {
+            return false;
+        };
+        let Some(i) = state.messages.windows(2).rposition(|pair| {
+            pair[0].0 == "user"
+                && pair[0].1 == user
+                && pair[1].0 == "assistant"
+                && pair[1].1 == assistant
+        }) else {
+            return false;
+        };
+        let was_persisted = state.messages.len() > PERSIST_THRESHOLD;
+        state.messages.drain(i..i + 2);
+        was_persisted
+    };
+    if was_persisted {
+        if let Err(e) = persist_session(source, session_id) {
+            debug!("Session memory: persist failed: {}", e);
+        }
+    }
+    debug!("Session memory: removed an exchange from session {}", key);
+    true
+}
+
 /// Replace the in-memory session with compacted messages. Persists the old session first.
 /// Used after session compaction to replace verbose history with a concise summary.
 pub fn replace_session(source: &str, session_id: u64, new_messages: Vec<(String, String)>) {
@@ -812,7 +851,7 @@ mod tests {
         add_message, before_session_reset_export, clear_session, extract_assistant_final_answer,
         get_messages, last_user_preview_from_markdown, load_messages_from_latest_session_file,
         normalize_conversational_message, parse_session_markdown, prune_old_session_files,
-        session_filename_matches_id, truncate_session_preview,
+        remove_exchange, session_filename_matches_id, truncate_session_preview,
     };
     use std::sync::Mutex;
     use std::time::Duration;
@@ -896,6 +935,20 @@ mod tests {
         clear_session("discord", sid);
     }
 
+    #[test]
+    fn remove_exchange_drops_only_the_matching_pair() {
+        let sid = 99997_u64;
+        clear_session("discord", sid);
+        add_message("discord", sid, "user", "disk usage?");
+        add_message("discord", sid, "assistant", "61% used");
+        add_message("discord", sid, "user", "and now?");
+        assert!(!remove_exchange("discord", sid, "and now?", "61% used"));
+        assert!(remove_exchange("discord", sid, "disk usage?", "61% used"));
+        let msgs = get_messages("discord", sid);
+        assert_eq!(msgs, vec![("user".to_string(), "and now?".to_string())]);
+        clear_session("discord", sid);
+    }
+
     #[test]
     fn internal_artifacts_not_persisted() {
         // Use a unique session id so we don't collide with real sessions.
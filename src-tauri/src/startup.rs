@@ -0,0 +1,86 @@
+//! Staged startup (`startupDelaySecs`)
+//!
+//! Everything launching at login competes for CPU and disk, which slower Macs feel. With a delay
+//! configured, the menu bar and metric sampling start right away while heavy integrations wait:
+//! exporters (local API, InfluxDB, MQTT, process watch) start once the delay has passed, the AI
+//! agent (Ollama warmup, Discord, scheduler) [`STAGE_GAP_SECS`] later and background jobs
+//! (website monitors, downloads organizer, health probe) after that. History compaction (SQLite
+//! maintenance, JSON checkpoints) is held off until the delay has passed. Without a delay every
+//! stage runs inline, exactly as before.
+
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::config::Config;
+
+/// Seconds between consecutive stages once the delay has passed
+pub const STAGE_GAP_SECS: u64 = 10;
+
+/// Groups of integrations started together, in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Exporters,
+    Agent,
+    Background,
+}
+
+impl Stage {
+    /// Seconds after launch at which this stage starts, for a configured `delay`
+    pub fn offset_secs(self, delay: u64) -> u64 {
+        if delay == 0 {
+            return 0;
+        }
+        let index = match self {
+            Stage::Exporters => 0,
+            Stage::Agent => 1,
+            Stage::Background => 2,
+        };
+        delay + index * STAGE_GAP_SECS
+    }
+}
+
+/// `startupDelaySecs`, read once per launch
+pub fn delay_secs() -> u64 {
+    static DELAY: OnceLock<u64> = OnceLock::new();
+    *DELAY.get_or_init(Config::startup_delay_secs)
+}
+
+/// True until the startup delay has passed since launch
+pub fn in_startup_delay() -> bool {
+    crate::state::process_uptime_secs() < delay_secs()
+}
+
+/// Run `start` at `stage`: inline when no delay is configured, otherwise on a thread once the
+/// stage's offset has passed
+pub fn run_at(stage: Stage, start: impl FnOnce() + Send + 'static) {
+    let offset = stage.offset_secs(delay_secs());
+    if offset == 0 {
+        start();
+        return;
+    }
+    tracing::info!(
+        target: "mac_stats_startup",
+        "Startup delay: {:?} stage starts in {}s",
+        stage,
+        offset
+    );
+    std::thread::spawn(move || {
+        let waited = crate::state::process_uptime_secs();
+        std::thread::sleep(Duration::from_secs(offset.saturating_sub(waited)));
+        tracing::debug!(target: "mac_stats_startup", "Starting {:?} stage", stage);
+        start();
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stages_follow_the_delay() {
+        assert_eq!(Stage::Background.offset_secs(0), 0);
+        assert_eq!(Stage::Exporters.offset_secs(30), 30);
+        assert_eq!(Stage::Agent.offset_secs(30), 30 + STAGE_GAP_SECS);
+        assert_eq!(Stage::Background.offset_secs(30), 30 + 2 * STAGE_GAP_SECS);
+    }
+}
@@ -6,6 +6,11 @@
 //! - Caches (temperature, frequency, chip info)
 //! - IOReport subscriptions
 //!
+//! Sensor readings (temperature, frequencies, power, battery, GPU usage / temperature, top
+//! processes) live in one [`MetricsSnapshot`] behind an `ArcSwap`: writers publish a new
+//! snapshot with [`update_snapshot`], readers take [`snapshot`] without locking, so a reader
+//! never sees a 0.0 because a writer happened to hold a cache lock.
+//!
 //! Note: Some state remains global due to:
 //! - Thread-local requirements (UI must be on main thread)
 //! - Cross-thread access patterns
@@ -15,12 +20,18 @@
 //! and passing it through Tauri's state management.
 
 use crate::metrics::history::HistoryBuffer;
+use crate::metrics::history_store::HistoryStore;
+use crate::metrics::history_wal::HistoryWal;
+use arc_swap::ArcSwap;
 use objc2::rc::Retained;
 use objc2::runtime::AnyObject;
 use objc2_app_kit::NSStatusItem;
+use objc2_foundation::NSTimer;
 use std::cell::RefCell;
-use std::sync::{Mutex, OnceLock};
-use std::time::Instant;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicU64};
+use std::sync::{Arc, LazyLock, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 use sysinfo::{Disks, System};
 use tauri::AppHandle;
 
@@ -32,11 +43,23 @@ pub(crate) static LAST_SYSTEM_REFRESH: Mutex<Option<Instant>> = Mutex::new(None)
 // UI state
 // Note: Thread-local is required for UI elements that must be accessed from main thread
 thread_local! {
-    pub(crate) static STATUS_ITEM: RefCell<Option<Retained<NSStatusItem>>> = const { RefCell::new(None) };
+    /// Status items by `menuBarItems` id (`"main"` unless split)
+    pub(crate) static STATUS_ITEMS: RefCell<BTreeMap<String, Retained<NSStatusItem>>> = const { RefCell::new(BTreeMap::new()) };
     pub(crate) static CLICK_HANDLER: RefCell<Option<Retained<AnyObject>>> = const { RefCell::new(None) };
+    /// Repeating timer that sends `processMenuBarUpdate:` to the click handler
+    pub(crate) static UPDATE_TIMER: RefCell<Option<Retained<NSTimer>>> = const { RefCell::new(None) };
 }
 pub(crate) static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
-pub(crate) static MENU_BAR_TEXT: Mutex<Option<String>> = Mutex::new(None);
+/// Pending status item titles by item id, taken by `process_menu_bar_update`
+pub(crate) static MENU_BAR_TEXT: Mutex<BTreeMap<String, String>> = Mutex::new(BTreeMap::new());
+/// Titles applied on the main thread (bumped by `process_menu_bar_update`). The update loop and
+/// `mac_stats ui-selftest` use it to tell a stalled main-thread timer from a stalled sampler.
+pub(crate) static MENU_BAR_TITLES_APPLIED: AtomicU64 = AtomicU64::new(0);
+/// "Pause Updates" in the status menu: the update loop stops storing new titles
+pub(crate) static MENU_BAR_PAUSED: AtomicBool = AtomicBool::new(false);
+/// Recent CPU readings for the menu bar sparkline (`menuBarSparkline`), one per update
+pub(crate) static CPU_SPARKLINE: Mutex<crate::ui::status_sparkline::Sparkline> =
+    Mutex::new(crate::ui::status_sparkline::Sparkline::new());
 
 /// Process start time (for Agent Ops uptime). Set once during Tauri setup.
 pub(crate) static PROCESS_START: OnceLock<Instant> = OnceLock::new();
@@ -80,48 +103,96 @@ pub(crate) fn format_process_uptime() -> String {
 // Caches
 pub(crate) static CHIP_INFO_CACHE: OnceLock<String> = OnceLock::new();
 
-pub(crate) static CAN_READ_TEMPERATURE: OnceLock<bool> = OnceLock::new();
-pub(crate) static CAN_READ_FREQUENCY: OnceLock<bool> = OnceLock::new();
-pub(crate) static CAN_READ_CPU_POWER: OnceLock<bool> = OnceLock::new();
-pub(crate) static CAN_READ_GPU_POWER: OnceLock<bool> = OnceLock::new();
+/// A cached value and when it was read
+#[derive(Debug, Clone)]
+pub(crate) struct Reading<T> {
+    pub value: T,
+    pub at: Instant,
+}
+
+impl<T> Reading<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            at: Instant::now(),
+        }
+    }
+
+    pub fn age(&self) -> Duration {
+        self.at.elapsed()
+    }
+}
+
+/// Latest sensor readings; None until first read (or after the cache was cleared)
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MetricsSnapshot {
+    /// CPU temperature (°C)
+    pub temperature: Option<Reading<f32>>,
+    /// Average CPU frequency (GHz)
+    pub frequency: Option<Reading<f32>>,
+    pub p_core_frequency: Option<Reading<f32>>,
+    pub e_core_frequency: Option<Reading<f32>>,
+    /// (CPU watts, GPU watts)
+    pub power: Option<Reading<(f32, f32)>>,
+    /// Neural Engine watts (`powermetrics` provider only)
+    pub ane_power: Option<Reading<f32>>,
+    /// (level %, charging); level < 0 when there is no battery
+    pub battery: Option<Reading<(f32, bool)>>,
+    pub gpu_usage: Option<Reading<f32>>,
+    /// Average GPU cluster temperature (°C)
+    pub gpu_temperature: Option<Reading<f32>>,
+    /// Top processes for the CPU window (refreshed every 10s while it is open)
+    pub processes: Option<Reading<Vec<crate::metrics::ProcessUsage>>>,
+}
+
+static SNAPSHOT: LazyLock<ArcSwap<MetricsSnapshot>> =
+    LazyLock::new(|| ArcSwap::from_pointee(MetricsSnapshot::default()));
+
+/// Current readings (lock-free; cheap to call from any thread)
+pub(crate) fn snapshot() -> Arc<MetricsSnapshot> {
+    SNAPSHOT.load_full()
+}
+
+/// Publish a new snapshot with `update` applied to a copy of the current one. `update` may run
+/// more than once when writers race, so it must only assign fields.
+pub(crate) fn update_snapshot(update: impl Fn(&mut MetricsSnapshot)) {
+    SNAPSHOT.rcu(|current| {
+        let mut next = MetricsSnapshot::clone(current);
+        update(&mut next);
+        next
+    });
+}
 
-// Temperature cache: (temperature_value, last_update_timestamp)
-pub(crate) static TEMP_CACHE: Mutex<Option<(f32, Instant)>> = Mutex::new(None);
-pub(crate) static M3_TEMP_KEY: Mutex<Option<String>> = Mutex::new(None);
+/// Raw SMC key found to give the CPU temperature when `cpu_temperature()` reads 0
+/// (M3 `Tf..` or Intel `TC..` keys)
+pub(crate) static RAW_TEMP_KEY: Mutex<Option<String>> = Mutex::new(None);
 
-// Frequency cache: (frequency_value_ghz, last_update_timestamp)
-pub(crate) static FREQ_CACHE: Mutex<Option<(f32, Instant)>> = Mutex::new(None);
+pub(crate) static GPU_TEMP_KEYS: Mutex<Option<Vec<String>>> = Mutex::new(None);
 
-// Process list cache: (process_list, last_update_timestamp)
-// Cache processes for 30 seconds to avoid expensive refresh on every call
-pub(crate) static PROCESS_CACHE: Mutex<Option<(Vec<crate::metrics::ProcessUsage>, Instant)>> =
+// Fan monitor: latest reading and audible seconds per day (see metrics::fan_noise)
+pub(crate) static FAN_READING: Mutex<Option<crate::metrics::fan_noise::FanReading>> =
     Mutex::new(None);
-// P-core and E-core frequency caches: (frequency_value_ghz, last_update_timestamp)
-pub(crate) static P_CORE_FREQ_CACHE: Mutex<Option<(f32, Instant)>> = Mutex::new(None);
-pub(crate) static E_CORE_FREQ_CACHE: Mutex<Option<(f32, Instant)>> = Mutex::new(None);
+pub(crate) static FAN_AUDIBLE_LOG: Mutex<Option<crate::metrics::fan_noise::FanAudibleLog>> =
+    Mutex::new(None);
+
+// Per-process CPU samples for the last minute (process list sparklines).
+// Lock order: SYSTEM, then PROCESS_HISTORY.
+pub(crate) static PROCESS_HISTORY: Mutex<Option<crate::metrics::process_history::ProcessHistory>> =
+    Mutex::new(None);
+// Long-term samples for `watchedProcesses` (see metrics::process_watch).
+// Lock order: SYSTEM, then WATCHED_PROCESS_HISTORY.
+pub(crate) static WATCHED_PROCESS_HISTORY: Mutex<
+    Option<crate::metrics::process_watch::WatchedHistory>,
+> = Mutex::new(None);
 #[allow(dead_code)]
 pub(crate) static M3_FREQ_KEY: Mutex<Option<String>> = Mutex::new(None);
 pub(crate) static NOMINAL_FREQ: OnceLock<f32> = OnceLock::new();
-pub(crate) static LAST_FREQ_READ: Mutex<Option<Instant>> = Mutex::new(None);
-pub(crate) static LAST_TEMP_UPDATE: Mutex<Option<Instant>> = Mutex::new(None);
 
 // Rate limiting for get_cpu_details() - prevent excessive calls
 pub(crate) static LAST_CPU_DETAILS_CALL: Mutex<Option<Instant>> = Mutex::new(None);
 
-// IOReport state (frequency)
-pub(crate) static IOREPORT_SUBSCRIPTION: Mutex<Option<usize>> = Mutex::new(None);
-pub(crate) static IOREPORT_CHANNELS: Mutex<Option<usize>> = Mutex::new(None);
-pub(crate) static IOREPORT_SUBSCRIPTION_DICT: Mutex<Option<usize>> = Mutex::new(None);
-pub(crate) static IOREPORT_ORIGINAL_CHANNELS: Mutex<Option<usize>> = Mutex::new(None);
-pub(crate) static LAST_IOREPORT_SAMPLE: Mutex<Option<(usize, Instant)>> = Mutex::new(None);
-
-// IOReport state (power)
-pub(crate) static IOREPORT_POWER_SUBSCRIPTION: Mutex<Option<usize>> = Mutex::new(None);
-pub(crate) static IOREPORT_POWER_CHANNELS: Mutex<Option<usize>> = Mutex::new(None);
-pub(crate) static IOREPORT_POWER_SUBSCRIPTION_DICT: Mutex<Option<usize>> = Mutex::new(None);
-pub(crate) static IOREPORT_POWER_ORIGINAL_CHANNELS: Mutex<Option<usize>> = Mutex::new(None);
-pub(crate) static LAST_IOREPORT_POWER_SAMPLE: Mutex<Option<(usize, Instant)>> = Mutex::new(None);
-pub(crate) static LAST_POWER_READ_TIME: Mutex<Option<Instant>> = Mutex::new(None);
+// IOReport handles live on the sampling thread (see `sampler`)
+
 // Flag to enable detailed frequency logging
 pub(crate) static FREQUENCY_LOGGING_ENABLED: Mutex<bool> = Mutex::new(false);
 // Flag to enable detailed power usage logging
@@ -131,27 +202,31 @@ pub(crate) static POWER_USAGE_LOGGING_ENABLED: Mutex<bool> = Mutex::new(false);
 // Default to true (show decorations) for better UX
 pub(crate) static WINDOW_DECORATIONS: Mutex<bool> = Mutex::new(true);
 
-// Power and battery caches
-// CPU/GPU power cache: (cpu_power_watts, gpu_power_watts, last_update_timestamp)
-pub(crate) static POWER_CACHE: Mutex<Option<(f32, f32, Instant)>> = Mutex::new(None);
-// Last successful power reading (cpu_power_watts, gpu_power_watts)
-// This is used as a fallback when POWER_CACHE is locked, to prevent flickering to 0.0
-// CRITICAL: Only updated when we successfully read from POWER_CACHE
-pub(crate) static LAST_SUCCESSFUL_POWER: Mutex<Option<(f32, f32)>> = Mutex::new(None);
-// Battery cache: (battery_level_percent, is_charging, last_update_timestamp)
-// Battery is read every second in background thread (IOKit is lightweight)
-pub(crate) static BATTERY_CACHE: Mutex<Option<(f32, bool, Instant)>> = Mutex::new(None);
-// GPU usage cache: (gpu_usage_percent, last_update_timestamp)
-// GPU usage reading is expensive, so we cache it for 2 seconds
-pub(crate) static GPU_USAGE_CACHE: Mutex<Option<(f32, Instant)>> = Mutex::new(None);
+// Battery drain: sliding window of charge levels plus the latest energy rate
+pub(crate) static BATTERY_DRAIN: Mutex<crate::metrics::battery_drain::DrainTracker> =
+    Mutex::new(crate::metrics::battery_drain::DrainTracker::new());
 // Reserved for future rate limiting when IOReport power reading is implemented
 #[allow(dead_code)]
 pub(crate) static LAST_POWER_READ: Mutex<Option<Instant>> = Mutex::new(None);
 #[allow(dead_code)]
 pub(crate) static LAST_BATTERY_READ: Mutex<Option<Instant>> = Mutex::new(None);
 
+// Latest network/disk throughput from the update loop (for `metrics-push` subscribers)
+pub(crate) static LATEST_IO_RATES: Mutex<Option<crate::metrics::io_rates::IoRates>> =
+    Mutex::new(None);
+
+// Last time a `menubar` alert action fired, per metric ("" for alerts without one); drives the
+// red menu bar columns and the `Alert ✕` cue
+pub(crate) static ALERT_MENU_BAR_HIGHLIGHT: Mutex<BTreeMap<String, Instant>> =
+    Mutex::new(BTreeMap::new());
+
 // Metrics history buffer for adaptive tiered history storage
 pub(crate) static METRICS_HISTORY: Mutex<Option<HistoryBuffer>> = Mutex::new(None);
+// Write-ahead log for METRICS_HISTORY (None when persistence is disabled or unavailable).
+// Lock order: METRICS_HISTORY before HISTORY_WAL.
+pub(crate) static HISTORY_WAL: Mutex<Option<HistoryWal>> = Mutex::new(None);
+// SQLite history store (None unless `historyStore` is "sqlite"); replaces HISTORY_WAL when open.
+pub(crate) static HISTORY_STORE: Mutex<Option<HistoryStore>> = Mutex::new(None);
 
 /// Application state structure (future refactoring target)
 ///
@@ -198,3 +273,21 @@ impl AppState {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn update_snapshot_keeps_other_readings() {
+        update_snapshot(|s| s.power = Some(Reading::new((4.5, 1.25))));
+        let before = snapshot();
+        update_snapshot(|s| s.gpu_usage = Some(Reading::new(30.0)));
+        let after = snapshot();
+
+        assert_eq!(after.power.as_ref().map(|p| p.value), Some((4.5, 1.25)));
+        assert_eq!(after.gpu_usage.as_ref().map(|g| g.value), Some(30.0));
+        // Readers holding the old snapshot are unaffected
+        assert!(before.gpu_usage.is_none() || !Arc::ptr_eq(&before, &after));
+    }
+}
@@ -0,0 +1,67 @@
+//! About window (`about.html`)
+//!
+//! Replaces the standard AppKit about panel: version and build date, the changelog (from
+//! `get_changelog`) and an update check against GitHub releases (`check_for_update`, see
+//! `updates`) with a button that downloads the latest DMG. Opened by a right click on the
+//! status item or the status menu's About entry; hidden rather than destroyed when closed.
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::config::Config;
+
+/// Tauri window label
+pub const LABEL: &str = "about";
+const SIZE: (f64, f64) = (460.0, 560.0);
+
+/// Result of `get_about_info`
+#[derive(Debug, Clone, Serialize)]
+pub struct AboutInfo {
+    pub name: &'static str,
+    pub version: String,
+    pub build_date: String,
+    pub releases_url: &'static str,
+}
+
+/// Name, version and build date for the about window
+#[tauri::command]
+pub fn get_about_info() -> AboutInfo {
+    AboutInfo {
+        name: "mac-stats",
+        version: Config::version(),
+        build_date: Config::build_date(),
+        releases_url: crate::updates::RELEASES_URL,
+    }
+}
+
+/// Show and focus the about window, creating it on first use
+pub fn show(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_webview_window(LABEL) {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+        return;
+    }
+    let built = WebviewWindowBuilder::new(app_handle, LABEL, WebviewUrl::App("about.html".into()))
+        .title("About mac-stats")
+        .inner_size(SIZE.0, SIZE.1)
+        .min_inner_size(380.0, 360.0)
+        .resizable(true)
+        .minimizable(false)
+        .visible(true)
+        .build();
+    match built {
+        Ok(window) => {
+            let window_for_close = window.clone();
+            window.on_window_event(move |event| {
+                if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                    api.prevent_close();
+                    let _ = window_for_close.hide();
+                }
+            });
+            let _ = window.set_focus();
+            tracing::debug!("About window created");
+        }
+        Err(e) => tracing::warn!("Could not create about window: {}", e),
+    }
+}
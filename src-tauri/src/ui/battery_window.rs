@@ -0,0 +1,45 @@
+//! Battery window (`battery.html`)
+//!
+//! Opened from the status menu ("Open Battery Window"): charge level, adapter wattage and
+//! discharge rate charted from the metrics history (`battery_level`, `adapter_power`,
+//! `battery_power`), plus the current power flow, health and estimated time remaining from
+//! `get_battery_details` (see `metrics::battery_details`). Hidden rather than destroyed when
+//! closed, like the network window.
+
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+/// Tauri window label
+pub const LABEL: &str = "battery";
+const SIZE: (f64, f64) = (520.0, 600.0);
+
+/// Show and focus the battery window, creating it on first use
+pub fn show(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_webview_window(LABEL) {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+        return;
+    }
+    let built =
+        WebviewWindowBuilder::new(app_handle, LABEL, WebviewUrl::App("battery.html".into()))
+            .title("Battery")
+            .inner_size(SIZE.0, SIZE.1)
+            .min_inner_size(400.0, 420.0)
+            .resizable(true)
+            .visible(true)
+            .build();
+    match built {
+        Ok(window) => {
+            let window_for_close = window.clone();
+            window.on_window_event(move |event| {
+                if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                    api.prevent_close();
+                    let _ = window_for_close.hide();
+                }
+            });
+            let _ = window.set_focus();
+            tracing::debug!("Battery window created");
+        }
+        Err(e) => tracing::warn!("Could not create battery window: {}", e),
+    }
+}
@@ -0,0 +1,185 @@
+//! Status item click actions (`statusItemClickActions`)
+//!
+//! Maps left, right, middle and ⌥-left clicks on a status item to an action, e.g.
+//! `{"left": "cpuWindow", "right": "menu", "optionLeft": "copyStats", "middle": {"run":
+//! "open -a Terminal"}}`. Actions: `menu` (the status menu), `cpuWindow`, `about`,
+//! `activityMonitor`, `copyStats` (current stats as text on the clipboard; `{"copyStats":
+//! "markdown"}` or `"json"` for another format), `{"run": "<shell command>"}` and `none`. Control-click counts as a right click. Unset clicks keep the
+//! defaults: left opens the menu, right the About window, the others do nothing. Read on every
+//! click, so edits apply right away.
+
+use super::copy_stats::StatsFormat;
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+/// Which click happened on the status item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Click {
+    Left,
+    Right,
+    Middle,
+    OptionLeft,
+}
+
+impl Click {
+    /// From `NSEvent.buttonNumber` and the ⌥ / ⌃ modifier state
+    pub fn from_event(button_number: isize, option: bool, control: bool) -> Self {
+        match button_number {
+            0 if control => Click::Right,
+            0 if option => Click::OptionLeft,
+            0 => Click::Left,
+            1 => Click::Right,
+            _ => Click::Middle,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClickAction {
+    Menu,
+    CpuWindow,
+    About,
+    ActivityMonitor,
+    /// Current stats on the clipboard (see `copy_stats`)
+    CopyStats(StatsFormat),
+    /// Shell command, run with `/bin/sh -c` (not in guest mode)
+    Run(String),
+    None,
+}
+
+fn parse_action(value: &serde_json::Value) -> Option<ClickAction> {
+    if let Some(command) = value.get("run").and_then(|v| v.as_str()) {
+        let command = command.trim();
+        return (!command.is_empty()).then(|| ClickAction::Run(command.to_string()));
+    }
+    if let Some(format) = value.get("copyStats").and_then(|v| v.as_str()) {
+        return StatsFormat::parse(format).map(ClickAction::CopyStats);
+    }
+    match value.as_str()?.trim() {
+        "menu" => Some(ClickAction::Menu),
+        "cpuWindow" => Some(ClickAction::CpuWindow),
+        "about" => Some(ClickAction::About),
+        "activityMonitor" => Some(ClickAction::ActivityMonitor),
+        "copyStats" => Some(ClickAction::CopyStats(StatsFormat::Text)),
+        "none" => Some(ClickAction::None),
+        _ => None,
+    }
+}
+
+/// Action per click
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClickActions {
+    pub left: ClickAction,
+    pub right: ClickAction,
+    pub middle: ClickAction,
+    pub option_left: ClickAction,
+}
+
+impl Default for ClickActions {
+    fn default() -> Self {
+        Self {
+            left: ClickAction::Menu,
+            right: ClickAction::About,
+            middle: ClickAction::None,
+            option_left: ClickAction::None,
+        }
+    }
+}
+
+impl ClickActions {
+    /// From the `statusItemClickActions` value; missing or invalid entries keep the default
+    pub fn from_json(value: Option<&serde_json::Value>) -> Self {
+        let mut actions = Self::default();
+        let Some(obj) = value.and_then(|v| v.as_object()) else {
+            return actions;
+        };
+        for (key, slot) in [
+            ("left", &mut actions.left),
+            ("right", &mut actions.right),
+            ("middle", &mut actions.middle),
+            ("optionLeft", &mut actions.option_left),
+        ] {
+            let Some(value) = obj.get(key) else {
+                continue;
+            };
+            match parse_action(value) {
+                Some(action) => *slot = action,
+                None => tracing::warn!("statusItemClickActions.{}: unknown action {}", key, value),
+            }
+        }
+        actions
+    }
+
+    pub fn for_click(&self, click: Click) -> &ClickAction {
+        match click {
+            Click::Left => &self.left,
+            Click::Right => &self.right,
+            Click::Middle => &self.middle,
+            Click::OptionLeft => &self.option_left,
+        }
+    }
+}
+
+/// Open Activity Monitor
+pub fn open_activity_monitor() {
+    if let Err(e) = std::process::Command::new("/usr/bin/open")
+        .args(["-b", "com.apple.ActivityMonitor"])
+        .spawn()
+    {
+        debug1!("Opening Activity Monitor failed: {}", e);
+    }
+}
+
+/// Run a configured shell command in the background (skipped in guest mode)
+pub fn run_command(command: &str) {
+    if crate::guest_mode::is_active() {
+        debug1!("Click command skipped (guest mode)");
+        return;
+    }
+    let mut cmd = std::process::Command::new("/bin/sh");
+    cmd.arg("-c").arg(command);
+    crate::security::host_exec_env::apply_host_exec_env_hardening(&mut cmd);
+    match cmd.spawn() {
+        Ok(mut child) => {
+            // Reap it so it does not linger as a zombie
+            std::thread::spawn(move || {
+                let _ = child.wait();
+            });
+        }
+        Err(e) => tracing::warn!("Click command '{}' failed to start: {}", command, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_actions_with_defaults() {
+        assert_eq!(ClickActions::from_json(None), ClickActions::default());
+        let actions = ClickActions::from_json(Some(&serde_json::json!({
+            "left": "cpuWindow",
+            "right": "menu",
+            "middle": {"run": "open -a Terminal"},
+            "optionLeft": "launchRockets"
+        })));
+        let copy = ClickActions::from_json(Some(&serde_json::json!({
+            "optionLeft": {"copyStats": "markdown"}
+        })));
+        assert_eq!(
+            copy.option_left,
+            ClickAction::CopyStats(StatsFormat::Markdown)
+        );
+        assert_eq!(actions.left, ClickAction::CpuWindow);
+        assert_eq!(actions.right, ClickAction::Menu);
+        assert_eq!(
+            actions.for_click(Click::Middle),
+            &ClickAction::Run("open -a Terminal".to_string())
+        );
+        assert_eq!(actions.option_left, ClickAction::None);
+
+        assert_eq!(Click::from_event(0, false, true), Click::Right);
+        assert_eq!(Click::from_event(0, true, false), Click::OptionLeft);
+        assert_eq!(Click::from_event(2, false, false), Click::Middle);
+    }
+}
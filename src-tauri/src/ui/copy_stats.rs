@@ -0,0 +1,106 @@
+//! Copy current stats to the clipboard
+//!
+//! One fresh snapshot (the same data as `mac_stats stats`) as plain text, JSON or Markdown
+//! tables on the general pasteboard, for pasting into bug reports or chat. Used by the status
+//! menu's Copy Stats submenu, the `copyStats` click action and `copy_stats_to_clipboard`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::metrics::cli;
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StatsFormat {
+    /// The `mac_stats stats` text
+    #[default]
+    Text,
+    /// `{timestamp, metrics, cpu}` as `mac_stats stats --json` prints it
+    Json,
+    /// Metric and top process tables
+    Markdown,
+}
+
+impl StatsFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "text" | "txt" | "plain" => Some(Self::Text),
+            "json" => Some(Self::Json),
+            "markdown" | "md" => Some(Self::Markdown),
+            _ => None,
+        }
+    }
+}
+
+/// A fresh snapshot in `format`
+pub fn stats_text(format: StatsFormat) -> Result<String, String> {
+    let snapshot = cli::snapshot();
+    match format {
+        StatsFormat::Text => Ok(cli::human_text(&snapshot)),
+        StatsFormat::Json => serde_json::to_string_pretty(&snapshot).map_err(|e| e.to_string()),
+        StatsFormat::Markdown => Ok(cli::markdown_text(&snapshot)),
+    }
+}
+
+/// Put `text` on the general pasteboard
+pub fn copy_text(text: &str) -> Result<(), String> {
+    use std::io::Write;
+    let mut child = std::process::Command::new("/usr/bin/pbcopy")
+        .stdin(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("pbcopy: {}", e))?;
+    child
+        .stdin
+        .take()
+        .ok_or("pbcopy: no stdin")?
+        .write_all(text.as_bytes())
+        .map_err(|e| format!("pbcopy: {}", e))?;
+    let status = child.wait().map_err(|e| format!("pbcopy: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("pbcopy exited with {}", status))
+    }
+}
+
+/// Copy current stats in the background (sampling takes a moment; menu actions must not block)
+pub fn copy_in_background(format: StatsFormat) {
+    std::thread::spawn(move || {
+        if let Err(e) = stats_text(format).and_then(|text| copy_text(&text)) {
+            debug1!("Copying stats failed: {}", e);
+        }
+    });
+}
+
+/// Copy current stats as `format` (`"text"` when omitted, `"json"` or `"markdown"`); returns
+/// the copied text
+#[tauri::command]
+pub async fn copy_stats_to_clipboard(format: Option<String>) -> Result<String, String> {
+    let format = match format.as_deref() {
+        None => StatsFormat::Text,
+        Some(f) => StatsFormat::parse(f)
+            .ok_or_else(|| format!("Unknown format '{}' (expected text, json or markdown)", f))?,
+    };
+    tokio::task::spawn_blocking(move || {
+        let text = stats_text(format)?;
+        copy_text(&text)?;
+        Ok::<_, String>(text)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_formats() {
+        assert_eq!(StatsFormat::parse("Markdown"), Some(StatsFormat::Markdown));
+        assert_eq!(StatsFormat::parse(" md "), Some(StatsFormat::Markdown));
+        assert_eq!(StatsFormat::parse("json"), Some(StatsFormat::Json));
+        assert_eq!(StatsFormat::parse("plain"), Some(StatsFormat::Text));
+        assert_eq!(StatsFormat::parse("yaml"), None);
+    }
+}
@@ -0,0 +1,43 @@
+//! Multi-machine dashboard window (`dashboard.html`)
+//!
+//! Opened from the status menu ("Open Dashboard"): one tile per machine, this Mac first, with
+//! CPU/GPU/RAM/disk, temperature, battery and alert state from `get_dashboard` (see
+//! `metrics::remote_machines`). Hidden rather than destroyed when closed.
+
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+/// Tauri window label
+pub const LABEL: &str = "dashboard";
+const SIZE: (f64, f64) = (720.0, 520.0);
+
+/// Show and focus the dashboard window, creating it on first use
+pub fn show(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_webview_window(LABEL) {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+        return;
+    }
+    let built =
+        WebviewWindowBuilder::new(app_handle, LABEL, WebviewUrl::App("dashboard.html".into()))
+            .title("Dashboard")
+            .inner_size(SIZE.0, SIZE.1)
+            .min_inner_size(360.0, 300.0)
+            .resizable(true)
+            .visible(true)
+            .build();
+    match built {
+        Ok(window) => {
+            let window_for_close = window.clone();
+            window.on_window_event(move |event| {
+                if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                    api.prevent_close();
+                    let _ = window_for_close.hide();
+                }
+            });
+            let _ = window.set_focus();
+            tracing::debug!("Dashboard window created");
+        }
+        Err(e) => tracing::warn!("Could not create dashboard window: {}", e),
+    }
+}
@@ -0,0 +1,42 @@
+//! Log viewer window (`logs.html`)
+//!
+//! Opened from the status menu ("Open Log Viewer"): `~/.mac-stats/debug.log` as a table with
+//! level, module, tag and text filters and live follow, from `read_log_entries` (see
+//! `logging::entries`). Hidden rather than destroyed when closed.
+
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+/// Tauri window label
+pub const LABEL: &str = "logs";
+const SIZE: (f64, f64) = (900.0, 560.0);
+
+/// Show and focus the log viewer window, creating it on first use
+pub fn show(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_webview_window(LABEL) {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+        return;
+    }
+    let built = WebviewWindowBuilder::new(app_handle, LABEL, WebviewUrl::App("logs.html".into()))
+        .title("Log Viewer")
+        .inner_size(SIZE.0, SIZE.1)
+        .min_inner_size(520.0, 300.0)
+        .resizable(true)
+        .visible(true)
+        .build();
+    match built {
+        Ok(window) => {
+            let window_for_close = window.clone();
+            window.on_window_event(move |event| {
+                if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                    api.prevent_close();
+                    let _ = window_for_close.hide();
+                }
+            });
+            let _ = window.set_focus();
+            tracing::debug!("Log viewer window created");
+        }
+        Err(e) => tracing::warn!("Could not create log viewer window: {}", e),
+    }
+}
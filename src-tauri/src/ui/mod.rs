@@ -2,4 +2,27 @@
 //!
 //! Contains UI-related functionality including status bar and window management.
 
+pub mod about_window;
+pub mod battery_window;
+pub mod click_actions;
+pub mod copy_stats;
+pub mod dashboard_window;
+pub mod log_window;
+pub mod network_window;
+pub mod onboarding_window;
+pub mod reduced_motion;
+pub mod selftest;
+pub mod status_accessibility;
+pub mod status_alerts;
 pub mod status_bar;
+pub mod status_compaction;
+pub mod status_extras;
+pub mod status_items;
+pub mod status_menu;
+pub mod status_removal;
+pub mod status_sparkline;
+pub mod status_template;
+pub mod theme;
+pub mod widget_window;
+pub mod window_frame;
+pub mod window_pin;
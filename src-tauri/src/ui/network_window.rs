@@ -0,0 +1,44 @@
+//! Network details window (`network.html`)
+//!
+//! Opened from the status menu ("Open Network Window"): a live chart of per-interface
+//! throughput, bytes transferred this session and the top talking processes, from
+//! `get_network_details` / `get_network_history` (see `metrics::network_details`). Like the
+//! CPU window it is hidden rather than destroyed when closed.
+
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+/// Tauri window label
+pub const LABEL: &str = "network";
+const SIZE: (f64, f64) = (560.0, 640.0);
+
+/// Show and focus the network window, creating it on first use
+pub fn show(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_webview_window(LABEL) {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+        return;
+    }
+    let built =
+        WebviewWindowBuilder::new(app_handle, LABEL, WebviewUrl::App("network.html".into()))
+            .title("Network")
+            .inner_size(SIZE.0, SIZE.1)
+            .min_inner_size(420.0, 400.0)
+            .resizable(true)
+            .visible(true)
+            .build();
+    match built {
+        Ok(window) => {
+            let window_for_close = window.clone();
+            window.on_window_event(move |event| {
+                if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                    api.prevent_close();
+                    let _ = window_for_close.hide();
+                }
+            });
+            let _ = window.set_focus();
+            tracing::debug!("Network window created");
+        }
+        Err(e) => tracing::warn!("Could not create network window: {}", e),
+    }
+}
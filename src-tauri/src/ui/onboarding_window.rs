@@ -0,0 +1,391 @@
+//! First-run onboarding window (`onboarding.html`)
+//!
+//! Shown once on the first launch (no `config.json` yet; never in guest mode). It walks
+//! through a test notification (macOS asks for permission on the first one), launch at login,
+//! the menu bar metrics and update interval, and the capability probe with an explanation for
+//! every sensor this Mac lacks. Done (`complete_onboarding`) writes the choices and
+//! `onboardingComplete: true`; Skip or closing the window only writes the flag.
+//!
+//! The metrics map onto the existing layouts: CPU + temperature is the compact layout,
+//! CPU/GPU/RAM/SSD the grid, anything else a `columns` menu bar template. Labels and
+//! explanations come from the `i18n` catalogs.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
+
+use crate::capabilities::{self, Capabilities};
+use crate::config::Config;
+use crate::i18n::Lang;
+
+/// Tauri window label
+pub const LABEL: &str = "onboarding";
+const SIZE: (f64, f64) = (520.0, 640.0);
+
+/// Menu bar metrics offered, in menu bar order: id, label key and the template segment
+const METRICS: &[(&str, &str, &str)] = &[
+    ("cpu", "onboarding.metric_cpu", "CPU: {cpu}%"),
+    ("gpu", "onboarding.metric_gpu", "GPU: {gpu}%"),
+    ("ram", "onboarding.metric_ram", "RAM: {ram}%"),
+    ("disk", "onboarding.metric_disk", "SSD: {disk}%"),
+    ("temp", "onboarding.metric_temp", "Temp: {temp}{temp_unit}"),
+    ("fan", "onboarding.metric_fan", "Fan: {fan}"),
+    ("battery", "onboarding.metric_battery", "Bat: {battery}%"),
+    ("net", "onboarding.metric_net", "Net: {net_rx}{net_unit}"),
+];
+const COMPACT: &[&str] = &["cpu", "temp"];
+const GRID: &[&str] = &["cpu", "gpu", "ram", "disk"];
+/// Choices for the update interval, in seconds
+const INTERVALS: &[u64] = &[1, 2, 3, 5, 10, 30];
+
+/// Set by [`note_first_launch`] before anything writes `config.json`
+static FIRST_LAUNCH: AtomicBool = AtomicBool::new(false);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricOption {
+    pub id: &'static str,
+    pub label: &'static str,
+    /// False when this Mac has no such sensor (the value would always read `--`)
+    pub available: bool,
+}
+
+/// One capability with what it means for the user
+#[derive(Debug, Clone, Serialize)]
+pub struct CapabilityNote {
+    pub name: &'static str,
+    pub available: bool,
+    pub detail: &'static str,
+}
+
+/// Result of `get_onboarding_info`
+#[derive(Debug, Clone, Serialize)]
+pub struct OnboardingInfo {
+    pub metrics: Vec<MetricOption>,
+    /// Metrics of the current layout
+    pub selected: Vec<&'static str>,
+    pub update_interval_secs: u64,
+    pub intervals: &'static [u64],
+    pub capabilities: Vec<CapabilityNote>,
+}
+
+/// The probe result in plain words
+fn capability_notes(lang: Lang, caps: &Capabilities) -> Vec<CapabilityNote> {
+    let note =
+        |key: &'static str, available: bool, yes: &'static str, no: &'static str| CapabilityNote {
+            name: lang.text(key),
+            available,
+            detail: lang.text(if available { yes } else { no }),
+        };
+    vec![
+        note(
+            "capability.temperature",
+            caps.temperature,
+            "capability.temperature_yes",
+            "capability.temperature_no",
+        ),
+        note(
+            "capability.gpu_temperature",
+            caps.gpu_temperature,
+            "capability.gpu_temperature_yes",
+            "capability.gpu_temperature_no",
+        ),
+        note(
+            "capability.fan",
+            caps.fan,
+            "capability.fan_yes",
+            "capability.fan_no",
+        ),
+        note(
+            "capability.frequency",
+            caps.frequency,
+            "capability.frequency_yes",
+            "capability.frequency_no",
+        ),
+        note(
+            "capability.power",
+            caps.cpu_power,
+            "capability.power_yes",
+            if caps.apple_silicon {
+                "capability.power_no"
+            } else {
+                "capability.power_intel"
+            },
+        ),
+        note(
+            "capability.powermetrics",
+            caps.powermetrics,
+            "capability.powermetrics_yes",
+            "capability.powermetrics_no",
+        ),
+        note(
+            "capability.battery",
+            caps.battery,
+            "capability.battery_yes",
+            "capability.battery_no",
+        ),
+    ]
+}
+
+fn metric_options(lang: Lang, caps: &Capabilities) -> Vec<MetricOption> {
+    METRICS
+        .iter()
+        .map(|&(id, label, _)| MetricOption {
+            id,
+            label: lang.text(label),
+            available: match id {
+                "temp" => caps.temperature,
+                "fan" => caps.fan,
+                "battery" => caps.battery,
+                _ => true,
+            },
+        })
+        .collect()
+}
+
+/// Metrics shown by the current menu bar layout
+fn current_metrics() -> Vec<&'static str> {
+    match Config::menu_bar_template() {
+        Some(template) => METRICS
+            .iter()
+            .map(|(id, _, _)| *id)
+            .filter(|id| {
+                let placeholder = if *id == "net" { "net_rx" } else { id };
+                template.contains(&format!("{{{}}}", placeholder))
+            })
+            .collect(),
+        None if Config::menu_bar_compact() => COMPACT.to_vec(),
+        None => GRID.to_vec(),
+    }
+}
+
+/// `config.json` changes for the chosen metrics (`None` removes a key)
+fn layout_changes(metrics: &[String]) -> Result<Vec<(&'static str, Option<Value>)>, String> {
+    if let Some(unknown) = metrics
+        .iter()
+        .find(|m| !METRICS.iter().any(|(id, _, _)| id == m))
+    {
+        return Err(format!("Unknown menu bar metric: {}", unknown));
+    }
+    let chosen: Vec<&(&str, &str, &str)> = METRICS
+        .iter()
+        .filter(|(id, _, _)| metrics.iter().any(|m| m == id))
+        .collect();
+    if chosen.is_empty() {
+        return Err("Pick at least one menu bar metric".to_string());
+    }
+    let ids: Vec<&str> = chosen.iter().map(|(id, _, _)| *id).collect();
+    if ids == COMPACT || ids == GRID {
+        return Ok(vec![
+            ("menuBarCompact", Some(Value::Bool(ids == COMPACT))),
+            ("menuBarTemplate", None),
+            ("menuBarTemplateStyle", None),
+        ]);
+    }
+    let template: Vec<&str> = chosen.iter().map(|(_, _, segment)| *segment).collect();
+    Ok(vec![
+        ("menuBarTemplate", Some(Value::from(template.join(" | ")))),
+        ("menuBarTemplateStyle", Some(Value::from("columns"))),
+    ])
+}
+
+/// Remember whether this is the first launch. Call at the start of setup.
+pub fn note_first_launch() {
+    FIRST_LAUNCH.store(!Config::config_file_path().exists(), Ordering::Relaxed);
+}
+
+/// Open onboarding when [`note_first_launch`] saw a first launch
+pub fn show_if_first_launch(app_handle: &AppHandle) {
+    if !FIRST_LAUNCH.load(Ordering::Relaxed) || crate::guest_mode::is_active() {
+        return;
+    }
+    tracing::info!("First launch: showing onboarding");
+    show(app_handle);
+}
+
+fn mark_complete() {
+    if let Err(e) = Config::merge_config_values(&[("onboardingComplete", Some(Value::Bool(true)))])
+    {
+        tracing::warn!("Could not save onboardingComplete: {}", e);
+    }
+}
+
+/// Show and focus the onboarding window, creating it on first use
+pub fn show(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_webview_window(LABEL) {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+        return;
+    }
+    let built =
+        WebviewWindowBuilder::new(app_handle, LABEL, WebviewUrl::App("onboarding.html".into()))
+            .title("Welcome to mac-stats")
+            .inner_size(SIZE.0, SIZE.1)
+            .min_inner_size(440.0, 480.0)
+            .resizable(true)
+            .minimizable(false)
+            .center()
+            .visible(true)
+            .build();
+    match built {
+        Ok(window) => {
+            window.on_window_event(|event| {
+                // Closing counts as skipping; it is not offered again
+                if let tauri::WindowEvent::CloseRequested { .. } = event {
+                    if !Config::onboarding_complete() {
+                        mark_complete();
+                    }
+                }
+            });
+            let _ = window.set_focus();
+            tracing::debug!("Onboarding window created");
+        }
+        Err(e) => tracing::warn!("Could not create onboarding window: {}", e),
+    }
+}
+
+fn close(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_webview_window(LABEL) {
+        let _ = window.close();
+    }
+}
+
+/// Metric choices, the current layout and interval, and the capability probe explained
+#[tauri::command]
+pub async fn get_onboarding_info() -> Result<OnboardingInfo, String> {
+    let caps = tokio::task::spawn_blocking(capabilities::probe)
+        .await
+        .map_err(|e| format!("Capability probe failed: {}", e))?;
+    let lang = Lang::current();
+    Ok(OnboardingInfo {
+        metrics: metric_options(lang, &caps),
+        selected: current_metrics(),
+        update_interval_secs: Config::update_interval_secs(),
+        intervals: INTERVALS,
+        capabilities: capability_notes(lang, &caps),
+    })
+}
+
+/// Post a test notification the way alerts do; macOS asks for permission on the first one
+#[tauri::command]
+pub fn request_notification_permission() -> Result<String, String> {
+    let lang = Lang::current();
+    let script = format!(
+        "display notification \"{}\" with title \"mac-stats\"",
+        lang.text("onboarding.notification").replace('"', "\\\"")
+    );
+    let out = std::process::Command::new("/usr/bin/osascript")
+        .arg("-e")
+        .arg(script)
+        .output()
+        .map_err(|e| format!("osascript: {}", e))?;
+    if !out.status.success() {
+        return Err(format!(
+            "osascript {}: {}",
+            out.status,
+            String::from_utf8_lossy(&out.stderr).trim()
+        ));
+    }
+    Ok(lang.text("onboarding.notification_sent").to_string())
+}
+
+/// Save the menu bar metrics and update interval, mark onboarding complete and close it
+#[tauri::command]
+pub fn complete_onboarding(
+    app_handle: AppHandle,
+    metrics: Vec<String>,
+    update_interval_secs: u64,
+    confirmation_token: Option<String>,
+) -> Result<(), String> {
+    crate::guest_mode::ensure_allowed("Saving onboarding choices")?;
+    crate::commands::confirmation::require_confirmation(
+        confirmation_token.as_deref(),
+        "config_write",
+        "onboarding",
+    )?;
+    if !INTERVALS.contains(&update_interval_secs) {
+        return Err(format!(
+            "Update interval must be one of {:?} seconds",
+            INTERVALS
+        ));
+    }
+    let mut changes = layout_changes(&metrics)?;
+    changes.push((
+        "updateIntervalSecs",
+        Some(Value::from(update_interval_secs)),
+    ));
+    changes.push(("onboardingComplete", Some(Value::Bool(true))));
+    Config::merge_config_values(&changes)?;
+    tracing::info!(
+        "Onboarding complete: metrics {}, update every {}s",
+        metrics.join(", "),
+        update_interval_secs
+    );
+    close(&app_handle);
+    Ok(())
+}
+
+/// Skip onboarding without changing anything else
+#[tauri::command]
+pub fn dismiss_onboarding(app_handle: AppHandle) {
+    mark_complete();
+    close(&app_handle);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn metrics_map_onto_built_in_layouts_or_a_template() {
+        let compact = layout_changes(&ids(&["temp", "cpu"])).unwrap();
+        assert_eq!(compact[0], ("menuBarCompact", Some(Value::Bool(true))));
+        assert_eq!(compact[1], ("menuBarTemplate", None));
+
+        let grid = layout_changes(&ids(&["cpu", "gpu", "ram", "disk"])).unwrap();
+        assert_eq!(grid[0], ("menuBarCompact", Some(Value::Bool(false))));
+
+        let custom = layout_changes(&ids(&["net", "cpu", "battery"])).unwrap();
+        assert_eq!(
+            custom[0].1,
+            Some(Value::from(
+                "CPU: {cpu}% | Bat: {battery}% | Net: {net_rx}{net_unit}"
+            ))
+        );
+        assert_eq!(custom[1].1, Some(Value::from("columns")));
+
+        assert!(layout_changes(&[]).is_err());
+        assert!(layout_changes(&ids(&["cpu", "swap"]))
+            .unwrap_err()
+            .contains("swap"));
+    }
+
+    #[test]
+    fn missing_sensors_are_explained() {
+        let caps = Capabilities {
+            apple_silicon: true,
+            temperature: true,
+            ..Default::default()
+        };
+        let notes = capability_notes(Lang::En, &caps);
+        assert!(notes[0].available);
+        let fan = notes.iter().find(|n| n.name == "Fan").unwrap();
+        assert!(!fan.available && fan.detail.contains("Fanless"));
+        let german = capability_notes(Lang::De, &caps);
+        assert_eq!(german[2].name, "Lüfter");
+        let options = metric_options(Lang::En, &caps);
+        assert!(
+            !options
+                .iter()
+                .find(|m| m.id == "battery")
+                .unwrap()
+                .available
+        );
+    }
+}
@@ -0,0 +1,89 @@
+//! Reduced-motion menu bar (`menuBarReducedMotion`)
+//!
+//! The status item normally redraws on every sample, so CPU usage flickers between 11% and
+//! 13% every few seconds. In this mode the menu bar shows whole percents and holds each of
+//! CPU, GPU, RAM and SSD until it moves by at least `menuBarMinChange` points; titles that
+//! come out the same as last time are not handed to AppKit at all. History, alerts, the status
+//! menu and the windows keep the raw readings.
+//!
+//! Defaults to the macOS Reduce motion setting (Accessibility > Display), read at launch.
+
+use std::sync::OnceLock;
+
+use crate::metrics::SystemMetrics;
+
+/// Holds the menu bar values between samples
+#[derive(Debug, Default)]
+pub struct Damper {
+    shown: Option<[f32; 4]>,
+}
+
+impl Damper {
+    /// `metrics` as the menu bar should show them: rounded, and each value kept at what was
+    /// shown before while it moved by less than `min_change` points
+    pub fn settle(&mut self, metrics: &SystemMetrics, min_change: f32) -> SystemMetrics {
+        let raw = [metrics.cpu, metrics.gpu, metrics.ram, metrics.disk];
+        let shown = match self.shown {
+            Some(prev) => std::array::from_fn(|i| {
+                if (raw[i] - prev[i]).abs() >= min_change {
+                    raw[i].round()
+                } else {
+                    prev[i]
+                }
+            }),
+            None => raw.map(f32::round),
+        };
+        self.shown = Some(shown);
+        SystemMetrics {
+            cpu: shown[0],
+            gpu: shown[1],
+            ram: shown[2],
+            disk: shown[3],
+            ..metrics.clone()
+        }
+    }
+}
+
+/// macOS Accessibility > Display > Reduce motion (`com.apple.universalaccess reduceMotion`).
+/// Read once.
+pub fn system_reduce_motion() -> bool {
+    static REDUCE_MOTION: OnceLock<bool> = OnceLock::new();
+    *REDUCE_MOTION.get_or_init(|| {
+        std::process::Command::new("/usr/bin/defaults")
+            .args(["read", "com.apple.universalaccess", "reduceMotion"])
+            .stderr(std::process::Stdio::null())
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .is_some_and(|o| String::from_utf8_lossy(&o.stdout).trim() == "1")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(cpu: f32, ram: f32) -> SystemMetrics {
+        SystemMetrics {
+            cpu,
+            gpu: 0.0,
+            ram,
+            disk: 40.0,
+            plugins: Default::default(),
+        }
+    }
+
+    #[test]
+    fn holds_values_until_they_move_enough() {
+        let mut damper = Damper::default();
+        let first = damper.settle(&metrics(11.4, 60.6), 2.0);
+        assert_eq!((first.cpu, first.ram), (11.0, 61.0));
+
+        let jitter = damper.settle(&metrics(12.9, 61.9), 2.0);
+        assert_eq!((jitter.cpu, jitter.ram), (11.0, 61.0));
+
+        let moved = damper.settle(&metrics(13.2, 59.4), 2.0);
+        assert_eq!((moved.cpu, moved.ram), (13.0, 61.0));
+        assert_eq!(moved.disk, 40.0);
+    }
+}
@@ -0,0 +1,221 @@
+//! `mac_stats ui-selftest`: run the menu bar pipeline once in this process and report the
+//! first stage that breaks.
+//!
+//! Stages mirror the app: sample metrics → `build_status_text` → `MENU_BAR_TEXT` → the
+//! `processMenuBarUpdate:` timer on the main run loop → `setAttributedTitle` on the status
+//! button. The running app is another process and is not inspected; if every stage passes here
+//! while the app's menu bar is frozen, look for "Menu bar title not updated" in `debug.log`.
+
+use std::sync::atomic::Ordering;
+use std::sync::TryLockError;
+use std::time::{Duration, Instant};
+
+use core_foundation::runloop::{kCFRunLoopDefaultMode, CFRunLoop};
+use objc2::MainThreadMarker;
+use objc2_app_kit::{
+    NSApplication, NSApplicationActivationPolicy, NSFontAttributeName, NSStatusBar,
+};
+
+use super::status_bar::{build_item_text, build_status_text, setup_status_item};
+use crate::config::Config;
+use crate::metrics::get_metrics;
+use crate::state::{MENU_BAR_TEXT, MENU_BAR_TITLES_APPLIED, STATUS_ITEMS};
+
+/// The first timer fires 2s after `setup_status_item`; allow for a busy main thread
+const APPLY_TIMEOUT: Duration = Duration::from_secs(6);
+const LOCK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A failed stage: (stage name, what went wrong)
+type StageError = (&'static str, String);
+
+fn pass(stage: &str, detail: impl std::fmt::Display) {
+    println!("  ✓ {:<14} {}", stage, detail);
+}
+
+fn sample() -> Result<crate::metrics::SystemMetrics, String> {
+    let started = Instant::now();
+    // CPU usage is a delta between two refreshes, as in `mac_stats stats`
+    crate::metrics::cli::refresh_system();
+    std::thread::sleep(sysinfo::MINIMUM_CPU_UPDATE_INTERVAL.max(Duration::from_millis(500)));
+    crate::metrics::cli::refresh_system();
+    let metrics = get_metrics();
+    if !metrics.is_valid() {
+        return Err(
+            "CPU, GPU and RAM all read 0% — the update loop skips such samples, so the menu bar \
+             never changes"
+                .to_string(),
+        );
+    }
+    pass(
+        "sample",
+        format!(
+            "CPU {:.1}%  GPU {:.1}%  RAM {:.1}%  SSD {:.1}% ({} ms)",
+            metrics.cpu,
+            metrics.gpu,
+            metrics.ram,
+            metrics.disk,
+            started.elapsed().as_millis()
+        ),
+    );
+    Ok(metrics)
+}
+
+/// Store `text` for status item `id` the way the update loop does, but without blocking forever
+/// on a held lock
+fn store_pending(id: &str, text: &str) -> Result<(), String> {
+    let deadline = Instant::now() + LOCK_TIMEOUT;
+    loop {
+        match MENU_BAR_TEXT.try_lock() {
+            Ok(mut pending) => {
+                pending.insert(id.to_string(), text.to_string());
+                return Ok(());
+            }
+            Err(TryLockError::Poisoned(_)) => {
+                return Err(
+                    "mutex poisoned (a thread panicked while holding it); the main-thread \
+                     update can never take the text"
+                        .to_string(),
+                )
+            }
+            Err(TryLockError::WouldBlock) if Instant::now() < deadline => {
+                std::thread::sleep(Duration::from_millis(10));
+            }
+            Err(TryLockError::WouldBlock) => {
+                return Err(format!("still locked after {}s", LOCK_TIMEOUT.as_secs()))
+            }
+        }
+    }
+}
+
+fn text_pending() -> bool {
+    MENU_BAR_TEXT
+        .try_lock()
+        .map(|p| !p.is_empty())
+        .unwrap_or(true)
+}
+
+/// Run the main run loop until the timer applies a title (or [`APPLY_TIMEOUT`])
+fn wait_for_apply(applied_before: u64) -> Result<Duration, String> {
+    let started = Instant::now();
+    while MENU_BAR_TITLES_APPLIED.load(Ordering::Relaxed) == applied_before {
+        if started.elapsed() > APPLY_TIMEOUT {
+            return Err(if text_pending() {
+                format!(
+                    "processMenuBarUpdate: did not run within {}s (text still pending)",
+                    APPLY_TIMEOUT.as_secs()
+                )
+            } else {
+                "text was taken from MENU_BAR_TEXT but no title was set (status button \
+                 missing)"
+                    .to_string()
+            });
+        }
+        // SAFETY: kCFRunLoopDefaultMode is an immutable CFString constant exported by
+        // CoreFoundation; the update timer runs in the common modes, which include it.
+        let mode = unsafe { kCFRunLoopDefaultMode };
+        CFRunLoop::run_in_mode(mode, Duration::from_millis(100), true);
+    }
+    Ok(started.elapsed())
+}
+
+/// Compare the attributed title of item `id` with the text that was stored
+fn check_title(mtm: MainThreadMarker, id: &str, text: &str) -> Result<String, String> {
+    STATUS_ITEMS.with(|cell| {
+        let items = cell.borrow();
+        let item = items.get(id).ok_or("status item was released")?;
+        let button = item.button(mtm).ok_or("status item has no button")?;
+        let title = button.attributedTitle();
+        let shown = title.string().to_string();
+        if shown != text {
+            return Err(format!(
+                "button shows {:?} but {:?} was stored",
+                shown, text
+            ));
+        }
+        // SAFETY: index 0 is in bounds (the title equals the non-empty stored text) and a null
+        // effective-range pointer is allowed.
+        let font = unsafe {
+            title.attribute_atIndex_effectiveRange(NSFontAttributeName, 0, std::ptr::null_mut())
+        };
+        if font.is_none() {
+            return Err(
+                "title has no font attribute (make_attributed_title did not style it)".to_string(),
+            );
+        }
+        Ok(format!(
+            "matches stored text ({} UTF-16 units, styled)",
+            title.length()
+        ))
+    })
+}
+
+fn remove_status_items() {
+    for item in STATUS_ITEMS
+        .with(|cell| std::mem::take(&mut *cell.borrow_mut()))
+        .into_values()
+    {
+        NSStatusBar::systemStatusBar().removeStatusItem(&item);
+    }
+}
+
+fn run_stages(mtm: MainThreadMarker) -> Result<(), StageError> {
+    let metrics = sample().map_err(|e| ("sample", e))?;
+
+    // The leftmost item (`menuBarItems`; the main one by default)
+    let id = Config::menu_bar_items().remove(0);
+    let text = build_item_text(&id, &metrics, &build_status_text(&metrics));
+    if text.trim().is_empty() {
+        return Err(("text", format!("no text for status item '{}'", id)));
+    }
+    pass("text", format!("{:?}", text));
+
+    store_pending(&id, &text).map_err(|e| ("MENU_BAR_TEXT", e))?;
+    pass("MENU_BAR_TEXT", "stored");
+
+    let app = NSApplication::sharedApplication(mtm);
+    app.setActivationPolicy(NSApplicationActivationPolicy::Accessory);
+    let applied_before = MENU_BAR_TITLES_APPLIED.load(Ordering::Relaxed);
+    setup_status_item();
+    let has_button = STATUS_ITEMS.with(|cell| {
+        cell.borrow()
+            .get(&id)
+            .is_some_and(|item| item.button(mtm).is_some())
+    });
+    if !has_button {
+        return Err(("status item", "NSStatusBar returned no button".to_string()));
+    }
+    pass("status item", "created, update timer scheduled");
+
+    let waited = wait_for_apply(applied_before).map_err(|e| ("main thread", e))?;
+    pass(
+        "main thread",
+        format!("timer applied the title after {:.1}s", waited.as_secs_f64()),
+    );
+
+    let detail = check_title(mtm, &id, &text).map_err(|e| ("title", e))?;
+    pass("title", detail);
+    Ok(())
+}
+
+/// Run the self-test and print one line per stage. Returns the process exit code.
+pub fn run_ui_selftest_stdio() -> i32 {
+    println!("mac-stats menu bar self-test");
+    println!("────────────────────────────");
+    let Some(mtm) = MainThreadMarker::new() else {
+        println!("  ✗ {:<14} not running on the main thread", "main thread");
+        return 1;
+    };
+    let result = run_stages(mtm);
+    remove_status_items();
+    match result {
+        Ok(()) => {
+            println!("All stages passed.");
+            0
+        }
+        Err((stage, e)) => {
+            println!("  ✗ {:<14} {}", stage, e);
+            tracing::warn!("ui-selftest failed at {}: {}", stage, e);
+            1
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! VoiceOver support for the status items
+//!
+//! The menu bar text is a dense two-line layout: labels over values, in tab-separated columns.
+//! VoiceOver would read all labels, then all values. Each status button therefore gets an
+//! accessibility label (what the item is) and a value that pairs every label with its reading
+//! ("CPU 12%, GPU 5%, RAM 61%, SSD 40%"), updated with the title.
+//!
+//! `menuBarPlainText` goes further and draws that text as the title itself, on one line in the
+//! standard menu bar font, for users who prefer it or rely on screen magnifiers.
+
+/// Bidi isolates and the narrow no-break space from `number_format` are not read aloud
+fn clean(field: &str) -> String {
+    field
+        .chars()
+        .filter(|c| !matches!(c, '\u{2066}' | '\u{2069}'))
+        .map(|c| if c == '\u{202f}' { ' ' } else { c })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Status item text (labels line over values line, tab-separated columns; or one line) as
+/// `label value` pairs joined by `separator`
+pub fn plain_text(text: &str, separator: &str) -> String {
+    let fields = |line: &str| -> Vec<String> {
+        line.split('\t')
+            .map(clean)
+            .filter(|f| !f.is_empty())
+            .collect()
+    };
+    let mut lines = text.lines();
+    let labels = fields(lines.next().unwrap_or_default());
+    let values = fields(lines.next().unwrap_or_default());
+    // Compact layout ("CPU  12%" over "61°"): the first line already carries a reading
+    let labelled = labels.len() == values.len()
+        && !labels.iter().any(|l| l.chars().any(|c| c.is_ascii_digit()));
+    let parts: Vec<String> = if labelled {
+        labels
+            .iter()
+            .zip(&values)
+            .map(|(label, value)| format!("{} {}", label, value))
+            .collect()
+    } else {
+        labels.into_iter().chain(values).collect()
+    };
+    parts.join(separator)
+}
+
+/// What VoiceOver reads for the status item `id` (`menuBarItems`)
+pub fn label(id: &str, lang: crate::i18n::Lang) -> String {
+    let base = lang.text("status.accessibility_label");
+    match id {
+        super::status_items::MAIN => base.to_string(),
+        item => format!("{} ({})", base, item),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pairs_labels_with_values() {
+        assert_eq!(
+            plain_text("CPU\tGPU\tRAM\tSSD\n12%\t5%\t61%\t40\u{202f}%", ", "),
+            "CPU 12%, GPU 5%, RAM 61%, SSD 40 %"
+        );
+        assert_eq!(
+            plain_text("CPU  \u{2066}12%\u{2069}\n61°", ", "),
+            "CPU 12%, 61°"
+        );
+        assert_eq!(plain_text("CPU\n12%", "  "), "CPU 12%");
+        assert_eq!(plain_text("12% 61°C | 40%", ", "), "12% 61°C | 40%");
+        assert_eq!(
+            label("network", crate::i18n::Lang::En),
+            "mac-stats system stats (network)"
+        );
+    }
+}
@@ -0,0 +1,113 @@
+//! Alert tint for menu bar columns
+//!
+//! While a `menubar` alert action is recent, the metric it is about is drawn red in the status
+//! item: its label and value in the grid (`CPU`/`GPU`/`RAM`/`SSD`), or the CPU line and the
+//! temperature line in compact mode. Alerts on metrics the status item does not show (battery,
+//! fan, power, ...) fall back to the `Alert ✕` line.
+
+/// Status item column label for each metric it shows
+const COLUMN_LABELS: &[(&str, &str)] = &[
+    ("cpu", "CPU"),
+    ("gpu", "GPU"),
+    ("ram", "RAM"),
+    ("disk", "SSD"),
+];
+
+/// Grid column label of `metric` (`"disk"` → `"SSD"`)
+pub fn column_label(metric: &str) -> Option<&'static str> {
+    COLUMN_LABELS
+        .iter()
+        .find(|(m, _)| *m == metric)
+        .map(|(_, l)| *l)
+}
+
+/// `(start, length)` in UTF-16 units of each tab-separated field of `line` (at `offset`)
+fn fields(line: &str, offset: usize) -> Vec<(usize, usize)> {
+    let mut pos = offset;
+    line.split('\t')
+        .map(|field| {
+            let len = field.encode_utf16().count();
+            let range = (pos, len);
+            pos += len + 1;
+            range
+        })
+        .collect()
+}
+
+/// UTF-16 ranges (`location`, `length`) of `text` to tint for `metric`; empty when the status
+/// item does not show it
+pub fn metric_ranges(text: &str, metric: &str) -> Vec<(usize, usize)> {
+    let mut lines = text.split('\n');
+    let (Some(labels), values) = (lines.next(), lines.next()) else {
+        return Vec::new();
+    };
+    let label_fields = fields(labels, 0);
+    let value_fields = values
+        .map(|v| fields(v, labels.encode_utf16().count() + 1))
+        .unwrap_or_default();
+    // Compact mode with temperature: "CPU  12%" over "45°"
+    let compact_cpu = labels
+        .split('\t')
+        .next()
+        .is_some_and(|f| f.starts_with("CPU "));
+    if compact_cpu {
+        return match metric {
+            "cpu" => label_fields.into_iter().take(1).collect(),
+            "temperature" if values.is_some_and(|v| v.contains('°')) => {
+                value_fields.into_iter().take(1).collect()
+            }
+            _ => Vec::new(),
+        };
+    }
+    let Some(label) = column_label(metric) else {
+        return Vec::new();
+    };
+    match labels.split('\t').position(|f| f.trim() == label) {
+        Some(i) => label_fields
+            .get(i)
+            .into_iter()
+            .chain(value_fields.get(i))
+            .copied()
+            .filter(|(_, len)| *len > 0)
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Ranges to tint for all `metrics`
+pub fn alert_ranges(text: &str, metrics: &[String]) -> Vec<(usize, usize)> {
+    metrics
+        .iter()
+        .flat_map(|m| metric_ranges(text, m))
+        .collect()
+}
+
+/// Whether any of `metrics` has no column in `text` (and needs the `Alert ✕` line instead)
+pub fn has_unshown(text: &str, metrics: &[String]) -> bool {
+    metrics.iter().any(|m| metric_ranges(text, m).is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_alerting_columns() {
+        let grid = "CPU\tGPU\tRAM\tSSD\n12%\t3%\t85%\t40%";
+        assert_eq!(metric_ranges(grid, "ram"), vec![(8, 3), (23, 3)]);
+        assert_eq!(metric_ranges(grid, "cpu"), vec![(0, 3), (16, 3)]);
+        assert!(metric_ranges(grid, "battery").is_empty());
+        assert!(metric_ranges(grid, "temperature").is_empty());
+
+        let compact = "CPU  12%\n45°";
+        assert_eq!(metric_ranges(compact, "cpu"), vec![(0, 8)]);
+        assert_eq!(metric_ranges(compact, "temperature"), vec![(9, 3)]);
+        assert!(metric_ranges(compact, "ram").is_empty());
+        assert_eq!(metric_ranges("CPU\n12%", "cpu"), vec![(0, 3), (4, 3)]);
+
+        let alerting = vec!["ram".to_string(), "fan_rpm".to_string()];
+        assert_eq!(alert_ranges(grid, &alerting).len(), 2);
+        assert!(has_unshown(grid, &alerting));
+        assert!(!has_unshown(grid, &alerting[..1]));
+    }
+}
@@ -7,23 +7,28 @@ use objc2::rc::Retained;
 use objc2::runtime::{AnyClass, AnyObject, NSObject, Sel};
 use objc2::{msg_send, sel, ClassType, MainThreadMarker};
 use objc2_app_kit::{
-    NSAboutPanelOptionApplicationName, NSAboutPanelOptionApplicationVersion,
-    NSAboutPanelOptionCredits, NSAboutPanelOptionVersion, NSApplication,
-    NSBaselineOffsetAttributeName, NSColor, NSEvent, NSFont, NSFontAttributeName,
-    NSFontWeightRegular, NSFontWeightSemibold, NSForegroundColorAttributeName,
-    NSMutableParagraphStyle, NSParagraphStyleAttributeName, NSStatusBar, NSTextAlignment,
-    NSTextTab, NSTextTabOptionKey, NSVariableStatusItemLength,
+    NSApplication, NSBaselineOffsetAttributeName, NSCellImagePosition, NSColor, NSEvent,
+    NSEventModifierFlags, NSFont,
+    NSFontAttributeName, NSFontWeightRegular, NSFontWeightSemibold, NSForegroundColorAttributeName,
+    NSMenuItem, NSMutableParagraphStyle, NSParagraphStyleAttributeName, NSStatusBar,
+    NSStatusBarButton, NSTextAlignment, NSTextTab, NSTextTabOptionKey, NSVariableStatusItemLength,
+    NSView,
 };
 use objc2_foundation::{
-    NSArray, NSAttributedString, NSDictionary, NSMutableAttributedString, NSMutableDictionary,
-    NSNumber, NSRange, NSString,
+    NSArray, NSDictionary, NSMutableAttributedString, NSNumber, NSPoint, NSRange, NSRect,
+    NSRunLoop, NSRunLoopCommonModes, NSString, NSTimer,
 };
+use std::sync::atomic::Ordering;
 use std::sync::OnceLock;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindowBuilder};
 
+use super::click_actions::{Click, ClickAction};
+use super::copy_stats::StatsFormat;
 use crate::config::Config;
 use crate::logging::write_structured_log;
 use crate::metrics::SystemMetrics;
+use crate::number_format::NumberFormat;
 use crate::state::*;
 
 // Import debug macros
@@ -35,42 +40,116 @@ fn as_any<T: objc2::Message>(obj: &T) -> &AnyObject {
     unsafe { &*(obj as *const T as *const AnyObject) }
 }
 
-/// Build status text from metrics
+/// Build status text from metrics (values formatted for the user's locale), followed by any
+/// configured informational columns (`menuBarExtras`)
 pub fn build_status_text(metrics: &SystemMetrics) -> String {
+    super::status_extras::append_extras(build_metrics_text(metrics))
+}
+
+fn build_metrics_text(metrics: &SystemMetrics) -> String {
+    let fmt = NumberFormat::current();
+    let decimals = Config::menu_bar_decimals();
+    if let Some(template) = Config::menu_bar_template() {
+        return super::status_template::render(
+            &template,
+            Config::menu_bar_template_columns(),
+            |name| template_value(name, metrics, &fmt, decimals),
+        );
+    }
+    let pct = |v: f32| fmt.percent(v as f64, decimals);
     if Config::menu_bar_compact() {
-        // Default: CPU (+ cached °C when the window/SMC path has already filled TEMP_CACHE).
-        let temp = crate::state::TEMP_CACHE
-            .try_lock()
-            .ok()
-            .and_then(|g| g.as_ref().map(|(t, _)| *t))
+        // Default: CPU (+ cached °C when the window/SMC path has already read a temperature).
+        let temp = crate::state::snapshot()
+            .temperature
+            .as_ref()
+            .map(|r| r.value)
             .filter(|t| *t > 0.0);
         return match temp {
             Some(t) => format!(
-                "CPU  {:.0}%\n{:.0}°",
-                metrics.cpu.round() as i32,
-                t.round() as i32
+                "CPU  {}\n{}",
+                pct(metrics.cpu),
+                fmt.with_unit(crate::units::Units::current().temperature(t as f64), 0, "°")
             ),
-            None => format!("CPU\n{:.0}%", metrics.cpu.round() as i32),
+            None => format!("CPU\n{}", pct(metrics.cpu)),
         };
     }
     let label_line = "CPU\tGPU\tRAM\tSSD".to_string();
     let value_line = format!(
-        "{:.0}%\t{:.0}%\t{:.0}%\t{:.0}%",
-        metrics.cpu.round() as i32,
-        metrics.gpu.round() as i32,
-        metrics.ram.round() as i32,
-        metrics.disk.round() as i32
+        "{}\t{}\t{}\t{}",
+        pct(metrics.cpu),
+        pct(metrics.gpu),
+        pct(metrics.ram),
+        pct(metrics.disk)
     );
     format!("{label_line}\n{value_line}")
 }
 
+/// Text for the status item `id` (`menuBarItems`): `main_text` for the main item, the item's
+/// own metric otherwise
+pub fn build_item_text(id: &str, metrics: &SystemMetrics, main_text: &str) -> String {
+    if id == super::status_items::MAIN {
+        return main_text.to_string();
+    }
+    let fmt = NumberFormat::current();
+    let decimals = Config::menu_bar_decimals();
+    super::status_items::item_text(id, |name| template_value(name, metrics, &fmt, decimals))
+}
+
+/// Value for a `menuBarTemplate` placeholder; `None` when there is no current reading
+fn template_value(
+    name: &str,
+    metrics: &SystemMetrics,
+    fmt: &NumberFormat,
+    decimals: usize,
+) -> Option<String> {
+    let number = |v: f64, d: usize| fmt.isolate(&fmt.number(v, d));
+    let units = crate::units::Units::current();
+    let io = || {
+        crate::state::LATEST_IO_RATES
+            .try_lock()
+            .ok()
+            .and_then(|r| *r)
+    };
+    match name {
+        "cpu" => Some(number(metrics.cpu as f64, decimals)),
+        "gpu" => Some(number(metrics.gpu as f64, decimals)),
+        "ram" => Some(number(metrics.ram as f64, decimals)),
+        "disk" => Some(number(metrics.disk as f64, decimals)),
+        "temp" => crate::state::snapshot()
+            .temperature
+            .as_ref()
+            .map(|r| r.value)
+            .filter(|t| *t > 0.0)
+            .map(|t| number(units.temperature(t as f64), 0)),
+        "temp_unit" => Some(units.temperature_symbol().to_string()),
+        "fan" => crate::metrics::fan_noise::current().map(|r| number(r.rpm as f64, 0)),
+        "battery" => crate::state::snapshot()
+            .battery
+            .as_ref()
+            .map(|r| r.value.0)
+            .filter(|level| *level >= 0.0)
+            .map(|level| number(level as f64, 0)),
+        "net_rx" => io()
+            .map(|r| super::status_template::short_rate(units.rate_value(r.net_rx as f64), number)),
+        "net_tx" => io()
+            .map(|r| super::status_template::short_rate(units.rate_value(r.net_tx as f64), number)),
+        "net_unit" => Some(units.rate_suffix().to_string()),
+        plugin => metrics
+            .plugins
+            .get(plugin)
+            .map(|v| number(*v as f64, decimals)),
+    }
+}
+
 /// Process menu bar update (must be called from main thread)
 pub fn process_menu_bar_update() {
     // This function must be called from the main thread
     if let Some(mtm) = MainThreadMarker::new() {
-        let update_text = {
+        super::status_removal::watch(mtm);
+        super::status_compaction::observe(any_item_hidden(mtm));
+        let updates = {
             if let Ok(mut pending) = MENU_BAR_TEXT.try_lock() {
-                pending.take()
+                std::mem::take(&mut *pending)
             } else {
                 write_structured_log(
                     "ui/status_bar.rs",
@@ -82,25 +161,43 @@ pub fn process_menu_bar_update() {
             }
         };
 
-        if let Some(text) = update_text {
-            debug3!("Processing menu bar update: '{}'", text);
-            let attributed = make_attributed_title(&text);
-            STATUS_ITEM.with(|cell| {
-                if let Some(item) = cell.borrow().as_ref() {
-                    if let Some(button) = item.button(mtm) {
-                        button.setAttributedTitle(&attributed);
-                        debug3!("Menu bar text updated successfully");
+        if updates.is_empty() {
+            return;
+        }
+        let alerting = crate::alerts::channels::menu_bar_alert_metrics();
+        let plain = Config::menu_bar_plain_text();
+        let lang = crate::i18n::Lang::current();
+        STATUS_ITEMS.with(|cell| {
+            let items = cell.borrow();
+            let sparkline_item = super::status_items::sparkline_item(items.keys());
+            for (id, text) in updates {
+                debug3!("Processing menu bar update for '{}': '{}'", id, text);
+                let Some(item) = items.get(&id) else {
+                    continue;
+                };
+                if let Some(button) = item.button(mtm) {
+                    if plain {
+                        let title = super::status_accessibility::plain_text(&text, "  ");
+                        button.setTitle(&NSString::from_str(&title));
                     } else {
-                        write_structured_log(
-                            "ui/status_bar.rs",
-                            "Button not found",
-                            &serde_json::json!({}),
-                            "G",
-                        );
+                        button.setAttributedTitle(&make_attributed_title(&text, &alerting));
                     }
+                    apply_accessibility(&button, &id, &text, lang);
+                    if sparkline_item == Some(id.as_str()) {
+                        apply_sparkline(&button);
+                    }
+                    MENU_BAR_TITLES_APPLIED.fetch_add(1, Ordering::Relaxed);
+                    debug3!("Menu bar text updated successfully");
+                } else {
+                    write_structured_log(
+                        "ui/status_bar.rs",
+                        "Button not found",
+                        &serde_json::json!({"item": id}),
+                        "G",
+                    );
                 }
-            });
-        }
+            }
+        });
     } else {
         write_structured_log(
             "ui/status_bar.rs",
@@ -111,8 +208,132 @@ pub fn process_menu_bar_update() {
     }
 }
 
-/// Create attributed title string for status bar
-pub fn make_attributed_title(text: &str) -> Retained<NSMutableAttributedString> {
+/// Whether macOS hid one of our status items for lack of room (see `status_compaction`); items
+/// the user removed do not count
+fn any_item_hidden(mtm: MainThreadMarker) -> bool {
+    STATUS_ITEMS.with(|cell| {
+        cell.borrow()
+            .values()
+            .filter(|item| super::status_removal::is_visible(item))
+            .filter_map(|item| item.button(mtm))
+            .any(|button| is_hidden(&button))
+    })
+}
+
+/// A status button that does not fit is moved off screen (its window has no screen or starts
+/// left of it) or sits behind the camera housing, between the screen's auxiliary top areas
+/// (macOS 12+). Before the first layout there is no window yet, which does not count.
+fn is_hidden(button: &NSStatusBarButton) -> bool {
+    unsafe {
+        let window: Option<Retained<AnyObject>> = msg_send![button, window];
+        let Some(window) = window else {
+            return false;
+        };
+        let frame: NSRect = msg_send![&*window, frame];
+        let screen: Option<Retained<AnyObject>> = msg_send![&*window, screen];
+        let Some(screen) = screen else {
+            return true;
+        };
+        let screen_frame: NSRect = msg_send![&*screen, frame];
+        if frame.origin.x < screen_frame.origin.x {
+            return true;
+        }
+        let has_housing: bool = msg_send![&*screen, respondsToSelector: sel!(auxiliaryTopLeftArea)];
+        if !has_housing {
+            return false;
+        }
+        let left: NSRect = msg_send![&*screen, auxiliaryTopLeftArea];
+        let right: NSRect = msg_send![&*screen, auxiliaryTopRightArea];
+        if left.size.width <= 0.0 || right.size.width <= 0.0 {
+            return false;
+        }
+        let housing_start = screen_frame.origin.x + left.size.width;
+        let housing_end = screen_frame.origin.x + screen_frame.size.width - right.size.width;
+        frame.origin.x < housing_end && frame.origin.x + frame.size.width > housing_start
+    }
+}
+
+/// VoiceOver label and `label value` pairs for the status button (see `status_accessibility`)
+fn apply_accessibility(button: &NSStatusBarButton, id: &str, text: &str, lang: crate::i18n::Lang) {
+    let label = NSString::from_str(&super::status_accessibility::label(id, lang));
+    let value = NSString::from_str(&super::status_accessibility::plain_text(text, ", "));
+    unsafe {
+        let _: () = msg_send![button, setAccessibilityLabel: &*label];
+        let _: () = msg_send![button, setAccessibilityValue: &*value];
+    }
+}
+
+/// Show the CPU sparkline left of the title when `menuBarSparkline` is on, otherwise none
+fn apply_sparkline(button: &NSStatusBarButton) {
+    let image = if Config::menu_bar_sparkline() {
+        CPU_SPARKLINE
+            .try_lock()
+            .ok()
+            .and_then(|spark| super::status_sparkline::draw_image(&spark.samples()))
+    } else {
+        None
+    };
+    if image.is_some() {
+        button.setImagePosition(NSCellImagePosition::ImageLeft);
+    }
+    let _: () = unsafe { msg_send![button, setImage: image.as_deref()] };
+}
+
+/// Seconds without an applied title (while the update loop keeps storing text) before warning
+const TITLE_STALL_WARN_SECS: u64 = 30;
+
+/// Watches [`MENU_BAR_TITLES_APPLIED`] from the update loop and logs once when the main-thread
+/// timer stops applying titles, so a frozen menu bar shows up in `debug.log` with a cause.
+pub struct TitleStallWatch {
+    applied: u64,
+    since: Instant,
+    warned: bool,
+}
+
+impl TitleStallWatch {
+    pub fn new() -> Self {
+        Self {
+            applied: MENU_BAR_TITLES_APPLIED.load(Ordering::Relaxed),
+            since: Instant::now(),
+            warned: false,
+        }
+    }
+
+    /// Call after each `MENU_BAR_TEXT` store
+    pub fn check(&mut self) {
+        let applied = MENU_BAR_TITLES_APPLIED.load(Ordering::Relaxed);
+        if applied != self.applied {
+            if self.warned {
+                tracing::info!("Menu bar title updates resumed");
+            }
+            *self = Self {
+                applied,
+                since: Instant::now(),
+                warned: false,
+            };
+        } else if !self.warned && self.since.elapsed() > Duration::from_secs(TITLE_STALL_WARN_SECS)
+        {
+            self.warned = true;
+            tracing::warn!(
+                "Menu bar title not updated for {}s although new text is stored: the main-thread \
+                 update timer has stopped (run `mac_stats ui-selftest`)",
+                self.since.elapsed().as_secs()
+            );
+        }
+    }
+}
+
+impl Default for TitleStallWatch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Create attributed title string for status bar; columns of `alert_metrics` are drawn red
+pub fn make_attributed_title(
+    text: &str,
+    alert_metrics: &[String],
+) -> Retained<NSMutableAttributedString> {
     let ns_text = NSString::from_str(text);
     let attributed = NSMutableAttributedString::from_nsstring(&ns_text);
     let length = ns_text.length();
@@ -137,7 +358,13 @@ pub fn make_attributed_title(text: &str) -> Retained<NSMutableAttributedString>
         length: value_len,
     };
 
-    let label_font = NSFont::monospacedSystemFontOfSize_weight(8.5, unsafe { NSFontWeightRegular });
+    // A single line (`menuBarTemplate`) uses the value font and sits on the normal baseline
+    let single_line = lines.len() == 1;
+    let label_font = if single_line {
+        NSFont::monospacedSystemFontOfSize_weight(12.5, unsafe { NSFontWeightSemibold })
+    } else {
+        NSFont::monospacedSystemFontOfSize_weight(8.5, unsafe { NSFontWeightRegular })
+    };
     let value_font =
         NSFont::monospacedSystemFontOfSize_weight(12.5, unsafe { NSFontWeightSemibold });
     // Use controlTextColor for menu bar - this works better than labelColor in status bar context
@@ -148,39 +375,37 @@ pub fn make_attributed_title(text: &str) -> Retained<NSMutableAttributedString>
     paragraph.setLineSpacing(-2.0);
     paragraph.setLineHeightMultiple(0.75);
     paragraph.setAlignment(NSTextAlignment::Left);
-    paragraph.setDefaultTabInterval(38.0);
+    paragraph.setDefaultTabInterval(super::status_extras::TAB_INTERVAL_PT);
 
     let options: Retained<NSDictionary<NSTextTabOptionKey, AnyObject>> = NSDictionary::new();
-    let tab1: Retained<NSTextTab> = unsafe {
-        let tab: *mut NSTextTab = msg_send![NSTextTab::class(), alloc];
-        let tab: *mut NSTextTab = msg_send![tab, initWithTextAlignment: NSTextAlignment::Left, location: 38.0f64, options: &*options];
-        Retained::from_raw(tab).unwrap()
-    };
-    let tab2: Retained<NSTextTab> = unsafe {
-        let tab: *mut NSTextTab = msg_send![NSTextTab::class(), alloc];
-        let tab: *mut NSTextTab = msg_send![tab, initWithTextAlignment: NSTextAlignment::Left, location: 76.0f64, options: &*options];
-        Retained::from_raw(tab).unwrap()
-    };
-    let tab3: Retained<NSTextTab> = unsafe {
-        let tab: *mut NSTextTab = msg_send![NSTextTab::class(), alloc];
-        let tab: *mut NSTextTab = msg_send![tab, initWithTextAlignment: NSTextAlignment::Left, location: 114.0f64, options: &*options];
-        Retained::from_raw(tab).unwrap()
-    };
-    let tab4: Retained<NSTextTab> = unsafe {
-        let tab: *mut NSTextTab = msg_send![NSTextTab::class(), alloc];
-        let tab: *mut NSTextTab = msg_send![tab, initWithTextAlignment: NSTextAlignment::Left, location: 152.0f64, options: &*options];
-        Retained::from_raw(tab).unwrap()
-    };
-    let tabs = NSArray::from_slice(&[&*tab1, &*tab2, &*tab3, &*tab4]);
+    // Four metric columns plus up to four `menuBarExtras` columns.
+    let tab_stops: Vec<Retained<NSTextTab>> = (1..=8)
+        .map(|i| unsafe {
+            let location = super::status_extras::TAB_INTERVAL_PT * i as f64;
+            let tab: *mut NSTextTab = msg_send![NSTextTab::class(), alloc];
+            let tab: *mut NSTextTab = msg_send![tab, initWithTextAlignment: NSTextAlignment::Left, location: location, options: &*options];
+            Retained::from_raw(tab).unwrap()
+        })
+        .collect();
+    let tab_refs: Vec<&NSTextTab> = tab_stops.iter().map(|t| &**t).collect();
+    let tabs = NSArray::from_slice(&tab_refs);
     paragraph.setTabStops(Some(&tabs));
-    let baseline_offset = NSNumber::new_f64(-4.8);
+    let baseline_offset = NSNumber::new_f64(if single_line { 0.0 } else { -4.8 });
 
     unsafe {
         if label_len > 0 {
-            attributed.addAttribute_value_range(NSFontAttributeName, as_any(&*label_font), label_range);
+            attributed.addAttribute_value_range(
+                NSFontAttributeName,
+                as_any(&*label_font),
+                label_range,
+            );
         }
         if value_len > 0 {
-            attributed.addAttribute_value_range(NSFontAttributeName, as_any(&*value_font), value_range);
+            attributed.addAttribute_value_range(
+                NSFontAttributeName,
+                as_any(&*value_font),
+                value_range,
+            );
         }
         attributed.addAttribute_value_range(
             NSForegroundColorAttributeName,
@@ -198,17 +423,15 @@ pub fn make_attributed_title(text: &str) -> Retained<NSMutableAttributedString>
             full_range,
         );
 
-        // Color monitor-down alert line(s) red (e.g. "Mon ✕")
-        let mut utf16_pos: usize = 0;
-        for (i, line) in lines.iter().enumerate() {
-            let line_utf16 = line.encode_utf16().count();
-            let is_mon_alert = line.starts_with("Mon ") && line.contains('✕');
-            if is_mon_alert && line_utf16 > 0 {
+        // Color monitor-down and threshold alert cues red ("Mon ✕", "Alert ✕"), on their own
+        // line or after a compacted single line (`status_compaction::push_cue`)
+        for cue in ["Mon ✕", "Alert ✕"] {
+            for (start, _) in text.match_indices(cue) {
                 let alert_font =
                     NSFont::monospacedSystemFontOfSize_weight(10.0, NSFontWeightSemibold);
                 let range = NSRange {
-                    location: utf16_pos,
-                    length: line_utf16,
+                    location: text[..start].encode_utf16().count(),
+                    length: cue.encode_utf16().count(),
                 };
                 attributed.addAttribute_value_range(
                     NSForegroundColorAttributeName,
@@ -221,11 +444,28 @@ pub fn make_attributed_title(text: &str) -> Retained<NSMutableAttributedString>
                     range,
                 );
             }
-            utf16_pos += line_utf16;
-            if i + 1 < lines.len() {
-                utf16_pos += 1; // newline
+        }
+
+        // Per-metric colors for the current appearance (`theme.metricColors`)
+        for (metric, c) in super::theme::menu_bar_colors() {
+            let metric_color = NSColor::colorWithSRGBRed_green_blue_alpha(c.r, c.g, c.b, c.a);
+            for (location, length) in super::status_alerts::metric_ranges(text, &metric) {
+                attributed.addAttribute_value_range(
+                    NSForegroundColorAttributeName,
+                    as_any(&*metric_color),
+                    NSRange { location, length },
+                );
             }
         }
+
+        // Tint the label and value of metrics with an active menu bar alert
+        for (location, length) in super::status_alerts::alert_ranges(text, alert_metrics) {
+            attributed.addAttribute_value_range(
+                NSForegroundColorAttributeName,
+                as_any(&*alert_color),
+                NSRange { location, length },
+            );
+        }
     }
 
     attributed
@@ -235,7 +475,6 @@ pub fn make_attributed_title(text: &str) -> Retained<NSMutableAttributedString>
 pub fn setup_status_item() {
     let mtm = MainThreadMarker::new().unwrap();
     let status_bar = NSStatusBar::systemStatusBar();
-    let status_item = status_bar.statusItemWithLength(NSVariableStatusItemLength);
 
     let handler_class = click_handler_class();
     debug2!("Creating handler instance from class");
@@ -319,148 +558,179 @@ pub fn setup_status_item() {
     // Instead, use the button's action directly and handle events properly
     let action = sel!(onStatusItemClick:);
 
-    if let Some(button) = status_item.button(mtm) {
-        debug2!("Setting up button target and action (NO menu set)...");
-        write_structured_log(
-            "ui/status_bar.rs",
-            "Setting button target/action (no menu)",
-            &serde_json::json!({"handler": format!("{:p}", &*handler), "action": action.name()}),
-            "J",
-        );
-        unsafe {
-            // Set target and action on the button
-            button.setTarget(Some(&*handler));
-            button.setAction(Some(action));
-            button.setEnabled(true);
-
-            // CRITICAL: Use sendActionOn to specify which events trigger the action
-            // This is required for NSStatusBarButton to work properly
-            // sendActionOn returns the previous mask, we want left mouse up events
-            // NSEventMask is a bitmask - use LeftMouseUpMask
-            use objc2_app_kit::NSEventMask;
-            let event_mask = NSEventMask::LeftMouseUp;
-            let _previous_mask = button.sendActionOn(event_mask);
-
+    // macOS places each new status item left of the existing ones, so create them right to left
+    for id in Config::menu_bar_items().iter().rev() {
+        let status_item = status_bar.statusItemWithLength(NSVariableStatusItemLength);
+        debug2!("Creating status item '{}'", id);
+        super::status_removal::configure(&status_item, id);
+        if let Some(button) = status_item.button(mtm) {
+            debug2!("Setting up button target and action (NO menu set)...");
             write_structured_log(
                 "ui/status_bar.rs",
-                "Button target/action and sendAction set",
-                &serde_json::json!({}),
+                "Setting button target/action (no menu)",
+                &serde_json::json!({"handler": format!("{:p}", &*handler), "action": action.name()}),
                 "J",
             );
-            debug3!("Button target, action, and sendAction set");
+            unsafe {
+                // Set target and action on the button
+                button.setTarget(Some(&*handler));
+                button.setAction(Some(action));
+                button.setEnabled(true);
 
-            // Verify setup
-            if let Some(target) = button.target() {
-                debug3!("Button target verified: {:?}", target);
-                write_structured_log(
-                    "ui/status_bar.rs",
-                    "Button target verified",
-                    &serde_json::json!({"target": format!("{:p}", target)}),
-                    "J",
-                );
+                // CRITICAL: Use sendActionOn to specify which events trigger the action
+                // This is required for NSStatusBarButton to work properly
+                // sendActionOn returns the previous mask, we want left mouse up events
+                // NSEventMask is a bitmask - use LeftMouseUpMask
+                use objc2_app_kit::NSEventMask;
+                // Left, right and middle clicks; what they do is `statusItemClickActions`
+                let event_mask = NSEventMask::LeftMouseUp
+                    | NSEventMask::RightMouseUp
+                    | NSEventMask::OtherMouseUp;
+                let _previous_mask = button.sendActionOn(event_mask);
 
-                // CRITICAL: Verify target responds to the action selector
-                let target_responds = {
-                    let responds: bool = msg_send![&*target, respondsToSelector: action];
-                    responds
-                };
-                let selector_name = action.name().to_string_lossy();
-                debug1!(
-                    "Button target responds to action selector '{}': {}",
-                    selector_name,
-                    target_responds
-                );
                 write_structured_log(
                     "ui/status_bar.rs",
-                    "Target respondsToSelector check",
-                    &serde_json::json!({"responds": target_responds, "selector": selector_name}),
+                    "Button target/action and sendAction set",
+                    &serde_json::json!({}),
                     "J",
                 );
+                debug3!("Button target, action, and sendAction set");
 
-                if !target_responds {
-                    debug1!("ERROR: Button target does NOT respond to action selector!");
+                // Verify setup
+                if let Some(target) = button.target() {
+                    debug3!("Button target verified: {:?}", target);
                     write_structured_log(
                         "ui/status_bar.rs",
-                        "ERROR: Target does not respond to selector",
-                        &serde_json::json!({}),
+                        "Button target verified",
+                        &serde_json::json!({"target": format!("{:p}", target)}),
                         "J",
                     );
+
+                    // CRITICAL: Verify target responds to the action selector
+                    let target_responds = {
+                        let responds: bool = msg_send![&*target, respondsToSelector: action];
+                        responds
+                    };
+                    let selector_name = action.name().to_string_lossy();
+                    debug1!(
+                        "Button target responds to action selector '{}': {}",
+                        selector_name,
+                        target_responds
+                    );
+                    write_structured_log(
+                        "ui/status_bar.rs",
+                        "Target respondsToSelector check",
+                        &serde_json::json!({"responds": target_responds, "selector": selector_name}),
+                        "J",
+                    );
+
+                    if !target_responds {
+                        debug1!("ERROR: Button target does NOT respond to action selector!");
+                        write_structured_log(
+                            "ui/status_bar.rs",
+                            "ERROR: Target does not respond to selector",
+                            &serde_json::json!({}),
+                            "J",
+                        );
+                    }
                 }
-            }
-            if let Some(set_action) = button.action() {
-                debug3!("Button action verified: {:?}", set_action.name());
+                if let Some(set_action) = button.action() {
+                    debug3!("Button action verified: {:?}", set_action.name());
+                    write_structured_log(
+                        "ui/status_bar.rs",
+                        "Button action verified",
+                        &serde_json::json!({"action": set_action.name()}),
+                        "J",
+                    );
+                }
+
+                // CRITICAL: Check if button is enabled
+                let is_enabled = button.isEnabled();
+                debug1!("Button isEnabled: {}", is_enabled);
                 write_structured_log(
                     "ui/status_bar.rs",
-                    "Button action verified",
-                    &serde_json::json!({"action": set_action.name()}),
+                    "Button enabled check",
+                    &serde_json::json!({"enabled": is_enabled}),
                     "J",
                 );
-            }
 
-            // CRITICAL: Check if button is enabled
-            let is_enabled = button.isEnabled();
-            debug1!("Button isEnabled: {}", is_enabled);
+                // CRITICAL: Try manually sending the action to verify it works
+                // if let Some(target) = button.target() {
+                //     debug1!("Attempting to manually send action to verify it works...");
+                //     write_structured_log("ui/status_bar.rs", "Manual action send attempt", &serde_json::json!({}), "J");
+                //     let action_sent = {
+                //         use objc2_app_kit::NSApplication;
+                //         let app = NSApplication::sharedApplication(mtm);
+                //         let sent: bool = msg_send![&*app, sendAction: action, to: &*target, from: &*button];
+                //         sent
+                //     };
+                //     debug1!("Manual sendAction result: {}", action_sent);
+                //     write_structured_log("ui/status_bar.rs", "Manual sendAction result", &serde_json::json!({"sent": action_sent}), "J");
+                // }
+            }
+            debug2!("Button target and action set (no menu)");
+        } else {
+            debug1!("ERROR: Could not get button from status item!");
             write_structured_log(
                 "ui/status_bar.rs",
-                "Button enabled check",
-                &serde_json::json!({"enabled": is_enabled}),
+                "ERROR: Button not found",
+                &serde_json::json!({}),
                 "J",
             );
-
-            // CRITICAL: Try manually sending the action to verify it works
-            // if let Some(target) = button.target() {
-            //     debug1!("Attempting to manually send action to verify it works...");
-            //     write_structured_log("ui/status_bar.rs", "Manual action send attempt", &serde_json::json!({}), "J");
-            //     let action_sent = {
-            //         use objc2_app_kit::NSApplication;
-            //         let app = NSApplication::sharedApplication(mtm);
-            //         let sent: bool = msg_send![&*app, sendAction: action, to: &*target, from: &*button];
-            //         sent
-            //     };
-            //     debug1!("Manual sendAction result: {}", action_sent);
-            //     write_structured_log("ui/status_bar.rs", "Manual sendAction result", &serde_json::json!({"sent": action_sent}), "J");
-            // }
-        }
-        debug2!("Button target and action set (no menu)");
-    } else {
-        debug1!("ERROR: Could not get button from status item!");
-        write_structured_log(
-            "ui/status_bar.rs",
-            "ERROR: Button not found",
-            &serde_json::json!({}),
-            "J",
-        );
+        }
+        STATUS_ITEMS.with(|cell| {
+            cell.borrow_mut().insert(id.clone(), status_item);
+        });
     }
 
     // Handler is already stored in CLICK_HANDLER above, so it's retained
     // The button should also retain it via setTarget, so we have double retention
     debug3!("Handler retention: stored in CLICK_HANDLER and set as button target");
 
-    STATUS_ITEM.with(|cell| {
-        *cell.borrow_mut() = Some(status_item);
-    });
     debug2!("Status item setup complete");
 
-    // Start automatic menu bar updates by scheduling the first update
-    // The handler will reschedule itself every 2 seconds
+    // Start automatic menu bar updates
     if let Some(handler) = CLICK_HANDLER.with(|cell| cell.borrow().clone()) {
-        let update_sel = sel!(processMenuBarUpdate:);
-        unsafe {
-            // Schedule first update after 2 seconds
-            let _: () = msg_send![&*handler, performSelector: update_sel, withObject: std::ptr::null_mut::<AnyObject>(), afterDelay: 2.0];
-            debug1!("Scheduled automatic menu bar updates (first update in 2 seconds)");
-            write_structured_log(
-                "ui/status_bar.rs",
-                "Automatic updates scheduled",
-                &serde_json::json!({}),
-                "M",
-            );
-        }
+        start_update_timer(&handler);
     } else {
         debug1!("WARNING: Could not get handler for automatic updates");
     }
 }
 
+/// Seconds between `processMenuBarUpdate:` ticks; the update loop stores new text every 2s, so
+/// a title is at most this late
+const UPDATE_TIMER_INTERVAL_SECS: f64 = 1.0;
+
+/// Start the repeating main-thread timer that applies `MENU_BAR_TEXT` (replacing any earlier
+/// one). It runs in the common run loop modes, so titles keep updating while a menu is open or
+/// a window is being dragged or resized.
+fn start_update_timer(handler: &AnyObject) {
+    let timer = unsafe {
+        NSTimer::timerWithTimeInterval_target_selector_userInfo_repeats(
+            UPDATE_TIMER_INTERVAL_SECS,
+            handler,
+            sel!(processMenuBarUpdate:),
+            None,
+            true,
+        )
+    };
+    timer.setTolerance(UPDATE_TIMER_INTERVAL_SECS / 10.0);
+    unsafe { NSRunLoop::mainRunLoop().addTimer_forMode(&timer, NSRunLoopCommonModes) };
+    if let Some(previous) = UPDATE_TIMER.with(|cell| cell.borrow_mut().replace(timer)) {
+        previous.invalidate();
+    }
+    debug1!(
+        "Menu bar update timer started (every {}s)",
+        UPDATE_TIMER_INTERVAL_SECS
+    );
+    write_structured_log(
+        "ui/status_bar.rs",
+        "Automatic updates scheduled",
+        &serde_json::json!({"interval_secs": UPDATE_TIMER_INTERVAL_SECS}),
+        "M",
+    );
+}
+
 /// CPU window control for menu bar / chat: closes any existing `cpu` window, then creates one if none remains.
 ///
 /// Effect is always a visible CPU window after return (not a strict “close and stay closed” toggle).
@@ -472,6 +742,7 @@ pub fn toggle_cpu_window(app_handle: &AppHandle) {
         let is_visible = window.is_visible().unwrap_or(false);
         if is_visible {
             debug1!("CPU window is visible, hiding it");
+            super::window_frame::save(&window);
             let _ = window.hide();
         } else {
             // Reuse the existing WebView — recreating it caused multi-second hourglass
@@ -485,6 +756,8 @@ pub fn toggle_cpu_window(app_handle: &AppHandle) {
             if let Ok(mut last_call) = crate::state::LAST_CPU_DETAILS_CALL.try_lock() {
                 *last_call = None;
             }
+            // Temperature / frequency / power are only sampled while the window is visible
+            crate::sampler::send(crate::sampler::Command::RefreshNow);
         }
     } else {
         debug1!("CPU window doesn't exist, creating it");
@@ -492,6 +765,137 @@ pub fn toggle_cpu_window(app_handle: &AppHandle) {
     }
 }
 
+/// Show (or focus) the CPU window from an AppKit action on the main thread
+fn open_cpu_window_deferred() {
+    write_structured_log(
+        "ui/status_bar.rs",
+        "Click handler: about to open window",
+        &serde_json::json!({}),
+        "I",
+    );
+    run_deferred("show_cpu_window", show_cpu_window);
+}
+
+/// Run `f` on a later main-thread turn. Tauri 2 / wry: `AppHandle::run_on_main_thread` runs
+/// **inline** when already on the main thread (see tauri-runtime-wry `send_user_message`).
+/// NSStatusBarButton and menu actions fire on the main thread during AppKit event delivery;
+/// creating a `WebviewWindow` there re-enters the event loop and can deadlock or crash.
+/// Spawning a side thread forces `run_on_main_thread` to use the event-loop proxy so window
+/// creation runs on a clean main-thread turn.
+fn run_deferred(what: &'static str, f: fn(&AppHandle)) {
+    let Some(app_handle) = APP_HANDLE.get() else {
+        write_structured_log(
+            "ui/status_bar.rs",
+            "APP_HANDLE not available",
+            &serde_json::json!({}),
+            "I",
+        );
+        debug1!("APP_HANDLE not available!");
+        return;
+    };
+    let handle = app_handle.clone();
+    std::thread::spawn(move || {
+        let h = handle.clone();
+        if let Err(e) = handle.run_on_main_thread(move || f(&h)) {
+            debug1!("Deferred {} failed: {}", what, e);
+            write_structured_log(
+                "ui/status_bar.rs",
+                "Deferred run_on_main_thread error",
+                &serde_json::json!({"action": what, "error": e.to_string()}),
+                "I",
+            );
+        }
+    });
+}
+
+/// Wait after launch before pre-warming, so the WebView doesn't compete with startup
+const PREWARM_DELAY: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Create the CPU window hidden (`windowPrewarm`), so the first click only has to show it.
+/// Skipped while macOS reports memory pressure; see [`drop_hidden_cpu_window`].
+pub fn prewarm_cpu_window_if_enabled() {
+    if !Config::window_prewarm() {
+        return;
+    }
+    std::thread::spawn(|| {
+        std::thread::sleep(PREWARM_DELAY);
+        use crate::ffi::memory_pressure::{current_level, MemoryPressure};
+        if current_level().is_some_and(|level| level > MemoryPressure::Normal) {
+            debug1!("CPU window pre-warm skipped: memory pressure");
+            return;
+        }
+        run_deferred("prewarm_cpu_window", |app_handle| {
+            if app_handle.get_webview_window("cpu").is_none() {
+                build_cpu_window(app_handle, false);
+            }
+        });
+    });
+}
+
+/// Close the CPU window if it exists but is hidden (pre-warmed or closed by the user), freeing
+/// its WebView under memory pressure; the next click creates it again. True when one was closed.
+pub fn drop_hidden_cpu_window() -> bool {
+    let Some(window) = APP_HANDLE.get().and_then(|h| h.get_webview_window("cpu")) else {
+        return false;
+    };
+    if window.is_visible().unwrap_or(true) {
+        return false;
+    }
+    match window.destroy() {
+        Ok(()) => true,
+        Err(e) => {
+            debug1!("Could not close hidden CPU window: {}", e);
+            false
+        }
+    }
+}
+
+/// Like [`toggle_cpu_window`], but a visible window is focused instead of hidden
+fn show_cpu_window(app_handle: &AppHandle) {
+    match app_handle.get_webview_window("cpu") {
+        Some(window) if window.is_visible().unwrap_or(false) => {
+            let _ = window.set_focus();
+        }
+        _ => toggle_cpu_window(app_handle),
+    }
+}
+
+/// Drop-down menu under the status item `button`: live metrics, top processes and actions
+fn show_status_menu(mtm: MainThreadMarker, handler: &AnyObject, button: &NSView) {
+    let metrics = crate::metrics::get_metrics();
+    let fmt = NumberFormat::current();
+    let decimals = Config::menu_bar_decimals();
+    let lang = crate::i18n::Lang::current();
+    let rows = super::status_menu::metric_rows(lang, |name| {
+        template_value(name, &metrics, &fmt, decimals)
+    });
+    let processes: Vec<String> =
+        crate::metrics::processes_by_cpu(super::status_menu::TOP_PROCESSES)
+            .iter()
+            .map(|p| format!("{}  {}", p.name, fmt.percent(p.cpu as f64, 1)))
+            .collect();
+    let state = super::status_menu::MenuState {
+        paused: MENU_BAR_PAUSED.load(Ordering::Relaxed),
+        widget_visible: APP_HANDLE
+            .get()
+            .is_some_and(super::widget_window::is_visible),
+        widget_click_through: Config::widget_click_through(),
+        keep_awake: crate::keep_awake::status(),
+        profiles: crate::config::profiles::list()
+            .profiles
+            .into_iter()
+            .map(|p| p.name)
+            .collect(),
+        active_profile: crate::config::profiles::active(),
+        items_removed: !super::status_removal::removed(mtm).is_empty(),
+        lang,
+    };
+    let menu = super::status_menu::build_menu(mtm, handler, &rows, &processes, state);
+    // The button is flipped: y grows downwards, so this is just below the menu bar
+    let at = NSPoint::new(0.0, button.bounds().size.height + 5.0);
+    menu.popUpMenuPositioningItem_atLocation_inView(None, at, Some(button));
+}
+
 /// Get or create the Objective-C click handler class
 pub fn click_handler_class() -> &'static AnyClass {
     static REGISTER: OnceLock<&'static AnyClass> = OnceLock::new();
@@ -500,20 +904,13 @@ pub fn click_handler_class() -> &'static AnyClass {
         debug2!("Creating Objective-C class: {:?}", name);
         let mut builder = ClassBuilder::new(name, NSObject::class()).expect("class already exists");
 
-        // Add method to process menu bar updates (called automatically every 2 seconds)
+        // Fired by the update timer (see `start_update_timer`) on the main thread
         extern "C-unwind" fn process_menu_bar_update_timer(
-            this: &AnyObject,
+            _this: &AnyObject,
             _cmd: Sel,
-            _sender: *mut AnyObject,
+            _timer: *mut AnyObject,
         ) {
-            // This is called from Objective-C runtime, we're on the main thread
             process_menu_bar_update();
-
-            // Schedule next update in 2 seconds
-            let sel = sel!(processMenuBarUpdate:);
-            unsafe {
-                let _: () = msg_send![this, performSelector: sel, withObject: std::ptr::null_mut::<AnyObject>(), afterDelay: 2.0];
-            }
         }
 
         extern "C-unwind" fn on_status_item_click(
@@ -542,49 +939,199 @@ pub fn click_handler_class() -> &'static AnyClass {
             };
 
             let app = NSApplication::sharedApplication(mtm);
-            let is_right_click = app
+            let click = app
                 .currentEvent()
                 .map(|event: Retained<NSEvent>| {
                     let button_number = event.buttonNumber();
-                    debug3!("Event button number: {}", button_number);
-                    button_number == 1
+                    let flags = event.modifierFlags();
+                    debug3!("Event button number: {}, modifiers: {:?}", button_number, flags);
+                    Click::from_event(
+                        button_number,
+                        flags.contains(NSEventModifierFlags::Option),
+                        flags.contains(NSEventModifierFlags::Control),
+                    )
                 })
-                .unwrap_or(false);
-            debug2!("Is right click: {}", is_right_click);
+                .unwrap_or(Click::Left);
+            let action = Config::status_click_actions().for_click(click).clone();
+            debug1!("{:?} click: {:?}", click, action);
 
-            if is_right_click {
-                debug1!("Showing about panel");
-                show_about_panel();
-            } else {
-                debug1!("Left click - toggling CPU window");
-                write_structured_log("ui/status_bar.rs", "Click handler: about to toggle window", &serde_json::json!({}), "I");
-                if let Some(app_handle) = APP_HANDLE.get() {
-                    write_structured_log("ui/status_bar.rs", "APP_HANDLE found", &serde_json::json!({}), "I");
-                    // Tauri 2 / wry: `AppHandle::run_on_main_thread` runs **inline** when already on the
-                    // main thread (see tauri-runtime-wry `send_user_message`). NSStatusBarButton fires on
-                    // the main thread during AppKit event delivery; creating a `WebviewWindow` there
-                    // re-enters the event loop and can deadlock or crash. Spawning a side thread forces
-                    // `run_on_main_thread` to use the event-loop proxy so window creation runs on a clean
-                    // main-thread turn.
-                    let handle = app_handle.clone();
-                    std::thread::spawn(move || {
-                        let h = handle.clone();
-                        if let Err(e) = handle.run_on_main_thread(move || {
-                            toggle_cpu_window(&h);
-                        }) {
-                            debug1!("Deferred toggle_cpu_window failed: {}", e);
-                            write_structured_log(
-                                "ui/status_bar.rs",
-                                "Deferred toggle_cpu_window run_on_main_thread error",
-                                &serde_json::json!({"error": e.to_string()}),
-                                "I",
-                            );
-                        }
-                    });
-                } else {
-                    write_structured_log("ui/status_bar.rs", "APP_HANDLE not available", &serde_json::json!({}), "I");
-                    debug1!("APP_HANDLE not available!");
+            match action {
+                ClickAction::Menu => {
+                    // SAFETY: the action's sender is the status item button (an NSView)
+                    match unsafe { (sender as *const NSView).as_ref() } {
+                        Some(button) => show_status_menu(mtm, this, button),
+                        None => open_cpu_window_deferred(),
+                    }
                 }
+                ClickAction::CpuWindow => open_cpu_window_deferred(),
+                ClickAction::About => run_deferred("show_about_window", super::about_window::show),
+                ClickAction::ActivityMonitor => super::click_actions::open_activity_monitor(),
+                ClickAction::CopyStats(format) => super::copy_stats::copy_in_background(format),
+                ClickAction::Run(command) => super::click_actions::run_command(&command),
+                ClickAction::None => {}
+            }
+        }
+
+        extern "C-unwind" fn open_cpu_window(_this: &AnyObject, _cmd: Sel, _sender: *mut AnyObject) {
+            open_cpu_window_deferred();
+        }
+
+        extern "C-unwind" fn toggle_pause_updates(
+            _this: &AnyObject,
+            _cmd: Sel,
+            _sender: *mut AnyObject,
+        ) {
+            let paused = !MENU_BAR_PAUSED.fetch_xor(true, Ordering::Relaxed);
+            tracing::info!(
+                "Menu bar updates {}",
+                if paused { "paused" } else { "resumed" }
+            );
+        }
+
+        extern "C-unwind" fn toggle_widget(_this: &AnyObject, _cmd: Sel, _sender: *mut AnyObject) {
+            run_deferred("toggle_widget", super::widget_window::toggle);
+        }
+
+        extern "C-unwind" fn open_network_window(
+            _this: &AnyObject,
+            _cmd: Sel,
+            _sender: *mut AnyObject,
+        ) {
+            run_deferred("show_network_window", super::network_window::show);
+        }
+
+        extern "C-unwind" fn open_battery_window(
+            _this: &AnyObject,
+            _cmd: Sel,
+            _sender: *mut AnyObject,
+        ) {
+            run_deferred("show_battery_window", super::battery_window::show);
+        }
+
+        extern "C-unwind" fn open_dashboard_window(
+            _this: &AnyObject,
+            _cmd: Sel,
+            _sender: *mut AnyObject,
+        ) {
+            run_deferred("show_dashboard_window", super::dashboard_window::show);
+        }
+
+        extern "C-unwind" fn open_log_window(_this: &AnyObject, _cmd: Sel, _sender: *mut AnyObject) {
+            run_deferred("show_log_window", super::log_window::show);
+        }
+
+        extern "C-unwind" fn toggle_widget_click_through(
+            _this: &AnyObject,
+            _cmd: Sel,
+            _sender: *mut AnyObject,
+        ) {
+            if let Some(app_handle) = APP_HANDLE.get() {
+                super::widget_window::toggle_click_through(app_handle);
+            }
+        }
+
+        fn keep_awake(duration: Option<std::time::Duration>, enabled: bool) {
+            if let Err(e) = crate::keep_awake::set(enabled, duration) {
+                tracing::warn!("Keep awake failed: {}", e);
+            }
+        }
+
+        extern "C-unwind" fn keep_awake_30_minutes(
+            _this: &AnyObject,
+            _cmd: Sel,
+            _sender: *mut AnyObject,
+        ) {
+            keep_awake(Some(std::time::Duration::from_secs(30 * 60)), true);
+        }
+
+        extern "C-unwind" fn keep_awake_1_hour(_this: &AnyObject, _cmd: Sel, _sender: *mut AnyObject) {
+            keep_awake(Some(std::time::Duration::from_secs(60 * 60)), true);
+        }
+
+        extern "C-unwind" fn keep_awake_2_hours(
+            _this: &AnyObject,
+            _cmd: Sel,
+            _sender: *mut AnyObject,
+        ) {
+            keep_awake(Some(std::time::Duration::from_secs(2 * 60 * 60)), true);
+        }
+
+        extern "C-unwind" fn keep_awake_indefinitely(
+            _this: &AnyObject,
+            _cmd: Sel,
+            _sender: *mut AnyObject,
+        ) {
+            keep_awake(None, true);
+        }
+
+        extern "C-unwind" fn keep_awake_off(_this: &AnyObject, _cmd: Sel, _sender: *mut AnyObject) {
+            keep_awake(None, false);
+        }
+
+        fn use_profile(name: Option<&str>) {
+            if let Err(e) = crate::config::profiles::use_profile(name) {
+                tracing::warn!("Profile from status menu: {}", e);
+            }
+        }
+
+        extern "C-unwind" fn use_profile_item(
+            _this: &AnyObject,
+            _cmd: Sel,
+            sender: *mut AnyObject,
+        ) {
+            // SAFETY: the sender of a Profile submenu action is its NSMenuItem
+            let Some(item) = (unsafe { (sender as *const NSMenuItem).as_ref() }) else {
+                return;
+            };
+            use_profile(Some(&item.title().to_string()));
+        }
+
+        extern "C-unwind" fn profile_off(_this: &AnyObject, _cmd: Sel, _sender: *mut AnyObject) {
+            use_profile(None);
+        }
+
+        extern "C-unwind" fn copy_stats_text(_this: &AnyObject, _cmd: Sel, _sender: *mut AnyObject) {
+            super::copy_stats::copy_in_background(StatsFormat::Text);
+        }
+
+        extern "C-unwind" fn copy_stats_markdown(
+            _this: &AnyObject,
+            _cmd: Sel,
+            _sender: *mut AnyObject,
+        ) {
+            super::copy_stats::copy_in_background(StatsFormat::Markdown);
+        }
+
+        extern "C-unwind" fn copy_stats_json(_this: &AnyObject, _cmd: Sel, _sender: *mut AnyObject) {
+            super::copy_stats::copy_in_background(StatsFormat::Json);
+        }
+
+        extern "C-unwind" fn generate_system_report(
+            _this: &AnyObject,
+            _cmd: Sel,
+            _sender: *mut AnyObject,
+        ) {
+            crate::metrics::system_report::generate_and_open();
+        }
+
+        extern "C-unwind" fn restore_menu_bar_items(
+            _this: &AnyObject,
+            _cmd: Sel,
+            _sender: *mut AnyObject,
+        ) {
+            if let Some(mtm) = MainThreadMarker::new() {
+                super::status_removal::restore(mtm);
+            }
+        }
+
+        extern "C-unwind" fn show_about(_this: &AnyObject, _cmd: Sel, _sender: *mut AnyObject) {
+            run_deferred("show_about_window", super::about_window::show);
+        }
+
+        extern "C-unwind" fn quit_app(_this: &AnyObject, _cmd: Sel, _sender: *mut AnyObject) {
+            debug1!("Quit from status menu");
+            if let Some(app_handle) = APP_HANDLE.get() {
+                app_handle.exit(0);
             }
         }
         unsafe {
@@ -601,6 +1148,96 @@ pub fn click_handler_class() -> &'static AnyClass {
                 update_sel,
                 process_menu_bar_update_timer as extern "C-unwind" fn(_, _, _),
             );
+
+            // Status menu actions (see `status_menu::build_menu`)
+            builder.add_method(
+                sel!(openCpuWindow:),
+                open_cpu_window as extern "C-unwind" fn(_, _, _),
+            );
+            builder.add_method(
+                sel!(openNetworkWindow:),
+                open_network_window as extern "C-unwind" fn(_, _, _),
+            );
+            builder.add_method(
+                sel!(openBatteryWindow:),
+                open_battery_window as extern "C-unwind" fn(_, _, _),
+            );
+            builder.add_method(
+                sel!(openDashboardWindow:),
+                open_dashboard_window as extern "C-unwind" fn(_, _, _),
+            );
+            builder.add_method(
+                sel!(openLogWindow:),
+                open_log_window as extern "C-unwind" fn(_, _, _),
+            );
+            builder.add_method(
+                sel!(toggleWidget:),
+                toggle_widget as extern "C-unwind" fn(_, _, _),
+            );
+            builder.add_method(
+                sel!(toggleWidgetClickThrough:),
+                toggle_widget_click_through as extern "C-unwind" fn(_, _, _),
+            );
+            builder.add_method(
+                sel!(togglePauseUpdates:),
+                toggle_pause_updates as extern "C-unwind" fn(_, _, _),
+            );
+            builder.add_method(
+                sel!(keepAwake30Minutes:),
+                keep_awake_30_minutes as extern "C-unwind" fn(_, _, _),
+            );
+            builder.add_method(
+                sel!(keepAwake1Hour:),
+                keep_awake_1_hour as extern "C-unwind" fn(_, _, _),
+            );
+            builder.add_method(
+                sel!(keepAwake2Hours:),
+                keep_awake_2_hours as extern "C-unwind" fn(_, _, _),
+            );
+            builder.add_method(
+                sel!(keepAwakeIndefinitely:),
+                keep_awake_indefinitely as extern "C-unwind" fn(_, _, _),
+            );
+            builder.add_method(
+                sel!(keepAwakeOff:),
+                keep_awake_off as extern "C-unwind" fn(_, _, _),
+            );
+            builder.add_method(
+                sel!(useProfile:),
+                use_profile_item as extern "C-unwind" fn(_, _, _),
+            );
+            builder.add_method(
+                sel!(profileOff:),
+                profile_off as extern "C-unwind" fn(_, _, _),
+            );
+            builder.add_method(
+                sel!(copyStatsText:),
+                copy_stats_text as extern "C-unwind" fn(_, _, _),
+            );
+            builder.add_method(
+                sel!(copyStatsMarkdown:),
+                copy_stats_markdown as extern "C-unwind" fn(_, _, _),
+            );
+            builder.add_method(
+                sel!(copyStatsJson:),
+                copy_stats_json as extern "C-unwind" fn(_, _, _),
+            );
+            builder.add_method(
+                sel!(generateSystemReport:),
+                generate_system_report as extern "C-unwind" fn(_, _, _),
+            );
+            builder.add_method(
+                sel!(restoreMenuBarItems:),
+                restore_menu_bar_items as extern "C-unwind" fn(_, _, _),
+            );
+            builder.add_method(
+                sel!(showAbout:),
+                show_about as extern "C-unwind" fn(_, _, _),
+            );
+            builder.add_method(
+                sel!(quitApp:),
+                quit_app as extern "C-unwind" fn(_, _, _),
+            );
         }
         let registered_class = builder.register();
         debug2!("Objective-C class registered: {:?}", registered_class);
@@ -624,53 +1261,14 @@ pub fn click_handler_class() -> &'static AnyClass {
     })
 }
 
-/// Show the about panel
-pub fn show_about_panel() {
-    let mtm = MainThreadMarker::new().unwrap();
-    let app = NSApplication::sharedApplication(mtm);
-
-    // Use a nicer application name
-    let name = NSString::from_str("mac-stats");
-    let version = NSString::from_str(&Config::version());
-    let build = NSString::from_str(&Config::build_date());
-
-    // Create a nicely formatted credits text with better styling
-    let credits_text = format!(
-        "A lightweight system monitor for macOS\n\n\
-        Built with Rust and Tauri\n\
-        Inspired by Stats by exelban\n\n\
-        Version {}\n\
-        Build: {}\n\n\
-        © 2026",
-        Config::version(),
-        Config::build_date()
-    );
-    let credits = NSAttributedString::from_nsstring(&NSString::from_str(&credits_text));
-
-    let keys = unsafe {
-        [
-            NSAboutPanelOptionApplicationName,
-            NSAboutPanelOptionApplicationVersion,
-            NSAboutPanelOptionVersion,
-            NSAboutPanelOptionCredits,
-        ]
-    };
-    let values: [&AnyObject; 4] = [
-        as_any(&*name),
-        as_any(&*version),
-        as_any(&*build),
-        as_any(&*credits),
-    ];
-    let options = NSMutableDictionary::from_slices(&keys, &values);
-
-    unsafe {
-        app.orderFrontStandardAboutPanelWithOptions(options.as_ref());
-    }
-}
-
 /// Create the CPU details window
 pub fn create_cpu_window(app_handle: &tauri::AppHandle) {
-    debug1!("Creating CPU window...");
+    build_cpu_window(app_handle, true);
+}
+
+/// Create the CPU window (`show`: visible and focused, otherwise hidden with its page loaded)
+fn build_cpu_window(app_handle: &tauri::AppHandle, show: bool) {
+    debug1!("Creating CPU window (show={})...", show);
     write_structured_log(
         "ui/status_bar.rs",
         "create_cpu_window ENTRY",
@@ -687,15 +1285,21 @@ pub fn create_cpu_window(app_handle: &tauri::AppHandle) {
         decorations
     );
 
-    let cpu_window =
+    // Last position / size / always-on-top (`cpuWindowFrame`); the position only when it is
+    // still on a connected display
+    let frame = Config::cpu_window_frame();
+    let mut builder =
         WebviewWindowBuilder::new(app_handle, "cpu", WebviewUrl::App("cpu.html".into()))
             .title("CPU")
-            .visible(true) // Show immediately when created
-            .inner_size(644.0, 995.0)
+            .visible(show)
+            .inner_size(frame.width, frame.height)
             .resizable(true)
-            .always_on_top(false)
-            .decorations(decorations)
-            .build();
+            .always_on_top(frame.always_on_top)
+            .decorations(decorations);
+    if let Some((x, y)) = frame.visible_position(&super::window_frame::displays(app_handle)) {
+        builder = builder.position(x, y);
+    }
+    let cpu_window = builder.build();
 
     match cpu_window {
         Ok(window) => {
@@ -709,19 +1313,18 @@ pub fn create_cpu_window(app_handle: &tauri::AppHandle) {
 
             // Clear process cache to force fresh collection on first call
             // This ensures we get up-to-date process list immediately when window opens
-            use crate::state::PROCESS_CACHE;
-            if let Ok(mut cache) = PROCESS_CACHE.try_lock() {
-                *cache = None;
+            if show {
+                crate::state::update_snapshot(|s| s.processes = None);
                 debug2!("Process cache cleared - will refresh immediately on first get_cpu_details() call");
-            }
 
-            // Clear rate limiter so first call always goes through (instant data on window open)
-            use crate::state::LAST_CPU_DETAILS_CALL;
-            if let Ok(mut last_call) = LAST_CPU_DETAILS_CALL.try_lock() {
-                *last_call = None;
-                debug2!(
-                    "Rate limiter cleared - first get_cpu_details() call will execute immediately"
-                );
+                // Clear rate limiter so first call always goes through (instant data on window open)
+                use crate::state::LAST_CPU_DETAILS_CALL;
+                if let Ok(mut last_call) = LAST_CPU_DETAILS_CALL.try_lock() {
+                    *last_call = None;
+                    debug2!(
+                        "Rate limiter cleared - first get_cpu_details() call will execute immediately"
+                    );
+                }
             }
 
             // Enable devtools for right-click inspect
@@ -733,10 +1336,19 @@ pub fn create_cpu_window(app_handle: &tauri::AppHandle) {
                 // Right-click inspect should work
             }
 
-            let _ = window.set_always_on_top(false);
-            let _ = window.show();
-            let _ = window.set_focus();
-            let _ = window.unminimize();
+            let _ = window.set_always_on_top(frame.always_on_top);
+            let pin_mode = Config::cpu_window_pin_mode();
+            if pin_mode != super::window_pin::PinMode::Normal {
+                if let Err(e) = super::window_pin::apply(&window, pin_mode) {
+                    debug1!("Could not apply CPU window pin mode {:?}: {}", pin_mode, e);
+                }
+            }
+            if show {
+                let _ = window.show();
+                let _ = window.set_focus();
+                let _ = window.unminimize();
+                crate::sampler::send(crate::sampler::Command::RefreshNow);
+            }
 
             // Title-bar close should hide (keep WebView warm) instead of destroying —
             // destroying forced a full recreate + JS boot on every menu-bar click.
@@ -744,19 +1356,19 @@ pub fn create_cpu_window(app_handle: &tauri::AppHandle) {
             window.on_window_event(move |event| {
                 if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                     api.prevent_close();
+                    super::window_frame::save(&window_for_close);
                     let _ = window_for_close.hide();
                     debug1!("CPU window close requested — hidden instead of destroyed");
                 }
             });
 
-            
-            debug1!("CPU window shown and focused");
-            write_structured_log(
-                "ui/status_bar.rs",
-                "CPU window shown and focused",
-                &serde_json::json!({}),
-                "I",
-            );
+            let outcome = if show {
+                "CPU window shown and focused"
+            } else {
+                "CPU window pre-warmed (hidden)"
+            };
+            debug1!("{}", outcome);
+            write_structured_log("ui/status_bar.rs", outcome, &serde_json::json!({}), "I");
         }
         Err(e) => {
             debug1!("ERROR: Failed to create CPU window: {:?}", e);
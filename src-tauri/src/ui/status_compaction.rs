@@ -0,0 +1,314 @@
+//! Menu bar width auto-compaction (`menuBarAutoCompact`)
+//!
+//! When the menu bar is full (many status items, or a wide app menu next to the camera housing)
+//! macOS hides the status items that do not fit rather than shrinking them. The status bar timer
+//! checks every second whether one of ours ended up off screen or behind the housing, and then
+//! narrows the main item one step per applied title:
+//!
+//! 1. labels longer than three characters are cut (`Tokyo` → `Tok`)
+//! 2. one line, each value after its label's initial (`C12% G5% R61% S40%`)
+//! 3. the lowest-ranked column is dropped, one per step, down to a single column
+//!
+//! Ranks come from `#N` on `menuBarTemplate` segments (1 is kept longest, see
+//! `status_template`); the built-in grid keeps CPU, then RAM, GPU and SSD. Unranked columns
+//! (`menuBarExtras`) go first, right to left, and a column with an active menu bar alert stays.
+//! Steps that would change nothing for the current layout are skipped; the compact CPU + °C
+//! layout is already as narrow as it gets. Every few minutes one step is undone to see whether
+//! the wider layout fits again.
+
+use std::cmp::Reverse;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+
+/// How long a compaction step stays before it is undone on trial
+const RETRY_AFTER: Duration = Duration::from_secs(300);
+/// Ranks of the built-in grid columns (CPU, GPU, RAM, SSD)
+const GRID_PRIORITIES: [Option<u8>; 4] = [Some(1), Some(3), Some(2), Some(4)];
+/// Labels at step 1 keep this many characters
+const SHORT_LABEL_CHARS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    Abbreviate,
+    SingleLine,
+    Drop,
+}
+
+#[derive(Debug)]
+struct Compaction {
+    /// Steps currently applied
+    level: usize,
+    /// Steps the current layout offers
+    steps: usize,
+    /// `MENU_BAR_TITLES_APPLIED` when `level` last changed
+    applied: u64,
+    retry_at: Option<Instant>,
+}
+
+static STATE: Mutex<Compaction> = Mutex::new(Compaction {
+    level: 0,
+    steps: 0,
+    applied: 0,
+    retry_at: None,
+});
+
+impl Compaction {
+    /// One timer tick: `truncated` when macOS hid a status item, `applied` the titles set so far
+    fn observe(&mut self, truncated: bool, applied: u64, now: Instant) {
+        if truncated {
+            // Wait until the last step's title has been laid out before taking another
+            if self.level < self.steps && applied > self.applied {
+                self.level += 1;
+                self.applied = applied;
+                self.retry_at = Some(now + RETRY_AFTER);
+                tracing::info!(
+                    "Menu bar has no room for the status item: compaction step {} of {}",
+                    self.level,
+                    self.steps
+                );
+            }
+        } else if self.level > 0 && self.retry_at.is_some_and(|at| now >= at) {
+            self.level -= 1;
+            self.applied = applied;
+            self.retry_at = Some(now + RETRY_AFTER);
+            tracing::debug!("Menu bar compaction: trying step {}", self.level);
+        }
+    }
+}
+
+/// Call from the status bar timer (main thread) with whether a status item is hidden
+pub fn observe(truncated: bool) {
+    let Ok(mut state) = STATE.lock() else {
+        return;
+    };
+    if !Config::menu_bar_auto_compact() {
+        state.level = 0;
+        return;
+    }
+    let applied = crate::state::MENU_BAR_TITLES_APPLIED.load(std::sync::atomic::Ordering::Relaxed);
+    state.observe(truncated, applied, Instant::now());
+}
+
+/// Whether a compaction step is applied
+pub fn active() -> bool {
+    STATE.lock().is_ok_and(|state| state.level > 0)
+}
+
+/// Main status item text at the current compaction level. Columns of the `alerting` metrics
+/// are never dropped.
+pub fn compact(text: &str, alerting: &[String]) -> String {
+    let keep: Vec<&str> = alerting
+        .iter()
+        .filter_map(|m| super::status_alerts::column_label(m))
+        .collect();
+    let priorities = priorities();
+    let Ok(mut state) = STATE.lock() else {
+        return text.to_string();
+    };
+    let (compacted, steps) = compact_text(text, &priorities, &keep, state.level);
+    state.steps = steps;
+    state.level = state.level.min(steps);
+    compacted
+}
+
+/// Append a cue such as `Mon ✕`: on its own line, or after the text once compaction made it a
+/// single line
+pub fn push_cue(text: &mut String, cue: &str) {
+    text.push_str(if active() && !text.contains('\n') {
+        "  "
+    } else {
+        "\n"
+    });
+    text.push_str(cue);
+}
+
+/// Rank of each column of the current layout
+fn priorities() -> Vec<Option<u8>> {
+    if let Some(template) = Config::menu_bar_template() {
+        return super::status_template::priorities(&template);
+    }
+    if Config::menu_bar_compact() {
+        return Vec::new();
+    }
+    GRID_PRIORITIES.to_vec()
+}
+
+struct Column {
+    label: String,
+    value: String,
+    priority: Option<u8>,
+    keep: bool,
+}
+
+/// Columns of a "labels\nvalues" text: tab fields paired by position, or with empty fields
+/// (extra alignment tabs) removed. `None` when the lines do not pair up, e.g. compact mode.
+fn columns(labels: &str, values: &str) -> Option<Vec<(String, String)>> {
+    let split = |line: &str, skip_empty: bool| -> Vec<String> {
+        line.split('\t')
+            .map(|f| f.trim().to_string())
+            .filter(|f| !skip_empty || !f.is_empty())
+            .collect()
+    };
+    if labels.chars().any(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    [false, true].into_iter().find_map(|skip_empty| {
+        let (l, v) = (split(labels, skip_empty), split(values, skip_empty));
+        (l.len() == v.len()).then(|| l.into_iter().zip(v).collect())
+    })
+}
+
+/// `text` with the first `level` steps applied, and how many steps it offers
+fn compact_text(
+    text: &str,
+    priorities: &[Option<u8>],
+    keep: &[&str],
+    level: usize,
+) -> (String, usize) {
+    let lines: Vec<&str> = text.split('\n').collect();
+    let (pairs, two_line) = match lines.as_slice() {
+        // One-line template: its `|` segments
+        [line] => (
+            line.split('|')
+                .map(|s| (String::new(), s.trim().to_string()))
+                .collect(),
+            false,
+        ),
+        [labels, values] => match columns(labels, values) {
+            Some(pairs) => (pairs, true),
+            None => return (text.to_string(), 0),
+        },
+        _ => return (text.to_string(), 0),
+    };
+    let mut cols: Vec<Column> = pairs
+        .into_iter()
+        .enumerate()
+        .map(|(i, (label, value))| Column {
+            keep: !label.is_empty() && keep.contains(&label.as_str()),
+            priority: priorities.get(i).copied().flatten(),
+            label,
+            value,
+        })
+        .collect();
+
+    let mut steps = Vec::new();
+    if two_line
+        && cols
+            .iter()
+            .any(|c| c.label.chars().count() > SHORT_LABEL_CHARS)
+    {
+        steps.push(Step::Abbreviate);
+    }
+    if two_line {
+        steps.push(Step::SingleLine);
+    }
+    let droppable = cols.iter().filter(|c| !c.keep).count();
+    steps.extend(std::iter::repeat_n(
+        Step::Drop,
+        droppable.min(cols.len() - 1),
+    ));
+    if level == 0 {
+        return (text.to_string(), steps.len());
+    }
+
+    let applied = &steps[..level.min(steps.len())];
+    if applied.contains(&Step::Abbreviate) {
+        for col in &mut cols {
+            col.label = col.label.chars().take(SHORT_LABEL_CHARS).collect();
+        }
+    }
+    let drops = applied.iter().filter(|s| **s == Step::Drop).count();
+    // Unranked first, then the highest rank number; rightmost first among equals
+    let mut order: Vec<usize> = (0..cols.len()).filter(|i| !cols[*i].keep).collect();
+    order.sort_by_key(|&i| {
+        let rank = cols[i].priority.map_or(u16::MAX, u16::from);
+        (Reverse(rank), Reverse(i))
+    });
+    let mut dropped: Vec<usize> = order.into_iter().take(drops).collect();
+    dropped.sort_unstable();
+    for i in dropped.into_iter().rev() {
+        cols.remove(i);
+    }
+
+    let compacted = if !two_line {
+        cols.iter()
+            .map(|c| c.value.as_str())
+            .collect::<Vec<_>>()
+            .join(" | ")
+    } else if applied.contains(&Step::SingleLine) {
+        cols.iter()
+            .map(|c| match c.label.chars().next() {
+                Some(initial) => format!("{}{}", initial.to_uppercase(), c.value),
+                None => c.value.clone(),
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    } else {
+        let (first, rest) = cols.split_first().expect("at least one column");
+        let rest: Vec<(String, String)> = rest
+            .iter()
+            .map(|c| (c.label.clone(), c.value.clone()))
+            .collect();
+        super::status_extras::append_columns(&format!("{}\n{}", first.label, first.value), &rest)
+    };
+    (compacted, steps.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRID: &str = "CPU\tGPU\tRAM\tSSD\tTokyo\n12%\t5%\t61%\t40%\t09:15";
+
+    #[test]
+    fn steps_abbreviate_then_join_then_drop_by_rank() {
+        let at = |level| compact_text(GRID, &GRID_PRIORITIES, &[], level);
+        assert_eq!(at(0), (GRID.to_string(), 6));
+        assert_eq!(at(1).0, "CPU\tGPU\tRAM\tSSD\tTok\n12%\t5%\t61%\t40%\t09:15");
+        assert_eq!(at(2).0, "C12% G5% R61% S40% T09:15");
+        assert_eq!(at(3).0, "C12% G5% R61% S40%");
+        assert_eq!(at(4).0, "C12% G5% R61%");
+        assert_eq!(at(5).0, "C12% R61%");
+        assert_eq!(at(6).0, "C12%");
+        assert_eq!(at(9).0, "C12%");
+
+        // An alerting column stays; the built-in labels need no abbreviation
+        let grid = "CPU\tGPU\tRAM\tSSD\n12%\t5%\t61%\t40%";
+        let (text, steps) = compact_text(grid, &GRID_PRIORITIES, &["SSD"], 3);
+        assert_eq!((text.as_str(), steps), ("C12% S40%", 4));
+
+        // One-line template segments by `#N` rank; compact mode is left alone
+        let ranks = [Some(2), None, Some(1)];
+        assert_eq!(
+            compact_text("12% | 61°C | 40%", &ranks, &[], 1),
+            ("12% | 40%".to_string(), 2)
+        );
+        assert_eq!(compact_text("12% | 61°C | 40%", &ranks, &[], 2).0, "40%");
+        assert_eq!(
+            compact_text("CPU  12%\n61°", &[], &[], 3),
+            ("CPU  12%\n61°".to_string(), 0)
+        );
+    }
+
+    #[test]
+    fn retries_a_wider_step_after_a_while() {
+        let start = Instant::now();
+        let mut state = Compaction {
+            level: 0,
+            steps: 3,
+            applied: 0,
+            retry_at: None,
+        };
+        state.observe(true, 1, start);
+        // Same title still laid out: no second step yet
+        state.observe(true, 1, start);
+        assert_eq!(state.level, 1);
+        state.observe(true, 2, start);
+        state.observe(false, 3, start + Duration::from_secs(1));
+        assert_eq!(state.level, 2);
+        state.observe(false, 4, start + RETRY_AFTER + Duration::from_secs(1));
+        assert_eq!(state.level, 1);
+    }
+}
@@ -0,0 +1,224 @@
+//! Informational (non-system) menu bar columns
+//!
+//! `menuBarExtras` in config.json adds world clocks and a next-calendar-event countdown to the
+//! status item, after the system metrics. Columns share the status item's two-line layout
+//! (small label over larger value) and its tab stops, so they line up in both grid and compact
+//! mode.
+
+use chrono::{DateTime, FixedOffset, Local, Offset, Utc};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::config::{Config, MenuBarExtra};
+use crate::ffi::calendar;
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+/// Tab interval used by `make_attributed_title` (points)
+pub(crate) const TAB_INTERVAL_PT: f64 = 38.0;
+/// Approximate advance of one monospaced character in the label (8.5pt) and value (12.5pt) fonts
+const LABEL_CHAR_PT: f64 = 8.5 * 0.6;
+const VALUE_CHAR_PT: f64 = 12.5 * 0.6;
+/// Calendar lookups are cached; the status item refreshes every second.
+const NEXT_EVENT_REFRESH: Duration = Duration::from_secs(60);
+
+/// Cached next-event lookup: (taken at, lookahead hours, start of next event)
+type NextEventCache = Option<(Instant, u32, Option<i64>)>;
+static NEXT_EVENT_CACHE: Mutex<NextEventCache> = Mutex::new(None);
+
+/// Rendered x position (points) at the end of `line`, expanding tabs to the next stop.
+fn line_width(line: &str, char_pt: f64) -> f64 {
+    line.chars().fold(0.0, |x, c| {
+        if c == '\t' {
+            ((x / TAB_INTERVAL_PT).floor() + 1.0) * TAB_INTERVAL_PT
+        } else {
+            x + char_pt
+        }
+    })
+}
+
+/// Tabs needed to move from `x` to the tab stop `target`.
+fn tabs_to(x: f64, target: f64) -> usize {
+    let mut tabs = 0;
+    let mut x = x;
+    while x < target {
+        x = ((x / TAB_INTERVAL_PT).floor() + 1.0) * TAB_INTERVAL_PT;
+        tabs += 1;
+    }
+    tabs.max(1)
+}
+
+/// Append `(label, value)` columns to a "labels\nvalues" status text. Each column starts at
+/// the first tab stop past both lines, so labels stay above their values even when the two
+/// lines (different font sizes) end at different widths, e.g. compact mode.
+pub(crate) fn append_columns(text: &str, columns: &[(String, String)]) -> String {
+    let (mut labels, rest) = match text.split_once('\n') {
+        Some((labels, rest)) => (labels.to_string(), rest.to_string()),
+        None => (text.to_string(), String::new()),
+    };
+    let (mut values, tail) = match rest.split_once('\n') {
+        Some((values, tail)) => (values.to_string(), Some(tail.to_string())),
+        None => (rest, None),
+    };
+    for (label, value) in columns {
+        let (lx, vx) = (
+            line_width(&labels, LABEL_CHAR_PT),
+            line_width(&values, VALUE_CHAR_PT),
+        );
+        let target = ((lx.max(vx) / TAB_INTERVAL_PT).floor() + 1.0) * TAB_INTERVAL_PT;
+        labels.push_str(&"\t".repeat(tabs_to(lx, target)));
+        labels.push_str(label);
+        values.push_str(&"\t".repeat(tabs_to(vx, target)));
+        values.push_str(value);
+    }
+    match tail {
+        Some(tail) => format!("{labels}\n{values}\n{tail}"),
+        None => format!("{labels}\n{values}"),
+    }
+}
+
+/// `HH:MM` (or `H:MM` for 12-hour clocks) at `offset`.
+fn format_clock(now: DateTime<Utc>, offset: FixedOffset, hour12: bool) -> String {
+    let local = now.with_timezone(&offset);
+    if hour12 {
+        local.format("%-I:%M").to_string()
+    } else {
+        local.format("%H:%M").to_string()
+    }
+}
+
+/// Compact countdown: `<1m`, `12m`, `1h05`; `—` when nothing is coming up.
+fn format_countdown(secs: Option<i64>) -> String {
+    match secs {
+        None => "—".to_string(),
+        Some(s) if s < 60 => "<1m".to_string(),
+        Some(s) if s < 3600 => format!("{}m", s / 60),
+        Some(s) => format!("{}h{:02}", s / 3600, (s % 3600) / 60),
+    }
+}
+
+fn clock_offset(time_zone: Option<&str>) -> Option<FixedOffset> {
+    match time_zone {
+        None => Some(Local::now().offset().fix()),
+        Some(name) => calendar::time_zone_offset_secs(name).and_then(FixedOffset::east_opt),
+    }
+}
+
+fn next_event_start(now: DateTime<Utc>, lookahead_hours: u32) -> Option<i64> {
+    let Ok(mut cache) = NEXT_EVENT_CACHE.lock() else {
+        return None;
+    };
+    if let Some((taken_at, hours, start)) = *cache {
+        // Also refresh once the cached event has started, to pick up the one after it.
+        let started = start.is_some_and(|s| s <= now.timestamp());
+        if hours == lookahead_hours && taken_at.elapsed() < NEXT_EVENT_REFRESH && !started {
+            return start;
+        }
+    }
+    let start = calendar::next_event_start(now.timestamp(), i64::from(lookahead_hours) * 3600)
+        .unwrap_or_else(|e| {
+            debug2!("Next calendar event unavailable: {}", e);
+            None
+        });
+    *cache = Some((Instant::now(), lookahead_hours, start));
+    start
+}
+
+fn render(extra: &MenuBarExtra, now: DateTime<Utc>) -> (String, String) {
+    match extra {
+        MenuBarExtra::Clock {
+            label,
+            time_zone,
+            hour12,
+        } => {
+            let value = match clock_offset(time_zone.as_deref()) {
+                Some(offset) => format_clock(now, offset, *hour12),
+                None => {
+                    debug2!("Unknown menu bar clock time zone: {:?}", time_zone);
+                    "?".to_string()
+                }
+            };
+            (label.clone(), value)
+        }
+        MenuBarExtra::NextEvent {
+            label,
+            lookahead_hours,
+        } => {
+            let start = next_event_start(now, *lookahead_hours);
+            (
+                label.clone(),
+                format_countdown(start.map(|s| s - now.timestamp())),
+            )
+        }
+    }
+}
+
+/// Append the configured `menuBarExtras` columns to the status text (no-op when none).
+pub fn append_extras(text: String) -> String {
+    let extras = Config::menu_bar_extras();
+    if extras.is_empty() {
+        return text;
+    }
+    let now = Utc::now();
+    let columns: Vec<(String, String)> = extras.iter().map(|e| render(e, now)).collect();
+    // A single-line `menuBarTemplate` gets the extras inline
+    if !text.contains('\n') {
+        return columns.iter().fold(text, |line, (label, value)| {
+            format!("{line}  {label} {value}")
+        });
+    }
+    append_columns(&text, &columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn columns_align_labels_over_values() {
+        let grid = append_columns(
+            "CPU\tGPU\tRAM\tSSD\n12%\t3%\t61%\t48%",
+            &[("NYC".to_string(), "09:15".to_string())],
+        );
+        assert_eq!(grid, "CPU\tGPU\tRAM\tSSD\tNYC\n12%\t3%\t61%\t48%\t09:15");
+
+        // Compact: "CPU  12.5%" (small font) is wider than "52°" (large font); both must
+        // advance to the same stop.
+        let compact = append_columns(
+            "CPU  12.5%\n52°",
+            &[("Next".to_string(), "12m".to_string())],
+        );
+        let (labels, values) = compact.split_once('\n').unwrap();
+        let target = line_width("CPU  12.5%", LABEL_CHAR_PT).max(line_width("52°", VALUE_CHAR_PT));
+        let label_stop = line_width(labels.trim_end_matches("Next"), LABEL_CHAR_PT);
+        let value_stop = line_width(values.trim_end_matches("12m"), VALUE_CHAR_PT);
+        assert_eq!(label_stop, value_stop);
+        assert!(label_stop > target);
+    }
+
+    #[test]
+    fn trailing_alert_lines_are_kept() {
+        let text = append_columns(
+            "CPU\n12%\nMon ✕",
+            &[("UTC".to_string(), "14:00".to_string())],
+        );
+        assert_eq!(text, "CPU\tUTC\n12%\t14:00\nMon ✕");
+    }
+
+    #[test]
+    fn clock_and_countdown_formatting() {
+        let now = DateTime::parse_from_rfc3339("2026-03-01T13:05:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let ny = FixedOffset::west_opt(5 * 3600).unwrap();
+        assert_eq!(format_clock(now, ny, false), "08:05");
+        assert_eq!(
+            format_clock(now, FixedOffset::east_opt(0).unwrap(), true),
+            "1:05"
+        );
+        assert_eq!(format_countdown(None), "—");
+        assert_eq!(format_countdown(Some(30)), "<1m");
+        assert_eq!(format_countdown(Some(12 * 60 + 59)), "12m");
+        assert_eq!(format_countdown(Some(3600 + 5 * 60)), "1h05");
+    }
+}
@@ -0,0 +1,108 @@
+//! Separate status items (`menuBarItems`)
+//!
+//! By default mac-stats shows one status item with the whole layout (grid, compact or
+//! `menuBarTemplate`). `menuBarItems` splits metrics across several items, listed left to
+//! right, e.g. `["cpu", "network", "battery"]`: each is its own `NSStatusItem` that macOS spaces
+//! like any other app's, and clicking any of them opens the CPU window. An entry can also be
+//! `{"item": "network", "enabled": false}` to keep its place in the list while hiding it.
+
+use std::collections::BTreeSet;
+
+/// The full layout, with `menuBarExtras` and the alert / Ollama cues
+pub const MAIN: &str = "main";
+pub const CPU: &str = "cpu";
+pub const NETWORK: &str = "network";
+pub const BATTERY: &str = "battery";
+/// Items `menuBarItems` can list
+pub const ITEMS: &[&str] = &[MAIN, CPU, NETWORK, BATTERY];
+
+/// Label over value for each split item, filled like a `menuBarTemplate`
+const TEMPLATES: &[(&str, &str)] = &[
+    (CPU, "CPU\n{cpu}%"),
+    (NETWORK, "NET\n↓{net_rx} ↑{net_tx}"),
+    (BATTERY, "BAT\n{battery}%"),
+];
+
+/// Enabled items in display order (left to right) from the `menuBarItems` value. Unknown and
+/// repeated names are skipped; without any enabled item there is just [`MAIN`].
+pub fn parse(value: Option<&serde_json::Value>) -> Vec<String> {
+    let mut seen = BTreeSet::new();
+    let mut items = Vec::new();
+    for entry in value.and_then(|v| v.as_array()).into_iter().flatten() {
+        let (name, enabled) = match entry {
+            serde_json::Value::String(s) => (s.as_str(), true),
+            serde_json::Value::Object(o) => (
+                o.get("item").and_then(|v| v.as_str()).unwrap_or_default(),
+                o.get("enabled").and_then(|v| v.as_bool()).unwrap_or(true),
+            ),
+            _ => continue,
+        };
+        let name = name.trim().to_ascii_lowercase();
+        if !ITEMS.contains(&name.as_str()) {
+            tracing::warn!(
+                "menuBarItems: unknown item '{}' (expected {})",
+                name,
+                ITEMS.join(", ")
+            );
+            continue;
+        }
+        if seen.insert(name.clone()) && enabled {
+            items.push(name);
+        }
+    }
+    if items.is_empty() {
+        items.push(MAIN.to_string());
+    }
+    items
+}
+
+/// Status item text for a split item; `value` fills the placeholders as for `menuBarTemplate`
+pub fn item_text(id: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    TEMPLATES
+        .iter()
+        .find(|(item, _)| *item == id)
+        .map(|(_, template)| super::status_template::fill(template, &value))
+        .unwrap_or_default()
+}
+
+/// Item that carries the CPU sparkline (`menuBarSparkline`): the CPU item when shown, else the
+/// main one
+pub fn sparkline_item<'a>(ids: impl IntoIterator<Item = &'a String>) -> Option<&'static str> {
+    let ids: Vec<&String> = ids.into_iter().collect();
+    [CPU, MAIN]
+        .into_iter()
+        .find(|item| ids.iter().any(|id| id.as_str() == *item))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_order_and_enabled_flags() {
+        let value = serde_json::json!([
+            "Network",
+            {"item": "cpu"},
+            {"item": "battery", "enabled": false},
+            "network",
+            "gpu"
+        ]);
+        assert_eq!(parse(Some(&value)), vec!["network", "cpu"]);
+        assert_eq!(parse(None), vec![MAIN]);
+        assert_eq!(
+            parse(Some(
+                &serde_json::json!([{"item": "cpu", "enabled": false}])
+            )),
+            vec![MAIN]
+        );
+
+        let value = |name: &str| (name == "cpu").then(|| "12".to_string());
+        assert_eq!(item_text(CPU, value), "CPU\n12%");
+        assert_eq!(item_text(BATTERY, value), "BAT\n--%");
+        assert_eq!(item_text(MAIN, value), "");
+
+        let ids = vec![MAIN.to_string(), NETWORK.to_string()];
+        assert_eq!(sparkline_item(&ids), Some(MAIN));
+        assert_eq!(sparkline_item(&[NETWORK.to_string()]), None);
+    }
+}
@@ -0,0 +1,297 @@
+//! Drop-down menu of the status item
+//!
+//! A left click opens a menu built fresh each time: live metric rows, a "Top Processes"
+//! submenu, then Open CPU Window, Open Network Window, Open Battery Window, Show/Hide Widget
+//! (with its click-through switch), Pause/Resume Updates, a Keep Awake submenu (see
+//! `keep_awake`), a Profile submenu (see `config::profiles`), Restore Menu Bar Items while one
+//! was removed (see `status_removal`), About and Quit. A right click still shows the About
+//! window directly (both remappable, see `click_actions`). Menu actions are methods of the
+//! status item's click handler (see `status_bar::click_handler_class`). Titles come from the
+//! `i18n` catalogs.
+
+use std::cell::Cell;
+
+use objc2::rc::Retained;
+use objc2::runtime::{AnyObject, Sel};
+use objc2::{sel, MainThreadMarker};
+use objc2_app_kit::{NSControlStateValueOff, NSControlStateValueOn, NSMenu, NSMenuItem};
+use objc2_foundation::NSString;
+
+use crate::i18n::Lang;
+
+/// Processes listed in the "Top Processes" submenu
+pub const TOP_PROCESSES: usize = 5;
+
+/// Toggle states shown in the menu
+#[derive(Debug, Clone, Default)]
+pub struct MenuState {
+    pub paused: bool,
+    pub widget_visible: bool,
+    pub widget_click_through: bool,
+    pub keep_awake: crate::keep_awake::KeepAwakeStatus,
+    /// Config profile names (`config::profiles`)
+    pub profiles: Vec<String>,
+    pub active_profile: Option<String>,
+    /// A status item was ⌘-dragged out of the menu bar (`status_removal`)
+    pub items_removed: bool,
+    pub lang: Lang,
+}
+
+/// Metric rows: label key and a `menuBarTemplate`-style value
+const METRIC_ROWS: &[(&str, &str)] = &[
+    ("menu.cpu", "{cpu}%"),
+    ("menu.gpu", "{gpu}%"),
+    ("menu.ram", "{ram}%"),
+    ("menu.ssd", "{disk}%"),
+    ("menu.temperature", "{temp}{temp_unit}"),
+    ("menu.fan", "{fan} rpm"),
+    ("menu.battery", "{battery}%"),
+    ("menu.network", "↓{net_rx}{net_unit}  ↑{net_tx}{net_unit}"),
+];
+
+/// `Label: value` rows for the menu; rows without any current reading (no battery, fan not
+/// read yet, ...) are left out (`*_unit` placeholders are not readings)
+pub fn metric_rows(lang: Lang, value: impl Fn(&str) -> Option<String>) -> Vec<String> {
+    METRIC_ROWS
+        .iter()
+        .filter_map(|(label, template)| {
+            let present = Cell::new(false);
+            let text = super::status_template::fill(template, &|name: &str| {
+                let v = value(name);
+                present.set(present.get() || (v.is_some() && !name.ends_with("_unit")));
+                v
+            });
+            present
+                .get()
+                .then(|| format!("{}: {}", lang.text(label), text))
+        })
+        .collect()
+}
+
+fn menu_item(
+    mtm: MainThreadMarker,
+    title: &str,
+    action: Option<Sel>,
+    key: &str,
+    target: &AnyObject,
+) -> Retained<NSMenuItem> {
+    let item = unsafe {
+        NSMenuItem::initWithTitle_action_keyEquivalent(
+            mtm.alloc(),
+            &NSString::from_str(title),
+            action,
+            &NSString::from_str(key),
+        )
+    };
+    if action.is_some() {
+        unsafe { item.setTarget(Some(target)) };
+    }
+    item
+}
+
+/// Build the menu; rows without an action show greyed out (NSMenu auto-enables by action)
+pub fn build_menu(
+    mtm: MainThreadMarker,
+    handler: &AnyObject,
+    rows: &[String],
+    processes: &[String],
+    state: MenuState,
+) -> Retained<NSMenu> {
+    let t = |key| state.lang.text(key);
+    let menu = NSMenu::new(mtm);
+    for row in rows {
+        menu.addItem(&menu_item(mtm, row, None, "", handler));
+    }
+    menu.addItem(&NSMenuItem::separatorItem(mtm));
+
+    let top = NSMenu::initWithTitle(mtm.alloc(), &NSString::from_str(t("menu.top_processes")));
+    if processes.is_empty() {
+        top.addItem(&menu_item(
+            mtm,
+            t("menu.no_process_data"),
+            None,
+            "",
+            handler,
+        ));
+    }
+    for process in processes {
+        top.addItem(&menu_item(mtm, process, None, "", handler));
+    }
+    let top_item = menu_item(mtm, t("menu.top_processes"), None, "", handler);
+    top_item.setSubmenu(Some(&top));
+    menu.addItem(&top_item);
+    menu.addItem(&NSMenuItem::separatorItem(mtm));
+
+    let pause_title = if state.paused {
+        t("menu.resume_updates")
+    } else {
+        t("menu.pause_updates")
+    };
+    let widget_title = if state.widget_visible {
+        t("menu.hide_widget")
+    } else {
+        t("menu.show_widget")
+    };
+    for (title, action, key) in [
+        (t("menu.open_cpu_window"), sel!(openCpuWindow:), "o"),
+        (t("menu.open_network_window"), sel!(openNetworkWindow:), "n"),
+        (t("menu.open_battery_window"), sel!(openBatteryWindow:), "b"),
+        (t("menu.open_dashboard"), sel!(openDashboardWindow:), "d"),
+        (t("menu.open_log_viewer"), sel!(openLogWindow:), "l"),
+        (widget_title, sel!(toggleWidget:), "w"),
+    ] {
+        menu.addItem(&menu_item(mtm, title, Some(action), key, handler));
+    }
+    let click_through = menu_item(
+        mtm,
+        t("menu.widget_click_through"),
+        Some(sel!(toggleWidgetClickThrough:)),
+        "",
+        handler,
+    );
+    click_through.setState(if state.widget_click_through {
+        NSControlStateValueOn
+    } else {
+        NSControlStateValueOff
+    });
+    menu.addItem(&click_through);
+    menu.addItem(&menu_item(
+        mtm,
+        pause_title,
+        Some(sel!(togglePauseUpdates:)),
+        "p",
+        handler,
+    ));
+
+    let awake = NSMenu::initWithTitle(mtm.alloc(), &NSString::from_str(t("menu.keep_awake")));
+    for (title, action) in [
+        (t("menu.for_30_minutes"), sel!(keepAwake30Minutes:)),
+        (t("menu.for_1_hour"), sel!(keepAwake1Hour:)),
+        (t("menu.for_2_hours"), sel!(keepAwake2Hours:)),
+        (t("menu.until_turned_off"), sel!(keepAwakeIndefinitely:)),
+    ] {
+        awake.addItem(&menu_item(mtm, title, Some(action), "", handler));
+    }
+    if state.keep_awake.active {
+        awake.addItem(&NSMenuItem::separatorItem(mtm));
+        awake.addItem(&menu_item(
+            mtm,
+            t("menu.turn_off"),
+            Some(sel!(keepAwakeOff:)),
+            "",
+            handler,
+        ));
+    }
+    let awake_item = menu_item(
+        mtm,
+        &state.keep_awake.menu_title(state.lang),
+        None,
+        "",
+        handler,
+    );
+    awake_item.setSubmenu(Some(&awake));
+    awake_item.setState(if state.keep_awake.active {
+        NSControlStateValueOn
+    } else {
+        NSControlStateValueOff
+    });
+    menu.addItem(&awake_item);
+
+    // Items are titled with the profile name; `useProfile:` reads it back from the sender
+    let profiles = NSMenu::initWithTitle(mtm.alloc(), &NSString::from_str(t("menu.profile")));
+    for name in &state.profiles {
+        let item = menu_item(mtm, name, Some(sel!(useProfile:)), "", handler);
+        if state.active_profile.as_ref() == Some(name) {
+            item.setState(NSControlStateValueOn);
+        }
+        profiles.addItem(&item);
+    }
+    profiles.addItem(&NSMenuItem::separatorItem(mtm));
+    let none = menu_item(
+        mtm,
+        t("menu.profile_none"),
+        Some(sel!(profileOff:)),
+        "",
+        handler,
+    );
+    if state.active_profile.is_none() {
+        none.setState(NSControlStateValueOn);
+    }
+    profiles.addItem(&none);
+    let profile_title = match &state.active_profile {
+        Some(name) => state.lang.format("menu.profile_active", &[("name", name)]),
+        None => t("menu.profile").to_string(),
+    };
+    let profile_item = menu_item(mtm, &profile_title, None, "", handler);
+    profile_item.setSubmenu(Some(&profiles));
+    menu.addItem(&profile_item);
+
+    let copy = NSMenu::initWithTitle(mtm.alloc(), &NSString::from_str(t("menu.copy_stats")));
+    for (title, action, key) in [
+        (t("menu.as_text"), sel!(copyStatsText:), "c"),
+        (t("menu.as_markdown"), sel!(copyStatsMarkdown:), ""),
+        (t("menu.as_json"), sel!(copyStatsJson:), ""),
+    ] {
+        copy.addItem(&menu_item(mtm, title, Some(action), key, handler));
+    }
+    let copy_item = menu_item(mtm, t("menu.copy_stats"), None, "", handler);
+    copy_item.setSubmenu(Some(&copy));
+    menu.addItem(&copy_item);
+    menu.addItem(&menu_item(
+        mtm,
+        t("menu.system_report"),
+        Some(sel!(generateSystemReport:)),
+        "",
+        handler,
+    ));
+    menu.addItem(&NSMenuItem::separatorItem(mtm));
+    if state.items_removed {
+        menu.addItem(&menu_item(
+            mtm,
+            t("menu.restore_menu_bar_items"),
+            Some(sel!(restoreMenuBarItems:)),
+            "",
+            handler,
+        ));
+    }
+    menu.addItem(&menu_item(
+        mtm,
+        t("menu.about"),
+        Some(sel!(showAbout:)),
+        "",
+        handler,
+    ));
+    menu.addItem(&menu_item(
+        mtm,
+        t("menu.quit"),
+        Some(sel!(quitApp:)),
+        "q",
+        handler,
+    ));
+    menu
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skips_rows_without_readings() {
+        let value = |name: &str| match name {
+            "cpu" => Some("12".to_string()),
+            "net_rx" => Some("1.2M".to_string()),
+            "temp_unit" => Some("°F".to_string()),
+            "net_unit" => Some("b/s".to_string()),
+            _ => None,
+        };
+        assert_eq!(
+            metric_rows(Lang::En, value),
+            vec!["CPU: 12%", "Network: ↓1.2Mb/s  ↑--b/s"]
+        );
+        assert_eq!(
+            metric_rows(Lang::De, value)[1],
+            "Netzwerk: ↓1.2Mb/s  ↑--b/s"
+        );
+        assert!(metric_rows(Lang::En, |_| None).is_empty());
+    }
+}
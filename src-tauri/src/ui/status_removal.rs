@@ -0,0 +1,150 @@
+//! Status item position and removal (`menuBarItemRemovable`)
+//!
+//! Every status item has an autosave name (`mac-stats.<id>`), so macOS keeps the place it was
+//! ⌘-dragged to across launches. With `menuBarItemRemovable` (default on) ⌘-dragging an item out
+//! of the menu bar removes it, which macOS also remembers (`NSStatusItem Visible <name>` in the
+//! app's defaults). mac-stats keeps running and says so in a notification; the items come back
+//! when mac-stats is opened again (the reopen event), from **Restore Menu Bar Items** in the
+//! status menu of an item that is still there, or with `mac_stats menu-bar restore`.
+
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap};
+
+use objc2::msg_send;
+use objc2::MainThreadMarker;
+use objc2_app_kit::NSStatusItem;
+use objc2_foundation::NSString;
+
+use crate::alerts::channels::{AlertChannel, NotificationChannel};
+use crate::alerts::AlertContext;
+use crate::config::Config;
+use crate::state::STATUS_ITEMS;
+
+/// Bundle identifier (`tauri.conf.json`), whose defaults hold the item positions
+const BUNDLE_ID: &str = "com.raro42.mac-stats";
+/// `NSStatusItemBehaviorRemovalAllowed`
+const BEHAVIOR_REMOVAL_ALLOWED: usize = 1 << 1;
+
+thread_local! {
+    /// Items removed as of the last timer tick
+    static REMOVED: RefCell<BTreeSet<String>> = const { RefCell::new(BTreeSet::new()) };
+}
+
+fn autosave_name(id: &str) -> String {
+    format!("mac-stats.{}", id)
+}
+
+/// Give a new status item its autosave name and removal behavior. Set before the first title,
+/// so macOS restores its saved position and visibility.
+pub fn configure(item: &NSStatusItem, id: &str) {
+    let name = NSString::from_str(&autosave_name(id));
+    let removable = Config::menu_bar_item_removable();
+    unsafe {
+        let _: () = msg_send![item, setAutosaveName: &*name];
+        let behavior: usize = if removable {
+            BEHAVIOR_REMOVAL_ALLOWED
+        } else {
+            0
+        };
+        let _: () = msg_send![item, setBehavior: behavior];
+        // Removed while removal was allowed: not removable now means shown
+        if !removable {
+            let _: () = msg_send![item, setVisible: true];
+        }
+    }
+}
+
+/// Whether the user has not removed `item` from the menu bar
+pub fn is_visible(item: &NSStatusItem) -> bool {
+    unsafe { msg_send![item, isVisible] }
+}
+
+/// Ids of the removed status items
+pub fn removed(_mtm: MainThreadMarker) -> Vec<String> {
+    STATUS_ITEMS.with(|cell| {
+        cell.borrow()
+            .iter()
+            .filter(|(_, item)| !is_visible(item))
+            .map(|(id, _)| id.clone())
+            .collect()
+    })
+}
+
+/// Call from the status bar timer: notifies once when an item is removed
+pub fn watch(mtm: MainThreadMarker) {
+    let now: BTreeSet<String> = removed(mtm).into_iter().collect();
+    let newly_removed = REMOVED.with(|cell| {
+        let mut last = cell.borrow_mut();
+        let newly = now.difference(&last).cloned().collect::<Vec<_>>();
+        *last = now;
+        newly
+    });
+    if newly_removed.is_empty() {
+        return;
+    }
+    tracing::info!(
+        "Status item(s) removed from the menu bar: {}",
+        newly_removed.join(", ")
+    );
+    let text = crate::i18n::Lang::current().text("status.removed");
+    std::thread::spawn(move || notify(text));
+}
+
+/// Show every removed status item again; returns how many there were
+pub fn restore(mtm: MainThreadMarker) -> usize {
+    let ids = removed(mtm);
+    STATUS_ITEMS.with(|cell| {
+        for id in &ids {
+            if let Some(item) = cell.borrow().get(id) {
+                let _: () = unsafe { msg_send![&**item, setVisible: true] };
+            }
+        }
+    });
+    if !ids.is_empty() {
+        tracing::info!("Restored status item(s): {}", ids.join(", "));
+    }
+    ids.len()
+}
+
+fn notify(message: &str) {
+    let context = AlertContext {
+        monitor_id: None,
+        monitor_status: None,
+        system_metrics: None,
+        cpu_details: None,
+        custom_data: HashMap::new(),
+    };
+    if let Err(e) = NotificationChannel.send(message, &context) {
+        tracing::debug!("Status item removal notification failed: {}", e);
+    }
+}
+
+/// `mac_stats menu-bar restore`: forget the removals, then open mac-stats, which starts it with
+/// every item or, when it is running, restores them through the reopen event
+pub fn run_restore_cli() -> i32 {
+    for id in super::status_items::ITEMS {
+        let key = format!("NSStatusItem Visible {}", autosave_name(id));
+        // Fails when the item was never removed; nothing to forget then
+        let _ = std::process::Command::new("/usr/bin/defaults")
+            .args(["delete", BUNDLE_ID, &key])
+            .stderr(std::process::Stdio::null())
+            .status();
+    }
+    match std::process::Command::new("/usr/bin/open")
+        .args(["-b", BUNDLE_ID])
+        .status()
+    {
+        Ok(status) if status.success() => {
+            println!("Menu bar items restored.");
+            0
+        }
+        Ok(status) => {
+            eprintln!("open -b {} exited with {}", BUNDLE_ID, status);
+            1
+        }
+        Err(e) => {
+            eprintln!("open: {}", e);
+            1
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! CPU sparkline in the status item (`menuBarSparkline`)
+//!
+//! Keeps the last [`SAMPLES`] CPU readings (one per menu bar update) and draws them as a small
+//! filled line graph into an `NSImage` left of the text, like the graph mode of other menu bar
+//! monitors. The image is a template image, so macOS tints it for light and dark menu bars.
+
+use std::collections::VecDeque;
+
+use objc2::msg_send;
+use objc2::rc::Retained;
+use objc2::runtime::{AnyClass, AnyObject};
+use objc2_foundation::{NSPoint, NSSize};
+
+/// Samples shown (one per second)
+pub const SAMPLES: usize = 60;
+/// Image size in points; the menu bar is 22pt tall
+pub const WIDTH_PT: f64 = 40.0;
+pub const HEIGHT_PT: f64 = 16.0;
+/// Alpha of the area under the line (the line itself is opaque)
+const FILL_ALPHA: f64 = 0.35;
+
+/// Rolling CPU history for the sparkline
+#[derive(Debug, Default)]
+pub struct Sparkline {
+    samples: VecDeque<f32>,
+}
+
+impl Sparkline {
+    pub const fn new() -> Self {
+        Self {
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Add a CPU reading (percent); the oldest is dropped past [`SAMPLES`]
+    pub fn push(&mut self, cpu: f32) {
+        if self.samples.len() == SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(cpu);
+    }
+
+    pub fn samples(&self) -> Vec<f32> {
+        self.samples.iter().copied().collect()
+    }
+}
+
+/// Line points for `samples` in a `width` × `height` box (origin bottom left), newest at the
+/// right edge; 0–100% maps to 1pt above the bottom up to 1pt below the top
+pub fn points(samples: &[f32], width: f64, height: f64) -> Vec<(f64, f64)> {
+    let step = width / (SAMPLES - 1) as f64;
+    let last = samples.len().saturating_sub(1);
+    samples
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            let x = width - (last - i) as f64 * step;
+            let y = 1.0 + (v.clamp(0.0, 100.0) as f64 / 100.0) * (height - 2.0);
+            (x, y)
+        })
+        .collect()
+}
+
+/// Template `NSImage` of the sparkline; `None` until there are two samples
+pub fn draw_image(samples: &[f32]) -> Option<Retained<AnyObject>> {
+    let points = points(samples, WIDTH_PT, HEIGHT_PT);
+    if points.len() < 2 {
+        return None;
+    }
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let image_class = AnyClass::get(c"NSImage")?;
+    let path_class = AnyClass::get(c"NSBezierPath")?;
+    let color_class = AnyClass::get(c"NSColor")?;
+    let point = |(x, y): (f64, f64)| NSPoint::new(x, y);
+    unsafe {
+        let image: *mut AnyObject = msg_send![image_class, alloc];
+        let image: *mut AnyObject =
+            msg_send![image, initWithSize: NSSize::new(WIDTH_PT, HEIGHT_PT)];
+        let image = Retained::from_raw(image)?;
+        let _: () = msg_send![&*image, lockFocus];
+
+        let black: *mut AnyObject = msg_send![color_class, blackColor];
+        let fill_color: *mut AnyObject = msg_send![black, colorWithAlphaComponent: FILL_ALPHA];
+        let area: *mut AnyObject = msg_send![path_class, bezierPath];
+        let _: () = msg_send![area, moveToPoint: point((first.0, 0.0))];
+        for p in &points {
+            let _: () = msg_send![area, lineToPoint: point(*p)];
+        }
+        let _: () = msg_send![area, lineToPoint: point((last.0, 0.0))];
+        let _: () = msg_send![area, closePath];
+        let _: () = msg_send![fill_color, set];
+        let _: () = msg_send![area, fill];
+
+        let line: *mut AnyObject = msg_send![path_class, bezierPath];
+        let _: () = msg_send![line, setLineWidth: 1.0f64];
+        let _: () = msg_send![line, moveToPoint: point(first)];
+        for p in &points[1..] {
+            let _: () = msg_send![line, lineToPoint: point(*p)];
+        }
+        let _: () = msg_send![black, set];
+        let _: () = msg_send![line, stroke];
+
+        let _: () = msg_send![&*image, unlockFocus];
+        let _: () = msg_send![&*image, setTemplate: true];
+        Some(image)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_last_samples_right_aligned() {
+        let mut spark = Sparkline::new();
+        for i in 0..SAMPLES + 5 {
+            spark.push(i as f32);
+        }
+        let samples = spark.samples();
+        assert_eq!(samples.len(), SAMPLES);
+        assert_eq!(samples[0], 5.0);
+
+        let pts = points(&[0.0, 50.0, 150.0], 59.0, 12.0);
+        assert_eq!(pts, vec![(57.0, 1.0), (58.0, 6.0), (59.0, 11.0)]);
+        let full = points(&spark.samples(), WIDTH_PT, HEIGHT_PT);
+        assert!(full[0].0.abs() < 1e-9);
+        assert_eq!(full[SAMPLES - 1].0, WIDTH_PT);
+    }
+}
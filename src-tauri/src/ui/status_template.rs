@@ -0,0 +1,150 @@
+//! Config-driven menu bar layout (`menuBarTemplate`)
+//!
+//! A template such as `"{cpu}% {temp}°C | {ram}%"` replaces the built-in CPU/GPU/RAM/SSD (or
+//! compact CPU + °C) layout. Placeholders are filled with bare numbers in the user's locale, so
+//! the template decides units and separators. By default it renders as one line; with
+//! `menuBarTemplateStyle: "columns"` each `|`-separated `Label: value` segment becomes a column
+//! with the small label over the value, like the built-in grid. Metric plugin values are
+//! available as `{<plugin>.<metric>}`.
+//!
+//! A segment may end in `#N` (`"CPU: {cpu}% #1 | Temp: {temp}° #2 | {ram}%"`): its rank when
+//! the menu bar runs out of room, 1 being kept longest (see `status_compaction`).
+
+/// Placeholders a template can use
+pub const PLACEHOLDERS: &[&str] = &[
+    "cpu",
+    "gpu",
+    "ram",
+    "disk",
+    "temp",
+    "temp_unit",
+    "fan",
+    "battery",
+    "net_rx",
+    "net_tx",
+    "net_unit",
+];
+/// Whether `name` is a placeholder: a built-in one or a metric plugin value (`<plugin>.<metric>`)
+pub fn is_placeholder(name: &str) -> bool {
+    PLACEHOLDERS.contains(&name) || name.contains('.')
+}
+
+/// Shown for a known placeholder without a current reading (e.g. `{temp}` before the first SMC
+/// read, `{battery}` on a desktop)
+pub const MISSING: &str = "--";
+
+/// Fill `{name}` placeholders from `value`. Unknown names are left as written.
+pub fn fill(template: &str, value: &impl Fn(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        match after.find('}') {
+            Some(end) if is_placeholder(after[..end].trim()) => {
+                let name = after[..end].trim();
+                out.push_str(&value(name).unwrap_or_else(|| MISSING.to_string()));
+                rest = &after[end + 1..];
+            }
+            _ => {
+                out.push('{');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// A `|` segment without its trailing `#N` rank, and the rank
+pub fn split_priority(segment: &str) -> (&str, Option<u8>) {
+    if let Some((rest, rank)) = segment.trim_end().rsplit_once('#') {
+        if (1..=2).contains(&rank.len()) && rank.bytes().all(|b| b.is_ascii_digit()) {
+            return (rest.trim_end(), rank.parse().ok());
+        }
+    }
+    (segment, None)
+}
+
+/// Rank of each `|` segment of `template`
+pub fn priorities(template: &str) -> Vec<Option<u8>> {
+    template.split('|').map(|s| split_priority(s).1).collect()
+}
+
+/// Status item text for `template`: one line, or a label line over a value line when `columns`
+pub fn render(template: &str, columns: bool, value: impl Fn(&str) -> Option<String>) -> String {
+    if !columns {
+        let line = if priorities(template).iter().any(Option::is_some) {
+            template
+                .split('|')
+                .map(|s| split_priority(s).0.trim())
+                .collect::<Vec<_>>()
+                .join(" | ")
+        } else {
+            template.to_string()
+        };
+        return fill(&line, &value).trim().to_string();
+    }
+    let (labels, values): (Vec<String>, Vec<String>) = template
+        .split('|')
+        .map(|segment| split_priority(segment).0)
+        .map(|segment| match segment.split_once(':') {
+            Some((label, v)) => (label.trim().to_string(), fill(v.trim(), &value)),
+            None => (String::new(), fill(segment.trim(), &value)),
+        })
+        .unzip();
+    format!("{}\n{}", labels.join("\t"), values.join("\t"))
+}
+
+/// A per-second rate as a short number with a K/M/G suffix (`{net_rx}`, `{net_tx}`); the template
+/// adds the unit, e.g. `{net_rx}{net_unit}`
+pub fn short_rate(bytes_per_sec: f64, number: impl Fn(f64, usize) -> String) -> String {
+    const STEPS: [(f64, &str); 3] = [(1e9, "G"), (1e6, "M"), (1e3, "K")];
+    for (scale, suffix) in STEPS {
+        if bytes_per_sec >= scale {
+            let v = bytes_per_sec / scale;
+            return format!("{}{}", number(v, if v < 10.0 { 1 } else { 0 }), suffix);
+        }
+    }
+    number(bytes_per_sec.max(0.0), 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(name: &str) -> Option<String> {
+        match name {
+            "cpu" => Some("12".to_string()),
+            "ram" => Some("61".to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn renders_line_and_column_layouts() {
+        assert_eq!(
+            render("{cpu}% {temp}°C | {ram}%", false, sample),
+            "12% --°C | 61%"
+        );
+        assert_eq!(render("{ cpu }% {nope} {", false, sample), "12% {nope} {");
+        assert_eq!(render("Q {queue.depth}", false, sample), "Q --");
+        assert_eq!(
+            render("CPU: {cpu}% | Temp: {temp}° | {ram}%", true, sample),
+            "CPU\tTemp\t\n12%\t--°\t61%"
+        );
+        assert_eq!(
+            render("{cpu}% #1 | {temp}°C #12 | {ram}% #x", false, sample),
+            "12% | --°C | 61% #x"
+        );
+        assert_eq!(
+            priorities("CPU: {cpu}% #1 | Temp: {temp}° | {ram}% #2"),
+            [Some(1), None, Some(2)]
+        );
+
+        let plain = |v: f64, d: usize| format!("{:.*}", d, v);
+        assert_eq!(short_rate(512.0, plain), "512");
+        assert_eq!(short_rate(1_250_000.0, plain), "1.2M");
+        assert_eq!(short_rate(42_000.0, plain), "42K");
+    }
+}
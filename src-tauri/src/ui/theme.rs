@@ -0,0 +1,378 @@
+//! Appearance-aware colors (`theme`)
+//!
+//! Tracks the macOS light/dark appearance by observing `NSApp.effectiveAppearance` (KVO) and
+//! resolves the configured colors for it: `theme.accentColor` (default: the system accent
+//! color) and `theme.metricColors` for the menu bar columns, each either one color or
+//! `{"light": ..., "dark": ...}`. The menu bar title picks them up on its next update; windows
+//! get them as CSS variables from `get_theme` and the `theme-changed` event, sent whenever
+//! the appearance flips.
+
+use std::collections::BTreeMap;
+use std::ffi::c_void;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use objc2::declare::ClassBuilder;
+use objc2::rc::Retained;
+use objc2::runtime::{AnyClass, AnyObject, NSObject, Sel};
+use objc2::{msg_send, sel, ClassType, MainThreadMarker};
+use objc2_app_kit::NSApplication;
+use objc2_foundation::NSString;
+use serde::Serialize;
+use tauri::Emitter;
+
+use crate::config::Config;
+use crate::state::APP_HANDLE;
+
+/// Event sent to all windows when the appearance changes (payload: [`Theme`])
+pub const EVENT: &str = "theme-changed";
+/// Metrics `theme.metricColors` can color (the menu bar columns)
+pub const METRICS: &[&str] = &["cpu", "gpu", "ram", "disk", "temperature"];
+
+static DARK: AtomicBool = AtomicBool::new(false);
+
+/// sRGB color, components 0–1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rgba {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
+    pub a: f64,
+}
+
+impl Rgba {
+    /// `#rgb`, `#rrggbb` or `#rrggbbaa`
+    pub fn parse(s: &str) -> Option<Self> {
+        let hex = s.trim().strip_prefix('#')?;
+        if !hex.is_ascii() {
+            return None;
+        }
+        let expanded: String = match hex.len() {
+            3 => hex.chars().flat_map(|c| [c, c]).collect(),
+            6 | 8 => hex.to_string(),
+            _ => return None,
+        };
+        let channel = |i: usize| {
+            u8::from_str_radix(&expanded[i..i + 2], 16)
+                .ok()
+                .map(|v| v as f64 / 255.0)
+        };
+        Some(Self {
+            r: channel(0)?,
+            g: channel(2)?,
+            b: channel(4)?,
+            a: if expanded.len() == 8 {
+                channel(6)?
+            } else {
+                1.0
+            },
+        })
+    }
+
+    /// CSS value: `#rrggbb`, or `rgba(...)` when translucent
+    pub fn to_css(self) -> String {
+        let byte = |v: f64| (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        if self.a >= 1.0 {
+            format!(
+                "#{:02x}{:02x}{:02x}",
+                byte(self.r),
+                byte(self.g),
+                byte(self.b)
+            )
+        } else {
+            format!(
+                "rgba({}, {}, {}, {})",
+                byte(self.r),
+                byte(self.g),
+                byte(self.b),
+                (self.a * 100.0).round() / 100.0
+            )
+        }
+    }
+}
+
+/// A configured color for light and dark appearance
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorSetting {
+    pub light: Rgba,
+    pub dark: Rgba,
+}
+
+impl ColorSetting {
+    /// `"#hex"` or `{"light": "#hex", "dark": "#hex"}` (either may be left out)
+    fn from_json(value: &serde_json::Value) -> Option<Self> {
+        let color =
+            |v: Option<&serde_json::Value>| v.and_then(|v| v.as_str()).and_then(Rgba::parse);
+        match value {
+            serde_json::Value::String(s) => Rgba::parse(s).map(|c| Self { light: c, dark: c }),
+            serde_json::Value::Object(o) => {
+                let (light, dark) = (color(o.get("light")), color(o.get("dark")));
+                Some(Self {
+                    light: light.or(dark)?,
+                    dark: dark.or(light)?,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    pub fn pick(&self, dark: bool) -> Rgba {
+        if dark {
+            self.dark
+        } else {
+            self.light
+        }
+    }
+}
+
+/// The `theme` config object
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ThemeSettings {
+    /// `None`: follow the system accent color
+    pub accent: Option<ColorSetting>,
+    pub metric_colors: BTreeMap<String, ColorSetting>,
+}
+
+impl ThemeSettings {
+    pub fn from_json(value: Option<&serde_json::Value>) -> Self {
+        let Some(obj) = value.and_then(|v| v.as_object()) else {
+            return Self::default();
+        };
+        let accent = obj
+            .get("accentColor")
+            .filter(|v| v.as_str() != Some("system"))
+            .and_then(|v| {
+                let setting = ColorSetting::from_json(v);
+                if setting.is_none() {
+                    tracing::warn!("theme.accentColor: expected \"#rrggbb\" or \"system\"");
+                }
+                setting
+            });
+        let mut metric_colors = BTreeMap::new();
+        for (metric, v) in obj
+            .get("metricColors")
+            .and_then(|v| v.as_object())
+            .into_iter()
+            .flatten()
+        {
+            let metric = metric.trim().to_ascii_lowercase();
+            match ColorSetting::from_json(v) {
+                Some(setting) if METRICS.contains(&metric.as_str()) => {
+                    metric_colors.insert(metric, setting);
+                }
+                Some(_) => tracing::warn!(
+                    "theme.metricColors: unknown metric '{}' (expected {})",
+                    metric,
+                    METRICS.join(", ")
+                ),
+                None => tracing::warn!("theme.metricColors.{}: expected \"#rrggbb\"", metric),
+            }
+        }
+        Self {
+            accent,
+            metric_colors,
+        }
+    }
+}
+
+/// Colors for one appearance, as sent to windows
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Theme {
+    /// `"light"` or `"dark"`
+    pub appearance: &'static str,
+    pub accent: String,
+    pub metric_colors: BTreeMap<String, String>,
+    /// CSS custom properties to set on `:root`
+    pub css: BTreeMap<String, String>,
+}
+
+/// Resolve `settings` for the appearance; `system_accent` fills in an unset accent color
+pub fn resolve(settings: &ThemeSettings, dark: bool, system_accent: Option<Rgba>) -> Theme {
+    let accent = settings
+        .accent
+        .map(|c| c.pick(dark))
+        .or(system_accent)
+        .unwrap_or(Rgba {
+            r: 0.0,
+            g: 122.0 / 255.0,
+            b: 1.0,
+            a: 1.0,
+        })
+        .to_css();
+    let metric_colors: BTreeMap<String, String> = settings
+        .metric_colors
+        .iter()
+        .map(|(m, c)| (m.clone(), c.pick(dark).to_css()))
+        .collect();
+    let appearance = if dark { "dark" } else { "light" };
+    let mut css = BTreeMap::new();
+    css.insert("--mac-stats-appearance".to_string(), appearance.to_string());
+    css.insert("--mac-stats-accent".to_string(), accent.clone());
+    // A configured accent also overrides the window theme's own `--accent`
+    if settings.accent.is_some() {
+        css.insert("--accent".to_string(), accent.clone());
+    }
+    for (metric, color) in &metric_colors {
+        css.insert(format!("--mac-stats-{}-color", metric), color.clone());
+    }
+    Theme {
+        appearance,
+        accent,
+        metric_colors,
+        css,
+    }
+}
+
+/// Whether the app currently has a dark appearance (as of the last KVO notification)
+pub fn is_dark() -> bool {
+    DARK.load(Ordering::Relaxed)
+}
+
+/// Menu bar colors by metric for the current appearance
+pub fn menu_bar_colors() -> Vec<(String, Rgba)> {
+    let dark = is_dark();
+    Config::theme_settings()
+        .metric_colors
+        .into_iter()
+        .map(|(m, c)| (m, c.pick(dark)))
+        .collect()
+}
+
+/// Theme for the current appearance and config
+pub fn current() -> Theme {
+    resolve(&Config::theme_settings(), is_dark(), system_accent())
+}
+
+/// System accent color in sRGB (`NSColor.controlAccentColor`)
+fn system_accent() -> Option<Rgba> {
+    let color_class = AnyClass::get(c"NSColor")?;
+    let space_class = AnyClass::get(c"NSColorSpace")?;
+    unsafe {
+        let accent: *mut AnyObject = msg_send![color_class, controlAccentColor];
+        let srgb: *mut AnyObject = msg_send![space_class, sRGBColorSpace];
+        let color: *mut AnyObject = msg_send![accent, colorUsingColorSpace: srgb];
+        let color = color.as_ref()?;
+        Some(Rgba {
+            r: msg_send![color, redComponent],
+            g: msg_send![color, greenComponent],
+            b: msg_send![color, blueComponent],
+            a: msg_send![color, alphaComponent],
+        })
+    }
+}
+
+fn read_appearance(mtm: MainThreadMarker) -> bool {
+    let app = NSApplication::sharedApplication(mtm);
+    unsafe {
+        let appearance: *mut AnyObject = msg_send![&*app, effectiveAppearance];
+        let Some(appearance) = appearance.as_ref() else {
+            return false;
+        };
+        let name: Option<Retained<NSString>> = msg_send![appearance, name];
+        name.is_some_and(|n| n.to_string().contains("Dark"))
+    }
+}
+
+fn appearance_changed(mtm: MainThreadMarker) {
+    let dark = read_appearance(mtm);
+    if DARK.swap(dark, Ordering::Relaxed) == dark {
+        return;
+    }
+    tracing::info!(
+        "Appearance changed to {}",
+        if dark { "dark" } else { "light" }
+    );
+    if let Some(app) = APP_HANDLE.get() {
+        if let Err(e) = app.emit(EVENT, current()) {
+            tracing::debug!("Theme event failed: {}", e);
+        }
+    }
+}
+
+fn observer_class() -> &'static AnyClass {
+    static REGISTER: OnceLock<&'static AnyClass> = OnceLock::new();
+    REGISTER.get_or_init(|| {
+        let mut builder = ClassBuilder::new(c"MacStatsAppearanceObserver", NSObject::class())
+            .expect("class already exists");
+
+        extern "C-unwind" fn observe(
+            _this: &AnyObject,
+            _cmd: Sel,
+            _key_path: *mut AnyObject,
+            _object: *mut AnyObject,
+            _change: *mut AnyObject,
+            _context: *mut c_void,
+        ) {
+            if let Some(mtm) = MainThreadMarker::new() {
+                appearance_changed(mtm);
+            }
+        }
+        unsafe {
+            builder.add_method(
+                sel!(observeValueForKeyPath:ofObject:change:context:),
+                observe as extern "C-unwind" fn(_, _, _, _, _, _),
+            );
+        }
+        builder.register()
+    })
+}
+
+/// Read the current appearance and observe `NSApp.effectiveAppearance` (main thread, once)
+pub fn start_observing(mtm: MainThreadMarker) {
+    DARK.store(read_appearance(mtm), Ordering::Relaxed);
+    let app = NSApplication::sharedApplication(mtm);
+    let key_path = NSString::from_str("effectiveAppearance");
+    unsafe {
+        let observer: *mut AnyObject = msg_send![observer_class(), new];
+        // NSKeyValueObservingOptionNew; the observer lives as long as the app
+        let _: () = msg_send![&*app, addObserver: observer, forKeyPath: &*key_path, options: 1usize, context: std::ptr::null_mut::<c_void>()];
+    }
+}
+
+/// Colors for the calling window (CSS variables in `css`; re-sent as `theme-changed`)
+#[tauri::command]
+pub fn get_theme() -> Theme {
+    current()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_colors_per_appearance() {
+        assert_eq!(
+            Rgba::parse("#f80").map(|c| c.to_css()),
+            Some("#ff8800".to_string())
+        );
+        assert_eq!(
+            Rgba::parse("#00000080").map(|c| c.to_css()),
+            Some("rgba(0, 0, 0, 0.5)".to_string())
+        );
+        assert!(Rgba::parse("red").is_none());
+        assert!(Rgba::parse("#12345").is_none());
+
+        let settings = ThemeSettings::from_json(Some(&serde_json::json!({
+            "accentColor": "#ff9f0a",
+            "metricColors": {
+                "CPU": {"light": "#0040dd", "dark": "#409cff"},
+                "ram": {"dark": "#30d158"},
+                "battery": "#ffffff"
+            }
+        })));
+        assert_eq!(settings.metric_colors.len(), 2);
+
+        let dark = resolve(&settings, true, None);
+        assert_eq!(dark.appearance, "dark");
+        assert_eq!(dark.metric_colors["cpu"], "#409cff");
+        assert_eq!(dark.css["--accent"], "#ff9f0a");
+        let light = resolve(&settings, false, None);
+        assert_eq!(light.css["--mac-stats-cpu-color"], "#0040dd");
+        assert_eq!(light.metric_colors["ram"], "#30d158");
+
+        let system = Rgba::parse("#bf5af2");
+        let default = resolve(&ThemeSettings::from_json(None), false, system);
+        assert_eq!(default.accent, "#bf5af2");
+        assert!(!default.css.contains_key("--accent"));
+    }
+}
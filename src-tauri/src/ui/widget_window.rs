@@ -0,0 +1,101 @@
+//! Widget HUD: a small always-on-top window with a few key numbers
+//!
+//! A lightweight alternative to the CPU window, toggled from the status menu ("Show Widget").
+//! It is borderless and translucent, shows CPU, GPU, RAM and network receive rate from the
+//! `system` / `network` metric push (`widget.html`), and can be dragged anywhere; its position
+//! is remembered in `widgetWindowFrame` (see `window_frame`). With `widgetClickThrough` the
+//! widget ignores the mouse, so clicks reach the windows below; switch it off from the menu
+//! again to move the widget.
+
+use tauri::{AppHandle, Manager, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+use crate::config::Config;
+
+/// Tauri window label
+pub const LABEL: &str = "widget";
+/// Default size in points
+pub const SIZE: (f64, f64) = (190.0, 92.0);
+
+/// The widget window if it exists and is shown
+fn visible_window(app_handle: &AppHandle) -> Option<WebviewWindow> {
+    app_handle
+        .get_webview_window(LABEL)
+        .filter(|w| w.is_visible().unwrap_or(false))
+}
+
+pub fn is_visible(app_handle: &AppHandle) -> bool {
+    visible_window(app_handle).is_some()
+}
+
+/// Show the widget, or hide it (remembering where it was) when it is shown
+pub fn toggle(app_handle: &AppHandle) {
+    if let Some(window) = visible_window(app_handle) {
+        super::window_frame::save(&window);
+        let _ = window.hide();
+    } else if let Some(window) = app_handle.get_webview_window(LABEL) {
+        let _ = window.show();
+    } else {
+        create(app_handle);
+    }
+}
+
+fn create(app_handle: &AppHandle) {
+    let frame = Config::widget_window_frame();
+    let mut builder =
+        WebviewWindowBuilder::new(app_handle, LABEL, WebviewUrl::App("widget.html".into()))
+            .title("mac-stats widget")
+            .inner_size(frame.width, frame.height)
+            .resizable(false)
+            .decorations(false)
+            .transparent(true)
+            .shadow(false)
+            .always_on_top(true)
+            .skip_taskbar(true)
+            .focused(false)
+            .visible(true);
+    if let Some((x, y)) = frame.visible_position(&super::window_frame::displays(app_handle)) {
+        builder = builder.position(x, y);
+    }
+    match builder.build() {
+        Ok(window) => {
+            set_click_through(&window, Config::widget_click_through());
+            let window_for_close = window.clone();
+            window.on_window_event(move |event| {
+                if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+                    api.prevent_close();
+                    super::window_frame::save(&window_for_close);
+                    let _ = window_for_close.hide();
+                }
+            });
+            tracing::debug!("Widget window created");
+        }
+        Err(e) => tracing::warn!("Could not create widget window: {}", e),
+    }
+}
+
+fn set_click_through(window: &WebviewWindow, enabled: bool) {
+    if let Err(e) = window.set_ignore_cursor_events(enabled) {
+        tracing::debug!("Widget click-through {}: {}", enabled, e);
+    }
+}
+
+/// Flip `widgetClickThrough` and apply it to the widget if it is open (a config write, so not
+/// in read-only guest mode)
+pub fn toggle_click_through(app_handle: &AppHandle) {
+    if let Err(e) = crate::guest_mode::ensure_allowed("Changing settings") {
+        tracing::info!("Widget click-through unchanged: {}", e);
+        return;
+    }
+    let enabled = !Config::widget_click_through();
+    if let Err(e) = Config::set_widget_click_through(enabled) {
+        tracing::warn!("Could not save widgetClickThrough: {}", e);
+        return;
+    }
+    if let Some(window) = app_handle.get_webview_window(LABEL) {
+        set_click_through(&window, enabled);
+    }
+    tracing::info!(
+        "Widget click-through {}",
+        if enabled { "on" } else { "off" }
+    );
+}
@@ -0,0 +1,173 @@
+//! Remembered window frames (`cpuWindowFrame`, `widgetWindowFrame`)
+//!
+//! Position, size and always-on-top state of the CPU window (and of the widget HUD) are saved to
+//! `config.json` when it is closed or hidden and applied again when it is built, so the window
+//! comes back where it was left. Values are logical points (top-left origin, like Tauri's
+//! builder). A saved position that no longer lies on any display (monitor unplugged) is dropped
+//! and the window opens at the default place with the saved size.
+
+use serde::{Deserialize, Serialize};
+use tauri::WebviewWindow;
+
+/// Default inner size of the CPU window
+pub const CPU_WINDOW_SIZE: (f64, f64) = (644.0, 995.0);
+/// Smaller saved sizes are ignored (a collapsed or broken frame)
+const MIN_SIZE: f64 = 60.0;
+/// Part of the window (from its top-left corner) that must be on a display to keep the position
+const VISIBLE_MARGIN: f64 = 40.0;
+
+/// The `cpuWindowFrame` / `widgetWindowFrame` config object
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowFrame {
+    pub x: Option<f64>,
+    pub y: Option<f64>,
+    pub width: f64,
+    pub height: f64,
+    #[serde(default)]
+    pub always_on_top: bool,
+}
+
+/// A display's area in logical points: x, y, width, height
+pub type DisplayRect = (f64, f64, f64, f64);
+
+impl WindowFrame {
+    /// Lenient parse: sizes below the minimum fall back to `default_size`, a position needs both
+    /// coordinates
+    pub fn from_json(value: Option<&serde_json::Value>, default_size: (f64, f64)) -> Self {
+        let number = |key: &str| value.and_then(|v| v.get(key)).and_then(|v| v.as_f64());
+        let size = |key: &str, default: f64| {
+            number(key)
+                .filter(|v| v.is_finite() && *v >= MIN_SIZE)
+                .unwrap_or(default)
+        };
+        let (x, y) = match (number("x"), number("y")) {
+            (Some(x), Some(y)) if x.is_finite() && y.is_finite() => (Some(x), Some(y)),
+            _ => (None, None),
+        };
+        Self {
+            x,
+            y,
+            width: size("width", default_size.0),
+            height: size("height", default_size.1),
+            always_on_top: value
+                .and_then(|v| v.get("alwaysOnTop"))
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
+        }
+    }
+
+    /// The saved position if its top-left corner (plus a small margin) is on one of `displays`
+    pub fn visible_position(&self, displays: &[DisplayRect]) -> Option<(f64, f64)> {
+        let (x, y) = (self.x?, self.y?);
+        displays
+            .iter()
+            .any(|(dx, dy, dw, dh)| {
+                x + VISIBLE_MARGIN > *dx
+                    && x + VISIBLE_MARGIN < dx + dw
+                    && y >= *dy
+                    && y + VISIBLE_MARGIN < dy + dh
+            })
+            .then_some((x, y))
+    }
+}
+
+/// Current frame of `window` in logical points, `None` while it is minimized or unavailable
+pub fn capture(window: &WebviewWindow) -> Option<WindowFrame> {
+    if window.is_minimized().unwrap_or(false) {
+        return None;
+    }
+    let scale = window.scale_factor().ok()?;
+    let position = window.outer_position().ok()?.to_logical::<f64>(scale);
+    let size = window.inner_size().ok()?.to_logical::<f64>(scale);
+    Some(WindowFrame {
+        x: Some(position.x),
+        y: Some(position.y),
+        width: size.width,
+        height: size.height,
+        always_on_top: window.is_always_on_top().unwrap_or(false),
+    })
+}
+
+/// Save the frame of `window` to `cpuWindowFrame` or `widgetWindowFrame` (skipped in read-only
+/// guest mode)
+pub fn save(window: &WebviewWindow) {
+    if crate::guest_mode::is_active() {
+        return;
+    }
+    let Some(frame) = capture(window) else {
+        return;
+    };
+    let saved = if window.label() == super::widget_window::LABEL {
+        crate::config::Config::set_widget_window_frame(&frame)
+    } else {
+        crate::config::Config::set_cpu_window_frame(&frame)
+    };
+    if let Err(e) = saved {
+        tracing::debug!("Could not save frame of window '{}': {}", window.label(), e);
+    }
+}
+
+/// Logical areas of all displays, for [`WindowFrame::visible_position`]
+pub fn displays(app_handle: &tauri::AppHandle) -> Vec<DisplayRect> {
+    app_handle
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(|m| {
+            let scale = m.scale_factor();
+            let pos = m.position().to_logical::<f64>(scale);
+            let size = m.size().to_logical::<f64>(scale);
+            (pos.x, pos.y, size.width, size.height)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_checks_position() {
+        let frame = WindowFrame::from_json(
+            Some(&serde_json::json!({
+                "x": 100.0, "y": 50.0, "width": 800.0, "height": 20.0, "alwaysOnTop": true
+            })),
+            CPU_WINDOW_SIZE,
+        );
+        assert_eq!(
+            frame,
+            WindowFrame {
+                x: Some(100.0),
+                y: Some(50.0),
+                width: 800.0,
+                height: CPU_WINDOW_SIZE.1,
+                always_on_top: true,
+            }
+        );
+        let main = (0.0, 0.0, 1440.0, 900.0);
+        assert_eq!(frame.visible_position(&[main]), Some((100.0, 50.0)));
+        let unplugged = WindowFrame {
+            x: Some(2000.0),
+            ..frame
+        };
+        assert_eq!(unplugged.visible_position(&[main]), None);
+        assert_eq!(
+            unplugged.visible_position(&[main, (1440.0, 0.0, 1920.0, 1080.0)]),
+            Some((2000.0, 50.0))
+        );
+
+        let partial = WindowFrame::from_json(Some(&serde_json::json!({"x": 10})), (200.0, 90.0));
+        assert_eq!(
+            partial,
+            WindowFrame {
+                x: None,
+                y: None,
+                width: 200.0,
+                height: 90.0,
+                always_on_top: false,
+            }
+        );
+        assert_eq!(partial.visible_position(&[main]), None);
+    }
+}
@@ -0,0 +1,141 @@
+//! Pin modes of the CPU window (`cpuWindowPinMode`)
+//!
+//! - `normal`: an ordinary window on the Space it was opened on
+//! - `alwaysOnTop`: floats above other apps' windows
+//! - `allSpaces`: follows you to every desktop (Space), including full-screen ones, via the
+//!   `NSWindow` collection behavior `CanJoinAllSpaces | FullScreenAuxiliary`
+//!
+//! `set_window_pin_mode` applies a mode to the calling window right away; for the CPU window
+//! it is also saved and applied again when the window is created.
+
+use objc2::msg_send;
+use objc2::runtime::AnyObject;
+use serde::{Deserialize, Serialize};
+use tauri::WebviewWindow;
+
+/// `NSWindowCollectionBehaviorCanJoinAllSpaces`
+const CAN_JOIN_ALL_SPACES: usize = 1 << 0;
+/// `NSWindowCollectionBehaviorMoveToActiveSpace` (excludes `CanJoinAllSpaces`)
+const MOVE_TO_ACTIVE_SPACE: usize = 1 << 1;
+/// `NSWindowCollectionBehaviorFullScreenAuxiliary`: may share a full-screen app's Space
+const FULL_SCREEN_AUXILIARY: usize = 1 << 8;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PinMode {
+    #[default]
+    Normal,
+    AlwaysOnTop,
+    AllSpaces,
+}
+
+impl PinMode {
+    /// Lenient parse: `"alwaysOnTop"`, `"always on top"`, `"always_on_top"`, `"pin"`, ...
+    pub fn parse(s: &str) -> Option<Self> {
+        let key = s
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect::<String>()
+            .to_ascii_lowercase();
+        match key.as_str() {
+            "normal" | "none" | "off" => Some(Self::Normal),
+            "alwaysontop" | "ontop" | "top" | "float" | "pin" | "pinned" => Some(Self::AlwaysOnTop),
+            "allspaces" | "visibleonallspaces" | "alldesktops" | "follow" => Some(Self::AllSpaces),
+            _ => None,
+        }
+    }
+
+    /// `collectionBehavior` with the Space bits for this mode, other bits kept
+    fn collection_behavior(self, current: usize) -> usize {
+        let cleared =
+            current & !(CAN_JOIN_ALL_SPACES | MOVE_TO_ACTIVE_SPACE | FULL_SCREEN_AUXILIARY);
+        match self {
+            Self::AllSpaces => cleared | CAN_JOIN_ALL_SPACES | FULL_SCREEN_AUXILIARY,
+            Self::Normal | Self::AlwaysOnTop => cleared,
+        }
+    }
+}
+
+/// Apply `mode` to `window` (level and Space behavior); AppKit calls run on the main thread
+pub fn apply(window: &WebviewWindow, mode: PinMode) -> Result<(), String> {
+    window
+        .set_always_on_top(mode == PinMode::AlwaysOnTop)
+        .map_err(|e| e.to_string())?;
+    let ns_window = window.ns_window().map_err(|e| e.to_string())? as usize;
+    window
+        .run_on_main_thread(move || {
+            let ns_window = ns_window as *mut AnyObject;
+            if ns_window.is_null() {
+                return;
+            }
+            unsafe {
+                let current: usize = msg_send![ns_window, collectionBehavior];
+                let _: () = msg_send![
+                    ns_window,
+                    setCollectionBehavior: mode.collection_behavior(current)
+                ];
+            }
+        })
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn get_window_pin_mode() -> PinMode {
+    crate::config::Config::cpu_window_pin_mode()
+}
+
+/// Pin the calling window: `"normal"`, `"alwaysOnTop"` or `"allSpaces"`
+#[tauri::command]
+pub fn set_window_pin_mode(
+    window: WebviewWindow,
+    mode: String,
+    confirmation_token: Option<String>,
+) -> Result<PinMode, String> {
+    crate::guest_mode::ensure_allowed("Changing settings")?;
+    let mode = PinMode::parse(&mode).ok_or_else(|| {
+        format!(
+            "Unknown pin mode '{}' (expected normal, alwaysOnTop or allSpaces)",
+            mode
+        )
+    })?;
+    crate::commands::confirmation::require_confirmation(
+        confirmation_token.as_deref(),
+        "config_write",
+        "cpuWindowPinMode",
+    )?;
+    apply(&window, mode)?;
+    if window.label() == "cpu" {
+        crate::config::Config::set_cpu_window_pin_mode(mode)?;
+    }
+    Ok(mode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_modes_and_space_bits() {
+        assert_eq!(PinMode::parse("always on top"), Some(PinMode::AlwaysOnTop));
+        assert_eq!(PinMode::parse("allSpaces"), Some(PinMode::AllSpaces));
+        assert_eq!(
+            PinMode::parse("visible_on_all_spaces"),
+            Some(PinMode::AllSpaces)
+        );
+        assert_eq!(PinMode::parse("Normal"), Some(PinMode::Normal));
+        assert_eq!(PinMode::parse("sideways"), None);
+        assert_eq!(
+            serde_json::to_value(PinMode::AlwaysOnTop).unwrap(),
+            serde_json::json!("alwaysOnTop")
+        );
+
+        // Other bits (e.g. 1 << 7, FullScreenPrimary) are kept
+        let current = MOVE_TO_ACTIVE_SPACE | (1 << 7);
+        assert_eq!(
+            PinMode::AllSpaces.collection_behavior(current),
+            CAN_JOIN_ALL_SPACES | FULL_SCREEN_AUXILIARY | (1 << 7)
+        );
+        let all = CAN_JOIN_ALL_SPACES | FULL_SCREEN_AUXILIARY | (1 << 7);
+        assert_eq!(PinMode::Normal.collection_behavior(all), 1 << 7);
+    }
+}
@@ -0,0 +1,280 @@
+//! Display units (`units`): °C or °F, GB or GiB, bytes or bits per second
+//!
+//! Everything mac-stats shows as text follows these: the menu bar and its drop-down menu,
+//! `mac_stats stats`, the metrics summary given to the AI agent and the CPU window (which reads
+//! them via `get_units` and follows `units-changed`). Data interfaces stay in SI base units so
+//! dashboards and scripts never need to know the preference: JSON command results, history
+//! exports, the local API, InfluxDB and MQTT report °C, bytes and bytes/s.
+//!
+//! Fields `units` leaves out follow the system ([`Units::system`]): °F when macOS is set to
+//! Fahrenheit or the region uses it.
+
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use tauri::Emitter;
+
+/// Event sent to all windows after `set_units` (payload: [`Units`])
+pub const EVENT: &str = "units-changed";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TemperatureUnit {
+    #[default]
+    #[serde(rename = "C")]
+    Celsius,
+    #[serde(rename = "F")]
+    Fahrenheit,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SizeUnit {
+    /// Powers of 1000: KB, MB, GB
+    #[default]
+    #[serde(rename = "GB")]
+    Decimal,
+    /// Powers of 1024: KiB, MiB, GiB
+    #[serde(rename = "GiB")]
+    Binary,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RateUnit {
+    /// B/s, KB/s, ...
+    #[default]
+    #[serde(rename = "bytes")]
+    Bytes,
+    /// bit/s, kbit/s, ... (always powers of 1000)
+    #[serde(rename = "bits")]
+    Bits,
+}
+
+/// The `units` config object, e.g. `{"temperature": "F", "size": "GiB", "networkRate": "bits"}`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Units {
+    pub temperature: TemperatureUnit,
+    pub size: SizeUnit,
+    pub network_rate: RateUnit,
+}
+
+impl Units {
+    /// Units from `config.json`
+    pub fn current() -> Self {
+        crate::config::Config::units()
+    }
+
+    /// Lenient parse: each field on its own, case-insensitive, unknown values keep the default
+    pub fn from_json(value: Option<&serde_json::Value>) -> Self {
+        Self::from_json_or(value, Self::default())
+    }
+
+    /// [`Units::from_json`] with missing or unknown fields taken from `defaults`
+    pub fn from_json_or(value: Option<&serde_json::Value>, defaults: Self) -> Self {
+        let field = |key: &str| {
+            value
+                .and_then(|v| v.get(key))
+                .and_then(|v| v.as_str())
+                .map(|s| s.trim().to_ascii_lowercase())
+                .unwrap_or_default()
+        };
+        Self {
+            temperature: match field("temperature").as_str() {
+                "f" | "°f" | "fahrenheit" => TemperatureUnit::Fahrenheit,
+                "c" | "°c" | "celsius" => TemperatureUnit::Celsius,
+                _ => defaults.temperature,
+            },
+            size: match field("size").as_str() {
+                "gib" | "binary" => SizeUnit::Binary,
+                "gb" | "decimal" => SizeUnit::Decimal,
+                _ => defaults.size,
+            },
+            network_rate: match field("networkRate").as_str() {
+                "bits" | "bit" | "bps" => RateUnit::Bits,
+                "bytes" | "byte" => RateUnit::Bytes,
+                _ => defaults.network_rate,
+            },
+        }
+    }
+
+    /// Defaults for fields `units` leaves out: the macOS temperature unit (System Settings >
+    /// General > Language & Region, `AppleTemperatureUnit`), else Fahrenheit in regions that
+    /// use it. Read once.
+    pub fn system() -> Self {
+        static SYSTEM: OnceLock<Units> = OnceLock::new();
+        *SYSTEM.get_or_init(|| {
+            let preference = std::process::Command::new("/usr/bin/defaults")
+                .args(["read", "-g", "AppleTemperatureUnit"])
+                .stderr(std::process::Stdio::null())
+                .output()
+                .ok()
+                .filter(|o| o.status.success())
+                .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string());
+            Self {
+                temperature: system_temperature(
+                    preference.as_deref(),
+                    &crate::number_format::system_locale(),
+                ),
+                ..Self::default()
+            }
+        })
+    }
+
+    /// A Celsius reading in the preferred unit
+    pub fn temperature(&self, celsius: f64) -> f64 {
+        match self.temperature {
+            TemperatureUnit::Celsius => celsius,
+            TemperatureUnit::Fahrenheit => celsius * 9.0 / 5.0 + 32.0,
+        }
+    }
+
+    /// `°C` or `°F`
+    pub fn temperature_symbol(&self) -> &'static str {
+        match self.temperature {
+            TemperatureUnit::Celsius => "°C",
+            TemperatureUnit::Fahrenheit => "°F",
+        }
+    }
+
+    /// `bytes` scaled to the largest unit it reaches, e.g. `(1.5, "GB")` or `(1.4, "GiB")`
+    pub fn size(&self, bytes: f64) -> (f64, &'static str) {
+        match self.size {
+            SizeUnit::Decimal => scale(bytes, 1000.0, &["B", "KB", "MB", "GB", "TB"]),
+            SizeUnit::Binary => scale(bytes, 1024.0, &["B", "KiB", "MiB", "GiB", "TiB"]),
+        }
+    }
+
+    /// A byte rate scaled like [`Units::size`], e.g. `(1.2, "MB/s")` or `(9.6, "Mbit/s")`
+    pub fn rate(&self, bytes_per_sec: f64) -> (f64, &'static str) {
+        match self.network_rate {
+            RateUnit::Bytes => scale(
+                bytes_per_sec,
+                1000.0,
+                &["B/s", "KB/s", "MB/s", "GB/s", "TB/s"],
+            ),
+            RateUnit::Bits => scale(
+                bytes_per_sec * 8.0,
+                1000.0,
+                &["bit/s", "kbit/s", "Mbit/s", "Gbit/s", "Tbit/s"],
+            ),
+        }
+    }
+
+    /// Per-second value for the compact menu bar rates (`{net_rx}` with a K/M/G suffix): bytes,
+    /// or bits when `networkRate` is `"bits"`
+    pub fn rate_value(&self, bytes_per_sec: f64) -> f64 {
+        match self.network_rate {
+            RateUnit::Bytes => bytes_per_sec,
+            RateUnit::Bits => bytes_per_sec * 8.0,
+        }
+    }
+
+    /// Unit after a compact rate: `B/s` or `b/s`
+    pub fn rate_suffix(&self) -> &'static str {
+        match self.network_rate {
+            RateUnit::Bytes => "B/s",
+            RateUnit::Bits => "b/s",
+        }
+    }
+}
+
+/// Regions that measure temperature in Fahrenheit
+const FAHRENHEIT_REGIONS: &[&str] = &["US", "BS", "BZ", "KY", "LR", "PW", "FM", "MH"];
+
+/// `AppleTemperatureUnit` (`Celsius` / `Fahrenheit`) when set, otherwise by the region of
+/// `locale` (e.g. `en_US`)
+fn system_temperature(preference: Option<&str>, locale: &str) -> TemperatureUnit {
+    match preference {
+        Some("Fahrenheit") => TemperatureUnit::Fahrenheit,
+        Some("Celsius") => TemperatureUnit::Celsius,
+        _ => {
+            let region = locale
+                .split(['.', '@'])
+                .next()
+                .unwrap_or("")
+                .split(['_', '-'])
+                .skip(1)
+                .find(|p| p.len() == 2)
+                .unwrap_or("");
+            if FAHRENHEIT_REGIONS.contains(&region.to_ascii_uppercase().as_str()) {
+                TemperatureUnit::Fahrenheit
+            } else {
+                TemperatureUnit::Celsius
+            }
+        }
+    }
+}
+
+fn scale(value: f64, step: f64, units: &[&'static str]) -> (f64, &'static str) {
+    let mut value = value.max(0.0);
+    let mut i = 0;
+    while value >= step && i + 1 < units.len() {
+        value /= step;
+        i += 1;
+    }
+    (value, units[i])
+}
+
+#[tauri::command]
+pub fn get_units() -> Units {
+    Units::current()
+}
+
+/// Save the unit preference; open windows get `units-changed`
+#[tauri::command]
+pub fn set_units(units: Units, confirmation_token: Option<String>) -> Result<Units, String> {
+    crate::guest_mode::ensure_allowed("Changing settings")?;
+    crate::commands::confirmation::require_confirmation(
+        confirmation_token.as_deref(),
+        "config_write",
+        "units",
+    )?;
+    crate::config::Config::set_units(&units)?;
+    if let Some(app) = crate::state::APP_HANDLE.get() {
+        if let Err(e) = app.emit(EVENT, units) {
+            tracing::debug!("Units event failed: {}", e);
+        }
+    }
+    Ok(Units::current())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_and_scales() {
+        let units = Units::from_json(Some(&serde_json::json!({
+            "temperature": "F",
+            "size": "GiB",
+            "networkRate": "bits"
+        })));
+        assert_eq!(units.temperature(100.0), 212.0);
+        assert_eq!(units.temperature_symbol(), "°F");
+        assert_eq!(units.size(1536.0), (1.5, "KiB"));
+        assert_eq!(units.rate(125_000.0), (1.0, "Mbit/s"));
+        assert_eq!(units.rate_suffix(), "b/s");
+
+        let default = Units::from_json(Some(&serde_json::json!({"temperature": "kelvin"})));
+        assert_eq!(default, Units::default());
+        assert_eq!(default.size(2_500_000_000.0), (2.5, "GB"));
+        assert_eq!(default.rate(512.0), (512.0, "B/s"));
+
+        let us = Units {
+            temperature: system_temperature(None, "en_US.UTF-8"),
+            ..Units::default()
+        };
+        assert_eq!(us.temperature, TemperatureUnit::Fahrenheit);
+        assert_eq!(
+            system_temperature(Some("Celsius"), "en_US"),
+            TemperatureUnit::Celsius
+        );
+        assert_eq!(system_temperature(None, "de_CH"), TemperatureUnit::Celsius);
+        let explicit = Units::from_json_or(Some(&serde_json::json!({"temperature": "C"})), us);
+        assert_eq!(explicit.temperature, TemperatureUnit::Celsius);
+        assert_eq!(Units::from_json_or(None, us), us);
+        assert_eq!(
+            serde_json::to_value(default).unwrap(),
+            serde_json::json!({"temperature": "C", "size": "GB", "networkRate": "bytes"})
+        );
+    }
+}
@@ -0,0 +1,370 @@
+//! Download, verify and stage a release, then swap it in on the next launch
+//!
+//! Staging (`autoUpdate: "install"` or `install_update`):
+//! 1. Download the release DMG to `~/.mac-stats/updates/` and compare its SHA-256 with the
+//!    digest GitHub publishes for the asset; a release without a digest is not installed.
+//! 2. Mount the DMG read-only, copy `mac-stats.app` to `updates/staged/` and detach.
+//! 3. `codesign --verify --deep --strict` the copy and require it to be signed by the same team
+//!    as the running app. Ad-hoc signed local builds have no team to compare against, so they
+//!    only check for updates and never stage one ([`can_install`]).
+//! 4. Write `updates/pending.json` with the version and the staged path.
+//!
+//! On the next launch [`apply_pending`] checks that the pending path is the copy in
+//! `updates/staged/` and verifies its signature again (pending.json is user-writable), then
+//! moves the running bundle to `updates/previous/`, moves the staged one into its place,
+//! relaunches it and exits. Anything unexpected (not running from an `.app` bundle, the bundle
+//! not writable, the staged version not newer or no longer verified) discards the staged copy
+//! and keeps the current app.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use super::{is_newer, ReleaseAsset, UpdateCheck};
+use crate::config::Config;
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+const APP_NAME: &str = "mac-stats.app";
+const PENDING_FILE: &str = "pending.json";
+
+/// A verified update waiting for the next launch (`updates/pending.json`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingUpdate {
+    pub version: String,
+    pub app_path: PathBuf,
+    /// Unix seconds
+    pub staged_at: i64,
+}
+
+fn pending_path() -> PathBuf {
+    Config::updates_dir().join(PENDING_FILE)
+}
+
+/// The staged update, if any
+pub fn pending() -> Option<PendingUpdate> {
+    let content = std::fs::read_to_string(pending_path()).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn staged_dir() -> PathBuf {
+    Config::updates_dir().join("staged")
+}
+
+fn discard_pending() {
+    let _ = std::fs::remove_file(pending_path());
+    let _ = std::fs::remove_dir_all(staged_dir());
+}
+
+/// Hex SHA-256 of everything `reader` yields
+pub fn sha256_hex(mut reader: impl Read) -> std::io::Result<String> {
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect())
+}
+
+/// `TeamIdentifier=` from `codesign -dv` output (stderr); None for ad-hoc signatures
+pub fn parse_team_id(codesign_output: &str) -> Option<String> {
+    codesign_output
+        .lines()
+        .find_map(|l| l.strip_prefix("TeamIdentifier="))
+        .map(str::trim)
+        .filter(|t| !t.is_empty() && *t != "not set")
+        .map(str::to_string)
+}
+
+/// Developer ID team that signed `app` (a bundle or a binary); None when ad-hoc or unsigned
+pub fn team_id(app: &Path) -> Option<String> {
+    let out = Command::new("/usr/bin/codesign")
+        .arg("-dv")
+        .arg(app)
+        .output()
+        .ok()?;
+    parse_team_id(&String::from_utf8_lossy(&out.stderr))
+}
+
+fn run(cmd: &mut Command, what: &str) -> Result<(), String> {
+    let out = cmd
+        .output()
+        .map_err(|e| format!("{} failed: {}", what, e))?;
+    if out.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} failed: {}",
+            what,
+            String::from_utf8_lossy(&out.stderr).trim()
+        ))
+    }
+}
+
+/// The `.app` bundle the running executable belongs to
+pub fn current_bundle() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    exe.ancestors()
+        .find(|p| p.extension().is_some_and(|e| e == "app"))
+        .map(Path::to_path_buf)
+}
+
+fn download(asset: &ReleaseAsset, dir: &Path) -> Result<PathBuf, String> {
+    let expected = asset
+        .sha256
+        .as_deref()
+        .ok_or("The release publishes no checksum for its DMG; not installing it")?;
+    let path = dir.join(&asset.name);
+    let client = reqwest::blocking::Client::builder()
+        .timeout(std::time::Duration::from_secs(600))
+        .user_agent(format!("mac-stats/{}", Config::version()))
+        .build()
+        .map_err(|e| format!("HTTP client: {}", e))?;
+    let mut response = client
+        .get(&asset.url)
+        .send()
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Download failed: {}", e))?;
+    let mut file = std::fs::File::create(&path).map_err(|e| e.to_string())?;
+    std::io::copy(&mut response, &mut file).map_err(|e| format!("Download failed: {}", e))?;
+    let actual = sha256_hex(std::fs::File::open(&path).map_err(|e| e.to_string())?)
+        .map_err(|e| e.to_string())?;
+    if !actual.eq_ignore_ascii_case(expected) {
+        let _ = std::fs::remove_file(&path);
+        return Err(format!(
+            "Checksum mismatch for {} (expected {}, got {})",
+            asset.name, expected, actual
+        ));
+    }
+    Ok(path)
+}
+
+/// Copy the app out of the DMG into `staged/`
+fn extract(dmg: &Path, dir: &Path) -> Result<PathBuf, String> {
+    let mount = dir.join("mount");
+    let staged = dir.join("staged");
+    let _ = std::fs::remove_dir_all(&staged);
+    std::fs::create_dir_all(&mount).map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&staged).map_err(|e| e.to_string())?;
+    run(
+        Command::new("/usr/bin/hdiutil")
+            .args([
+                "attach",
+                "-nobrowse",
+                "-readonly",
+                "-noautoopen",
+                "-mountpoint",
+            ])
+            .arg(&mount)
+            .arg(dmg),
+        "Mounting the update",
+    )?;
+    let app = staged.join(APP_NAME);
+    let copied = run(
+        Command::new("/usr/bin/ditto")
+            .arg(mount.join(APP_NAME))
+            .arg(&app),
+        "Copying the update",
+    );
+    let _ = run(
+        Command::new("/usr/bin/hdiutil")
+            .args(["detach", "-quiet"])
+            .arg(&mount),
+        "Unmounting the update",
+    );
+    copied.map(|_| app)
+}
+
+/// Developer ID team of the running app; updates must come from the same team
+fn running_team() -> Result<String, String> {
+    current_bundle()
+        .as_deref()
+        .and_then(team_id)
+        .ok_or_else(|| {
+            "This build is not signed with a Developer ID, so updates cannot be verified; \
+             download new versions manually"
+                .to_string()
+        })
+}
+
+/// Whether this build can stage updates at all (`autoUpdate: "install"`, `install_update`)
+pub fn can_install() -> bool {
+    running_team().is_ok()
+}
+
+fn verify_signature(app: &Path) -> Result<(), String> {
+    let running = running_team()?;
+    run(
+        Command::new("/usr/bin/codesign")
+            .args(["--verify", "--deep", "--strict"])
+            .arg(app),
+        "Signature check",
+    )?;
+    match team_id(app) {
+        Some(new) if new == running => Ok(()),
+        new => Err(format!(
+            "The update is signed by {} instead of {}",
+            new.as_deref().unwrap_or("nobody"),
+            running
+        )),
+    }
+}
+
+/// `app_path` from pending.json must be the copy [`stage`] made in `updates/staged/`
+fn is_staged_copy(app_path: &Path) -> bool {
+    let (Ok(path), Ok(staged)) = (
+        std::fs::canonicalize(app_path),
+        std::fs::canonicalize(staged_dir()),
+    ) else {
+        return false;
+    };
+    path == staged.join(APP_NAME)
+}
+
+/// Download, verify and stage the release in `check` for the next launch (blocking)
+pub fn stage(check: &UpdateCheck) -> Result<PendingUpdate, String> {
+    if !check.update_available {
+        return Err(format!("mac-stats {} is up to date", check.current_version));
+    }
+    running_team()?;
+    if let Some(p) = pending().filter(|p| p.version == check.latest_version) {
+        return Ok(p);
+    }
+    let asset = check
+        .dmg
+        .as_ref()
+        .ok_or("The latest release has no DMG to install")?;
+    let dir = Config::updates_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    debug1!(
+        "Downloading update {} from {}",
+        check.latest_version,
+        asset.url
+    );
+    let dmg = download(asset, &dir)?;
+    let app = extract(&dmg, &dir);
+    let _ = std::fs::remove_file(&dmg);
+    let app = app?;
+    if let Err(e) = verify_signature(&app) {
+        discard_pending();
+        return Err(e);
+    }
+    let pending = PendingUpdate {
+        version: check.latest_version.clone(),
+        app_path: app,
+        staged_at: chrono::Utc::now().timestamp(),
+    };
+    let json = serde_json::to_string_pretty(&pending).map_err(|e| e.to_string())?;
+    std::fs::write(pending_path(), json).map_err(|e| e.to_string())?;
+    tracing::info!(
+        "Update {} verified and staged; it is installed on the next launch",
+        pending.version
+    );
+    Ok(pending)
+}
+
+/// Swap in a staged update at launch. Returns only when there is nothing to apply (or the swap
+/// failed and the current app keeps running); after a swap the new app is relaunched and this
+/// process exits.
+pub fn apply_pending() {
+    let Some(update) = pending() else {
+        return;
+    };
+    if !is_newer(&update.version, &Config::version()) || !update.app_path.exists() {
+        debug2!("Discarding staged update {}", update.version);
+        discard_pending();
+        return;
+    }
+    if !is_staged_copy(&update.app_path) {
+        tracing::warn!(
+            "Discarding staged update {}: {} is not in {}",
+            update.version,
+            update.app_path.display(),
+            staged_dir().display()
+        );
+        discard_pending();
+        return;
+    }
+    if let Err(e) = verify_signature(&update.app_path) {
+        tracing::warn!("Discarding staged update {}: {}", update.version, e);
+        discard_pending();
+        return;
+    }
+    let Some(bundle) = current_bundle() else {
+        debug1!(
+            "Not running from an app bundle; keeping staged update {}",
+            update.version
+        );
+        return;
+    };
+    let previous = Config::updates_dir().join("previous");
+    let _ = std::fs::remove_dir_all(&previous);
+    if let Err(e) = std::fs::create_dir_all(&previous) {
+        tracing::warn!("Could not install update {}: {}", update.version, e);
+        return;
+    }
+    let backup = previous.join(APP_NAME);
+    if let Err(e) = std::fs::rename(&bundle, &backup) {
+        tracing::warn!(
+            "Could not install update {} ({} is not writable: {})",
+            update.version,
+            bundle.display(),
+            e
+        );
+        discard_pending();
+        return;
+    }
+    if let Err(e) = std::fs::rename(&update.app_path, &bundle) {
+        tracing::warn!(
+            "Could not install update {}: {}; restoring",
+            update.version,
+            e
+        );
+        let _ = std::fs::rename(&backup, &bundle);
+        discard_pending();
+        return;
+    }
+    discard_pending();
+    tracing::info!("Installed update {}; relaunching", update.version);
+    // The single-instance lock is released when this process exits; give it a moment
+    let relaunched = Command::new("/bin/sh")
+        .arg("-c")
+        .arg("sleep 1; /usr/bin/open -n \"$0\"")
+        .arg(&bundle)
+        .spawn();
+    match relaunched {
+        Ok(_) => std::process::exit(0),
+        Err(e) => tracing::warn!("Could not relaunch after the update: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_and_reads_team_ids() {
+        assert_eq!(
+            sha256_hex(&b"abc"[..]).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        let signed = "Executable=/Applications/mac-stats.app/Contents/MacOS/mac_stats\n\
+                      Signature size=9000\nTeamIdentifier=AB12CD34EF\n";
+        assert_eq!(parse_team_id(signed), Some("AB12CD34EF".to_string()));
+        assert_eq!(
+            parse_team_id("Signature=adhoc\nTeamIdentifier=not set\n"),
+            None
+        );
+    }
+}
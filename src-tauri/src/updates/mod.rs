@@ -0,0 +1,356 @@
+//! Update check against GitHub releases (`check_for_update`) and the self-updater
+//!
+//! Asks the GitHub API for the latest release of `raro42/mac-stats` and compares its tag with
+//! the running version. The About window calls it when it opens; the result carries the
+//! release page, the notes and the DMG download when the release has one. Drafts and
+//! pre-releases are never returned by `releases/latest`, so only stable versions are offered.
+//!
+//! With `autoUpdate` at `check` (default) a background thread repeats the check every
+//! `autoUpdateIntervalHours` and posts a notification once per new version; at `install` it
+//! also downloads, verifies and stages the release, which [`installer::apply_pending`] swaps
+//! in on the next launch.
+
+pub mod installer;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::alerts::channels::{AlertChannel, NotificationChannel};
+use crate::alerts::AlertContext;
+use crate::config::Config;
+#[allow(unused_imports)]
+use crate::{debug1, debug2, debug3};
+
+pub const RELEASES_URL: &str = "https://github.com/raro42/mac-stats/releases/latest";
+const LATEST_RELEASE_API: &str = "https://api.github.com/repos/raro42/mac-stats/releases/latest";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// First automatic check after launch (after the startup stages)
+const FIRST_CHECK_DELAY: Duration = Duration::from_secs(120);
+
+/// What the background updater does (`autoUpdate`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AutoUpdateMode {
+    Off,
+    /// Check and notify
+    #[default]
+    Check,
+    /// Check, download, verify and stage for the next launch
+    Install,
+}
+
+impl AutoUpdateMode {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "off" | "false" | "0" | "none" => Some(Self::Off),
+            "check" | "notify" => Some(Self::Check),
+            "install" | "auto" => Some(Self::Install),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+    #[serde(default)]
+    size: u64,
+    /// `sha256:<hex>`
+    digest: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    html_url: Option<String>,
+    body: Option<String>,
+    published_at: Option<String>,
+    #[serde(default)]
+    assets: Vec<GithubAsset>,
+}
+
+/// A downloadable release file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub url: String,
+    pub size: u64,
+    /// Hex SHA-256 published by GitHub for the asset
+    pub sha256: Option<String>,
+}
+
+/// Result of `check_for_update`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UpdateCheck {
+    pub current_version: String,
+    /// Latest release version without the leading `v`
+    pub latest_version: String,
+    pub update_available: bool,
+    pub release_url: String,
+    /// Release notes (markdown)
+    pub notes: String,
+    pub published_at: Option<String>,
+    /// The `.dmg` asset, if the release has one
+    pub dmg: Option<ReleaseAsset>,
+}
+
+/// Numeric `major.minor.patch...` parts of a version tag (`v0.1.253` → `[0, 1, 253]`);
+/// pre-release and build suffixes are ignored
+fn version_parts(version: &str) -> Vec<u64> {
+    let core = version
+        .trim()
+        .trim_start_matches(['v', 'V'])
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default();
+    core.split('.').map(|n| n.parse().unwrap_or(0)).collect()
+}
+
+/// Whether `latest` is a higher version than `current`
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    let (a, b) = (version_parts(latest), version_parts(current));
+    for i in 0..a.len().max(b.len()) {
+        let (x, y) = (
+            a.get(i).copied().unwrap_or(0),
+            b.get(i).copied().unwrap_or(0),
+        );
+        if x != y {
+            return x > y;
+        }
+    }
+    false
+}
+
+fn to_update_check(release: GithubRelease, current: &str) -> UpdateCheck {
+    let latest = release
+        .tag_name
+        .trim()
+        .trim_start_matches(['v', 'V'])
+        .to_string();
+    let dmg = release
+        .assets
+        .into_iter()
+        .find(|a| a.name.to_ascii_lowercase().ends_with(".dmg"))
+        .map(|a| ReleaseAsset {
+            name: a.name,
+            url: a.browser_download_url,
+            size: a.size,
+            sha256: a
+                .digest
+                .and_then(|d| d.strip_prefix("sha256:").map(str::to_string)),
+        });
+    UpdateCheck {
+        current_version: current.to_string(),
+        update_available: is_newer(&latest, current),
+        latest_version: latest,
+        release_url: release.html_url.unwrap_or_else(|| RELEASES_URL.to_string()),
+        notes: release.body.unwrap_or_default(),
+        published_at: release.published_at,
+        dmg,
+    }
+}
+
+/// Fetch the latest release and compare it with the running version
+pub async fn fetch_latest() -> Result<UpdateCheck, String> {
+    let current = Config::version();
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent(format!("mac-stats/{}", current))
+        .build()
+        .map_err(|e| format!("HTTP client: {}", e))?;
+    let response = client
+        .get(LATEST_RELEASE_API)
+        .header("Accept", "application/vnd.github+json")
+        .send()
+        .await
+        .map_err(|e| format!("Update check failed: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Update check failed: GitHub returned {}",
+            response.status()
+        ));
+    }
+    let release: GithubRelease = response
+        .json()
+        .await
+        .map_err(|e| format!("Unexpected release data: {}", e))?;
+    let check = to_update_check(release, &current);
+    debug2!(
+        "Update check: running {}, latest {} (update available: {})",
+        check.current_version,
+        check.latest_version,
+        check.update_available
+    );
+    Ok(check)
+}
+
+/// Latest GitHub release compared with the running version
+#[tauri::command]
+pub async fn check_for_update() -> Result<UpdateCheck, String> {
+    let check = fetch_latest().await?;
+    remember(&check);
+    Ok(check)
+}
+
+/// Result of `get_update_status`
+#[derive(Debug, Clone, Serialize)]
+pub struct UpdateStatus {
+    pub mode: AutoUpdateMode,
+    /// Last check this session (Unix seconds) and its result
+    pub last_checked: Option<i64>,
+    pub last_check: Option<UpdateCheck>,
+    /// Version staged for the next launch
+    pub pending_version: Option<String>,
+    /// False for builds without a Developer ID signature, which only check
+    pub can_install: bool,
+}
+
+static LAST_CHECK: Mutex<Option<(i64, UpdateCheck)>> = Mutex::new(None);
+/// Latest version a notification was posted for (once per version and session)
+static NOTIFIED_VERSION: Mutex<Option<String>> = Mutex::new(None);
+
+fn remember(check: &UpdateCheck) {
+    if let Ok(mut last) = LAST_CHECK.lock() {
+        *last = Some((chrono::Utc::now().timestamp(), check.clone()));
+    }
+}
+
+/// Updater mode, last check and staged update
+#[tauri::command]
+pub fn get_update_status() -> UpdateStatus {
+    let last = LAST_CHECK.lock().ok().and_then(|l| l.clone());
+    UpdateStatus {
+        mode: Config::auto_update_mode(),
+        last_checked: last.as_ref().map(|(at, _)| *at),
+        last_check: last.map(|(_, c)| c),
+        pending_version: installer::pending().map(|p| p.version),
+        can_install: installer::can_install(),
+    }
+}
+
+/// Download, verify and stage the latest release now; it is installed on the next launch
+#[tauri::command]
+pub async fn install_update() -> Result<String, String> {
+    crate::guest_mode::ensure_allowed("Installing updates")?;
+    let check = fetch_latest().await?;
+    remember(&check);
+    let pending = tokio::task::spawn_blocking(move || installer::stage(&check))
+        .await
+        .map_err(|e| e.to_string())??;
+    Ok(format!(
+        "mac-stats {} is ready and will be installed the next time mac-stats starts",
+        pending.version
+    ))
+}
+
+fn notify(message: &str) {
+    let context = AlertContext {
+        monitor_id: None,
+        monitor_status: None,
+        system_metrics: None,
+        cpu_details: None,
+        custom_data: HashMap::new(),
+    };
+    if let Err(e) = NotificationChannel.send(message, &context) {
+        debug1!("Update notification failed: {}", e);
+    }
+}
+
+fn run_automatic_check(mode: AutoUpdateMode) {
+    let check = match tauri::async_runtime::block_on(fetch_latest()) {
+        Ok(check) => check,
+        Err(e) => {
+            debug1!("Automatic update check: {}", e);
+            return;
+        }
+    };
+    remember(&check);
+    if !check.update_available {
+        return;
+    }
+    let message = if mode == AutoUpdateMode::Install {
+        match installer::stage(&check) {
+            Ok(p) => format!(
+                "mac-stats {} will be installed on the next launch",
+                p.version
+            ),
+            Err(e) => {
+                tracing::warn!("Automatic update to {} failed: {}", check.latest_version, e);
+                format!(
+                    "mac-stats {} is available (automatic install failed: {})",
+                    check.latest_version, e
+                )
+            }
+        }
+    } else {
+        format!("mac-stats {} is available", check.latest_version)
+    };
+    let first_time = NOTIFIED_VERSION
+        .lock()
+        .map(|mut v| v.replace(check.latest_version.clone()) != Some(check.latest_version))
+        .unwrap_or(false);
+    if first_time {
+        notify(&message);
+    }
+}
+
+/// Background checks per `autoUpdate` (nothing when `off` or in guest mode; `install` only
+/// checks in builds that cannot verify updates)
+pub fn spawn_auto_update_if_enabled() {
+    let mut mode = Config::auto_update_mode();
+    if mode == AutoUpdateMode::Off || crate::guest_mode::is_active() {
+        return;
+    }
+    if mode == AutoUpdateMode::Install && !installer::can_install() {
+        tracing::warn!(
+            "autoUpdate is \"install\" but this build has no Developer ID signature; only checking"
+        );
+        mode = AutoUpdateMode::Check;
+    }
+    let interval = Duration::from_secs(Config::auto_update_interval_hours() * 3600);
+    std::thread::spawn(move || {
+        std::thread::sleep(FIRST_CHECK_DELAY);
+        loop {
+            run_automatic_check(mode);
+            std::thread::sleep(interval);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_versions_and_picks_the_dmg() {
+        assert!(is_newer("v0.1.254", "0.1.253"));
+        assert!(is_newer("0.2", "0.1.999"));
+        assert!(!is_newer("v0.1.253", "0.1.253"));
+        assert!(!is_newer("0.1.253-beta.1", "0.1.253"));
+        assert!(!is_newer("0.1.9", "0.1.10"));
+        assert_eq!(
+            AutoUpdateMode::parse(" Install "),
+            Some(AutoUpdateMode::Install)
+        );
+        assert_eq!(AutoUpdateMode::parse("weekly"), None);
+
+        let release: GithubRelease = serde_json::from_str(
+            r#"{"tag_name":"v0.2.0","html_url":"https://example.com/r","body":"- Fixes",
+                "published_at":"2026-10-01T10:00:00Z","assets":[
+                {"name":"checksums.txt","browser_download_url":"https://example.com/c","size":1},
+                {"name":"mac-stats_0.2.0_aarch64.dmg","browser_download_url":"https://example.com/d",
+                 "size":9000,"digest":"sha256:00ff"}]}"#,
+        )
+        .unwrap();
+        let check = to_update_check(release, "0.1.253");
+        assert!(check.update_available);
+        assert_eq!(check.latest_version, "0.2.0");
+        let dmg = check.dmg.unwrap();
+        assert_eq!(dmg.url, "https://example.com/d");
+        assert_eq!(dmg.sha256.as_deref(), Some("00ff"));
+    }
+}
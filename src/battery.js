@@ -0,0 +1,192 @@
+// Battery window (see `ui/battery_window.rs`): charge and power charts from the metrics history
+// (`battery_level`, `battery_power`, `adapter_power`) and the current state from
+// `get_battery_details`. Temperature follows the `units` preference. The Low Power Mode switch
+// calls `set_low_power_mode` (needs the privileged helper) through `confirmedInvoke` from confirm.js.
+const BATTERY_REFRESH_MS = 5000;
+const BATTERY_CHART_POINTS = 300;
+const BATTERY_METRICS = ["battery_level", "battery_power", "adapter_power"];
+
+let batteryUnits = { temperature: "C" };
+let rangeSeconds = 21600;
+
+function getInvoke() {
+  return window.__TAURI__?.core?.invoke || window.__TAURI_INTERNALS__?.invoke?.bind(window.__TAURI_INTERNALS__) || null;
+}
+
+function setText(id, text) {
+  const el = document.getElementById(id);
+  if (el && el.textContent !== text) el.textContent = text;
+}
+
+function formatWatts(watts) {
+  return watts > 0 ? `${watts >= 10 ? Math.round(watts) : watts.toFixed(1)} W` : "--";
+}
+
+function formatDuration(secs) {
+  if (!secs) return "--";
+  const h = Math.floor(secs / 3600);
+  const m = Math.round((secs % 3600) / 60);
+  return h > 0 ? `${h}h ${String(m).padStart(2, "0")}m` : `${m}m`;
+}
+
+function formatTemperature(celsius) {
+  if (celsius == null) return "--";
+  return batteryUnits.temperature === "F"
+    ? `${Math.round((celsius * 9) / 5 + 32)}°F`
+    : `${Math.round(celsius)}°C`;
+}
+
+function renderLowPowerMode(enabled) {
+  const toggle = document.getElementById("bat-low-power");
+  if (!toggle) return;
+  toggle.disabled = enabled == null;
+  if (!toggle.dataset.busy) toggle.checked = !!enabled;
+  setText("bat-low-power-state", enabled == null ? "Needs macOS 12" : enabled ? "On" : "Off");
+}
+
+function renderDetails(details) {
+  renderLowPowerMode(details.low_power_mode);
+  const b = details.battery;
+  if (!b) {
+    setText("bat-level", "--");
+    setText("bat-state", "No battery");
+    return;
+  }
+  setText("bat-level", `${Math.round(b.level)}%`);
+  setText("bat-state", b.state.charAt(0).toUpperCase() + b.state.slice(1));
+  const charging = b.state === "charging";
+  setText("bat-power-label", charging ? "Charging at" : "Power draw");
+  setText("bat-power", formatWatts(charging ? b.charge_watts : b.discharge_watts));
+  setText("bat-adapter", b.adapter_watts ? formatWatts(b.adapter_watts) : "Unplugged");
+  setText("bat-remaining-label", charging ? "Until full" : "Time remaining");
+  setText("bat-remaining", formatDuration(charging ? b.time_to_full_secs : details.remaining_secs));
+  setText("bat-drain-rate", details.percent_per_hour > 0 ? `${details.percent_per_hour.toFixed(1)} %/h` : "--");
+  setText("bat-health", b.health_percent != null ? `${Math.round(b.health_percent)}%` : "--");
+  setText("bat-cycles", b.cycle_count != null ? String(b.cycle_count) : "--");
+  setText("bat-temperature", formatTemperature(b.temperature_c));
+}
+
+// Line chart of `series` ([key, css color]) over `points`; `max` fixes the scale (else auto)
+function drawChart(id, points, series, label, max) {
+  const canvas = document.getElementById(id);
+  if (!canvas) return;
+  const dpr = window.devicePixelRatio || 1;
+  const width = canvas.clientWidth;
+  const height = canvas.clientHeight;
+  canvas.width = width * dpr;
+  canvas.height = height * dpr;
+  const ctx = canvas.getContext("2d");
+  ctx.scale(dpr, dpr);
+  ctx.clearRect(0, 0, width, height);
+
+  const style = getComputedStyle(document.documentElement);
+  const top = 14;
+  const scale = max || Math.max(1, ...points.flatMap((p) => series.map(([key]) => p[key] || 0)));
+  ctx.strokeStyle = style.getPropertyValue("--grid");
+  ctx.fillStyle = style.getPropertyValue("--muted");
+  ctx.font = "10px -apple-system, sans-serif";
+  ctx.lineWidth = 1;
+  for (const f of [0, 0.5, 1]) {
+    const y = top + (height - top) * (1 - f);
+    ctx.beginPath();
+    ctx.moveTo(0, y);
+    ctx.lineTo(width, y);
+    ctx.stroke();
+  }
+  ctx.fillText(label(scale), 2, 10);
+  if (points.length < 2) return;
+
+  const t0 = points[0].timestamp;
+  const span = Math.max(1, points[points.length - 1].timestamp - t0);
+  for (const [key, color] of series) {
+    ctx.strokeStyle = style.getPropertyValue(color);
+    ctx.lineWidth = 1.5;
+    ctx.beginPath();
+    let drawing = false;
+    for (const p of points) {
+      const value = p[key];
+      // Break the line where the metric was not available (no battery reading yet)
+      if (value == null || value < 0) {
+        drawing = false;
+        continue;
+      }
+      const x = ((p.timestamp - t0) / span) * width;
+      const y = top + (height - top) * (1 - Math.min(value, scale) / scale);
+      if (drawing) ctx.lineTo(x, y);
+      else ctx.moveTo(x, y);
+      drawing = true;
+    }
+    ctx.stroke();
+  }
+}
+
+async function refreshBattery() {
+  const invoke = getInvoke();
+  if (!invoke) return;
+  try {
+    const [details, history] = await Promise.all([
+      invoke("get_battery_details"),
+      invoke("get_metrics_history", {
+        time_range_seconds: rangeSeconds,
+        max_display_points: BATTERY_CHART_POINTS,
+        metrics: BATTERY_METRICS,
+      }),
+    ]);
+    renderDetails(details);
+    const points = history?.points || [];
+    drawChart("bat-level-chart", points, [["battery_level", "--level"]], () => "100%", 100);
+    drawChart(
+      "bat-power-chart",
+      points,
+      [
+        ["battery_power", "--drain"],
+        ["adapter_power", "--adapter"],
+      ],
+      (max) => formatWatts(max)
+    );
+  } catch (err) {
+    console.warn("Battery details unavailable:", err);
+  }
+}
+
+async function startBattery() {
+  const invoke = getInvoke();
+  if (!invoke) {
+    setTimeout(startBattery, 100);
+    return;
+  }
+  try {
+    batteryUnits = await invoke("get_units");
+    await window.__TAURI__?.event?.listen?.("units-changed", (event) => {
+      batteryUnits = event.payload || batteryUnits;
+      refreshBattery();
+    });
+  } catch (err) {
+    console.warn("Unit preferences unavailable:", err);
+  }
+  document.getElementById("bat-low-power")?.addEventListener("change", async (event) => {
+    const toggle = event.target;
+    const enabled = toggle.checked;
+    toggle.dataset.busy = "1";
+    try {
+      renderLowPowerMode(
+        await confirmedInvoke("set_low_power_mode", { enabled }, "low_power_mode", enabled ? "enable" : "disable")
+      );
+    } catch (err) {
+      toggle.checked = !enabled;
+      setText("bat-low-power-state", String(err));
+    } finally {
+      delete toggle.dataset.busy;
+    }
+  });
+  document.getElementById("bat-range-select")?.addEventListener("change", (event) => {
+    rangeSeconds = Number(event.target.value) || rangeSeconds;
+    refreshBattery();
+  });
+  refreshBattery();
+  setInterval(() => {
+    if (!document.hidden) refreshBattery();
+  }, BATTERY_REFRESH_MS);
+}
+
+startBattery();
@@ -201,11 +201,15 @@ let invoke = null;
 let lastProcessUpdate = 0;
 let lastProcessListKey = "";
 let isWaitingForData = false; // Track if we're waiting for real data (non-zero usage)
+let pushUnlisten = null; // Set while CPU details are pushed by the backend (`subscribe`)
+let displayUnits = { temperature: "C", size: "GB", networkRate: "bytes" }; // `units` config
+let unitsUnlisten = null;
+const CPU_PUSH_INTERVAL_MS = 1000; // Same cadence as the menu bar
 
 // Make refresh available globally for refresh button
 window.refreshData = refresh;
 
-async function refresh() {
+async function refresh(pushed) {
   if (!invoke) {
     invoke = getInvoke();
     if (!invoke) {
@@ -221,7 +225,8 @@ async function refresh() {
       window._forceProcessUpdate = true;
     }
     
-    const data = await invoke("get_cpu_details");
+    // Pushed details (`metrics-push`) when subscribed; polled otherwise
+    const data = pushed && typeof pushed.usage === "number" ? pushed : await invoke("get_cpu_details");
     
     // Update battery/power with the data we just fetched
     updateBatteryPower(data);
@@ -254,12 +259,12 @@ async function refresh() {
     const tempEl = document.getElementById("temperature-value");
     const tempHint = document.getElementById("temperature-hint");
     const tempSubtext = document.getElementById("temperature-subtext");
-    const newTemp = Math.round(data.temperature);
+    const newTemp = Math.round(displayTemperature(data.temperature));
     
     if (shouldUpdateTemperature) {
       if (!data.can_read_temperature) {
         failedAttempts.temperature++;
-        const currentDisplay = tempEl.textContent.replace(/°C/g, "").trim();
+        const currentDisplay = tempEl.textContent.replace(/°[CF]/g, "").trim();
         if (currentDisplay !== "—") {
           scheduleDOMUpdate(() => {
             tempEl.innerHTML = "—";
@@ -294,7 +299,7 @@ async function refresh() {
               if (tempEl.firstChild && tempEl.firstChild.nodeType === 3) {
                 tempEl.firstChild.textContent = numberText;
               } else {
-                tempEl.innerHTML = `${numberText}<span class="metric-unit">°C</span>`;
+                tempEl.innerHTML = `${numberText}<span class="metric-unit">${temperatureSymbol()}</span>`;
               }
             });
             previousValues.temperature = 0;
@@ -306,7 +311,7 @@ async function refresh() {
           }
         } else {
           const numberText = `${newTemp}`;
-          // Get current number by extracting digits from textContent (ignoring °C)
+          // Get current number by extracting digits from textContent (ignoring the unit)
           const currentText = tempEl.textContent.match(/^\d+/) ? tempEl.textContent.match(/^\d+/)[0] : "";
           
           if (currentText !== numberText) {
@@ -315,7 +320,7 @@ async function refresh() {
               if (tempEl.firstChild && tempEl.firstChild.nodeType === 3) {
                 tempEl.firstChild.textContent = numberText;
               } else {
-                tempEl.innerHTML = `${numberText}<span class="metric-unit">°C</span>`;
+                tempEl.innerHTML = `${numberText}<span class="metric-unit">${temperatureSymbol()}</span>`;
               }
             });
             previousValues.temperature = newTemp;
@@ -697,6 +702,55 @@ async function refresh() {
       }
     }
 
+    // GPU temperature (average of GPU cluster SMC keys, cached every 20s like CPU temperature)
+    const gpuTempEl = document.getElementById("gpu-temperature");
+    if (gpuTempEl) {
+      const gpuTempText = data.can_read_gpu_temperature && data.gpu_temperature > 0
+        ? `${Math.round(displayTemperature(data.gpu_temperature))}${temperatureSymbol()}`
+        : "--";
+      if (gpuTempEl.textContent !== gpuTempText) {
+        scheduleDOMUpdate(() => {
+          gpuTempEl.textContent = gpuTempText;
+        });
+      }
+    }
+
+    // Fan speed with the "audible" cue (fanAudibleRpm) and today's audible minutes
+    const fanEl = document.getElementById("fan-speed");
+    if (fanEl) {
+      let fanText = "--";
+      if (data.can_read_fan && data.fan_rpm > 0) {
+        fanText = `${Math.round(data.fan_rpm)} rpm`;
+        if (data.fan_audible) fanText += " · audible";
+        if (data.fan_audible_minutes_today >= 1) {
+          fanText += ` (${Math.round(data.fan_audible_minutes_today)} min today)`;
+        }
+      } else if (data.can_read_fan) {
+        fanText = "Off";
+      }
+      if (fanEl.textContent !== fanText) {
+        scheduleDOMUpdate(() => {
+          fanEl.textContent = fanText;
+        });
+      }
+    }
+
+    // Build activity: compiler/build-tool load and elapsed time, else the last build's length
+    const buildEl = document.getElementById("build-activity");
+    if (buildEl) {
+      let buildText = "--";
+      if (data.build_elapsed_secs > 0 || data.build_processes > 0) {
+        buildText = `${Math.round(data.build_load || 0)}% · ${formatTime(data.build_elapsed_secs || 0)}`;
+      } else if (data.last_build_secs > 0) {
+        buildText = `Last ${formatTime(data.last_build_secs)}`;
+      }
+      if (buildEl.textContent !== buildText) {
+        scheduleDOMUpdate(() => {
+          buildEl.textContent = buildText;
+        });
+      }
+    }
+
     // STEP 7: Update process list only every 15 seconds to reduce CPU usage
     // Use document fragment to batch DOM updates and reduce WebKit reflows
     // But allow forced immediate updates when needed (e.g., after force quit, or on initial load)
@@ -706,6 +760,8 @@ async function refresh() {
     if (forceUpdate || isInitialLoad || now - lastProcessUpdate >= 15000) {
       lastProcessUpdate = now;
       window._forceProcessUpdate = false; // Reset flag after use
+      refreshContainers();
+      refreshBackupStatus();
       
       const list = document.getElementById("process-list");
       if (!list) return;
@@ -752,6 +808,8 @@ async function refresh() {
           percent.textContent = `${proc.cpu.toFixed(1)}%`;
           
           bar.appendChild(barFill);
+          const spark = processSparkline(proc.history);
+          if (spark) usage.appendChild(spark);
           usage.appendChild(bar);
           usage.appendChild(percent);
           
@@ -790,6 +848,144 @@ async function refresh() {
   }
 }
 
+// Docker containers (`dockerStats`): a section after Top Processes, refreshed with the process
+// list. Created on first data so themes need no markup; removed again when turned off.
+let lastContainerKey = "";
+async function refreshContainers() {
+  if (!invoke) return;
+  let stats;
+  try {
+    stats = await invoke("get_container_stats");
+  } catch (error) {
+    console.warn("Failed to get container stats", error);
+    return;
+  }
+  let section = document.getElementById("containers-section");
+  if (!stats.enabled) {
+    if (section) scheduleDOMUpdate(() => section.remove());
+    lastContainerKey = "";
+    return;
+  }
+  const key = stats.error
+    ? `error:${stats.error}`
+    : stats.containers
+        .map((c) => `${c.id}:${c.cpu_percent == null ? "-" : c.cpu_percent.toFixed(1)}:${c.memory_bytes}`)
+        .join("|");
+  if (section && key === lastContainerKey) return;
+  lastContainerKey = key;
+
+  const processes = document.getElementById("processes-section");
+  if (!section) {
+    if (!processes) return;
+    section = document.createElement("section");
+    section.className = processes.className;
+    section.id = "containers-section";
+    const title = document.createElement("h2");
+    title.className = "section-title";
+    title.textContent = "Containers";
+    const list = document.createElement("div");
+    list.className = "process-table";
+    list.id = "container-list";
+    section.appendChild(title);
+    section.appendChild(list);
+  }
+
+  const fragment = document.createDocumentFragment();
+  if (stats.error || stats.containers.length === 0) {
+    const msg = document.createElement("div");
+    msg.className = "process-empty";
+    msg.textContent = stats.error || "No running containers";
+    msg.style.textAlign = "center";
+    msg.style.padding = "1rem";
+    msg.style.color = "var(--text-secondary, #666)";
+    fragment.appendChild(msg);
+  } else {
+    stats.containers.forEach((c) => {
+      const row = document.createElement("div");
+      row.className = "process-row";
+      row.title = `${c.image} (${c.id})`;
+
+      const name = document.createElement("div");
+      name.className = "process-name";
+      name.textContent = c.name;
+
+      const usage = document.createElement("div");
+      usage.className = "process-usage";
+      const bar = document.createElement("div");
+      bar.className = "process-bar";
+      const barFill = document.createElement("div");
+      barFill.className = "process-bar-fill";
+      barFill.style.width = `${Math.min(100, c.cpu_percent || 0)}%`;
+      bar.appendChild(barFill);
+      const percent = document.createElement("div");
+      percent.className = "process-percent";
+      percent.textContent = c.cpu_percent == null ? "--" : `${c.cpu_percent.toFixed(1)}%`;
+      const memory = document.createElement("div");
+      memory.className = "process-percent";
+      memory.textContent = formatBytes(c.memory_bytes);
+
+      usage.appendChild(bar);
+      usage.appendChild(percent);
+      usage.appendChild(memory);
+      row.appendChild(name);
+      row.appendChild(usage);
+      fragment.appendChild(row);
+    });
+  }
+
+  scheduleDOMUpdate(() => {
+    if (!section.isConnected) processes.after(section);
+    const list = section.querySelector("#container-list");
+    list.replaceChildren();
+    list.appendChild(fragment);
+  });
+}
+
+// Time Machine (`get_backup_status`): while a backup runs, a line above Top Processes says so,
+// since it explains disk and CPU spikes. Created on demand so themes need no markup.
+let lastBackupKey = "";
+async function refreshBackupStatus() {
+  if (!invoke) return;
+  let status;
+  try {
+    status = await invoke("get_backup_status");
+  } catch (error) {
+    console.warn("Failed to get backup status", error);
+    return;
+  }
+  let line = document.getElementById("backup-status");
+  if (!status.running) {
+    if (line) scheduleDOMUpdate(() => line.remove());
+    lastBackupKey = "";
+    return;
+  }
+  const parts = ["Time Machine backup running"];
+  if (status.phase) parts.push(status.phase);
+  if (status.percent != null) parts.push(`${Math.round(status.percent)}%`);
+  if (status.time_remaining_secs != null) {
+    parts.push(`about ${Math.max(1, Math.round(status.time_remaining_secs / 60))} min left`);
+  }
+  const text = parts.join(" · ");
+  if (line && text === lastBackupKey) return;
+  lastBackupKey = text;
+
+  const processes = document.getElementById("processes-section");
+  if (!line) {
+    if (!processes) return;
+    line = document.createElement("div");
+    line.id = "backup-status";
+    line.className = "process-empty";
+    line.style.textAlign = "center";
+    line.style.padding = "0.5rem";
+    line.style.color = "var(--text-secondary, #666)";
+    line.title = "Backups read and copy a lot of data, which can explain disk and CPU spikes";
+  }
+  scheduleDOMUpdate(() => {
+    line.textContent = text;
+    if (!line.isConnected) processes.before(line);
+  });
+}
+
 // Wait for Tauri to be available
 // CRITICAL: Keep trying even after maxAttempts - Tauri might not be ready when window first opens
 function waitForTauri(callback, maxAttempts = 200) {
@@ -825,6 +1021,86 @@ function startRefresh() {
   // Once we get real data (usage > 0), continue with 1-second interval (matches menu bar)
   isWaitingForData = true;
   refreshInterval = setInterval(refresh, 1000); // 1-second polling (matches menu bar frequency)
+  startPush();
+  startTheme();
+  startUnits();
+}
+
+// Ask the backend to push CPU details to this window; polling stops once the subscription is
+// up and stays as the fallback when it fails (e.g. an older backend)
+async function startPush() {
+  const listen = window.__TAURI__?.event?.listen;
+  if (pushUnlisten || !listen || !invoke) return;
+  try {
+    pushUnlisten = await listen("metrics-push", (event) => {
+      if (event.payload?.cpu) refresh(event.payload.cpu);
+    });
+    await invoke("subscribe", { metrics: ["cpu"], intervalMs: CPU_PUSH_INTERVAL_MS });
+    isWaitingForData = false;
+    if (refreshInterval) {
+      clearInterval(refreshInterval);
+      refreshInterval = null;
+    }
+  } catch (err) {
+    console.warn("Metrics push unavailable, polling instead:", err);
+    if (pushUnlisten) {
+      pushUnlisten();
+      pushUnlisten = null;
+    }
+  }
+}
+
+// Colors from the backend (`theme` config) as CSS variables on :root, re-applied when macOS
+// switches between light and dark
+function applyTheme(theme) {
+  if (!theme?.css) return;
+  const root = document.documentElement;
+  for (const [name, value] of Object.entries(theme.css)) {
+    root.style.setProperty(name, value);
+  }
+  root.dataset.appearance = theme.appearance;
+}
+
+async function startTheme() {
+  const listen = window.__TAURI__?.event?.listen;
+  if (!invoke) return;
+  try {
+    applyTheme(await invoke("get_theme"));
+    if (listen) await listen("theme-changed", (event) => applyTheme(event.payload));
+  } catch (err) {
+    console.warn("Theme colors unavailable:", err);
+  }
+}
+
+// Readings arrive in °C and bytes; the `units` config only changes how they are shown
+function displayTemperature(celsius) {
+  return displayUnits.temperature === "F" ? celsius * 9 / 5 + 32 : celsius;
+}
+
+function temperatureSymbol() {
+  return displayUnits.temperature === "F" ? "°F" : "°C";
+}
+
+function applyUnits(units) {
+  if (!units) return;
+  displayUnits = { ...displayUnits, ...units };
+  // Rebuild the temperature (number and unit span) on the next refresh
+  const tempEl = document.getElementById("temperature-value");
+  if (tempEl) tempEl.innerHTML = "";
+  lastTemperatureUpdateMs = 0;
+}
+
+async function startUnits() {
+  const listen = window.__TAURI__?.event?.listen;
+  if (!invoke) return;
+  try {
+    applyUnits(await invoke("get_units"));
+    if (listen && !unitsUnlisten) {
+      unitsUnlisten = await listen("units-changed", (event) => applyUnits(event.payload));
+    }
+  } catch (err) {
+    console.warn("Unit preferences unavailable:", err);
+  }
 }
 
 // Initialize when DOM and Tauri are ready
@@ -909,6 +1185,8 @@ if (document.readyState === "loading") {
     // Fetch version once at startup (no polling)
     fetchAppVersion().then((v) => {
       showFirstLaunchTip();
+      showConfigIssuesBanner();
+      watchConfigChanges();
       checkForAppUpdate(v);
     });
     initRingGauges();
@@ -916,6 +1194,8 @@ if (document.readyState === "loading") {
   });
 } else {
   showFirstLaunchTip();
+  showConfigIssuesBanner();
+  watchConfigChanges();
   (async () => {
     try {
       const inv = typeof getInvoke === "function" ? getInvoke() : null;
@@ -929,7 +1209,7 @@ if (document.readyState === "loading") {
   init();
 }
 
-/** Shared glass styles for first-launch tip + update banner. */
+/** Shared glass styles for first-launch tip, update and config banners. */
 function ensureAppBannerStyles() {
   if (document.getElementById("mac-stats-banner-styles")) return;
   const style = document.createElement("style");
@@ -948,6 +1228,7 @@ function ensureAppBannerStyles() {
     }
     #mac-stats-banners > * { pointer-events: auto; }
     #mac-stats-first-launch-tip,
+    #mac-stats-config-banner,
     #mac-stats-update-banner {
       display: flex;
       align-items: flex-start;
@@ -973,6 +1254,7 @@ function ensureAppBannerStyles() {
       border-color: color-mix(in srgb, var(--accent, #8bb4e8) 35%, transparent);
     }
     #mac-stats-first-launch-tip .tip-glyph,
+    #mac-stats-config-banner .tip-glyph,
     #mac-stats-update-banner .tip-glyph {
       flex-shrink: 0;
       width: 28px;
@@ -989,13 +1271,26 @@ function ensureAppBannerStyles() {
     #mac-stats-update-banner .tip-glyph {
       color: color-mix(in srgb, var(--accent, #007aff) 70%, var(--text, #1d1d1f));
     }
+    #mac-stats-config-banner {
+      background: color-mix(in srgb, #ff9f0a 18%, var(--panel, rgba(255,255,255,0.72)));
+      border-color: color-mix(in srgb, #ff9f0a 35%, transparent);
+    }
+    #mac-stats-config-banner .tip-glyph {
+      color: #c76a00;
+    }
+    #mac-stats-config-banner ul {
+      margin: 2px 0 0;
+      padding-left: 16px;
+    }
     #mac-stats-first-launch-tip .tip-glyph svg,
+    #mac-stats-config-banner .tip-glyph svg,
     #mac-stats-update-banner .tip-glyph svg {
       width: 14px;
       height: 14px;
       display: block;
     }
     #mac-stats-first-launch-tip .tip-body,
+    #mac-stats-config-banner .tip-body,
     #mac-stats-update-banner .tip-body {
       flex: 1;
       min-width: 0;
@@ -1004,6 +1299,7 @@ function ensureAppBannerStyles() {
       padding-top: 1px;
     }
     #mac-stats-first-launch-tip .tip-title,
+    #mac-stats-config-banner .tip-title,
     #mac-stats-update-banner .tip-title {
       font-size: 13px;
       font-weight: 650;
@@ -1011,17 +1307,20 @@ function ensureAppBannerStyles() {
       color: var(--text, rgba(12, 12, 16, 0.88));
     }
     #mac-stats-first-launch-tip .tip-copy,
+    #mac-stats-config-banner .tip-copy,
     #mac-stats-update-banner .tip-copy {
       font-size: 12px;
       line-height: 1.45;
       color: var(--muted, rgba(12, 12, 16, 0.55));
     }
     #mac-stats-first-launch-tip .tip-copy strong,
+    #mac-stats-config-banner .tip-copy strong,
     #mac-stats-update-banner .tip-copy strong {
       font-weight: 600;
       color: var(--text, rgba(12, 12, 16, 0.78));
     }
     #mac-stats-first-launch-tip code,
+    #mac-stats-config-banner code,
     #mac-stats-update-banner code {
       font-family: ui-monospace, "SF Mono", Menlo, monospace;
       font-size: 11px;
@@ -1032,6 +1331,7 @@ function ensureAppBannerStyles() {
       color: var(--text, rgba(12, 12, 16, 0.72));
     }
     #mac-stats-first-launch-tip .tip-dismiss,
+    #mac-stats-config-banner .tip-dismiss,
     #mac-stats-update-banner .tip-dismiss {
       flex-shrink: 0;
       width: 24px;
@@ -1050,6 +1350,7 @@ function ensureAppBannerStyles() {
       transition: background 0.15s ease, color 0.15s ease, border-color 0.15s ease;
     }
     #mac-stats-first-launch-tip .tip-dismiss:hover,
+    #mac-stats-config-banner .tip-dismiss:hover,
     #mac-stats-update-banner .tip-dismiss:hover {
       color: var(--text, rgba(12, 12, 16, 0.8));
       background: rgba(255, 255, 255, 0.55);
@@ -1121,6 +1422,75 @@ function showFirstLaunchTip() {
   getAppBannerHost().prepend(tip);
 }
 
+/** Re-check the config banner whenever config.json is edited (`config-changed`). */
+let configChangedUnlisten = null;
+async function watchConfigChanges() {
+  const listen = window.__TAURI__?.event?.listen;
+  if (!listen || configChangedUnlisten) return;
+  configChangedUnlisten = await listen("config-changed", () => {
+    document.getElementById("mac-stats-config-banner")?.remove();
+    const host = document.getElementById("mac-stats-banners");
+    if (host && !host.children.length) host.remove();
+    showConfigIssuesBanner();
+  });
+}
+
+/** Errors in ~/.mac-stats/config.json (wrong types, out-of-range values, invalid JSON). */
+async function showConfigIssuesBanner() {
+  const inv = typeof getInvoke === "function" ? getInvoke() : null;
+  if (!inv) return;
+  let report;
+  try {
+    report = await inv("get_config_issues");
+  } catch (_) {
+    return;
+  }
+  const errors = (report && report.errors) || [];
+  if (!errors.length || document.getElementById("mac-stats-config-banner")) return;
+  const describe = (i) => (i.key ? `${i.key}: ${i.message}` : i.message);
+  // Dismissed until the errors change
+  const dismissKey = `mac_stats_config_issues_dismissed_${errors.map(describe).join("|")}`;
+  try {
+    if (sessionStorage.getItem(dismissKey) === "1") return;
+  } catch (_) {}
+
+  const banner = document.createElement("div");
+  banner.id = "mac-stats-config-banner";
+  banner.setAttribute("role", "alert");
+  banner.innerHTML = `
+    <div class="tip-glyph" aria-hidden="true">
+      <svg viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="1.5" stroke-linecap="round" stroke-linejoin="round">
+        <path d="M12 4l9 16H3z"></path>
+        <path d="M12 10v4"></path>
+        <path d="M12 17v.01"></path>
+      </svg>
+    </div>
+    <div class="tip-body">
+      <div class="tip-title">config.json has ${errors.length === 1 ? "an error" : `${errors.length} errors`}</div>
+      <div class="tip-copy">
+        <ul></ul>
+        Run <code>mac_stats config validate</code> for the full list.
+      </div>
+    </div>
+    <button type="button" class="tip-dismiss" title="Dismiss" aria-label="Dismiss">×</button>
+  `;
+  const list = banner.querySelector("ul");
+  errors.slice(0, 3).forEach((issue) => {
+    const li = document.createElement("li");
+    li.textContent = describe(issue);
+    list.appendChild(li);
+  });
+  banner.querySelector("button").addEventListener("click", () => {
+    try {
+      sessionStorage.setItem(dismissKey, "1");
+    } catch (_) {}
+    banner.remove();
+    const host = document.getElementById("mac-stats-banners");
+    if (host && !host.children.length) host.remove();
+  });
+  getAppBannerHost().prepend(banner);
+}
+
 function parseSemverParts(v) {
   const s = String(v || "").replace(/^v/i, "").split(/[+-]/)[0];
   return s.split(".").map((n) => parseInt(n, 10) || 0);
@@ -1218,7 +1588,7 @@ document.addEventListener("visibilitychange", () => {
     if (invoke) {
       // Tauri is ready - refresh immediately and start interval
       refresh(); // Immediate refresh
-      if (!refreshInterval) {
+      if (!refreshInterval && !pushUnlisten) {
         startRefresh();
       }
     } else {
@@ -1229,15 +1599,44 @@ document.addEventListener("visibilitychange", () => {
 });
 
 // Process details popover
+// Inline SVG sparkline of a process's CPU samples (last minute, oldest first)
+function processSparkline(history) {
+  if (!Array.isArray(history) || history.length < 2) return null;
+  const width = 36;
+  const height = 12;
+  const max = Math.max(100, ...history);
+  const step = width / (history.length - 1);
+  const points = history
+    .map((v, i) => `${(i * step).toFixed(1)},${(height - (Math.max(0, v) / max) * height).toFixed(1)}`)
+    .join(" ");
+  const ns = "http://www.w3.org/2000/svg";
+  const svg = document.createElementNS(ns, "svg");
+  svg.setAttribute("class", "process-sparkline");
+  svg.setAttribute("width", String(width));
+  svg.setAttribute("height", String(height));
+  svg.setAttribute("viewBox", `0 0 ${width} ${height}`);
+  svg.style.marginRight = "4px";
+  svg.style.flexShrink = "0";
+  const line = document.createElementNS(ns, "polyline");
+  line.setAttribute("points", points);
+  line.setAttribute("fill", "none");
+  line.setAttribute("stroke", "currentColor");
+  line.setAttribute("stroke-width", "1");
+  line.setAttribute("opacity", "0.7");
+  svg.appendChild(line);
+  return svg;
+}
+
 let processDetailsModal = null;
 let currentProcessPid = null;
 let processDetailsRefreshInterval = null;
 
 function formatBytes(bytes) {
   if (bytes === 0) return "0 B";
-  const k = 1024;
-  const sizes = ["B", "KB", "MB", "GB"];
-  const i = Math.floor(Math.log(bytes) / Math.log(k));
+  const binary = displayUnits.size === "GiB";
+  const k = binary ? 1024 : 1000;
+  const sizes = binary ? ["B", "KiB", "MiB", "GiB", "TiB"] : ["B", "KB", "MB", "GB", "TB"];
+  const i = Math.min(Math.floor(Math.log(bytes) / Math.log(k)), sizes.length - 1);
   return Math.round((bytes / Math.pow(k, i)) * 100) / 100 + " " + sizes[i];
 }
 
@@ -1413,19 +1812,7 @@ function populateProcessDetailsBody(body, details, pid) {
       forceQuitBtn.parentNode.replaceChild(newBtn, forceQuitBtn);
       
       newBtn.addEventListener("click", async () => {
-        // WKWebView: window.confirm()/alert() are unreliable — two-click confirm instead.
-        if (newBtn.dataset.confirmArmed !== "1") {
-          newBtn.dataset.confirmArmed = "1";
-          newBtn.textContent = "Click again to confirm Force Quit";
-          setTimeout(() => {
-            if (newBtn.dataset.confirmArmed === "1") {
-              newBtn.dataset.confirmArmed = "0";
-              newBtn.textContent = "Force Quit Process";
-            }
-          }, 4000);
-          return;
-        }
-        
+        // The backend asks for confirmation in a native dialog (see confirmedInvoke in confirm.js)
         try {
           if (!invoke) {
             invoke = getInvoke();
@@ -1435,7 +1822,7 @@ function populateProcessDetailsBody(body, details, pid) {
             }
           }
           
-          await invoke("force_quit_process", { pid });
+          await confirmedInvoke("force_quit_process", { pid }, "force_quit", pid);
           
           // Clear refresh interval and close modal
           if (processDetailsRefreshInterval) {
@@ -1452,8 +1839,6 @@ function populateProcessDetailsBody(body, details, pid) {
           }
         } catch (error) {
           console.error("Failed to force quit process:", error);
-          newBtn.dataset.confirmArmed = "0";
-          newBtn.textContent = "Force Quit Process";
         }
       });
     }
@@ -1598,7 +1983,15 @@ function updateBatteryPower(cpuDetails) {
     const isCharging = cpuDetails.is_charging || false;
     
     if (batteryLevel) batteryLevel.textContent = `${level.toFixed(0)}%`;
-    if (batteryStatus) batteryStatus.textContent = isCharging ? 'Charging' : 'Discharging';
+    // Drain rate over the last 10 minutes (backend sliding window); 0 until enough samples
+    const drainRate = cpuDetails.battery_drain_percent_per_hour || 0;
+    const drainWatts = cpuDetails.battery_drain_watts || 0;
+    let statusText = isCharging ? 'Charging' : 'Discharging';
+    if (!isCharging && drainRate > 0) {
+      statusText += ` · ${drainRate.toFixed(1)}%/h`;
+      if (drainWatts > 0) statusText += ` (${drainWatts.toFixed(1)} W)`;
+    }
+    if (batteryStatus) batteryStatus.textContent = statusText;
     
     // Update battery icon SVG for charging state
     if (batteryIcon && batteryIcon.tagName === 'svg') {
@@ -1638,9 +2031,12 @@ function updateBatteryPower(cpuDetails) {
       }
     }
     
-    if (timeRemaining && !isCharging && level > 0 && totalPower > 0) {
-      const hours = (level / 100) * 10 / (totalPower / 20); // Simplified estimate
-      timeRemaining.textContent = `~${hours.toFixed(1)}h remaining`;
+    // Projection at the current drain rate (computed in the backend)
+    const remainingSecs = cpuDetails.battery_remaining_secs || 0;
+    if (timeRemaining && !isCharging && remainingSecs > 0) {
+      const hours = Math.floor(remainingSecs / 3600);
+      const minutes = Math.floor((remainingSecs % 3600) / 60);
+      timeRemaining.textContent = hours > 0 ? `~${hours}h ${minutes}m remaining` : `~${minutes}m remaining`;
     } else if (timeRemaining) {
       timeRemaining.textContent = '';
     }
@@ -3960,6 +4356,9 @@ window.addEventListener('beforeunload', () => {
   if (refreshInterval) {
     clearInterval(refreshInterval);
   }
+  if (pushUnlisten && invoke) {
+    invoke("unsubscribe").catch(() => {});
+  }
   if (processDetailsRefreshInterval) {
     clearInterval(processDetailsRefreshInterval);
     processDetailsRefreshInterval = null;
@@ -509,7 +509,8 @@ async function applyDownloadsOrganizerSettings() {
     if (pr) patch.path = pr;
     if (statusEl) statusEl.textContent = 'Saving…';
     try {
-        await invoke('set_downloads_organizer_settings', { patch });
+        await window.confirmedInvoke(
+            'set_downloads_organizer_settings', { patch }, 'config_write', 'downloadsOrganizer');
         if (statusEl) statusEl.textContent = 'Saved. Config is read on each run (no restart needed).';
         loadSettingsDownloads();
     } catch (err) {
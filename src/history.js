@@ -33,6 +33,8 @@
   };
 
   let currentTimeRange = '5m'; // Default to last 5 minutes
+  // When set, the usage chart overlays the same window this many seconds earlier (dashed)
+  let comparisonOffsetSeconds = null;
 
   // Get colors from CSS variables or computed styles
   function getColors() {
@@ -277,25 +279,73 @@
       return { x, y, value, timestamp: buffer.timestamps[index] };
     });
 
+    // Contiguous runs of points; a gap (sleep, app not running) starts a new run so the
+    // chart breaks there instead of drawing a line across the missing hours
+    const breaks = buffer.breaks || [];
+    const segments = [];
+    points.forEach((point, i) => {
+      if (i === 0 || breaks[i]) segments.push([]);
+      segments[segments.length - 1].push(i);
+    });
+
+    // Draw min/max band for downsampled ranges (short spikes stay visible)
+    const toY = value => height - ((value - minValue) / range) * height;
+    if (buffer.highs && buffer.lows && buffer.highs.some((h, i) => h !== buffer.points[i])) {
+      ctx.beginPath();
+      segments.forEach(segment => {
+        segment.forEach((i, n) => {
+          if (n === 0) ctx.moveTo(points[i].x, toY(buffer.highs[i]));
+          else ctx.lineTo(points[i].x, toY(buffer.highs[i]));
+        });
+        for (let n = segment.length - 1; n >= 0; n--) {
+          ctx.lineTo(points[segment[n]].x, toY(buffer.lows[segment[n]]));
+        }
+        ctx.closePath();
+      });
+      ctx.fillStyle = colors.fill;
+      ctx.fill();
+    }
+
     // Draw filled area
     ctx.beginPath();
-    ctx.moveTo(points[0].x, height);
-    points.forEach(point => ctx.lineTo(point.x, point.y));
-    ctx.lineTo(points[points.length - 1].x, height);
-    ctx.closePath();
+    segments.forEach(segment => {
+      ctx.moveTo(points[segment[0]].x, height);
+      segment.forEach(i => ctx.lineTo(points[i].x, points[i].y));
+      ctx.lineTo(points[segment[segment.length - 1]].x, height);
+      ctx.closePath();
+    });
     ctx.fillStyle = colors.fill;
     ctx.fill();
 
     // Draw line
     ctx.beginPath();
-    ctx.moveTo(points[0].x, points[0].y);
-    points.forEach(point => ctx.lineTo(point.x, point.y));
+    segments.forEach(segment => {
+      ctx.moveTo(points[segment[0]].x, points[segment[0]].y);
+      segment.forEach(i => ctx.lineTo(points[i].x, points[i].y));
+    });
     ctx.strokeStyle = colors.line;
     ctx.lineWidth = 1.5;
     ctx.lineCap = 'round';
     ctx.lineJoin = 'round';
     ctx.stroke();
 
+    // Comparison overlay: the same window earlier (see setComparison), dashed
+    if (buffer.comparison && buffer.comparison.length > 1) {
+      ctx.save();
+      ctx.beginPath();
+      buffer.comparison.forEach((p, i) => {
+        const x = p.fraction * width;
+        const y = toY(p.value);
+        if (i === 0) ctx.moveTo(x, y); else ctx.lineTo(x, y);
+      });
+      ctx.setLineDash([3, 3]);
+      ctx.globalAlpha = 0.6;
+      ctx.strokeStyle = colors.line;
+      ctx.lineWidth = 1;
+      ctx.stroke();
+      ctx.restore();
+    }
+
     // Store points for hover detection
     canvas.pointsData = points;
   }
@@ -336,12 +386,16 @@
     canvas.addEventListener('mouseleave', hideTooltip);
   }
 
+  // Metrics the charts draw (history also holds GPU, RAM, disk, power, battery, network and disk I/O)
+  const CHART_METRICS = ['temperature', 'cpu', 'frequency'];
+
   // Fetch history from backend
   async function fetchHistoryFromBackend(timeRangeSeconds, maxPoints) {
     try {
       const result = await tauriInvoke('get_metrics_history', {
         time_range_seconds: timeRangeSeconds,
-        max_display_points: maxPoints
+        max_display_points: maxPoints,
+        metrics: CHART_METRICS
       });
 
       return result;
@@ -377,25 +431,45 @@
       lastPoint: result.points[result.points.length - 1]
     });
 
-    // Extract data by metric
-    const temperatureData = result.points.map(p => ({ value: p.temperature, timestamp: p.timestamp }));
-    const usageData = result.points.map(p => ({ value: p.cpu, timestamp: p.timestamp })); // CPU usage
-    const frequencyData = result.points.map(p => ({ value: p.frequency, timestamp: p.timestamp }));
+    // Extract data by metric (downsampled points carry bands: { metric: { min, max } })
+    const withBand = (p, key) => ({
+      value: p[key],
+      timestamp: p.timestamp,
+      low: p.bands ? p.bands[key].min : p[key],
+      high: p.bands ? p.bands[key].max : p[key]
+    });
+    const temperatureData = result.points.map(p => withBand(p, 'temperature'));
+    const usageData = result.points.map(p => withBand(p, 'cpu')); // CPU usage
+    const frequencyData = result.points.map(p => withBand(p, 'frequency'));
+
+    // A point starts a new line segment when a gap (sleep, app not running) lies before it
+    const gaps = result.gaps || [];
+    const breaks = result.points.map((p, i) => i > 0 &&
+      gaps.some(g => g.end > result.points[i - 1].timestamp && g.start < p.timestamp));
 
     // Update buffers
     dataBuffers.temperature.points = temperatureData.map(d => d.value);
     dataBuffers.temperature.timestamps = temperatureData.map(d => d.timestamp);
-    dataBuffers.temperature.max = Math.max(100, ...temperatureData.map(d => d.value || 0));
+    dataBuffers.temperature.lows = temperatureData.map(d => d.low);
+    dataBuffers.temperature.highs = temperatureData.map(d => d.high);
+    dataBuffers.temperature.breaks = breaks;
+    dataBuffers.temperature.max = Math.max(100, ...temperatureData.map(d => d.high || 0));
     dataBuffers.temperature.min = Math.min(0, ...temperatureData.map(d => d.value || 0));
 
     dataBuffers.usage.points = usageData.map(d => d.value);
     dataBuffers.usage.timestamps = usageData.map(d => d.timestamp);
-    dataBuffers.usage.max = Math.max(100, ...usageData.map(d => d.value || 0));
+    dataBuffers.usage.lows = usageData.map(d => d.low);
+    dataBuffers.usage.highs = usageData.map(d => d.high);
+    dataBuffers.usage.breaks = breaks;
+    dataBuffers.usage.max = Math.max(100, ...usageData.map(d => d.high || 0));
     dataBuffers.usage.min = Math.min(0, ...usageData.map(d => d.value || 0));
 
     dataBuffers.frequency.points = frequencyData.map(d => d.value);
     dataBuffers.frequency.timestamps = frequencyData.map(d => d.timestamp);
-    dataBuffers.frequency.max = Math.max(4.0, ...frequencyData.map(d => d.value || 0));
+    dataBuffers.frequency.lows = frequencyData.map(d => d.low);
+    dataBuffers.frequency.highs = frequencyData.map(d => d.high);
+    dataBuffers.frequency.breaks = breaks;
+    dataBuffers.frequency.max = Math.max(4.0, ...frequencyData.map(d => d.high || 0));
     dataBuffers.frequency.min = Math.min(0, ...frequencyData.map(d => d.value || 0));
 
     // Redraw charts (temperature only every 3s; usage and frequency every cycle)
@@ -410,6 +484,11 @@
       frequencyPoints: dataBuffers.frequency.points.length
     });
     
+    if (shouldRedrawTemperature) {
+      updateChartStats(timeRangeSeconds);
+      await updateComparison(timeRangeSeconds);
+    }
+
     Object.keys(canvases).forEach(metric => {
       if (metric === 'temperature' && !shouldRedrawTemperature) return;
       if (canvases[metric]) {
@@ -420,6 +499,49 @@
     });
   }
 
+  // Percentile summary as chart tooltips (averages hide the spikes)
+  async function updateChartStats(timeRangeSeconds) {
+    let stats;
+    try {
+      stats = await tauriInvoke('get_history_stats', { timeRangeSeconds });
+    } catch (error) {
+      console.warn('[history] Failed to fetch history stats:', error);
+      return;
+    }
+    const describe = (s, unit, digits) => {
+      if (!s) return '';
+      const f = v => v.toFixed(digits) + unit;
+      return `p50 ${f(s.p50)} · p95 ${f(s.p95)} · p99 ${f(s.p99)} · max ${f(s.max)}`;
+    };
+    if (canvases.usage) canvases.usage.title = describe(stats.cpu, '%', 0);
+    if (canvases.temperature) canvases.temperature.title = describe(stats.temperature, '°C', 0);
+  }
+
+  // CPU usage of the same window `comparisonOffsetSeconds` earlier, aligned by offset
+  async function updateComparison(timeRangeSeconds) {
+    if (!comparisonOffsetSeconds) {
+      dataBuffers.usage.comparison = null;
+      return;
+    }
+    const end = Math.floor(Date.now() / 1000);
+    const start = end - timeRangeSeconds;
+    let result;
+    try {
+      result = await tauriInvoke('compare_history', {
+        rangeA: { start, end },
+        rangeB: { start: start - comparisonOffsetSeconds, end: end - comparisonOffsetSeconds },
+        maxPoints: HISTORY_POINTS
+      });
+    } catch (error) {
+      console.warn('[history] Failed to fetch history comparison:', error);
+      dataBuffers.usage.comparison = null;
+      return;
+    }
+    dataBuffers.usage.comparison = result.points
+      .filter(p => p.b)
+      .map(p => ({ fraction: p.offset_seconds / timeRangeSeconds, value: p.b.cpu }));
+  }
+
   // Set time range and update charts
   async function setTimeRange(timeRange) {
     if (TIME_RANGES[timeRange]) {
@@ -476,6 +598,12 @@
     // New backend-integrated API
     fetchAndUpdateHistory: updateChartsFromBackend,
     setTimeRange: setTimeRange,
+    // Overlay the same window earlier, e.g. 86400 (yesterday) or 604800 (last week); null to clear
+    setComparison: (offsetSeconds) => {
+      comparisonOffsetSeconds = offsetSeconds || null;
+      lastTemperatureDrawMs = 0;
+      return updateChartsFromBackend();
+    },
 
     // Initialize charts (call on page load)
     init: () => {
@@ -512,6 +640,21 @@
       // Refresh every 2 seconds
       setInterval(updateChartsFromBackend, 2000);
       
+      // Drop cached chart data when the backend reports memory pressure (refetched next cycle)
+      if (window.__TAURI__?.event?.listen) {
+        window.__TAURI__.event.listen('memory-pressure', (event) => {
+          console.log('[history] Memory pressure, dropping chart buffers:', event.payload?.level);
+          Object.values(dataBuffers).forEach(buffer => {
+            buffer.points = [];
+            buffer.timestamps = [];
+            buffer.lows = [];
+            buffer.highs = [];
+            buffer.breaks = [];
+            buffer.comparison = null;
+          });
+        });
+      }
+
       // Handle window resize
       let resizeTimeout;
       window.addEventListener('resize', () => {
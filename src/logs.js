@@ -0,0 +1,140 @@
+// Log viewer window (see `ui/log_window.rs`): parsed `debug.log` lines from `read_log_entries`
+// with level/module/tag/text filters. While "Follow" is on, new lines are appended every second
+// and the view sticks to the bottom. Clicking a module or tag filters by it.
+const LOG_FOLLOW_MS = 1000;
+const LOG_MAX_ROWS = 5000;
+const LOG_FILTER_DEBOUNCE_MS = 250;
+
+let logOffset = null;
+let logRows = 0;
+let logBusy = false;
+let logGeneration = 0;
+let filterTimer = null;
+
+function getInvoke() {
+  return window.__TAURI__?.core?.invoke || window.__TAURI_INTERNALS__?.invoke?.bind(window.__TAURI_INTERNALS__) || null;
+}
+
+function currentFilter() {
+  const value = (id) => document.getElementById(id).value.trim() || null;
+  return {
+    level: value("log-level"),
+    module: value("log-module"),
+    tag: value("log-tag"),
+    text: value("log-text"),
+  };
+}
+
+function formatTime(timestamp) {
+  if (!timestamp) return "";
+  const date = new Date(timestamp);
+  return Number.isNaN(date.getTime()) ? timestamp : date.toLocaleTimeString([], { hour12: false });
+}
+
+function cell(className, text) {
+  const td = document.createElement("td");
+  td.className = className;
+  td.textContent = text;
+  return td;
+}
+
+function setFilterField(id, value) {
+  document.getElementById(id).value = value;
+  reloadLog();
+}
+
+function renderEntry(entry) {
+  const tr = document.createElement("tr");
+  if (entry.level) tr.className = `level-${entry.level}`;
+  const module = cell("module", entry.module);
+  module.title = entry.module;
+  if (entry.module) module.addEventListener("click", () => setFilterField("log-module", entry.module));
+  const tag = cell("tag", entry.tag);
+  if (entry.tag) tag.addEventListener("click", () => setFilterField("log-tag", entry.tag));
+  const message = cell("message", entry.message);
+  if (entry.data) {
+    const data = document.createElement("div");
+    data.className = "data";
+    data.textContent = JSON.stringify(entry.data);
+    message.append(data);
+  }
+  tr.append(cell("time", formatTime(entry.timestamp)), cell("level", entry.level), module, tag, message);
+  return tr;
+}
+
+async function refreshLog() {
+  const invoke = getInvoke();
+  if (!invoke || logBusy) return;
+  logBusy = true;
+  const generation = logGeneration;
+  try {
+    const scroller = document.getElementById("log-scroll");
+    const atBottom = scroller.scrollHeight - scroller.scrollTop - scroller.clientHeight < 40;
+    const result = await invoke("read_log_entries", { offset: logOffset, filter: currentFilter(), limit: LOG_MAX_ROWS });
+    if (generation !== logGeneration) return; // filters changed while reading
+    const tbody = document.getElementById("log-rows");
+    if (result.reset) {
+      tbody.replaceChildren();
+      logRows = 0;
+    }
+    if (result.entries.length) {
+      const fragment = document.createDocumentFragment();
+      result.entries.forEach((entry) => fragment.append(renderEntry(entry)));
+      tbody.append(fragment);
+      logRows += result.entries.length;
+      while (logRows > LOG_MAX_ROWS && tbody.firstChild) {
+        tbody.firstChild.remove();
+        logRows -= 1;
+      }
+    }
+    logOffset = result.offset;
+    document.getElementById("log-empty").hidden = logRows > 0;
+    document.getElementById("log-status").textContent = `${logRows} lines · ${result.path}`;
+    if (document.getElementById("log-follow").checked && (atBottom || result.reset)) {
+      scroller.scrollTop = scroller.scrollHeight;
+    }
+  } catch (err) {
+    document.getElementById("log-status").textContent = `Log unavailable: ${err}`;
+  } finally {
+    logBusy = false;
+  }
+}
+
+// Filters apply to the whole tail, so start over from the end of the file
+function reloadLog() {
+  logGeneration += 1;
+  logOffset = null;
+  logRows = 0;
+  document.getElementById("log-rows").replaceChildren();
+  logBusy = false;
+  refreshLog().then(() => {
+    const scroller = document.getElementById("log-scroll");
+    scroller.scrollTop = scroller.scrollHeight;
+  });
+}
+
+function startLogViewer() {
+  if (!getInvoke()) {
+    setTimeout(startLogViewer, 100);
+    return;
+  }
+  document.getElementById("log-level").addEventListener("change", reloadLog);
+  ["log-module", "log-tag", "log-text"].forEach((id) => {
+    document.getElementById(id).addEventListener("input", () => {
+      clearTimeout(filterTimer);
+      filterTimer = setTimeout(reloadLog, LOG_FILTER_DEBOUNCE_MS);
+    });
+  });
+  document.getElementById("log-follow").addEventListener("change", (event) => {
+    if (event.target.checked) refreshLog();
+  });
+  document.getElementById("log-open").addEventListener("click", () => {
+    getInvoke()("open_debug_log").catch((err) => console.warn("Could not open log:", err));
+  });
+  reloadLog();
+  setInterval(() => {
+    if (!document.hidden && document.getElementById("log-follow").checked) refreshLog();
+  }, LOG_FOLLOW_MS);
+}
+
+startLogViewer();
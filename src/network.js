@@ -0,0 +1,203 @@
+// Network window (see `ui/network_window.rs`): session totals, a live per-interface chart and
+// top talkers from `get_network_details` / `get_network_history`. Rates and sizes follow the
+// `units` preference.
+const NETWORK_REFRESH_MS = 2000;
+
+let networkUnits = { size: "GB", networkRate: "bytes" };
+let selectedInterface = "";
+
+function getInvoke() {
+  return window.__TAURI__?.core?.invoke || window.__TAURI_INTERNALS__?.invoke?.bind(window.__TAURI_INTERNALS__) || null;
+}
+
+function scaled(value, step, units) {
+  let v = Math.max(0, value || 0);
+  let i = 0;
+  while (v >= step && i < units.length - 1) {
+    v /= step;
+    i++;
+  }
+  return `${v >= 10 || i === 0 ? Math.round(v) : v.toFixed(1)} ${units[i]}`;
+}
+
+function formatRate(bytesPerSec) {
+  return networkUnits.networkRate === "bits"
+    ? scaled(bytesPerSec * 8, 1000, ["bit/s", "kbit/s", "Mbit/s", "Gbit/s"])
+    : scaled(bytesPerSec, 1000, ["B/s", "KB/s", "MB/s", "GB/s"]);
+}
+
+function formatSize(bytes) {
+  return networkUnits.size === "GiB"
+    ? scaled(bytes, 1024, ["B", "KiB", "MiB", "GiB", "TiB"])
+    : scaled(bytes, 1000, ["B", "KB", "MB", "GB", "TB"]);
+}
+
+function setText(id, text) {
+  const el = document.getElementById(id);
+  if (el && el.textContent !== text) el.textContent = text;
+}
+
+function fillTable(id, rows, columns, emptyText) {
+  const body = document.getElementById(id);
+  if (!body) return;
+  body.replaceChildren();
+  if (!rows.length) {
+    const tr = body.insertRow();
+    const td = tr.insertCell();
+    td.colSpan = columns;
+    td.className = "empty";
+    td.textContent = emptyText;
+    return;
+  }
+  for (const cells of rows) {
+    const tr = body.insertRow();
+    cells.forEach((text, i) => {
+      const td = tr.insertCell();
+      td.textContent = text;
+      if (i > 0) td.className = "num";
+    });
+  }
+}
+
+function renderDetails(details) {
+  setText("net-rx-rate", formatRate(details.total_rx_rate));
+  setText("net-tx-rate", formatRate(details.total_tx_rate));
+  setText("net-session-rx", formatSize(details.session_rx));
+  setText("net-session-tx", formatSize(details.session_tx));
+  fillTable(
+    "net-interfaces",
+    details.interfaces.map((i) => [
+      i.name,
+      formatRate(i.rx_rate),
+      formatRate(i.tx_rate),
+      formatSize(i.session_rx),
+      formatSize(i.session_tx),
+    ]),
+    5,
+    "No traffic yet"
+  );
+  fillTable(
+    "net-talkers",
+    details.top_talkers.map((t) => [t.name, String(t.pid), formatRate(t.rx_rate), formatRate(t.tx_rate)]),
+    4,
+    details.top_talkers_error || "No process is transferring data"
+  );
+}
+
+// Interface picker: "All interfaces" plus every active one; keeps the current choice
+function updateInterfaceOptions(names) {
+  const select = document.getElementById("net-interface-select");
+  if (!select) return;
+  const wanted = ["", ...names];
+  const current = Array.from(select.options).map((o) => o.value);
+  if (current.join("\n") !== wanted.join("\n")) {
+    select.replaceChildren(
+      ...wanted.map((name) => new Option(name || "All interfaces", name))
+    );
+  }
+  if (!wanted.includes(selectedInterface)) selectedInterface = "";
+  select.value = selectedInterface;
+}
+
+// Sum the selected interfaces per timestamp
+function chartSeries(history) {
+  const names = selectedInterface ? [selectedInterface] : Object.keys(history);
+  const byTime = new Map();
+  for (const name of names) {
+    for (const p of history[name] || []) {
+      const point = byTime.get(p.timestamp) || { timestamp: p.timestamp, rx: 0, tx: 0 };
+      point.rx += p.rx;
+      point.tx += p.tx;
+      byTime.set(p.timestamp, point);
+    }
+  }
+  return Array.from(byTime.values()).sort((a, b) => a.timestamp - b.timestamp);
+}
+
+function drawChart(points) {
+  const canvas = document.getElementById("net-chart");
+  if (!canvas) return;
+  const dpr = window.devicePixelRatio || 1;
+  const width = canvas.clientWidth;
+  const height = canvas.clientHeight;
+  canvas.width = width * dpr;
+  canvas.height = height * dpr;
+  const ctx = canvas.getContext("2d");
+  ctx.scale(dpr, dpr);
+  ctx.clearRect(0, 0, width, height);
+
+  const style = getComputedStyle(document.documentElement);
+  const top = 14;
+  const max = Math.max(1, ...points.map((p) => Math.max(p.rx, p.tx)));
+  ctx.strokeStyle = style.getPropertyValue("--grid");
+  ctx.fillStyle = style.getPropertyValue("--muted");
+  ctx.font = "10px -apple-system, sans-serif";
+  ctx.lineWidth = 1;
+  for (const f of [0, 0.5, 1]) {
+    const y = top + (height - top) * (1 - f);
+    ctx.beginPath();
+    ctx.moveTo(0, y);
+    ctx.lineTo(width, y);
+    ctx.stroke();
+  }
+  ctx.fillText(formatRate(max), 2, 10);
+  if (points.length < 2) return;
+
+  const t0 = points[0].timestamp;
+  const span = Math.max(1, points[points.length - 1].timestamp - t0);
+  for (const [key, color] of [["rx", "--rx"], ["tx", "--tx"]]) {
+    ctx.strokeStyle = style.getPropertyValue(color);
+    ctx.lineWidth = 1.5;
+    ctx.beginPath();
+    points.forEach((p, i) => {
+      const x = ((p.timestamp - t0) / span) * width;
+      const y = top + (height - top) * (1 - p[key] / max);
+      if (i === 0) ctx.moveTo(x, y);
+      else ctx.lineTo(x, y);
+    });
+    ctx.stroke();
+  }
+}
+
+async function refreshNetwork() {
+  const invoke = getInvoke();
+  if (!invoke) return;
+  try {
+    const [details, history] = await Promise.all([
+      invoke("get_network_details"),
+      invoke("get_network_history"),
+    ]);
+    renderDetails(details);
+    updateInterfaceOptions(details.interfaces.map((i) => i.name));
+    drawChart(chartSeries(history));
+  } catch (err) {
+    console.warn("Network details unavailable:", err);
+  }
+}
+
+async function startNetwork() {
+  const invoke = getInvoke();
+  if (!invoke) {
+    setTimeout(startNetwork, 100);
+    return;
+  }
+  try {
+    networkUnits = await invoke("get_units");
+    await window.__TAURI__?.event?.listen?.("units-changed", (event) => {
+      networkUnits = event.payload || networkUnits;
+      refreshNetwork();
+    });
+  } catch (err) {
+    console.warn("Unit preferences unavailable:", err);
+  }
+  document.getElementById("net-interface-select")?.addEventListener("change", (event) => {
+    selectedInterface = event.target.value;
+    refreshNetwork();
+  });
+  refreshNetwork();
+  setInterval(() => {
+    if (!document.hidden) refreshNetwork();
+  }, NETWORK_REFRESH_MS);
+}
+
+startNetwork();